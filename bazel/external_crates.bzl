@@ -787,6 +787,9 @@ def external_crates_repository(name, static_openssl, cargo_lockfile, lockfile):
             "quickcheck": crate.spec(
                 version = "^1.0.3",
             ),
+            "quinn": crate.spec(
+                version = "^0.10.1",
+            ),
             "quote": crate.spec(
                 version = "^1.0",
             ),
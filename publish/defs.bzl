@@ -109,3 +109,26 @@ malicious_binary = rule(
         "binary": attr.label(mandatory = True, cfg = malicious_code_enabled_transition, allow_single_file = True),
     },
 )
+
+def _malicious_test_impl(ctx):
+    test = ctx.attr.test[0]
+    info = test[DefaultInfo]
+
+    executable = ctx.actions.declare_file(ctx.label.name)
+    ctx.actions.symlink(output = executable, target_file = ctx.file.test)
+
+    return [
+        DefaultInfo(files = info.files, runfiles = info.default_runfiles, executable = executable),
+    ]
+
+# Runs a test built with malicious code enabled in its whole dependency graph.
+malicious_test = rule(
+    implementation = _malicious_test_impl,
+    test = True,
+    attrs = {
+        "_allowlist_function_transition": attr.label(
+            default = "@bazel_tools//tools/allowlists/function_transition_allowlist",
+        ),
+        "test": attr.label(mandatory = True, cfg = malicious_code_enabled_transition, allow_single_file = True),
+    },
+)
@@ -2,24 +2,48 @@ use clap::{arg, Arg, Command};
 use ic_artifact_pool::{
     certification_pool::CertificationPoolImpl,
     consensus_pool::{PoolSectionOps, UncachedConsensusPoolImpl},
+    pool_migration::{migrate_pool, PoolBackendKind},
 };
 use ic_config::artifact_pool::ArtifactPoolConfig;
 use ic_interfaces::consensus_pool::*;
 use ic_logger::{LoggerImpl, ReplicaLogger};
 use ic_metrics::MetricsRegistry;
 use ic_types::{
-    consensus::{certification::CertificationMessage, CatchUpPackage, ConsensusMessageHashable},
+    consensus::{
+        certification::CertificationMessage, Block, CatchUpPackage, ConsensusMessageHashable,
+        HasHeight, HasRank,
+    },
+    crypto::CryptoHashOf,
     time::current_time,
+    Height, NodeId, Time,
 };
 use prost::Message;
 use serde::{Deserialize, Serialize};
 use serde_bytes_repr::{ByteFmtDeserializer, ByteFmtSerializer};
-use serde_json::{Deserializer, Serializer};
+use serde_json::{json, Deserializer, Serializer};
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::TryFrom;
 use std::io::BufRead;
 use std::io::Write;
 use std::path::PathBuf;
 
+/// Optional `--from` and `--to` height arguments, shared by the analysis
+/// subcommands.
+fn height_range_args() -> [Arg<'static>; 2] {
+    [
+        Arg::new("from")
+            .long("from")
+            .value_name("HEIGHT")
+            .help("Lowest height to consider (inclusive)")
+            .takes_value(true),
+        Arg::new("to")
+            .long("to")
+            .value_name("HEIGHT")
+            .help("Highest height to consider (inclusive)")
+            .takes_value(true),
+    ]
+}
+
 fn main() {
     let mut app = Command::new("ic-consensus-pool-util")
         .version("0.1")
@@ -50,6 +74,65 @@ fn main() {
                         .takes_value(true),
                 ),
         )
+        .subcommand(
+            Command::new("heights")
+                .about("List block proposals, notarizations and finalizations per height and rank")
+                .args(height_range_args()),
+        )
+        .subcommand(
+            Command::new("signers")
+                .about("Show which nodes signed each share at the given height")
+                .arg(
+                    Arg::new("height")
+                        .long("height")
+                        .value_name("HEIGHT")
+                        .help("Height to inspect")
+                        .required(true)
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            Command::new("stats")
+                .about("Compute per-node notarization participation and latency")
+                .args(height_range_args()),
+        )
+        .subcommand(
+            Command::new("graph")
+                .about("Export the block tree as a DOT or JSON graph")
+                .args(height_range_args())
+                .arg(
+                    Arg::new("format")
+                        .short('f')
+                        .long("format")
+                        .value_name("FORMAT")
+                        .help("Output format")
+                        .possible_values(["dot", "json"])
+                        .default_value("dot")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            Command::new("migrate")
+                .about("Copy the pool into a new directory using a different persistent backend")
+                .arg(
+                    Arg::new("to-backend")
+                        .long("to-backend")
+                        .value_name("BACKEND")
+                        .help("Persistent pool backend of the destination")
+                        .possible_values(["lmdb", "rocksdb"])
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("DIR")
+                        .help("Destination directory, which must not contain a pool yet")
+                        .required(true)
+                        .takes_value(true),
+                ),
+        )
         .arg(arg!(<PATH>       "PATH to the consensus pool directory"));
     let mut help = Vec::new();
     app.write_help(&mut help)
@@ -64,6 +147,16 @@ fn main() {
         import(path)
     } else if let Some(matches) = matches.subcommand_matches("export-cup-proto") {
         export_cup_proto(path, matches)
+    } else if let Some(matches) = matches.subcommand_matches("heights") {
+        heights(path, matches)
+    } else if let Some(matches) = matches.subcommand_matches("signers") {
+        signers(path, matches)
+    } else if let Some(matches) = matches.subcommand_matches("stats") {
+        stats(path, matches)
+    } else if let Some(matches) = matches.subcommand_matches("graph") {
+        graph(path, matches)
+    } else if let Some(matches) = matches.subcommand_matches("migrate") {
+        migrate(path, matches)
     } else {
         eprintln!(
             "{}",
@@ -272,3 +365,409 @@ fn export_cup_proto(path: &str, matches: &clap::ArgMatches) {
     file.write_all(&buf)
         .unwrap_or_else(|err| panic!("Cannot write to file {}: {:?}", filename, err));
 }
+
+fn parse_height(matches: &clap::ArgMatches, name: &str) -> Option<Height> {
+    matches.value_of(name).map(|value| {
+        Height::from(
+            value
+                .parse::<u64>()
+                .unwrap_or_else(|err| panic!("Invalid height '{}': {:?}", value, err)),
+        )
+    })
+}
+
+/// Returns the range of heights selected by the `--from` and `--to` arguments,
+/// defaulting to the range of heights of validated block proposals.
+fn selected_height_range(
+    consensus_pool: &UncachedConsensusPoolImpl,
+    matches: &clap::ArgMatches,
+) -> Option<HeightRange> {
+    let pool_range = consensus_pool.validated().block_proposal().height_range()?;
+    let min = parse_height(matches, "from").unwrap_or(pool_range.min);
+    let max = parse_height(matches, "to").unwrap_or(pool_range.max);
+    (min <= max).then(|| HeightRange::new(min, max))
+}
+
+fn hash_to_string<T>(hash: &CryptoHashOf<T>) -> String {
+    hash.get_ref()
+        .0
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Short, human readable prefix of a block hash.
+fn short_hash<T>(hash: &CryptoHashOf<T>) -> String {
+    hash_to_string(hash).chars().take(8).collect()
+}
+
+fn node_list(nodes: &[NodeId]) -> String {
+    nodes
+        .iter()
+        .map(|node| node.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A validated block proposal, as relevant for analysis.
+struct ProposalInfo {
+    hash: CryptoHashOf<Block>,
+    parent: CryptoHashOf<Block>,
+    height: Height,
+    rank: u64,
+    signer: NodeId,
+    timestamp: Option<Time>,
+}
+
+/// Collects the validated block proposals in the given height range, keyed by
+/// block hash.
+fn collect_proposals(
+    consensus_pool: &UncachedConsensusPoolImpl,
+    range: &HeightRange,
+) -> BTreeMap<CryptoHashOf<Block>, ProposalInfo> {
+    let validated = consensus_pool.validated();
+    validated
+        .block_proposal()
+        .get_by_height_range(range.clone())
+        .map(|proposal| {
+            let hash = proposal.content.get_hash().clone();
+            let info = ProposalInfo {
+                hash: hash.clone(),
+                parent: proposal.content.get_value().parent.clone(),
+                height: proposal.height(),
+                rank: proposal.rank().0,
+                signer: proposal.signature.signer,
+                timestamp: validated.get_timestamp(&proposal.get_id()),
+            };
+            (hash, info)
+        })
+        .collect()
+}
+
+/// Returns the hashes of the blocks that are notarized, resp. finalized, in the
+/// given height range.
+fn collect_notarized_and_finalized(
+    consensus_pool: &UncachedConsensusPoolImpl,
+    range: &HeightRange,
+) -> (BTreeSet<CryptoHashOf<Block>>, BTreeSet<CryptoHashOf<Block>>) {
+    let validated = consensus_pool.validated();
+    let notarized = validated
+        .notarization()
+        .get_by_height_range(range.clone())
+        .map(|notarization| notarization.content.block)
+        .collect();
+    let finalized = validated
+        .finalization()
+        .get_by_height_range(range.clone())
+        .map(|finalization| finalization.content.block)
+        .collect();
+    (notarized, finalized)
+}
+
+fn rank_of(
+    proposals: &BTreeMap<CryptoHashOf<Block>, ProposalInfo>,
+    hash: &CryptoHashOf<Block>,
+) -> String {
+    proposals
+        .get(hash)
+        .map(|proposal| proposal.rank.to_string())
+        .unwrap_or_else(|| format!("?({})", short_hash(hash)))
+}
+
+fn heights(path: &str, matches: &clap::ArgMatches) {
+    let consensus_pool = open_consensus_pool(path, true);
+    let range = match selected_height_range(&consensus_pool, matches) {
+        Some(range) => range,
+        None => return,
+    };
+    let proposals = collect_proposals(&consensus_pool, &range);
+
+    let mut proposals_by_height: BTreeMap<Height, BTreeMap<u64, usize>> = BTreeMap::new();
+    for proposal in proposals.values() {
+        *proposals_by_height
+            .entry(proposal.height)
+            .or_default()
+            .entry(proposal.rank)
+            .or_default() += 1;
+    }
+
+    let validated = consensus_pool.validated();
+    let mut height = range.min;
+    while height <= range.max {
+        let notarized_ranks = validated
+            .notarization()
+            .get_by_height(height)
+            .map(|notarization| rank_of(&proposals, &notarization.content.block))
+            .collect::<Vec<_>>()
+            .join(",");
+        let finalized_ranks = validated
+            .finalization()
+            .get_by_height(height)
+            .map(|finalization| rank_of(&proposals, &finalization.content.block))
+            .collect::<Vec<_>>()
+            .join(",");
+        let proposal_ranks = proposals_by_height
+            .get(&height)
+            .map(|ranks| {
+                ranks
+                    .iter()
+                    .map(|(rank, count)| format!("{}:{}", rank, count))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .unwrap_or_default();
+        println!(
+            "height={} proposals(rank:count)=[{}] notarized(rank)=[{}] finalized(rank)=[{}]",
+            height, proposal_ranks, notarized_ranks, finalized_ranks
+        );
+        height.inc_assign();
+    }
+}
+
+fn signers(path: &str, matches: &clap::ArgMatches) {
+    let height = parse_height(matches, "height").expect("Missing height");
+    let consensus_pool = open_consensus_pool(path, true);
+    let validated = consensus_pool.validated();
+    let proposals = collect_proposals(&consensus_pool, &HeightRange::new(height, height));
+
+    for proposal in proposals.values() {
+        println!(
+            "BlockProposal rank={} block={} signer={}",
+            proposal.rank,
+            short_hash(&proposal.hash),
+            proposal.signer
+        );
+    }
+
+    let mut shares: BTreeMap<(&str, CryptoHashOf<Block>), Vec<NodeId>> = BTreeMap::new();
+    for share in validated.notarization_share().get_by_height(height) {
+        shares
+            .entry(("NotarizationShare", share.content.block))
+            .or_default()
+            .push(share.signature.signer);
+    }
+    for share in validated.finalization_share().get_by_height(height) {
+        shares
+            .entry(("FinalizationShare", share.content.block))
+            .or_default()
+            .push(share.signature.signer);
+    }
+    for ((kind, block), mut signers) in shares {
+        signers.sort();
+        println!(
+            "{} rank={} block={} signers=[{}]",
+            kind,
+            rank_of(&proposals, &block),
+            short_hash(&block),
+            node_list(&signers)
+        );
+    }
+
+    let mut beacon_signers = validated
+        .random_beacon_share()
+        .get_by_height(height)
+        .map(|share| share.signature.signer)
+        .collect::<Vec<_>>();
+    beacon_signers.sort();
+    println!("RandomBeaconShare signers=[{}]", node_list(&beacon_signers));
+
+    for notarization in validated.notarization().get_by_height(height) {
+        println!(
+            "Notarization rank={} block={} signers=[{}]",
+            rank_of(&proposals, &notarization.content.block),
+            short_hash(&notarization.content.block),
+            node_list(&notarization.signature.signers)
+        );
+    }
+    for finalization in validated.finalization().get_by_height(height) {
+        println!(
+            "Finalization rank={} block={} signers=[{}]",
+            rank_of(&proposals, &finalization.content.block),
+            short_hash(&finalization.content.block),
+            node_list(&finalization.signature.signers)
+        );
+    }
+}
+
+/// Per-node notarization statistics.
+#[derive(Default)]
+struct NodeNotarizationStats {
+    /// Number of heights at which the node issued at least one notarization share.
+    heights_participated: u64,
+    /// Number of notarization shares issued by the node.
+    shares: u64,
+    /// Sum and maximum of the delays (in milliseconds) between the validation of
+    /// a block proposal and the validation of the node's notarization share on it.
+    total_latency_ms: u64,
+    max_latency_ms: u64,
+    latency_samples: u64,
+}
+
+fn stats(path: &str, matches: &clap::ArgMatches) {
+    let consensus_pool = open_consensus_pool(path, true);
+    let range = match selected_height_range(&consensus_pool, matches) {
+        Some(range) => range,
+        None => return,
+    };
+    let validated = consensus_pool.validated();
+    let proposals = collect_proposals(&consensus_pool, &range);
+    let notarized_heights = validated
+        .notarization()
+        .get_by_height_range(range.clone())
+        .map(|notarization| notarization.height())
+        .collect::<BTreeSet<_>>();
+
+    let mut stats: BTreeMap<NodeId, NodeNotarizationStats> = BTreeMap::new();
+    let mut participation: BTreeSet<(NodeId, Height)> = BTreeSet::new();
+    for share in validated
+        .notarization_share()
+        .get_by_height_range(range.clone())
+    {
+        let signer = share.signature.signer;
+        let node_stats = stats.entry(signer).or_default();
+        node_stats.shares += 1;
+        if participation.insert((signer, share.height())) {
+            node_stats.heights_participated += 1;
+        }
+
+        let share_timestamp = validated.get_timestamp(&share.get_id());
+        let proposal_timestamp = proposals
+            .get(&share.content.block)
+            .and_then(|proposal| proposal.timestamp);
+        if let (Some(share_timestamp), Some(proposal_timestamp)) =
+            (share_timestamp, proposal_timestamp)
+        {
+            let latency_ms = share_timestamp
+                .as_nanos_since_unix_epoch()
+                .saturating_sub(proposal_timestamp.as_nanos_since_unix_epoch())
+                / 1_000_000;
+            node_stats.total_latency_ms += latency_ms;
+            node_stats.max_latency_ms = node_stats.max_latency_ms.max(latency_ms);
+            node_stats.latency_samples += 1;
+        }
+    }
+
+    println!(
+        "heights={}..={} notarized_heights={}",
+        range.min,
+        range.max,
+        notarized_heights.len()
+    );
+    for (node, node_stats) in stats {
+        let participation_pct = if notarized_heights.is_empty() {
+            0.0
+        } else {
+            100.0 * node_stats.heights_participated as f64 / notarized_heights.len() as f64
+        };
+        let mean_latency_ms = if node_stats.latency_samples == 0 {
+            0
+        } else {
+            node_stats.total_latency_ms / node_stats.latency_samples
+        };
+        println!(
+            "node={} shares={} participation={:.1}% mean_latency_ms={} max_latency_ms={}",
+            node, node_stats.shares, participation_pct, mean_latency_ms, node_stats.max_latency_ms
+        );
+    }
+}
+
+fn graph(path: &str, matches: &clap::ArgMatches) {
+    let consensus_pool = open_consensus_pool(path, true);
+    let range = match selected_height_range(&consensus_pool, matches) {
+        Some(range) => range,
+        None => return,
+    };
+    let proposals = collect_proposals(&consensus_pool, &range);
+    let (notarized, finalized) = collect_notarized_and_finalized(&consensus_pool, &range);
+
+    match matches.value_of("format") {
+        Some("json") => {
+            let nodes = proposals
+                .values()
+                .map(|proposal| {
+                    json!({
+                        "hash": hash_to_string(&proposal.hash),
+                        "parent": hash_to_string(&proposal.parent),
+                        "height": proposal.height.get(),
+                        "rank": proposal.rank,
+                        "signer": proposal.signer.to_string(),
+                        "notarized": notarized.contains(&proposal.hash),
+                        "finalized": finalized.contains(&proposal.hash),
+                    })
+                })
+                .collect::<Vec<_>>();
+            println!("{}", json!({ "blocks": nodes }));
+        }
+        _ => {
+            println!("digraph blocks {{");
+            println!("  rankdir=LR;");
+            for proposal in proposals.values() {
+                let style = if finalized.contains(&proposal.hash) {
+                    ", style=filled, fillcolor=green"
+                } else if notarized.contains(&proposal.hash) {
+                    ", style=filled, fillcolor=yellow"
+                } else {
+                    ""
+                };
+                println!(
+                    "  \"{}\" [label=\"h={} r={}\\n{}\"{}];",
+                    hash_to_string(&proposal.hash),
+                    proposal.height,
+                    proposal.rank,
+                    short_hash(&proposal.hash),
+                    style
+                );
+                // Only draw edges to parents within the selected range.
+                if proposals.contains_key(&proposal.parent) {
+                    println!(
+                        "  \"{}\" -> \"{}\";",
+                        hash_to_string(&proposal.parent),
+                        hash_to_string(&proposal.hash)
+                    );
+                }
+            }
+            println!("}}");
+        }
+    }
+}
+
+fn migrate(path: &str, matches: &clap::ArgMatches) {
+    let logger = LoggerImpl::new(&Default::default(), "dump_consensus_pool".to_string());
+    let log = ReplicaLogger::new(logger.root.clone().into());
+
+    let source_path = PathBuf::from(path);
+    let source_backend = PoolBackendKind::detect(&source_path)
+        .unwrap_or_else(|| panic!("No persistent pool found at {}", path));
+    let to_backend: PoolBackendKind = matches
+        .value_of("to-backend")
+        .expect("Missing destination backend")
+        .parse()
+        .unwrap_or_else(|err: String| panic!("{}", err));
+    let output = PathBuf::from(
+        matches
+            .value_of("output")
+            .expect("Missing output directory"),
+    );
+    std::fs::create_dir_all(&output).expect("Failed to create the output directory");
+
+    let mut source = ArtifactPoolConfig::new(source_path.clone());
+    source.persistent_pool_backend =
+        source_backend.backend_at(source_path, &source.persistent_pool_backend);
+    let mut destination = ArtifactPoolConfig::new(output.clone());
+    destination.persistent_pool_backend =
+        to_backend.backend_at(output, &destination.persistent_pool_backend);
+
+    match migrate_pool(source, destination, log) {
+        Ok(summary) => println!(
+            "Migrated {} consensus and {} certification artifacts from {} to {}",
+            summary.consensus_artifacts,
+            summary.certification_artifacts,
+            source_backend,
+            to_backend
+        ),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
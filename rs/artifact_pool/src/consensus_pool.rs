@@ -6,6 +6,7 @@ use crate::{
     },
     inmemory_pool::InMemoryPoolSection,
     metrics::{LABEL_POOL_TYPE, POOL_TYPE_UNVALIDATED, POOL_TYPE_VALIDATED},
+    peer_quota::{PeerQuota, PeerQuotaTracker},
 };
 use ic_config::artifact_pool::{ArtifactPoolConfig, PersistentPoolBackend};
 use ic_interfaces::artifact_manager::ProcessingResult;
@@ -85,6 +86,24 @@ struct PerTypeMetrics<T> {
 
 const LABEL_TYPE: &str = "type";
 const LABEL_STAT: &str = "stat";
+const POOL_CONSENSUS: &str = "consensus";
+
+/// The artifact type label of a consensus message, as used by the pool metrics.
+fn artifact_type_label(msg: &ConsensusMessage) -> &'static str {
+    match msg {
+        ConsensusMessage::RandomBeacon(_) => "random_beacon",
+        ConsensusMessage::Finalization(_) => "finalization",
+        ConsensusMessage::Notarization(_) => "notarization",
+        ConsensusMessage::BlockProposal(_) => "block_proposal",
+        ConsensusMessage::RandomBeaconShare(_) => "random_beacon_share",
+        ConsensusMessage::NotarizationShare(_) => "notarization_share",
+        ConsensusMessage::FinalizationShare(_) => "finalization_share",
+        ConsensusMessage::RandomTape(_) => "random_tape",
+        ConsensusMessage::RandomTapeShare(_) => "random_tape_share",
+        ConsensusMessage::CatchUpPackage(_) => "catch_up_package",
+        ConsensusMessage::CatchUpPackageShare(_) => "catch_up_package_share",
+    }
+}
 
 impl<T> PerTypeMetrics<T> {
     fn new(registry: &ic_metrics::MetricsRegistry, pool_portion: &str, type_name: &str) -> Self {
@@ -258,6 +277,7 @@ pub struct ConsensusPoolImpl {
     unvalidated: Box<dyn MutablePoolSection<UnvalidatedConsensusArtifact> + Send + Sync>,
     validated_metrics: PoolMetrics,
     unvalidated_metrics: PoolMetrics,
+    unvalidated_quota: PeerQuotaTracker<ConsensusMessageId>,
     invalidated_artifacts: IntCounter,
     cache: Arc<ConsensusCacheImpl>,
     backup: Option<Backup>,
@@ -268,6 +288,7 @@ pub struct ConsensusPoolImpl {
 pub struct UncachedConsensusPoolImpl {
     pub validated: Box<dyn InitializablePoolSection + Send + Sync>,
     unvalidated: Box<dyn MutablePoolSection<UnvalidatedConsensusArtifact> + Send + Sync>,
+    unvalidated_quota: PeerQuota,
 }
 
 impl UncachedConsensusPoolImpl {
@@ -296,6 +317,10 @@ impl UncachedConsensusPoolImpl {
         UncachedConsensusPoolImpl {
             validated,
             unvalidated: Box::new(InMemoryPoolSection::new(log)),
+            unvalidated_quota: PeerQuota {
+                max_count: config.consensus_pool_unvalidated_capacity_per_peer,
+                max_bytes: config.consensus_pool_unvalidated_max_bytes_per_peer,
+            },
         }
     }
 }
@@ -436,7 +461,12 @@ impl ConsensusPoolImpl {
                 "The number of invalidated consensus artifacts",
             ),
             validated_metrics: PoolMetrics::new(registry.clone(), POOL_TYPE_VALIDATED),
-            unvalidated_metrics: PoolMetrics::new(registry, POOL_TYPE_UNVALIDATED),
+            unvalidated_metrics: PoolMetrics::new(registry.clone(), POOL_TYPE_UNVALIDATED),
+            unvalidated_quota: PeerQuotaTracker::new(
+                uncached.unvalidated_quota,
+                registry,
+                POOL_CONSENSUS,
+            ),
             cache,
             backup: None,
             log,
@@ -499,6 +529,9 @@ impl ConsensusPoolImpl {
     ) -> Vec<ConsensusMessageId> {
         if !ops.ops.is_empty() {
             let purged = self.unvalidated.mutate(ops);
+            for id in purged.iter() {
+                self.unvalidated_quota.remove(id);
+            }
             self.unvalidated_metrics
                 .update(self.unvalidated.pool_section());
             purged
@@ -528,8 +561,21 @@ impl ConsensusPool for ConsensusPoolImpl {
 
 impl MutablePool<ConsensusArtifact, ChangeSet> for ConsensusPoolImpl {
     fn insert(&mut self, unvalidated_artifact: UnvalidatedConsensusArtifact) {
+        let msg_id = unvalidated_artifact.message.get_id();
+        let evicted = self.unvalidated_quota.insert(
+            unvalidated_artifact.peer_id,
+            artifact_type_label(&unvalidated_artifact.message),
+            msg_id.clone(),
+            bincode::serialized_size(&unvalidated_artifact.message).unwrap_or_default() as usize,
+        );
         let mut ops = PoolSectionOps::new();
-        ops.insert(unvalidated_artifact);
+        // If the artifact alone exceeds the peer's quota, it is dropped right away.
+        if !evicted.contains(&msg_id) {
+            ops.insert(unvalidated_artifact);
+        }
+        for id in evicted.into_iter().filter(|id| *id != msg_id) {
+            ops.remove(id);
+        }
         self.apply_changes_unvalidated(ops);
     }
 
@@ -984,6 +1030,65 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_unvalidated_peer_quota() {
+        ic_test_utilities::artifact_pool_config::with_test_pool_config(|mut pool_config| {
+            pool_config.consensus_pool_unvalidated_capacity_per_peer = 2;
+            let time_source = FastForwardTimeSource::new();
+            let mut pool = ConsensusPoolImpl::new_from_cup_without_bytes(
+                subnet_test_id(0),
+                make_genesis(ic_types::consensus::dkg::Summary::fake()),
+                pool_config,
+                ic_metrics::MetricsRegistry::new(),
+                no_op_logger(),
+            );
+            let beacon = |height: u64| {
+                RandomBeacon::fake(RandomBeaconContent::new(
+                    Height::from(height),
+                    CryptoHashOf::from(CryptoHash(Vec::new())),
+                ))
+            };
+            let insert = |pool: &mut ConsensusPoolImpl, height: u64, peer: u64| {
+                pool.insert(UnvalidatedArtifact {
+                    message: beacon(height).into_message(),
+                    peer_id: node_test_id(peer),
+                    timestamp: time_source.get_relative_time(),
+                })
+            };
+            let peer = node_test_id(0);
+            let count =
+                |pool: &ConsensusPoolImpl| pool.unvalidated_quota.usage(peer, "random_beacon").0;
+
+            // The peer's oldest artifact is evicted once it exceeds its quota,
+            // other peers are not affected.
+            insert(&mut pool, 1, 0);
+            insert(&mut pool, 2, 1);
+            insert(&mut pool, 3, 0);
+            insert(&mut pool, 4, 0);
+            assert!(!pool.unvalidated().contains(&beacon(1).get_id()));
+            for height in 2..=4 {
+                assert!(pool.unvalidated().contains(&beacon(height).get_id()));
+            }
+            assert_eq!(count(&pool), 2);
+
+            // Moving or removing the peer's artifacts releases its quota.
+            pool.apply_changes(
+                time_source.as_ref(),
+                vec![
+                    ChangeAction::MoveToValidated(beacon(3).into_message()),
+                    ChangeAction::RemoveFromUnvalidated(beacon(4).into_message()),
+                ],
+            );
+            assert_eq!(count(&pool), 0);
+            insert(&mut pool, 5, 0);
+            insert(&mut pool, 6, 0);
+            assert!(pool.unvalidated().contains(&beacon(5).get_id()));
+            assert!(pool.unvalidated().contains(&beacon(6).get_id()));
+            assert!(pool.unvalidated().contains(&beacon(2).get_id()));
+            assert_eq!(count(&pool), 2);
+        })
+    }
+
     #[test]
     fn test_adverts() {
         ic_test_utilities::artifact_pool_config::with_test_pool_config(|pool_config| {
@@ -1,7 +1,10 @@
 /// Ingress Pool provides storage for all ingress messages in artifact_pool
 /// Logically it can be viewed as part of the artifact pool
 /// But we keep it separated for code readability
-use crate::metrics::{PoolMetrics, POOL_TYPE_UNVALIDATED, POOL_TYPE_VALIDATED};
+use crate::{
+    metrics::{PoolMetrics, POOL_TYPE_UNVALIDATED, POOL_TYPE_VALIDATED},
+    peer_quota::{PeerQuota, PeerQuotaTracker},
+};
 use ic_config::artifact_pool::ArtifactPoolConfig;
 use ic_constants::MAX_INGRESS_TTL;
 use ic_interfaces::{
@@ -191,11 +194,15 @@ pub struct IngressPoolImpl {
     ingress_pool_max_count: usize,
     ingress_pool_max_bytes: usize,
     ingress_messages_throttled: IntCounter,
+    // Per-peer quotas on the unvalidated section. Messages submitted to this
+    // node directly are bounded by the throttler instead.
+    unvalidated_quota: PeerQuotaTracker<IngressMessageId>,
     node_id: NodeId,
     log: ReplicaLogger,
 }
 
 const POOL_INGRESS: &str = "ingress";
+const ARTIFACT_TYPE_INGRESS: &str = "ingress";
 
 impl IngressPoolImpl {
     pub fn new(
@@ -217,10 +224,18 @@ impl IngressPoolImpl {
                 POOL_TYPE_VALIDATED,
             )),
             unvalidated: IngressPoolSection::new(PoolMetrics::new(
-                metrics_registry,
+                metrics_registry.clone(),
                 POOL_INGRESS,
                 POOL_TYPE_UNVALIDATED,
             )),
+            unvalidated_quota: PeerQuotaTracker::new(
+                PeerQuota {
+                    max_count: config.ingress_pool_unvalidated_capacity_per_peer,
+                    max_bytes: config.ingress_pool_unvalidated_max_bytes_per_peer,
+                },
+                metrics_registry,
+                POOL_INGRESS,
+            ),
             node_id,
             log,
         }
@@ -232,6 +247,7 @@ impl IngressPoolImpl {
         &mut self,
         message_id: &IngressMessageId,
     ) -> Option<(UnvalidatedIngressArtifact, usize)> {
+        self.unvalidated_quota.remove(message_id);
         match self.unvalidated.remove(message_id) {
             Some(unvalidated_artifact) => {
                 let size = unvalidated_artifact.message.signed_ingress.count_bytes();
@@ -272,8 +288,31 @@ impl MutablePool<IngressArtifact, ChangeSet> for IngressPoolImpl {
             ingress_message.message_id => format!("{}", ingress_pool_obj.message_id)
         );
 
+        let message_id = IngressMessageId::from(&ingress_pool_obj);
+        let evicted = if peer_id != self.node_id {
+            self.unvalidated_quota
+                .insert(peer_id, ARTIFACT_TYPE_INGRESS, message_id.clone(), size)
+        } else {
+            self.unvalidated_quota.remove(&message_id);
+            Vec::new()
+        };
+        for evicted_id in evicted.iter().filter(|id| **id != message_id) {
+            if let Some((_, evicted_size)) = self.remove_unvalidated(evicted_id) {
+                debug!(
+                    self.log,
+                    "Ingress pool: evict {} bytes from unvalidated, peer {} exceeded its quota",
+                    evicted_size,
+                    peer_id
+                );
+            }
+        }
+        // If the message alone exceeds the peer's quota, it is dropped right away.
+        if evicted.contains(&message_id) {
+            return;
+        }
+
         self.unvalidated.insert(
-            IngressMessageId::from(&ingress_pool_obj),
+            message_id,
             UnvalidatedIngressArtifact {
                 message: ingress_pool_obj,
                 peer_id,
@@ -314,6 +353,7 @@ impl MutablePool<IngressArtifact, ChangeSet> for IngressPoolImpl {
                             id: message_id.clone(),
                             attribute: attribute.clone(),
                             integrity_hash: integrity_hash.clone(),
+                            chunk_tree_root: None,
                         });
                     }
                     // remove it from unvalidated pool and remove it from peer_index, move it
@@ -382,7 +422,10 @@ impl MutablePool<IngressArtifact, ChangeSet> for IngressPoolImpl {
                             .purge_below(expiry)
                             .map(|i| (&i.msg.signed_ingress).into()),
                     );
-                    let _unused = self.unvalidated.purge_below(expiry);
+                    for artifact in self.unvalidated.purge_below(expiry) {
+                        self.unvalidated_quota
+                            .remove(&IngressMessageId::from(&artifact.message));
+                    }
                 }
             }
         }
@@ -976,4 +1019,56 @@ mod tests {
             timestamp: time,
         });
     }
+
+    #[test]
+    fn test_unvalidated_quota_per_peer() {
+        with_test_replica_logger(|log| {
+            ic_test_utilities::artifact_pool_config::with_test_pool_config(|mut pool_config| {
+                pool_config.ingress_pool_unvalidated_capacity_per_peer = 3;
+                let time_source = FastForwardTimeSource::new();
+                let mut ingress_pool =
+                    IngressPoolImpl::new(node_test_id(0), pool_config, MetricsRegistry::new(), log);
+                let insert = |pool: &mut IngressPoolImpl, nonce: u64, peer_id: u64| {
+                    let ingress_msg = SignedIngressBuilder::new().nonce(nonce).build();
+                    let message_id = IngressMessageId::from(&ingress_msg);
+                    pool.insert(UnvalidatedArtifact {
+                        message: ingress_msg,
+                        peer_id: node_test_id(peer_id),
+                        timestamp: time_source.get_relative_time(),
+                    });
+                    message_id
+                };
+
+                // The two oldest messages of peer 1 are evicted.
+                let peer_1_ids = (0..5)
+                    .map(|nonce| insert(&mut ingress_pool, nonce, 1))
+                    .collect::<Vec<_>>();
+                // Other peers have their own quota, and the node's own
+                // messages are not subject to quotas.
+                let peer_2_ids = (5..8)
+                    .map(|nonce| insert(&mut ingress_pool, nonce, 2))
+                    .collect::<Vec<_>>();
+                let own_ids = (8..13)
+                    .map(|nonce| insert(&mut ingress_pool, nonce, 0))
+                    .collect::<Vec<_>>();
+
+                assert_eq!(ingress_pool.unvalidated().size(), 3 + 3 + 5);
+                assert!(!ingress_pool.contains(&peer_1_ids[0]));
+                assert!(!ingress_pool.contains(&peer_1_ids[1]));
+                for id in peer_1_ids[2..].iter().chain(&peer_2_ids).chain(&own_ids) {
+                    assert!(ingress_pool.contains(id));
+                }
+
+                // Removing a message frees up quota for the peer.
+                ingress_pool.apply_changes(
+                    &SysTimeSource::new(),
+                    vec![ChangeAction::RemoveFromUnvalidated(peer_1_ids[2].clone())],
+                );
+                let new_id = insert(&mut ingress_pool, 13, 1);
+                assert!(ingress_pool.contains(&new_id));
+                assert!(ingress_pool.contains(&peer_1_ids[3]));
+                assert_eq!(ingress_pool.unvalidated().size(), 3 + 3 + 5);
+            })
+        })
+    }
 }
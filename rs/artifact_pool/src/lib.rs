@@ -8,7 +8,9 @@ mod height_index;
 pub mod ingress_pool;
 mod inmemory_pool;
 mod metrics;
+mod peer_quota;
 mod pool_common;
+pub mod pool_migration;
 #[cfg(test)]
 mod test_utils;
 
@@ -144,3 +144,66 @@ impl EcdsaPoolMetrics {
         self.persistence_errors.with_label_values(&[label]).inc();
     }
 }
+
+/// Metrics for the per-peer quotas of a pool's unvalidated section.
+#[derive(Clone)]
+pub struct PeerQuotaMetrics {
+    peer_artifacts: IntGaugeVec,
+    peer_artifact_bytes: IntGaugeVec,
+    evicted_artifacts: IntCounterVec,
+}
+
+impl PeerQuotaMetrics {
+    pub fn new(metrics_registry: MetricsRegistry, pool: &str) -> Self {
+        Self {
+            peer_artifacts: metrics_registry.register(
+                IntGaugeVec::new(
+                    opts!(
+                        "artifact_pool_unvalidated_peer_artifacts",
+                        "Current number of unvalidated artifacts in the given pool, by peer and artifact type",
+                        labels! {LABEL_POOL => pool}
+                    ),
+                    &["peer", "artifact_type"],
+                )
+                .unwrap(),
+            ),
+            peer_artifact_bytes: metrics_registry.register(
+                IntGaugeVec::new(
+                    opts!(
+                        "artifact_pool_unvalidated_peer_artifact_bytes",
+                        "Current byte size of unvalidated artifacts in the given pool, by peer and artifact type",
+                        labels! {LABEL_POOL => pool}
+                    ),
+                    &["peer", "artifact_type"],
+                )
+                .unwrap(),
+            ),
+            evicted_artifacts: metrics_registry.register(
+                IntCounterVec::new(
+                    opts!(
+                        "artifact_pool_unvalidated_peer_evicted_artifacts",
+                        "Unvalidated artifacts evicted from the given pool because a peer exceeded its quota, by peer and artifact type",
+                        labels! {LABEL_POOL => pool}
+                    ),
+                    &["peer", "artifact_type"],
+                )
+                .unwrap(),
+            ),
+        }
+    }
+
+    pub fn observe_usage(&self, peer: &str, artifact_type: &str, count: usize, bytes: usize) {
+        self.peer_artifacts
+            .with_label_values(&[peer, artifact_type])
+            .set(count as i64);
+        self.peer_artifact_bytes
+            .with_label_values(&[peer, artifact_type])
+            .set(bytes as i64);
+    }
+
+    pub fn observe_eviction(&self, peer: &str, artifact_type: &str) {
+        self.evicted_artifacts
+            .with_label_values(&[peer, artifact_type])
+            .inc();
+    }
+}
@@ -0,0 +1,196 @@
+//! Per-peer quotas for the unvalidated sections of the artifact pools.
+//!
+//! Unvalidated artifacts are only purged once consensus catches up with them,
+//! so without a bound a single misbehaving peer could fill the unvalidated
+//! section with arbitrary artifacts. [`PeerQuotaTracker`] keeps track of the
+//! number and byte size of the artifacts each peer contributed, per artifact
+//! type, and tells the pool which of that peer's artifacts to evict (oldest
+//! first) whenever an insertion exceeds the quota.
+use crate::metrics::PeerQuotaMetrics;
+use ic_metrics::MetricsRegistry;
+use ic_types::NodeId;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// Limits on what a single peer may store in an unvalidated section, per
+/// artifact type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct PeerQuota {
+    pub(crate) max_count: usize,
+    pub(crate) max_bytes: usize,
+}
+
+/// Artifacts of one type received from one peer, in insertion order.
+#[derive(Clone, Default)]
+struct PeerUsage<K> {
+    entries: BTreeMap<u64, (K, usize)>,
+    bytes: usize,
+}
+
+#[derive(Clone)]
+struct Owner {
+    peer: NodeId,
+    artifact_type: &'static str,
+    seq: u64,
+}
+
+#[derive(Clone)]
+pub(crate) struct PeerQuotaTracker<K> {
+    quota: PeerQuota,
+    usage: BTreeMap<(NodeId, &'static str), PeerUsage<K>>,
+    owners: HashMap<K, Owner>,
+    next_seq: u64,
+    metrics: PeerQuotaMetrics,
+}
+
+impl<K: Clone + Eq + Hash> PeerQuotaTracker<K> {
+    pub(crate) fn new(quota: PeerQuota, metrics_registry: MetricsRegistry, pool: &str) -> Self {
+        Self {
+            quota,
+            usage: BTreeMap::new(),
+            owners: HashMap::new(),
+            next_seq: 0,
+            metrics: PeerQuotaMetrics::new(metrics_registry, pool),
+        }
+    }
+
+    /// Account for an artifact of `size_bytes` received from `peer`. Return
+    /// the keys of the artifacts that must be evicted from the pool so that
+    /// the peer stays within its quota. The returned keys may include `key`
+    /// itself, if the artifact alone exceeds the quota.
+    pub(crate) fn insert(
+        &mut self,
+        peer: NodeId,
+        artifact_type: &'static str,
+        key: K,
+        size_bytes: usize,
+    ) -> Vec<K> {
+        // Re-inserting a known artifact refreshes its accounting.
+        self.remove(&key);
+
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        let usage = self.usage.entry((peer, artifact_type)).or_default();
+        usage.entries.insert(seq, (key.clone(), size_bytes));
+        usage.bytes += size_bytes;
+        self.owners.insert(
+            key,
+            Owner {
+                peer,
+                artifact_type,
+                seq,
+            },
+        );
+
+        let mut evicted = Vec::new();
+        while usage.entries.len() > self.quota.max_count || usage.bytes > self.quota.max_bytes {
+            let (key, size) = match usage.entries.pop_first() {
+                Some((_, entry)) => entry,
+                None => break,
+            };
+            usage.bytes -= size;
+            self.owners.remove(&key);
+            self.metrics
+                .observe_eviction(&peer.to_string(), artifact_type);
+            evicted.push(key);
+        }
+        let (count, bytes) = (usage.entries.len(), usage.bytes);
+        self.metrics
+            .observe_usage(&peer.to_string(), artifact_type, count, bytes);
+        evicted
+    }
+
+    /// Stop accounting for the artifact with the given key, e.g. because it
+    /// was validated, invalidated or purged. Unknown keys are ignored.
+    pub(crate) fn remove(&mut self, key: &K) {
+        let owner = match self.owners.remove(key) {
+            Some(owner) => owner,
+            None => return,
+        };
+        let usage_key = (owner.peer, owner.artifact_type);
+        if let Some(usage) = self.usage.get_mut(&usage_key) {
+            if let Some((_, size)) = usage.entries.remove(&owner.seq) {
+                usage.bytes -= size;
+            }
+            self.metrics.observe_usage(
+                &owner.peer.to_string(),
+                owner.artifact_type,
+                usage.entries.len(),
+                usage.bytes,
+            );
+            if usage.entries.is_empty() {
+                self.usage.remove(&usage_key);
+            }
+        }
+    }
+
+    /// Number and byte size of the artifacts of the given type currently
+    /// accounted to `peer`.
+    #[cfg(test)]
+    pub(crate) fn usage(&self, peer: NodeId, artifact_type: &'static str) -> (usize, usize) {
+        self.usage
+            .get(&(peer, artifact_type))
+            .map_or((0, 0), |usage| (usage.entries.len(), usage.bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_test_utilities::types::ids::node_test_id;
+
+    fn tracker(max_count: usize, max_bytes: usize) -> PeerQuotaTracker<u64> {
+        PeerQuotaTracker::new(
+            PeerQuota {
+                max_count,
+                max_bytes,
+            },
+            MetricsRegistry::new(),
+            "test",
+        )
+    }
+
+    #[test]
+    fn test_evicts_oldest_when_count_exceeded() {
+        let mut tracker = tracker(2, usize::MAX);
+        let peer = node_test_id(1);
+        assert!(tracker.insert(peer, "a", 1, 10).is_empty());
+        assert!(tracker.insert(peer, "a", 2, 10).is_empty());
+        assert_eq!(tracker.insert(peer, "a", 3, 10), vec![1]);
+        assert_eq!(tracker.usage(peer, "a"), (2, 20));
+
+        // Quotas are per artifact type and per peer.
+        assert!(tracker.insert(peer, "b", 4, 10).is_empty());
+        assert!(tracker.insert(node_test_id(2), "a", 5, 10).is_empty());
+        assert_eq!(tracker.usage(peer, "a"), (2, 20));
+    }
+
+    #[test]
+    fn test_evicts_oldest_when_bytes_exceeded() {
+        let mut tracker = tracker(usize::MAX, 100);
+        let peer = node_test_id(1);
+        assert!(tracker.insert(peer, "a", 1, 40).is_empty());
+        assert!(tracker.insert(peer, "a", 2, 40).is_empty());
+        assert_eq!(tracker.insert(peer, "a", 3, 50), vec![1]);
+        assert_eq!(tracker.usage(peer, "a"), (2, 90));
+
+        // An artifact that alone exceeds the quota evicts everything.
+        assert_eq!(tracker.insert(peer, "a", 4, 200), vec![2, 3, 4]);
+        assert_eq!(tracker.usage(peer, "a"), (0, 0));
+    }
+
+    #[test]
+    fn test_remove_releases_quota() {
+        let mut tracker = tracker(2, usize::MAX);
+        let peer = node_test_id(1);
+        tracker.insert(peer, "a", 1, 10);
+        tracker.insert(peer, "a", 2, 10);
+        tracker.remove(&1);
+        tracker.remove(&42);
+        assert_eq!(tracker.usage(peer, "a"), (1, 10));
+        assert!(tracker.insert(peer, "a", 3, 10).is_empty());
+        // Re-inserting a known key does not count twice.
+        assert!(tracker.insert(peer, "a", 3, 10).is_empty());
+        assert_eq!(tracker.usage(peer, "a"), (2, 20));
+    }
+}
@@ -0,0 +1,865 @@
+//! Migration of the validated, persistent artifact pool between the LMDB and
+//! RocksDB backends.
+//!
+//! The migration copies every validated consensus and certification artifact
+//! (together with its insertion timestamp) from a source pool into an empty
+//! destination pool, re-inserts the highest catch-up package in its original
+//! protobuf form, and finally verifies that both pools hold exactly the same
+//! artifacts at the same heights.
+//!
+//! It is used by `ic-consensus-pool-util migrate` and by the replica on
+//! startup, when the configured backend differs from the one found on disk.
+use crate::{
+    certification_pool::CertificationPoolImpl,
+    consensus_pool::{PoolSectionOps, UncachedConsensusPoolImpl},
+};
+use ic_config::artifact_pool::{
+    ArtifactPoolConfig, LMDBConfig, PersistentPoolBackend, RocksDBConfig,
+    PERSISTENT_POOL_VALIDATED_PURGE_INTERVAL,
+};
+use ic_interfaces::consensus_pool::{HeightIndexedPool, PoolSection, ValidatedConsensusArtifact};
+use ic_logger::{info, warn, ReplicaLogger};
+use ic_metrics::MetricsRegistry;
+use ic_types::{
+    artifact::ConsensusMessageId,
+    consensus::{certification::CertificationMessage, ConsensusMessageHashable},
+    time::current_time,
+    Height,
+};
+use std::{
+    collections::HashSet,
+    fmt, fs,
+    hash::Hash,
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Sub-directory of the pool path holding the validated consensus pool.
+const CONSENSUS_DIR: &str = "consensus";
+/// Sub-directory of the pool path holding the validated certification pool.
+const CERTIFICATION_DIR: &str = "certification";
+/// Number of consensus artifacts inserted per mutation of the destination.
+const INSERT_BATCH_SIZE: usize = 1000;
+/// Suffix of the sibling directory the pool is migrated into.
+const MIGRATION_STAGING_SUFFIX: &str = ".migration";
+/// Suffix of the sibling directory holding the pre-migration sections while
+/// they are being replaced.
+const MIGRATION_BACKUP_SUFFIX: &str = ".backup";
+/// File marking the staging directory as holding a complete, verified copy.
+const MIGRATION_COMPLETE_MARKER: &str = "MIGRATION_COMPLETE";
+
+/// The kind of backend a persistent pool is stored with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolBackendKind {
+    Lmdb,
+    RocksDB,
+}
+
+impl PoolBackendKind {
+    /// Return the backend kind of the given configuration.
+    pub fn of(backend: &PersistentPoolBackend) -> Self {
+        match backend {
+            PersistentPoolBackend::Lmdb(_) => PoolBackendKind::Lmdb,
+            PersistentPoolBackend::RocksDB(_) => PoolBackendKind::RocksDB,
+        }
+    }
+
+    /// Detect the backend of the pool stored at `pool_path` by looking at the
+    /// files of its consensus section. Return `None` if no pool is found.
+    pub fn detect(pool_path: &Path) -> Option<Self> {
+        let consensus_path = pool_path.join(CONSENSUS_DIR);
+        if consensus_path.join("data.mdb").exists() {
+            Some(PoolBackendKind::Lmdb)
+        } else if consensus_path.join("CURRENT").exists() {
+            Some(PoolBackendKind::RocksDB)
+        } else {
+            None
+        }
+    }
+
+    /// Build a persistent pool backend of this kind stored at `pool_path`,
+    /// taking the remaining settings from `template` where they apply.
+    pub fn backend_at(
+        self,
+        pool_path: PathBuf,
+        template: &PersistentPoolBackend,
+    ) -> PersistentPoolBackend {
+        match self {
+            PoolBackendKind::Lmdb => PersistentPoolBackend::Lmdb(LMDBConfig {
+                persistent_pool_validated_persistent_db_path: pool_path,
+            }),
+            PoolBackendKind::RocksDB => {
+                let (skip_fsync, purge_interval) = match template {
+                    PersistentPoolBackend::RocksDB(config) => (
+                        config.persistent_pool_validated_skip_fsync_for_tests,
+                        config.persistent_pool_validated_purge_interval,
+                    ),
+                    PersistentPoolBackend::Lmdb(_) => (
+                        false,
+                        Height::from(PERSISTENT_POOL_VALIDATED_PURGE_INTERVAL),
+                    ),
+                };
+                PersistentPoolBackend::RocksDB(RocksDBConfig {
+                    persistent_pool_validated_skip_fsync_for_tests: skip_fsync,
+                    persistent_pool_validated_persistent_db_path: pool_path,
+                    persistent_pool_validated_purge_interval: purge_interval,
+                })
+            }
+        }
+    }
+}
+
+impl fmt::Display for PoolBackendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PoolBackendKind::Lmdb => write!(f, "lmdb"),
+            PoolBackendKind::RocksDB => write!(f, "rocksdb"),
+        }
+    }
+}
+
+impl std::str::FromStr for PoolBackendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lmdb" => Ok(PoolBackendKind::Lmdb),
+            "rocksdb" => Ok(PoolBackendKind::RocksDB),
+            other => Err(format!(
+                "Unsupported persistent pool backend: {}, must be either \"lmdb\" or \"rocksdb\".",
+                other
+            )),
+        }
+    }
+}
+
+/// Number of artifacts copied by a successful migration.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MigrationSummary {
+    pub consensus_artifacts: usize,
+    pub certification_artifacts: usize,
+    /// Height of the highest catch-up package, if the source pool had one.
+    pub highest_cup_height: Option<Height>,
+}
+
+#[derive(Debug)]
+pub enum MigrationError {
+    /// No pool was found at the source path.
+    SourceNotFound(PathBuf),
+    /// The destination path already contains a pool.
+    DestinationNotEmpty(PathBuf),
+    /// The source and destination pools are stored at the same path.
+    SamePath(PathBuf),
+    /// The destination does not match the source after copying.
+    VerificationFailed(String),
+    /// Moving the migrated pool into place failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::SourceNotFound(path) => {
+                write!(f, "No persistent pool found at {}", path.display())
+            }
+            MigrationError::DestinationNotEmpty(path) => write!(
+                f,
+                "Destination {} already contains a persistent pool",
+                path.display()
+            ),
+            MigrationError::SamePath(path) => write!(
+                f,
+                "Source and destination are both stored at {}",
+                path.display()
+            ),
+            MigrationError::VerificationFailed(reason) => {
+                write!(f, "Verification of the migrated pool failed: {}", reason)
+            }
+            MigrationError::Io(err) => write!(f, "I/O error during migration: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl From<io::Error> for MigrationError {
+    fn from(err: io::Error) -> Self {
+        MigrationError::Io(err)
+    }
+}
+
+/// Copy all validated artifacts from the pool described by `source` into the
+/// empty pool described by `destination`, and verify the result.
+///
+/// The source pool is opened read-only and is left untouched.
+pub fn migrate_pool(
+    source: ArtifactPoolConfig,
+    destination: ArtifactPoolConfig,
+    log: ReplicaLogger,
+) -> Result<MigrationSummary, MigrationError> {
+    let source_path = source.persistent_pool_db_path();
+    let destination_path = destination.persistent_pool_db_path();
+    if source_path == destination_path {
+        return Err(MigrationError::SamePath(source_path));
+    }
+    if PoolBackendKind::detect(&source_path)
+        != Some(PoolBackendKind::of(&source.persistent_pool_backend))
+    {
+        return Err(MigrationError::SourceNotFound(source_path));
+    }
+    if PoolBackendKind::detect(&destination_path).is_some() {
+        return Err(MigrationError::DestinationNotEmpty(destination_path));
+    }
+
+    let mut source = source;
+    source.persistent_pool_read_only = true;
+    let mut destination = destination;
+    destination.persistent_pool_read_only = false;
+
+    info!(
+        log,
+        "Migrating persistent pool from {} ({}) to {} ({})",
+        source_path.display(),
+        PoolBackendKind::of(&source.persistent_pool_backend),
+        destination_path.display(),
+        PoolBackendKind::of(&destination.persistent_pool_backend),
+    );
+
+    let source_consensus = UncachedConsensusPoolImpl::new(source.clone(), log.clone());
+    let mut destination_consensus =
+        UncachedConsensusPoolImpl::new(destination.clone(), log.clone());
+    let (consensus_artifacts, highest_cup_height) = copy_consensus_artifacts(
+        source_consensus.validated.pool_section(),
+        &mut destination_consensus,
+    );
+
+    let source_certification =
+        CertificationPoolImpl::new(source, log.clone(), MetricsRegistry::new());
+    let destination_certification =
+        CertificationPoolImpl::new(destination, log.clone(), MetricsRegistry::new());
+    let certification_artifacts =
+        copy_certification_artifacts(&source_certification, &destination_certification);
+
+    verify_consensus_artifacts(
+        source_consensus.validated.pool_section(),
+        destination_consensus.validated.pool_section(),
+    )?;
+    verify_certification_artifacts(&source_certification, &destination_certification)?;
+
+    let summary = MigrationSummary {
+        consensus_artifacts,
+        certification_artifacts,
+        highest_cup_height,
+    };
+    info!(log, "Persistent pool migration succeeded: {:?}", summary);
+    Ok(summary)
+}
+
+/// Migrate the persistent pool of `config` in place if the backend found on
+/// disk differs from the configured one.
+///
+/// The pool is first migrated into a sibling staging directory, which is
+/// marked as complete once the copy has been verified. The old consensus and
+/// certification sections are then renamed into a sibling backup directory
+/// and replaced by the migrated ones; the backup is only deleted afterwards.
+/// A migration interrupted at any point is thus either rolled forward (from a
+/// complete staging directory) or rolled back (from the backup) on the next
+/// call. Return the summary of the migration, or `None` if no migration was
+/// necessary.
+pub fn migrate_pool_if_needed(
+    config: &ArtifactPoolConfig,
+    log: &ReplicaLogger,
+) -> Result<Option<MigrationSummary>, MigrationError> {
+    let pool_path = config.persistent_pool_db_path();
+    let staging_path = sibling_path(&pool_path, MIGRATION_STAGING_SUFFIX);
+    let backup_path = sibling_path(&pool_path, MIGRATION_BACKUP_SUFFIX);
+    recover_interrupted_migration(&pool_path, &staging_path, &backup_path, log)?;
+
+    let configured = PoolBackendKind::of(&config.persistent_pool_backend);
+    let on_disk = match PoolBackendKind::detect(&pool_path) {
+        Some(kind) if kind != configured => kind,
+        _ => return Ok(None),
+    };
+    info!(
+        log,
+        "Persistent pool at {} uses the {} backend, but {} is configured",
+        pool_path.display(),
+        on_disk,
+        configured
+    );
+
+    fs::create_dir_all(&staging_path)?;
+    let mut source = config.clone();
+    source.persistent_pool_backend =
+        on_disk.backend_at(pool_path.clone(), &config.persistent_pool_backend);
+    let mut destination = config.clone();
+    destination.persistent_pool_backend =
+        configured.backend_at(staging_path.clone(), &config.persistent_pool_backend);
+
+    let summary = match migrate_pool(source, destination, log.clone()) {
+        Ok(summary) => summary,
+        Err(err) => {
+            let _ = fs::remove_dir_all(&staging_path);
+            return Err(err);
+        }
+    };
+    fs::File::create(staging_path.join(MIGRATION_COMPLETE_MARKER))?.sync_all()?;
+
+    recover_interrupted_migration(&pool_path, &staging_path, &backup_path, log)?;
+    Ok(Some(summary))
+}
+
+// Returns `path` with `suffix` appended to its last component.
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut sibling = path.as_os_str().to_owned();
+    sibling.push(suffix);
+    PathBuf::from(sibling)
+}
+
+// Brings the pool at `pool_path` into a consistent state after a (possibly
+// interrupted) migration, then removes the staging and backup directories.
+//
+// If the staging directory holds a complete migration, every section still
+// found in it is swapped in, moving the live section (if any) into the backup
+// directory first. Otherwise, any section that is missing from the pool is
+// restored from the backup directory. Either way, every section is present in
+// exactly one consistent version before anything is deleted.
+fn recover_interrupted_migration(
+    pool_path: &Path,
+    staging_path: &Path,
+    backup_path: &Path,
+    log: &ReplicaLogger,
+) -> io::Result<()> {
+    if !staging_path.exists() && !backup_path.exists() {
+        return Ok(());
+    }
+    let staging_complete = staging_path.join(MIGRATION_COMPLETE_MARKER).exists();
+    if staging_complete {
+        info!(
+            log,
+            "Completing the migration of the persistent pool from {}",
+            staging_path.display()
+        );
+    }
+
+    for dir in [CONSENSUS_DIR, CERTIFICATION_DIR] {
+        let live = pool_path.join(dir);
+        let staged = staging_path.join(dir);
+        let backed_up = backup_path.join(dir);
+        if staging_complete && staged.exists() {
+            if live.exists() {
+                if backed_up.exists() {
+                    fs::remove_dir_all(&backed_up)?;
+                }
+                fs::create_dir_all(backup_path)?;
+                fs::rename(&live, &backed_up)?;
+            }
+            fs::rename(&staged, &live)?;
+        } else if !live.exists() && backed_up.exists() {
+            warn!(
+                log,
+                "Restoring {} from an interrupted migration",
+                live.display()
+            );
+            fs::rename(&backed_up, &live)?;
+        }
+    }
+
+    if backup_path.exists() {
+        fs::remove_dir_all(backup_path)?;
+    }
+    if staging_path.exists() {
+        if !staging_complete {
+            warn!(
+                log,
+                "Removing leftovers of an interrupted migration at {}",
+                staging_path.display()
+            );
+        }
+        fs::remove_dir_all(staging_path)?;
+    }
+    Ok(())
+}
+
+// Insert all artifacts of `pool` into `destination` in batches, preserving
+// their timestamps. Returns the number of copied artifacts.
+fn copy_section<T>(
+    source: &dyn PoolSection<ValidatedConsensusArtifact>,
+    pool: &dyn HeightIndexedPool<T>,
+    destination: &mut UncachedConsensusPoolImpl,
+    skip: Option<&ConsensusMessageId>,
+) -> usize
+where
+    T: ConsensusMessageHashable,
+{
+    let mut copied = 0;
+    let mut ops = PoolSectionOps::new();
+    let mut pending = 0;
+    for artifact in pool.get_all() {
+        let id = artifact.get_id();
+        if Some(&id) == skip {
+            continue;
+        }
+        let timestamp = source.get_timestamp(&id).unwrap_or_else(current_time);
+        ops.insert(ValidatedConsensusArtifact {
+            msg: artifact.into_message(),
+            timestamp,
+        });
+        pending += 1;
+        copied += 1;
+        if pending == INSERT_BATCH_SIZE {
+            destination
+                .validated
+                .mutate(std::mem::replace(&mut ops, PoolSectionOps::new()));
+            pending = 0;
+        }
+    }
+    if pending > 0 {
+        destination.validated.mutate(ops);
+    }
+    copied
+}
+
+fn copy_consensus_artifacts(
+    source: &dyn PoolSection<ValidatedConsensusArtifact>,
+    destination: &mut UncachedConsensusPoolImpl,
+) -> (usize, Option<Height>) {
+    let mut copied = 0;
+    copied += copy_section(source, source.random_beacon(), destination, None);
+    copied += copy_section(source, source.random_tape(), destination, None);
+    copied += copy_section(source, source.block_proposal(), destination, None);
+    copied += copy_section(source, source.notarization(), destination, None);
+    copied += copy_section(source, source.finalization(), destination, None);
+    copied += copy_section(source, source.random_beacon_share(), destination, None);
+    copied += copy_section(source, source.random_tape_share(), destination, None);
+    copied += copy_section(source, source.notarization_share(), destination, None);
+    copied += copy_section(source, source.finalization_share(), destination, None);
+    copied += copy_section(source, source.catch_up_package_share(), destination, None);
+
+    // The highest CUP is re-inserted with its original protobuf bytes, so
+    // that the destination serves exactly what peers and the orchestrator
+    // received before the migration.
+    let highest_cup = source
+        .catch_up_package()
+        .height_range()
+        .map(|_| source.highest_catch_up_package_proto());
+    let highest_cup_id = highest_cup
+        .as_ref()
+        .map(|_| source.highest_catch_up_package().get_id());
+    copied += copy_section(
+        source,
+        source.catch_up_package(),
+        destination,
+        highest_cup_id.as_ref(),
+    );
+    let highest_cup_height = highest_cup_id.as_ref().map(|id| id.height);
+    if let Some(proto) = highest_cup {
+        destination.validated.insert_cup_with_proto(proto);
+        copied += 1;
+    }
+    (copied, highest_cup_height)
+}
+
+fn copy_certification_artifacts(
+    source: &CertificationPoolImpl,
+    destination: &CertificationPoolImpl,
+) -> usize {
+    let mut copied = 0;
+    for certification in source.persistent_pool.certifications().get_all() {
+        destination
+            .persistent_pool
+            .insert(CertificationMessage::Certification(certification));
+        copied += 1;
+    }
+    for share in source.persistent_pool.certification_shares().get_all() {
+        destination
+            .persistent_pool
+            .insert(CertificationMessage::CertificationShare(share));
+        copied += 1;
+    }
+    copied
+}
+
+fn section_ids<T: ConsensusMessageHashable>(
+    pool: &dyn HeightIndexedPool<T>,
+) -> HashSet<ConsensusMessageId> {
+    pool.get_all().map(|artifact| artifact.get_id()).collect()
+}
+
+fn verify_section<T: ConsensusMessageHashable>(
+    name: &str,
+    source: &dyn HeightIndexedPool<T>,
+    destination: &dyn HeightIndexedPool<T>,
+) -> Result<(), MigrationError> {
+    if source.height_range() != destination.height_range() {
+        return Err(MigrationError::VerificationFailed(format!(
+            "{} height range differs: {:?} != {:?}",
+            name,
+            source.height_range(),
+            destination.height_range()
+        )));
+    }
+    let source_ids = section_ids(source);
+    let destination_ids = section_ids(destination);
+    if source_ids != destination_ids {
+        return Err(MigrationError::VerificationFailed(format!(
+            "{} artifacts differ: {} in source, {} in destination",
+            name,
+            source_ids.len(),
+            destination_ids.len()
+        )));
+    }
+    Ok(())
+}
+
+fn verify_consensus_artifacts(
+    source: &dyn PoolSection<ValidatedConsensusArtifact>,
+    destination: &dyn PoolSection<ValidatedConsensusArtifact>,
+) -> Result<(), MigrationError> {
+    verify_section(
+        "random_beacon",
+        source.random_beacon(),
+        destination.random_beacon(),
+    )?;
+    verify_section(
+        "random_tape",
+        source.random_tape(),
+        destination.random_tape(),
+    )?;
+    verify_section(
+        "block_proposal",
+        source.block_proposal(),
+        destination.block_proposal(),
+    )?;
+    verify_section(
+        "notarization",
+        source.notarization(),
+        destination.notarization(),
+    )?;
+    verify_section(
+        "finalization",
+        source.finalization(),
+        destination.finalization(),
+    )?;
+    verify_section(
+        "random_beacon_share",
+        source.random_beacon_share(),
+        destination.random_beacon_share(),
+    )?;
+    verify_section(
+        "random_tape_share",
+        source.random_tape_share(),
+        destination.random_tape_share(),
+    )?;
+    verify_section(
+        "notarization_share",
+        source.notarization_share(),
+        destination.notarization_share(),
+    )?;
+    verify_section(
+        "finalization_share",
+        source.finalization_share(),
+        destination.finalization_share(),
+    )?;
+    verify_section(
+        "catch_up_package",
+        source.catch_up_package(),
+        destination.catch_up_package(),
+    )?;
+    verify_section(
+        "catch_up_package_share",
+        source.catch_up_package_share(),
+        destination.catch_up_package_share(),
+    )?;
+    if source.catch_up_package().height_range().is_some()
+        && source.highest_catch_up_package_proto() != destination.highest_catch_up_package_proto()
+    {
+        return Err(MigrationError::VerificationFailed(
+            "highest catch-up package differs".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn verify_certification_section<T: Eq + Hash>(
+    name: &str,
+    source: &dyn HeightIndexedPool<T>,
+    destination: &dyn HeightIndexedPool<T>,
+) -> Result<(), MigrationError> {
+    if source.height_range() != destination.height_range() {
+        return Err(MigrationError::VerificationFailed(format!(
+            "{} height range differs: {:?} != {:?}",
+            name,
+            source.height_range(),
+            destination.height_range()
+        )));
+    }
+    let source_artifacts: HashSet<T> = source.get_all().collect();
+    let destination_artifacts: HashSet<T> = destination.get_all().collect();
+    if source_artifacts != destination_artifacts {
+        return Err(MigrationError::VerificationFailed(format!(
+            "{} artifacts differ: {} in source, {} in destination",
+            name,
+            source_artifacts.len(),
+            destination_artifacts.len()
+        )));
+    }
+    Ok(())
+}
+
+fn verify_certification_artifacts(
+    source: &CertificationPoolImpl,
+    destination: &CertificationPoolImpl,
+) -> Result<(), MigrationError> {
+    verify_certification_section(
+        "certification",
+        source.persistent_pool.certifications(),
+        destination.persistent_pool.certifications(),
+    )?;
+    verify_certification_section(
+        "certification_share",
+        source.persistent_pool.certification_shares(),
+        destination.persistent_pool.certification_shares(),
+    )
+}
+
+#[cfg(all(test, feature = "rocksdb_backend"))]
+mod tests {
+    use super::*;
+    use crate::test_utils::{finalization_share_ops, make_summary, random_beacon_ops};
+    use ic_logger::replica_logger::no_op_logger;
+    use ic_protobuf::types::v1 as pb;
+    use ic_test_utilities::{
+        artifact_pool_config::with_test_pool_configs,
+        consensus::{
+            fake::{Fake, FakeSigner},
+            make_genesis,
+        },
+        mock_time,
+        types::ids::node_test_id,
+    };
+    use ic_types::{
+        consensus::{
+            certification::{Certification, CertificationContent, CertificationShare},
+            HasHeight,
+        },
+        crypto::{CryptoHash, Signed},
+        signature::{ThresholdSignature, ThresholdSignatureShare},
+        CryptoHashOfPartialState,
+    };
+
+    fn with_backend(config: &ArtifactPoolConfig, kind: PoolBackendKind) -> ArtifactPoolConfig {
+        let mut config = config.clone();
+        config.persistent_pool_backend = kind.backend_at(
+            config.persistent_pool_db_path(),
+            &config.persistent_pool_backend,
+        );
+        config
+    }
+
+    // Populates the pool with random beacons, finalization shares, a CUP and
+    // a few certification artifacts. Returns the number of inserted
+    // consensus and certification artifacts.
+    fn populate(config: &ArtifactPoolConfig) -> (usize, usize) {
+        let mut consensus = UncachedConsensusPoolImpl::new(config.clone(), no_op_logger());
+        let beacons = random_beacon_ops();
+        let shares = finalization_share_ops();
+        let consensus_artifacts = beacons.ops.len() + shares.ops.len() + 1;
+        consensus.validated.mutate(beacons);
+        consensus.validated.mutate(shares);
+        let cup = make_genesis(make_summary(Height::from(20)));
+        consensus
+            .validated
+            .insert_cup_with_proto(pb::CatchUpPackage::from(&cup));
+
+        let certification =
+            CertificationPoolImpl::new(config.clone(), no_op_logger(), MetricsRegistry::new());
+        let content =
+            CertificationContent::new(CryptoHashOfPartialState::from(CryptoHash(Vec::new())));
+        for height in 1..4 {
+            certification
+                .persistent_pool
+                .insert(CertificationMessage::Certification(Certification {
+                    height: Height::from(height),
+                    signed: Signed {
+                        content: content.clone(),
+                        signature: ThresholdSignature::fake(),
+                    },
+                }));
+            certification
+                .persistent_pool
+                .insert(CertificationMessage::CertificationShare(
+                    CertificationShare {
+                        height: Height::from(height),
+                        signed: Signed {
+                            content: content.clone(),
+                            signature: ThresholdSignatureShare::fake(node_test_id(0)),
+                        },
+                    },
+                ));
+        }
+        (consensus_artifacts, 6)
+    }
+
+    #[test]
+    fn test_migrate_lmdb_to_rocksdb() {
+        with_test_pool_configs(2, |configs| {
+            let source = with_backend(&configs[0], PoolBackendKind::Lmdb);
+            let destination = with_backend(&configs[1], PoolBackendKind::RocksDB);
+            let (consensus_artifacts, certification_artifacts) = populate(&source);
+
+            let summary =
+                migrate_pool(source.clone(), destination.clone(), no_op_logger()).unwrap();
+            assert_eq!(
+                summary,
+                MigrationSummary {
+                    consensus_artifacts,
+                    certification_artifacts,
+                    highest_cup_height: Some(Height::from(20)),
+                }
+            );
+            assert_eq!(
+                PoolBackendKind::detect(&destination.persistent_pool_db_path()),
+                Some(PoolBackendKind::RocksDB)
+            );
+
+            // Timestamps are preserved.
+            let migrated = UncachedConsensusPoolImpl::new(destination, no_op_logger());
+            let section = migrated.validated.pool_section();
+            for beacon in section.random_beacon().get_all() {
+                assert_eq!(section.get_timestamp(&beacon.get_id()), Some(mock_time()));
+            }
+        })
+    }
+
+    #[test]
+    fn test_migrate_rejects_non_empty_destination() {
+        with_test_pool_configs(2, |configs| {
+            let source = with_backend(&configs[0], PoolBackendKind::Lmdb);
+            let destination = with_backend(&configs[1], PoolBackendKind::RocksDB);
+            populate(&source);
+            populate(&destination);
+
+            assert!(matches!(
+                migrate_pool(source, destination, no_op_logger()),
+                Err(MigrationError::DestinationNotEmpty(_))
+            ));
+        })
+    }
+
+    #[test]
+    fn test_migrate_pool_if_needed_switches_backend_in_place() {
+        with_test_pool_configs(1, |configs| {
+            let lmdb = with_backend(&configs[0], PoolBackendKind::Lmdb);
+            let rocksdb = with_backend(&configs[0], PoolBackendKind::RocksDB);
+            let (consensus_artifacts, _) = populate(&lmdb);
+
+            // Nothing to do if the configured backend is the one on disk.
+            assert!(migrate_pool_if_needed(&lmdb, &no_op_logger())
+                .unwrap()
+                .is_none());
+
+            let summary = migrate_pool_if_needed(&rocksdb, &no_op_logger())
+                .unwrap()
+                .expect("migration should have happened");
+            assert_eq!(summary.consensus_artifacts, consensus_artifacts);
+            let pool_path = rocksdb.persistent_pool_db_path();
+            assert_eq!(
+                PoolBackendKind::detect(&pool_path),
+                Some(PoolBackendKind::RocksDB)
+            );
+            assert!(!pool_path.join(CONSENSUS_DIR).join("data.mdb").exists());
+
+            let migrated = UncachedConsensusPoolImpl::new(rocksdb.clone(), no_op_logger());
+            assert_eq!(
+                migrated
+                    .validated
+                    .pool_section()
+                    .highest_catch_up_package()
+                    .height(),
+                Height::from(20)
+            );
+            drop(migrated);
+            assert!(migrate_pool_if_needed(&rocksdb, &no_op_logger())
+                .unwrap()
+                .is_none());
+        })
+    }
+
+    #[test]
+    fn test_migrate_pool_if_needed_completes_interrupted_swap() {
+        with_test_pool_configs(1, |configs| {
+            let lmdb = with_backend(&configs[0], PoolBackendKind::Lmdb);
+            let rocksdb = with_backend(&configs[0], PoolBackendKind::RocksDB);
+            populate(&lmdb);
+            let pool_path = rocksdb.persistent_pool_db_path();
+            let staging_path = sibling_path(&pool_path, MIGRATION_STAGING_SUFFIX);
+            let backup_path = sibling_path(&pool_path, MIGRATION_BACKUP_SUFFIX);
+
+            // Simulate a crash right after the live consensus section was
+            // moved into the backup directory.
+            let mut staging = rocksdb.clone();
+            staging.persistent_pool_backend = PoolBackendKind::RocksDB
+                .backend_at(staging_path.clone(), &rocksdb.persistent_pool_backend);
+            migrate_pool(lmdb, staging, no_op_logger()).unwrap();
+            fs::File::create(staging_path.join(MIGRATION_COMPLETE_MARKER)).unwrap();
+            fs::create_dir_all(&backup_path).unwrap();
+            fs::rename(
+                pool_path.join(CONSENSUS_DIR),
+                backup_path.join(CONSENSUS_DIR),
+            )
+            .unwrap();
+
+            // The migration is rolled forward, nothing is left to migrate.
+            assert!(migrate_pool_if_needed(&rocksdb, &no_op_logger())
+                .unwrap()
+                .is_none());
+            assert_eq!(
+                PoolBackendKind::detect(&pool_path),
+                Some(PoolBackendKind::RocksDB)
+            );
+            assert!(!staging_path.exists());
+            assert!(!backup_path.exists());
+            let migrated = UncachedConsensusPoolImpl::new(rocksdb, no_op_logger());
+            assert_eq!(
+                migrated
+                    .validated
+                    .pool_section()
+                    .highest_catch_up_package()
+                    .height(),
+                Height::from(20)
+            );
+        })
+    }
+
+    #[test]
+    fn test_migrate_pool_if_needed_restores_backup_of_incomplete_migration() {
+        with_test_pool_configs(1, |configs| {
+            let lmdb = with_backend(&configs[0], PoolBackendKind::Lmdb);
+            populate(&lmdb);
+            let pool_path = lmdb.persistent_pool_db_path();
+            let staging_path = sibling_path(&pool_path, MIGRATION_STAGING_SUFFIX);
+            let backup_path = sibling_path(&pool_path, MIGRATION_BACKUP_SUFFIX);
+
+            // An incomplete staging directory, and a live section only found in
+            // the backup directory.
+            fs::create_dir_all(staging_path.join(CONSENSUS_DIR)).unwrap();
+            fs::create_dir_all(&backup_path).unwrap();
+            fs::rename(
+                pool_path.join(CONSENSUS_DIR),
+                backup_path.join(CONSENSUS_DIR),
+            )
+            .unwrap();
+
+            assert!(migrate_pool_if_needed(&lmdb, &no_op_logger())
+                .unwrap()
+                .is_none());
+            assert_eq!(
+                PoolBackendKind::detect(&pool_path),
+                Some(PoolBackendKind::Lmdb)
+            );
+            assert!(!staging_path.exists());
+            assert!(!backup_path.exists());
+        })
+    }
+}
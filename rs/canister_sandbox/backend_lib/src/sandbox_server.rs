@@ -213,6 +213,7 @@ mod tests {
             CanisterTimer::Inactive,
             0,
             BTreeSet::from([controller]),
+            None,
         )
     }
 
@@ -60,6 +60,7 @@ impl TryFrom<RequestV3> for ic_types::messages::Request {
             payment: request.payment.cycles.try_into()?,
             method_name: request.method_name,
             method_payload: request.method_payload,
+            deadline: None,
         })
     }
 }
@@ -7,7 +7,11 @@ const MAX_INGRESS_POOL_VALIDATED_CAPACITY: usize = 1024;
 const MAX_INGRESS_POOL_UNVALIDATED_CAPACITY_PER_PEER: usize = 100_000_000;
 const MAX_CONSENSUS_POOL_VALIDATED_CAPACITY: usize = 2048;
 const MAX_CONSENSUS_POOL_UNVALIDATED_CAPACITY_PER_PEER: usize = 2048;
-const PERSISTENT_POOL_VALIDATED_PURGE_INTERVAL: u64 = 5000;
+/// Default byte quotas for the unvalidated sections, per peer (and, for the
+/// consensus pool, per artifact type).
+const MAX_INGRESS_POOL_UNVALIDATED_BYTES_PER_PEER: usize = 256 * 1024 * 1024;
+const MAX_CONSENSUS_POOL_UNVALIDATED_BYTES_PER_PEER: usize = 512 * 1024 * 1024;
+pub const PERSISTENT_POOL_VALIDATED_PURGE_INTERVAL: u64 = 5000;
 
 /// The number of height folders we store grouped inside a single "shard" folder
 /// (to avoid running into inode limits on potentially misconfigured file
@@ -68,8 +72,13 @@ pub struct ArtifactPoolConfig {
     /// of the ingress pool.
     pub ingress_pool_validated_capacity: usize,
     /// The maximum size, in number of messages, of the unvalidated section
-    /// of the ingress pool, per peer.
+    /// of the ingress pool, per peer. When exceeded, the oldest messages
+    /// received from that peer are evicted.
     pub ingress_pool_unvalidated_capacity_per_peer: usize,
+    /// The maximum size, in bytes, of the unvalidated section of the ingress
+    /// pool, per peer. When exceeded, the oldest messages received from that
+    /// peer are evicted.
+    pub ingress_pool_unvalidated_max_bytes_per_peer: usize,
     /// Maximum number of artifacts in ingress pool. If exceeded, we start
     /// throttling ingress. We also throttle if [`ingress_pool_size_max_bytes`]
     /// is exceeded.
@@ -78,8 +87,13 @@ pub struct ArtifactPoolConfig {
     /// We also throttle if [`ingress_pool_size_max_count`] is exceeded.
     pub ingress_pool_max_bytes: usize,
     /// The maximum size, in number of messages, of the unvalidated section
-    /// of the artifact pool, per peer.
+    /// of the consensus pool, per peer and artifact type. When exceeded, the
+    /// oldest artifacts of that type received from that peer are evicted.
     pub consensus_pool_unvalidated_capacity_per_peer: usize,
+    /// The maximum size, in bytes, of the unvalidated section of the
+    /// consensus pool, per peer and artifact type. When exceeded, the oldest
+    /// artifacts of that type received from that peer are evicted.
+    pub consensus_pool_unvalidated_max_bytes_per_peer: usize,
     /// The maximum size, in number of messages, of the validated section
     /// of the artifact pool.
     pub consensus_pool_validated_capacity: usize,
@@ -146,10 +160,15 @@ impl From<ArtifactPoolTomlConfig> for ArtifactPoolConfig {
             ingress_pool_validated_capacity: MAX_INGRESS_POOL_VALIDATED_CAPACITY,
             ingress_pool_unvalidated_capacity_per_peer:
                 MAX_INGRESS_POOL_UNVALIDATED_CAPACITY_PER_PEER,
+            ingress_pool_unvalidated_max_bytes_per_peer:
+                MAX_INGRESS_POOL_UNVALIDATED_BYTES_PER_PEER,
             ingress_pool_max_count: toml_config.ingress_pool_max_count,
             ingress_pool_max_bytes: toml_config.ingress_pool_max_bytes,
-            consensus_pool_unvalidated_capacity_per_peer: MAX_CONSENSUS_POOL_VALIDATED_CAPACITY,
-            consensus_pool_validated_capacity: MAX_CONSENSUS_POOL_UNVALIDATED_CAPACITY_PER_PEER,
+            consensus_pool_unvalidated_capacity_per_peer:
+                MAX_CONSENSUS_POOL_UNVALIDATED_CAPACITY_PER_PEER,
+            consensus_pool_unvalidated_max_bytes_per_peer:
+                MAX_CONSENSUS_POOL_UNVALIDATED_BYTES_PER_PEER,
+            consensus_pool_validated_capacity: MAX_CONSENSUS_POOL_VALIDATED_CAPACITY,
             persistent_pool_backend,
             persistent_pool_read_only: false,
             backup_config: toml_config.backup,
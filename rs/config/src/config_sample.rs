@@ -130,6 +130,10 @@ pub const SAMPLE_CONFIG: &str = r#"
         // - EXAMPLE: csp_vault_type: { unix_socket: "/some/path/to/socket" },
         //   CspVault is run as a separate process, which can be reached via a Unix socket.
         csp_vault_type: { unix_socket: "/some/path/to/socket" },
+        // The file holding the key-encryption key of the secret key stores. If set,
+        // the secret key stores are encrypted at rest, and unencrypted secret key
+        // stores are migrated when the CspVault is started.
+        // EXAMPLE: secret_key_encryption_key_file: "/run/ic-node/crypto-csp/kek",
     },
     // ========================================
     // Configuration of the message scheduling.
@@ -59,6 +59,17 @@ pub struct CryptoConfig {
     )]
     pub crypto_root: PathBuf,
     pub csp_vault_type: CspVaultType,
+    /// Path to the file holding the key-encryption key of the secret key
+    /// stores. If set, the secret key stores in `crypto_root` are encrypted
+    /// at rest with this key, and existing unencrypted secret key stores are
+    /// migrated when the CSP vault is started. The file is read by the
+    /// process running the CSP vault, i.e., by the replica for an
+    /// `in_replica` vault and by the `CspVault`-server otherwise.
+    #[cfg_attr(
+        test,
+        proptest(strategy = "any::<Option<String>>().prop_map(|x| x.map(PathBuf::from))")
+    )]
+    pub secret_key_encryption_key_file: Option<PathBuf>,
 }
 
 impl Default for CryptoConfig {
@@ -66,6 +77,7 @@ impl Default for CryptoConfig {
         Self {
             crypto_root: PathBuf::from(CRYPTO_ROOT_DEFAULT_PATH),
             csp_vault_type: CspVaultType::InReplica,
+            secret_key_encryption_key_file: None,
         }
     }
 }
@@ -76,6 +88,7 @@ impl CryptoConfig {
         Self {
             crypto_root,
             csp_vault_type: CspVaultType::InReplica,
+            secret_key_encryption_key_file: None,
         }
     }
 
@@ -85,6 +98,7 @@ impl CryptoConfig {
         Self {
             crypto_root,
             csp_vault_type: CspVaultType::UnixSocket(socket_path),
+            secret_key_encryption_key_file: None,
         }
     }
 
@@ -68,6 +68,12 @@ pub struct FeatureFlags {
     /// Track dirty pages with a write barrier instead of the signal handler.
     pub write_barrier: FlagStatus,
     pub wasm_native_stable_memory: FlagStatus,
+    /// Allow canisters to use a 64-bit Wasm memory (the memory64 proposal).
+    pub wasm64: FlagStatus,
+    /// Allow canisters to import the Wasm-native stable memory as a second
+    /// Wasm memory and access it with regular load and store instructions.
+    /// Requires `wasm_native_stable_memory`.
+    pub direct_stable_memory_access: FlagStatus,
 }
 
 impl FeatureFlags {
@@ -76,6 +82,8 @@ impl FeatureFlags {
             rate_limiting_of_debug_prints: FlagStatus::Enabled,
             write_barrier: FlagStatus::Disabled,
             wasm_native_stable_memory: FlagStatus::Disabled,
+            wasm64: FlagStatus::Disabled,
+            direct_stable_memory_access: FlagStatus::Disabled,
         }
     }
 }
@@ -9,7 +9,7 @@ use ic_types::{
     MAX_WASM_MEMORY_IN_BYTES,
 };
 use serde::{Deserialize, Serialize};
-use std::{str::FromStr, time::Duration};
+use std::{path::PathBuf, str::FromStr, time::Duration};
 
 const MIB: u64 = 1024 * 1024;
 const GIB: u64 = MIB * 1024;
@@ -26,6 +26,15 @@ const GIB: u64 = MIB * 1024;
 /// canister's data and the deltas.
 const SUBNET_MEMORY_CAPACITY: NumBytes = NumBytes::new(450 * GIB);
 
+/// Once the subnet memory usage goes above this threshold, newly allocated
+/// memory starts reserving cycles for its future storage payments.
+/// See `CyclesAccountManager::storage_reservation_cycles()`.
+const SUBNET_MEMORY_THRESHOLD: NumBytes = NumBytes::new(300 * GIB);
+
+/// The upper limit on the reserved cycles balance of a canister that hasn't
+/// explicitly configured its `reserved_cycles_limit`.
+pub const DEFAULT_RESERVED_BALANCE_LIMIT: Cycles = Cycles::new(5 * 1_000_000_000_000);
+
 /// This is the upper limit on how much memory can be used by all canister
 /// messages on a given subnet.
 ///
@@ -109,6 +118,9 @@ const BITCOIN_MAINNET_SOFT_LAUNCH_CANISTER_ID: &str = "gsvzx-syaaa-aaaan-aaabq-c
 /// The capacity of the Wasm compilation cache.
 pub const MAX_COMPILATION_CACHE_SIZE: NumBytes = NumBytes::new(10 * GIB);
 
+/// The capacity of the on-disk tier of the Wasm compilation cache.
+pub const MAX_COMPILATION_CACHE_DISK_SIZE: NumBytes = NumBytes::new(50 * GIB);
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default)]
 pub struct Config {
@@ -124,6 +136,10 @@ pub struct Config {
     /// the subnet.
     pub subnet_memory_capacity: NumBytes,
 
+    /// The threshold of subnet memory usage above which newly allocated
+    /// memory reserves cycles for its future storage payments.
+    pub subnet_memory_threshold: NumBytes,
+
     /// The maximum amount of logical storage available to canister messages
     /// across the whole subnet.
     pub subnet_message_memory_capacity: NumBytes,
@@ -212,6 +228,11 @@ pub struct Config {
     /// Indicates whether replica side query caching is enabled.
     pub query_caching: FlagStatus,
 
+    /// Indicates whether canisters can use a 64-bit Wasm memory. The total
+    /// memory usage of such canisters is still bounded by
+    /// `max_canister_memory_size`.
+    pub wasm64: FlagStatus,
+
     /// Query cache capacity in bytes
     pub query_cache_capacity: NumBytes,
 
@@ -233,6 +254,14 @@ pub struct Config {
 
     /// The capacity of the Wasm compilation cache.
     pub max_compilation_cache_size: NumBytes,
+
+    /// The directory of the on-disk tier of the Wasm compilation cache. The
+    /// on-disk tier keeps compiled modules across replica restarts. It is
+    /// disabled if no directory is configured.
+    pub compilation_cache_dir: Option<PathBuf>,
+
+    /// The capacity of the on-disk tier of the Wasm compilation cache.
+    pub max_compilation_cache_disk_size: NumBytes,
 }
 
 impl Default for Config {
@@ -251,6 +280,7 @@ impl Default for Config {
             create_funds_whitelist: String::default(),
             max_instructions_for_message_acceptance_calls: MAX_INSTRUCTIONS_PER_MESSAGE_WITHOUT_DTS,
             subnet_memory_capacity: SUBNET_MEMORY_CAPACITY,
+            subnet_memory_threshold: SUBNET_MEMORY_THRESHOLD,
             subnet_message_memory_capacity: SUBNET_MESSAGE_MEMORY_CAPACITY,
             ingress_history_memory_capacity: INGRESS_HISTORY_MEMORY_CAPACITY,
             subnet_wasm_custom_sections_memory_capacity:
@@ -295,6 +325,7 @@ impl Default for Config {
             },
             composite_queries: FlagStatus::Disabled,
             query_caching: FlagStatus::Enabled,
+            wasm64: FlagStatus::Disabled,
             query_cache_capacity: QUERY_CACHE_CAPACITY,
             min_sandbox_count: embedders::DEFAULT_MIN_SANDBOX_COUNT,
             max_sandbox_count: embedders::DEFAULT_MAX_SANDBOX_COUNT,
@@ -303,6 +334,8 @@ impl Default for Config {
                 embedders::STABLE_MEMORY_DIRTY_PAGE_LIMIT,
             ),
             max_compilation_cache_size: MAX_COMPILATION_CACHE_SIZE,
+            compilation_cache_dir: None,
+            max_compilation_cache_disk_size: MAX_COMPILATION_CACHE_DISK_SIZE,
         }
     }
 }
@@ -30,6 +30,11 @@ pub struct Config {
 
     /// If this Sec256k1 PEM is available, use it instead of the HSM.
     pub node_operator_pem: Option<PathBuf>,
+
+    /// The period (in seconds) after which the node rotates its committee signing key and
+    /// TLS certificate. If not set, these keys are not rotated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_signing_keys_rotation_period_secs: Option<u64>,
 }
 
 // We allow for the operator to only specify some of the fields while the others
@@ -57,6 +62,7 @@ impl Default for Config {
             nns_url: None,
             nns_pub_key_pem: None,
             node_operator_pem: None,
+            node_signing_keys_rotation_period_secs: None,
         }
     }
 }
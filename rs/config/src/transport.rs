@@ -7,6 +7,8 @@ use std::default::Default;
 pub struct TransportConfig {
     pub node_ip: String,
 
+    /// The TCP port to listen on, or the UDP port if the subnet record enables
+    /// the QUIC transport.
     pub listening_port: u16,
 
     pub send_queue_size: usize,
@@ -22,7 +24,7 @@ impl Default for TransportConfig {
             send_queue_size: 51200,
             node_ip: String::default(),
             listening_port: u16::default(),
-            max_streams: 1,
+            max_streams: 4,
         }
     }
 }
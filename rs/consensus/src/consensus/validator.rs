@@ -34,7 +34,10 @@ use ic_types::{
         HasHeight, HasRank, Notarization, NotarizationContent, RandomBeacon, RandomBeaconShare,
         RandomTape, RandomTapeShare, Rank,
     },
-    crypto::{threshold_sig::ni_dkg::NiDkgId, CryptoError, CryptoHashOf, Signed},
+    crypto::{
+        threshold_sig::ni_dkg::NiDkgId, CryptoError, CryptoHashOf, CryptoResult,
+        IndividualMultiSigOf, Signed,
+    },
     registry::RegistryClientError,
     replica_config::ReplicaConfig,
     signature::{MultiSignature, MultiSignatureShare, ThresholdSignatureShare},
@@ -283,6 +286,12 @@ trait NotaryIssued: Sized + HasHeight + std::fmt::Debug {
         signed_message: &Signed<Self, MultiSignatureShare<Self>>,
         registry_version: RegistryVersion,
     ) -> ValidationResult<CryptoError>;
+    fn verify_multi_sig_individuals_batch(
+        crypto: &dyn ConsensusCrypto,
+        content: &Self,
+        signatures: &BTreeMap<NodeId, IndividualMultiSigOf<Self>>,
+        registry_version: RegistryVersion,
+    ) -> BTreeMap<NodeId, CryptoResult<()>>;
     fn is_duplicate(&self, pool: &PoolReader) -> bool;
     fn dependencies_validated(&self, pool: &PoolReader) -> Result<(), &str>;
 }
@@ -308,6 +317,15 @@ impl NotaryIssued for NotarizationContent {
         crypto.verify(signed_message, registry_version)
     }
 
+    fn verify_multi_sig_individuals_batch(
+        crypto: &dyn ConsensusCrypto,
+        content: &Self,
+        signatures: &BTreeMap<NodeId, IndividualMultiSigOf<Self>>,
+        registry_version: RegistryVersion,
+    ) -> BTreeMap<NodeId, CryptoResult<()>> {
+        crypto.verify_multi_sig_individuals_batch(signatures, content, registry_version)
+    }
+
     fn is_duplicate(&self, pool: &PoolReader) -> bool {
         pool.pool()
             .validated()
@@ -353,6 +371,15 @@ impl NotaryIssued for FinalizationContent {
         crypto.verify(signed_message, registry_version)
     }
 
+    fn verify_multi_sig_individuals_batch(
+        crypto: &dyn ConsensusCrypto,
+        content: &Self,
+        signatures: &BTreeMap<NodeId, IndividualMultiSigOf<Self>>,
+        registry_version: RegistryVersion,
+    ) -> BTreeMap<NodeId, CryptoResult<()>> {
+        crypto.verify_multi_sig_individuals_batch(signatures, content, registry_version)
+    }
+
     fn is_duplicate(&self, pool: &PoolReader) -> bool {
         pool.pool()
             .validated()
@@ -415,15 +442,26 @@ impl<T: NotaryIssued> SignatureVerify for Signed<T, MultiSignatureShare<T>> {
         crypto: &dyn ConsensusCrypto,
         pool: &PoolReader<'_>,
     ) -> ValidationResult<ValidatorError> {
-        let height = self.height();
-        let previous_beacon = get_previous_beacon(pool, height)?;
-        verify_notary(membership, height, &previous_beacon, self.signature.signer)?;
-        let registry_version = get_registry_version(pool, height)?;
+        let registry_version = verify_notary_share_signer(membership, pool, self)?;
         T::verify_multi_sig_individual(crypto, self, registry_version)?;
         Ok(())
     }
 }
 
+/// Checks that the signer of a `NotarizationShare` or `FinalizationShare` is
+/// in the notary committee, and returns the registry version that its
+/// signature has to be verified against.
+fn verify_notary_share_signer<T: NotaryIssued>(
+    membership: &Membership,
+    pool: &PoolReader<'_>,
+    share: &Signed<T, MultiSignatureShare<T>>,
+) -> Result<RegistryVersion, ValidatorError> {
+    let height = share.height();
+    let previous_beacon = get_previous_beacon(pool, height)?;
+    verify_notary(membership, height, &previous_beacon, share.signature.signer)?;
+    get_registry_version(pool, height)
+}
+
 fn get_previous_beacon(
     pool: &PoolReader<'_>,
     height: Height,
@@ -678,9 +716,7 @@ impl Validator {
             .finalization_share()
             .get_by_height_range(range);
 
-        finalization_shares
-            .filter_map(|share| self.validate_notary_issued(pool_reader, share))
-            .collect()
+        self.validate_notary_issued_shares(pool_reader, finalization_shares)
     }
 
     /// Return a `ChangeSet` of `Notarization`s. See
@@ -724,9 +760,7 @@ impl Validator {
             .notarization_share()
             .get_by_height_range(range);
 
-        notarization_shares
-            .filter_map(|share| self.validate_notary_issued(pool_reader, share))
-            .collect()
+        self.validate_notary_issued_shares(pool_reader, notarization_shares)
     }
 
     /// Validate a single `Signed`, `NotaryIssued` value. This involves checking
@@ -773,6 +807,101 @@ impl Validator {
         }
     }
 
+    /// Validate `NotarizationShare`s or `FinalizationShare`s. The checks are
+    /// the same as in `validate_notary_issued`, but the signatures of all
+    /// shares on the same content are verified together in a single batch,
+    /// which is considerably cheaper than verifying them one by one. If a
+    /// batch fails, crypto falls back to verifying each share individually, so
+    /// that only the invalid shares are rejected.
+    fn validate_notary_issued_shares<T>(
+        &self,
+        pool_reader: &PoolReader<'_>,
+        shares: impl Iterator<Item = Signed<T, MultiSignatureShare<T>>>,
+    ) -> ChangeSet
+    where
+        Signed<T, MultiSignatureShare<T>>: ConsensusMessageHashable,
+        T: NotaryIssued + PartialEq,
+    {
+        // Shares that passed all checks except signature verification, grouped
+        // by their content and the registry version of the signers' keys.
+        let mut batches: Vec<(RegistryVersion, Vec<Signed<T, MultiSignatureShare<T>>>)> =
+            Vec::new();
+        let mut change_set = ChangeSet::new();
+        for share in shares {
+            // This is checked before entering this function.
+            debug_assert!(share.height() > pool_reader.get_finalized_height());
+            if share.content.is_duplicate(pool_reader) {
+                change_set.push(ChangeAction::RemoveFromUnvalidated(share.into_message()));
+                continue;
+            }
+            if let Err(err) = share.content.dependencies_validated(pool_reader) {
+                if self.unvalidated_for_too_long(pool_reader, &share.get_id()) {
+                    warn!(every_n_seconds => LOG_EVERY_N_SECONDS,
+                          self.log,
+                          "{} {:?}", err, share.content
+                    );
+                }
+                continue;
+            }
+            let registry_version =
+                match verify_notary_share_signer(self.membership.as_ref(), pool_reader, &share) {
+                    Ok(registry_version) => registry_version,
+                    Err(err) => {
+                        change_set.extend(self.compute_action_from_sig_verification(
+                            pool_reader,
+                            Err(err),
+                            share.into_message(),
+                        ));
+                        continue;
+                    }
+                };
+            // A signer appearing twice for the same content starts a new batch,
+            // so that each batch has at most one share per signer.
+            let batch = batches.iter_mut().find(|(version, batch)| {
+                *version == registry_version
+                    && batch[0].content == share.content
+                    && batch
+                        .iter()
+                        .all(|other| other.signature.signer != share.signature.signer)
+            });
+            match batch {
+                Some((_, batch)) => batch.push(share),
+                None => batches.push((registry_version, vec![share])),
+            }
+        }
+
+        for (registry_version, batch) in batches {
+            let signatures = batch
+                .iter()
+                .map(|share| (share.signature.signer, share.signature.signature.clone()))
+                .collect();
+            let mut results = T::verify_multi_sig_individuals_batch(
+                self.crypto.as_ref(),
+                &batch[0].content,
+                &signatures,
+                registry_version,
+            );
+            for share in batch {
+                let result = match results.remove(&share.signature.signer) {
+                    Some(result) => result.map_err(ValidatorError::from),
+                    None => Err(TransientError::CryptoError(CryptoError::InvalidArgument {
+                        message: format!(
+                            "Missing batch verification result for signer {}",
+                            share.signature.signer
+                        ),
+                    })
+                    .into()),
+                };
+                change_set.extend(self.compute_action_from_sig_verification(
+                    pool_reader,
+                    result,
+                    share.into_message(),
+                ));
+            }
+        }
+        change_set
+    }
+
     /// Return a `ChangeSet` containing status updates concerning any currently
     /// unvalidated blocks that can now be marked valid or invalid. See
     /// `check_block_validity`.
@@ -2667,6 +2796,96 @@ pub mod test {
         })
     }
 
+    #[test]
+    fn test_invalid_notarization_share_in_batch_only_rejects_that_share() {
+        use ic_crypto_temp_crypto::{NodeKeysToGenerate, TempCryptoComponent};
+        use ic_interfaces::crypto::MultiSigner;
+
+        ic_test_utilities::artifact_pool_config::with_test_pool_config(|pool_config| {
+            let node_ids: Vec<_> = (0..4).map(node_test_id).collect();
+            let (
+                payload_builder,
+                membership,
+                state_manager,
+                message_routing,
+                _crypto,
+                data_provider,
+                registry_client,
+                mut pool,
+                dkg_pool,
+                time_source,
+                replica_config,
+            ) = setup_dependencies(pool_config, &node_ids);
+            // Use real crypto, so that the signatures of the shares are
+            // actually verified.
+            let registry_version = RegistryVersion::from(1);
+            let cryptos: Vec<_> = node_ids
+                .iter()
+                .map(|node_id| {
+                    TempCryptoComponent::builder()
+                        .with_node_id(*node_id)
+                        .with_registry_client_and_data(
+                            Arc::clone(&registry_client) as Arc<_>,
+                            Arc::clone(&data_provider),
+                        )
+                        .with_keys_in_registry_version(
+                            NodeKeysToGenerate::only_committee_signing_key(),
+                            registry_version,
+                        )
+                        .build_arc()
+                })
+                .collect();
+            registry_client.reload();
+
+            let block = pool.make_next_block();
+            pool.insert_validated(block.clone());
+            let shares: Vec<_> = node_ids
+                .iter()
+                .enumerate()
+                .map(|(i, node_id)| {
+                    let mut share = NotarizationShare::fake(block.as_ref(), *node_id);
+                    // The last share is signed with the key of another node.
+                    let signer = if i == 3 { 2 } else { i };
+                    share.signature.signature = cryptos[signer]
+                        .sign_multi(&share.content, node_ids[signer], registry_version)
+                        .unwrap();
+                    share
+                })
+                .collect();
+            for share in shares.iter() {
+                pool.insert_unvalidated(share.clone());
+            }
+
+            let validator = Validator::new(
+                replica_config,
+                membership,
+                registry_client,
+                cryptos[0].clone(),
+                payload_builder,
+                state_manager,
+                message_routing,
+                dkg_pool,
+                no_op_logger(),
+                ValidatorMetrics::new(MetricsRegistry::new()),
+                Arc::clone(&time_source) as Arc<_>,
+            );
+
+            let changeset = validator.on_state_change(&PoolReader::new(&pool));
+            assert_eq!(changeset.len(), shares.len());
+            for share in shares.iter() {
+                let message = share.clone().into_message();
+                if share.signature.signer == node_test_id(3) {
+                    assert!(changeset.iter().any(|action| matches!(
+                        action,
+                        ChangeAction::HandleInvalid(m, _) if *m == message
+                    )));
+                } else {
+                    assert!(changeset.contains(&ChangeAction::MoveToValidated(message)));
+                }
+            }
+        })
+    }
+
     #[test]
     fn test_notarization_deduped_by_content() {
         ic_test_utilities::artifact_pool_config::with_test_pool_config(|pool_config| {
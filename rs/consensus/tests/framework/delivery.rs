@@ -2,10 +2,55 @@
 
 use super::types::*;
 use ic_logger::trace;
+use ic_types::time::Time;
 use rand::seq::SliceRandom;
 use rand::Rng;
 use std::time::Duration;
 
+/// Deliver a message that `from` sent at time `sent` to the input queue of
+/// `to`, where it arrives at `msg.timestamp` unless network faults delay or
+/// drop it.
+fn deliver_to(
+    runner: &dyn ConsensusInstances<'_>,
+    from: &ConsensusInstance<'_>,
+    to: &ConsensusInstance<'_>,
+    sent: Time,
+    msg: Message,
+) {
+    let logger = runner.logger();
+    let arrival = runner.faults().arrival(
+        from.index,
+        to.index,
+        runner.start_time(),
+        sent,
+        msg.timestamp,
+        &mut *runner.rng(),
+    );
+    match arrival {
+        Some(timestamp) => {
+            let msg = Message {
+                message: msg.message,
+                timestamp,
+            };
+            trace!(
+                logger,
+                "Deliver from instance {} to {}: {:?}",
+                from.deps.replica_config.node_id,
+                to.deps.replica_config.node_id,
+                msg,
+            );
+            to.in_queue.borrow_mut().push(Input::Message(msg));
+        }
+        None => trace!(
+            logger,
+            "Drop from instance {} to {}: {:?}",
+            from.deps.replica_config.node_id,
+            to.deps.replica_config.node_id,
+            msg,
+        ),
+    }
+}
+
 fn get_instance_with_least_outgoing_message_timestamp<'a, 'b>(
     instances: &'b [ConsensusInstance<'a>],
 ) -> Option<&'b ConsensusInstance<'a>> {
@@ -31,7 +76,6 @@ impl Sequential {
 
 impl DeliveryStrategy for Sequential {
     fn deliver_next(&self, runner: &dyn ConsensusInstances<'_>) -> bool {
-        let logger = runner.logger();
         let instances = runner.instances();
         if let Some(instance) = get_instance_with_least_outgoing_message_timestamp(instances) {
             if let Some(x) = instance.out_queue.borrow_mut().pop() {
@@ -43,15 +87,7 @@ impl DeliveryStrategy for Sequential {
                 };
                 for other in instances.iter() {
                     if other.deps.replica_config.node_id != instance.deps.replica_config.node_id {
-                        trace!(
-                            logger,
-                            "Deliver from instance {} to {}: {:?}",
-                            instance.deps.replica_config.node_id,
-                            other.deps.replica_config.node_id,
-                            msg,
-                        );
-                        let mut in_queue = other.in_queue.borrow_mut();
-                        in_queue.push(Input::Message(msg.clone()));
+                        deliver_to(runner, instance, other, x.timestamp, msg.clone());
                     }
                 }
                 return true;
@@ -78,27 +114,17 @@ impl RandomReceive {
 
 impl DeliveryStrategy for RandomReceive {
     fn deliver_next(&self, runner: &dyn ConsensusInstances<'_>) -> bool {
-        let logger = runner.logger();
         let instances = runner.instances();
         if let Some(instance) = get_instance_with_least_outgoing_message_timestamp(instances) {
             if let Some(x) = instance.out_queue.borrow_mut().pop() {
-                let mut rng = runner.rng();
                 for other in instances.iter() {
                     if other.deps.replica_config.node_id != instance.deps.replica_config.node_id {
-                        let mut in_queue = other.in_queue.borrow_mut();
-                        let delay = rng.gen_range(UNIT_TIME_STEP..self.max_delta);
+                        let delay = runner.rng().gen_range(UNIT_TIME_STEP..self.max_delta);
                         let msg = Message {
                             message: x.message.clone(),
                             timestamp: x.timestamp + Duration::from_millis(delay),
                         };
-                        trace!(
-                            logger,
-                            "Deliver from instance {} to {}: {:?}",
-                            instance.deps.replica_config.node_id,
-                            other.deps.replica_config.node_id,
-                            msg,
-                        );
-                        in_queue.push(Input::Message(msg));
+                        deliver_to(runner, instance, other, x.timestamp, msg);
                     }
                 }
                 return true;
@@ -119,27 +145,18 @@ pub struct RandomGraph {
 
 impl DeliveryStrategy for RandomGraph {
     fn deliver_next(&self, runner: &dyn ConsensusInstances<'_>) -> bool {
-        let logger = runner.logger();
         let instances = runner.instances();
         if let Some(instance) = get_instance_with_least_outgoing_message_timestamp(instances) {
             if let Some(x) = instance.out_queue.borrow_mut().pop() {
                 for other in instances.iter() {
                     if other.deps.replica_config.node_id != instance.deps.replica_config.node_id {
-                        let mut in_queue = other.in_queue.borrow_mut();
                         let delay =
                             self.distances[instance.index][other.index] as u32 * self.unit_latency;
                         let msg = Message {
                             message: x.message.clone(),
                             timestamp: x.timestamp + delay,
                         };
-                        trace!(
-                            logger,
-                            "Deliver from instance {} to {}: {:?}",
-                            instance.deps.replica_config.node_id,
-                            other.deps.replica_config.node_id,
-                            msg,
-                        );
-                        in_queue.push(Input::Message(msg));
+                        deliver_to(runner, instance, other, x.timestamp, msg);
                     }
                 }
                 return true;
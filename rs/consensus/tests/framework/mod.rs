@@ -2,9 +2,11 @@
 mod delivery;
 mod driver;
 mod execution;
+mod network;
 mod runner;
 mod types;
 
+pub use network::{NetworkFaults, Partition};
 pub use runner::ConsensusRunner;
 pub use types::{ConsensusDependencies, ConsensusDriver, ConsensusInstance, ConsensusRunnerConfig};
 
@@ -15,8 +17,8 @@ use ic_protobuf::registry::subnet::v1::{CatchUpPackageContents, InitialNiDkgTran
 use ic_registry_client_fake::FakeRegistryClient;
 use ic_registry_client_helpers::crypto::CryptoRegistry;
 use ic_registry_proto_data_provider::ProtoRegistryDataProvider;
-use ic_test_utilities::consensus::make_genesis;
-use ic_test_utilities_registry::SubnetRecordBuilder;
+use ic_test_utilities::{consensus::make_genesis, crypto::CryptoReturningOk};
+use ic_test_utilities_registry::{setup_registry_non_final, SubnetRecordBuilder};
 use ic_types::{
     consensus::CatchUpPackage,
     crypto::{
@@ -160,3 +162,42 @@ pub fn setup_subnet(
     let cup = make_genesis(summary);
     (registry_client, cup, cryptos)
 }
+
+/// Setup a subnet like [`setup_subnet`], but with initial NiDKG transcripts
+/// and crypto components that do not depend on any randomness, so that runs
+/// with the same seed can be reproduced exactly. Signatures are neither
+/// produced nor checked, so this is only suitable to test the consensus logic
+/// itself.
+pub fn setup_subnet_with_fake_crypto(
+    subnet_id: SubnetId,
+    node_ids: &[NodeId],
+) -> (
+    Arc<dyn RegistryClient>,
+    CatchUpPackage,
+    Vec<Arc<CryptoReturningOk>>,
+) {
+    let initial_version = 1;
+    let (data_provider, registry_client) = setup_registry_non_final(
+        subnet_id,
+        vec![(initial_version, SubnetRecordBuilder::from(node_ids).build())],
+    );
+    // This is required by the XNet payload builder.
+    for node in node_ids.iter() {
+        data_provider
+            .add(
+                &ic_registry_keys::make_node_record_key(*node),
+                RegistryVersion::from(initial_version),
+                Some(ic_protobuf::registry::node::v1::NodeRecord::default()),
+            )
+            .expect("Could not add node record.");
+    }
+    registry_client.update_to_latest_version();
+
+    let summary = ic_consensus::dkg::make_genesis_summary(&*registry_client, subnet_id, None);
+    let cup = make_genesis(summary);
+    let cryptos = node_ids
+        .iter()
+        .map(|_| Arc::new(CryptoReturningOk::default()))
+        .collect();
+    (registry_client, cup, cryptos)
+}
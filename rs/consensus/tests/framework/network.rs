@@ -0,0 +1,138 @@
+use ic_types::time::Time;
+use rand::Rng;
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+/// A network partition that separates the instances in `nodes` (given by
+/// their index) from all other instances during the time window
+/// `[from, until)`, measured from the start of the simulation.
+///
+/// Messages that would cross the partition while it is active are held back
+/// and delivered once the partition heals, which mimics P2P re-advertising
+/// validated artifacts to peers that become reachable again.
+#[derive(Clone, Debug)]
+pub struct Partition {
+    pub nodes: BTreeSet<usize>,
+    pub from: Duration,
+    pub until: Duration,
+}
+
+impl Partition {
+    fn separates(&self, i: usize, j: usize) -> bool {
+        self.nodes.contains(&i) != self.nodes.contains(&j)
+    }
+}
+
+/// Faults injected into message delivery, on top of the latency that a
+/// delivery strategy already assigns to every message.
+#[derive(Clone, Debug, Default)]
+pub struct NetworkFaults {
+    /// Probability that the delivery of a message to a single peer fails.
+    pub drop_rate: f64,
+    /// Delay after which a failed delivery is retried. If `None`, failed
+    /// deliveries are lost for good.
+    pub retransmission_delay: Option<Duration>,
+    /// Partitions that separate groups of instances for some time.
+    pub partitions: Vec<Partition>,
+}
+
+impl NetworkFaults {
+    /// Return the time at which a message sent by instance `from` at `sent`
+    /// reaches instance `to`, given that it would otherwise arrive at
+    /// `arrival`. Return `None` if the message is lost.
+    ///
+    /// The random number generator is only used when messages may be dropped,
+    /// so that runs without faults remain unaffected.
+    pub(crate) fn arrival<R: Rng>(
+        &self,
+        from: usize,
+        to: usize,
+        start: Time,
+        sent: Time,
+        arrival: Time,
+        rng: &mut R,
+    ) -> Option<Time> {
+        let mut arrival = arrival;
+        if self.drop_rate > 0.0 && rng.gen_bool(self.drop_rate) {
+            arrival = arrival + self.retransmission_delay?;
+        }
+        for partition in self.partitions.iter() {
+            let (begin, end) = (start + partition.from, start + partition.until);
+            if partition.separates(from, to) && sent < end && arrival >= begin {
+                arrival = std::cmp::max(arrival, end);
+            }
+        }
+        Some(arrival)
+    }
+
+    /// Return true if the faults may prevent some messages from ever being
+    /// delivered.
+    pub fn is_lossy(&self) -> bool {
+        self.drop_rate > 0.0 && self.retransmission_delay.is_none()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ic_types::time::UNIX_EPOCH;
+    use rand_chacha::{rand_core::SeedableRng, ChaChaRng};
+
+    fn at(millis: u64) -> Time {
+        UNIX_EPOCH + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_partition_holds_back_messages() {
+        let faults = NetworkFaults {
+            partitions: vec![Partition {
+                nodes: vec![0].into_iter().collect(),
+                from: Duration::from_millis(100),
+                until: Duration::from_millis(200),
+            }],
+            ..Default::default()
+        };
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let start = at(0);
+        // Before and after the partition, messages are not affected.
+        assert_eq!(
+            faults.arrival(0, 1, start, at(10), at(20), &mut rng),
+            Some(at(20))
+        );
+        assert_eq!(
+            faults.arrival(1, 0, start, at(200), at(210), &mut rng),
+            Some(at(210))
+        );
+        // Messages crossing the partition are held back until it heals.
+        assert_eq!(
+            faults.arrival(0, 1, start, at(90), at(110), &mut rng),
+            Some(at(200))
+        );
+        assert_eq!(
+            faults.arrival(1, 0, start, at(150), at(160), &mut rng),
+            Some(at(200))
+        );
+        // Messages within one side of the partition are not affected.
+        assert_eq!(
+            faults.arrival(1, 2, start, at(150), at(160), &mut rng),
+            Some(at(160))
+        );
+    }
+
+    #[test]
+    fn test_dropped_messages() {
+        let mut faults = NetworkFaults {
+            drop_rate: 1.0,
+            retransmission_delay: Some(Duration::from_millis(500)),
+            ..Default::default()
+        };
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        assert_eq!(
+            faults.arrival(0, 1, at(0), at(10), at(20), &mut rng),
+            Some(at(520))
+        );
+        faults.retransmission_delay = None;
+        assert!(faults.is_lossy());
+        assert_eq!(faults.arrival(0, 1, at(0), at(10), at(20), &mut rng), None);
+    }
+}
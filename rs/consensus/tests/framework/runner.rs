@@ -1,5 +1,6 @@
 use super::delivery::*;
 use super::execution::*;
+use super::network::NetworkFaults;
 use super::types::*;
 use ic_config::artifact_pool::ArtifactPoolConfig;
 use ic_consensus::consensus::dkg_key_manager::DkgKeyManager;
@@ -15,12 +16,12 @@ use ic_interfaces::time_source::TimeSource;
 use ic_logger::{info, warn, ReplicaLogger};
 use ic_test_utilities::FastForwardTimeSource;
 use ic_test_utilities_registry::FakeLocalStoreCertifiedTimeReader;
-use ic_types::malicious_flags::MaliciousFlags;
-use ic_types::Time;
+use ic_types::{Height, Time};
 use rand::{thread_rng, Rng, RngCore};
 use rand_chacha::{rand_core::SeedableRng, ChaChaRng};
 use slog::Drain;
 use std::cell::{RefCell, RefMut};
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::time::Duration;
@@ -44,6 +45,7 @@ const MAX_IDLE_TIME: u64 = 50000;
 
 pub struct ConsensusRunner<'a> {
     idle_since: RefCell<Time>,
+    start_time: Time,
     pub time: Arc<FastForwardTimeSource>,
     pub instances: Vec<ConsensusInstance<'a>>,
     pub(crate) stop_predicate: StopPredicate<'a>,
@@ -65,6 +67,12 @@ impl<'a> ConsensusInstances<'a> for ConsensusRunner<'a> {
     fn time_source(&self) -> &dyn TimeSource {
         self.time.as_ref()
     }
+    fn faults(&self) -> &NetworkFaults {
+        &self.config.faults
+    }
+    fn start_time(&self) -> Time {
+        self.start_time
+    }
 }
 
 const SLOG_ASYNC_CHAN_SIZE: usize = 10000;
@@ -102,6 +110,7 @@ impl<'a> ConsensusRunner<'a> {
         let rng = RefCell::new(ChaChaRng::seed_from_u64(config.random_seed));
         ConsensusRunner {
             idle_since: RefCell::new(now),
+            start_time: now,
             instances: Vec::new(),
             stop_predicate: &stop_immediately,
             time: time_source,
@@ -126,6 +135,13 @@ impl<'a> ConsensusRunner<'a> {
         pool_reader: &PoolReader<'_>,
     ) {
         let node_id = deps.replica_config.node_id;
+        let index = self.instances.len();
+        let malicious_flags = self
+            .config
+            .malicious
+            .get(&index)
+            .cloned()
+            .unwrap_or_default();
 
         let mut context = self.logger.get_context();
         context.node_id = format!("{}", node_id.get());
@@ -158,7 +174,7 @@ impl<'a> ConsensusRunner<'a> {
             deps.state_manager.clone(),
             Arc::clone(&self.time) as Arc<_>,
             Duration::from_secs(0),
-            MaliciousFlags::default(),
+            malicious_flags,
             deps.metrics_registry.clone(),
             replica_logger.clone(),
             fake_local_store_certified_time_reader,
@@ -202,7 +218,7 @@ impl<'a> ConsensusRunner<'a> {
                 deps.metrics_registry.clone(),
             ),
             clock: RefCell::new(now),
-            index: self.instances.len(),
+            index,
         });
     }
 
@@ -227,6 +243,38 @@ impl<'a> ConsensusRunner<'a> {
         }
     }
 
+    /// Check that all honest instances agree on the finalized chain, at all
+    /// heights for which more than one of them still has a finalized block.
+    /// Return an error describing the first disagreement otherwise.
+    pub fn check_safety(&self) -> Result<(), String> {
+        let pools: Vec<_> = self
+            .instances
+            .iter()
+            .filter(|instance| !self.config.malicious.contains_key(&instance.index))
+            .map(|instance| instance.driver.consensus_pool.read().unwrap())
+            .collect();
+        let readers: Vec<_> = pools.iter().map(|pool| PoolReader::new(&**pool)).collect();
+        let max_height = readers
+            .iter()
+            .map(|reader| reader.get_finalized_height().get())
+            .max()
+            .unwrap_or(0);
+        for height in 1..=max_height {
+            let hashes: BTreeSet<_> = readers
+                .iter()
+                .filter_map(|reader| reader.get_finalized_block(Height::from(height)))
+                .map(|block| ic_types::crypto::crypto_hash(&block))
+                .collect();
+            if hashes.len() > 1 {
+                return Err(format!(
+                    "Instances finalized different blocks at height {}: {:?}",
+                    height, hashes
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// Run a single step of all instances to finish processing their messages.
     /// Return the updated NetworkStatus.
     fn process(&self) -> NetworkStatus {
@@ -273,6 +321,8 @@ impl Default for ConsensusRunnerConfig {
             degree: 9,
             execution: GlobalMessage::new(),
             delivery: Sequential::new(),
+            faults: NetworkFaults::default(),
+            malicious: Default::default(),
         }
     }
 }
@@ -1,4 +1,5 @@
 #![allow(dead_code)]
+use super::network::NetworkFaults;
 use ic_artifact_pool::{
     canister_http_pool, certification_pool::CertificationPoolImpl,
     consensus_pool::ConsensusPoolImpl, dkg_pool, ecdsa_pool,
@@ -32,6 +33,7 @@ use ic_types::{
         certification::CertificationMessage, dkg::Message as DkgMessage, CatchUpPackage,
         ConsensusMessage,
     },
+    malicious_flags::MaliciousFlags,
     replica_config::ReplicaConfig,
     time::Time,
     NodeId, SubnetId,
@@ -39,7 +41,7 @@ use ic_types::{
 use rand_chacha::ChaChaRng;
 use std::cell::{RefCell, RefMut};
 use std::cmp::Ordering;
-use std::collections::BinaryHeap;
+use std::collections::{BTreeMap, BinaryHeap};
 use std::fmt;
 use std::rc::Rc;
 use std::sync::{Arc, RwLock};
@@ -265,6 +267,8 @@ pub trait ConsensusInstances<'a> {
     fn logger(&self) -> &ReplicaLogger;
     fn rng(&self) -> RefMut<'_, ChaChaRng>;
     fn time_source(&self) -> &dyn TimeSource;
+    fn faults(&self) -> &NetworkFaults;
+    fn start_time(&self) -> Time;
 }
 
 /// Configuration parameters that will be read from command line argument or
@@ -277,6 +281,10 @@ pub struct ConsensusRunnerConfig {
     pub degree: usize,
     pub execution: Box<dyn ExecutionStrategy>,
     pub delivery: Box<dyn DeliveryStrategy>,
+    pub faults: NetworkFaults,
+    /// Malicious behaviours of instances, by instance index. They only take
+    /// effect when built with the `malicious_code` feature.
+    pub malicious: BTreeMap<usize, MaliciousFlags>,
 }
 
 impl fmt::Display for ConsensusRunnerConfig {
@@ -284,14 +292,17 @@ impl fmt::Display for ConsensusRunnerConfig {
         write!(
             f,
             "ConsensusRunnerConfig {{ max_delta: {}, random_seed: {}, \
-             num_nodes: {}, num_rounds: {}, degree: {}, execution: {}, delivery: {} }}",
+             num_nodes: {}, num_rounds: {}, degree: {}, execution: {}, delivery: {}, \
+             faults: {:?}, malicious: {:?} }}",
             self.max_delta,
             self.random_seed,
             self.num_nodes,
             self.num_rounds,
             self.degree,
             get_name(&self.execution),
-            get_name(&self.delivery)
+            get_name(&self.delivery),
+            self.faults,
+            self.malicious.keys().collect::<Vec<_>>()
         )
     }
 }
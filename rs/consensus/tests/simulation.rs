@@ -0,0 +1,255 @@
+//! Deterministic simulations of a subnet running full consensus instances
+//! under network faults and malicious behaviour.
+//!
+//! Most simulations use fake crypto, so that a run only depends on the
+//! configuration and its random seed. A failing simulation can therefore be
+//! reproduced by running it again with the same RANDOM_SEED. Scenarios whose
+//! safety relies on signatures being checked use real crypto instead.
+mod framework;
+use crate::framework::{
+    setup_subnet, setup_subnet_with_fake_crypto, ConsensusDependencies, ConsensusInstance,
+    ConsensusRunner, ConsensusRunnerConfig, NetworkFaults, Partition,
+};
+use ic_consensus::certification::CertificationCrypto;
+use ic_consensus_utils::{
+    crypto::ConsensusCrypto, membership::Membership, pool_reader::PoolReader,
+};
+use ic_interfaces_registry::RegistryClient;
+use ic_test_utilities::{
+    types::ids::{node_test_id, subnet_test_id},
+    FastForwardTimeSource,
+};
+use ic_types::{crypto::CryptoHash, replica_config::ReplicaConfig, Height};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The crypto components used by the instances of a simulated subnet.
+#[derive(Clone, Copy)]
+enum SimulatedCrypto {
+    /// Crypto that neither produces nor checks signatures. Runs are
+    /// reproducible from the random seed.
+    Fake,
+    /// Crypto components with freshly generated keys, that produce and check
+    /// all signatures. Runs are not reproducible from the random seed alone.
+    Real,
+}
+
+/// Run a simulated subnet with fake crypto, see [`simulate_with_crypto`].
+fn simulate(config: ConsensusRunnerConfig) -> Option<Vec<CryptoHash>> {
+    simulate_with_crypto(config, SimulatedCrypto::Fake)
+}
+
+/// Run a simulated subnet until all instances have executed
+/// `config.num_rounds` batches, and check that honest instances agree on the
+/// finalized chain.
+///
+/// Return the hashes of the blocks finalized by the first instance, or `None`
+/// if the subnet stalled.
+fn simulate_with_crypto(
+    config: ConsensusRunnerConfig,
+    crypto: SimulatedCrypto,
+) -> Option<Vec<CryptoHash>> {
+    let nodes = config.num_nodes;
+    ic_test_utilities::artifact_pool_config::with_test_pool_configs(nodes, |pool_configs| {
+        let rounds = config.num_rounds;
+        let reach_n_rounds = move |inst: &ConsensusInstance<'_>| {
+            inst.deps.message_routing.expected_batch_height() >= Height::from(rounds)
+        };
+        let subnet_id = subnet_test_id(0);
+        let node_ids: Vec<_> = (0..nodes as u64).map(node_test_id).collect();
+        let (registry_client, cup, cryptos): (
+            _,
+            _,
+            Vec<(Arc<dyn ConsensusCrypto>, Arc<dyn CertificationCrypto>)>,
+        ) = match crypto {
+            SimulatedCrypto::Fake => {
+                let (registry_client, cup, cryptos) =
+                    setup_subnet_with_fake_crypto(subnet_id, &node_ids);
+                let cryptos = cryptos
+                    .into_iter()
+                    .map(|crypto| (crypto.clone() as Arc<_>, crypto as Arc<_>))
+                    .collect();
+                (registry_client, cup, cryptos)
+            }
+            SimulatedCrypto::Real => {
+                let (registry_client, cup, cryptos) = setup_subnet(subnet_id, &node_ids);
+                let cryptos = cryptos
+                    .into_iter()
+                    .map(|crypto| (crypto.clone() as Arc<_>, crypto as Arc<_>))
+                    .collect();
+                (registry_client, cup, cryptos)
+            }
+        };
+        let inst_deps: Vec<_> = node_ids
+            .iter()
+            .zip(pool_configs.iter())
+            .map(|(node_id, pool_config)| {
+                ConsensusDependencies::new(
+                    ReplicaConfig {
+                        node_id: *node_id,
+                        subnet_id,
+                    },
+                    pool_config.clone(),
+                    Arc::clone(&registry_client) as Arc<dyn RegistryClient>,
+                    cup.clone(),
+                )
+            })
+            .collect();
+
+        let mut runner = ConsensusRunner::new_with_config(config, FastForwardTimeSource::new());
+
+        for ((pool_config, deps), (consensus_crypto, certification_crypto)) in pool_configs
+            .iter()
+            .zip(inst_deps.iter())
+            .zip(cryptos.iter())
+        {
+            let membership = Membership::new(
+                deps.consensus_pool.read().unwrap().get_cache(),
+                Arc::clone(&registry_client) as Arc<dyn RegistryClient>,
+                subnet_id,
+            );
+            runner.add_instance(
+                Arc::new(membership),
+                consensus_crypto.clone(),
+                certification_crypto.clone(),
+                deps,
+                pool_config.clone(),
+                &PoolReader::new(&*deps.consensus_pool.read().unwrap()),
+            );
+        }
+        if !runner.run_until(&reach_n_rounds) {
+            return None;
+        }
+        runner.check_safety().unwrap();
+
+        let pool = runner.instances[0].driver.consensus_pool.read().unwrap();
+        let reader = PoolReader::new(&*pool);
+        let hashes: Vec<_> = (1..=reader.get_finalized_height().get())
+            .filter_map(|height| reader.get_finalized_block(Height::from(height)))
+            .map(|block| ic_types::crypto::crypto_hash(&block).get())
+            .collect();
+        Some(hashes)
+    })
+}
+
+/// A configuration that exercises the randomized strategies, with the given
+/// network faults.
+fn config_with_faults(random_seed: u64, faults: NetworkFaults) -> ConsensusRunnerConfig {
+    let mut config = ConsensusRunnerConfig::new(4, random_seed);
+    config.num_rounds = 20;
+    config.faults = faults;
+    config
+}
+
+#[test]
+fn simulation_is_deterministic() {
+    let run = || {
+        let faults = NetworkFaults {
+            drop_rate: 0.1,
+            retransmission_delay: Some(Duration::from_secs(1)),
+            ..Default::default()
+        };
+        simulate(config_with_faults(7, faults)).expect("Subnet stalled")
+    };
+    assert_eq!(run(), run());
+}
+
+#[test]
+fn dropped_messages_are_retransmitted() {
+    let faults = NetworkFaults {
+        drop_rate: 0.3,
+        retransmission_delay: Some(Duration::from_secs(2)),
+        ..Default::default()
+    };
+    assert!(simulate(config_with_faults(0, faults)).is_some());
+}
+
+#[test]
+fn minority_partition_catches_up() {
+    let faults = NetworkFaults {
+        partitions: vec![Partition {
+            nodes: vec![3].into_iter().collect(),
+            from: Duration::from_secs(2),
+            until: Duration::from_secs(20),
+        }],
+        ..Default::default()
+    };
+    assert!(simulate(config_with_faults(1, faults)).is_some());
+}
+
+#[test]
+fn subnet_recovers_after_even_split() {
+    // Neither side of the partition can notarize on its own, so the subnet
+    // only makes progress again once the partition heals.
+    let faults = NetworkFaults {
+        partitions: vec![Partition {
+            nodes: vec![0, 1].into_iter().collect(),
+            from: Duration::from_secs(2),
+            until: Duration::from_secs(30),
+        }],
+        ..Default::default()
+    };
+    assert!(simulate(config_with_faults(2, faults)).is_some());
+}
+
+#[cfg(feature = "malicious_code")]
+#[test]
+fn equivocating_block_maker_does_not_break_safety() {
+    use ic_types::malicious_flags::MaliciousFlags;
+    let mut config = config_with_faults(3, NetworkFaults::default());
+    config.malicious.insert(
+        0,
+        MaliciousFlags {
+            maliciously_propose_equivocating_blocks: true,
+            ..Default::default()
+        },
+    );
+    assert!(simulate(config).is_some());
+}
+
+#[cfg(feature = "malicious_code")]
+#[test]
+fn malicious_notary_does_not_break_safety() {
+    use ic_types::malicious_flags::MaliciousFlags;
+    let mut config = config_with_faults(4, NetworkFaults::default());
+    config.malicious.insert(
+        1,
+        MaliciousFlags {
+            maliciously_notarize_all: true,
+            maliciously_finalize_all: true,
+            ..Default::default()
+        },
+    );
+    assert!(simulate(config).is_some());
+}
+
+#[cfg(feature = "malicious_code")]
+#[test]
+fn malicious_notary_with_real_crypto_does_not_break_safety() {
+    // With real crypto, honest instances check the signatures of all shares
+    // and aggregates the malicious instance sends them.
+    use ic_types::malicious_flags::MaliciousFlags;
+    let mut config = config_with_faults(5, NetworkFaults::default());
+    config.num_rounds = 10;
+    config.malicious.insert(
+        2,
+        MaliciousFlags {
+            maliciously_notarize_all: true,
+            maliciously_finalize_all: true,
+            ..Default::default()
+        },
+    );
+    assert!(simulate_with_crypto(config, SimulatedCrypto::Real).is_some());
+}
+
+#[test]
+fn subnet_with_real_crypto_tolerates_dropped_messages() {
+    let faults = NetworkFaults {
+        drop_rate: 0.1,
+        retransmission_delay: Some(Duration::from_secs(1)),
+        ..Default::default()
+    };
+    let mut config = config_with_faults(6, faults);
+    config.num_rounds = 10;
+    assert!(simulate_with_crypto(config, SimulatedCrypto::Real).is_some());
+}
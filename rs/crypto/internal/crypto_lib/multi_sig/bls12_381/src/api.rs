@@ -118,6 +118,52 @@ pub fn verify_individual(
     }
 }
 
+/// Verifies individual signatures of several signers over the same `message`
+/// in a single batch.
+///
+/// This is substantially cheaper than verifying each signature separately,
+/// but does not tell which signature is invalid if the batch fails to verify.
+/// The randomness used for the linear combination is drawn from `rng`.
+///
+/// # Errors
+/// * `CryptoError::MalformedSignature` if any of the signatures cannot be
+///   parsed as a G1 point.
+/// * `CryptoError::MalformedPublicKey` if any of the public keys cannot be
+///   parsed as a valid G2 point.
+/// * `CryptoError::SignatureVerification` if verification of the batch fails,
+///   i.e. if at least one signature is invalid.
+pub fn verify_individual_batch<R: Rng + CryptoRng>(
+    message: &[u8],
+    signatures_and_public_keys: &[(IndividualSignatureBytes, PublicKeyBytes)],
+    rng: &mut R,
+) -> Result<(), CryptoError> {
+    let signatures_and_public_keys: Result<Vec<(IndividualSignature, PublicKey)>, CryptoError> =
+        signatures_and_public_keys
+            .iter()
+            .map(|(signature_bytes, public_key_bytes)| {
+                let signature: IndividualSignature = (*signature_bytes).try_into()?;
+                let public_key = PublicKey::try_from(*public_key_bytes)?;
+                Ok((signature, public_key))
+            })
+            .collect();
+    if crypto::verify_individual_message_signatures_batch(
+        message,
+        &signatures_and_public_keys?[..],
+        rng,
+    ) {
+        Ok(())
+    } else {
+        Err(CryptoError::SignatureVerification {
+            algorithm: AlgorithmId::MultiBls12_381,
+            public_key_bytes: Vec::new(),
+            sig_bytes: Vec::new(),
+            internal_error:
+                "Batch verification of individual contributions to multisignature failed"
+                    .to_string(),
+        })
+    }
+}
+
 /// Verifies a combined multisignature over the given `message` using the given
 /// array of `public_keys`.
 ///
@@ -99,6 +99,32 @@ proptest! {
         assert!(multi_sig::verify_individual(&message, evil_signature, public_key).is_err())
     }
 
+    #[test]
+    fn batch_verification_succeeds_for_valid_signatures(
+      keys in proptest::collection::vec(arbitrary::key_pair_bytes(), 1..10),
+      message in proptest::collection::vec(any::<u8>(), 0..100),
+    ) {
+        let (signatures, _signature, public_keys) = test_happy_path(&keys, &message);
+        let batch: Vec<_> = signatures.into_iter().zip(public_keys.into_iter()).collect();
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        assert!(multi_sig::verify_individual_batch(&message, &batch, &mut rng).is_ok());
+    }
+
+    #[test]
+    fn batch_verification_fails_if_one_signature_is_incorrect(
+      keys in proptest::collection::vec(arbitrary::key_pair_bytes(), 2..10),
+      message in proptest::collection::vec(any::<u8>(), 0..100),
+      bad_index in any::<prop::sample::Index>(),
+    ) {
+        let (signatures, _signature, public_keys) = test_happy_path(&keys, &message);
+        let mut batch: Vec<_> = signatures.into_iter().zip(public_keys.into_iter()).collect();
+        let bad_index = bad_index.index(batch.len());
+        let (secret_key, _) = &keys[bad_index];
+        batch[bad_index].0 = multi_sig::sign(b"another message", secret_key.clone());
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        assert!(multi_sig::verify_individual_batch(&message, &batch, &mut rng).is_err());
+    }
+
     #[test]
     fn incorrect_pop_fails(
       keys in arbitrary::key_pair_bytes(),
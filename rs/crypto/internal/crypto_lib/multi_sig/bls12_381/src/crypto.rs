@@ -7,7 +7,8 @@ use crate::types::{
 };
 
 use ic_crypto_internal_bls12_381_type::{
-    verify_bls_signature, G1Projective, G2Affine, G2Projective, Scalar,
+    verify_bls_signature, verify_bls_signature_batch_same_msg, G1Affine, G1Projective, G2Affine,
+    G2Projective, Scalar,
 };
 
 use ic_crypto_sha::{Context, DomainSeparationContext};
@@ -97,6 +98,24 @@ pub fn verify_individual_message_signature(
     let hash = hash_message_to_g1(message);
     verify_point(&hash, signature, public_key)
 }
+/// Verifies several individual signatures on the same message at once, using
+/// a random linear combination of the signatures and public keys.
+///
+/// Returns true if and only if (with overwhelming probability) all signatures
+/// are valid.
+pub fn verify_individual_message_signatures_batch<R: Rng + CryptoRng>(
+    message: &[u8],
+    signatures_and_public_keys: &[(IndividualSignature, PublicKey)],
+    rng: &mut R,
+) -> bool {
+    let hash = hash_message_to_g1(message).to_affine();
+    let sigs_pks: Vec<(G1Affine, G2Affine)> = signatures_and_public_keys
+        .iter()
+        .map(|(signature, public_key)| (signature.to_affine(), public_key.to_affine()))
+        .collect();
+    let sigs_pks_refs: Vec<_> = sigs_pks.iter().map(|(sig, pk)| (sig, pk)).collect();
+    verify_bls_signature_batch_same_msg(&sigs_pks_refs[..], &hash, rng)
+}
 pub fn verify_pop(pop: &Pop, public_key: &PublicKey) -> bool {
     let public_key_bytes = PublicKeyBytes::from(public_key.clone());
     let mut domain_separated_public_key: Vec<u8> = vec![];
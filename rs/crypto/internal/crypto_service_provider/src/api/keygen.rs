@@ -72,6 +72,24 @@ pub trait CspKeyGenerator {
         node_id: NodeId,
         not_after: &str,
     ) -> Result<TlsPublicKeyCert, CspTlsKeygenError>;
+
+    /// Generates a new committee signing key pair that replaces the current one.
+    ///
+    /// See documentation in
+    /// [`crate::vault::api::MultiSignatureCspVault::rotate_committee_signing_key_pair`].
+    fn rotate_committee_signing_key_pair(
+        &self,
+    ) -> Result<(CspPublicKey, CspPop), CspMultiSignatureKeygenError>;
+
+    /// Generates new TLS key material that replaces the current one.
+    ///
+    /// See documentation in
+    /// [`crate::vault::api::TlsHandshakeCspVault::rotate_tls_key_pair`].
+    fn rotate_tls_key_pair(
+        &self,
+        node_id: NodeId,
+        not_after: &str,
+    ) -> Result<TlsPublicKeyCert, CspTlsKeygenError>;
 }
 
 /// A trait that allows simultaneously checking the public and secret key stores for the
@@ -32,6 +32,20 @@ impl CspKeyGenerator for Csp {
     ) -> Result<TlsPublicKeyCert, CspTlsKeygenError> {
         self.csp_vault.gen_tls_key_pair(node_id, not_after)
     }
+
+    fn rotate_committee_signing_key_pair(
+        &self,
+    ) -> Result<(CspPublicKey, CspPop), CspMultiSignatureKeygenError> {
+        self.csp_vault.rotate_committee_signing_key_pair()
+    }
+
+    fn rotate_tls_key_pair(
+        &self,
+        node_id: NodeId,
+        not_after: &str,
+    ) -> Result<TlsPublicKeyCert, CspTlsKeygenError> {
+        self.csp_vault.rotate_tls_key_pair(node_id, not_after)
+    }
 }
 
 /// Some key related utils
@@ -20,8 +20,10 @@ pub mod vault;
 
 pub use crate::vault::api::TlsHandshakeCspVault;
 pub use crate::vault::local_csp_vault::LocalCspVault;
-pub use crate::vault::remote_csp_vault::run_csp_vault_server;
 use crate::vault::remote_csp_vault::RemoteCspVault;
+pub use crate::vault::remote_csp_vault::{
+    run_csp_vault_server, AuditedOperation, VaultAuditConfig,
+};
 
 use crate::api::{
     CspIDkgProtocol, CspKeyGenerator, CspPublicAndSecretKeyStoreChecker, CspPublicKeyStore,
@@ -184,11 +186,21 @@ impl Csp {
             logger,
             "Proceeding with an in-replica csp_vault, CryptoConfig: {:?}", config
         );
-        let csp_vault = Arc::new(LocalCspVault::new_in_dir(
-            &config.crypto_root,
-            metrics.clone(),
-            new_logger!(&logger),
-        ));
+        let csp_vault: Arc<dyn CspVault> = match &config.secret_key_encryption_key_file {
+            None => Arc::new(LocalCspVault::new_in_dir(
+                &config.crypto_root,
+                metrics.clone(),
+                new_logger!(&logger),
+            )),
+            Some(key_encryption_key_file) => {
+                Arc::new(LocalCspVault::new_in_dir_with_encrypted_secret_key_stores(
+                    &config.crypto_root,
+                    key_encryption_key_file,
+                    metrics.clone(),
+                    new_logger!(&logger),
+                ))
+            }
+        };
         Csp {
             csp_vault,
             logger,
@@ -1,6 +1,7 @@
 use crate::public_key_store::PublicKeyAddError;
 use crate::public_key_store::PublicKeyGenerationTimestamps;
 use crate::public_key_store::PublicKeyRetainError;
+use crate::public_key_store::PublicKeyRotationError;
 use crate::public_key_store::PublicKeySetOnceError;
 use crate::public_key_store::PublicKeyStore;
 use ic_protobuf::registry::crypto::v1::PublicKey;
@@ -27,6 +28,11 @@ mock! {
 
         fn committee_signing_pubkey(&self) -> Option<PublicKey>;
 
+        fn rotate_committee_signing_pubkey(
+            &mut self,
+            key: PublicKey,
+        ) -> Result<Option<PublicKey>, PublicKeyRotationError>;
+
         fn set_once_ni_dkg_dealing_encryption_pubkey(
             &mut self,
             key: PublicKey,
@@ -41,6 +47,11 @@ mock! {
 
         fn tls_certificate(&self) -> Option<X509PublicKeyCert>;
 
+        fn rotate_tls_certificate(
+            &mut self,
+            cert: X509PublicKeyCert,
+        ) -> Result<Option<X509PublicKeyCert>, PublicKeyRotationError>;
+
         fn add_idkg_dealing_encryption_pubkey(&mut self, key: PublicKey) -> Result<(), PublicKeyAddError>;
 
         fn retain_most_recent_idkg_public_keys_up_to_inclusive(&mut self, oldest_public_key_to_keep: &PublicKey) -> Result<bool, PublicKeyRetainError>;
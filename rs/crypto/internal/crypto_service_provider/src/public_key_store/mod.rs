@@ -21,6 +21,12 @@ pub enum PublicKeyAddError {
     Io(std::io::Error),
 }
 
+#[derive(Debug)]
+pub enum PublicKeyRotationError {
+    NotSet,
+    Io(std::io::Error),
+}
+
 #[derive(Debug)]
 pub enum PublicKeyRetainError {
     Io(std::io::Error),
@@ -58,6 +64,18 @@ pub trait PublicKeyStore: Send + Sync {
     /// Note: any timestamp in [`PublicKeyProto`] will be stripped off.
     fn committee_signing_pubkey(&self) -> Option<PublicKeyProto>;
 
+    /// Replaces the committee signing public key with `key`. The replaced key
+    /// is kept as the previous committee signing public key.
+    ///
+    /// Returns the key that was previous before this call, and is thus no
+    /// longer kept, if any.
+    ///
+    /// Returns an error if no key is set yet, or if writing to disk fails.
+    fn rotate_committee_signing_pubkey(
+        &mut self,
+        key: PublicKeyProto,
+    ) -> Result<Option<PublicKeyProto>, PublicKeyRotationError>;
+
     /// Sets the NI-DKG dealing encryption public key.
     ///
     /// Returns an error if a key is already set, or if writing to disk fails.
@@ -82,6 +100,18 @@ pub trait PublicKeyStore: Send + Sync {
     /// Gets the TLS certificate.
     fn tls_certificate(&self) -> Option<X509PublicKeyCert>;
 
+    /// Replaces the TLS certificate with `cert`. The replaced certificate is
+    /// kept as the previous TLS certificate.
+    ///
+    /// Returns the certificate that was previous before this call, and is thus
+    /// no longer kept, if any.
+    ///
+    /// Returns an error if no certificate is set yet, or if writing to disk fails.
+    fn rotate_tls_certificate(
+        &mut self,
+        cert: X509PublicKeyCert,
+    ) -> Result<Option<X509PublicKeyCert>, PublicKeyRotationError>;
+
     /// Adds a new iDKG dealing encryption public key.
     fn add_idkg_dealing_encryption_pubkey(
         &mut self,
@@ -1,6 +1,7 @@
 use crate::public_key_store::PublicKeyGenerationTimestamps;
 use crate::public_key_store::{
-    PublicKeyAddError, PublicKeyRetainError, PublicKeySetOnceError, PublicKeyStore,
+    PublicKeyAddError, PublicKeyRetainError, PublicKeyRotationError, PublicKeySetOnceError,
+    PublicKeyStore,
 };
 use ic_logger::{debug, ReplicaLogger};
 use ic_protobuf::crypto::v1::NodePublicKeys;
@@ -107,6 +108,26 @@ impl PublicKeyStore for ProtoPublicKeyStore {
             .map(|pk| remove_timestamp(pk.clone()))
     }
 
+    fn rotate_committee_signing_pubkey(
+        &mut self,
+        key: PublicKeyProto,
+    ) -> Result<Option<PublicKeyProto>, PublicKeyRotationError> {
+        let current_key = self
+            .keys
+            .committee_signing_pk
+            .take()
+            .ok_or(PublicKeyRotationError::NotSet)?;
+        self.keys.committee_signing_pk = Some(key);
+        debug!(
+            self.logger,
+            "Rotated committee signing public key '{:?}'", &current_key
+        );
+        let no_longer_kept_key = self.keys.previous_committee_signing_pk.replace(current_key);
+        self.write_node_public_keys_proto_to_disk()
+            .map_err(PublicKeyRotationError::Io)?;
+        Ok(no_longer_kept_key.map(remove_timestamp))
+    }
+
     fn set_once_ni_dkg_dealing_encryption_pubkey(
         &mut self,
         key: PublicKeyProto,
@@ -142,6 +163,23 @@ impl PublicKeyStore for ProtoPublicKeyStore {
         self.keys.tls_certificate.clone()
     }
 
+    fn rotate_tls_certificate(
+        &mut self,
+        cert: X509PublicKeyCert,
+    ) -> Result<Option<X509PublicKeyCert>, PublicKeyRotationError> {
+        let current_cert = self
+            .keys
+            .tls_certificate
+            .take()
+            .ok_or(PublicKeyRotationError::NotSet)?;
+        self.keys.tls_certificate = Some(cert);
+        debug!(self.logger, "Rotated TLS certificate '{:?}'", &current_cert);
+        let no_longer_kept_cert = self.keys.previous_tls_certificate.replace(current_cert);
+        self.write_node_public_keys_proto_to_disk()
+            .map_err(PublicKeyRotationError::Io)?;
+        Ok(no_longer_kept_cert)
+    }
+
     fn add_idkg_dealing_encryption_pubkey(
         &mut self,
         key: PublicKeyProto,
@@ -2,6 +2,7 @@
 
 use crate::public_key_store::proto_pubkey_store::ProtoPublicKeyStore;
 use crate::public_key_store::PublicKeyAddError;
+use crate::public_key_store::PublicKeyRotationError;
 use crate::public_key_store::{PublicKeySetOnceError, PublicKeyStore};
 use assert_matches::assert_matches;
 use ic_config::crypto::CryptoConfig;
@@ -815,6 +816,87 @@ mod retain_most_recent_idkg_public_keys_up_to_inclusive {
     }
 }
 
+mod rotate_committee_signing_pubkey_and_tls_certificate {
+    use super::*;
+
+    #[test]
+    fn should_fail_to_rotate_keys_that_are_not_set() {
+        let temp_dir = temp_dir();
+        let mut store = public_key_store(&temp_dir);
+
+        assert_matches!(
+            store.rotate_committee_signing_pubkey(public_key_with_key_value(1)),
+            Err(PublicKeyRotationError::NotSet)
+        );
+        assert_matches!(
+            store.rotate_tls_certificate(public_key_certificate_with_der_value(1)),
+            Err(PublicKeyRotationError::NotSet)
+        );
+        assert!(store.committee_signing_pubkey().is_none());
+        assert!(store.tls_certificate().is_none());
+    }
+
+    #[test]
+    fn should_keep_previous_keys_until_next_rotation_and_persist_them() {
+        let temp_dir = temp_dir();
+        let mut store = public_key_store(&temp_dir);
+        store
+            .set_once_committee_signing_pubkey(public_key_with_key_value(1))
+            .unwrap();
+        store
+            .set_once_tls_certificate(public_key_certificate_with_der_value(1))
+            .unwrap();
+
+        assert_matches!(
+            store.rotate_committee_signing_pubkey(public_key_with_key_value(2)),
+            Ok(None)
+        );
+        assert_matches!(
+            store.rotate_tls_certificate(public_key_certificate_with_der_value(2)),
+            Ok(None)
+        );
+        let keys_from_disk = read_from_public_key_store_file(temp_dir.path());
+        assert_eq!(
+            keys_from_disk.committee_signing_pk,
+            Some(public_key_with_key_value(2))
+        );
+        assert_eq!(
+            keys_from_disk.previous_committee_signing_pk,
+            Some(public_key_with_key_value(1))
+        );
+        assert_eq!(
+            keys_from_disk.tls_certificate,
+            Some(public_key_certificate_with_der_value(2))
+        );
+        assert_eq!(
+            keys_from_disk.previous_tls_certificate,
+            Some(public_key_certificate_with_der_value(1))
+        );
+
+        let mut store = public_key_store(&temp_dir);
+        assert_eq!(
+            store
+                .rotate_committee_signing_pubkey(public_key_with_key_value(3))
+                .unwrap(),
+            Some(public_key_with_key_value(1))
+        );
+        assert_eq!(
+            store
+                .rotate_tls_certificate(public_key_certificate_with_der_value(3))
+                .unwrap(),
+            Some(public_key_certificate_with_der_value(1))
+        );
+        assert_eq!(
+            store.committee_signing_pubkey(),
+            Some(public_key_with_key_value(3))
+        );
+        assert_eq!(
+            store.tls_certificate(),
+            Some(public_key_certificate_with_der_value(3))
+        );
+    }
+}
+
 fn add_idkg_dealing_encryption_public_keys(
     store: &mut ProtoPublicKeyStore,
     public_keys: Vec<PublicKey>,
@@ -1,7 +1,8 @@
 use crate::public_key_store::proto_pubkey_store::ProtoPublicKeyStore;
 use crate::public_key_store::PublicKeyGenerationTimestamps;
 use crate::public_key_store::{
-    PublicKeyAddError, PublicKeyRetainError, PublicKeySetOnceError, PublicKeyStore,
+    PublicKeyAddError, PublicKeyRetainError, PublicKeyRotationError, PublicKeySetOnceError,
+    PublicKeyStore,
 };
 use ic_logger::replica_logger::no_op_logger;
 use ic_protobuf::registry::crypto::v1::{PublicKey, X509PublicKeyCert};
@@ -65,6 +66,13 @@ impl PublicKeyStore for TempPublicKeyStore {
         self.store.committee_signing_pubkey()
     }
 
+    fn rotate_committee_signing_pubkey(
+        &mut self,
+        key: PublicKey,
+    ) -> Result<Option<PublicKey>, PublicKeyRotationError> {
+        self.store.rotate_committee_signing_pubkey(key)
+    }
+
     fn set_once_ni_dkg_dealing_encryption_pubkey(
         &mut self,
         key: PublicKey,
@@ -87,6 +95,13 @@ impl PublicKeyStore for TempPublicKeyStore {
         self.store.tls_certificate()
     }
 
+    fn rotate_tls_certificate(
+        &mut self,
+        cert: X509PublicKeyCert,
+    ) -> Result<Option<X509PublicKeyCert>, PublicKeyRotationError> {
+        self.store.rotate_tls_certificate(cert)
+    }
+
     fn add_idkg_dealing_encryption_pubkey(
         &mut self,
         key: PublicKey,
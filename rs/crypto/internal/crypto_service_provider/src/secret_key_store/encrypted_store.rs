@@ -0,0 +1,499 @@
+//! Filesystem-backed secret key store that encrypts the key material at rest
+//!
+//! The keys are serialized exactly as in the [`ProtoSecretKeyStore`], and the
+//! resulting protobuf is encrypted with AES-256-GCM under a key-encryption key
+//! (KEK) that is obtained from a [`KeyEncryptionKeyProvider`]. The file on disk
+//! therefore no longer relies on filesystem permissions alone to protect the
+//! secret keys, and since it only contains ciphertext, previous versions of
+//! the file do not need to be zeroized when the store is updated.
+//!
+//! # File format
+//!
+//! ```text
+//! magic (4 bytes) || format version (u32, big-endian) || nonce (12 bytes) || tag (16 bytes) || ciphertext
+//! ```
+//!
+//! The magic and the format version are authenticated as associated data.
+#![allow(clippy::unwrap_used)]
+use crate::key_id::KeyId;
+use crate::secret_key_store::proto_store::{
+    overwrite_file_with_zeroes_and_delete_if_it_exists, pb, with_read_lock, with_write_lock,
+    ProtoSecretKeyStore, SecretKeys,
+};
+use crate::secret_key_store::{
+    Scope, SecretKeyStore, SecretKeyStoreInsertionError, SecretKeyStoreWriteError,
+};
+use crate::types::CspSecretKey;
+use ic_config::crypto::CryptoConfig;
+use ic_crypto_secrets_containers::SecretArray;
+use ic_logger::{debug, info, replica_logger::no_op_logger, warn, ReplicaLogger};
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use parking_lot::RwLock;
+use prost::Message;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use std::fmt;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use zeroize::Zeroizing;
+
+#[cfg(test)]
+mod tests;
+
+/// Magic bytes identifying an encrypted secret key store file.
+const ENCRYPTED_SKS_MAGIC: &[u8; 4] = b"ESKS";
+/// Version of the encrypted file format (not of the protobuf it contains).
+const CURRENT_ENCRYPTED_SKS_FORMAT_VERSION: u32 = 1;
+const HEADER_LEN: usize = ENCRYPTED_SKS_MAGIC.len() + 4;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// Length in bytes of a key-encryption key.
+pub const KEY_ENCRYPTION_KEY_LEN: usize = 32;
+
+/// A source of the key-encryption key used by the [`EncryptedSecretKeyStore`].
+///
+/// Implementations may, e.g., read a sealed file, or unseal the key with the
+/// help of an HSM or a trusted execution environment. The key is requested
+/// every time the store is read from or written to disk, so that it does not
+/// have to be kept in memory by the store.
+pub trait KeyEncryptionKeyProvider: Send + Sync {
+    /// Returns the key-encryption key.
+    fn key_encryption_key(
+        &self,
+    ) -> Result<SecretArray<KEY_ENCRYPTION_KEY_LEN>, KeyEncryptionKeyProviderError>;
+}
+
+/// Errors that can occur while obtaining the key-encryption key
+#[derive(Clone, Debug)]
+pub enum KeyEncryptionKeyProviderError {
+    /// The key could not be obtained, e.g. because the backing file or device
+    /// is unavailable.
+    Unavailable(String),
+    /// The key was obtained but is not usable, e.g. it has the wrong length or
+    /// is not sufficiently protected.
+    Malformed(String),
+}
+
+impl std::error::Error for KeyEncryptionKeyProviderError {}
+
+impl fmt::Display for KeyEncryptionKeyProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyEncryptionKeyProviderError::Unavailable(e) => {
+                write!(f, "Key-encryption key is unavailable: {}", e)
+            }
+            KeyEncryptionKeyProviderError::Malformed(e) => {
+                write!(f, "Key-encryption key is malformed: {}", e)
+            }
+        }
+    }
+}
+
+/// A [`KeyEncryptionKeyProvider`] that reads the key-encryption key from a
+/// local file, e.g. one that is unsealed into a `tmpfs` at boot time.
+///
+/// The file must contain exactly [`KEY_ENCRYPTION_KEY_LEN`] bytes and must
+/// only be accessible by its owner.
+pub struct SealedFileKeyEncryptionKeyProvider {
+    path: PathBuf,
+}
+
+impl SealedFileKeyEncryptionKeyProvider {
+    /// Creates a provider that reads the key-encryption key from `path`. The
+    /// file is only read when the key is needed.
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+
+    /// Creates a provider for the file at `path`, first generating a fresh
+    /// random key-encryption key and atomically writing it to `path` if no
+    /// such file exists yet.
+    pub fn generate_if_missing(path: &Path) -> std::io::Result<Self> {
+        if !path.try_exists()? {
+            let kek = Zeroizing::new({
+                let mut kek = [0u8; KEY_ENCRYPTION_KEY_LEN];
+                OsRng.fill_bytes(&mut kek);
+                kek
+            });
+            ic_utils::fs::write_using_tmp_file(path, |writer| {
+                writer
+                    .get_ref()
+                    .set_permissions(fs::Permissions::from_mode(0o600))?;
+                writer.write_all(&*kek)
+            })?;
+        }
+        Ok(Self::new(path))
+    }
+
+    /// Returns the path to the file holding the key-encryption key.
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+}
+
+impl KeyEncryptionKeyProvider for SealedFileKeyEncryptionKeyProvider {
+    fn key_encryption_key(
+        &self,
+    ) -> Result<SecretArray<KEY_ENCRYPTION_KEY_LEN>, KeyEncryptionKeyProviderError> {
+        let path = self.path.to_string_lossy();
+        let metadata = fs::metadata(&self.path).map_err(|e| {
+            KeyEncryptionKeyProviderError::Unavailable(format!(
+                "error reading metadata of {}: {}",
+                path, e
+            ))
+        })?;
+        if metadata.permissions().mode() & 0o077 != 0 {
+            return Err(KeyEncryptionKeyProviderError::Malformed(format!(
+                "{} is accessible by users other than its owner",
+                path
+            )));
+        }
+        let bytes = Zeroizing::new(fs::read(&self.path).map_err(|e| {
+            KeyEncryptionKeyProviderError::Unavailable(format!("error reading {}: {}", path, e))
+        })?);
+        let mut kek: [u8; KEY_ENCRYPTION_KEY_LEN] = bytes.as_slice().try_into().map_err(|_| {
+            KeyEncryptionKeyProviderError::Malformed(format!(
+                "{} contains {} bytes instead of {}",
+                path,
+                bytes.len(),
+                KEY_ENCRYPTION_KEY_LEN
+            ))
+        })?;
+        Ok(SecretArray::new_and_zeroize_argument(&mut kek))
+    }
+}
+
+/// A secret key store that persists data to the filesystem, using protobufs for
+/// serialization and encrypting the serialized keys with a key-encryption key
+pub struct EncryptedSecretKeyStore<K: KeyEncryptionKeyProvider> {
+    encrypted_file: PathBuf,
+    kek_provider: K,
+    keys: Arc<RwLock<SecretKeys>>,
+    logger: ReplicaLogger,
+}
+
+impl<K: KeyEncryptionKeyProvider> EncryptedSecretKeyStore<K> {
+    /// Creates an `EncryptedSecretKeyStore` instance backed by the file `file_name` in `dir`,
+    /// whose contents are encrypted with the key-encryption key returned by `kek_provider`.
+    ///
+    /// # Panics
+    ///  - If the crypto root directory does not have the required permissions
+    ///  - If the secret key store file is not a POSIX regular file
+    ///  - If the key-encryption key cannot be obtained while an existing store is read
+    ///  - If the secret key store file cannot be decrypted or parsed
+    pub fn open(
+        dir: &Path,
+        file_name: &str,
+        kek_provider: K,
+        logger: Option<ReplicaLogger>,
+    ) -> Self {
+        CryptoConfig::check_dir_has_required_permissions(dir)
+            .expect("wrong crypto root permissions");
+        let encrypted_file = dir.join(file_name);
+        ensure_is_regular_file_if_exists(&encrypted_file);
+        let secret_keys = Self::read_sks_data_from_disk(&encrypted_file, &kek_provider)
+            .unwrap_or_else(SecretKeys::new);
+        EncryptedSecretKeyStore {
+            encrypted_file,
+            kek_provider,
+            keys: Arc::new(RwLock::new(secret_keys)),
+            logger: logger.unwrap_or_else(no_op_logger),
+        }
+    }
+
+    /// Like [`Self::open`], but first migrates the keys from the unencrypted
+    /// [`ProtoSecretKeyStore`] file `plaintext_file_name` in `dir`, if that file
+    /// exists.
+    ///
+    /// The keys are written to the encrypted store before the unencrypted file is
+    /// zeroized and deleted, so an interrupted migration is simply repeated the next
+    /// time the store is opened. If both files exist, the unencrypted file takes
+    /// precedence, since it is only ever written by a `ProtoSecretKeyStore`.
+    ///
+    /// # Panics
+    /// In the same cases as [`Self::open`] and [`ProtoSecretKeyStore::open`], and if the
+    /// migrated keys cannot be written to the encrypted store.
+    pub fn open_and_migrate(
+        dir: &Path,
+        file_name: &str,
+        plaintext_file_name: &str,
+        kek_provider: K,
+        logger: Option<ReplicaLogger>,
+    ) -> Self {
+        assert_ne!(
+            file_name, plaintext_file_name,
+            "encrypted and unencrypted secret key stores must use distinct files"
+        );
+        let sks = Self::open(dir, file_name, kek_provider, logger);
+        let plaintext_file = dir.join(plaintext_file_name);
+        let plaintext_exists = plaintext_file
+            .try_exists()
+            .expect("error determining if unencrypted secret key store file exists");
+        if !plaintext_exists {
+            return sks;
+        }
+        if sks.encrypted_file.try_exists().unwrap_or(false) {
+            warn!(
+                sks.logger,
+                "both encrypted secret key store {} and unencrypted secret key store {} exist, migrating the latter again",
+                sks.encrypted_file.to_string_lossy(),
+                plaintext_file.to_string_lossy()
+            );
+        }
+        let plaintext_keys = {
+            let plaintext_sks =
+                ProtoSecretKeyStore::open(dir, plaintext_file_name, Some(sks.logger.clone()));
+            plaintext_sks.secret_keys()
+        };
+        let migrated_keys_count = plaintext_keys.len();
+        with_write_lock(&sks.keys, |keys| {
+            *keys = plaintext_keys;
+            sks.write_secret_keys_to_disk(keys)
+        })
+        .unwrap_or_else(|e| panic!("error migrating secret key store: {}", e));
+        overwrite_file_with_zeroes_and_delete_if_it_exists(&plaintext_file, &sks.logger);
+        info!(
+            sks.logger,
+            "Migrated {} secret keys from {} to encrypted secret key store {}",
+            migrated_keys_count,
+            plaintext_file.to_string_lossy(),
+            sks.encrypted_file.to_string_lossy()
+        );
+        sks
+    }
+
+    /// Returns the path to the encrypted file storing the keys.
+    pub fn encrypted_file_path(&self) -> &Path {
+        self.encrypted_file.as_path()
+    }
+
+    fn write_secret_keys_to_disk(
+        &self,
+        secret_keys: &SecretKeys,
+    ) -> Result<(), SecretKeyStoreWriteError> {
+        let sks_proto = ProtoSecretKeyStore::secret_keys_to_sks_proto(secret_keys)?;
+        let plaintext = Zeroizing::new(sks_proto.encode_to_vec());
+        let kek = self.kek_provider.key_encryption_key().map_err(|e| {
+            SecretKeyStoreWriteError::TransientError(format!(
+                "Secret key store internal error obtaining key-encryption key: {}",
+                e
+            ))
+        })?;
+        let encrypted = encrypt_sks_data(&kek, &plaintext)
+            .map_err(SecretKeyStoreWriteError::SerializationError)?;
+        ic_utils::fs::write_using_tmp_file(&self.encrypted_file, |writer| {
+            writer.write_all(&encrypted)
+        })
+        .map_err(|e| {
+            SecretKeyStoreWriteError::TransientError(format!(
+                "Secret key store internal error writing encrypted data using tmp file: {}",
+                e
+            ))
+        })?;
+        debug!(
+            self.logger,
+            "Encrypted secret key store written to {:?}", self.encrypted_file
+        );
+        Ok(())
+    }
+
+    fn read_sks_data_from_disk(encrypted_file: &Path, kek_provider: &K) -> Option<SecretKeys> {
+        match fs::read(encrypted_file) {
+            Ok(data) => {
+                let kek = kek_provider
+                    .key_encryption_key()
+                    .unwrap_or_else(|e| panic!("error obtaining key-encryption key: {}", e));
+                let plaintext = decrypt_sks_data(&kek, &data)
+                    .unwrap_or_else(|e| panic!("error decrypting SKS data: {}", e));
+                let sks_pb = pb::SecretKeyStore::decode(plaintext.as_slice()).unwrap_or_else(
+                    |_ignored_so_that_no_data_is_leaked| panic!("error parsing SKS protobuf data"),
+                );
+                Some(ProtoSecretKeyStore::migrate_to_current_version(sks_pb))
+            }
+            Err(err) => {
+                if err.kind() == ErrorKind::NotFound {
+                    None
+                } else {
+                    panic!("Error reading SKS data: {}", err)
+                }
+            }
+        }
+    }
+}
+
+impl<K: KeyEncryptionKeyProvider> SecretKeyStore for EncryptedSecretKeyStore<K> {
+    fn insert(
+        &mut self,
+        id: KeyId,
+        key: CspSecretKey,
+        scope: Option<Scope>,
+    ) -> Result<(), SecretKeyStoreInsertionError> {
+        let inserted = with_write_lock(&self.keys, |keys| match keys.get(&id) {
+            Some(_) => Ok(false),
+            None => {
+                keys.insert(id, (key, scope));
+                self.write_secret_keys_to_disk(keys)?;
+                debug!(self.logger, "Inserted new secret key {}", id);
+                Ok(true)
+            }
+        })?;
+        if inserted {
+            Ok(())
+        } else {
+            Err(SecretKeyStoreInsertionError::DuplicateKeyId(id))
+        }
+    }
+
+    fn insert_or_replace(
+        &mut self,
+        id: KeyId,
+        key: CspSecretKey,
+        scope: Option<Scope>,
+    ) -> Result<(), SecretKeyStoreWriteError> {
+        with_write_lock(&self.keys, |keys| {
+            let previous_key = keys.insert(id, (key, scope));
+            self.write_secret_keys_to_disk(keys)?;
+            match previous_key {
+                None => debug!(self.logger, "Inserted new secret key {}", id),
+                Some(_) => debug!(self.logger, "Replaced existing secret key {}", id),
+            };
+            Ok(())
+        })
+    }
+
+    fn get(&self, id: &KeyId) -> Option<CspSecretKey> {
+        with_read_lock(&self.keys, |keys| {
+            keys.get(id).map(|(csp_key, _)| csp_key.to_owned())
+        })
+    }
+
+    fn contains(&self, id: &KeyId) -> bool {
+        self.get(id).is_some()
+    }
+
+    fn remove(&mut self, id: &KeyId) -> Result<bool, SecretKeyStoreWriteError> {
+        with_write_lock(&self.keys, |keys| match keys.get(id) {
+            Some(_) => {
+                keys.remove(id);
+                self.write_secret_keys_to_disk(keys)?;
+                debug!(self.logger, "Removed secret key {}", id);
+                Ok(true)
+            }
+            None => Ok(false),
+        })
+    }
+
+    fn retain<F>(&mut self, filter: F, scope: Scope) -> Result<(), SecretKeyStoreWriteError>
+    where
+        F: Fn(&KeyId, &CspSecretKey) -> bool,
+    {
+        with_write_lock(&self.keys, |keys| {
+            let orig_keys_count = keys.len();
+            keys.retain(|key_id, (csp_key, maybe_scope)| {
+                let keep = *maybe_scope != Some(scope) || filter(key_id, csp_key);
+                if !keep {
+                    info!(
+                        self.logger,
+                        "Deleting key with ID {} with scope {}", key_id, scope
+                    );
+                }
+                keep
+            });
+            if keys.len() < orig_keys_count {
+                self.write_secret_keys_to_disk(keys)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+fn ensure_is_regular_file_if_exists(file: &Path) {
+    if let Ok(true) = file.try_exists() {
+        if !ic_utils::fs::is_regular_file(&file)
+            .expect("error checking if secret key store is a regular file")
+        {
+            panic!(
+                "secret key store {} is not a regular file",
+                file.to_string_lossy()
+            );
+        }
+    }
+}
+
+fn header() -> [u8; HEADER_LEN] {
+    let mut header = [0u8; HEADER_LEN];
+    header[..ENCRYPTED_SKS_MAGIC.len()].copy_from_slice(ENCRYPTED_SKS_MAGIC);
+    header[ENCRYPTED_SKS_MAGIC.len()..]
+        .copy_from_slice(&CURRENT_ENCRYPTED_SKS_FORMAT_VERSION.to_be_bytes());
+    header
+}
+
+/// Encrypts the serialized secret key store `plaintext` under `kek`, and returns
+/// the contents of the encrypted secret key store file.
+fn encrypt_sks_data(
+    kek: &SecretArray<KEY_ENCRYPTION_KEY_LEN>,
+    plaintext: &[u8],
+) -> Result<Vec<u8>, String> {
+    let header = header();
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let mut tag = [0u8; TAG_LEN];
+    let ciphertext = encrypt_aead(
+        Cipher::aes_256_gcm(),
+        kek.expose_secret(),
+        Some(&nonce),
+        &header,
+        plaintext,
+        &mut tag,
+    )
+    .map_err(|e| format!("error encrypting secret key store: {}", e))?;
+    let mut data = Vec::with_capacity(HEADER_LEN + NONCE_LEN + TAG_LEN + ciphertext.len());
+    data.extend_from_slice(&header);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&tag);
+    data.extend_from_slice(&ciphertext);
+    Ok(data)
+}
+
+/// Decrypts the contents of an encrypted secret key store file with `kek`, and
+/// returns the serialized secret key store.
+fn decrypt_sks_data(
+    kek: &SecretArray<KEY_ENCRYPTION_KEY_LEN>,
+    data: &[u8],
+) -> Result<Zeroizing<Vec<u8>>, String> {
+    if data.len() < HEADER_LEN + NONCE_LEN + TAG_LEN {
+        return Err(format!("file too short ({} bytes)", data.len()));
+    }
+    let (header, rest) = data.split_at(HEADER_LEN);
+    let (magic, version) = header.split_at(ENCRYPTED_SKS_MAGIC.len());
+    if magic != ENCRYPTED_SKS_MAGIC {
+        return Err("not an encrypted secret key store".to_string());
+    }
+    let version = u32::from_be_bytes(version.try_into().unwrap());
+    if version != CURRENT_ENCRYPTED_SKS_FORMAT_VERSION {
+        return Err(format!(
+            "unsupported encrypted secret key store format version: {}",
+            version
+        ));
+    }
+    let (nonce, rest) = rest.split_at(NONCE_LEN);
+    let (tag, ciphertext) = rest.split_at(TAG_LEN);
+    decrypt_aead(
+        Cipher::aes_256_gcm(),
+        kek.expose_secret(),
+        Some(nonce),
+        header,
+        ciphertext,
+        tag,
+    )
+    .map(Zeroizing::new)
+    .map_err(|_ignored_so_that_no_data_is_leaked| {
+        "authentication failed, the key-encryption key may be wrong".to_string()
+    })
+}
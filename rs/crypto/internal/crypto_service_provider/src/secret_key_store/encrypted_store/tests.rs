@@ -0,0 +1,206 @@
+use super::*;
+use crate::secret_key_store::test_utils::{make_key_id, make_secret_key};
+use assert_matches::assert_matches;
+use ic_crypto_internal_csp_test_utils::files::mk_temp_dir_with_permissions;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+const SKS_FILE: &str = "encrypted_sks_data";
+const PLAINTEXT_SKS_FILE: &str = "sks_data.pb";
+
+struct FixedKeyEncryptionKey([u8; KEY_ENCRYPTION_KEY_LEN]);
+
+impl KeyEncryptionKeyProvider for FixedKeyEncryptionKey {
+    fn key_encryption_key(
+        &self,
+    ) -> Result<SecretArray<KEY_ENCRYPTION_KEY_LEN>, KeyEncryptionKeyProviderError> {
+        Ok(SecretArray::new_and_dont_zeroize_argument(&self.0))
+    }
+}
+
+struct UnavailableKeyEncryptionKey;
+
+impl KeyEncryptionKeyProvider for UnavailableKeyEncryptionKey {
+    fn key_encryption_key(
+        &self,
+    ) -> Result<SecretArray<KEY_ENCRYPTION_KEY_LEN>, KeyEncryptionKeyProviderError> {
+        Err(KeyEncryptionKeyProviderError::Unavailable(
+            "no sealing key".to_string(),
+        ))
+    }
+}
+
+fn open_store(dir: &Path, kek: u8) -> EncryptedSecretKeyStore<FixedKeyEncryptionKey> {
+    EncryptedSecretKeyStore::open(
+        dir,
+        SKS_FILE,
+        FixedKeyEncryptionKey([kek; KEY_ENCRYPTION_KEY_LEN]),
+        None,
+    )
+}
+
+fn ed25519_secret_key_bytes(key: &CspSecretKey) -> Vec<u8> {
+    match key {
+        CspSecretKey::Ed25519(sk) => sk.0.expose_secret().to_vec(),
+        _ => panic!("expected an Ed25519 secret key"),
+    }
+}
+
+#[test]
+fn should_persist_keys_across_reopening() {
+    let dir = mk_temp_dir_with_permissions(0o700);
+    let (key_id, key) = (make_key_id(1), make_secret_key(1));
+    {
+        let mut sks = open_store(dir.path(), 42);
+        assert!(sks.insert(key_id, key.clone(), None).is_ok());
+        assert_matches!(
+            sks.insert(key_id, key.clone(), None),
+            Err(SecretKeyStoreInsertionError::DuplicateKeyId(_))
+        );
+    }
+    let mut sks = open_store(dir.path(), 42);
+    assert!(sks.get(&key_id) == Some(key));
+
+    assert!(sks.remove(&key_id).unwrap());
+    let sks = open_store(dir.path(), 42);
+    assert!(!sks.contains(&key_id));
+}
+
+#[test]
+fn should_not_store_key_material_in_plaintext() {
+    let dir = mk_temp_dir_with_permissions(0o700);
+    let key = make_secret_key(1);
+    let mut sks = open_store(dir.path(), 42);
+    sks.insert(make_key_id(1), key.clone(), None).unwrap();
+
+    let data = fs::read(sks.encrypted_file_path()).unwrap();
+    assert!(data.starts_with(ENCRYPTED_SKS_MAGIC));
+    let secret = ed25519_secret_key_bytes(&key);
+    assert!(!data.windows(secret.len()).any(|window| window == secret));
+}
+
+#[test]
+fn should_panic_without_leaking_data_when_opening_with_wrong_kek() {
+    let dir = mk_temp_dir_with_permissions(0o700);
+    open_store(dir.path(), 42)
+        .insert(make_key_id(1), make_secret_key(1), None)
+        .unwrap();
+
+    let panic_msg = catch_unwind(AssertUnwindSafe(|| open_store(dir.path(), 43)));
+    assert_eq!(
+        "error decrypting SKS data: authentication failed, the key-encryption key may be wrong",
+        *panic_msg.unwrap_err().downcast_ref::<String>().unwrap()
+    );
+}
+
+#[test]
+fn should_detect_tampering_with_header_and_ciphertext() {
+    let kek = SecretArray::new_and_dont_zeroize_argument(&[1; KEY_ENCRYPTION_KEY_LEN]);
+    let plaintext = b"serialized secret key store";
+    let data = encrypt_sks_data(&kek, plaintext).unwrap();
+    assert_eq!(decrypt_sks_data(&kek, &data).unwrap().as_slice(), plaintext);
+
+    for index in [HEADER_LEN - 1, HEADER_LEN + NONCE_LEN, data.len() - 1] {
+        let mut tampered = data.clone();
+        tampered[index] ^= 1;
+        assert!(decrypt_sks_data(&kek, &tampered).is_err());
+    }
+    assert!(decrypt_sks_data(&kek, &data[..HEADER_LEN]).is_err());
+}
+
+#[test]
+fn should_fail_to_write_if_kek_is_unavailable() {
+    let dir = mk_temp_dir_with_permissions(0o700);
+    let mut sks =
+        EncryptedSecretKeyStore::open(dir.path(), SKS_FILE, UnavailableKeyEncryptionKey, None);
+    assert_matches!(
+        sks.insert(make_key_id(1), make_secret_key(1), None),
+        Err(SecretKeyStoreInsertionError::TransientError(_))
+    );
+    assert!(!sks.encrypted_file_path().exists());
+}
+
+#[test]
+fn should_migrate_keys_from_plaintext_store() {
+    let dir = mk_temp_dir_with_permissions(0o700);
+    let (key_id, key) = (make_key_id(1), make_secret_key(1));
+    {
+        let mut plaintext_sks = ProtoSecretKeyStore::open(dir.path(), PLAINTEXT_SKS_FILE, None);
+        plaintext_sks.insert(key_id, key.clone(), None).unwrap();
+    }
+
+    let sks = EncryptedSecretKeyStore::open_and_migrate(
+        dir.path(),
+        SKS_FILE,
+        PLAINTEXT_SKS_FILE,
+        FixedKeyEncryptionKey([42; KEY_ENCRYPTION_KEY_LEN]),
+        None,
+    );
+    assert!(sks.get(&key_id) == Some(key.clone()));
+    assert!(!dir.path().join(PLAINTEXT_SKS_FILE).exists());
+    drop(sks);
+
+    let sks = open_store(dir.path(), 42);
+    assert!(sks.get(&key_id) == Some(key));
+}
+
+#[test]
+fn should_not_touch_encrypted_store_if_there_is_nothing_to_migrate() {
+    let dir = mk_temp_dir_with_permissions(0o700);
+    let (key_id, key) = (make_key_id(1), make_secret_key(1));
+    open_store(dir.path(), 42)
+        .insert(key_id, key.clone(), None)
+        .unwrap();
+
+    let sks = EncryptedSecretKeyStore::open_and_migrate(
+        dir.path(),
+        SKS_FILE,
+        PLAINTEXT_SKS_FILE,
+        FixedKeyEncryptionKey([42; KEY_ENCRYPTION_KEY_LEN]),
+        None,
+    );
+    assert!(sks.get(&key_id) == Some(key));
+}
+
+#[test]
+fn should_generate_sealed_kek_file_only_once() {
+    let dir = mk_temp_dir_with_permissions(0o700);
+    let path = dir.path().join("kek");
+    let provider = SealedFileKeyEncryptionKeyProvider::generate_if_missing(&path).unwrap();
+    let kek = provider.key_encryption_key().unwrap();
+    assert_eq!(
+        fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+        0o600
+    );
+
+    let provider = SealedFileKeyEncryptionKeyProvider::generate_if_missing(&path).unwrap();
+    assert_eq!(
+        provider.key_encryption_key().unwrap().expose_secret(),
+        kek.expose_secret()
+    );
+}
+
+#[test]
+fn should_reject_sealed_kek_file_that_is_malformed_or_widely_readable() {
+    let dir = mk_temp_dir_with_permissions(0o700);
+    let path = dir.path().join("kek");
+    let provider = SealedFileKeyEncryptionKeyProvider::new(&path);
+    assert_matches!(
+        provider.key_encryption_key(),
+        Err(KeyEncryptionKeyProviderError::Unavailable(_))
+    );
+
+    fs::write(&path, [1; KEY_ENCRYPTION_KEY_LEN - 1]).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+    assert_matches!(
+        provider.key_encryption_key(),
+        Err(KeyEncryptionKeyProviderError::Malformed(_))
+    );
+
+    fs::write(&path, [1; KEY_ENCRYPTION_KEY_LEN]).unwrap();
+    assert!(provider.key_encryption_key().is_ok());
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+    assert_matches!(
+        provider.key_encryption_key(),
+        Err(KeyEncryptionKeyProviderError::Malformed(_))
+    );
+}
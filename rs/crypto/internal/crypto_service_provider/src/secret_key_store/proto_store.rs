@@ -36,7 +36,7 @@ fn key_id_from_hex(key_id_hex: &str) -> KeyId {
 #[path = "../gen/ic.crypto.v1.rs"]
 pub mod pb;
 
-type SecretKeys = HashMap<KeyId, (CspSecretKey, Option<Scope>)>;
+pub(super) type SecretKeys = HashMap<KeyId, (CspSecretKey, Option<Scope>)>;
 
 /// A secret key store that persists data to the filesystem, using protobufs for
 /// serialization
@@ -95,6 +95,11 @@ impl ProtoSecretKeyStore {
         self.proto_file.as_path()
     }
 
+    /// Returns a copy of all keys currently held by the store.
+    pub(super) fn secret_keys(&self) -> SecretKeys {
+        self.keys.read().clone()
+    }
+
     fn clean_up_old_sks(&self) {
         match self.old_proto_file_to_zeroize.try_exists() {
             Ok(exists) => {
@@ -245,7 +250,7 @@ impl ProtoSecretKeyStore {
         }
     }
 
-    fn migrate_to_current_version(sks_proto: pb::SecretKeyStore) -> SecretKeys {
+    pub(super) fn migrate_to_current_version(sks_proto: pb::SecretKeyStore) -> SecretKeys {
         match sks_proto.version {
             CURRENT_SKS_VERSION => ProtoSecretKeyStore::sks_proto_to_secret_keys(&sks_proto),
             2 => {
@@ -331,7 +336,7 @@ impl ProtoSecretKeyStore {
         }
     }
 
-    fn secret_keys_to_sks_proto(
+    pub(super) fn secret_keys_to_sks_proto(
         secret_keys: &SecretKeys,
     ) -> Result<pb::SecretKeyStore, SecretKeyStoreWriteError> {
         let mut sks_proto = pb::SecretKeyStore {
@@ -457,7 +462,7 @@ impl Drop for ProtoSecretKeyStore {
     }
 }
 
-fn overwrite_file_with_zeroes_and_delete_if_it_exists<P: AsRef<Path>>(
+pub(super) fn overwrite_file_with_zeroes_and_delete_if_it_exists<P: AsRef<Path>>(
     file: P,
     logger: &ReplicaLogger,
 ) {
@@ -498,7 +503,7 @@ fn overwrite_file_with_zeroes_and_delete_if_it_exists<P: AsRef<Path>>(
     };
 }
 
-fn with_write_lock<T, I, R, F>(v: T, f: F) -> Result<R, SecretKeyStoreWriteError>
+pub(super) fn with_write_lock<T, I, R, F>(v: T, f: F) -> Result<R, SecretKeyStoreWriteError>
 where
     T: AsRef<RwLock<I>>,
     F: FnOnce(&mut I) -> Result<R, SecretKeyStoreWriteError>,
@@ -507,7 +512,7 @@ where
     f(lock_result.borrow_mut())
 }
 
-fn with_read_lock<T, I, R, F>(v: T, f: F) -> Option<R>
+pub(super) fn with_read_lock<T, I, R, F>(v: T, f: F) -> Option<R>
 where
     T: AsRef<RwLock<I>>,
     F: FnOnce(&I) -> Option<R>,
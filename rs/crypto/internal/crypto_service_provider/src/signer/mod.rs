@@ -272,6 +272,37 @@ impl CspSigVerifier for Csp {
                     .collect();
                 ed25519::api::verify_batch(&pairs_of_refs[..], msg, seed)?;
             }
+            // use batch verification with a random linear combination for multi-signature shares
+            AlgorithmId::MultiBls12_381 => {
+                let sigs_and_pks: CryptoResult<
+                    Vec<(
+                        multi_sig::types::IndividualSignatureBytes,
+                        multi_sig::types::PublicKeyBytes,
+                    )>,
+                > = key_signature_pairs
+                    .iter()
+                    .map(|(pk, sig)| match (sig, pk) {
+                        (
+                            CspSignature::MultiBls12_381(MultiBls12_381_Signature::Individual(
+                                signature,
+                            )),
+                            CspPublicKey::MultiBls12_381(public_key),
+                        ) => Ok((*signature, *public_key)),
+                        (sig, pk) => Err(CryptoError::SignatureVerification {
+                            algorithm: algorithm_id,
+                            public_key_bytes: pk.pk_bytes().to_vec(),
+                            sig_bytes: sig.as_ref().to_vec(),
+                            internal_error: format!(
+                                "Invalid signature type: expected an individual {algorithm_id} signature but found {}",
+                                sig.algorithm()
+                            ),
+                        }),
+                    })
+                    .collect();
+                // generate a random seed to be used in batched sig verification
+                let seed = self.csp_vault.new_public_seed()?;
+                multi_sig::verify_individual_batch(msg, &sigs_and_pks?[..], &mut seed.into_rng())?;
+            }
             // use iterative verification for other `AlgorithmId`s
             _ => {
                 for (pk, sig) in key_signature_pairs {
@@ -810,6 +810,52 @@ mod multi {
             .is_ok());
     }
 
+    #[test]
+    fn individual_signatures_verify_in_batch() {
+        let [csp1, csp2, verifier] = csp_and_verifier_with_different_seeds();
+        let message = b"Three turtle doves";
+        let key_signature_pairs: Vec<_> = [csp1, csp2]
+            .iter()
+            .map(|csp| {
+                let (public_key, _pop) = csp.gen_committee_signing_key_pair().unwrap();
+                let key_id = KeyId::try_from(&public_key).unwrap();
+                let signature = csp
+                    .sign(AlgorithmId::MultiBls12_381, message, key_id)
+                    .expect("Signing failed");
+                (public_key, signature)
+            })
+            .collect();
+
+        assert_eq!(
+            verifier.verify_batch(&key_signature_pairs, message, AlgorithmId::MultiBls12_381),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn batch_verification_fails_if_one_individual_signature_is_invalid() {
+        let [csp1, csp2, verifier] = csp_and_verifier_with_different_seeds();
+        let message = b"Three turtle doves";
+        let mut key_signature_pairs: Vec<_> = [csp1, csp2]
+            .iter()
+            .map(|csp| {
+                let (public_key, _pop) = csp.gen_committee_signing_key_pair().unwrap();
+                let key_id = KeyId::try_from(&public_key).unwrap();
+                let signature = csp
+                    .sign(AlgorithmId::MultiBls12_381, message, key_id)
+                    .expect("Signing failed");
+                (public_key, signature)
+            })
+            .collect();
+        // The first signer's signature does not verify under the second signer's key.
+        key_signature_pairs[1].1 = key_signature_pairs[0].1.clone();
+
+        assert_matches!(
+            verifier.verify_batch(&key_signature_pairs, message, AlgorithmId::MultiBls12_381),
+            Err(CryptoError::SignatureVerification { .. })
+        );
+    }
+
     #[test]
     fn signature_verification_fails_gracefully_on_incompatible_signature() {
         let algorithm = AlgorithmId::MultiBls12_381;
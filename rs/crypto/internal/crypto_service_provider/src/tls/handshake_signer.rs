@@ -29,6 +29,14 @@ impl TlsHandshakeCspVault for CspTlsHandshakeSignerImpl {
         unimplemented!("CspTlsHandshakeSigner on purpose supports only tls_sign()-operation")
     }
 
+    fn rotate_tls_key_pair(
+        &self,
+        _node: NodeId,
+        _not_after: &str,
+    ) -> Result<TlsPublicKeyCert, CspTlsKeygenError> {
+        unimplemented!("CspTlsHandshakeSigner on purpose supports only tls_sign()-operation")
+    }
+
     fn tls_sign(&self, message: &[u8], key_id: &KeyId) -> Result<CspSignature, CspTlsSignError> {
         self.csp_vault.tls_sign(message, key_id)
     }
@@ -492,6 +492,30 @@ pub trait MultiSignatureCspVault {
     fn gen_committee_signing_key_pair(
         &self,
     ) -> Result<(CspPublicKey, CspPop), CspMultiSignatureKeygenError>;
+
+    /// Generates a new committee signing key pair, with a proof of possession,
+    /// that replaces the current committee signing key pair.
+    ///
+    /// The secret key of the replaced key pair is kept, so that the node can
+    /// still sign at registry versions at which the new public key is not yet
+    /// registered. The secret key that was kept from the rotation before is
+    /// deleted.
+    ///
+    /// # Returns
+    /// The public key of the new keypair and the proof of possession.
+    ///
+    /// # Errors
+    /// * `CspMultiSignatureKeygenError::InternalError` if there is an internal
+    ///   error (e.g., no committee signing public key is set yet).
+    /// * `CspMultiSignatureKeygenError::DuplicateKeyId` if there already
+    ///   exists a secret key in the store for the secret key ID derived from
+    ///   the public part of the randomly generated key pair.
+    /// * `CspMultiSignatureKeygenError::TransientInternalError` if there is a
+    ///   transient internal error, e.g,. an IO error when writing a key to
+    ///   disk, or an RPC error when calling a remote CSP vault.
+    fn rotate_committee_signing_key_pair(
+        &self,
+    ) -> Result<(CspPublicKey, CspPop), CspMultiSignatureKeygenError>;
 }
 
 /// Operations of `CspVault` related to threshold signatures
@@ -727,6 +751,26 @@ pub trait TlsHandshakeCspVault: Send + Sync {
         not_after: &str,
     ) -> Result<TlsPublicKeyCert, CspTlsKeygenError>;
 
+    /// Generates new TLS key material for node with ID `node_id` that replaces
+    /// the current TLS key material. The certificate is created as described
+    /// for [`Self::gen_tls_key_pair`].
+    ///
+    /// The secret key of the replaced certificate is kept, so that the node
+    /// can still perform TLS handshakes at registry versions at which the new
+    /// certificate is not yet registered. The secret key that was kept from
+    /// the rotation before is deleted.
+    ///
+    /// # Errors
+    /// * if `not_after` is not specified according to RFC 5280 or if
+    /// `not_after` is in the past
+    /// * if a malformed X509 certificate is generated
+    /// * if no TLS certificate is set yet
+    fn rotate_tls_key_pair(
+        &self,
+        node: NodeId,
+        not_after: &str,
+    ) -> Result<TlsPublicKeyCert, CspTlsKeygenError>;
+
     /// Signs the given message using the specified algorithm and key ID.
     ///
     /// # Arguments
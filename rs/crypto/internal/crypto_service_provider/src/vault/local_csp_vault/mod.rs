@@ -14,6 +14,9 @@ mod tls;
 
 use crate::public_key_store::proto_pubkey_store::ProtoPublicKeyStore;
 use crate::public_key_store::PublicKeyStore;
+use crate::secret_key_store::encrypted_store::{
+    EncryptedSecretKeyStore, SealedFileKeyEncryptionKeyProvider,
+};
 use crate::secret_key_store::proto_store::ProtoSecretKeyStore;
 use crate::secret_key_store::SecretKeyStore;
 use crate::CspRwLock;
@@ -121,10 +124,6 @@ impl ProdLocalCspVault {
         metrics: Arc<CryptoMetrics>,
         logger: ReplicaLogger,
     ) -> Self {
-        const SKS_DATA_FILENAME: &str = "sks_data.pb";
-        const PUBLIC_KEY_STORE_DATA_FILENAME: &str = "public_keys.pb";
-        const CANISTER_SKS_DATA_FILENAME: &str = "canister_sks_data.pb";
-
         let node_secret_key_store =
             ProtoSecretKeyStore::open(key_store_dir, SKS_DATA_FILENAME, Some(new_logger!(logger)));
         let canister_secret_key_store = ProtoSecretKeyStore::open(
@@ -147,6 +146,72 @@ impl ProdLocalCspVault {
     }
 }
 
+const SKS_DATA_FILENAME: &str = "sks_data.pb";
+const PUBLIC_KEY_STORE_DATA_FILENAME: &str = "public_keys.pb";
+const CANISTER_SKS_DATA_FILENAME: &str = "canister_sks_data.pb";
+const ENCRYPTED_SKS_DATA_FILENAME: &str = "sks_data.enc";
+const ENCRYPTED_CANISTER_SKS_DATA_FILENAME: &str = "canister_sks_data.enc";
+
+pub type EncryptedSecretKeyStoreProdLocalCspVault = LocalCspVault<
+    OsRng,
+    EncryptedSecretKeyStore<SealedFileKeyEncryptionKeyProvider>,
+    EncryptedSecretKeyStore<SealedFileKeyEncryptionKeyProvider>,
+    ProtoPublicKeyStore,
+>;
+
+impl EncryptedSecretKeyStoreProdLocalCspVault {
+    /// Creates a production-grade local CSP vault whose secret key stores in
+    /// `key_store_dir` are encrypted with the key-encryption key read from
+    /// `key_encryption_key_file`.
+    ///
+    /// Unencrypted secret key stores in `key_store_dir` are migrated to the
+    /// encrypted ones, and then zeroized and deleted.
+    ///
+    /// # Panics
+    /// If the secret key stores cannot be opened or migrated, see
+    /// [`EncryptedSecretKeyStore::open_and_migrate`].
+    pub fn new_in_dir_with_encrypted_secret_key_stores(
+        key_store_dir: &Path,
+        key_encryption_key_file: &Path,
+        metrics: Arc<CryptoMetrics>,
+        logger: ReplicaLogger,
+    ) -> Self {
+        let node_secret_key_store = EncryptedSecretKeyStore::open_and_migrate(
+            key_store_dir,
+            ENCRYPTED_SKS_DATA_FILENAME,
+            SKS_DATA_FILENAME,
+            SealedFileKeyEncryptionKeyProvider::new(key_encryption_key_file),
+            Some(new_logger!(logger)),
+        );
+        let canister_secret_key_store = EncryptedSecretKeyStore::open_and_migrate(
+            key_store_dir,
+            ENCRYPTED_CANISTER_SKS_DATA_FILENAME,
+            CANISTER_SKS_DATA_FILENAME,
+            SealedFileKeyEncryptionKeyProvider::new(key_encryption_key_file),
+            Some(new_logger!(logger)),
+        );
+        let public_key_store = ProtoPublicKeyStore::open(
+            key_store_dir,
+            PUBLIC_KEY_STORE_DATA_FILENAME,
+            new_logger!(logger),
+        );
+        ensure_unique_paths(&[
+            node_secret_key_store.encrypted_file_path(),
+            canister_secret_key_store.encrypted_file_path(),
+            public_key_store.proto_file_path(),
+        ]);
+        LocalCspVault::new_internal(
+            OsRng,
+            node_secret_key_store,
+            canister_secret_key_store,
+            public_key_store,
+            Arc::new(CurrentSystemTimeSource::new(new_logger!(&logger))),
+            metrics,
+            logger,
+        )
+    }
+}
+
 impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore>
     LocalCspVault<R, S, C, P>
 {
@@ -1,7 +1,7 @@
 //! Multi-Signature operations provided by the CSP vault.
 use crate::key_id::KeyId;
 use crate::keygen::utils::committee_signing_pk_to_proto;
-use crate::public_key_store::{PublicKeySetOnceError, PublicKeyStore};
+use crate::public_key_store::{PublicKeyRotationError, PublicKeySetOnceError, PublicKeyStore};
 use crate::secret_key_store::{SecretKeyStore, SecretKeyStoreInsertionError};
 use crate::types::{CspPop, CspPublicKey, CspSecretKey, CspSignature, MultiBls12_381_Signature};
 use crate::vault::api::{
@@ -11,6 +11,7 @@ use crate::vault::local_csp_vault::LocalCspVault;
 use ic_crypto_internal_logmon::metrics::{MetricsDomain, MetricsResult, MetricsScope};
 use ic_crypto_internal_multi_sig_bls12381 as multi_bls12381;
 use ic_crypto_node_key_validation::ValidCommitteeSigningPublicKey;
+use ic_logger::warn;
 use ic_protobuf::registry::crypto::v1::PublicKey;
 use ic_types::crypto::{AlgorithmId, CryptoError};
 use rand::{CryptoRng, Rng};
@@ -53,6 +54,21 @@ impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore
         );
         result
     }
+
+    fn rotate_committee_signing_key_pair(
+        &self,
+    ) -> Result<(CspPublicKey, CspPop), CspMultiSignatureKeygenError> {
+        let start_time = self.metrics.now();
+        let result = self.rotate_committee_signing_key_pair_internal();
+        self.metrics.observe_duration_seconds(
+            MetricsDomain::MultiSignature,
+            MetricsScope::Local,
+            "rotate_committee_signing_key_pair",
+            MetricsResult::from(&result),
+            start_time,
+        );
+        result
+    }
 }
 
 impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore>
@@ -69,6 +85,22 @@ impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore
         Ok(pk_and_pop)
     }
 
+    fn rotate_committee_signing_key_pair_internal(
+        &self,
+    ) -> Result<(CspPublicKey, CspPop), CspMultiSignatureKeygenError> {
+        let (secret_key, pk_and_pop) = self.gen_multi_bls12381_keypair_with_pop()?;
+        let key_id = KeyId::try_from(&pk_and_pop.0)?;
+        let mut committee_public_key_proto = committee_signing_pk_to_proto(pk_and_pop.clone());
+        self.set_timestamp(&mut committee_public_key_proto);
+        let valid_public_key = validate_committee_signing_public_key(committee_public_key_proto)?;
+        self.store_rotated_committee_signing_key_pair(
+            key_id,
+            secret_key,
+            valid_public_key.get().clone(),
+        )?;
+        Ok(pk_and_pop)
+    }
+
     fn store_committee_signing_key_pair(
         &self,
         key_id: KeyId,
@@ -78,18 +110,7 @@ impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore
         let (mut sks_write_lock, mut pks_write_lock) = self.sks_and_pks_write_locks();
         sks_write_lock
             .insert(key_id, secret_key, None)
-            .map_err(|sks_error| match sks_error {
-                SecretKeyStoreInsertionError::DuplicateKeyId(key_id) => {
-                    CspMultiSignatureKeygenError::DuplicateKeyId { key_id }
-                }
-                SecretKeyStoreInsertionError::SerializationError(serialization_error) => {
-                    CspMultiSignatureKeygenError::InternalError {internal_error:
-                    format!("Error persisting secret key store during CSP multi-signature key generation: {}", serialization_error)}}
-                SecretKeyStoreInsertionError::TransientError(io_error) => {
-                    CspMultiSignatureKeygenError::TransientInternalError {internal_error:
-                    format!("Error persisting secret key store during CSP multi-signature key generation: {}", io_error)}
-                }
-            })
+            .map_err(sks_insertion_error_to_keygen_error)
             .and_then(|()| {
                 pks_write_lock
                     .set_once_committee_signing_pubkey(committee_public_key_proto)
@@ -111,6 +132,78 @@ impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore
                     })
             })
     }
+
+    fn store_rotated_committee_signing_key_pair(
+        &self,
+        key_id: KeyId,
+        secret_key: CspSecretKey,
+        committee_public_key_proto: PublicKey,
+    ) -> Result<(), CspMultiSignatureKeygenError> {
+        let (mut sks_write_lock, mut pks_write_lock) = self.sks_and_pks_write_locks();
+        sks_write_lock
+            .insert(key_id, secret_key, None)
+            .map_err(sks_insertion_error_to_keygen_error)?;
+        let no_longer_kept_public_key = pks_write_lock
+            .rotate_committee_signing_pubkey(committee_public_key_proto)
+            .map_err(|e| match e {
+                PublicKeyRotationError::NotSet => CspMultiSignatureKeygenError::InternalError {
+                    internal_error: "no committee signing public key to rotate".to_string(),
+                },
+                PublicKeyRotationError::Io(io_error) => {
+                    CspMultiSignatureKeygenError::TransientInternalError {
+                        internal_error: format!(
+                            "IO error persisting rotated committee signing public key: {}",
+                            io_error
+                        ),
+                    }
+                }
+            })?;
+        if let Some(public_key_proto) = no_longer_kept_public_key {
+            // Failing to delete the secret key is not fatal: it merely stays in the store.
+            let removal_result = CspPublicKey::try_from(public_key_proto)
+                .map_err(|e| format!("{}", e))
+                .and_then(|public_key| KeyId::try_from(&public_key).map_err(|e| format!("{:?}", e)))
+                .and_then(|key_id| {
+                    sks_write_lock
+                        .remove(&key_id)
+                        .map_err(|e| format!("{:?}", e))
+                });
+            if let Err(error) = removal_result {
+                warn!(
+                    self.logger,
+                    "Failed to delete secret key of no longer kept committee signing key: {}",
+                    error
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn sks_insertion_error_to_keygen_error(
+    sks_error: SecretKeyStoreInsertionError,
+) -> CspMultiSignatureKeygenError {
+    match sks_error {
+        SecretKeyStoreInsertionError::DuplicateKeyId(key_id) => {
+            CspMultiSignatureKeygenError::DuplicateKeyId { key_id }
+        }
+        SecretKeyStoreInsertionError::SerializationError(serialization_error) => {
+            CspMultiSignatureKeygenError::InternalError {
+                internal_error: format!(
+                    "Error persisting secret key store during CSP multi-signature key generation: {}",
+                    serialization_error
+                ),
+            }
+        }
+        SecretKeyStoreInsertionError::TransientError(io_error) => {
+            CspMultiSignatureKeygenError::TransientInternalError {
+                internal_error: format!(
+                    "Error persisting secret key store during CSP multi-signature key generation: {}",
+                    io_error
+                ),
+            }
+        }
+    }
 }
 
 impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore>
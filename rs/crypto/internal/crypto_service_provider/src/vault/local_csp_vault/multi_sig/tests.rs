@@ -161,6 +161,46 @@ fn should_fail_with_transient_internal_error_if_committee_signing_key_persistenc
     );
 }
 
+#[test]
+fn should_rotate_committee_signing_key_pair_and_keep_only_the_previous_secret_key() {
+    let csp_vault = LocalCspVault::builder().build();
+    let (first_pk, _pop) = csp_vault.gen_committee_signing_key_pair().unwrap();
+    let (second_pk, _pop) = csp_vault.rotate_committee_signing_key_pair().unwrap();
+    let sks_contains = |pk: &CspPublicKey| {
+        csp_vault
+            .sks_contains(&KeyId::try_from(pk).unwrap())
+            .unwrap()
+    };
+    assert!(sks_contains(&first_pk));
+    assert!(sks_contains(&second_pk));
+
+    let (third_pk, third_pop) = csp_vault.rotate_committee_signing_key_pair().unwrap();
+
+    assert!(!sks_contains(&first_pk));
+    assert!(sks_contains(&second_pk));
+    assert!(sks_contains(&third_pk));
+    assert_eq!(
+        csp_vault
+            .current_node_public_keys()
+            .expect("missing public keys")
+            .committee_signing_public_key
+            .expect("missing committee signing key"),
+        committee_signing_pk_to_proto((third_pk, third_pop))
+    );
+}
+
+#[test]
+fn should_fail_with_internal_error_if_rotating_committee_signing_key_that_was_never_generated() {
+    let vault = LocalCspVault::builder().build();
+
+    let result = vault.rotate_committee_signing_key_pair();
+
+    assert_matches!(result,
+        Err(CspMultiSignatureKeygenError::InternalError { internal_error })
+        if internal_error.contains("no committee signing public key to rotate")
+    );
+}
+
 #[test]
 fn should_generate_verifiable_pop() {
     let csp_vault = LocalCspVault::builder().build();
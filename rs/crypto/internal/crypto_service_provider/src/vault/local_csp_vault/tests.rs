@@ -125,6 +125,78 @@ mod csp_new {
     }
 }
 
+mod encrypted_secret_key_stores {
+    use super::*;
+    use crate::secret_key_store::encrypted_store::SealedFileKeyEncryptionKeyProvider;
+    use crate::vault::api::SecretKeyStoreCspVault;
+
+    #[test]
+    fn should_migrate_unencrypted_secret_key_stores() {
+        let temp_dir = mk_temp_dir_with_permissions(0o700);
+        let kek_file = temp_dir.path().join("kek");
+        SealedFileKeyEncryptionKeyProvider::generate_if_missing(&kek_file)
+            .expect("failed to generate key-encryption key");
+        let key_id = make_key_id(42);
+        let canister_key_id = make_key_id(43);
+        {
+            let vault = LocalCspVault::new_in_dir(
+                temp_dir.path(),
+                Arc::new(CryptoMetrics::none()),
+                no_op_logger(),
+            );
+            vault
+                .sks_write_lock()
+                .insert(key_id, make_secret_key(42), None)
+                .expect("failed to insert key");
+            vault
+                .canister_sks_write_lock()
+                .insert(canister_key_id, make_secret_key(43), None)
+                .expect("failed to insert canister key");
+        }
+
+        let vault = LocalCspVault::new_in_dir_with_encrypted_secret_key_stores(
+            temp_dir.path(),
+            &kek_file,
+            Arc::new(CryptoMetrics::none()),
+            no_op_logger(),
+        );
+
+        assert_eq!(vault.sks_contains(&key_id), Ok(true));
+        assert!(vault.canister_sks_read_lock().contains(&canister_key_id));
+        assert!(!vault.sks_read_lock().contains(&canister_key_id));
+        for file in ["sks_data.pb", "canister_sks_data.pb"] {
+            assert!(!temp_dir.path().join(file).exists());
+        }
+        for file in ["sks_data.enc", "canister_sks_data.enc"] {
+            assert!(temp_dir.path().join(file).exists());
+        }
+    }
+
+    #[test]
+    fn should_persist_keys_in_encrypted_secret_key_stores() {
+        let temp_dir = mk_temp_dir_with_permissions(0o700);
+        let kek_file = temp_dir.path().join("kek");
+        SealedFileKeyEncryptionKeyProvider::generate_if_missing(&kek_file)
+            .expect("failed to generate key-encryption key");
+        let key_id = make_key_id(42);
+        let new_vault = || {
+            LocalCspVault::new_in_dir_with_encrypted_secret_key_stores(
+                temp_dir.path(),
+                &kek_file,
+                Arc::new(CryptoMetrics::none()),
+                no_op_logger(),
+            )
+        };
+        new_vault()
+            .sks_write_lock()
+            .insert(key_id, make_secret_key(42), None)
+            .expect("failed to insert key");
+
+        assert_eq!(new_vault().sks_contains(&key_id), Ok(true));
+        assert!(!temp_dir.path().join("sks_data.pb").exists());
+    }
+}
+
 #[test]
 fn should_have_separate_sks_and_canister_sks() {
     let vault = LocalCspVault::builder().build();
@@ -1,6 +1,6 @@
 //! TLS handshake operations provided by the CSP vault
 use crate::key_id::KeyId;
-use crate::public_key_store::{PublicKeySetOnceError, PublicKeyStore};
+use crate::public_key_store::{PublicKeyRotationError, PublicKeySetOnceError, PublicKeyStore};
 use crate::secret_key_store::{SecretKeyStore, SecretKeyStoreInsertionError};
 use crate::types::{CspSecretKey, CspSignature};
 use crate::vault::api::{CspTlsKeygenError, CspTlsSignError, TlsHandshakeCspVault};
@@ -13,6 +13,7 @@ use ic_crypto_internal_tls::keygen::{
 use ic_crypto_node_key_validation::ValidTlsCertificate;
 use ic_crypto_secrets_containers::{SecretArray, SecretVec};
 use ic_crypto_tls_interfaces::TlsPublicKeyCert;
+use ic_logger::warn;
 use ic_protobuf::registry::crypto::v1::X509PublicKeyCert;
 use ic_types::crypto::AlgorithmId;
 use ic_types::NodeId;
@@ -43,6 +44,23 @@ impl<R: Rng + CryptoRng + Send + Sync, S: SecretKeyStore, C: SecretKeyStore, P:
         result
     }
 
+    fn rotate_tls_key_pair(
+        &self,
+        node: NodeId,
+        not_after: &str,
+    ) -> Result<TlsPublicKeyCert, CspTlsKeygenError> {
+        let start_time = self.metrics.now();
+        let result = self.rotate_tls_key_pair_internal(node, not_after);
+        self.metrics.observe_duration_seconds(
+            MetricsDomain::TlsHandshake,
+            MetricsScope::Local,
+            "rotate_tls_key_pair",
+            MetricsResult::from(&result),
+            start_time,
+        );
+        result
+    }
+
     fn tls_sign(&self, message: &[u8], key_id: &KeyId) -> Result<CspSignature, CspTlsSignError> {
         let start_time = self.metrics.now();
         let result = self.tls_sign_internal(message, key_id);
@@ -104,6 +122,29 @@ impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore
         node: NodeId,
         not_after: &str,
     ) -> Result<TlsPublicKeyCert, CspTlsKeygenError> {
+        let (x509_pk_cert, key_id, secret_key, valid_cert) =
+            self.gen_tls_key_material(node, not_after)?;
+        self.store_tls_key_pair(key_id, secret_key, valid_cert.get().clone())?;
+        Ok(x509_pk_cert)
+    }
+
+    fn rotate_tls_key_pair_internal(
+        &self,
+        node: NodeId,
+        not_after: &str,
+    ) -> Result<TlsPublicKeyCert, CspTlsKeygenError> {
+        let (x509_pk_cert, key_id, secret_key, valid_cert) =
+            self.gen_tls_key_material(node, not_after)?;
+        self.store_rotated_tls_key_pair(key_id, secret_key, valid_cert.get().clone())?;
+        Ok(x509_pk_cert)
+    }
+
+    fn gen_tls_key_material(
+        &self,
+        node: NodeId,
+        not_after: &str,
+    ) -> Result<(TlsPublicKeyCert, KeyId, CspSecretKey, ValidTlsCertificate), CspTlsKeygenError>
+    {
         let common_name = &node.get().to_string()[..];
         let not_after_asn1 = Asn1Time::from_str_x509(not_after).map_err(|_| {
             CspTlsKeygenError::InvalidNotAfterDate {
@@ -137,9 +178,7 @@ impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore
         let secret_key = CspSecretKey::TlsEd25519(secret_key);
         let cert_proto = x509_pk_cert.to_proto();
         let valid_cert = validate_tls_certificate(cert_proto, node)?;
-        self.store_tls_key_pair(key_id, secret_key, valid_cert.get().clone())?;
-
-        Ok(x509_pk_cert)
+        Ok((x509_pk_cert, key_id, secret_key, valid_cert))
     }
 
     fn store_tls_key_pair(
@@ -151,27 +190,7 @@ impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore
         let (mut sks_write_lock, mut pks_write_lock) = self.sks_and_pks_write_locks();
         sks_write_lock
             .insert(key_id, secret_key, None)
-            .map_err(|sks_error| match sks_error {
-                SecretKeyStoreInsertionError::DuplicateKeyId(key_id) => {
-                    CspTlsKeygenError::DuplicateKeyId { key_id }
-                }
-                SecretKeyStoreInsertionError::SerializationError(serialization_error) => {
-                    CspTlsKeygenError::InternalError {
-                        internal_error: format!(
-                            "Error persisting secret key store during CSP TLS key generation: {}",
-                            serialization_error
-                        ),
-                    }
-                }
-                SecretKeyStoreInsertionError::TransientError(io_error) => {
-                    CspTlsKeygenError::TransientInternalError {
-                        internal_error: format!(
-                            "Error persisting secret key store during CSP TLS key generation: {}",
-                            io_error
-                        ),
-                    }
-                }
-            })
+            .map_err(sks_insertion_error_to_tls_keygen_error)
             .and_then(|()| {
                 pks_write_lock
                     .set_once_tls_certificate(cert_proto)
@@ -191,6 +210,51 @@ impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore
             })
     }
 
+    fn store_rotated_tls_key_pair(
+        &self,
+        key_id: KeyId,
+        secret_key: CspSecretKey,
+        cert_proto: X509PublicKeyCert,
+    ) -> Result<(), CspTlsKeygenError> {
+        let (mut sks_write_lock, mut pks_write_lock) = self.sks_and_pks_write_locks();
+        sks_write_lock
+            .insert(key_id, secret_key, None)
+            .map_err(sks_insertion_error_to_tls_keygen_error)?;
+        let no_longer_kept_cert =
+            pks_write_lock
+                .rotate_tls_certificate(cert_proto)
+                .map_err(|e| match e {
+                    PublicKeyRotationError::NotSet => CspTlsKeygenError::InternalError {
+                        internal_error: "no TLS certificate to rotate".to_string(),
+                    },
+                    PublicKeyRotationError::Io(io_error) => {
+                        CspTlsKeygenError::TransientInternalError {
+                            internal_error: format!(
+                                "IO error persisting rotated TLS certificate: {}",
+                                io_error
+                            ),
+                        }
+                    }
+                })?;
+        if let Some(cert_proto) = no_longer_kept_cert {
+            // Failing to delete the secret key is not fatal: it merely stays in the store.
+            let removal_result = KeyId::try_from((AlgorithmId::Tls, &cert_proto.certificate_der))
+                .map_err(|e| format!("{:?}", e))
+                .and_then(|key_id| {
+                    sks_write_lock
+                        .remove(&key_id)
+                        .map_err(|e| format!("{:?}", e))
+                });
+            if let Err(error) = removal_result {
+                warn!(
+                    self.logger,
+                    "Failed to delete secret key of no longer kept TLS certificate: {}", error
+                );
+            }
+        }
+        Ok(())
+    }
+
     fn tls_sign_internal(
         &self,
         message: &[u8],
@@ -221,6 +285,32 @@ impl<R: Rng + CryptoRng, S: SecretKeyStore, C: SecretKeyStore, P: PublicKeyStore
     }
 }
 
+fn sks_insertion_error_to_tls_keygen_error(
+    sks_error: SecretKeyStoreInsertionError,
+) -> CspTlsKeygenError {
+    match sks_error {
+        SecretKeyStoreInsertionError::DuplicateKeyId(key_id) => {
+            CspTlsKeygenError::DuplicateKeyId { key_id }
+        }
+        SecretKeyStoreInsertionError::SerializationError(serialization_error) => {
+            CspTlsKeygenError::InternalError {
+                internal_error: format!(
+                    "Error persisting secret key store during CSP TLS key generation: {}",
+                    serialization_error
+                ),
+            }
+        }
+        SecretKeyStoreInsertionError::TransientError(io_error) => {
+            CspTlsKeygenError::TransientInternalError {
+                internal_error: format!(
+                    "Error persisting secret key store during CSP TLS key generation: {}",
+                    io_error
+                ),
+            }
+        }
+    }
+}
+
 fn validate_tls_certificate(
     cert_proto: X509PublicKeyCert,
     node: NodeId,
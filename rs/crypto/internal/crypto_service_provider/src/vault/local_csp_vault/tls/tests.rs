@@ -15,8 +15,11 @@ mod keygen {
     use crate::public_key_store::PublicKeySetOnceError;
     use crate::secret_key_store::mock_secret_key_store::MockSecretKeyStore;
     use crate::vault::api::CspTlsKeygenError;
+    use crate::vault::api::PublicKeyStoreCspVault;
+    use crate::vault::api::SecretKeyStoreCspVault;
     use crate::vault::api::TlsHandshakeCspVault;
     use crate::vault::local_csp_vault::LocalCspVault;
+    use ic_crypto_tls_interfaces::TlsPublicKeyCert;
     use mockall::Sequence;
 
     const NOT_AFTER: &str = "99991231235959Z";
@@ -241,6 +244,50 @@ mod keygen {
             if internal_error.contains(&expected_serialization_error)
         );
     }
+
+    #[test]
+    fn should_rotate_tls_key_pair_and_keep_only_the_previous_secret_key() {
+        let vault = LocalCspVault::builder().build();
+        let first_cert = vault
+            .gen_tls_key_pair(node_test_id(test_utils::tls::NODE_1), NOT_AFTER)
+            .unwrap();
+        let second_cert = vault
+            .rotate_tls_key_pair(node_test_id(test_utils::tls::NODE_1), NOT_AFTER)
+            .unwrap();
+        let sks_contains =
+            |cert: &TlsPublicKeyCert| vault.sks_contains(&KeyId::try_from(cert).unwrap()).unwrap();
+        assert!(sks_contains(&first_cert));
+        assert!(sks_contains(&second_cert));
+
+        let third_cert = vault
+            .rotate_tls_key_pair(node_test_id(test_utils::tls::NODE_1), NOT_AFTER)
+            .unwrap();
+
+        assert!(!sks_contains(&first_cert));
+        assert!(sks_contains(&second_cert));
+        assert!(sks_contains(&third_cert));
+        assert_eq!(
+            vault
+                .current_node_public_keys()
+                .expect("missing public keys")
+                .tls_certificate
+                .expect("missing tls certificate"),
+            third_cert.to_proto()
+        );
+    }
+
+    #[test]
+    fn should_fail_with_internal_error_if_rotating_tls_certificate_that_was_never_generated() {
+        let vault = LocalCspVault::builder().build();
+
+        let result = vault.rotate_tls_key_pair(node_test_id(test_utils::tls::NODE_1), NOT_AFTER);
+
+        assert_matches!(
+            result,
+            Err(CspTlsKeygenError::InternalError { internal_error })
+            if internal_error.contains("no TLS certificate to rotate")
+        );
+    }
 }
 
 mod sign {
@@ -0,0 +1,465 @@
+//! Auditing of requests to the remote CSP vault.
+//!
+//! The vault server records one line per audited request (i.e., per signing
+//! request) in a local audit log that is rotated once it exceeds a configured
+//! size, and enforces a maximum number of requests per second for each
+//! audited operation. Together, this allows to detect (and limit) anomalous
+//! signing behaviour of a compromised replica.
+//!
+//! The audit log is written by a dedicated thread, so that the vault server
+//! never blocks on file I/O while serving requests.
+use crate::key_id::KeyId;
+use ic_crypto_sha::Sha256;
+use ic_logger::{warn, ReplicaLogger};
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
+
+#[cfg(test)]
+mod tests;
+
+const DEFAULT_MAX_FILE_SIZE_BYTES: u64 = 100 * 1024 * 1024;
+const DEFAULT_MAX_ROTATED_FILES: usize = 5;
+pub const DEFAULT_MAX_REQUESTS_PER_SECOND: u32 = 1_000;
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(1);
+const AUDIT_LOG_QUEUE_CAPACITY: usize = 10_000;
+
+/// The vault operations that are audited and rate limited.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AuditedOperation {
+    Sign,
+    MultiSign,
+    ThresholdSign,
+    TlsSign,
+    EcdsaSignShare,
+}
+
+impl AuditedOperation {
+    pub const ALL: [AuditedOperation; 5] = [
+        AuditedOperation::Sign,
+        AuditedOperation::MultiSign,
+        AuditedOperation::ThresholdSign,
+        AuditedOperation::TlsSign,
+        AuditedOperation::EcdsaSignShare,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditedOperation::Sign => "sign",
+            AuditedOperation::MultiSign => "multi_sign",
+            AuditedOperation::ThresholdSign => "threshold_sign",
+            AuditedOperation::TlsSign => "tls_sign",
+            AuditedOperation::EcdsaSignShare => "ecdsa_sign_share",
+        }
+    }
+}
+
+impl FromStr for AuditedOperation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AuditedOperation::ALL
+            .into_iter()
+            .find(|operation| operation.as_str() == s)
+            .ok_or_else(|| {
+                format!(
+                    "unknown operation '{}', expected one of: {}",
+                    s,
+                    AuditedOperation::ALL
+                        .map(|operation| operation.as_str())
+                        .join(", ")
+                )
+            })
+    }
+}
+
+/// Configuration of the audit log and the rate limits of the vault server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VaultAuditConfig {
+    log_file: PathBuf,
+    max_file_size_bytes: u64,
+    max_rotated_files: usize,
+    default_max_requests_per_second: u32,
+    max_requests_per_second: BTreeMap<AuditedOperation, u32>,
+}
+
+impl VaultAuditConfig {
+    /// Creates a configuration that writes the audit log to `log_file`, with
+    /// default values for the log rotation and the rate limits.
+    pub fn new(log_file: PathBuf) -> Self {
+        Self {
+            log_file,
+            max_file_size_bytes: DEFAULT_MAX_FILE_SIZE_BYTES,
+            max_rotated_files: DEFAULT_MAX_ROTATED_FILES,
+            default_max_requests_per_second: DEFAULT_MAX_REQUESTS_PER_SECOND,
+            max_requests_per_second: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the size after which the audit log is rotated.
+    pub fn with_max_file_size_bytes(mut self, max_file_size_bytes: u64) -> Self {
+        self.max_file_size_bytes = max_file_size_bytes;
+        self
+    }
+
+    /// Sets the number of rotated audit log files that are kept.
+    pub fn with_max_rotated_files(mut self, max_rotated_files: usize) -> Self {
+        self.max_rotated_files = max_rotated_files;
+        self
+    }
+
+    /// Sets the maximum number of requests per second for all operations
+    /// without a limit of their own.
+    pub fn with_default_max_requests_per_second(mut self, max_requests_per_second: u32) -> Self {
+        self.default_max_requests_per_second = max_requests_per_second;
+        self
+    }
+
+    /// Sets the maximum number of requests per second for `operation`.
+    pub fn with_max_requests_per_second(
+        mut self,
+        operation: AuditedOperation,
+        max_requests_per_second: u32,
+    ) -> Self {
+        self.max_requests_per_second
+            .insert(operation, max_requests_per_second);
+        self
+    }
+
+    fn max_requests_per_second(&self, operation: AuditedOperation) -> u32 {
+        self.max_requests_per_second
+            .get(&operation)
+            .copied()
+            .unwrap_or(self.default_max_requests_per_second)
+    }
+}
+
+/// The process that connected to the vault server, as determined from the
+/// credentials of the peer of the Unix domain socket.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VaultCaller {
+    pub pid: Option<i32>,
+    pub uid: Option<u32>,
+}
+
+impl VaultCaller {
+    pub fn unknown() -> Self {
+        Self {
+            pid: None,
+            uid: None,
+        }
+    }
+
+    pub fn from_unix_stream(stream: &tokio::net::UnixStream) -> Self {
+        match stream.peer_cred() {
+            Ok(cred) => Self {
+                pid: cred.pid(),
+                uid: Some(cred.uid()),
+            },
+            Err(_) => Self::unknown(),
+        }
+    }
+}
+
+impl fmt::Display for VaultCaller {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.pid {
+            Some(pid) => write!(f, "pid={}", pid)?,
+            None => write!(f, "pid=none")?,
+        }
+        match self.uid {
+            Some(uid) => write!(f, " uid={}", uid),
+            None => write!(f, " uid=none"),
+        }
+    }
+}
+
+/// A single audited request to the vault.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditedRequest {
+    operation: AuditedOperation,
+    component_id: String,
+    key_id: Option<KeyId>,
+    message_hash: Option<[u8; 32]>,
+}
+
+impl AuditedRequest {
+    /// Creates a request for `operation` issued by the client component
+    /// `component_id`, as sent by the client in the request metadata.
+    pub fn new(operation: AuditedOperation, component_id: String) -> Self {
+        Self {
+            operation,
+            component_id,
+            key_id: None,
+            message_hash: None,
+        }
+    }
+
+    pub fn with_key_id(mut self, key_id: KeyId) -> Self {
+        self.key_id = Some(key_id);
+        self
+    }
+
+    /// Records the SHA-256 hash of `message`, so that the message itself is not logged.
+    pub fn with_message(mut self, message: &[u8]) -> Self {
+        self.message_hash = Some(Sha256::hash(message));
+        self
+    }
+
+    pub fn operation(&self) -> AuditedOperation {
+        self.operation
+    }
+
+    pub fn component_id(&self) -> &str {
+        &self.component_id
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AuditOutcome {
+    Ok,
+    Err,
+    RateLimited,
+}
+
+impl AuditOutcome {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AuditOutcome::Ok => "ok",
+            AuditOutcome::Err => "error",
+            AuditOutcome::RateLimited => "rate_limited",
+        }
+    }
+}
+
+/// Formats an audit log line, e.g.,
+/// `timestamp_ms=1680000000000 operation=sign key_id=KeyId(0x..) message_sha256=ab.. component=replica pid=42 uid=0 latency_us=120 outcome=ok`.
+fn audit_log_line(
+    timestamp: SystemTime,
+    request: &AuditedRequest,
+    caller: &VaultCaller,
+    latency: Duration,
+    outcome: AuditOutcome,
+) -> String {
+    let timestamp_ms = timestamp
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let key_id = request
+        .key_id
+        .map(|key_id| key_id.to_string())
+        .unwrap_or_else(|| "none".to_string());
+    let message_hash = request
+        .message_hash
+        .map(hex::encode)
+        .unwrap_or_else(|| "none".to_string());
+    format!(
+        "timestamp_ms={} operation={} key_id={} message_sha256={} component={} {} latency_us={} outcome={}\n",
+        timestamp_ms,
+        request.operation.as_str(),
+        key_id,
+        message_hash,
+        request.component_id,
+        caller,
+        latency.as_micros(),
+        outcome.as_str()
+    )
+}
+
+/// An append-only log file that is rotated once it exceeds a maximum size.
+///
+/// Upon rotation, `<file>` is renamed to `<file>.1`, `<file>.1` to `<file>.2`,
+/// and so on, and the oldest file beyond the maximum number of rotated files
+/// is deleted.
+struct RotatingLogFile {
+    path: PathBuf,
+    max_file_size_bytes: u64,
+    max_rotated_files: usize,
+    file: File,
+    file_size_bytes: u64,
+}
+
+impl RotatingLogFile {
+    fn open(
+        path: PathBuf,
+        max_file_size_bytes: u64,
+        max_rotated_files: usize,
+    ) -> std::io::Result<Self> {
+        let file = open_for_append(&path)?;
+        let file_size_bytes = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_file_size_bytes,
+            max_rotated_files,
+            file,
+            file_size_bytes,
+        })
+    }
+
+    fn append(&mut self, line: &str) -> std::io::Result<()> {
+        if self.file_size_bytes > 0
+            && self.file_size_bytes + line.len() as u64 > self.max_file_size_bytes
+        {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.file_size_bytes += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        if self.max_rotated_files == 0 {
+            std::fs::remove_file(&self.path)?;
+        } else {
+            for index in (1..self.max_rotated_files).rev() {
+                let from = rotated_path(&self.path, index);
+                if from.exists() {
+                    std::fs::rename(&from, rotated_path(&self.path, index + 1))?;
+                }
+            }
+            std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        }
+        self.file = open_for_append(&self.path)?;
+        self.file_size_bytes = 0;
+        Ok(())
+    }
+}
+
+fn open_for_append(path: &Path) -> std::io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut file_name = path.as_os_str().to_os_string();
+    file_name.push(format!(".{}", index));
+    PathBuf::from(file_name)
+}
+
+/// Limits the number of requests per operation within fixed one-second windows.
+struct OperationRateLimiter {
+    windows: BTreeMap<AuditedOperation, (Instant, u32)>,
+}
+
+impl OperationRateLimiter {
+    fn new() -> Self {
+        Self {
+            windows: BTreeMap::new(),
+        }
+    }
+
+    fn try_acquire(
+        &mut self,
+        operation: AuditedOperation,
+        max_requests: u32,
+        now: Instant,
+    ) -> bool {
+        let (window_start, count) = self.windows.entry(operation).or_insert((now, 0));
+        if now.duration_since(*window_start) >= RATE_LIMIT_WINDOW {
+            *window_start = now;
+            *count = 0;
+        }
+        if *count >= max_requests {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+}
+
+/// Writes the audit log and enforces the rate limits of the vault server.
+///
+/// Entries are handed over to a writer thread through a bounded queue. If the
+/// queue is full, entries are dropped rather than blocking the caller.
+pub struct VaultAuditor {
+    config: VaultAuditConfig,
+    sender: Option<SyncSender<String>>,
+    writer: Option<JoinHandle<()>>,
+    rate_limiter: Mutex<OperationRateLimiter>,
+}
+
+impl VaultAuditor {
+    pub fn new(config: VaultAuditConfig, logger: ReplicaLogger) -> std::io::Result<Self> {
+        let log_file = RotatingLogFile::open(
+            config.log_file.clone(),
+            config.max_file_size_bytes,
+            config.max_rotated_files,
+        )?;
+        let (sender, receiver) = sync_channel(AUDIT_LOG_QUEUE_CAPACITY);
+        let writer = std::thread::Builder::new()
+            .name("vault-audit-log".to_string())
+            .spawn(move || write_audit_log(log_file, receiver, logger))?;
+        Ok(Self {
+            config,
+            sender: Some(sender),
+            writer: Some(writer),
+            rate_limiter: Mutex::new(OperationRateLimiter::new()),
+        })
+    }
+
+    /// Returns an error if the maximum number of requests per second for
+    /// `operation` was exceeded.
+    pub fn check_rate_limit(&self, operation: AuditedOperation) -> Result<(), String> {
+        let max_requests = self.config.max_requests_per_second(operation);
+        if self
+            .rate_limiter
+            .lock()
+            .try_acquire(operation, max_requests, Instant::now())
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "rate limit of {} requests per second exceeded for operation {}",
+                max_requests,
+                operation.as_str()
+            ))
+        }
+    }
+
+    /// Queues an entry for `request` to be appended to the audit log.
+    ///
+    /// Returns an error if the entry was dropped because the queue is full.
+    pub fn record(
+        &self,
+        request: &AuditedRequest,
+        caller: &VaultCaller,
+        latency: Duration,
+        outcome: AuditOutcome,
+    ) -> Result<(), String> {
+        let line = audit_log_line(SystemTime::now(), request, caller, latency, outcome);
+        let sender = self.sender.as_ref().expect("the audit log sender is set");
+        sender.try_send(line).map_err(|e| match e {
+            TrySendError::Full(_) => "the audit log queue is full".to_string(),
+            TrySendError::Disconnected(_) => "the audit log writer terminated".to_string(),
+        })
+    }
+}
+
+impl Drop for VaultAuditor {
+    /// Writes all queued entries before the auditor is dropped.
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+fn write_audit_log(
+    mut log_file: RotatingLogFile,
+    receiver: Receiver<String>,
+    logger: ReplicaLogger,
+) {
+    for line in receiver {
+        if let Err(e) = log_file.append(&line) {
+            warn!(logger, "Failed to write to the vault audit log: {}", e);
+        }
+    }
+}
@@ -0,0 +1,208 @@
+#![allow(clippy::unwrap_used)]
+
+use super::*;
+use ic_logger::replica_logger::no_op_logger;
+use tempfile::TempDir;
+
+fn caller() -> VaultCaller {
+    VaultCaller {
+        pid: Some(42),
+        uid: Some(1000),
+    }
+}
+
+fn request(operation: AuditedOperation) -> AuditedRequest {
+    AuditedRequest::new(operation, "replica".to_string())
+}
+
+mod audit_log {
+    use super::*;
+
+    #[test]
+    fn should_format_audit_log_line_with_all_fields() {
+        let key_id = KeyId::from([7; 32]);
+        let request = request(AuditedOperation::Sign)
+            .with_key_id(key_id)
+            .with_message(b"message");
+
+        let line = audit_log_line(
+            SystemTime::UNIX_EPOCH + Duration::from_millis(1234),
+            &request,
+            &caller(),
+            Duration::from_micros(56),
+            AuditOutcome::Ok,
+        );
+
+        assert_eq!(
+            line,
+            format!(
+                "timestamp_ms=1234 operation=sign key_id={} message_sha256={} component=replica pid=42 uid=1000 latency_us=56 outcome=ok\n",
+                key_id,
+                hex::encode(Sha256::hash(b"message"))
+            )
+        );
+    }
+
+    #[test]
+    fn should_not_log_message_in_clear() {
+        let request = request(AuditedOperation::TlsSign).with_message(b"secret message");
+
+        let line = audit_log_line(
+            SystemTime::now(),
+            &request,
+            &VaultCaller::unknown(),
+            Duration::ZERO,
+            AuditOutcome::RateLimited,
+        );
+
+        assert!(!line.contains("secret message"));
+        assert!(line.contains("key_id=none"));
+        assert!(
+            line.contains("component=replica pid=none uid=none latency_us=0 outcome=rate_limited")
+        );
+    }
+
+    #[test]
+    fn should_append_entries_to_audit_log_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_file = temp_dir.path().join("audit.log");
+        let auditor =
+            VaultAuditor::new(VaultAuditConfig::new(log_file.clone()), no_op_logger()).unwrap();
+        let request = request(AuditedOperation::MultiSign);
+
+        auditor
+            .record(&request, &caller(), Duration::ZERO, AuditOutcome::Ok)
+            .unwrap();
+        auditor
+            .record(&request, &caller(), Duration::ZERO, AuditOutcome::Err)
+            .unwrap();
+        // dropping the auditor waits until all queued entries are written
+        drop(auditor);
+
+        let content = std::fs::read_to_string(&log_file).unwrap();
+        let lines: Vec<_> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("outcome=ok"));
+        assert!(lines[1].ends_with("outcome=error"));
+    }
+
+    #[test]
+    fn should_rotate_audit_log_file_and_keep_limited_number_of_rotated_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_file = temp_dir.path().join("audit.log");
+        let request = request(AuditedOperation::ThresholdSign);
+        let line_len = audit_log_line(
+            SystemTime::now(),
+            &request,
+            &caller(),
+            Duration::ZERO,
+            AuditOutcome::Ok,
+        )
+        .len() as u64;
+        let auditor = VaultAuditor::new(
+            VaultAuditConfig::new(log_file.clone())
+                .with_max_file_size_bytes(line_len)
+                .with_max_rotated_files(2),
+            no_op_logger(),
+        )
+        .unwrap();
+
+        for _ in 0..4 {
+            auditor
+                .record(&request, &caller(), Duration::ZERO, AuditOutcome::Ok)
+                .unwrap();
+        }
+        drop(auditor);
+
+        assert!(log_file.exists());
+        assert!(rotated_path(&log_file, 1).exists());
+        assert!(rotated_path(&log_file, 2).exists());
+        assert!(!rotated_path(&log_file, 3).exists());
+        for path in [
+            log_file.clone(),
+            rotated_path(&log_file, 1),
+            rotated_path(&log_file, 2),
+        ] {
+            assert_eq!(std::fs::read_to_string(path).unwrap().lines().count(), 1);
+        }
+    }
+}
+
+mod rate_limit {
+    use super::*;
+
+    #[test]
+    fn should_reject_requests_exceeding_limit_within_window() {
+        let mut rate_limiter = OperationRateLimiter::new();
+        let now = Instant::now();
+
+        assert!(rate_limiter.try_acquire(AuditedOperation::Sign, 2, now));
+        assert!(rate_limiter.try_acquire(AuditedOperation::Sign, 2, now));
+        assert!(!rate_limiter.try_acquire(AuditedOperation::Sign, 2, now));
+    }
+
+    #[test]
+    fn should_accept_requests_again_in_next_window() {
+        let mut rate_limiter = OperationRateLimiter::new();
+        let now = Instant::now();
+        assert!(rate_limiter.try_acquire(AuditedOperation::Sign, 1, now));
+        assert!(!rate_limiter.try_acquire(AuditedOperation::Sign, 1, now));
+
+        assert!(rate_limiter.try_acquire(AuditedOperation::Sign, 1, now + RATE_LIMIT_WINDOW));
+    }
+
+    #[test]
+    fn should_limit_each_operation_separately() {
+        let mut rate_limiter = OperationRateLimiter::new();
+        let now = Instant::now();
+        assert!(rate_limiter.try_acquire(AuditedOperation::Sign, 1, now));
+        assert!(!rate_limiter.try_acquire(AuditedOperation::Sign, 1, now));
+
+        assert!(rate_limiter.try_acquire(AuditedOperation::TlsSign, 1, now));
+    }
+
+    #[test]
+    fn should_use_configured_limit_per_operation() {
+        let temp_dir = TempDir::new().unwrap();
+        let auditor = VaultAuditor::new(
+            VaultAuditConfig::new(temp_dir.path().join("audit.log"))
+                .with_max_requests_per_second(AuditedOperation::EcdsaSignShare, 1),
+            no_op_logger(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            auditor.check_rate_limit(AuditedOperation::EcdsaSignShare),
+            Ok(())
+        );
+        assert_eq!(
+            auditor.check_rate_limit(AuditedOperation::EcdsaSignShare),
+            Err(
+                "rate limit of 1 requests per second exceeded for operation ecdsa_sign_share"
+                    .to_string()
+            )
+        );
+        assert_eq!(auditor.check_rate_limit(AuditedOperation::Sign), Ok(()));
+    }
+
+    #[test]
+    fn should_use_configured_default_limit_for_operations_without_own_limit() {
+        let config = VaultAuditConfig::new(PathBuf::from("audit.log"))
+            .with_default_max_requests_per_second(10)
+            .with_max_requests_per_second(AuditedOperation::Sign, 1);
+
+        assert_eq!(config.max_requests_per_second(AuditedOperation::Sign), 1);
+        assert_eq!(
+            config.max_requests_per_second(AuditedOperation::TlsSign),
+            10
+        );
+    }
+
+    #[test]
+    fn should_parse_operation_names() {
+        for operation in AuditedOperation::ALL {
+            assert_eq!(operation.as_str().parse(), Ok(operation));
+        }
+        assert!("unknown".parse::<AuditedOperation>().is_err());
+    }
+}
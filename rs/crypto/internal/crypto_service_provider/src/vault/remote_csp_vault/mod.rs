@@ -31,16 +31,20 @@ use std::path::Path;
 use tokio::net::UnixListener;
 
 const FOUR_GIGA_BYTES: usize = 4 * 1024 * 1024 * 1024;
+mod audit;
 mod codec;
 mod robust_unix_socket;
 mod tarpc_csp_vault_client;
 mod tarpc_csp_vault_server;
 
 use crate::key_id::KeyId;
+use crate::vault::api::CspVault;
 pub use crate::vault::local_csp_vault::ProdLocalCspVault;
 use crate::ExternalPublicKeys;
+pub use audit::{AuditedOperation, VaultAuditConfig};
 use ic_crypto_internal_logmon::metrics::CryptoMetrics;
 use ic_crypto_node_key_validation::ValidNodePublicKeys;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 pub use tarpc_csp_vault_client::{RemoteCspVault, RemoteCspVaultBuilder};
 pub use tarpc_csp_vault_server::{TarpcCspVaultServerImpl, TarpcCspVaultServerImplBuilder};
@@ -52,6 +56,13 @@ use super::api::PublicRandomSeedGeneratorError;
 #[cfg(test)]
 mod tests;
 
+/// Metadata that the client sends along with each audited request.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultRequestMetadata {
+    /// Identifies the client component (e.g., `replica` or `orchestrator`) in the audit log.
+    pub component_id: String,
+}
+
 // The actual `tarpc`-based CspVault trait.
 // As `tarpc` does not support composed traits (i.e. we cannot just write
 // that this trait implements e.g. BasicSignatureCspVault-trait)
@@ -61,6 +72,7 @@ mod tests;
 pub trait TarpcCspVault {
     // Corresponds to `BasicSignatureCspVault.sign()`.
     async fn sign(
+        metadata: VaultRequestMetadata,
         algorithm_id: AlgorithmId,
         message: Vec<u8>,
         key_id: KeyId,
@@ -71,6 +83,7 @@ pub trait TarpcCspVault {
 
     // Corresponds to `MultiSignatureCspVault.multi_sign()`.
     async fn multi_sign(
+        metadata: VaultRequestMetadata,
         algorithm_id: AlgorithmId,
         message: Vec<u8>,
         key_id: KeyId,
@@ -80,8 +93,13 @@ pub trait TarpcCspVault {
     async fn gen_committee_signing_key_pair(
     ) -> Result<(CspPublicKey, CspPop), CspMultiSignatureKeygenError>;
 
+    // Corresponds to `MultiSignatureCspVault.rotate_committee_signing_key_pair()`.
+    async fn rotate_committee_signing_key_pair(
+    ) -> Result<(CspPublicKey, CspPop), CspMultiSignatureKeygenError>;
+
     // Corresponds to `ThresholdSignatureCspVault.threshold_sign()`.
     async fn threshold_sign(
+        metadata: VaultRequestMetadata,
         algorithm_id: AlgorithmId,
         message: Vec<u8>,
         key_id: KeyId,
@@ -151,8 +169,18 @@ pub trait TarpcCspVault {
         not_after: String,
     ) -> Result<TlsPublicKeyCert, CspTlsKeygenError>;
 
+    // Corresponds to `TlsHandshakeCspVault.rotate_tls_key_pair()`.
+    async fn rotate_tls_key_pair(
+        node: NodeId,
+        not_after: String,
+    ) -> Result<TlsPublicKeyCert, CspTlsKeygenError>;
+
     // Corresponds to `TlsHandshakeCspVault.tls_sign()`.
-    async fn tls_sign(message: Vec<u8>, key_id: KeyId) -> Result<CspSignature, CspTlsSignError>;
+    async fn tls_sign(
+        metadata: VaultRequestMetadata,
+        message: Vec<u8>,
+        key_id: KeyId,
+    ) -> Result<CspSignature, CspTlsSignError>;
 
     // Corresponds to `IDkgProtocolCspVault.idkg_create_dealing`
     #[allow(clippy::too_many_arguments)]
@@ -216,6 +244,7 @@ pub trait TarpcCspVault {
     // Corresponds to `ThresholdEcdsaSignerCspVault.ecdsa_sign_share`
     #[allow(clippy::too_many_arguments)]
     async fn ecdsa_sign_share(
+        metadata: VaultRequestMetadata,
         derivation_path: ExtendedDerivationPath,
         hashed_message: Vec<u8>,
         nonce: Randomness,
@@ -230,17 +259,49 @@ pub trait TarpcCspVault {
     async fn new_public_seed() -> Result<Seed, PublicRandomSeedGeneratorError>;
 }
 
+/// Runs a CSP vault server with the key stores in `sks_dir`.
+///
+/// If `secret_key_encryption_key_file` is set, the secret key stores are
+/// encrypted with the key-encryption key read from that file, and existing
+/// unencrypted secret key stores are migrated.
 pub async fn run_csp_vault_server(
     sks_dir: &Path,
+    secret_key_encryption_key_file: Option<&Path>,
+    listener: UnixListener,
+    logger: ReplicaLogger,
+    metrics: CryptoMetrics,
+    audit_config: Option<VaultAuditConfig>,
+) {
+    match secret_key_encryption_key_file {
+        None => {
+            let server_builder = TarpcCspVaultServerImpl::builder(sks_dir);
+            run_server(server_builder, listener, logger, metrics, audit_config).await
+        }
+        Some(key_encryption_key_file) => {
+            let server_builder =
+                TarpcCspVaultServerImplBuilder::new_with_encrypted_secret_key_stores(
+                    sks_dir,
+                    key_encryption_key_file,
+                );
+            run_server(server_builder, listener, logger, metrics, audit_config).await
+        }
+    }
+}
+
+async fn run_server<C: CspVault + 'static>(
+    server_builder: TarpcCspVaultServerImplBuilder<C>,
     listener: UnixListener,
     logger: ReplicaLogger,
     metrics: CryptoMetrics,
+    audit_config: Option<VaultAuditConfig>,
 ) {
-    let server = TarpcCspVaultServerImpl::builder(sks_dir)
+    let mut server_builder = server_builder
         .with_logger(logger)
-        .with_metrics(Arc::new(metrics))
-        .build(listener);
-    server.run().await
+        .with_metrics(Arc::new(metrics));
+    if let Some(audit_config) = audit_config {
+        server_builder = server_builder.with_audit_config(audit_config);
+    }
+    server_builder.build(listener).run().await
 }
 
 pub fn remote_vault_codec_builder() -> Builder {
@@ -12,7 +12,8 @@ use crate::vault::api::{
 };
 use crate::vault::remote_csp_vault::codec::{CspVaultClientObserver, ObservableCodec};
 use crate::vault::remote_csp_vault::{
-    remote_vault_codec_builder, robust_unix_socket, TarpcCspVaultClient, FOUR_GIGA_BYTES,
+    remote_vault_codec_builder, robust_unix_socket, TarpcCspVaultClient, VaultRequestMetadata,
+    FOUR_GIGA_BYTES,
 };
 use crate::{ExternalPublicKeys, TlsHandshakeCspVault};
 use core::future::Future;
@@ -68,6 +69,8 @@ pub struct RemoteCspVault {
     rpc_timeout: Duration,
     // special, long timeout for RPC calls that should not really timeout.
     long_rpc_timeout: Duration,
+    // metadata sent along with each audited request.
+    request_metadata: VaultRequestMetadata,
     tokio_runtime_handle: tokio::runtime::Handle,
     logger: ReplicaLogger,
     metrics: Arc<CryptoMetrics>,
@@ -125,6 +128,7 @@ pub struct RemoteCspVaultBuilder {
     max_frame_length: usize,
     rpc_timeout: Duration,
     long_rpc_timeout: Duration,
+    component_id: String,
     logger: ReplicaLogger,
     metrics: Arc<CryptoMetrics>,
     #[cfg(test)]
@@ -139,6 +143,7 @@ impl RemoteCspVaultBuilder {
             max_frame_length: FOUR_GIGA_BYTES,
             rpc_timeout: DEFAULT_RPC_TIMEOUT,
             long_rpc_timeout: LONG_RPC_TIMEOUT,
+            component_id: default_component_id(),
             logger: no_op_logger(),
             metrics: Arc::new(CryptoMetrics::none()),
             #[cfg(test)]
@@ -176,6 +181,13 @@ impl RemoteCspVaultBuilder {
         self
     }
 
+    /// Sets the component id under which the requests of this client are audited
+    /// by the vault. Defaults to the name of the executable of the current process.
+    pub fn with_component_id<S: Into<String>>(mut self, component_id: S) -> Self {
+        self.component_id = component_id.into();
+        self
+    }
+
     pub fn with_logger(mut self, logger: ReplicaLogger) -> Self {
         self.logger = logger;
         self
@@ -215,6 +227,9 @@ impl RemoteCspVaultBuilder {
             tarpc_csp_client: client,
             rpc_timeout: self.rpc_timeout,
             long_rpc_timeout: self.long_rpc_timeout,
+            request_metadata: VaultRequestMetadata {
+                component_id: self.component_id,
+            },
             tokio_runtime_handle: self.rt_handle,
             logger: self.logger,
             metrics: self.metrics,
@@ -228,6 +243,16 @@ impl RemoteCspVaultBuilder {
     }
 }
 
+fn default_component_id() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
 fn deadline_from_now(timeout: Duration) -> SystemTime {
     SystemTime::now() + timeout
 }
@@ -250,6 +275,7 @@ impl BasicSignatureCspVault for RemoteCspVault {
     ) -> Result<CspSignature, CspBasicSignatureError> {
         self.tokio_block_on(self.tarpc_csp_client.sign(
             context_with_timeout(self.rpc_timeout),
+            self.request_metadata.clone(),
             algorithm_id,
             message.to_vec(),
             key_id,
@@ -283,6 +309,7 @@ impl MultiSignatureCspVault for RemoteCspVault {
     ) -> Result<CspSignature, CspMultiSignatureError> {
         self.tokio_block_on(self.tarpc_csp_client.multi_sign(
             context_with_timeout(self.rpc_timeout),
+            self.request_metadata.clone(),
             algorithm_id,
             message.to_vec(),
             key_id,
@@ -307,6 +334,20 @@ impl MultiSignatureCspVault for RemoteCspVault {
             })
         })
     }
+
+    fn rotate_committee_signing_key_pair(
+        &self,
+    ) -> Result<(CspPublicKey, CspPop), CspMultiSignatureKeygenError> {
+        self.tokio_block_on(
+            self.tarpc_csp_client
+                .rotate_committee_signing_key_pair(context_with_timeout(self.rpc_timeout)),
+        )
+        .unwrap_or_else(|rpc_error: tarpc::client::RpcError| {
+            Err(CspMultiSignatureKeygenError::TransientInternalError {
+                internal_error: rpc_error.to_string(),
+            })
+        })
+    }
 }
 
 impl ThresholdSignatureCspVault for RemoteCspVault {
@@ -318,6 +359,7 @@ impl ThresholdSignatureCspVault for RemoteCspVault {
     ) -> Result<CspSignature, CspThresholdSignError> {
         self.tokio_block_on(self.tarpc_csp_client.threshold_sign(
             context_with_timeout(self.rpc_timeout),
+            self.request_metadata.clone(),
             algorithm_id,
             message.to_vec(),
             key_id,
@@ -538,6 +580,23 @@ impl TlsHandshakeCspVault for RemoteCspVault {
         })
     }
 
+    fn rotate_tls_key_pair(
+        &self,
+        node: NodeId,
+        not_after: &str,
+    ) -> Result<TlsPublicKeyCert, CspTlsKeygenError> {
+        self.tokio_block_on(self.tarpc_csp_client.rotate_tls_key_pair(
+            context_with_timeout(self.rpc_timeout),
+            node,
+            not_after.to_string(),
+        ))
+        .unwrap_or_else(|rpc_error: tarpc::client::RpcError| {
+            Err(CspTlsKeygenError::TransientInternalError {
+                internal_error: rpc_error.to_string(),
+            })
+        })
+    }
+
     fn tls_sign(&self, message: &[u8], key_id: &KeyId) -> Result<CspSignature, CspTlsSignError> {
         // Here we cannot call `block_on` directly but have to wrap it in
         // `block_in_place` because this method here is called via a Rustls
@@ -549,6 +608,7 @@ impl TlsHandshakeCspVault for RemoteCspVault {
         tokio::task::block_in_place(|| {
             self.tokio_block_on(self.tarpc_csp_client.tls_sign(
                 context_with_timeout(self.rpc_timeout),
+                self.request_metadata.clone(),
                 message.to_vec(),
                 *key_id,
             ))
@@ -728,6 +788,7 @@ impl ThresholdEcdsaSignerCspVault for RemoteCspVault {
     ) -> Result<ThresholdEcdsaSigShareInternal, ThresholdEcdsaSignShareError> {
         self.tokio_block_on(self.tarpc_csp_client.ecdsa_sign_share(
             context_with_timeout(self.rpc_timeout),
+            self.request_metadata.clone(),
             derivation_path.clone(),
             hashed_message.to_vec(),
             *nonce,
@@ -7,8 +7,15 @@ use crate::vault::api::{
     CspTlsSignError, PublicRandomSeedGeneratorError, ValidatePksAndSksError,
 };
 use crate::vault::api::{CspPublicKeyStoreError, CspVault};
-use crate::vault::local_csp_vault::{LocalCspVault, ProdLocalCspVault};
-use crate::vault::remote_csp_vault::{remote_vault_codec_builder, TarpcCspVault};
+use crate::vault::local_csp_vault::{
+    EncryptedSecretKeyStoreProdLocalCspVault, LocalCspVault, ProdLocalCspVault,
+};
+use crate::vault::remote_csp_vault::audit::{
+    AuditOutcome, AuditedOperation, AuditedRequest, VaultAuditConfig, VaultAuditor, VaultCaller,
+};
+use crate::vault::remote_csp_vault::{
+    remote_vault_codec_builder, TarpcCspVault, VaultRequestMetadata,
+};
 use crate::vault::remote_csp_vault::{PksAndSksContainsErrors, FOUR_GIGA_BYTES};
 use crate::ExternalPublicKeys;
 use ic_crypto_internal_logmon::metrics::CryptoMetrics;
@@ -42,6 +49,7 @@ use ic_types::{NodeId, NumberOfNodes, Randomness};
 use std::collections::{BTreeMap, BTreeSet};
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Instant;
 use tarpc::server::BaseChannel;
 #[allow(unused_imports)]
 use tarpc::server::Serve;
@@ -56,7 +64,7 @@ pub struct TarpcCspVaultServerImpl<C: CspVault> {
     listener: UnixListener,
     thread_pool: ThreadPool,
     max_frame_length: usize,
-    #[allow(unused)]
+    auditor: Option<Arc<VaultAuditor>>,
     logger: ReplicaLogger,
 }
 
@@ -70,6 +78,9 @@ pub struct TarpcCspVaultServerImpl<C: CspVault> {
 struct TarpcCspVaultServerWorker<C: CspVault> {
     local_csp_vault: Arc<C>,
     thread_pool_handle: ThreadPool,
+    auditor: Option<Arc<VaultAuditor>>,
+    caller: Arc<VaultCaller>,
+    logger: ReplicaLogger,
 }
 
 async fn execute_on_thread_pool<F, T>(thread_pool_handle: ThreadPool, job: F) -> T
@@ -100,6 +111,84 @@ impl<C: CspVault> Clone for TarpcCspVaultServerWorker<C> {
         Self {
             local_csp_vault: Arc::clone(&self.local_csp_vault),
             thread_pool_handle: self.thread_pool_handle.clone(),
+            auditor: self.auditor.clone(),
+            caller: Arc::clone(&self.caller),
+            logger: self.logger.clone(),
+        }
+    }
+}
+
+impl<C: CspVault> TarpcCspVaultServerWorker<C> {
+    /// Returns a request for `operation` that is to be audited, or `None` if
+    /// auditing is disabled, in which case the request details need not be
+    /// computed.
+    fn audited_request(
+        &self,
+        operation: AuditedOperation,
+        metadata: VaultRequestMetadata,
+    ) -> Option<AuditedRequest> {
+        self.auditor
+            .as_ref()
+            .map(|_| AuditedRequest::new(operation, metadata.component_id))
+    }
+
+    /// Executes `job` on the thread pool like `execute_on_thread_pool`, but
+    /// first checks the rate limit of the requested operation, and records the
+    /// request in the audit log, if auditing is enabled.
+    ///
+    /// If the rate limit is exceeded, the job is not executed and the error
+    /// obtained from `rate_limit_error` is returned.
+    async fn execute_audited<F, T, E>(
+        self,
+        request: Option<AuditedRequest>,
+        job: F,
+        rate_limit_error: impl FnOnce(String) -> E,
+    ) -> Result<T, E>
+    where
+        F: FnOnce() -> Result<T, E>,
+        F: Send + 'static,
+        T: Send + 'static,
+        E: Send + 'static,
+    {
+        let (auditor, request) = match (&self.auditor, request) {
+            (Some(auditor), Some(request)) => (Arc::clone(auditor), request),
+            _ => return execute_on_thread_pool(self.thread_pool_handle, job).await,
+        };
+        let start = Instant::now();
+        if let Err(internal_error) = auditor.check_rate_limit(request.operation()) {
+            warn!(
+                self.logger,
+                "Rejecting {} request of component {} ({}): {}",
+                request.operation().as_str(),
+                request.component_id(),
+                self.caller,
+                internal_error
+            );
+            self.record(&auditor, &request, start, AuditOutcome::RateLimited);
+            return Err(rate_limit_error(internal_error));
+        }
+        let result = execute_on_thread_pool(self.thread_pool_handle.clone(), job).await;
+        let outcome = if result.is_ok() {
+            AuditOutcome::Ok
+        } else {
+            AuditOutcome::Err
+        };
+        self.record(&auditor, &request, start, outcome);
+        result
+    }
+
+    fn record(
+        &self,
+        auditor: &VaultAuditor,
+        request: &AuditedRequest,
+        start: Instant,
+        outcome: AuditOutcome,
+    ) {
+        if let Err(e) = auditor.record(request, &self.caller, start.elapsed(), outcome) {
+            warn!(
+                self.logger,
+                "Failed to record request in the vault audit log: {}", e
+            );
         }
     }
 }
@@ -110,13 +199,20 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
     async fn sign(
         self,
         _: context::Context,
+        metadata: VaultRequestMetadata,
         algorithm_id: AlgorithmId,
         msg: Vec<u8>,
         key_id: KeyId,
     ) -> Result<CspSignature, CspBasicSignatureError> {
-        let vault = self.local_csp_vault;
+        let request = self
+            .audited_request(AuditedOperation::Sign, metadata)
+            .map(|request| request.with_key_id(key_id).with_message(&msg));
+        let vault = Arc::clone(&self.local_csp_vault);
         let job = move || vault.sign(algorithm_id, &msg, key_id);
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        self.execute_audited(request, job, |internal_error| {
+            CspBasicSignatureError::InternalError { internal_error }
+        })
+        .await
     }
 
     async fn gen_node_signing_key_pair(
@@ -132,13 +228,20 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
     async fn multi_sign(
         self,
         _: context::Context,
+        metadata: VaultRequestMetadata,
         algorithm_id: AlgorithmId,
         message: Vec<u8>,
         key_id: KeyId,
     ) -> Result<CspSignature, CspMultiSignatureError> {
-        let vault = self.local_csp_vault;
+        let request = self
+            .audited_request(AuditedOperation::MultiSign, metadata)
+            .map(|request| request.with_key_id(key_id).with_message(&message));
+        let vault = Arc::clone(&self.local_csp_vault);
         let job = move || vault.multi_sign(algorithm_id, &message, key_id);
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        self.execute_audited(request, job, |internal_error| {
+            CspMultiSignatureError::InternalError { internal_error }
+        })
+        .await
     }
 
     async fn gen_committee_signing_key_pair(
@@ -150,17 +253,33 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         execute_on_thread_pool(self.thread_pool_handle, job).await
     }
 
+    async fn rotate_committee_signing_key_pair(
+        self,
+        _: context::Context,
+    ) -> Result<(CspPublicKey, CspPop), CspMultiSignatureKeygenError> {
+        let vault = self.local_csp_vault;
+        let job = move || vault.rotate_committee_signing_key_pair();
+        execute_on_thread_pool(self.thread_pool_handle, job).await
+    }
+
     // `ThresholdSignatureCspVault`-methods.
     async fn threshold_sign(
         self,
         _: context::Context,
+        metadata: VaultRequestMetadata,
         algorithm_id: AlgorithmId,
         message: Vec<u8>,
         key_id: KeyId,
     ) -> Result<CspSignature, CspThresholdSignError> {
-        let vault = self.local_csp_vault;
+        let request = self
+            .audited_request(AuditedOperation::ThresholdSign, metadata)
+            .map(|request| request.with_key_id(key_id).with_message(&message));
+        let vault = Arc::clone(&self.local_csp_vault);
         let job = move || vault.threshold_sign(algorithm_id, &message, key_id);
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        self.execute_audited(request, job, |internal_error| {
+            CspThresholdSignError::InternalError { internal_error }
+        })
+        .await
     }
 
     // `NiDkgCspVault`-methods.
@@ -310,15 +429,33 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         execute_on_thread_pool(self.thread_pool_handle, job).await
     }
 
+    async fn rotate_tls_key_pair(
+        self,
+        _: context::Context,
+        node: NodeId,
+        not_after: String,
+    ) -> Result<TlsPublicKeyCert, CspTlsKeygenError> {
+        let vault = self.local_csp_vault;
+        let job = move || vault.rotate_tls_key_pair(node, &not_after);
+        execute_on_thread_pool(self.thread_pool_handle, job).await
+    }
+
     async fn tls_sign(
         self,
         _: context::Context,
+        metadata: VaultRequestMetadata,
         message: Vec<u8>,
         key_id: KeyId,
     ) -> Result<CspSignature, CspTlsSignError> {
-        let vault = self.local_csp_vault;
+        let request = self
+            .audited_request(AuditedOperation::TlsSign, metadata)
+            .map(|request| request.with_key_id(key_id).with_message(&message));
+        let vault = Arc::clone(&self.local_csp_vault);
         let job = move || vault.tls_sign(&message, &key_id);
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        self.execute_audited(request, job, |internal_error| {
+            CspTlsSignError::InternalError { internal_error }
+        })
+        .await
     }
 
     // `IDkgProtocolCspVault`-methods.
@@ -462,6 +599,7 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
     async fn ecdsa_sign_share(
         self,
         _: context::Context,
+        metadata: VaultRequestMetadata,
         derivation_path: ExtendedDerivationPath,
         hashed_message: Vec<u8>,
         nonce: Randomness,
@@ -472,7 +610,10 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
         key_times_lambda: IDkgTranscriptInternal,
         algorithm_id: AlgorithmId,
     ) -> Result<ThresholdEcdsaSigShareInternal, ThresholdEcdsaSignShareError> {
-        let vault = self.local_csp_vault;
+        let request = self
+            .audited_request(AuditedOperation::EcdsaSignShare, metadata)
+            .map(|request| request.with_message(&hashed_message));
+        let vault = Arc::clone(&self.local_csp_vault);
         let job = move || {
             vault.ecdsa_sign_share(
                 &derivation_path,
@@ -486,7 +627,10 @@ impl<C: CspVault + 'static> TarpcCspVault for TarpcCspVaultServerWorker<C> {
                 algorithm_id,
             )
         };
-        execute_on_thread_pool(self.thread_pool_handle, job).await
+        self.execute_audited(request, job, |internal_error| {
+            ThresholdEcdsaSignShareError::InternalError { internal_error }
+        })
+        .await
     }
 
     async fn new_public_seed(
@@ -505,6 +649,7 @@ pub struct TarpcCspVaultServerImplBuilder<C> {
     local_csp_vault_factory: Box<VaultFactory<C>>,
     threadpool_builder: threadpool::Builder,
     max_frame_length: usize,
+    audit_config: Option<VaultAuditConfig>,
     logger: ReplicaLogger,
     metrics: Arc<CryptoMetrics>,
 }
@@ -523,6 +668,25 @@ impl TarpcCspVaultServerImplBuilder<ProdLocalCspVault> {
     }
 }
 
+impl TarpcCspVaultServerImplBuilder<EncryptedSecretKeyStoreProdLocalCspVault> {
+    pub fn new_with_encrypted_secret_key_stores(
+        key_store_dir: &Path,
+        key_encryption_key_file: &Path,
+    ) -> Self {
+        let key_store_path = key_store_dir.to_path_buf();
+        let key_encryption_key_path = key_encryption_key_file.to_path_buf();
+        let local_csp_vault_factory = Box::new(move |logger: &ReplicaLogger, metrics| {
+            Arc::new(LocalCspVault::new_in_dir_with_encrypted_secret_key_stores(
+                &key_store_path,
+                &key_encryption_key_path,
+                metrics,
+                new_logger!(logger),
+            ))
+        });
+        Self::new_internal(local_csp_vault_factory)
+    }
+}
+
 impl<C: 'static + Send + Sync> TarpcCspVaultServerImplBuilder<C> {
     pub fn new_with_local_csp_vault(local_csp_vault: Arc<C>) -> Self {
         let local_csp_vault_factory =
@@ -538,6 +702,7 @@ impl<C> TarpcCspVaultServerImplBuilder<C> {
             // defaults the number of threads to the number of CPUs
             threadpool_builder: threadpool::Builder::new().thread_name("ic-crypto-csp".to_string()),
             max_frame_length: FOUR_GIGA_BYTES,
+            audit_config: None,
             logger: no_op_logger(),
             metrics: Arc::new(CryptoMetrics::none()),
         }
@@ -557,6 +722,12 @@ impl<C> TarpcCspVaultServerImplBuilder<C> {
         self.max_frame_length = max_frame_length;
         self
     }
+
+    /// Enables the audit log and the rate limiting of signing requests.
+    pub fn with_audit_config(mut self, audit_config: VaultAuditConfig) -> Self {
+        self.audit_config = Some(audit_config);
+        self
+    }
 }
 
 impl<C: CspVault> TarpcCspVaultServerImplBuilder<C> {
@@ -564,11 +735,18 @@ impl<C: CspVault> TarpcCspVaultServerImplBuilder<C> {
         info!(&self.logger, "Starting new RPC CSP vault server");
         let local_csp_vault: Arc<C> =
             (self.local_csp_vault_factory)(&self.logger, Arc::clone(&self.metrics));
+        let auditor = self.audit_config.as_ref().map(|audit_config| {
+            Arc::new(
+                VaultAuditor::new(audit_config.clone(), new_logger!(&self.logger))
+                    .unwrap_or_else(|e| panic!("failed to open the vault audit log: {}", e)),
+            )
+        });
         TarpcCspVaultServerImpl {
             local_csp_vault,
             listener,
             thread_pool: self.threadpool_builder.clone().build(),
             max_frame_length: self.max_frame_length,
+            auditor,
             logger: new_logger!(&self.logger),
         }
     }
@@ -607,12 +785,21 @@ impl<C: CspVault + 'static> TarpcCspVaultServerImpl<C> {
             });
             let local_csp_vault = Arc::clone(&self.local_csp_vault);
             let thread_pool_handle = self.thread_pool.clone(); // creates a pool handle similar to Arc
+            let auditor = self.auditor.clone();
+            let caller = match &auditor {
+                Some(_) => VaultCaller::from_unix_stream(&conn),
+                None => VaultCaller::unknown(),
+            };
+            let logger = self.logger.clone();
             tokio::spawn(async move {
                 let framed = codec_builder.new_framed(conn);
                 let transport = serde_transport::new(framed, Bincode::default());
                 let worker = TarpcCspVaultServerWorker {
                     local_csp_vault,
                     thread_pool_handle,
+                    auditor,
+                    caller: Arc::new(caller),
+                    logger,
                 };
                 let channel_executor =
                     BaseChannel::with_defaults(transport).execute(worker.serve());
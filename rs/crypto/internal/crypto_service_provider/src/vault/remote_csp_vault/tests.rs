@@ -767,6 +767,70 @@ mod logging {
     }
 }
 
+mod audit {
+    use super::*;
+    use crate::key_id::KeyId;
+    use crate::vault::api::CspBasicSignatureError;
+    use crate::vault::remote_csp_vault::{AuditedOperation, VaultAuditConfig};
+    use ic_types::crypto::AlgorithmId;
+
+    #[test]
+    fn should_audit_and_rate_limit_sign_requests() {
+        let tokio_rt = new_tokio_runtime();
+        let audit_dir = tempfile::TempDir::new().expect("failed to create temp dir");
+        let audit_log_file = audit_dir.path().join("audit.log");
+        let (socket_path, sks_dir, listener) = setup_listener(tokio_rt.handle());
+        let server =
+            TarpcCspVaultServerImpl::builder_for_test(LocalCspVault::builder().build_into_arc())
+                .with_audit_config(
+                    VaultAuditConfig::new(audit_log_file.clone())
+                        .with_max_requests_per_second(AuditedOperation::Sign, 1),
+                )
+                .build(listener);
+        tokio_rt.handle().spawn(async move {
+            let _move_temp_dir_here_to_ensure_it_is_not_cleaned_up = sks_dir;
+            server.run().await;
+        });
+        let csp_vault = RemoteCspVault::builder(socket_path, tokio_rt.handle().clone())
+            .with_component_id("replica")
+            .build()
+            .expect("Could not create RemoteCspVault");
+        let public_key = csp_vault
+            .gen_node_signing_key_pair()
+            .expect("failed to generate keys");
+        let key_id = KeyId::try_from(&public_key).expect("invalid key ID");
+
+        let first_result = csp_vault.sign(AlgorithmId::Ed25519, b"message", key_id);
+        let second_result = csp_vault.sign(AlgorithmId::Ed25519, b"message", key_id);
+
+        assert!(first_result.is_ok());
+        assert_matches!(second_result,
+            Err(CspBasicSignatureError::InternalError { internal_error })
+            if internal_error.contains("rate limit of 1 requests per second exceeded")
+        );
+        let lines = wait_for_audit_log_lines(&audit_log_file, 2);
+        assert!(lines[0].contains("operation=sign"));
+        assert!(lines[0].contains("component=replica"));
+        assert!(lines[0].contains(&format!("key_id={}", key_id)));
+        assert!(lines[0].ends_with("outcome=ok"));
+        assert!(lines[1].ends_with("outcome=rate_limited"));
+    }
+
+    /// The audit log is written asynchronously, so wait until it contains the expected entries.
+    fn wait_for_audit_log_lines(audit_log_file: &std::path::Path, count: usize) -> Vec<String> {
+        for _ in 0..100 {
+            let audit_log = std::fs::read_to_string(audit_log_file).expect("missing audit log");
+            let lines: Vec<String> = audit_log.lines().map(String::from).collect();
+            if lines.len() >= count {
+                assert_eq!(lines.len(), count);
+                return lines;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        panic!("the audit log does not contain {} entries", count);
+    }
+}
+
 mod pks_and_sks {
     use super::*;
 
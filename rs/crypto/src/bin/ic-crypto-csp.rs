@@ -1,5 +1,6 @@
 use clap::Parser;
 use ic_config::{Config, ConfigSource};
+use ic_crypto_internal_csp::{AuditedOperation, VaultAuditConfig};
 use ic_crypto_internal_logmon::metrics::CryptoMetrics;
 use ic_logger::{info, new_replica_logger_from_config};
 use ic_metrics::MetricsRegistry;
@@ -20,11 +21,48 @@ struct Opts {
     /// Sets the replica configuration file
     #[clap(long = "replica-config-file", parse(from_os_str))]
     config: PathBuf,
+    /// Enables the audit log of signing requests, written to the given file
+    #[clap(long = "audit-log-file", parse(from_os_str))]
+    audit_log_file: Option<PathBuf>,
+    /// Sets the maximum number of signing requests per second for each
+    /// operation without a limit of its own (requires the audit log)
+    #[clap(long = "max-requests-per-second", requires = "audit_log_file")]
+    max_requests_per_second: Option<u32>,
+    /// Sets the maximum number of requests per second for a single operation,
+    /// given as `<operation>=<limit>`, e.g., `sign=100` (requires the audit log)
+    #[clap(
+        long = "operation-max-requests-per-second",
+        parse(try_from_str = parse_operation_limit),
+        requires = "audit_log_file"
+    )]
+    operation_max_requests_per_second: Vec<(AuditedOperation, u32)>,
+}
+
+fn parse_operation_limit(s: &str) -> Result<(AuditedOperation, u32), String> {
+    let (operation, limit) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected <operation>=<limit> but got '{}'", s))?;
+    let limit = limit
+        .parse()
+        .map_err(|e| format!("invalid limit '{}': {}", limit, e))?;
+    Ok((operation.parse()?, limit))
+}
+
+fn audit_config(opts: &Opts) -> Option<VaultAuditConfig> {
+    let mut config = VaultAuditConfig::new(opts.audit_log_file.clone()?);
+    if let Some(max_requests_per_second) = opts.max_requests_per_second {
+        config = config.with_default_max_requests_per_second(max_requests_per_second);
+    }
+    for (operation, max_requests_per_second) in &opts.operation_max_requests_per_second {
+        config = config.with_max_requests_per_second(*operation, *max_requests_per_second);
+    }
+    Some(config)
 }
 
 #[tokio::main]
 async fn main() {
     let opts = Opts::parse();
+    let audit_config = audit_config(&opts);
     let ic_config = get_ic_config(opts.config);
 
     let sks_dir = ic_config.crypto.crypto_root.as_path();
@@ -48,8 +86,15 @@ async fn main() {
     // This way we can capture all the context if a critical error happens.
     abort_on_panic();
     let metrics = CryptoMetrics::new(Some(&MetricsRegistry::global()));
-    ic_crypto_internal_csp::run_csp_vault_server(sks_dir, systemd_socket_listener, logger, metrics)
-        .await;
+    ic_crypto_internal_csp::run_csp_vault_server(
+        sks_dir,
+        ic_config.crypto.secret_key_encryption_key_file.as_deref(),
+        systemd_socket_listener,
+        logger,
+        metrics,
+        audit_config,
+    )
+    .await;
 }
 
 /// Aborts the whole program with a core dump if a single thread panics.
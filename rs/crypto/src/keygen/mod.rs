@@ -3,10 +3,13 @@ mod tests;
 
 use crate::tls::{tls_cert_from_registry_raw, TlsCertFromRegistryError};
 use crate::{key_from_registry, CryptoComponentImpl};
-use ic_crypto_internal_csp::keygen::utils::idkg_dealing_encryption_pk_to_proto;
+use ic_crypto_internal_csp::keygen::utils::{
+    committee_signing_pk_to_proto, idkg_dealing_encryption_pk_to_proto,
+};
 use ic_crypto_internal_csp::types::ExternalPublicKeys;
 use ic_crypto_internal_csp::vault::api::{
-    CspPublicKeyStoreError, NodeKeysErrors, PksAndSksContainsErrors,
+    CspMultiSignatureKeygenError, CspPublicKeyStoreError, CspTlsKeygenError, NodeKeysErrors,
+    PksAndSksContainsErrors,
 };
 use ic_crypto_internal_csp::CryptoServiceProvider;
 use ic_crypto_internal_logmon::metrics::{
@@ -14,7 +17,8 @@ use ic_crypto_internal_logmon::metrics::{
 };
 use ic_interfaces::crypto::{
     CheckKeysWithRegistryError, CurrentNodePublicKeysError, IDkgDealingEncryptionKeyRotationError,
-    IDkgKeyRotationResult, KeyManager, KeyRotationOutcome,
+    IDkgKeyRotationResult, KeyManager, KeyRotationOutcome, NodeSigningKeys,
+    NodeSigningKeysRotationError, NodeSigningKeysRotationResult,
 };
 use ic_logger::{error, info, warn};
 use ic_protobuf::registry::crypto::v1::{PublicKey as PublicKeyProto, X509PublicKeyCert};
@@ -115,8 +119,99 @@ impl<C: CryptoServiceProvider> KeyManager for CryptoComponentImpl<C> {
         self.record_key_rotation_metrics(&key_rotation_result);
         key_rotation_result
     }
+
+    fn rotate_node_signing_keys(
+        &self,
+        registry_version: RegistryVersion,
+        key_rotation_period: Duration,
+    ) -> Result<NodeSigningKeysRotationResult, NodeSigningKeysRotationError> {
+        let local_keys = self.csp.current_node_public_keys().map_err(
+            |CspPublicKeyStoreError::TransientInternalError(internal_error)| {
+                NodeSigningKeysRotationError::TransientInternalError(internal_error)
+            },
+        )?;
+        let local_keys = NodeSigningKeys {
+            committee_signing_public_key: local_keys
+                .committee_signing_public_key
+                .ok_or(NodeSigningKeysRotationError::PublicKeyNotFound)?,
+            tls_certificate: local_keys
+                .tls_certificate
+                .ok_or(NodeSigningKeysRotationError::PublicKeyNotFound)?,
+        };
+        let registry_committee_signing_public_key = self.registry_client.get_crypto_key_for_node(
+            self.node_id,
+            KeyPurpose::CommitteeSigning,
+            registry_version,
+        )?;
+        let registry_tls_certificate = self
+            .registry_client
+            .get_tls_certificate(self.node_id, registry_version)?;
+        let (registry_committee_signing_public_key, registry_tls_certificate) = match (
+            registry_committee_signing_public_key,
+            registry_tls_certificate,
+        ) {
+            (Some(public_key), Some(certificate)) => (public_key, certificate),
+            _ => {
+                error!(
+                    self.logger,
+                    "Committee signing public key or TLS certificate not found in registry",
+                );
+                return Err(NodeSigningKeysRotationError::RegistryKeyBadOrMissing);
+            }
+        };
+
+        // A previous rotation may have been interrupted after rotating only one of the keys, or
+        // the rotated keys may not have been registered yet. In both cases the current local keys
+        // are returned so that they get registered before any further rotation.
+        if !registry_committee_signing_public_key
+            .equal_ignoring_timestamp(&local_keys.committee_signing_public_key)
+            || registry_tls_certificate != local_keys.tls_certificate
+        {
+            info!(
+                self.logger,
+                "Local committee signing key and TLS certificate need registration"
+            );
+            return Ok(NodeSigningKeysRotationResult::KeysNeedRegistration {
+                keys: local_keys,
+                rotated: false,
+            });
+        }
+
+        let too_old = match get_key_timestamp(&registry_committee_signing_public_key) {
+            None => true,
+            Some(timestamp) => self.is_current_key_too_old(timestamp, key_rotation_period),
+        };
+        if !too_old {
+            return Ok(NodeSigningKeysRotationResult::LatestRotationTooRecent);
+        }
+        info!(
+            self.logger,
+            "Committee signing key and TLS certificate too old and need rotating"
+        );
+        let committee_signing_public_key = committee_signing_pk_to_proto(
+            self.csp
+                .rotate_committee_signing_key_pair()
+                .map_err(committee_signing_keygen_error_to_rotation_error)?,
+        );
+        let tls_certificate = self
+            .csp
+            .rotate_tls_key_pair(self.node_id, TLS_CERTIFICATE_NOT_AFTER)
+            .map_err(tls_keygen_error_to_rotation_error)?
+            .to_proto();
+        Ok(NodeSigningKeysRotationResult::KeysNeedRegistration {
+            keys: NodeSigningKeys {
+                committee_signing_public_key,
+                tls_certificate,
+            },
+            rotated: true,
+        })
+    }
 }
 
+/// The notAfter date of rotated TLS certificates, which according to RFC 5280 indicates that the
+/// certificate has no well-defined expiration date.
+const TLS_CERTIFICATE_NOT_AFTER: &str = "99991231235959Z";
+
 // Helpers for implementing `KeyManager`-trait.
 impl<C: CryptoServiceProvider> CryptoComponentImpl<C> {
     fn retrieve_keys_from_registry(&self, registry_version: RegistryVersion) -> RegistryKeysResult {
@@ -518,6 +613,32 @@ fn get_key_timestamp(public_key: &PublicKeyProto) -> Option<Time> {
     })
 }
 
+fn committee_signing_keygen_error_to_rotation_error(
+    error: CspMultiSignatureKeygenError,
+) -> NodeSigningKeysRotationError {
+    match error {
+        CspMultiSignatureKeygenError::TransientInternalError { internal_error } => {
+            NodeSigningKeysRotationError::TransientInternalError(internal_error)
+        }
+        _ => NodeSigningKeysRotationError::KeyGenerationError(format!(
+            "failed to rotate committee signing key: {:?}",
+            error
+        )),
+    }
+}
+
+fn tls_keygen_error_to_rotation_error(error: CspTlsKeygenError) -> NodeSigningKeysRotationError {
+    match error {
+        CspTlsKeygenError::TransientInternalError { internal_error } => {
+            NodeSigningKeysRotationError::TransientInternalError(internal_error)
+        }
+        _ => NodeSigningKeysRotationError::KeyGenerationError(format!(
+            "failed to rotate TLS key material: {:?}",
+            error
+        )),
+    }
+}
+
 fn to_crypto_error(e: TlsCertFromRegistryError) -> CryptoError {
     match e {
         TlsCertFromRegistryError::RegistryError(registry_error) => {
@@ -5,6 +5,7 @@ use assert_matches::assert_matches;
 use ic_base_types::SubnetId;
 use ic_base_types::{NodeId, PrincipalId};
 use ic_crypto_internal_csp::api::CspCreateMEGaKeyError;
+use ic_crypto_internal_csp::types::{CspPop, CspPublicKey};
 use ic_crypto_internal_csp::vault::api::ExternalPublicKeyError;
 use ic_crypto_internal_csp::vault::api::LocalPublicKeyError;
 use ic_crypto_internal_csp::vault::api::NodeKeysError;
@@ -1742,6 +1743,108 @@ mod rotate_idkg_dealing_encryption_keys {
     }
 }
 
+mod rotate_node_signing_keys {
+    use super::*;
+
+    const TWO_WEEKS: Duration = Duration::from_secs(2 * 7 * 24 * 60 * 60);
+
+    #[test]
+    fn should_return_local_keys_for_registration_if_they_differ_from_registry_keys() {
+        let registry_keys = CurrentNodePublicKeys {
+            committee_signing_public_key: Some(PublicKeyProto {
+                key_value: b"previous committee signing key".to_vec(),
+                ..valid_committee_signing_public_key()
+            }),
+            ..valid_current_node_public_keys()
+        };
+        let setup = Setup::builder()
+            .with_registry_public_keys(registry_keys)
+            .with_csp_current_node_public_keys_result(Ok(valid_current_node_public_keys()))
+            .build();
+
+        let result = setup
+            .crypto
+            .rotate_node_signing_keys(setup.registry_client.get_latest_version(), TWO_WEEKS);
+
+        assert_matches!(
+            result,
+            Ok(NodeSigningKeysRotationResult::KeysNeedRegistration {
+                keys: NodeSigningKeys { committee_signing_public_key, tls_certificate },
+                rotated: false,
+            })
+            if committee_signing_public_key == valid_committee_signing_public_key()
+                && tls_certificate == valid_tls_certificate()
+        );
+    }
+
+    #[test]
+    fn should_not_rotate_keys_when_last_rotation_too_recent() {
+        let registry_keys = CurrentNodePublicKeys {
+            committee_signing_public_key: Some(PublicKeyProto {
+                timestamp: Some(0),
+                ..valid_committee_signing_public_key()
+            }),
+            ..valid_current_node_public_keys()
+        };
+        let setup = Setup::builder()
+            .with_registry_public_keys(registry_keys)
+            .with_csp_current_node_public_keys_result(Ok(valid_current_node_public_keys()))
+            .build();
+
+        let result = setup
+            .crypto
+            .rotate_node_signing_keys(setup.registry_client.get_latest_version(), TWO_WEEKS);
+
+        assert_matches!(
+            result,
+            Ok(NodeSigningKeysRotationResult::LatestRotationTooRecent)
+        );
+    }
+
+    #[test]
+    fn should_rotate_keys_when_registry_key_does_not_have_timestamp() {
+        let setup = Setup::builder()
+            .with_registry_public_keys(valid_current_node_public_keys())
+            .with_csp_current_node_public_keys_result(Ok(valid_current_node_public_keys()))
+            .with_csp_rotate_committee_signing_key_pair_result(Err(
+                CspMultiSignatureKeygenError::TransientInternalError {
+                    internal_error: "RPC error".to_string(),
+                },
+            ))
+            .build();
+
+        let result = setup
+            .crypto
+            .rotate_node_signing_keys(setup.registry_client.get_latest_version(), TWO_WEEKS);
+
+        assert_matches!(
+            result,
+            Err(NodeSigningKeysRotationError::TransientInternalError(internal_error))
+            if internal_error == "RPC error"
+        );
+    }
+
+    #[test]
+    fn should_fail_if_tls_certificate_missing_from_registry() {
+        let setup = Setup::builder()
+            .with_registry_public_keys(CurrentNodePublicKeys {
+                tls_certificate: None,
+                ..valid_current_node_public_keys()
+            })
+            .with_csp_current_node_public_keys_result(Ok(valid_current_node_public_keys()))
+            .build();
+
+        let result = setup
+            .crypto
+            .rotate_node_signing_keys(setup.registry_client.get_latest_version(), TWO_WEEKS);
+
+        assert_matches!(
+            result,
+            Err(NodeSigningKeysRotationError::RegistryKeyBadOrMissing)
+        );
+    }
+}
+
 struct Setup {
     metrics_registry: MetricsRegistry,
     crypto: CryptoComponentImpl<MockAllCryptoServiceProvider>,
@@ -1759,6 +1862,7 @@ impl Setup {
             registry_public_keys: None,
             csp_idkg_dealing_encryption_public_keys_count_result: None,
             csp_idkg_gen_dealing_encryption_key_pair_result: None,
+            csp_rotate_committee_signing_key_pair_result: None,
             logger: None,
             ecdsa_subnet_config: None,
         }
@@ -1777,6 +1881,8 @@ struct SetupBuilder {
         Option<Result<usize, CspPublicKeyStoreError>>,
     csp_idkg_gen_dealing_encryption_key_pair_result:
         Option<Result<MEGaPublicKey, CspCreateMEGaKeyError>>,
+    csp_rotate_committee_signing_key_pair_result:
+        Option<Result<(CspPublicKey, CspPop), CspMultiSignatureKeygenError>>,
     logger: Option<ReplicaLogger>,
     ecdsa_subnet_config: Option<EcdsaSubnetConfig>,
 }
@@ -1844,6 +1950,18 @@ impl SetupBuilder {
         self
     }
 
+    fn with_csp_rotate_committee_signing_key_pair_result(
+        mut self,
+        rotate_committee_signing_key_pair_result: Result<
+            (CspPublicKey, CspPop),
+            CspMultiSignatureKeygenError,
+        >,
+    ) -> Self {
+        self.csp_rotate_committee_signing_key_pair_result =
+            Some(rotate_committee_signing_key_pair_result);
+        self
+    }
+
     fn with_logger(mut self, in_memory_logger: &InMemoryReplicaLogger) -> Self {
         self.logger = Some(ReplicaLogger::from(in_memory_logger));
         self
@@ -1895,6 +2013,14 @@ impl SetupBuilder {
                 .times(1)
                 .return_const(csp_idkg_gen_dealing_encryption_key_pair_result);
         }
+        if let Some(csp_rotate_committee_signing_key_pair_result) =
+            self.csp_rotate_committee_signing_key_pair_result
+        {
+            mock_csp
+                .expect_rotate_committee_signing_key_pair()
+                .times(1)
+                .return_const(csp_rotate_committee_signing_key_pair_result);
+        }
 
         let registry_client: Arc<dyn RegistryClient> = match self.registry_client_override {
             None => {
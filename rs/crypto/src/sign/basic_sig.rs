@@ -96,6 +96,43 @@ impl BasicSigVerifierInternal {
         )?;
         Ok(())
     }
+
+    /// Verifies basic signatures of several signers on the same message.
+    ///
+    /// All signatures are first verified in a single batch. Only if that fails
+    /// are they verified one by one, to identify the invalid ones.
+    pub fn verify_basic_sigs_batch<S: CspSigner + CspSigVerifier, H: Signable>(
+        csp_signer: &S,
+        registry: &dyn RegistryClient,
+        signatures: &BTreeMap<NodeId, BasicSigOf<H>>,
+        message: &H,
+        registry_version: RegistryVersion,
+    ) -> BTreeMap<NodeId, CryptoResult<()>> {
+        if signatures.len() > 1 {
+            let batch = BasicSignatureBatch {
+                signatures_map: signatures.clone(),
+            };
+            if Self::verify_basic_sig_batch(csp_signer, registry, &batch, message, registry_version)
+                .is_ok()
+            {
+                return signatures.keys().map(|signer| (*signer, Ok(()))).collect();
+            }
+        }
+        signatures
+            .iter()
+            .map(|(signer, signature)| {
+                let result = Self::verify_basic_sig(
+                    csp_signer,
+                    registry,
+                    signature,
+                    message,
+                    *signer,
+                    registry_version,
+                );
+                (*signer, result)
+            })
+            .collect()
+    }
 }
 
 pub struct BasicSignerInternal {}
@@ -397,6 +397,49 @@ mod verify_sig_batch {
         );
     }
 
+    #[test]
+    fn should_identify_invalid_signature_in_batch() {
+        let registry_data = Arc::new(ProtoRegistryDataProvider::new());
+        let registry_client =
+            Arc::new(FakeRegistryClient::new(Arc::clone(&registry_data) as Arc<_>));
+
+        let crypto_1 = TempCryptoComponent::builder()
+            .with_keys_in_registry_version(NodeKeysToGenerate::only_node_signing_key(), REG_V2)
+            .with_registry_client_and_data(
+                Arc::clone(&registry_client) as Arc<_>,
+                Arc::clone(&registry_data) as Arc<_>,
+            )
+            .with_node_id(NODE_1)
+            .build();
+        let crypto_2 = TempCryptoComponent::builder()
+            .with_keys_in_registry_version(NodeKeysToGenerate::only_node_signing_key(), REG_V2)
+            .with_registry_client_and_data(
+                Arc::clone(&registry_client) as Arc<_>,
+                Arc::clone(&registry_data) as Arc<_>,
+            )
+            .with_node_id(NODE_2)
+            .build();
+        registry_client.reload();
+
+        let msg = SignableMock::new(b"Hello World!".to_vec());
+        let other_msg = SignableMock::new(b"World Hello! ".to_vec());
+        let mut signatures = BTreeMap::new();
+        signatures.insert(NODE_1, crypto_1.sign_basic(&msg, NODE_1, REG_V2).unwrap());
+        signatures.insert(
+            NODE_2,
+            crypto_2.sign_basic(&other_msg, NODE_2, REG_V2).unwrap(),
+        );
+
+        let results = crypto_1.verify_basic_sigs_batch(&signatures, &msg, REG_V2);
+
+        assert_eq!(results.len(), 2);
+        assert_matches!(results[&NODE_1], Ok(()));
+        assert_matches!(
+            results[&NODE_2],
+            Err(CryptoError::SignatureVerification { .. })
+        );
+    }
+
     #[test]
     fn should_not_verify_an_empty_batch() {
         let (_, pk, msg, _) = basic_sig::testvec(ED25519_STABILITY_1);
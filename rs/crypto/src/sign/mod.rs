@@ -143,6 +143,48 @@ impl<C: CryptoServiceProvider, H: Signable> BasicSigVerifier<H> for CryptoCompon
         result
     }
 
+    fn verify_basic_sigs_batch(
+        &self,
+        signatures: &BTreeMap<NodeId, BasicSigOf<H>>,
+        message: &H,
+        registry_version: RegistryVersion,
+    ) -> BTreeMap<NodeId, CryptoResult<()>> {
+        let log_id = get_log_id(&self.logger, module_path!());
+        let logger = new_logger!(&self.logger;
+            crypto.log_id => log_id,
+            crypto.trait_name => "BasicSigVerifier",
+            crypto.method_name => "verify_basic_sigs_batch",
+        );
+        debug!(logger;
+            crypto.description => "start",
+            crypto.registry_version => registry_version.get(),
+            crypto.signed_bytes => format!("0x{}", hex::encode(message.as_signed_bytes())),
+            crypto.signature => format!("{:?}", signatures),
+        );
+        let start_time = self.metrics.now();
+        let results = BasicSigVerifierInternal::verify_basic_sigs_batch(
+            &self.csp,
+            self.registry_client.as_ref(),
+            signatures,
+            message,
+            registry_version,
+        );
+        let result = first_error_or_ok(&results);
+        self.metrics.observe_duration_seconds(
+            MetricsDomain::BasicSignature,
+            MetricsScope::Full,
+            "verify_basic_sigs_batch",
+            MetricsResult::from(&result),
+            start_time,
+        );
+        debug!(logger;
+            crypto.description => "end",
+            crypto.is_ok => result.is_ok(),
+            crypto.error => log_err(result.as_ref().err()),
+        );
+        results
+    }
+
     fn combine_basic_sig(
         &self,
         signatures: BTreeMap<NodeId, &BasicSigOf<H>>,
@@ -351,6 +393,48 @@ impl<C: CryptoServiceProvider, H: Signable> MultiSigVerifier<H> for CryptoCompon
         result
     }
 
+    fn verify_multi_sig_individuals_batch(
+        &self,
+        signatures: &BTreeMap<NodeId, IndividualMultiSigOf<H>>,
+        message: &H,
+        registry_version: RegistryVersion,
+    ) -> BTreeMap<NodeId, CryptoResult<()>> {
+        let log_id = get_log_id(&self.logger, module_path!());
+        let logger = new_logger!(&self.logger;
+            crypto.log_id => log_id,
+            crypto.trait_name => "MultiSigner",
+            crypto.method_name => "verify_multi_sig_individuals_batch",
+        );
+        debug!(logger;
+            crypto.description => "start",
+            crypto.registry_version => registry_version.get(),
+            crypto.signed_bytes => format!("0x{}", hex::encode(message.as_signed_bytes())),
+            crypto.signature => format!("{:?}", signatures),
+        );
+        let start_time = self.metrics.now();
+        let results = MultiSigVerifierInternal::verify_multi_sig_individuals_batch(
+            &self.csp,
+            self.registry_client.as_ref(),
+            signatures,
+            message,
+            registry_version,
+        );
+        let result = first_error_or_ok(&results);
+        self.metrics.observe_duration_seconds(
+            MetricsDomain::MultiSignature,
+            MetricsScope::Full,
+            "verify_multi_sig_individuals_batch",
+            MetricsResult::from(&result),
+            start_time,
+        );
+        debug!(logger;
+            crypto.description => "end",
+            crypto.is_ok => result.is_ok(),
+            crypto.error => log_err(result.as_ref().err()),
+        );
+        results
+    }
+
     /// Combines a non-empty collection of individual signatures into a combined
     /// signature.
     fn combine_multi_sig_individuals(
@@ -846,6 +930,14 @@ impl<C: CryptoServiceProvider> ThresholdEcdsaSigVerifier for CryptoComponentImpl
     }
 }
 
+/// Summarizes per-signer verification results as the first error, if any.
+fn first_error_or_ok(results: &BTreeMap<NodeId, CryptoResult<()>>) -> CryptoResult<()> {
+    results
+        .values()
+        .find_map(|result| result.clone().err())
+        .map_or(Ok(()), Err)
+}
+
 fn log_err<T: fmt::Display>(error_option: Option<&T>) -> String {
     if let Some(error) = error_option {
         return format!("{}", error);
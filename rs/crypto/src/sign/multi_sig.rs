@@ -1,5 +1,5 @@
 use super::*;
-use ic_crypto_internal_csp::api::CspSigner;
+use ic_crypto_internal_csp::api::{CspSigVerifier, CspSigner};
 use ic_crypto_internal_csp::key_id::KeyId;
 
 #[cfg(test)]
@@ -25,6 +25,47 @@ impl MultiSigVerifierInternal {
         csp_signer.verify(&csp_sig, &message_bytes, algorithm_id, csp_pubkey)
     }
 
+    /// Verifies individual signatures of several signers on the same message.
+    ///
+    /// All signatures are first verified in a single batch. Only if that fails
+    /// are they verified one by one, to identify the invalid ones.
+    pub fn verify_multi_sig_individuals_batch<S: CspSigner + CspSigVerifier, H: Signable>(
+        csp_signer: &S,
+        registry: &dyn RegistryClient,
+        signatures: &BTreeMap<NodeId, IndividualMultiSigOf<H>>,
+        message: &H,
+        registry_version: RegistryVersion,
+    ) -> BTreeMap<NodeId, CryptoResult<()>> {
+        if signatures.len() > 1 {
+            let batch_result = node_sigs_to_pubkey_sig_pairs(
+                registry,
+                signatures,
+                CommitteeSigning,
+                registry_version,
+            )
+            .and_then(|(pubkey_sig_pairs, algorithm)| {
+                csp_signer.verify_batch(&pubkey_sig_pairs, &message.as_signed_bytes(), algorithm)
+            });
+            if batch_result.is_ok() {
+                return signatures.keys().map(|signer| (*signer, Ok(()))).collect();
+            }
+        }
+        signatures
+            .iter()
+            .map(|(signer, signature)| {
+                let result = Self::verify_multi_sig_individual(
+                    csp_signer,
+                    registry,
+                    signature,
+                    message,
+                    *signer,
+                    registry_version,
+                );
+                (*signer, result)
+            })
+            .collect()
+    }
+
     /// Combines a non-empty collection of individual signatures into a combined
     /// signature.
     pub fn combine_multi_sig_individuals<S: CspSigner, H: Signable>(
@@ -42,7 +83,7 @@ impl MultiSigVerifierInternal {
 
         let (pubkey_sig_pairs, algorithm) = node_sigs_to_pubkey_sig_pairs(
             registry,
-            signatures,
+            &signatures,
             CommitteeSigning,
             registry_version,
         )?;
@@ -145,7 +186,7 @@ fn node_ids_to_pubkeys(
 /// - one of the given signatures is not valid
 fn node_sigs_to_pubkey_sig_pairs<H>(
     registry: &dyn RegistryClient,
-    node_sigs: BTreeMap<NodeId, IndividualMultiSigOf<H>>,
+    node_sigs: &BTreeMap<NodeId, IndividualMultiSigOf<H>>,
     key_purpose: KeyPurpose,
     registry_version: RegistryVersion,
 ) -> CryptoResult<(Vec<(CspPublicKey, CspSignature)>, AlgorithmId)>
@@ -96,6 +96,57 @@ mod test_multi_sig_verification {
         assert_matches!(result, Ok(()));
     }
 
+    #[test]
+    fn should_verify_multi_sig_individuals_in_batch_and_identify_invalid_ones() {
+        let registry_data = Arc::new(ProtoRegistryDataProvider::new());
+        let registry_client =
+            Arc::new(FakeRegistryClient::new(Arc::clone(&registry_data) as Arc<_>));
+
+        let crypto_1 = TempCryptoComponent::builder()
+            .with_keys_in_registry_version(NodeKeysToGenerate::only_committee_signing_key(), REG_V2)
+            .with_registry_client_and_data(
+                Arc::clone(&registry_client) as Arc<_>,
+                Arc::clone(&registry_data) as Arc<_>,
+            )
+            .with_node_id(NODE_1)
+            .build();
+        let crypto_2 = TempCryptoComponent::builder()
+            .with_keys_in_registry_version(NodeKeysToGenerate::only_committee_signing_key(), REG_V2)
+            .with_registry_client_and_data(
+                Arc::clone(&registry_client) as Arc<_>,
+                Arc::clone(&registry_data) as Arc<_>,
+            )
+            .with_node_id(NODE_2)
+            .build();
+        registry_client.reload();
+
+        let msg = SignableMock::new(b"Hello World!".to_vec());
+        let other_msg = SignableMock::new(b"World Hello!".to_vec());
+        let sig_node1_on_msg = crypto_1.sign_multi(&msg, NODE_1, REG_V2).unwrap();
+        let sig_node2_on_msg = crypto_2.sign_multi(&msg, NODE_2, REG_V2).unwrap();
+        let sig_node2_on_other_msg = crypto_2.sign_multi(&other_msg, NODE_2, REG_V2).unwrap();
+
+        let valid_signatures = vec![
+            (NODE_1, sig_node1_on_msg.clone()),
+            (NODE_2, sig_node2_on_msg),
+        ]
+        .into_iter()
+        .collect();
+        let results = crypto_1.verify_multi_sig_individuals_batch(&valid_signatures, &msg, REG_V2);
+        assert_eq!(results.len(), 2);
+        assert!(results.values().all(Result::is_ok));
+
+        let signatures = vec![(NODE_1, sig_node1_on_msg), (NODE_2, sig_node2_on_other_msg)]
+            .into_iter()
+            .collect();
+        let results = crypto_1.verify_multi_sig_individuals_batch(&signatures, &msg, REG_V2);
+        assert_matches!(results[&NODE_1], Ok(()));
+        assert_matches!(
+            results[&NODE_2],
+            Err(CryptoError::SignatureVerification { .. })
+        );
+    }
+
     #[test]
     fn should_not_combine_zero_individual_sigs() {
         let (_, pk_1, _, _, _) = multi_bls12_381::testvec(STABILITY_1);
@@ -5,7 +5,9 @@ use crate::tls::rustls::{certified_key, RustlsTlsStream};
 use crate::tls::{tls_cert_from_registry, TlsCertFromRegistryError};
 use ic_crypto_internal_csp::api::CspTlsHandshakeSignerProvider;
 use ic_crypto_internal_csp::key_id::KeyId;
-use ic_crypto_tls_interfaces::{SomeOrAllNodes, TlsClientHandshakeError, TlsStream};
+use ic_crypto_tls_interfaces::{
+    SomeOrAllNodes, TlsClientHandshakeError, TlsConfigError, TlsPublicKeyCert, TlsStream,
+};
 use ic_interfaces_registry::RegistryClient;
 use ic_types::{NodeId, RegistryVersion};
 use std::sync::Arc;
@@ -43,7 +45,53 @@ pub async fn perform_tls_client_handshake<P: CspTlsHandshakeSignerProvider>(
         registry_client,
         registry_version,
     );
-    let config = ClientConfig::builder()
+    let config = client_config_with_tls13_and_aes_ciphersuites_and_ed25519_signing_key(
+        server_cert_verifier,
+        self_tls_cert,
+        ed25519_signing_key,
+    );
+
+    connect(tcp_stream, config).await
+}
+
+/// Returns the client configuration used by `perform_tls_client_handshake`,
+/// for transports that perform the handshake themselves.
+pub fn client_config<P: CspTlsHandshakeSignerProvider>(
+    signer_provider: &P,
+    self_node_id: NodeId,
+    registry_client: Arc<dyn RegistryClient>,
+    server: NodeId,
+    registry_version: RegistryVersion,
+) -> Result<ClientConfig, TlsConfigError> {
+    let self_tls_cert =
+        tls_cert_from_registry(registry_client.as_ref(), self_node_id, registry_version)?;
+    let self_tls_cert_key_id = KeyId::try_from(&self_tls_cert).map_err(|error| {
+        TlsConfigError::MalformedSelfCertificate {
+            internal_error: format!("Cannot instantiate KeyId: {:?}", error),
+        }
+    })?;
+    let ed25519_signing_key =
+        CspServerEd25519SigningKey::new(self_tls_cert_key_id, signer_provider.handshake_signer());
+    let server_cert_verifier = NodeServerCertVerifier::new(
+        SomeOrAllNodes::new_with_single_node(server),
+        registry_client,
+        registry_version,
+    );
+    Ok(
+        client_config_with_tls13_and_aes_ciphersuites_and_ed25519_signing_key(
+            server_cert_verifier,
+            self_tls_cert,
+            ed25519_signing_key,
+        ),
+    )
+}
+
+fn client_config_with_tls13_and_aes_ciphersuites_and_ed25519_signing_key(
+    server_cert_verifier: NodeServerCertVerifier,
+    self_tls_cert: TlsPublicKeyCert,
+    ed25519_signing_key: CspServerEd25519SigningKey,
+) -> ClientConfig {
+    ClientConfig::builder()
         .with_cipher_suites(&[TLS13_AES_256_GCM_SHA384, TLS13_AES_128_GCM_SHA256])
         .with_safe_default_kx_groups()
         .with_protocol_versions(&[&TLS13])
@@ -52,9 +100,7 @@ pub async fn perform_tls_client_handshake<P: CspTlsHandshakeSignerProvider>(
         .with_client_cert_resolver(static_cert_resolver(
             certified_key(self_tls_cert, ed25519_signing_key),
             SignatureScheme::ED25519,
-        ));
-
-    connect(tcp_stream, config).await
+        ))
 }
 
 fn static_cert_resolver(key: CertifiedKey, scheme: SignatureScheme) -> Arc<dyn ResolvesClientCert> {
@@ -1,4 +1,4 @@
-use crate::tls::{node_id_from_cert_subject_common_name, tls_cert_from_registry};
+use crate::tls::tls_cert_from_registry;
 use ic_crypto_tls_cert_validation::ValidTlsCertificate;
 use ic_crypto_tls_interfaces::{SomeOrAllNodes, TlsPublicKeyCert};
 use ic_interfaces_registry::RegistryClient;
@@ -23,8 +23,10 @@ mod tests;
 ///   contained in `allowed_nodes` (as passed to `new`).
 /// * The end entity certificate equals the node's certificate fetched from the
 ///   `registry_client` at version `registry_version` for the `NodeId` parsed
-///   from the end entity certificate. (The `registry_client` and
-///   `registry_version` are passed to `new`.)
+///   from the end entity certificate, or the node's previous certificate if
+///   the node rotated its certificate and the previous one is not yet
+///   retired. (The `registry_client` and `registry_version` are passed to
+///   `new`.)
 ///
 /// If any of these conditions does not hold, a `TLSError` is returned.
 pub struct NodeServerCertVerifier {
@@ -154,7 +156,18 @@ fn verify_node_cert(
     ensure_node_id_in_allowed_nodes(end_entity_node_id, allowed_nodes)?;
     let node_cert_from_registry =
         node_cert_from_registry(end_entity_node_id, registry_client, registry_version)?;
-    ensure_certificates_equal(end_entity, end_entity_node_id, node_cert_from_registry)?;
+    // While a rotated certificate is in transition, peers may still present
+    // the node's previous certificate.
+    if node_cert_from_registry != end_entity {
+        let previous_node_cert = previous_node_cert_from_registry(
+            end_entity_node_id,
+            registry_client,
+            registry_version,
+        )?;
+        if previous_node_cert.as_ref() != Some(&end_entity) {
+            ensure_certificates_equal(end_entity, end_entity_node_id, node_cert_from_registry)?;
+        }
+    }
     // It's important to do the validity check after checking equality to the
     // registry cert because the cert validation uses a different parser
     // (`x509_parser` as opposed to OpenSSL that is used above) and it is safer
@@ -181,7 +194,7 @@ fn cert_from_der(cert_der: Vec<u8>) -> Result<TlsPublicKeyCert, TLSError> {
 }
 
 fn node_id_from_subject_cn(cert: &TlsPublicKeyCert) -> Result<NodeId, TLSError> {
-    node_id_from_cert_subject_common_name(cert).map_err(|e| {
+    cert.node_id_from_subject_common_name().map_err(|e| {
         TLSError::General(format!(
             "The presented certificate subject CN could not be parsed as node ID: {:?}",
             e
@@ -215,6 +228,32 @@ fn node_cert_from_registry(
     })
 }
 
+fn previous_node_cert_from_registry(
+    node_id: NodeId,
+    registry_client: &dyn RegistryClient,
+    registry_version: RegistryVersion,
+) -> Result<Option<TlsPublicKeyCert>, TLSError> {
+    use ic_registry_client_helpers::crypto::CryptoRegistry;
+    let maybe_previous_cert = registry_client
+        .get_previous_tls_certificate(node_id, registry_version)
+        .map_err(|e| {
+            TLSError::General(format!(
+                "Failed to retrieve previous TLS certificate for node ID {} from the registry at registry version {}: {:?}",
+                node_id, registry_version, e
+            ))
+        })?;
+    maybe_previous_cert
+        .map(|cert| {
+            TlsPublicKeyCert::try_from(cert).map_err(|e| {
+                TLSError::General(format!(
+                    "The previous TLS certificate of node ID {} in the registry is malformed: {:?}",
+                    node_id, e
+                ))
+            })
+        })
+        .transpose()
+}
+
 fn ensure_certificates_equal(
     end_entity_cert: TlsPublicKeyCert,
     node_id: NodeId,
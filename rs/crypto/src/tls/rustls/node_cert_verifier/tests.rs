@@ -121,6 +121,29 @@ mod client_cert_verifier_tests {
         );
     }
 
+    #[test]
+    fn should_return_ok_if_cert_matches_previous_registry_cert() {
+        let previous_node_1_cert = CertWithPrivateKey::builder()
+            .cn(NODE_1.to_string())
+            .build_ed25519();
+        let rotated_node_1_cert = CertWithPrivateKey::builder()
+            .cn(NODE_1.to_string())
+            .build_ed25519();
+        let registry = TlsRegistry::new();
+        let verifier = verifier_with_allowed_nodes(btreeset! {NODE_1, NODE_2}, &registry);
+        registry
+            .add_cert(NODE_1, x509_public_key_cert(&rotated_node_1_cert.x509()))
+            .add_previous_cert(NODE_1, x509_public_key_cert(&previous_node_1_cert.x509()))
+            .update();
+
+        for cert in [&previous_node_1_cert, &rotated_node_1_cert] {
+            let result =
+                verifier.verify_client_cert(&Certificate(cert.cert_der()), &[], UNIX_EPOCH);
+
+            assert!(result.is_ok());
+        }
+    }
+
     #[test]
     fn should_return_error_if_presented_cert_node_id_cannot_be_parsed() {
         let cert_with_no_node_id_as_cn = CertWithPrivateKey::builder()
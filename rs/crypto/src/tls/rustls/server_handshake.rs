@@ -2,13 +2,12 @@ use crate::tls::rustls::cert_resolver::StaticCertResolver;
 use crate::tls::rustls::csp_server_signing_key::CspServerEd25519SigningKey;
 use crate::tls::rustls::node_cert_verifier::NodeClientCertVerifier;
 use crate::tls::rustls::{certified_key, RustlsTlsStream};
-use crate::tls::{
-    node_id_from_cert_subject_common_name, tls_cert_from_registry, TlsCertFromRegistryError,
-};
+use crate::tls::{tls_cert_from_registry, TlsCertFromRegistryError};
 use ic_crypto_internal_csp::api::CspTlsHandshakeSignerProvider;
 use ic_crypto_internal_csp::key_id::KeyId;
 use ic_crypto_tls_interfaces::{
-    AllowedClients, AuthenticatedPeer, TlsPublicKeyCert, TlsServerHandshakeError, TlsStream,
+    AllowedClients, AuthenticatedPeer, SomeOrAllNodes, TlsConfigError, TlsPublicKeyCert,
+    TlsServerHandshakeError, TlsStream,
 };
 use ic_interfaces_registry::RegistryClient;
 use ic_types::{NodeId, RegistryVersion};
@@ -56,7 +55,7 @@ pub async fn perform_tls_server_handshake<P: CspTlsHandshakeSignerProvider>(
     let rustls_stream = accept_connection(tcp_stream, config).await?;
 
     let client_cert_from_handshake = single_client_cert_from_handshake(&rustls_stream)?;
-    let authenticated_peer = node_id_from_cert_subject_common_name(&client_cert_from_handshake)?;
+    let authenticated_peer = client_cert_from_handshake.node_id_from_subject_common_name()?;
     let tls_stream = RustlsTlsStream::new(tokio_rustls::TlsStream::from(rustls_stream));
 
     Ok((
@@ -93,6 +92,38 @@ pub async fn perform_tls_server_handshake_without_client_auth<P: CspTlsHandshake
     )))
 }
 
+/// Returns the server configuration used by `perform_tls_server_handshake`,
+/// for transports that perform the handshake themselves.
+pub fn server_config<P: CspTlsHandshakeSignerProvider>(
+    signer_provider: &P,
+    self_node_id: NodeId,
+    registry_client: Arc<dyn RegistryClient>,
+    allowed_clients: SomeOrAllNodes,
+    registry_version: RegistryVersion,
+) -> Result<ServerConfig, TlsConfigError> {
+    let self_tls_cert =
+        tls_cert_from_registry(registry_client.as_ref(), self_node_id, registry_version)?;
+    let self_tls_cert_key_id = KeyId::try_from(&self_tls_cert).map_err(|error| {
+        TlsConfigError::MalformedSelfCertificate {
+            internal_error: format!("Cannot instantiate KeyId: {:?}", error),
+        }
+    })?;
+    let client_cert_verifier = NodeClientCertVerifier::new_with_mandatory_client_auth(
+        allowed_clients,
+        registry_client,
+        registry_version,
+    );
+    let ed25519_signing_key =
+        CspServerEd25519SigningKey::new(self_tls_cert_key_id, signer_provider.handshake_signer());
+    Ok(
+        server_config_with_tls13_and_aes_ciphersuites_and_ed25519_signing_key(
+            Arc::new(client_cert_verifier),
+            self_tls_cert,
+            ed25519_signing_key,
+        ),
+    )
+}
+
 fn server_config_with_tls13_and_aes_ciphersuites_and_ed25519_signing_key(
     client_cert_verifier: Arc<dyn ClientCertVerifier>,
     self_tls_cert: TlsPublicKeyCert,
@@ -12,9 +12,10 @@ use ic_crypto_node_key_generation::{
 use ic_crypto_temp_crypto_vault::{
     RemoteVaultEnvironment, TempCspVaultServer, TokioRuntimeOrHandle,
 };
+use ic_crypto_tls_interfaces::rustls::{ClientConfig, ServerConfig};
 use ic_crypto_tls_interfaces::{
-    AllowedClients, AuthenticatedPeer, TlsClientHandshakeError, TlsHandshake, TlsPublicKeyCert,
-    TlsServerHandshakeError, TlsStream,
+    AllowedClients, AuthenticatedPeer, SomeOrAllNodes, TlsClientHandshakeError, TlsConfig,
+    TlsConfigError, TlsHandshake, TlsPublicKeyCert, TlsServerHandshakeError, TlsStream,
 };
 use ic_crypto_utils_basic_sig::conversions::derive_node_id;
 use ic_crypto_utils_time::CurrentSystemTimeSource;
@@ -22,8 +23,9 @@ use ic_interfaces::crypto::{
     BasicSigVerifier, BasicSigVerifierByPublicKey, BasicSigner, CanisterSigVerifier,
     CheckKeysWithRegistryError, CurrentNodePublicKeysError, IDkgDealingEncryptionKeyRotationError,
     IDkgKeyRotationResult, IDkgProtocol, KeyManager, LoadTranscriptResult, MultiSigVerifier,
-    MultiSigner, NiDkgAlgorithm, ThresholdEcdsaSigVerifier, ThresholdEcdsaSigner,
-    ThresholdSigVerifier, ThresholdSigVerifierByPublicKey, ThresholdSigner,
+    MultiSigner, NiDkgAlgorithm, NodeSigningKeysRotationError, NodeSigningKeysRotationResult,
+    ThresholdEcdsaSigVerifier, ThresholdEcdsaSigner, ThresholdSigVerifier,
+    ThresholdSigVerifierByPublicKey, ThresholdSigner,
 };
 use ic_interfaces::time_source::TimeSource;
 use ic_interfaces_registry::RegistryClient;
@@ -763,6 +765,26 @@ impl<C: CryptoServiceProvider + Send + Sync> TlsHandshake for TempCryptoComponen
     }
 }
 
+impl<C: CryptoServiceProvider + Send + Sync> TlsConfig for TempCryptoComponentGeneric<C> {
+    fn server_config(
+        &self,
+        allowed_clients: SomeOrAllNodes,
+        registry_version: RegistryVersion,
+    ) -> Result<ServerConfig, TlsConfigError> {
+        self.crypto_component
+            .server_config(allowed_clients, registry_version)
+    }
+
+    fn client_config(
+        &self,
+        server: NodeId,
+        registry_version: RegistryVersion,
+    ) -> Result<ClientConfig, TlsConfigError> {
+        self.crypto_component
+            .client_config(server, registry_version)
+    }
+}
+
 impl<C: CryptoServiceProvider, T: Signable> BasicSigVerifier<T> for TempCryptoComponentGeneric<C> {
     fn verify_basic_sig(
         &self,
@@ -793,6 +815,16 @@ impl<C: CryptoServiceProvider, T: Signable> BasicSigVerifier<T> for TempCryptoCo
         self.crypto_component
             .verify_basic_sig_batch(signature, message, registry_version)
     }
+
+    fn verify_basic_sigs_batch(
+        &self,
+        signatures: &BTreeMap<NodeId, BasicSigOf<T>>,
+        message: &T,
+        registry_version: RegistryVersion,
+    ) -> BTreeMap<NodeId, CryptoResult<()>> {
+        self.crypto_component
+            .verify_basic_sigs_batch(signatures, message, registry_version)
+    }
 }
 
 impl<C: CryptoServiceProvider, T: Signable> MultiSigVerifier<T> for TempCryptoComponentGeneric<C> {
@@ -811,6 +843,19 @@ impl<C: CryptoServiceProvider, T: Signable> MultiSigVerifier<T> for TempCryptoCo
         )
     }
 
+    fn verify_multi_sig_individuals_batch(
+        &self,
+        signatures: &BTreeMap<NodeId, IndividualMultiSigOf<T>>,
+        message: &T,
+        registry_version: RegistryVersion,
+    ) -> BTreeMap<NodeId, CryptoResult<()>> {
+        self.crypto_component.verify_multi_sig_individuals_batch(
+            signatures,
+            message,
+            registry_version,
+        )
+    }
+
     fn combine_multi_sig_individuals(
         &self,
         signatures: BTreeMap<NodeId, IndividualMultiSigOf<T>>,
@@ -912,6 +957,15 @@ impl<C: CryptoServiceProvider> KeyManager for TempCryptoComponentGeneric<C> {
         self.crypto_component
             .rotate_idkg_dealing_encryption_keys(registry_version)
     }
+
+    fn rotate_node_signing_keys(
+        &self,
+        registry_version: RegistryVersion,
+        key_rotation_period: Duration,
+    ) -> Result<NodeSigningKeysRotationResult, NodeSigningKeysRotationError> {
+        self.crypto_component
+            .rotate_node_signing_keys(registry_version, key_rotation_period)
+    }
 }
 
 impl<C: CryptoServiceProvider> NiDkgAlgorithm for TempCryptoComponentGeneric<C> {
@@ -110,6 +110,16 @@ mock! {
             node_id: NodeId,
             not_after: &str,
         ) -> Result<TlsPublicKeyCert, CspTlsKeygenError>;
+
+        fn rotate_committee_signing_key_pair(
+            &self,
+        ) -> Result<(CspPublicKey, CspPop), CspMultiSignatureKeygenError>;
+
+        fn rotate_tls_key_pair(
+            &self,
+            node_id: NodeId,
+            not_after: &str,
+        ) -> Result<TlsPublicKeyCert, CspTlsKeygenError>;
     }
 
     pub trait ThresholdSignatureCspClient {
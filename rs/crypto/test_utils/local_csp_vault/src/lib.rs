@@ -72,6 +72,10 @@ mock! {
         fn gen_committee_signing_key_pair(
             &self,
         ) -> Result<(CspPublicKey, CspPop), CspMultiSignatureKeygenError>;
+
+        fn rotate_committee_signing_key_pair(
+            &self,
+        ) -> Result<(CspPublicKey, CspPop), CspMultiSignatureKeygenError>;
     }
 
     pub trait ThresholdSignatureCspVault {
@@ -214,6 +218,12 @@ mock! {
             not_after: &str,
         ) -> Result<TlsPublicKeyCert, CspTlsKeygenError>;
 
+        fn rotate_tls_key_pair(
+            &self,
+            node: NodeId,
+            not_after: &str,
+        ) -> Result<TlsPublicKeyCert, CspTlsKeygenError>;
+
         fn tls_sign(&self, message: &[u8], key_id: &KeyId) -> Result<CspSignature, CspTlsSignError>;
     }
 
@@ -1,6 +1,6 @@
 use ic_protobuf::registry::crypto::v1::X509PublicKeyCert;
 use ic_registry_client_fake::FakeRegistryClient;
-use ic_registry_keys::make_crypto_tls_cert_key;
+use ic_registry_keys::{make_crypto_tls_cert_key, make_previous_crypto_tls_cert_key};
 use ic_registry_proto_data_provider::ProtoRegistryDataProvider;
 use ic_types::{NodeId, RegistryVersion};
 use openssl::x509::X509;
@@ -30,6 +30,17 @@ impl TlsRegistry {
         self
     }
 
+    pub fn add_previous_cert(self, node_id: NodeId, cert: X509PublicKeyCert) -> TlsRegistry {
+        self.data_provider
+            .add(
+                &make_previous_crypto_tls_cert_key(node_id),
+                REG_V1,
+                Some(cert),
+            )
+            .expect("failed to add previous TLS cert to registry");
+        self
+    }
+
     pub fn with_cert_from_x509(self, node_id: NodeId, cert: X509) -> TlsRegistry {
         let cert = X509PublicKeyCert {
             certificate_der: cert.to_der().expect("could not DER encode certificate"),
@@ -6,17 +6,22 @@ use async_trait::async_trait;
 use core::fmt;
 use ic_protobuf::registry::crypto::v1::X509PublicKeyCert;
 use ic_types::registry::RegistryClientError;
-use ic_types::{NodeId, RegistryVersion};
+use ic_types::{NodeId, PrincipalId, RegistryVersion};
 use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
 use openssl::x509::X509;
 use serde::{Deserialize, Deserializer, Serialize};
 use std::cmp::Ordering;
 use std::collections::BTreeSet;
 use std::fmt::{Display, Formatter};
 use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::TcpStream;
 
+/// The rustls version used by the configurations returned by [`TlsConfig`].
+pub use tokio_rustls::rustls;
+
 #[cfg(test)]
 mod tests;
 
@@ -69,6 +74,33 @@ impl TlsPublicKeyCert {
         &self.cert
     }
 
+    /// Returns the node ID encoded in the certificate's (single) subject
+    /// common name entry.
+    ///
+    /// Note that this does not authenticate the node: the caller must ensure
+    /// that the certificate was obtained from the registry or presented by a
+    /// peer during a TLS handshake that verified it against the registry.
+    pub fn node_id_from_subject_common_name(
+        &self,
+    ) -> Result<NodeId, MalformedPeerCertificateError> {
+        let mut common_name_entries = self.cert.subject_name().entries_by_nid(Nid::COMMONNAME);
+        let common_name_entry = common_name_entries
+            .next()
+            .ok_or_else(|| MalformedPeerCertificateError::new("Missing X509NameEntryRef"))?;
+        if common_name_entries.next().is_some() {
+            return Err(MalformedPeerCertificateError::new(
+                "Too many X509NameEntryRefs",
+            ));
+        }
+        let common_name = common_name_entry.data().as_utf8().map_err(|e| {
+            MalformedPeerCertificateError::new(&format!("ASN1 to UTF-8 conversion error: {}", e))
+        })?;
+        let principal_id = PrincipalId::from_str(common_name.as_ref()).map_err(|e| {
+            MalformedPeerCertificateError::new(&format!("Principal ID parse error: {}", e))
+        })?;
+        Ok(NodeId::from(principal_id))
+    }
+
     /// Returns the certificate in protobuf format
     pub fn to_proto(&self) -> X509PublicKeyCert {
         X509PublicKeyCert {
@@ -398,6 +430,74 @@ pub trait TlsHandshake {
     ) -> Result<Box<dyn TlsStream>, TlsClientHandshakeError>;
 }
 
+/// Implementors provide rustls configurations for transports that perform
+/// the TLS handshake themselves, such as QUIC, where the handshake is part of
+/// the transport protocol and cannot be run on top of a `TcpStream`.
+///
+/// The returned configurations are equivalent to the ones used by
+/// [`TlsHandshake`]: TLS 1.3 only, ed25519 signatures, AES-GCM cipher suites,
+/// and mandatory authentication of the peer against its certificate in the
+/// registry. The node's secret key never leaves the secret key store; the
+/// configurations sign via the crypto service provider.
+pub trait TlsConfig {
+    /// Returns a server configuration that only accepts handshakes from the
+    /// given `allowed_clients`.
+    ///
+    /// After a successful handshake, the authenticated client is the node
+    /// whose ID is contained in the subject common name of the (single)
+    /// certificate the client presented, see
+    /// [`TlsPublicKeyCert::node_id_from_subject_common_name`].
+    ///
+    /// # Errors
+    /// * TlsConfigError::RegistryError if the registry cannot be accessed.
+    /// * TlsConfigError::CertificateNotInRegistry if the node's own
+    ///   certificate is not found in the registry.
+    /// * TlsConfigError::MalformedSelfCertificate if the node's own
+    ///   certificate is malformed.
+    fn server_config(
+        &self,
+        allowed_clients: SomeOrAllNodes,
+        registry_version: RegistryVersion,
+    ) -> Result<rustls::ServerConfig, TlsConfigError>;
+
+    /// Returns a client configuration that only completes handshakes with the
+    /// given `server`.
+    ///
+    /// # Errors
+    /// * TlsConfigError::RegistryError if the registry cannot be accessed.
+    /// * TlsConfigError::CertificateNotInRegistry if the node's own
+    ///   certificate is not found in the registry.
+    /// * TlsConfigError::MalformedSelfCertificate if the node's own
+    ///   certificate is malformed.
+    fn client_config(
+        &self,
+        server: NodeId,
+        registry_version: RegistryVersion,
+    ) -> Result<rustls::ClientConfig, TlsConfigError>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Errors from creating a TLS configuration. Please refer to the `TlsConfig`
+/// methods for detailed error variant descriptions.
+pub enum TlsConfigError {
+    RegistryError(RegistryClientError),
+    CertificateNotInRegistry {
+        node_id: NodeId,
+        registry_version: RegistryVersion,
+    },
+    MalformedSelfCertificate {
+        internal_error: String,
+    },
+}
+
+impl Display for TlsConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for TlsConfigError {}
+
 #[derive(Clone, Debug)]
 /// A list of allowed TLS peers, which can be `All` to allow any node to connect.
 pub struct AllowedClients {
@@ -61,6 +61,59 @@ impl std::fmt::Display for CyclesAccountManagerError {
     }
 }
 
+/// Describes the saturation of a subnet resource such as memory.
+///
+/// The resource is considered saturated once its usage goes above the
+/// threshold. The saturation grows linearly between the threshold and the
+/// capacity of the resource.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResourceSaturation {
+    usage: u64,
+    threshold: u64,
+    capacity: u64,
+}
+
+impl ResourceSaturation {
+    pub fn new(usage: u64, threshold: u64, capacity: u64) -> Self {
+        let threshold = threshold.min(capacity);
+        let usage = usage.min(capacity);
+        Self {
+            usage,
+            threshold,
+            capacity,
+        }
+    }
+
+    /// Returns the usage of the resource above the threshold.
+    pub fn usage_above_threshold(&self) -> u64 {
+        self.usage.saturating_sub(self.threshold)
+    }
+
+    /// Returns a new saturation with the usage increased by `allocation`.
+    pub fn add(&self, allocation: u64) -> Self {
+        Self::new(
+            self.usage.saturating_add(allocation),
+            self.threshold,
+            self.capacity,
+        )
+    }
+
+    /// Scales `value` proportionally to the average saturation between the
+    /// current usage and the usage after allocating `allocation` units.
+    /// Returns zero while the resulting usage stays below the threshold, and
+    /// `value` once the resource is fully saturated.
+    pub fn reservation_factor(&self, allocation: u64, value: u64) -> u64 {
+        let max_usage_above_threshold = self.capacity.saturating_sub(self.threshold);
+        if max_usage_above_threshold == 0 {
+            return 0;
+        }
+        let before = self.usage_above_threshold() as u128;
+        let after = self.add(allocation).usage_above_threshold() as u128;
+        let average = (before + after) / 2;
+        (value as u128 * average / max_usage_above_threshold as u128) as u64
+    }
+}
+
 /// Handles any operation related to cycles accounting, such as charging (due to
 /// using system resources) or refunding unused cycles.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -505,6 +558,9 @@ impl CyclesAccountManager {
     /// stable memory (among other things). This will be revised in the future
     /// to take into account charging for dirty/read pages by the canister.
     ///
+    /// The cycles reserved for future storage payments are used first and
+    /// only the remainder is charged from the main balance.
+    ///
     /// # Errors
     ///
     /// Returns a `CanisterOutOfCyclesError` if there's
@@ -517,14 +573,38 @@ impl CyclesAccountManager {
         subnet_size: usize,
     ) -> Result<(), CanisterOutOfCyclesError> {
         let cycles_amount = self.memory_cost(bytes, duration, subnet_size);
+        let covered_by_reserved = system_state.reserved_balance().min(cycles_amount);
 
         // Can charge all the way to the empty account (zero cycles)
         self.consume_with_threshold(
             system_state,
-            cycles_amount,
+            cycles_amount - covered_by_reserved,
             Cycles::zero(),
             CyclesUseCase::Memory,
-        )
+        )?;
+        system_state.consume_reserved_cycles(covered_by_reserved, CyclesUseCase::Memory);
+        Ok(())
+    }
+
+    /// Returns the amount of cycles that need to be reserved for future
+    /// storage payments when a canister allocates `allocated_bytes` of new
+    /// memory on a subnet with the given memory saturation.
+    ///
+    /// The reservation covers the storage cost of the newly allocated bytes
+    /// for a period that grows linearly from zero (when the subnet memory
+    /// usage is at the threshold) to `max_storage_reservation_period` (when
+    /// the usage is at the capacity).
+    pub fn storage_reservation_cycles(
+        &self,
+        allocated_bytes: NumBytes,
+        subnet_memory_saturation: &ResourceSaturation,
+        subnet_size: usize,
+    ) -> Cycles {
+        let period = subnet_memory_saturation.reservation_factor(
+            allocated_bytes.get(),
+            self.config.max_storage_reservation_period.as_secs(),
+        );
+        self.memory_cost(allocated_bytes, Duration::from_secs(period), subnet_size)
     }
 
     /// The cost of using `bytes` worth of memory.
@@ -1,7 +1,7 @@
 use ic_base_types::NumSeconds;
 use ic_config::subnet_config::SubnetConfigs;
 use ic_constants::SMALL_APP_SUBNET_MAX_SIZE;
-use ic_cycles_account_manager::IngressInductionCost;
+use ic_cycles_account_manager::{IngressInductionCost, ResourceSaturation};
 use ic_ic00_types::{CanisterIdRecord, Payload, IC_00};
 use ic_interfaces::execution_environment::CanisterOutOfCyclesError;
 use ic_logger::replica_logger::no_op_logger;
@@ -765,3 +765,69 @@ fn consume_cycles_updates_consumed_cycles() {
         NominalCycles::from(1_000_000)
     );
 }
+
+#[test]
+fn storage_reservation_cycles_scale_with_subnet_memory_saturation() {
+    let cycles_account_manager = CyclesAccountManagerBuilder::new()
+        .with_subnet_type(SubnetType::Application)
+        .build();
+    let allocated_bytes = NumBytes::from(1 << 30);
+    let subnet_size = SMALL_APP_SUBNET_MAX_SIZE;
+
+    // No reservation while the subnet memory usage stays below the threshold.
+    let below_threshold = ResourceSaturation::new(0, 100 << 30, 200 << 30);
+    assert_eq!(
+        cycles_account_manager.storage_reservation_cycles(
+            allocated_bytes,
+            &below_threshold,
+            subnet_size
+        ),
+        Cycles::zero()
+    );
+
+    // The reservation grows as the subnet memory usage approaches the capacity.
+    let half_saturated = ResourceSaturation::new(150 << 30, 100 << 30, 200 << 30);
+    let saturated = ResourceSaturation::new(200 << 30, 100 << 30, 200 << 30);
+    let half_saturated_reservation = cycles_account_manager.storage_reservation_cycles(
+        allocated_bytes,
+        &half_saturated,
+        subnet_size,
+    );
+    let saturated_reservation =
+        cycles_account_manager.storage_reservation_cycles(allocated_bytes, &saturated, subnet_size);
+    assert!(Cycles::zero() < half_saturated_reservation);
+    assert!(half_saturated_reservation < saturated_reservation);
+}
+
+#[test]
+fn charge_for_memory_uses_reserved_cycles_first() {
+    let subnet_size = SMALL_APP_SUBNET_MAX_SIZE;
+    let mut system_state = SystemStateBuilder::new().build();
+    let cycles_account_manager = CyclesAccountManagerBuilder::new()
+        .with_subnet_type(SubnetType::Application)
+        .build();
+    let bytes = NumBytes::from(1 << 30);
+    let duration = Duration::from_secs(1);
+    let fee = cycles_account_manager.memory_cost(bytes, duration, subnet_size);
+
+    system_state
+        .reserve_cycles(fee * 2_u64, INITIAL_CYCLES)
+        .unwrap();
+    let balance_before = system_state.balance();
+    cycles_account_manager
+        .charge_for_memory(&mut system_state, bytes, duration, subnet_size)
+        .unwrap();
+    assert_eq!(system_state.balance(), balance_before);
+    assert_eq!(system_state.reserved_balance(), fee);
+
+    // The reserved balance covers only one more charge, the next one is
+    // charged from the main balance.
+    cycles_account_manager
+        .charge_for_memory(&mut system_state, bytes, duration, subnet_size)
+        .unwrap();
+    cycles_account_manager
+        .charge_for_memory(&mut system_state, bytes, duration, subnet_size)
+        .unwrap();
+    assert_eq!(system_state.reserved_balance(), Cycles::zero());
+    assert_eq!(system_state.balance(), balance_before - fee);
+}
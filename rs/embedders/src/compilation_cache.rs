@@ -1,44 +1,93 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
 
 use crate::SerializedModule;
+use ic_config::embedders::Config as EmbeddersConfig;
+use ic_crypto_sha::Sha256;
 use ic_interfaces::execution_environment::HypervisorResult;
-use ic_types::NumBytes;
+use ic_types::{replica_version::REPLICA_BINARY_HASH, NumBytes, ReplicaVersion};
 use ic_utils_lru_cache::LruCache;
 use ic_wasm_types::{CanisterModule, WasmHash};
 
 /// Stores the serialized modules of wasm code that has already been compiled so
 /// that it can be used again without recompiling.
+///
+/// Optionally, successfully compiled modules are also persisted on disk, so
+/// that they survive replica restarts. The on-disk tier is consulted whenever
+/// the in-memory tier misses.
 pub struct CompilationCache {
     cache: Mutex<LruCache<WasmHash, HypervisorResult<Arc<SerializedModule>>>>,
+    disk_cache: Option<DiskCache>,
 }
 
 impl CompilationCache {
     pub fn new(capacity: NumBytes) -> Self {
         Self {
             cache: Mutex::new(LruCache::new(capacity)),
+            disk_cache: None,
         }
     }
 
+    /// Creates a compilation cache with an on-disk tier of at most
+    /// `disk_capacity` bytes stored in `dir`.
+    ///
+    /// Entries are only valid for the replica version and configuration they
+    /// were compiled with, so entries written by a different version or
+    /// configuration are removed. Other contents of `dir` are left untouched.
+    pub fn new_with_disk_cache(
+        capacity: NumBytes,
+        dir: &Path,
+        disk_capacity: NumBytes,
+        embedder_config: &EmbeddersConfig,
+    ) -> io::Result<Self> {
+        Ok(Self {
+            cache: Mutex::new(LruCache::new(capacity)),
+            disk_cache: Some(DiskCache::open(dir, disk_capacity, embedder_config)?),
+        })
+    }
+
     pub fn insert(
         &self,
         canister_module: &CanisterModule,
         serialized_module: HypervisorResult<Arc<SerializedModule>>,
     ) {
+        let wasm_hash = WasmHash::from(canister_module);
+        if let (Some(disk_cache), Ok(serialized_module)) = (&self.disk_cache, &serialized_module) {
+            // The on-disk tier is best-effort: a failed write only means that
+            // the module will be recompiled after a restart.
+            let _ = disk_cache.insert(&wasm_hash, serialized_module);
+        }
         self.cache
             .lock()
             .unwrap()
-            .push(WasmHash::from(canister_module), serialized_module);
+            .push(wasm_hash, serialized_module);
     }
 
     pub fn get(
         &self,
         canister_module: &CanisterModule,
     ) -> Option<HypervisorResult<Arc<SerializedModule>>> {
+        let wasm_hash = WasmHash::from(canister_module);
+        let cached = self
+            .cache
+            .lock()
+            .unwrap()
+            .get(&wasm_hash)
+            .map(|o| o.as_ref().map(Arc::clone).map_err(|e| e.clone()));
+        if cached.is_some() {
+            return cached;
+        }
+        let serialized_module = Arc::new(self.disk_cache.as_ref()?.get(&wasm_hash)?);
         self.cache
             .lock()
             .unwrap()
-            .get(&WasmHash::from(canister_module))
-            .map(|o| o.as_ref().map(Arc::clone).map_err(|e| e.clone()))
+            .push(wasm_hash, Ok(Arc::clone(&serialized_module)));
+        Some(Ok(serialized_module))
     }
 
     #[doc(hidden)]
@@ -46,3 +95,328 @@ impl CompilationCache {
         self.cache.lock().unwrap().clear()
     }
 }
+
+/// Every file of the on-disk tier starts with this header.
+const DISK_CACHE_MAGIC: &[u8; 8] = b"ICWASMC1";
+
+/// The length of the checksum that follows the header.
+const DISK_CACHE_CHECKSUM_LEN: usize = 32;
+
+/// Suffix of files that are being written and are not complete yet.
+const DISK_CACHE_TMP_SUFFIX: &str = "tmp";
+
+/// Prefix of the subdirectories created by the on-disk tier. Only entries with
+/// this prefix are ever removed from the cache root.
+const DISK_CACHE_DIR_PREFIX: &str = "wasm-compilation-cache-";
+
+/// The on-disk tier of the compilation cache.
+///
+/// Entries are stored in a subdirectory named after a fingerprint of the
+/// replica version and embedder configuration, one file per Wasm hash. Each file
+/// contains a header, the SHA-256 checksum of the payload, and the payload
+/// itself, which is the `bincode` encoding of the `SerializedModule`. Files
+/// with an invalid header or checksum are treated as misses and removed.
+struct DiskCache {
+    dir: PathBuf,
+    capacity: NumBytes,
+    state: Mutex<DiskCacheState>,
+}
+
+#[derive(Default)]
+struct DiskCacheState {
+    /// The sizes of the entries in the order they have been written. The
+    /// oldest entries are evicted first once the capacity is exceeded.
+    entries: VecDeque<(WasmHash, u64)>,
+    total_size: u64,
+}
+
+impl DiskCache {
+    fn open(
+        root: &Path,
+        capacity: NumBytes,
+        embedder_config: &EmbeddersConfig,
+    ) -> io::Result<Self> {
+        let dir_name = format!("{}{}", DISK_CACHE_DIR_PREFIX, fingerprint(embedder_config));
+        fs::create_dir_all(root)?;
+
+        // Remove entries of other replica versions or configurations: they
+        // can never be used again and would only take up the disk capacity.
+        // Anything that was not created by this cache is left alone.
+        for dir_entry in fs::read_dir(root)? {
+            let dir_entry = dir_entry?;
+            let file_name = dir_entry.file_name();
+            let file_name = match file_name.to_str() {
+                Some(file_name) => file_name,
+                None => continue,
+            };
+            if file_name.starts_with(DISK_CACHE_DIR_PREFIX)
+                && file_name != dir_name
+                && dir_entry.file_type()?.is_dir()
+            {
+                fs::remove_dir_all(dir_entry.path())?;
+            }
+        }
+
+        let dir = root.join(dir_name);
+        fs::create_dir_all(&dir)?;
+
+        let mut files = vec![];
+        for dir_entry in fs::read_dir(&dir)? {
+            let dir_entry = dir_entry?;
+            let path = dir_entry.path();
+            let metadata = dir_entry.metadata()?;
+            match parse_file_name(&path) {
+                Some(wasm_hash) if metadata.is_file() => {
+                    files.push((metadata.modified()?, wasm_hash, metadata.len()))
+                }
+                // Leftovers of interrupted writes and unknown files.
+                _ => fs::remove_file(path)?,
+            }
+        }
+        files.sort_by_key(|(modified, _, _)| *modified);
+
+        let mut state = DiskCacheState::default();
+        for (_, wasm_hash, size) in files {
+            state.total_size += size;
+            state.entries.push_back((wasm_hash, size));
+        }
+
+        let disk_cache = Self {
+            dir,
+            capacity,
+            state: Mutex::new(state),
+        };
+        disk_cache.evict(&mut disk_cache.state.lock().unwrap());
+        Ok(disk_cache)
+    }
+
+    fn path(&self, wasm_hash: &WasmHash) -> PathBuf {
+        self.dir.join(to_hex(&wasm_hash.to_vec()))
+    }
+
+    fn get(&self, wasm_hash: &WasmHash) -> Option<SerializedModule> {
+        let path = self.path(wasm_hash);
+        let contents = fs::read(&path).ok()?;
+        match decode(&contents) {
+            Some(serialized_module) => Some(serialized_module),
+            None => {
+                // The file is corrupted, so it is removed to be overwritten
+                // after the next compilation.
+                self.remove(wasm_hash);
+                None
+            }
+        }
+    }
+
+    fn insert(&self, wasm_hash: &WasmHash, serialized_module: &SerializedModule) -> io::Result<()> {
+        let payload = bincode::serialize(serialized_module)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let size = (DISK_CACHE_MAGIC.len() + DISK_CACHE_CHECKSUM_LEN + payload.len()) as u64;
+        if size > self.capacity.get() {
+            return Ok(());
+        }
+
+        let path = self.path(wasm_hash);
+        let tmp_path = path.with_extension(DISK_CACHE_TMP_SUFFIX);
+        {
+            let mut file = fs::File::create(&tmp_path)?;
+            file.write_all(DISK_CACHE_MAGIC)?;
+            file.write_all(&Sha256::hash(&payload))?;
+            file.write_all(&payload)?;
+            file.sync_all()?;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        // The file is renamed only after the write completed, so that a crash
+        // never leaves a partially written entry behind.
+        fs::rename(&tmp_path, &path)?;
+        if let Some(index) = state.entries.iter().position(|(h, _)| h == wasm_hash) {
+            let (_, old_size) = state.entries.remove(index).unwrap();
+            state.total_size -= old_size;
+        }
+        state.entries.push_back((wasm_hash.clone(), size));
+        state.total_size += size;
+        self.evict(&mut state);
+        Ok(())
+    }
+
+    fn remove(&self, wasm_hash: &WasmHash) {
+        let mut state = self.state.lock().unwrap();
+        let _ = fs::remove_file(self.path(wasm_hash));
+        if let Some(index) = state.entries.iter().position(|(h, _)| h == wasm_hash) {
+            let (_, size) = state.entries.remove(index).unwrap();
+            state.total_size -= size;
+        }
+    }
+
+    /// Removes the oldest entries until the cache fits into its capacity.
+    fn evict(&self, state: &mut DiskCacheState) {
+        while state.total_size > self.capacity.get() {
+            match state.entries.pop_front() {
+                Some((wasm_hash, size)) => {
+                    let _ = fs::remove_file(self.path(&wasm_hash));
+                    state.total_size -= size;
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+/// Returns a fingerprint of everything that affects the result of compiling
+/// and instrumenting a Wasm module: the replica version (and the hash of the
+/// replica binary when it is known), the Wasmtime version, and the embedder
+/// configuration. The replica version changes with every release, so any
+/// change to the instrumentation or the compiler invalidates the cache.
+fn fingerprint(embedder_config: &EmbeddersConfig) -> String {
+    let mut hasher = Sha256::new();
+    let replica_version = ReplicaVersion::default();
+    hasher.write(&(replica_version.as_ref().len() as u64).to_le_bytes());
+    hasher.write(replica_version.as_ref().as_bytes());
+    let binary_hash = REPLICA_BINARY_HASH.get().map(String::as_str).unwrap_or("");
+    hasher.write(&(binary_hash.len() as u64).to_le_bytes());
+    hasher.write(binary_hash.as_bytes());
+    hasher.write(wasmtime_environ::VERSION.as_bytes());
+    hasher.write(
+        &bincode::serialize(embedder_config).expect("Failed to serialize the embedder config"),
+    );
+    to_hex(&hasher.finish())
+}
+
+fn decode(contents: &[u8]) -> Option<SerializedModule> {
+    let contents = contents.strip_prefix(DISK_CACHE_MAGIC.as_slice())?;
+    if contents.len() < DISK_CACHE_CHECKSUM_LEN {
+        return None;
+    }
+    let (checksum, payload) = contents.split_at(DISK_CACHE_CHECKSUM_LEN);
+    if checksum != Sha256::hash(payload).as_slice() {
+        return None;
+    }
+    bincode::deserialize(payload).ok()
+}
+
+fn parse_file_name(path: &Path) -> Option<WasmHash> {
+    if path.extension().is_some() {
+        return None;
+    }
+    let name = path.file_name()?.to_str()?;
+    let bytes = from_hex(name)?;
+    let bytes: [u8; 32] = bytes.try_into().ok()?;
+    Some(WasmHash::from(bytes))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{wasm_utils::compile, WasmtimeEmbedder};
+    use ic_logger::replica_logger::no_op_logger;
+    use ic_wasm_types::BinaryEncodedWasm;
+
+    fn compile_module(wat: &str) -> (CanisterModule, Arc<SerializedModule>) {
+        let wasm = wat::parse_str(wat).unwrap();
+        let embedder = WasmtimeEmbedder::new(EmbeddersConfig::default(), no_op_logger());
+        let (_, serialized_module) = compile(&embedder, &BinaryEncodedWasm::new(wasm.clone()))
+            .1
+            .unwrap();
+        (CanisterModule::new(wasm), Arc::new(serialized_module))
+    }
+
+    fn disk_cache(dir: &Path, capacity: NumBytes) -> CompilationCache {
+        CompilationCache::new_with_disk_cache(
+            NumBytes::new(1 << 30),
+            dir,
+            capacity,
+            &EmbeddersConfig::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn compiled_module_survives_restart() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (canister_module, serialized_module) = compile_module("(module)");
+        disk_cache(tmp.path(), NumBytes::new(1 << 30))
+            .insert(&canister_module, Ok(Arc::clone(&serialized_module)));
+
+        let cached = disk_cache(tmp.path(), NumBytes::new(1 << 30))
+            .get(&canister_module)
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.bytes.as_slice(), serialized_module.bytes.as_slice());
+    }
+
+    #[test]
+    fn corrupted_entry_is_a_miss() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (canister_module, serialized_module) = compile_module("(module)");
+        let cache = disk_cache(tmp.path(), NumBytes::new(1 << 30));
+        cache.insert(&canister_module, Ok(serialized_module));
+
+        let path = cache
+            .disk_cache
+            .as_ref()
+            .unwrap()
+            .path(&WasmHash::from(&canister_module));
+        let mut contents = fs::read(&path).unwrap();
+        let last = contents.len() - 1;
+        contents[last] ^= 0xff;
+        fs::write(&path, contents).unwrap();
+
+        let cache = disk_cache(tmp.path(), NumBytes::new(1 << 30));
+        assert!(cache.get(&canister_module).is_none());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn entries_are_evicted_above_capacity() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (module_a, serialized_a) = compile_module("(module (func))");
+        let (module_b, serialized_b) = compile_module("(module (func) (func))");
+        let size = bincode::serialize(serialized_a.as_ref()).unwrap().len() as u64
+            + (DISK_CACHE_MAGIC.len() + DISK_CACHE_CHECKSUM_LEN) as u64;
+
+        // The capacity is enough for one entry, but not for two.
+        let cache = disk_cache(tmp.path(), NumBytes::new(size + size / 2));
+        cache.insert(&module_a, Ok(serialized_a));
+        cache.insert(&module_b, Ok(serialized_b));
+
+        let cache = disk_cache(tmp.path(), NumBytes::new(size + size / 2));
+        assert!(cache.get(&module_a).is_none());
+        assert!(cache.get(&module_b).is_some());
+    }
+
+    #[test]
+    fn only_stale_cache_directories_are_removed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let stale_dir = tmp.path().join(format!("{}stale", DISK_CACHE_DIR_PREFIX));
+        let foreign_dir = tmp.path().join("foreign");
+        let foreign_file = tmp.path().join("foreign_file");
+        fs::create_dir_all(&stale_dir).unwrap();
+        fs::create_dir_all(&foreign_dir).unwrap();
+        fs::write(&foreign_file, b"data").unwrap();
+
+        let (canister_module, serialized_module) = compile_module("(module)");
+        disk_cache(tmp.path(), NumBytes::new(1 << 30))
+            .insert(&canister_module, Ok(serialized_module));
+        let cache = disk_cache(tmp.path(), NumBytes::new(1 << 30));
+        assert!(cache.get(&canister_module).is_some());
+
+        assert!(!stale_dir.exists());
+        assert!(foreign_dir.exists());
+        assert!(foreign_file.exists());
+    }
+}
@@ -109,15 +109,37 @@
 //! (memory (export "stable_memory_bytemap") i32 (i64.const STABLE_BYTEMAP_SIZE) (i64.const STABLE_BYTEMAP_SIZE))
 //! ```
 //!
+//! # Direct stable memory access
+//!
+//! If direct stable memory access is enabled, then a canister can import the
+//! stable memory as a second Wasm memory:
+//! ```wasm
+//! (import "ic0" "stable_memory" (memory i64 0))
+//! ```
+//! The import is removed and all references to it are redirected to the
+//! injected stable memory above, so that loads and stores on it do not need to
+//! go through the System API. Such loads and stores are charged
+//! `DIRECT_STABLE_MEMORY_ACCESS_COST` instructions, while the pages they
+//! modify are tracked by the memory tracker and charged after execution.
+//!
+//! # 64-bit Wasm memory
+//!
+//! If the Wasm memory of the module is a 64-bit memory, then its maximum size
+//! is limited to `MAX_WASM64_MEMORY_IN_BYTES`, and the instrumentation of
+//! `memory.grow`, bulk memory instructions, and the write barrier operates on
+//! `i64` addresses and sizes.
+//!
 
 use super::system_api_replacements::replacement_functions;
-use super::validation::API_VERSION_IC0;
+use super::validation::{
+    has_memory64, is_stable_memory_import, stable_memory_import_index, API_VERSION_IC0,
+};
 use super::{InstrumentationOutput, Segments, SystemApiFunc};
 use ic_config::flag_status::FlagStatus;
 use ic_registry_subnet_type::SubnetType;
 use ic_replicated_state::NumWasmPages;
 use ic_sys::PAGE_SIZE;
-use ic_types::{methods::WasmMethod, MAX_WASM_MEMORY_IN_BYTES};
+use ic_types::{methods::WasmMethod, MAX_WASM64_MEMORY_IN_BYTES, MAX_WASM_MEMORY_IN_BYTES};
 use ic_types::{NumInstructions, MAX_STABLE_MEMORY_IN_BYTES};
 use ic_wasm_types::{BinaryEncodedWasm, WasmError, WasmInstrumentationError};
 use wasmtime_environ::WASM_PAGE_SIZE;
@@ -128,8 +150,8 @@ use crate::wasmtime_embedder::{
     WASM_HEAP_MEMORY_NAME,
 };
 use wasmparser::{
-    BlockType, Export, ExternalKind, FuncType, GlobalType, Import, MemoryType, Operator, Type,
-    TypeRef, ValType,
+    BlockType, Export, ExternalKind, FuncType, GlobalType, Import, MemArg, MemoryType, Operator,
+    Type, TypeRef, ValType,
 };
 
 use std::collections::BTreeMap;
@@ -154,6 +176,11 @@ impl InjectedImports {
     }
 }
 
+/// The cost of a load or a store on the directly accessed stable memory. It
+/// is higher than the cost of other instructions because the first access to
+/// each page is handled by the memory tracker.
+const DIRECT_STABLE_MEMORY_ACCESS_COST: u64 = 20;
+
 // Gets the cost of an instruction.
 fn instruction_to_cost(i: &Operator) -> u64 {
     match i {
@@ -164,6 +191,13 @@ fn instruction_to_cost(i: &Operator) -> u64 {
         Operator::End => 0,
         Operator::Loop { .. } => 0,
 
+        // The heap is always memory 0 after instrumentation, so any other
+        // memory accessed by canister code is the directly accessed stable
+        // memory.
+        _ if memory_access_index(i).map_or(false, |mem| mem != 0) => {
+            DIRECT_STABLE_MEMORY_ACCESS_COST
+        }
+
         // Default cost of an instruction is 1.
         _ => 1,
     }
@@ -185,6 +219,10 @@ const CANISTER_START_STR: &str = "canister_start";
 const BYTEMAP_SIZE_IN_WASM_PAGES: u64 =
     MAX_WASM_MEMORY_IN_BYTES / (PAGE_SIZE as u64) / (WASM_PAGE_SIZE as u64);
 
+const MAX_WASM64_MEMORY_IN_WASM_PAGES: u64 = MAX_WASM64_MEMORY_IN_BYTES / (WASM_PAGE_SIZE as u64);
+/// There is one byte for each OS page in a 64-bit wasm heap.
+const WASM64_BYTEMAP_SIZE_IN_WASM_PAGES: u64 = MAX_WASM64_MEMORY_IN_WASM_PAGES / (PAGE_SIZE as u64);
+
 const MAX_STABLE_MEMORY_IN_WASM_PAGES: u64 = MAX_STABLE_MEMORY_IN_BYTES / (WASM_PAGE_SIZE as u64);
 /// There is one byte for each OS page in the stable memory.
 const STABLE_BYTEMAP_SIZE_IN_WASM_PAGES: u64 = MAX_STABLE_MEMORY_IN_WASM_PAGES / (PAGE_SIZE as u64);
@@ -262,6 +300,93 @@ fn mutate_function_indices(module: &mut Module, f: impl Fn(u32) -> u32) {
     }
 }
 
+// Matches the memory argument of a load or a store instruction.
+macro_rules! load_store_memarg {
+    ($op:expr) => {{
+        use Operator::*;
+        match $op {
+            I32Load { memarg }
+            | I64Load { memarg }
+            | F32Load { memarg }
+            | F64Load { memarg }
+            | I32Load8S { memarg }
+            | I32Load8U { memarg }
+            | I32Load16S { memarg }
+            | I32Load16U { memarg }
+            | I64Load8S { memarg }
+            | I64Load8U { memarg }
+            | I64Load16S { memarg }
+            | I64Load16U { memarg }
+            | I64Load32S { memarg }
+            | I64Load32U { memarg }
+            | I32Store { memarg }
+            | I64Store { memarg }
+            | F32Store { memarg }
+            | F64Store { memarg }
+            | I32Store8 { memarg }
+            | I32Store16 { memarg }
+            | I64Store8 { memarg }
+            | I64Store16 { memarg }
+            | I64Store32 { memarg } => Some(memarg),
+            _ => None,
+        }
+    }};
+}
+
+// Returns the index of the memory accessed by a load or a store instruction.
+fn memory_access_index(op: &Operator) -> Option<u32> {
+    load_store_memarg!(op).map(|memarg: &MemArg| memarg.memory)
+}
+
+fn mutate_memory_indices(module: &mut Module, f: impl Fn(u32) -> u32) {
+    use Operator::*;
+    for func_body in &mut module.code_sections {
+        for op in &mut func_body.instructions {
+            if let Some(memarg) = load_store_memarg!(op) {
+                let memarg: &mut MemArg = memarg;
+                memarg.memory = f(memarg.memory);
+                continue;
+            }
+            match op {
+                MemorySize { mem, .. }
+                | MemoryGrow { mem, .. }
+                | MemoryFill { mem }
+                | MemoryInit { mem, .. } => {
+                    *mem = f(*mem);
+                }
+                MemoryCopy { dst_mem, src_mem } => {
+                    *dst_mem = f(*dst_mem);
+                    *src_mem = f(*src_mem);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    for exp in &mut module.exports {
+        if let ExternalKind::Memory = exp.kind {
+            exp.index = f(exp.index);
+        }
+    }
+
+    for data_segment in &mut module.data {
+        if let wasm_transform::DataSegmentKind::Active { memory_index, .. } = &mut data_segment.kind
+        {
+            *memory_index = f(*memory_index);
+        }
+    }
+}
+
+// Removes the import of the directly accessed stable memory. Returns the index
+// the stable memory had in the memory index space of the original module.
+fn remove_stable_memory_import(module: &mut Module) -> Option<u32> {
+    let index = stable_memory_import_index(module)?;
+    module
+        .imports
+        .retain(|import| !is_stable_memory_import(import.module, import.name));
+    Some(index)
+}
+
 /// Injects hidden api functions.
 ///
 /// Note that these functions are injected as the first imports, so that we
@@ -383,7 +508,7 @@ pub(super) struct SpecialIndices {
 /// Returns an [`InstrumentationOutput`] or an error if the input binary could
 /// not be instrumented.
 pub(super) fn instrument(
-    module: Module<'_>,
+    mut module: Module<'_>,
     cost_to_compile_wasm_instruction: NumInstructions,
     write_barrier: FlagStatus,
     wasm_native_stable_memory: FlagStatus,
@@ -391,10 +516,17 @@ pub(super) fn instrument(
     dirty_page_overhead: NumInstructions,
 ) -> Result<InstrumentationOutput, WasmInstrumentationError> {
     let stable_memory_index;
+    let memory64 = has_memory64(&module);
+    let stable_memory_import = remove_stable_memory_import(&mut module);
     let mut module = inject_helper_functions(module, wasm_native_stable_memory);
     module = export_table(module);
-    (module, stable_memory_index) =
-        update_memories(module, write_barrier, wasm_native_stable_memory);
+    (module, stable_memory_index) = update_memories(
+        module,
+        write_barrier,
+        wasm_native_stable_memory,
+        memory64,
+        stable_memory_import,
+    );
 
     let mut extra_strs: Vec<String> = Vec::new();
     module = export_mutable_globals(module, &mut extra_strs);
@@ -448,7 +580,7 @@ pub(super) fn instrument(
 
     // inject instructions counter decrementation
     for func_body in &mut module.code_sections {
-        inject_metering(&mut func_body.instructions, &special_indices);
+        inject_metering(&mut func_body.instructions, &special_indices, memory64);
     }
 
     // Collect all the function types of the locally defined functions inside the
@@ -468,9 +600,9 @@ pub(super) fn instrument(
     if !func_types.is_empty() {
         let func_bodies = &mut module.code_sections;
         for (func_ix, func_type) in func_types.into_iter().enumerate() {
-            inject_update_available_memory(&mut func_bodies[func_ix], &func_type);
+            inject_update_available_memory(&mut func_bodies[func_ix], &func_type, memory64);
             if write_barrier == FlagStatus::Enabled {
-                inject_mem_barrier(&mut func_bodies[func_ix], &func_type);
+                inject_mem_barrier(&mut func_bodies[func_ix], &func_type, memory64);
             }
         }
     }
@@ -879,8 +1011,9 @@ impl InjectionPoint {
 //   of every reentrant block (a loop or a function call).
 // - we insert a function call before each dynamic cost instruction which
 //   performs an overflow check and then decrements the counter by the value at
-//   the top of the stack.
-fn inject_metering(code: &mut Vec<Operator>, export_data_module: &SpecialIndices) {
+//   the top of the stack. The value is an `i64` for `memory.fill` and
+//   `memory.copy` on 64-bit Wasm memories and an `i32` otherwise.
+fn inject_metering(code: &mut Vec<Operator>, export_data_module: &SpecialIndices, memory64: bool) {
     let points = injections(code);
     let points = points.iter().filter(|point| match point.cost_detail {
         InjectionPointCostDetail::StaticCost {
@@ -927,6 +1060,13 @@ fn inject_metering(code: &mut Vec<Operator>, export_data_module: &SpecialIndices
                     ]);
                 }
             }
+            InjectionPointCostDetail::DynamicCost
+                if has_i64_size(&orig_elems[point.position], memory64) =>
+            {
+                elems.push(Call {
+                    function_index: export_data_module.decr_instruction_counter_fn,
+                });
+            }
             InjectionPointCostDetail::DynamicCost => {
                 elems.extend_from_slice(&[
                     I64ExtendI32U,
@@ -946,17 +1086,78 @@ fn inject_metering(code: &mut Vec<Operator>, export_data_module: &SpecialIndices
     *orig_elems = elems;
 }
 
+// Returns true if the size argument of a bulk memory instruction is an `i64`,
+// which is the case if all memories it operates on are 64-bit memories. The
+// stable memory is always a 64-bit memory and it is the only memory besides
+// the heap (memory 0) that canister code can access.
+fn has_i64_size(op: &Operator, memory64: bool) -> bool {
+    let is_64_bit = |mem: u32| mem != 0 || memory64;
+    match op {
+        Operator::MemoryFill { mem } => is_64_bit(*mem),
+        Operator::MemoryCopy { dst_mem, src_mem } => is_64_bit(*dst_mem) && is_64_bit(*src_mem),
+        _ => false,
+    }
+}
+
 // This function adds mem barrier writes, assuming that arguments
 // of the original store operation are on the stack
 fn write_barrier_instructions<'a>(
     offset: u64,
     val_arg_idx: u32,
     addr_arg_idx: u32,
+    memory64: bool,
 ) -> Vec<Operator<'a>> {
     use Operator::*;
     let page_size_shift = PAGE_SIZE.trailing_zeros() as i32;
     let tracking_mem_idx = 1;
-    if offset % PAGE_SIZE as u64 == 0 {
+    if memory64 {
+        // The address is an `i64`, but the page index always fits into an
+        // `i32` because the bytemap is a 32-bit memory.
+        let mut instructions = vec![
+            LocalSet {
+                local_index: val_arg_idx,
+            }, // value
+            LocalTee {
+                local_index: addr_arg_idx,
+            }, // address
+        ];
+        if offset % PAGE_SIZE as u64 != 0 {
+            instructions.extend_from_slice(&[
+                I64Const {
+                    value: offset as i64,
+                },
+                I64Add,
+            ]);
+        }
+        instructions.extend_from_slice(&[
+            I64Const {
+                value: page_size_shift as i64,
+            },
+            I64ShrU,
+            I32WrapI64,
+            I32Const { value: 1 },
+            I32Store8 {
+                memarg: wasmparser::MemArg {
+                    align: 0,
+                    max_align: 0,
+                    offset: if offset % PAGE_SIZE as u64 == 0 {
+                        offset >> page_size_shift
+                    } else {
+                        0
+                    },
+                    memory: tracking_mem_idx,
+                },
+            },
+            // Put original params on the stack
+            LocalGet {
+                local_index: addr_arg_idx,
+            },
+            LocalGet {
+                local_index: val_arg_idx,
+            },
+        ]);
+        instructions
+    } else if offset % PAGE_SIZE as u64 == 0 {
         vec![
             LocalSet {
                 local_index: val_arg_idx,
@@ -1021,7 +1222,7 @@ fn write_barrier_instructions<'a>(
     }
 }
 
-fn inject_mem_barrier(func_body: &mut wasm_transform::Body, func_type: &FuncType) {
+fn inject_mem_barrier(func_body: &mut wasm_transform::Body, func_type: &FuncType, memory64: bool) {
     use Operator::*;
     let mut val_i32_needed = false;
     let mut val_i64_needed = false;
@@ -1031,6 +1232,11 @@ fn inject_mem_barrier(func_body: &mut wasm_transform::Body, func_type: &FuncType
     let mut injection_points: Vec<usize> = Vec::new();
     {
         for (idx, instr) in func_body.instructions.iter().enumerate() {
+            // Stores to the directly accessed stable memory are tracked by the
+            // memory tracker and don't need a barrier.
+            if memory_access_index(instr).map_or(false, |mem| mem != 0) {
+                continue;
+            }
             match instr {
                 I32Store { .. } | I32Store8 { .. } | I32Store16 { .. } => {
                     val_i32_needed = true;
@@ -1060,7 +1266,7 @@ fn inject_mem_barrier(func_body: &mut wasm_transform::Body, func_type: &FuncType
         // the total number of locals.
         let n_locals: u32 = func_body.locals.iter().map(|x| x.0).sum();
         let mut next_local = func_type.params().len() as u32 + n_locals;
-        let arg_i32_addr_idx = next_local;
+        let arg_addr_idx = next_local;
         next_local += 1;
 
         // conditionally add following locals
@@ -1069,7 +1275,17 @@ fn inject_mem_barrier(func_body: &mut wasm_transform::Body, func_type: &FuncType
         let arg_f32_val_idx;
         let arg_f64_val_idx;
 
-        if val_i32_needed {
+        if memory64 {
+            // The address local is an `i64` for a 64-bit memory.
+            func_body.locals.push((1, ValType::I64));
+            if val_i32_needed {
+                arg_i32_val_idx = next_local;
+                next_local += 1;
+                func_body.locals.push((1, ValType::I32));
+            } else {
+                arg_i32_val_idx = u32::MAX;
+            }
+        } else if val_i32_needed {
             arg_i32_val_idx = next_local;
             next_local += 1;
             func_body.locals.push((2, ValType::I32)); // addr and val locals
@@ -1114,7 +1330,8 @@ fn inject_mem_barrier(func_body: &mut wasm_transform::Body, func_type: &FuncType
                     elems.extend_from_slice(&write_barrier_instructions(
                         memarg.offset,
                         arg_i32_val_idx,
-                        arg_i32_addr_idx,
+                        arg_addr_idx,
+                        memory64,
                     ));
                 }
                 I64Store { memarg }
@@ -1124,21 +1341,24 @@ fn inject_mem_barrier(func_body: &mut wasm_transform::Body, func_type: &FuncType
                     elems.extend_from_slice(&write_barrier_instructions(
                         memarg.offset,
                         arg_i64_val_idx,
-                        arg_i32_addr_idx,
+                        arg_addr_idx,
+                        memory64,
                     ));
                 }
                 F32Store { memarg } => {
                     elems.extend_from_slice(&write_barrier_instructions(
                         memarg.offset,
                         arg_f32_val_idx,
-                        arg_i32_addr_idx,
+                        arg_addr_idx,
+                        memory64,
                     ));
                 }
                 F64Store { memarg } => {
                     elems.extend_from_slice(&write_barrier_instructions(
                         memarg.offset,
                         arg_f64_val_idx,
-                        arg_i32_addr_idx,
+                        arg_addr_idx,
+                        memory64,
                     ));
                 }
                 _ => {}
@@ -1157,7 +1377,16 @@ fn inject_mem_barrier(func_body: &mut wasm_transform::Body, func_type: &FuncType
 // `table.grow` instruction to make sure that there's enough available memory
 // left to support the requested extra memory. If no `memory.grow` or
 // `table.grow` instructions are present then the code remains unchanged.
-fn inject_update_available_memory(func_body: &mut wasm_transform::Body, func_type: &FuncType) {
+//
+// The argument and the result of `memory.grow` on a 64-bit memory are `i64`s.
+// They are wrapped to `i32`s for `update_available_memory`, which is lossless
+// for all successful grows because the size of a 64-bit memory is bounded by
+// `MAX_WASM64_MEMORY_IN_BYTES`, and a failed grow returns -1 either way.
+fn inject_update_available_memory(
+    func_body: &mut wasm_transform::Body,
+    func_type: &FuncType,
+    memory64: bool,
+) {
     // This is an overestimation of table element size computed based on the
     // existing canister limits.
     const TABLE_ELEMENT_SIZE: u32 = 1024;
@@ -1182,6 +1411,10 @@ fn inject_update_available_memory(func_body: &mut wasm_transform::Body, func_typ
         let n_locals: u32 = func_body.locals.iter().map(|x| x.0).sum();
         let memory_local_ix = func_type.params().len() as u32 + n_locals;
         func_body.locals.push((1, ValType::I32));
+        let memory64_local_ix = memory_local_ix + 1;
+        if memory64 {
+            func_body.locals.push((1, ValType::I64));
+        }
 
         let orig_elems = &func_body.instructions;
         let mut elems: Vec<Operator> = Vec::new();
@@ -1189,6 +1422,28 @@ fn inject_update_available_memory(func_body: &mut wasm_transform::Body, func_typ
         for (point, element_size) in injection_points {
             let update_available_memory_instr = orig_elems[point].clone();
             elems.extend_from_slice(&orig_elems[last_injection_position..point]);
+            if memory64 && matches!(update_available_memory_instr, MemoryGrow { .. }) {
+                elems.extend_from_slice(&[
+                    LocalTee {
+                        local_index: memory64_local_ix,
+                    },
+                    update_available_memory_instr,
+                    I32WrapI64,
+                    LocalGet {
+                        local_index: memory64_local_ix,
+                    },
+                    I32WrapI64,
+                    I32Const {
+                        value: element_size as i32,
+                    },
+                    Call {
+                        function_index: InjectedImports::UpdateAvailableMemory as u32,
+                    },
+                    I64ExtendI32S,
+                ]);
+                last_injection_position = point + 1;
+                continue;
+            }
             // At this point we have a memory.grow so the argument to it will be on top of
             // the stack, which we just assign to `memory_local_ix` with a local.tee
             // instruction.
@@ -1284,6 +1539,7 @@ fn get_data(
                     offset_expr,
                 } => match offset_expr {
                     Operator::I32Const { value } => *value as usize,
+                    Operator::I64Const { value } => *value as usize,
                     _ => return Err(WasmInstrumentationError::WasmDeserializeError(WasmError::new(
                         "complex initialization expressions for data segments are not supported!".into()
                     ))),
@@ -1326,12 +1582,41 @@ fn export_table(mut module: Module) -> Module {
 /// Exports existing memories and injects new memories. Returns the index of an
 /// injected stable memory when using wasm-native stable memory. The bytemap for
 /// the stable memory will always be inserted directly after the stable memory.
+///
+/// If the module imported the stable memory for direct access, then all
+/// references to the removed import are redirected to the injected stable
+/// memory.
 fn update_memories(
     mut module: Module,
     write_barrier: FlagStatus,
     wasm_native_stable_memory: FlagStatus,
+    memory64: bool,
+    stable_memory_import: Option<u32>,
 ) -> (Module, u32) {
     let mut stable_index = 0;
+    let has_heap_bytemap = write_barrier == FlagStatus::Enabled && !module.memories.is_empty();
+    // The stable memory is injected after the heap and its bytemap below.
+    let injected_stable_index = module.memories.len() as u32 + u32::from(has_heap_bytemap);
+
+    if let Some(import_index) = stable_memory_import {
+        mutate_memory_indices(&mut module, |index| match index.cmp(&import_index) {
+            std::cmp::Ordering::Less => index,
+            std::cmp::Ordering::Equal => injected_stable_index,
+            std::cmp::Ordering::Greater => index - 1,
+        });
+    }
+
+    // A 64-bit memory can grow beyond 4GiB, so its maximum is set explicitly.
+    if memory64 {
+        if let Some(heap) = module.memories.first_mut() {
+            heap.maximum = Some(
+                heap.maximum
+                    .map_or(MAX_WASM64_MEMORY_IN_WASM_PAGES, |maximum| {
+                        maximum.min(MAX_WASM64_MEMORY_IN_WASM_PAGES)
+                    }),
+            );
+        }
+    }
 
     let mut memory_already_exported = false;
     for export in &mut module.exports {
@@ -1350,12 +1635,17 @@ fn update_memories(
         module.exports.push(memory_export);
     }
 
-    if write_barrier == FlagStatus::Enabled && !module.memories.is_empty() {
+    if has_heap_bytemap {
+        let bytemap_size = if memory64 {
+            WASM64_BYTEMAP_SIZE_IN_WASM_PAGES
+        } else {
+            BYTEMAP_SIZE_IN_WASM_PAGES
+        };
         module.memories.push(MemoryType {
             memory64: false,
             shared: false,
-            initial: BYTEMAP_SIZE_IN_WASM_PAGES,
-            maximum: Some(BYTEMAP_SIZE_IN_WASM_PAGES),
+            initial: bytemap_size,
+            maximum: Some(bytemap_size),
         });
 
         module.exports.push(Export {
@@ -1367,6 +1657,7 @@ fn update_memories(
 
     if wasm_native_stable_memory == FlagStatus::Enabled {
         stable_index = module.memories.len() as u32;
+        debug_assert_eq!(stable_index, injected_stable_index);
         module.memories.push(MemoryType {
             memory64: true,
             shared: false,
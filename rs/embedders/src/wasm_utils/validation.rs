@@ -3,17 +3,18 @@
 
 use super::{WasmImportsDetails, WasmValidationDetails};
 
-use ic_config::embedders::Config as EmbeddersConfig;
+use ic_config::{embedders::Config as EmbeddersConfig, flag_status::FlagStatus};
 use ic_replicated_state::canister_state::execution_state::{
     CustomSection, CustomSectionType, WasmMetadata,
 };
-use ic_types::{NumBytes, NumInstructions};
+use ic_types::{NumBytes, NumInstructions, MAX_WASM64_MEMORY_IN_BYTES};
 use ic_wasm_types::{BinaryEncodedWasm, WasmValidationError};
 use std::{
     cmp,
     collections::{BTreeMap, HashMap, HashSet},
 };
 use wasmtime::Config;
+use wasmtime_environ::WASM_PAGE_SIZE;
 
 use crate::wasm_utils::instrumentation::{
     ACCESSED_PAGES_COUNTER_GLOBAL_NAME, DIRTY_PAGES_COUNTER_GLOBAL_NAME,
@@ -46,7 +47,55 @@ struct FunctionSignature {
 }
 
 const METHOD_MODULE: &str = "method";
-pub(super) const API_VERSION_IC0: &str = "ic0";
+pub(crate) const API_VERSION_IC0: &str = "ic0";
+
+/// System API functions that take pointers into the Wasm memory (or offsets and
+/// sizes of such regions), with the indices of these parameters. A canister
+/// with a 64-bit Wasm memory imports these functions with `i64` instead of
+/// `i32` for these parameters. Canisters with a 64-bit Wasm memory are expected
+/// to use the `stable64_*` functions, which already take 64-bit pointers.
+pub(crate) const WASM64_POINTER_PARAMS: &[(&str, &[usize])] = &[
+    ("msg_caller_copy", &[0, 1, 2]),
+    ("msg_arg_data_copy", &[0, 1, 2]),
+    ("msg_method_name_copy", &[0, 1, 2]),
+    ("msg_reply_data_append", &[0, 1]),
+    ("msg_reject", &[0, 1]),
+    ("msg_reject_msg_copy", &[0, 1, 2]),
+    ("canister_self_copy", &[0, 1, 2]),
+    ("debug_print", &[0, 1]),
+    ("trap", &[0, 1]),
+    ("call_new", &[0, 1, 2, 3]),
+    ("call_data_append", &[0, 1]),
+    ("canister_cycle_balance128", &[0]),
+    ("msg_cycles_available128", &[0]),
+    ("msg_cycles_refunded128", &[0]),
+    ("msg_cycles_accept128", &[2]),
+    ("certified_data_set", &[0, 1]),
+    ("data_certificate_copy", &[0, 1, 2]),
+    ("is_controller", &[0, 1]),
+];
+
+/// Returns the indices of the parameters of the given `ic0` function that are
+/// 64-bit for canisters with a 64-bit Wasm memory.
+pub(crate) fn wasm64_pointer_params(function_name: &str) -> Option<&'static [usize]> {
+    WASM64_POINTER_PARAMS
+        .iter()
+        .find(|(name, _)| *name == function_name)
+        .map(|(_, params)| *params)
+}
+
+/// Returns the System API signature that a canister with a 64-bit Wasm memory
+/// is expected to import.
+fn wasm64_signature(field: &str, signature: &FunctionSignature) -> FunctionSignature {
+    let mut param_types = signature.param_types.clone();
+    for index in wasm64_pointer_params(field).unwrap_or(&[]) {
+        param_types[*index] = ValType::I64;
+    }
+    FunctionSignature {
+        param_types,
+        return_type: signature.return_type.clone(),
+    }
+}
 
 // Constructs a map of function name -> HashMap<String,
 // `FunctionSignature`> (to allow the same function to be imported from
@@ -259,6 +308,16 @@ fn get_valid_system_apis() -> HashMap<String, HashMap<String, FunctionSignature>
                 },
             )],
         ),
+        (
+            "call_with_best_effort_response",
+            vec![(
+                API_VERSION_IC0,
+                FunctionSignature {
+                    param_types: vec![ValType::I32],
+                    return_type: vec![],
+                },
+            )],
+        ),
         (
             "call_cycles_add",
             vec![(
@@ -721,7 +780,10 @@ fn set_imports_details(import_details: &mut WasmImportsDetails, import_module: &
 //
 // Returns information about what IC0 methods are imported via
 // `WasmImportsDetails`.
-fn validate_import_section(module: &Module) -> Result<WasmImportsDetails, WasmValidationError> {
+fn validate_import_section(
+    module: &Module,
+    memory64: bool,
+) -> Result<WasmImportsDetails, WasmValidationError> {
     let mut imports_details = WasmImportsDetails::default();
 
     if !module.imports.is_empty() {
@@ -735,6 +797,13 @@ fn validate_import_section(module: &Module) -> Result<WasmImportsDetails, WasmVa
                     match valid_system_apis.get(field) {
                         Some(signatures) => {
                             match signatures.get(import_module) {
+                                Some(signature) if memory64 && import_module == API_VERSION_IC0 => {
+                                    validate_function_signature(
+                                        &wasm64_signature(field, signature),
+                                        field,
+                                        &module.types[*index as usize],
+                                    )?;
+                                },
                                 Some(signature) => {
                                     validate_function_signature(
                                         signature,
@@ -900,7 +969,7 @@ fn validate_data_section(module: &Module) -> Result<(), WasmValidationError> {
                 memory_index: _,
                 offset_expr,
             } => match offset_expr {
-                Operator::I32Const { .. } => Ok(()),
+                Operator::I32Const { .. } | Operator::I64Const { .. } => Ok(()),
                 _ => Err(WasmValidationError::InvalidDataSection(format!(
                     "Invalid offset expression in data segment: {:?}",
                     offset_expr
@@ -915,6 +984,152 @@ fn validate_data_section(module: &Module) -> Result<(), WasmValidationError> {
     Ok(())
 }
 
+/// Returns true if the import is the Wasm-native stable memory imported as
+/// `ic0.stable_memory` for direct access.
+pub(super) fn is_stable_memory_import(module: &str, name: &str) -> bool {
+    module == API_VERSION_IC0 && name == STABLE_MEMORY_NAME
+}
+
+/// Returns the index of the directly accessed stable memory in the memory
+/// index space of the module, if the module imports it.
+pub(super) fn stable_memory_import_index(module: &Module) -> Option<u32> {
+    module
+        .imports
+        .iter()
+        .filter(|import| matches!(import.ty, TypeRef::Memory(_)))
+        .position(|import| is_stable_memory_import(import.module, import.name))
+        .map(|index| index as u32)
+}
+
+/// Returns true if the Wasm memory of the module is a 64-bit memory.
+pub(super) fn has_memory64(module: &Module) -> bool {
+    let imported = module.imports.iter().find_map(|import| match import.ty {
+        TypeRef::Memory(_) if is_stable_memory_import(import.module, import.name) => None,
+        TypeRef::Memory(memory_type) => Some(memory_type),
+        _ => None,
+    });
+    imported
+        .or_else(|| module.memories.first().copied())
+        .map_or(false, |memory_type| memory_type.memory64)
+}
+
+// Checks that the module uses a 64-bit Wasm memory only if it is enabled and
+// supported by the configuration, and that the memory does not start larger
+// than the maximum size of a 64-bit Wasm memory.
+fn validate_memory_section(
+    module: &Module,
+    config: &EmbeddersConfig,
+) -> Result<(), WasmValidationError> {
+    if !has_memory64(module) {
+        return Ok(());
+    }
+    if config.feature_flags.wasm64 == FlagStatus::Disabled {
+        return Err(WasmValidationError::InvalidMemorySection(
+            "64-bit Wasm memories are not supported.".to_string(),
+        ));
+    }
+    // The System API replacements of Wasm-native stable memory copy between
+    // the stable memory and a 32-bit Wasm memory.
+    if config.feature_flags.wasm_native_stable_memory == FlagStatus::Enabled {
+        return Err(WasmValidationError::InvalidMemorySection(
+            "64-bit Wasm memories are not supported with Wasm-native stable memory.".to_string(),
+        ));
+    }
+    let max_pages = MAX_WASM64_MEMORY_IN_BYTES / WASM_PAGE_SIZE as u64;
+    for memory in &module.memories {
+        if memory.initial > max_pages {
+            return Err(WasmValidationError::InvalidMemorySection(format!(
+                "Wasm memory has an initial size of {} pages which exceeds the maximum of {} pages.",
+                memory.initial, max_pages
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Checks that the stable memory is imported for direct access only if it is
+// enabled, and that the module does not bypass the stable memory System API
+// for growing it or for initializing its contents:
+// * The import must be a 64-bit memory without explicit limits because its
+//   size is managed by `ic0.stable_grow` and `ic0.stable64_grow`.
+// * The stable memory cannot be the target of `memory.grow` or of data
+//   segments, and it cannot be exported.
+// * The module must also have a Wasm memory, which remains memory 0 after
+//   instrumentation.
+fn validate_stable_memory_import(
+    module: &Module,
+    config: &EmbeddersConfig,
+) -> Result<(), WasmValidationError> {
+    let stable_memory_index = match stable_memory_import_index(module) {
+        Some(index) => index,
+        None => return Ok(()),
+    };
+    if config.feature_flags.direct_stable_memory_access == FlagStatus::Disabled
+        || config.feature_flags.wasm_native_stable_memory == FlagStatus::Disabled
+    {
+        return Err(WasmValidationError::InvalidImportSection(format!(
+            "Importing the stable memory as {}.{} is not supported.",
+            API_VERSION_IC0, STABLE_MEMORY_NAME
+        )));
+    }
+    let num_imported_memories = module
+        .imports
+        .iter()
+        .filter(|import| matches!(import.ty, TypeRef::Memory(_)))
+        .count();
+    if num_imported_memories + module.memories.len() < 2 {
+        return Err(WasmValidationError::InvalidMemorySection(
+            "A module that imports the stable memory must also have a Wasm memory.".to_string(),
+        ));
+    }
+    for import in &module.imports {
+        if let TypeRef::Memory(memory_type) = import.ty {
+            if is_stable_memory_import(import.module, import.name)
+                && (!memory_type.memory64
+                    || memory_type.shared
+                    || memory_type.initial != 0
+                    || memory_type.maximum.is_some())
+            {
+                return Err(WasmValidationError::InvalidImportSection(format!(
+                    "The stable memory must be imported as a 64-bit memory without limits: \
+                     expected `(import \"{}\" \"{}\" (memory i64 0))`.",
+                    API_VERSION_IC0, STABLE_MEMORY_NAME
+                )));
+            }
+        }
+    }
+    for export in &module.exports {
+        if export.kind == ExternalKind::Memory && export.index == stable_memory_index {
+            return Err(WasmValidationError::InvalidExportSection(
+                "Exporting the stable memory is not allowed.".to_string(),
+            ));
+        }
+    }
+    for segment in &module.data {
+        if let DataSegmentKind::Active { memory_index, .. } = segment.kind {
+            if memory_index == stable_memory_index {
+                return Err(WasmValidationError::InvalidDataSection(
+                    "Data segments cannot initialize the stable memory.".to_string(),
+                ));
+            }
+        }
+    }
+    for func_body in &module.code_sections {
+        for instruction in &func_body.instructions {
+            if let Operator::MemoryGrow { mem, .. } = instruction {
+                if *mem == stable_memory_index {
+                    return Err(WasmValidationError::InvalidCodeSection(
+                        "The stable memory can only be grown with `ic0.stable_grow` or \
+                         `ic0.stable64_grow`."
+                            .to_string(),
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 // Checks that no more than `max_globals` are defined in the module.
 fn validate_global_section(module: &Module, max_globals: usize) -> Result<(), WasmValidationError> {
     if module.globals.len() > max_globals {
@@ -1134,9 +1349,18 @@ pub fn ensure_determinism(config: &mut Config) {
         .cranelift_nan_canonicalization(true);
 }
 
-fn can_compile(wasm: &BinaryEncodedWasm) -> Result<(), WasmValidationError> {
+fn can_compile(
+    wasm: &BinaryEncodedWasm,
+    embedders_config: &EmbeddersConfig,
+) -> Result<(), WasmValidationError> {
     let mut config = wasmtime::Config::default();
     ensure_determinism(&mut config);
+    if embedders_config.feature_flags.wasm64 == FlagStatus::Enabled {
+        config.wasm_memory64(true);
+    }
+    if embedders_config.feature_flags.direct_stable_memory_access == FlagStatus::Enabled {
+        config.wasm_multi_memory(true).wasm_memory64(true);
+    }
     let engine = wasmtime::Engine::new(&config).map_err(|_| {
         WasmValidationError::WasmtimeValidation(String::from("Failed to initialize Wasm engine"))
     })?;
@@ -1154,6 +1378,8 @@ fn can_compile(wasm: &BinaryEncodedWasm) -> Result<(), WasmValidationError> {
 /// It constructs a module by parsing the input Wasm binary and then calls into
 /// more specific methods that validate different sections of the Wasm binary.
 /// Currently, the sections we verify are:
+/// * Memory
+/// * Stable memory import
 /// * Import
 /// * Export
 /// * Code
@@ -1167,10 +1393,16 @@ pub(super) fn validate_wasm_binary<'a>(
     wasm: &'a BinaryEncodedWasm,
     config: &EmbeddersConfig,
 ) -> Result<(WasmValidationDetails, Module<'a>), WasmValidationError> {
-    can_compile(wasm)?;
-    let module = Module::parse(wasm.as_slice(), false)
-        .map_err(|err| WasmValidationError::DecodingError(format!("{}", err)))?;
-    let imports_details = validate_import_section(&module)?;
+    let module = Module::parse(wasm.as_slice(), false);
+    // The memory section is checked before compilation so that a 64-bit Wasm
+    // memory is reported as such rather than as a Wasmtime validation error.
+    if let Ok(module) = &module {
+        validate_memory_section(module, config)?;
+    }
+    can_compile(wasm, config)?;
+    let module = module.map_err(|err| WasmValidationError::DecodingError(format!("{}", err)))?;
+    validate_stable_memory_import(&module, config)?;
+    let imports_details = validate_import_section(&module, has_memory64(&module))?;
     let reserved_exports = validate_export_section(
         &module,
         config.max_number_exported_functions,
@@ -5,7 +5,7 @@ pub mod system_api_complexity;
 
 use std::{
     cell::Ref,
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     convert::TryFrom,
     mem::size_of,
     sync::{atomic::Ordering, Arc, Mutex},
@@ -13,7 +13,8 @@ use std::{
 
 use ic_system_api::ModificationTracking;
 use wasmtime::{
-    unix::StoreExt, Engine, Instance, Memory, Module, Mutability, OptLevel, Store, Val, ValType,
+    unix::StoreExt, Engine, ExternType, Instance, Memory, Module, Mutability, OptLevel, Store, Val,
+    ValType,
 };
 
 pub use host_memory::WasmtimeMemoryCreator;
@@ -29,7 +30,7 @@ use ic_replicated_state::{
 use ic_sys::PAGE_SIZE;
 use ic_types::{
     methods::{FuncRef, WasmMethod},
-    CanisterId, MAX_STABLE_MEMORY_IN_BYTES,
+    CanisterId, NumInstructions, MAX_STABLE_MEMORY_IN_BYTES,
 };
 use ic_wasm_types::{BinaryEncodedWasm, WasmEngineError};
 use memory_tracker::{DirtyPageTracking, PageBitmap, SigsegvMemoryTracker};
@@ -191,7 +192,9 @@ impl WasmtimeEmbedder {
         {
             config.wasm_multi_memory(true);
         }
-        if embedder_config.feature_flags.wasm_native_stable_memory == FlagStatus::Enabled {
+        if embedder_config.feature_flags.wasm_native_stable_memory == FlagStatus::Enabled
+            || embedder_config.feature_flags.wasm64 == FlagStatus::Enabled
+        {
             config.wasm_memory64(true);
         }
         config
@@ -287,14 +290,22 @@ impl WasmtimeEmbedder {
         }];
 
         if self.config.feature_flags.wasm_native_stable_memory == FlagStatus::Enabled {
+            // Wasm native stable memory will always be tracked by a bytemap
+            // within the wasm module. Direct stores to the stable memory
+            // bypass the bytemap, so they are tracked by the memory tracker.
+            let dirty_page_tracking = match (
+                modification_tracking,
+                self.config.feature_flags.direct_stable_memory_access,
+            ) {
+                (ModificationTracking::Track, FlagStatus::Enabled) => DirtyPageTracking::Track,
+                _ => DirtyPageTracking::Ignore,
+            };
             result.push(WasmMemoryInfo {
                 name: STABLE_MEMORY_NAME,
                 bytemap_name: Some(STABLE_BYTEMAP_MEMORY_NAME),
                 memory: stable_memory.clone(),
                 memory_type: CanisterMemoryType::Stable,
-                // Wasm native stable memory will always be tracked by a
-                // bytemap within the wasm module.
-                dirty_page_tracking: DirtyPageTracking::Ignore,
+                dirty_page_tracking,
             });
         }
         result
@@ -327,7 +338,7 @@ impl WasmtimeEmbedder {
             },
         );
 
-        let linker = system_api::syscalls(
+        let mut linker = system_api::syscalls(
             self.log.clone(),
             canister_id,
             &store,
@@ -335,6 +346,19 @@ impl WasmtimeEmbedder {
             self.config.stable_memory_dirty_page_limit,
             self.config.stable_memory_accessed_page_limit,
         );
+        let memory64 = matches!(
+            module.get_export(WASM_HEAP_MEMORY_NAME),
+            Some(ExternType::Memory(memory_type)) if memory_type.is_64()
+        );
+        if memory64 {
+            linker = system_api::wasm64_syscalls(
+                linker,
+                self.log.clone(),
+                canister_id,
+                self.config.feature_flags,
+                self.config.stable_memory_dirty_page_limit,
+            );
+        }
 
         let instance = match linker.instantiate(&mut store, module) {
             Ok(instance) => instance,
@@ -442,9 +466,10 @@ impl WasmtimeEmbedder {
             store,
             write_barrier: self.config.feature_flags.write_barrier,
             wasm_native_stable_memory: self.config.feature_flags.wasm_native_stable_memory,
+            direct_stable_memory_access: self.config.feature_flags.direct_stable_memory_access,
             modification_tracking,
-            #[cfg(debug_assertions)]
             stable_memory_dirty_page_limit: self.config.stable_memory_dirty_page_limit,
+            dirty_page_overhead: self.config.dirty_page_overhead,
         })
     }
 
@@ -646,6 +671,9 @@ pub struct PageAccessResults {
     pub read_before_write_count: usize,
     pub direct_write_count: usize,
     pub stable_dirty_pages: Vec<PageIndex>,
+    /// The number of `stable_dirty_pages` that were modified only by direct
+    /// stores to the stable memory.
+    pub num_direct_stable_dirty_pages: usize,
 }
 
 /// Encapsulates a Wasmtime instance on the Internet Computer.
@@ -658,9 +686,10 @@ pub struct WasmtimeInstance<S: SystemApi> {
     store: wasmtime::Store<StoreData<S>>,
     write_barrier: FlagStatus,
     wasm_native_stable_memory: FlagStatus,
+    direct_stable_memory_access: FlagStatus,
     modification_tracking: ModificationTracking,
-    #[cfg(debug_assertions)]
     stable_memory_dirty_page_limit: ic_types::NumPages,
+    dirty_page_overhead: NumInstructions,
 }
 
 impl<S: SystemApi> WasmtimeInstance<S> {
@@ -692,8 +721,22 @@ impl<S: SystemApi> WasmtimeInstance<S> {
 
     fn page_accesses(&mut self) -> HypervisorResult<PageAccessResults> {
         let mut stable_dirty_pages = vec![];
+        let mut num_direct_stable_dirty_pages = 0;
         if self.wasm_native_stable_memory == FlagStatus::Enabled {
             stable_dirty_pages = self.dirty_pages_from_bytemap(CanisterMemoryType::Stable)?;
+            if self.direct_stable_memory_access == FlagStatus::Enabled
+                && self.modification_tracking == ModificationTracking::Track
+            {
+                let marked_in_bytemap: BTreeSet<PageIndex> =
+                    stable_dirty_pages.iter().copied().collect();
+                let direct_dirty_pages: Vec<PageIndex> = self
+                    .dirty_pages_from_tracker(CanisterMemoryType::Stable)
+                    .into_iter()
+                    .filter(|page| !marked_in_bytemap.contains(page))
+                    .collect();
+                num_direct_stable_dirty_pages = direct_dirty_pages.len();
+                stable_dirty_pages.extend(direct_dirty_pages);
+            }
         };
 
         if self
@@ -711,6 +754,7 @@ impl<S: SystemApi> WasmtimeInstance<S> {
                 read_before_write_count: 0,
                 direct_write_count: 0,
                 stable_dirty_pages,
+                num_direct_stable_dirty_pages,
             })
         } else {
             let dirty_pages = match self.modification_tracking {
@@ -718,21 +762,7 @@ impl<S: SystemApi> WasmtimeInstance<S> {
                     FlagStatus::Enabled => {
                         self.dirty_pages_from_bytemap(CanisterMemoryType::Heap)?
                     }
-                    FlagStatus::Disabled => {
-                        let tracker = self
-                            .memory_trackers
-                            .get(&CanisterMemoryType::Heap)
-                            .unwrap()
-                            .lock()
-                            .unwrap();
-                        let speculatively_dirty_pages = tracker.take_speculatively_dirty_pages();
-                        let dirty_pages = tracker.take_dirty_pages();
-                        dirty_pages
-                            .into_iter()
-                            .chain(speculatively_dirty_pages.into_iter())
-                            .filter_map(|p| tracker.validate_speculatively_dirty_page(p))
-                            .collect::<Vec<PageIndex>>()
-                    }
+                    FlagStatus::Disabled => self.dirty_pages_from_tracker(CanisterMemoryType::Heap),
                 },
                 ModificationTracking::Ignore => {
                     vec![]
@@ -750,10 +780,78 @@ impl<S: SystemApi> WasmtimeInstance<S> {
                 read_before_write_count: tracker.read_before_write_count(),
                 direct_write_count: tracker.direct_write_count(),
                 stable_dirty_pages,
+                num_direct_stable_dirty_pages,
             })
         }
     }
 
+    fn dirty_pages_from_tracker(&self, memory_type: CanisterMemoryType) -> Vec<PageIndex> {
+        match self.memory_trackers.get(&memory_type) {
+            Some(tracker) => {
+                let tracker = tracker.lock().unwrap();
+                let speculatively_dirty_pages = tracker.take_speculatively_dirty_pages();
+                let dirty_pages = tracker.take_dirty_pages();
+                dirty_pages
+                    .into_iter()
+                    .chain(speculatively_dirty_pages.into_iter())
+                    .filter_map(|p| tracker.validate_speculatively_dirty_page(p))
+                    .collect()
+            }
+            None => vec![],
+        }
+    }
+
+    /// Charges for the stable memory pages modified by direct stores, which
+    /// are not seen by the stable memory System API, and checks them against
+    /// the limit on the number of dirty stable memory pages.
+    fn charge_direct_stable_dirty_pages(&mut self, dirty_pages: usize) -> HypervisorResult<()> {
+        if dirty_pages == 0 {
+            return Ok(());
+        }
+        let dirty_pages_counter = self
+            .instance
+            .get_global(&mut self.store, DIRTY_PAGES_COUNTER_GLOBAL_NAME)
+            .ok_or_else(|| {
+                HypervisorError::ContractViolation(format!(
+                    "export '{}' not found",
+                    DIRTY_PAGES_COUNTER_GLOBAL_NAME
+                ))
+            })?;
+        let remaining_dirty_pages = dirty_pages_counter
+            .get(&mut self.store)
+            .i64()
+            .unwrap_or_default()
+            .saturating_sub(dirty_pages as i64);
+        if remaining_dirty_pages < 0 {
+            return Err(HypervisorError::MemoryAccessLimitExceeded(format!(
+                "Exceeded the limit for the number of modified pages in the stable memory in a single message execution: limit: {} KB.",
+                self.stable_memory_dirty_page_limit.get() * (PAGE_SIZE as u64 / 1024),
+            )));
+        }
+        dirty_pages_counter
+            .set(&mut self.store, Val::I64(remaining_dirty_pages))
+            .map_err(|e| HypervisorError::ContractViolation(e.to_string()))?;
+
+        if let Some(instructions_counter) = self.store.data().num_instructions_global {
+            let cost = self
+                .dirty_page_overhead
+                .get()
+                .saturating_mul(dirty_pages as u64);
+            let remaining_instructions = instructions_counter
+                .get(&mut self.store)
+                .i64()
+                .unwrap_or_default()
+                .saturating_sub(i64::try_from(cost).unwrap_or(i64::MAX));
+            instructions_counter
+                .set(&mut self.store, Val::I64(remaining_instructions))
+                .map_err(|e| HypervisorError::ContractViolation(e.to_string()))?;
+            if remaining_instructions < 0 {
+                return Err(HypervisorError::InstructionLimitExceeded);
+            }
+        }
+        Ok(())
+    }
+
     fn get_memory(&mut self, name: &str) -> HypervisorResult<Memory> {
         match self.instance.get_export(&mut self.store, name) {
             Some(export) => export.into_memory().ok_or_else(|| {
@@ -771,7 +869,7 @@ impl<S: SystemApi> WasmtimeInstance<S> {
     pub fn run(&mut self, func_ref: FuncRef) -> HypervisorResult<InstanceRunResult> {
         let _alt_sig_stack = unsafe { self.signal_stack.register() };
 
-        let result = match &func_ref {
+        let mut result = match &func_ref {
             FuncRef::Method(wasm_method) => self.invoke_export(&wasm_method.to_string(), &[]),
             FuncRef::QueryClosure(closure) | FuncRef::UpdateClosure(closure) => self
                 .instance
@@ -830,10 +928,15 @@ impl<S: SystemApi> WasmtimeInstance<S> {
                             .i64()
                             .unwrap();
                     assert_eq!(
-                        access.stable_dirty_pages.len() as i64,
+                        (access.stable_dirty_pages.len() - access.num_direct_stable_dirty_pages)
+                            as i64,
                         num_stable_dirty_pages
                     );
                 }
+                if result.is_ok() {
+                    result =
+                        self.charge_direct_stable_dirty_pages(access.num_direct_stable_dirty_pages);
+                }
                 access.stable_dirty_pages
             }
             FlagStatus::Disabled => self
@@ -14,6 +14,7 @@ use ic_types::{CanisterId, Cycles, NumBytes, NumInstructions, NumPages, Time};
 
 use wasmtime::{AsContextMut, Caller, Global, Linker, Store, Val};
 
+use crate::wasm_utils::validation::API_VERSION_IC0;
 use crate::InternalErrorCode;
 use std::convert::TryFrom;
 
@@ -153,7 +154,7 @@ fn charge_for_system_api_call<S: SystemApi>(
     canister_id: CanisterId,
     caller: &mut Caller<'_, StoreData<S>>,
     system_api_overhead: NumInstructions,
-    num_bytes: u32,
+    num_bytes: u64,
     complexity: ExecutionComplexity,
     dirty_page_cost: NumInstructions,
     stable_memory_dirty_page_limit: NumPages,
@@ -168,7 +169,7 @@ fn charge_for_system_api_call<S: SystemApi>(
     let num_instructions_from_bytes = caller
         .data()
         .system_api
-        .get_num_instructions_from_bytes(NumBytes::from(num_bytes as u64));
+        .get_num_instructions_from_bytes(NumBytes::from(num_bytes));
     let (num_instructions1, overflow1) = num_instructions_from_bytes
         .get()
         .overflowing_add(dirty_page_cost.get());
@@ -466,7 +467,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::MSG_ARG_DATA_COPY,
-                    size as u32,
+                    size as u32 as u64,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::MSG_ARG_DATA_COPY,
                         ..Default::default()
@@ -509,7 +510,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::MSG_METHOD_NAME_COPY,
-                    size as u32,
+                    size as u32 as u64,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::MSG_METHOD_NAME_COPY,
                         ..Default::default()
@@ -552,7 +553,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::MSG_REPLY_DATA_APPEND,
-                    size as u32,
+                    size as u32 as u64,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::MSG_REPLY_DATA_APPEND,
                         ..Default::default()
@@ -594,7 +595,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::MSG_REJECT,
-                    size as u32,
+                    size as u32 as u64,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::MSG_REJECT,
                         ..Default::default()
@@ -632,7 +633,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::MSG_REJECT_MSG_COPY,
-                    size as u32,
+                    size as u32 as u64,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::MSG_REJECT_MSG_COPY,
                         ..Default::default()
@@ -711,7 +712,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::DEBUG_PRINT,
-                    length as u32,
+                    length as u32 as u64,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::DEBUG_PRINT,
                         ..Default::default()
@@ -747,7 +748,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::TRAP,
-                    length as u32,
+                    length as u32 as u64,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::TRAP,
                         ..Default::default()
@@ -810,7 +811,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::CALL_DATA_APPEND,
-                    size as u32,
+                    size as u32 as u64,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::CALL_DATA_APPEND,
                         ..Default::default()
@@ -836,6 +837,17 @@ pub(crate) fn syscalls<S: SystemApi>(
         })
         .unwrap();
 
+    linker
+        .func_wrap("ic0", "call_with_best_effort_response", {
+            move |mut caller: Caller<'_, StoreData<S>>, timeout_seconds: i32| {
+                with_system_api(&mut caller, |s| {
+                    s.ic0_call_with_best_effort_response(timeout_seconds as u32)
+                })
+                .map_err(|e| process_err(&mut caller, e))
+            }
+        })
+        .unwrap();
+
     linker
         .func_wrap("ic0", "call_cycles_add", {
             move |mut caller: Caller<'_, StoreData<S>>, amount: i64| {
@@ -922,7 +934,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::STABLE_READ,
-                    size as u32,
+                    size as u32 as u64,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::STABLE_READ,
                         ..Default::default()
@@ -956,7 +968,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::STABLE_WRITE,
-                    size,
+                    size as u64,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::STABLE_WRITE,
                         stable_dirty_pages,
@@ -1019,7 +1031,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     )
                 })?;
                 if feature_flags.write_barrier == FlagStatus::Enabled {
-                    mark_writes_on_bytemap(&mut caller, dst as u32 as usize, size as u32 as usize)
+                    mark_writes_on_bytemap(&mut caller, dst as u64 as usize, size as u64 as usize)
                 } else {
                     Ok(())
                 }
@@ -1036,7 +1048,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::STABLE64_READ,
-                    size as u32,
+                    size as u64,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::STABLE64_READ,
                         ..Default::default()
@@ -1048,7 +1060,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     system_api.ic0_stable64_read(dst as u64, offset as u64, size as u64, memory)
                 })?;
                 if feature_flags.write_barrier == FlagStatus::Enabled {
-                    mark_writes_on_bytemap(&mut caller, dst as u32 as usize, size as u32 as usize)
+                    mark_writes_on_bytemap(&mut caller, dst as u64 as usize, size as u64 as usize)
                 } else {
                     Ok(())
                 }
@@ -1070,7 +1082,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::STABLE64_WRITE,
-                    size as u32,
+                    size,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::STABLE64_WRITE,
                         stable_dirty_pages,
@@ -1413,7 +1425,7 @@ pub(crate) fn syscalls<S: SystemApi>(
                     canister_id,
                     &mut caller,
                     system_api_complexity::overhead::IS_CONTROLLER,
-                    size as u32,
+                    size as u32 as u64,
                     ExecutionComplexity {
                         cpu: system_api_complexity::cpu::IS_CONTROLLER,
                         ..Default::default()
@@ -1509,3 +1521,671 @@ pub(crate) fn syscalls<S: SystemApi>(
 
     linker
 }
+
+/// Converts a 64-bit System API argument that is not a pointer into the Wasm
+/// memory (e.g. an offset into the message payload) to the 32-bit value
+/// expected by the System API.
+fn wasm64_arg_to_u32<S: SystemApi>(
+    caller: &mut Caller<'_, StoreData<S>>,
+    name: &str,
+    value: i64,
+) -> Result<u32, anyhow::Error> {
+    u32::try_from(value as u64).map_err(|_| {
+        process_err(
+            caller,
+            HypervisorError::ContractViolation(format!(
+                "ic0.{}: argument {} exceeds the maximum of {}",
+                name,
+                value as u64,
+                u32::MAX
+            )),
+        )
+    })
+}
+
+/// Runs `f` on the region `[start, start + size)` of a 64-bit Wasm memory.
+///
+/// The System API functions take 32-bit pointers, so they are given only the
+/// region, which starts at offset 0 of the slice they receive. This way the
+/// region can be anywhere in the Wasm memory, including above 4GiB. The size
+/// of the region is passed to `f` as a 32-bit value.
+fn with_memory_region_and_system_api<S: SystemApi, T>(
+    caller: &mut Caller<'_, StoreData<S>>,
+    name: &str,
+    start: i64,
+    size: i64,
+    f: impl FnOnce(&mut S, u32, &mut [u8]) -> HypervisorResult<T>,
+) -> Result<T, anyhow::Error> {
+    let (start, size) = (start as u64, size as u64);
+    let memory = match caller
+        .get_export(WASM_HEAP_MEMORY_NAME)
+        .and_then(|ext| ext.into_memory())
+    {
+        Some(memory) => memory,
+        None => {
+            return Err(process_err(
+                caller,
+                HypervisorError::ContractViolation(
+                    "WebAssembly module must define memory".to_string(),
+                ),
+            ))
+        }
+    };
+    let size32 = wasm64_arg_to_u32(caller, name, size as i64)?;
+    let memory_size = memory.data_size(&*caller) as u64;
+    let end = match start.checked_add(size) {
+        Some(end) if end <= memory_size => end,
+        _ => {
+            return Err(process_err(
+                caller,
+                HypervisorError::ContractViolation(format!(
+                    "ic0.{}: src={} + length={} exceeds the slice size={}",
+                    name, start, size, memory_size
+                )),
+            ))
+        }
+    };
+    let (memory, store) = memory.data_and_store_mut(&mut *caller);
+    let result = f(
+        &mut store.system_api,
+        size32,
+        &mut memory[start as usize..end as usize],
+    );
+    result.map_err(|err| process_err(caller, err))
+}
+
+/// Replaces the System API functions that take pointers into the Wasm memory
+/// with variants that take 64-bit pointers, offsets, and sizes, as imported by
+/// canisters with a 64-bit Wasm memory (see `WASM64_POINTER_PARAMS`).
+///
+/// Pointers can address the whole 64-bit Wasm memory. Offsets into System API
+/// buffers (e.g. the message payload) and sizes still have to fit into 32 bits
+/// because these buffers are much smaller than 4GiB. The `stable64_*`
+/// functions already take 64-bit pointers and are shared with canisters with a
+/// 32-bit Wasm memory.
+pub(crate) fn wasm64_syscalls<S: SystemApi>(
+    mut linker: Linker<StoreData<S>>,
+    log: ReplicaLogger,
+    canister_id: CanisterId,
+    feature_flags: FeatureFlags,
+    stable_memory_dirty_page_limit: NumPages,
+) -> Linker<StoreData<S>> {
+    fn mark_region_writes<S: SystemApi>(
+        caller: &mut Caller<'_, StoreData<S>>,
+        feature_flags: &FeatureFlags,
+        dst: i64,
+        size: u64,
+    ) -> Result<(), anyhow::Error> {
+        if feature_flags.write_barrier == FlagStatus::Enabled {
+            mark_writes_on_bytemap(caller, dst as u64 as usize, size as usize)
+        } else {
+            Ok(())
+        }
+    }
+
+    linker.allow_shadowing(true);
+
+    linker
+        .func_wrap(API_VERSION_IC0, "msg_caller_copy", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, dst: i64, offset: i64, size: i64| {
+                observe_execution_complexity(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::MSG_CALLER_COPY,
+                        ..Default::default()
+                    },
+                    stable_memory_dirty_page_limit,
+                )?;
+                let offset = wasm64_arg_to_u32(&mut caller, "msg_caller_copy", offset)?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "msg_caller_copy",
+                    dst,
+                    size,
+                    |system_api, size, region| {
+                        system_api.ic0_msg_caller_copy(0, offset, size, region)
+                    },
+                )?;
+                mark_region_writes(&mut caller, &feature_flags, dst, size as u64)
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "msg_arg_data_copy", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, dst: i64, offset: i64, size: i64| {
+                charge_for_system_api_call(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    system_api_complexity::overhead::MSG_ARG_DATA_COPY,
+                    size as u64,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::MSG_ARG_DATA_COPY,
+                        ..Default::default()
+                    },
+                    NumInstructions::from(0),
+                    stable_memory_dirty_page_limit,
+                )?;
+                let offset = wasm64_arg_to_u32(&mut caller, "msg_arg_data_copy", offset)?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "msg_arg_data_copy",
+                    dst,
+                    size,
+                    |system_api, size, region| {
+                        system_api.ic0_msg_arg_data_copy(0, offset, size, region)
+                    },
+                )?;
+                mark_region_writes(&mut caller, &feature_flags, dst, size as u64)
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "msg_method_name_copy", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, dst: i64, offset: i64, size: i64| {
+                charge_for_system_api_call(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    system_api_complexity::overhead::MSG_METHOD_NAME_COPY,
+                    size as u64,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::MSG_METHOD_NAME_COPY,
+                        ..Default::default()
+                    },
+                    NumInstructions::from(0),
+                    stable_memory_dirty_page_limit,
+                )?;
+                let offset = wasm64_arg_to_u32(&mut caller, "msg_method_name_copy", offset)?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "msg_method_name_copy",
+                    dst,
+                    size,
+                    |system_api, size, region| {
+                        system_api.ic0_msg_method_name_copy(0, offset, size, region)
+                    },
+                )?;
+                mark_region_writes(&mut caller, &feature_flags, dst, size as u64)
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "msg_reply_data_append", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, src: i64, size: i64| {
+                charge_for_system_api_call(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    system_api_complexity::overhead::MSG_REPLY_DATA_APPEND,
+                    size as u64,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::MSG_REPLY_DATA_APPEND,
+                        ..Default::default()
+                    },
+                    NumInstructions::from(0),
+                    stable_memory_dirty_page_limit,
+                )?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "msg_reply_data_append",
+                    src,
+                    size,
+                    |system_api, size, region| {
+                        system_api.ic0_msg_reply_data_append(0, size, region)
+                    },
+                )
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "msg_reject", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, src: i64, size: i64| {
+                charge_for_system_api_call(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    system_api_complexity::overhead::MSG_REJECT,
+                    size as u64,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::MSG_REJECT,
+                        ..Default::default()
+                    },
+                    NumInstructions::from(0),
+                    stable_memory_dirty_page_limit,
+                )?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "msg_reject",
+                    src,
+                    size,
+                    |system_api, size, region| system_api.ic0_msg_reject(0, size, region),
+                )
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "msg_reject_msg_copy", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, dst: i64, offset: i64, size: i64| {
+                charge_for_system_api_call(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    system_api_complexity::overhead::MSG_REJECT_MSG_COPY,
+                    size as u64,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::MSG_REJECT_MSG_COPY,
+                        ..Default::default()
+                    },
+                    NumInstructions::from(0),
+                    stable_memory_dirty_page_limit,
+                )?;
+                let offset = wasm64_arg_to_u32(&mut caller, "msg_reject_msg_copy", offset)?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "msg_reject_msg_copy",
+                    dst,
+                    size,
+                    |system_api, size, region| {
+                        system_api.ic0_msg_reject_msg_copy(0, offset, size, region)
+                    },
+                )?;
+                mark_region_writes(&mut caller, &feature_flags, dst, size as u64)
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "canister_self_copy", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, dst: i64, offset: i64, size: i64| {
+                observe_execution_complexity(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::CANISTER_SELF_COPY,
+                        ..Default::default()
+                    },
+                    stable_memory_dirty_page_limit,
+                )?;
+                let offset = wasm64_arg_to_u32(&mut caller, "canister_self_copy", offset)?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "canister_self_copy",
+                    dst,
+                    size,
+                    |system_api, size, region| {
+                        system_api.ic0_canister_self_copy(0, offset, size, region)
+                    },
+                )?;
+                mark_region_writes(&mut caller, &feature_flags, dst, size as u64)
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "debug_print", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, offset: i64, length: i64| {
+                charge_for_system_api_call(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    system_api_complexity::overhead::DEBUG_PRINT,
+                    length as u64,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::DEBUG_PRINT,
+                        ..Default::default()
+                    },
+                    NumInstructions::from(0),
+                    stable_memory_dirty_page_limit,
+                )?;
+                match (
+                    caller.data().system_api.subnet_type(),
+                    feature_flags.rate_limiting_of_debug_prints,
+                ) {
+                    // Debug print is a no-op on non-system subnets with rate limiting.
+                    (SubnetType::Application, FlagStatus::Enabled) => Ok(()),
+                    (SubnetType::VerifiedApplication, FlagStatus::Enabled) => Ok(()),
+                    // If rate limiting is disabled or the subnet is a system subnet, then
+                    // debug print produces output.
+                    (_, FlagStatus::Disabled) | (SubnetType::System, FlagStatus::Enabled) => {
+                        with_memory_region_and_system_api(
+                            &mut caller,
+                            "debug_print",
+                            offset,
+                            length,
+                            |system_api, length, region| {
+                                system_api.ic0_debug_print(0, length, region)
+                            },
+                        )
+                    }
+                }
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "trap", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, offset: i64, length: i64| -> Result<(), _> {
+                charge_for_system_api_call(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    system_api_complexity::overhead::TRAP,
+                    length as u64,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::TRAP,
+                        ..Default::default()
+                    },
+                    NumInstructions::from(0),
+                    stable_memory_dirty_page_limit,
+                )?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "trap",
+                    offset,
+                    length,
+                    |system_api, length, region| system_api.ic0_trap(0, length, region),
+                )
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "call_new", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>,
+                  callee_src: i64,
+                  callee_size: i64,
+                  name_src: i64,
+                  name_len: i64,
+                  reply_fun: i32,
+                  reply_env: i32,
+                  reject_fun: i32,
+                  reject_env: i32| {
+                observe_execution_complexity(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::CALL_NEW,
+                        ..Default::default()
+                    },
+                    stable_memory_dirty_page_limit,
+                )?;
+                // The callee and the method name are passed to the System API
+                // as one region that spans both of them.
+                let callee_size32 = wasm64_arg_to_u32(&mut caller, "call_new", callee_size)?;
+                let name_len32 = wasm64_arg_to_u32(&mut caller, "call_new", name_len)?;
+                let (callee_src, name_src) = (callee_src as u64, name_src as u64);
+                let start = callee_src.min(name_src);
+                let end = callee_src
+                    .saturating_add(callee_size32 as u64)
+                    .max(name_src.saturating_add(name_len32 as u64));
+                let callee_offset =
+                    wasm64_arg_to_u32(&mut caller, "call_new", (callee_src - start) as i64)?;
+                let name_offset =
+                    wasm64_arg_to_u32(&mut caller, "call_new", (name_src - start) as i64)?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "call_new",
+                    start as i64,
+                    (end - start) as i64,
+                    |system_api, _, region| {
+                        system_api.ic0_call_new(
+                            callee_offset,
+                            callee_size32,
+                            name_offset,
+                            name_len32,
+                            reply_fun as u32,
+                            reply_env as u32,
+                            reject_fun as u32,
+                            reject_env as u32,
+                            region,
+                        )
+                    },
+                )
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "call_data_append", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, src: i64, size: i64| {
+                charge_for_system_api_call(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    system_api_complexity::overhead::CALL_DATA_APPEND,
+                    size as u64,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::CALL_DATA_APPEND,
+                        ..Default::default()
+                    },
+                    NumInstructions::from(0),
+                    stable_memory_dirty_page_limit,
+                )?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "call_data_append",
+                    src,
+                    size,
+                    |system_api, size, region| system_api.ic0_call_data_append(0, size, region),
+                )
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "canister_cycle_balance128", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, dst: i64| {
+                observe_execution_complexity(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::CANISTER_CYCLES_BALANCE128,
+                        ..Default::default()
+                    },
+                    stable_memory_dirty_page_limit,
+                )?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "canister_cycle_balance128",
+                    dst,
+                    16,
+                    |system_api, _, region| system_api.ic0_canister_cycle_balance128(0, region),
+                )?;
+                mark_region_writes(&mut caller, &feature_flags, dst, 16)
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "msg_cycles_available128", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, dst: i64| {
+                observe_execution_complexity(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::MSG_CYCLES_AVAILABLE128,
+                        ..Default::default()
+                    },
+                    stable_memory_dirty_page_limit,
+                )?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "msg_cycles_available128",
+                    dst,
+                    16,
+                    |system_api, _, region| system_api.ic0_msg_cycles_available128(0, region),
+                )?;
+                mark_region_writes(&mut caller, &feature_flags, dst, 16)
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "msg_cycles_refunded128", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, dst: i64| {
+                observe_execution_complexity(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::MSG_CYCLES_REFUNDED128,
+                        ..Default::default()
+                    },
+                    stable_memory_dirty_page_limit,
+                )?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "msg_cycles_refunded128",
+                    dst,
+                    16,
+                    |system_api, _, region| system_api.ic0_msg_cycles_refunded128(0, region),
+                )?;
+                mark_region_writes(&mut caller, &feature_flags, dst, 16)
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "msg_cycles_accept128", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>,
+                  amount_high: i64,
+                  amount_low: i64,
+                  dst: i64| {
+                observe_execution_complexity(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::MSG_CYCLES_ACCEPT128,
+                        ..Default::default()
+                    },
+                    stable_memory_dirty_page_limit,
+                )?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "msg_cycles_accept128",
+                    dst,
+                    16,
+                    |system_api, _, region| {
+                        system_api.ic0_msg_cycles_accept128(
+                            Cycles::from_parts(amount_high as u64, amount_low as u64),
+                            0,
+                            region,
+                        )
+                    },
+                )?;
+                mark_region_writes(&mut caller, &feature_flags, dst, 16)
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "certified_data_set", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, src: i64, size: i64| {
+                observe_execution_complexity(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::CERTIFIED_DATA_SET,
+                        ..Default::default()
+                    },
+                    stable_memory_dirty_page_limit,
+                )?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "certified_data_set",
+                    src,
+                    size,
+                    |system_api, size, region| system_api.ic0_certified_data_set(0, size, region),
+                )
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "data_certificate_copy", {
+            let log = log.clone();
+            move |mut caller: Caller<'_, StoreData<S>>, dst: i64, offset: i64, size: i64| {
+                observe_execution_complexity(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::DATA_CERTIFICATE_COPY,
+                        ..Default::default()
+                    },
+                    stable_memory_dirty_page_limit,
+                )?;
+                let offset = wasm64_arg_to_u32(&mut caller, "data_certificate_copy", offset)?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "data_certificate_copy",
+                    dst,
+                    size,
+                    |system_api, size, region| {
+                        system_api.ic0_data_certificate_copy(0, offset, size, region)
+                    },
+                )?;
+                mark_region_writes(&mut caller, &feature_flags, dst, size as u64)
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(API_VERSION_IC0, "is_controller", {
+            move |mut caller: Caller<'_, StoreData<S>>, src: i64, size: i64| {
+                charge_for_system_api_call(
+                    &log,
+                    canister_id,
+                    &mut caller,
+                    system_api_complexity::overhead::IS_CONTROLLER,
+                    size as u64,
+                    ExecutionComplexity {
+                        cpu: system_api_complexity::cpu::IS_CONTROLLER,
+                        ..Default::default()
+                    },
+                    NumInstructions::from(0),
+                    stable_memory_dirty_page_limit,
+                )?;
+                with_memory_region_and_system_api(
+                    &mut caller,
+                    "is_controller",
+                    src,
+                    size,
+                    |system_api, size, region| system_api.ic0_is_controller(0, size, region),
+                )
+            }
+        })
+        .unwrap();
+
+    linker
+}
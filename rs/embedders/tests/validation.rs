@@ -1,5 +1,5 @@
 use assert_matches::assert_matches;
-use ic_config::embedders::Config as EmbeddersConfig;
+use ic_config::{embedders::Config as EmbeddersConfig, flag_status::FlagStatus};
 use ic_embedders::{
     wasm_utils::{
         validate_and_instrument_for_testing,
@@ -958,3 +958,142 @@ fn complex_function_rejected() {
         })
     )
 }
+
+fn wasm64_config() -> EmbeddersConfig {
+    let mut config = EmbeddersConfig::default();
+    config.feature_flags.wasm64 = FlagStatus::Enabled;
+    config
+}
+
+#[test]
+fn can_reject_wasm64_memory_when_disabled() {
+    let wasm = wat2wasm(r#"(module (memory i64 1))"#).unwrap();
+    assert_matches!(
+        validate_wasm_binary(&wasm, &EmbeddersConfig::default()),
+        Err(WasmValidationError::InvalidMemorySection(_))
+    );
+}
+
+#[test]
+fn can_validate_wasm64_memory_with_64_bit_system_api() {
+    let wasm = wat2wasm(
+        r#"(module
+                (import "ic0" "msg_reply_data_append" (func $append (param i64 i64)))
+                (import "ic0" "msg_reply" (func $reply))
+                (func (export "canister_update grow")
+                    (drop (memory.grow (i64.const 1)))
+                    (memory.fill (i64.const 0) (i32.const 1) (i64.const 10))
+                    (i64.store (i64.const 8) (i64.const 42))
+                    (call $append (i64.const 0) (i64.const 16))
+                    (call $reply))
+                (memory i64 1)
+                (data (i64.const 0) "abc"))"#,
+    )
+    .unwrap();
+    assert!(validate_wasm_binary(&wasm, &wasm64_config()).is_ok());
+}
+
+#[test]
+fn can_reject_wasm64_memory_with_32_bit_system_api() {
+    let wasm = wat2wasm(
+        r#"(module
+                (import "ic0" "msg_reply_data_append" (func $append (param i32 i32)))
+                (memory i64 1))"#,
+    )
+    .unwrap();
+    assert_matches!(
+        validate_wasm_binary(&wasm, &wasm64_config()),
+        Err(WasmValidationError::InvalidFunctionSignature(_))
+    );
+}
+
+#[test]
+fn can_reject_wasm64_memory_larger_than_maximum() {
+    let wasm = wat2wasm(r#"(module (memory i64 1000000))"#).unwrap();
+    assert_matches!(
+        validate_wasm_binary(&wasm, &wasm64_config()),
+        Err(WasmValidationError::InvalidMemorySection(_))
+    );
+}
+
+fn direct_stable_memory_config() -> EmbeddersConfig {
+    let mut config = EmbeddersConfig::default();
+    config.feature_flags.wasm_native_stable_memory = FlagStatus::Enabled;
+    config.feature_flags.direct_stable_memory_access = FlagStatus::Enabled;
+    config
+}
+
+#[test]
+fn can_validate_direct_stable_memory_access() {
+    let wasm = wat2wasm(
+        r#"(module
+                (import "ic0" "stable_memory" (memory $stable i64 0))
+                (func (export "canister_update test")
+                    (i64.store $stable (i64.const 0) (i64.load $heap (i32.const 0)))
+                    (memory.copy $heap $stable (i32.const 0) (i64.const 0) (i32.const 10)))
+                (memory $heap 1))"#,
+    )
+    .unwrap();
+    assert!(validate_wasm_binary(&wasm, &direct_stable_memory_config()).is_ok());
+}
+
+#[test]
+fn can_reject_stable_memory_import_when_disabled() {
+    let wasm = wat2wasm(
+        r#"(module
+                (import "ic0" "stable_memory" (memory i64 0))
+                (memory 1))"#,
+    )
+    .unwrap();
+    let mut config = direct_stable_memory_config();
+    config.feature_flags.wasm_native_stable_memory = FlagStatus::Disabled;
+    assert_matches!(
+        validate_wasm_binary(&wasm, &config),
+        Err(WasmValidationError::InvalidImportSection(_))
+    );
+}
+
+#[test]
+fn can_reject_stable_memory_import_with_limits() {
+    let wasm = wat2wasm(
+        r#"(module
+                (import "ic0" "stable_memory" (memory i64 1 10))
+                (memory 1))"#,
+    )
+    .unwrap();
+    assert_matches!(
+        validate_wasm_binary(&wasm, &direct_stable_memory_config()),
+        Err(WasmValidationError::InvalidImportSection(_))
+    );
+}
+
+#[test]
+fn can_reject_memory_grow_on_imported_stable_memory() {
+    let wasm = wat2wasm(
+        r#"(module
+                (import "ic0" "stable_memory" (memory $stable i64 0))
+                (func (export "canister_update test")
+                    (drop (memory.grow $stable (i64.const 1))))
+                (memory $heap 1))"#,
+    )
+    .unwrap();
+    assert_matches!(
+        validate_wasm_binary(&wasm, &direct_stable_memory_config()),
+        Err(WasmValidationError::InvalidCodeSection(_))
+    );
+}
+
+#[test]
+fn can_reject_data_segment_in_imported_stable_memory() {
+    let wasm = wat2wasm(
+        r#"(module
+                (import "ic0" "stable_memory" (memory $stable i64 0))
+                (memory $heap 1)
+                (data (memory $stable) (i64.const 0) "abc"))"#,
+    )
+    .unwrap();
+    assert_matches!(
+        validate_wasm_binary(&wasm, &direct_stable_memory_config()),
+        Err(WasmValidationError::InvalidDataSection(_))
+    );
+}
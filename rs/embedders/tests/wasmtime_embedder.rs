@@ -1605,4 +1605,114 @@ mod test {
             .build();
         instance.run(func_ref("write_to_last_page")).unwrap();
     }
+
+    fn direct_stable_memory_config() -> ic_config::embedders::Config {
+        let mut config = ic_config::embedders::Config::default();
+        config.feature_flags.wasm_native_stable_memory =
+            ic_config::flag_status::FlagStatus::Enabled;
+        config.feature_flags.direct_stable_memory_access =
+            ic_config::flag_status::FlagStatus::Enabled;
+        config
+    }
+
+    const DIRECT_STABLE_MEMORY_WAT: &str = r#"
+        (module
+            (import "ic0" "stable_memory" (memory $stable i64 0))
+            (import "ic0" "stable_grow"
+                (func $ic0_stable_grow (param $pages i32) (result i32)))
+            (import "ic0" "stable64_read"
+                (func $ic0_stable64_read (param $dst i64) (param $offset i64) (param $size i64)))
+            (import "ic0" "trap" (func $ic_trap (param i32 i32)))
+
+            (func (export "canister_update write_and_read")
+                (drop (call $ic0_stable_grow (i32.const 1)))
+                (i32.store8 $stable (i64.const 100) (i32.const 72))
+                (i32.store8 $stable (i64.const 101) (i32.const 101))
+                (i32.store8 $stable (i64.const 102) (i32.const 108))
+                (i32.store8 $stable (i64.const 103) (i32.const 108))
+                (i32.store8 $stable (i64.const 104) (i32.const 111))
+                (call $ic0_stable64_read (i64.const 0) (i64.const 100) (i64.const 5))
+                (call $ic_trap (i32.const 0) (i32.const 5))
+            )
+
+            (func (export "canister_update write_two_pages")
+                (drop (call $ic0_stable_grow (i32.const 1)))
+                (i64.store $stable (i64.const 0) (i64.const 42))
+                (i64.store $stable (i64.const 12288) (i64.const 42))
+            )
+
+            (func (export "canister_update write_out_of_bounds")
+                (drop (call $ic0_stable_grow (i32.const 1)))
+                (i64.store $stable (i64.const 65536) (i64.const 42))
+            )
+            (memory $heap (export "memory") 1)
+        )"#;
+
+    #[test]
+    fn direct_stable_memory_write_and_read() {
+        let mut instance = WasmtimeInstanceBuilder::new()
+            .with_config(direct_stable_memory_config())
+            .with_wat(DIRECT_STABLE_MEMORY_WAT)
+            .build();
+        let err = instance
+            .run(FuncRef::Method(WasmMethod::Update(
+                "write_and_read".to_string(),
+            )))
+            .unwrap_err();
+        assert_eq!(err, HypervisorError::CalledTrap("Hello".to_string()));
+    }
+
+    #[test]
+    fn direct_stable_memory_writes_are_tracked() {
+        let mut instance = WasmtimeInstanceBuilder::new()
+            .with_config(direct_stable_memory_config())
+            .with_wat(DIRECT_STABLE_MEMORY_WAT)
+            .build();
+        let result = instance
+            .run(FuncRef::Method(WasmMethod::Update(
+                "write_two_pages".to_string(),
+            )))
+            .unwrap();
+        let mut stable_memory_dirty_pages = result.stable_memory_dirty_pages;
+        stable_memory_dirty_pages.sort();
+        assert_eq!(
+            stable_memory_dirty_pages,
+            vec![
+                ic_replicated_state::PageIndex::new(0),
+                ic_replicated_state::PageIndex::new(3)
+            ]
+        );
+    }
+
+    #[test]
+    fn direct_stable_memory_writes_respect_dirty_page_limit() {
+        let config = ic_config::embedders::Config {
+            stable_memory_dirty_page_limit: ic_types::NumPages::new(1),
+            ..direct_stable_memory_config()
+        };
+        let mut instance = WasmtimeInstanceBuilder::new()
+            .with_config(config)
+            .with_wat(DIRECT_STABLE_MEMORY_WAT)
+            .build();
+        let err = instance
+            .run(FuncRef::Method(WasmMethod::Update(
+                "write_two_pages".to_string(),
+            )))
+            .unwrap_err();
+        assert_matches!(err, HypervisorError::MemoryAccessLimitExceeded(_));
+    }
+
+    #[test]
+    fn direct_stable_memory_access_beyond_stable_size_traps() {
+        let mut instance = WasmtimeInstanceBuilder::new()
+            .with_config(direct_stable_memory_config())
+            .with_wat(DIRECT_STABLE_MEMORY_WAT)
+            .build();
+        let err = instance
+            .run(FuncRef::Method(WasmMethod::Update(
+                "write_out_of_bounds".to_string(),
+            )))
+            .unwrap_err();
+        assert_eq!(err, HypervisorError::Trapped(TrapCode::HeapOutOfBounds));
+    }
 }
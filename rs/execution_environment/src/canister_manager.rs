@@ -10,12 +10,12 @@ use crate::{
     util::GOVERNANCE_CANISTER_ID,
 };
 use ic_base_types::NumSeconds;
-use ic_config::flag_status::FlagStatus;
+use ic_config::{execution_environment::DEFAULT_RESERVED_BALANCE_LIMIT, flag_status::FlagStatus};
 use ic_cycles_account_manager::CyclesAccountManager;
 use ic_error_types::{ErrorCode, RejectCode, UserError};
 use ic_ic00_types::{
     CanisterChangeOrigin, CanisterInstallMode, CanisterStatusResultV2, CanisterStatusType,
-    InstallCodeArgs, Method as Ic00Method,
+    InstallCodeArgs, LogVisibility, Method as Ic00Method,
 };
 use ic_interfaces::execution_environment::{
     CanisterOutOfCyclesError, HypervisorError, IngressHistoryWriter, SubnetAvailableMemory,
@@ -24,7 +24,7 @@ use ic_interfaces::messages::CanisterCall;
 use ic_logger::{error, fatal, info, ReplicaLogger};
 use ic_registry_provisional_whitelist::ProvisionalWhitelist;
 use ic_registry_subnet_type::SubnetType;
-use ic_replicated_state::canister_state::system_state::CyclesUseCase;
+use ic_replicated_state::canister_state::system_state::{CyclesUseCase, ReservationError};
 use ic_replicated_state::{
     CallOrigin, CanisterState, CanisterStatus, NetworkTopology, ReplicatedState, SchedulerState,
     SystemState,
@@ -38,7 +38,7 @@ use ic_types::{
     messages::{Payload, RejectContext, Response as CanisterResponse, StopCanisterContext},
     CanisterId, CanisterTimer, ComputeAllocation, Cycles, InvalidComputeAllocationError,
     InvalidMemoryAllocationError, InvalidQueryAllocationError, MemoryAllocation, NumBytes,
-    PrincipalId, QueryAllocation, SubnetId, Time,
+    PrincipalId, QueryAllocation, SubnetId, Time, MAX_MEMORY_ALLOCATION,
 };
 use ic_wasm_types::CanisterModule;
 use num_traits::cast::ToPrimitive;
@@ -219,6 +219,19 @@ impl TryFrom<(CanisterChangeOrigin, InstallCodeArgs)> for InstallCodeContext {
 
     fn try_from(input: (CanisterChangeOrigin, InstallCodeArgs)) -> Result<Self, Self::Error> {
         let (origin, args) = input;
+        InstallCodeContext::try_from((origin, args, MAX_MEMORY_ALLOCATION))
+    }
+}
+
+/// Decodes the arguments, accepting memory allocations of up to the given
+/// maximum.
+impl TryFrom<(CanisterChangeOrigin, InstallCodeArgs, NumBytes)> for InstallCodeContext {
+    type Error = InstallCodeContextError;
+
+    fn try_from(
+        input: (CanisterChangeOrigin, InstallCodeArgs, NumBytes),
+    ) -> Result<Self, Self::Error> {
+        let (origin, args, max_memory_allocation) = input;
         let canister_id = CanisterId::new(args.canister_id).map_err(|err| {
             InstallCodeContextError::InvalidCanisterId(format!(
                 "Converting canister id {} failed with {}",
@@ -236,11 +249,14 @@ impl TryFrom<(CanisterChangeOrigin, InstallCodeArgs)> for InstallCodeContext {
             None => None,
         };
         let memory_allocation = match args.memory_allocation {
-            Some(ma) => Some(MemoryAllocation::try_from(NumBytes::from(
-                ma.0.to_u64().ok_or_else(|| {
-                    InstallCodeContextError::MemoryAllocation(InvalidMemoryAllocationError::new(ma))
-                })?,
-            ))?),
+            Some(ma) => Some(MemoryAllocation::try_from_with_max(
+                NumBytes::from(ma.0.to_u64().ok_or_else(|| {
+                    InstallCodeContextError::MemoryAllocation(
+                        InvalidMemoryAllocationError::with_max(ma, max_memory_allocation),
+                    )
+                })?),
+                max_memory_allocation,
+            )?),
             None => None,
         };
 
@@ -463,10 +479,32 @@ impl CanisterManager {
         if let Some(freezing_threshold) = settings.freezing_threshold {
             canister.system_state.freeze_threshold = freezing_threshold;
         }
+        if let Some(reserved_cycles_limit) = settings.reserved_cycles_limit {
+            canister
+                .system_state
+                .set_reserved_balance_limit(reserved_cycles_limit);
+        }
+        if let Some(wasm_memory_limit) = settings.wasm_memory_limit {
+            canister.system_state.wasm_memory_limit = if wasm_memory_limit.get() == 0 {
+                None
+            } else {
+                Some(wasm_memory_limit)
+            };
+        }
+        if let Some(log_visibility) = settings.log_visibility {
+            canister.system_state.log_visibility = log_visibility;
+        }
     }
 
     /// Tries to apply the requested settings on the canister identified by
     /// `canister_id`.
+    ///
+    /// If the requested memory allocation increases the memory reserved by
+    /// the canister while the subnet memory is saturated, then cycles are
+    /// moved into the reserved balance of the canister to cover future
+    /// storage payments. The settings are not applied if that fails or if the
+    /// main balance left after the reservation is below the freezing threshold
+    /// that results from the new settings.
     pub(crate) fn update_settings(
         &self,
         _timestamp_nanos: Time,
@@ -474,6 +512,7 @@ impl CanisterManager {
         settings: CanisterSettings,
         canister: &mut CanisterState,
         round_limits: &mut RoundLimits,
+        subnet_size: usize,
     ) -> Result<(), CanisterManagerError> {
         let sender = origin.origin();
 
@@ -492,6 +531,15 @@ impl CanisterManager {
             &self.config,
         )?;
 
+        if let Some(limit) = settings.reserved_cycles_limit() {
+            if canister.system_state.reserved_balance() > limit {
+                return Err(CanisterManagerError::ReservedCyclesLimitIsTooLow {
+                    cycles: canister.system_state.reserved_balance(),
+                    limit,
+                });
+            }
+        }
+
         let validated_settings =
             ValidatedCanisterSettings::try_from((settings, self.config.max_controllers))?;
 
@@ -503,6 +551,54 @@ impl CanisterManager {
             .max(old_usage);
         let old_compute_allocation = canister.scheduler_state.compute_allocation.as_percent();
 
+        if let Some(memory_allocation) = validated_settings.memory_allocation {
+            let new_mem = memory_allocation.bytes().max(old_usage);
+            if new_mem > old_mem {
+                let reservation_cycles = self.cycles_account_manager.storage_reservation_cycles(
+                    new_mem - old_mem,
+                    &self
+                        .hypervisor
+                        .subnet_memory_saturation(&round_limits.subnet_available_memory),
+                    subnet_size,
+                );
+                if reservation_cycles > Cycles::zero() {
+                    let threshold = self.cycles_account_manager.freeze_threshold_cycles(
+                        validated_settings
+                            .freezing_threshold
+                            .unwrap_or(canister.system_state.freeze_threshold),
+                        memory_allocation,
+                        old_usage,
+                        validated_settings
+                            .compute_allocation
+                            .unwrap_or(canister.scheduler_state.compute_allocation),
+                        subnet_size,
+                    );
+                    let available = canister.system_state.debited_balance();
+                    if available < reservation_cycles + threshold {
+                        return Err(
+                            CanisterManagerError::StorageReservationBelowFreezingThreshold {
+                                canister_id: canister.canister_id(),
+                                reservation: reservation_cycles,
+                                available,
+                                threshold,
+                            },
+                        );
+                    }
+                }
+                let limit = validated_settings
+                    .reserved_cycles_limit
+                    .or_else(|| canister.system_state.reserved_balance_limit())
+                    .unwrap_or(DEFAULT_RESERVED_BALANCE_LIMIT);
+                canister
+                    .system_state
+                    .reserve_cycles(reservation_cycles, limit)
+                    .map_err(|err| CanisterManagerError::StorageReservationFailed {
+                        canister_id: canister.canister_id(),
+                        err,
+                    })?;
+            }
+        }
+
         self.do_update_settings(validated_settings, canister);
 
         let new_compute_allocation = canister.scheduler_state.compute_allocation.as_percent();
@@ -951,6 +1047,10 @@ impl CanisterManager {
         let compute_allocation = canister.scheduler_state.compute_allocation;
         let memory_allocation = canister.memory_allocation();
         let freeze_threshold = canister.system_state.freeze_threshold;
+        let reserved_cycles_limit = canister
+            .system_state
+            .reserved_balance_limit()
+            .unwrap_or(DEFAULT_RESERVED_BALANCE_LIMIT);
 
         Ok(CanisterStatusResultV2::new(
             canister.status(),
@@ -973,6 +1073,13 @@ impl CanisterManager {
                     subnet_size,
                 )
                 .get(),
+            canister.system_state.reserved_balance().get(),
+            reserved_cycles_limit.get(),
+            canister
+                .system_state
+                .wasm_memory_limit
+                .map(|limit| limit.get()),
+            canister.system_state.log_visibility,
         ))
     }
 
@@ -986,6 +1093,7 @@ impl CanisterManager {
         new_controller: PrincipalId,
         state: &mut ReplicatedState,
         round_limits: &mut RoundLimits,
+        subnet_size: usize,
     ) -> Result<(), CanisterManagerError> {
         let canister = state
             .canister_state_mut(&canister_id)
@@ -994,7 +1102,14 @@ impl CanisterManager {
         let settings = CanisterSettingsBuilder::new()
             .with_controller(new_controller)
             .build();
-        self.update_settings(timestamp_nanos, origin, settings, canister, round_limits)
+        self.update_settings(
+            timestamp_nanos,
+            origin,
+            settings,
+            canister,
+            round_limits,
+            subnet_size,
+        )
     }
 
     /// Permanently deletes a canister from `ReplicatedState`.
@@ -1356,6 +1471,20 @@ pub(crate) enum CanisterManagerError {
     CanisterNotHostedBySubnet {
         message: String,
     },
+    ReservedCyclesLimitIsTooLow {
+        cycles: Cycles,
+        limit: Cycles,
+    },
+    StorageReservationFailed {
+        canister_id: CanisterId,
+        err: ReservationError,
+    },
+    StorageReservationBelowFreezingThreshold {
+        canister_id: CanisterId,
+        reservation: Cycles,
+        available: Cycles,
+        threshold: Cycles,
+    },
 }
 
 impl From<CanisterManagerError> for UserError {
@@ -1514,6 +1643,24 @@ impl From<CanisterManagerError> for UserError {
                     format!("Unsuccessful validation of specified ID: {}", message),
                 )
             }
+            ReservedCyclesLimitIsTooLow { cycles, limit } => {
+                Self::new(
+                    ErrorCode::CanisterContractViolation,
+                    format!("Cannot set the reserved cycles limit {} below the reserved cycles balance of the canister {}.", limit, cycles),
+                )
+            }
+            StorageReservationFailed { canister_id, err } => {
+                Self::new(
+                    ErrorCode::CanisterOutOfCycles,
+                    format!("Canister {} cannot reserve cycles for the requested memory allocation: {}.", canister_id, err),
+                )
+            }
+            StorageReservationBelowFreezingThreshold { canister_id, reservation, available, threshold } => {
+                Self::new(
+                    ErrorCode::CanisterOutOfCycles,
+                    format!("Canister {} cannot reserve {} cycles for the requested memory allocation: its balance of {} cycles would fall below the freezing threshold of {} cycles.", canister_id, reservation, available, threshold),
+                )
+            }
         }
     }
 }
@@ -1620,6 +1767,9 @@ struct ValidatedCanisterSettings {
     pub compute_allocation: Option<ComputeAllocation>,
     pub memory_allocation: Option<MemoryAllocation>,
     pub freezing_threshold: Option<NumSeconds>,
+    pub reserved_cycles_limit: Option<Cycles>,
+    pub wasm_memory_limit: Option<NumBytes>,
+    pub log_visibility: Option<LogVisibility>,
 }
 
 impl TryFrom<(CanisterSettings, usize)> for ValidatedCanisterSettings {
@@ -1655,6 +1805,9 @@ impl TryFrom<(CanisterSettings, usize)> for ValidatedCanisterSettings {
             compute_allocation: settings.compute_allocation(),
             memory_allocation: settings.memory_allocation(),
             freezing_threshold: settings.freezing_threshold(),
+            reserved_cycles_limit: settings.reserved_cycles_limit(),
+            wasm_memory_limit: settings.wasm_memory_limit(),
+            log_visibility: settings.log_visibility(),
         })
     }
 }
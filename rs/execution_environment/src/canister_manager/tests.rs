@@ -2101,6 +2101,7 @@ fn set_controller_with_incorrect_controller() {
                 new_controller,
                 &mut state,
                 &mut round_limits,
+                SMALL_APP_SUBNET_MAX_SIZE,
             ),
             Err(CanisterManagerError::CanisterInvalidController {
                 canister_id,
@@ -2145,7 +2146,8 @@ fn set_controller_with_correct_controller() {
                 canister_id,
                 new_controller,
                 &mut state,
-                &mut round_limits
+                &mut round_limits,
+                SMALL_APP_SUBNET_MAX_SIZE,
             )
             .is_ok());
 
@@ -3527,6 +3529,7 @@ fn lower_memory_allocation_than_usage_fails() {
                 settings,
                 canister,
                 &mut round_limits,
+                SMALL_APP_SUBNET_MAX_SIZE,
             ),
             Err(CanisterManagerError::NotEnoughMemoryAllocationGiven { .. })
         );
@@ -3601,6 +3604,7 @@ fn test_install_when_updating_memory_allocation_via_canister_settings() {
                 settings,
                 canister,
                 &mut round_limits,
+                SMALL_APP_SUBNET_MAX_SIZE,
             )
             .unwrap();
 
@@ -3723,6 +3727,7 @@ fn test_upgrade_when_updating_memory_allocation_via_canister_settings() {
                 settings,
                 canister,
                 &mut round_limits,
+                SMALL_APP_SUBNET_MAX_SIZE,
             )
             .unwrap();
 
@@ -3828,6 +3833,7 @@ fn test_install_when_setting_memory_allocation_to_zero() {
                 canister,
                 //memory_allocation_used,
                 &mut round_limits,
+                SMALL_APP_SUBNET_MAX_SIZE,
             )
             .unwrap();
 
@@ -3914,6 +3920,7 @@ fn test_upgrade_when_setting_memory_allocation_to_zero() {
                 settings,
                 canister,
                 &mut round_limits,
+                SMALL_APP_SUBNET_MAX_SIZE,
             )
             .unwrap();
 
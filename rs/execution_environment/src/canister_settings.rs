@@ -1,9 +1,9 @@
 use ic_base_types::{NumBytes, NumSeconds};
 use ic_error_types::{ErrorCode, UserError};
-use ic_ic00_types::CanisterSettingsArgs;
+use ic_ic00_types::{CanisterSettingsArgs, LogVisibility};
 use ic_types::{
-    ComputeAllocation, InvalidComputeAllocationError, InvalidMemoryAllocationError,
-    MemoryAllocation, PrincipalId,
+    ComputeAllocation, Cycles, InvalidComputeAllocationError, InvalidMemoryAllocationError,
+    MemoryAllocation, PrincipalId, MAX_MEMORY_ALLOCATION,
 };
 use num_traits::cast::ToPrimitive;
 use std::convert::TryFrom;
@@ -16,15 +16,23 @@ pub(crate) struct CanisterSettings {
     pub(crate) compute_allocation: Option<ComputeAllocation>,
     pub(crate) memory_allocation: Option<MemoryAllocation>,
     pub(crate) freezing_threshold: Option<NumSeconds>,
+    pub(crate) reserved_cycles_limit: Option<Cycles>,
+    /// A limit of zero bytes removes the Wasm memory limit.
+    pub(crate) wasm_memory_limit: Option<NumBytes>,
+    pub(crate) log_visibility: Option<LogVisibility>,
 }
 
 impl CanisterSettings {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         controller: Option<PrincipalId>,
         controllers: Option<Vec<PrincipalId>>,
         compute_allocation: Option<ComputeAllocation>,
         memory_allocation: Option<MemoryAllocation>,
         freezing_threshold: Option<NumSeconds>,
+        reserved_cycles_limit: Option<Cycles>,
+        wasm_memory_limit: Option<NumBytes>,
+        log_visibility: Option<LogVisibility>,
     ) -> Self {
         Self {
             controller,
@@ -32,6 +40,9 @@ impl CanisterSettings {
             compute_allocation,
             memory_allocation,
             freezing_threshold,
+            reserved_cycles_limit,
+            wasm_memory_limit,
+            log_visibility,
         }
     }
 
@@ -54,12 +65,35 @@ impl CanisterSettings {
     pub fn freezing_threshold(&self) -> Option<NumSeconds> {
         self.freezing_threshold
     }
+
+    pub fn reserved_cycles_limit(&self) -> Option<Cycles> {
+        self.reserved_cycles_limit
+    }
+
+    pub fn wasm_memory_limit(&self) -> Option<NumBytes> {
+        self.wasm_memory_limit
+    }
+
+    pub fn log_visibility(&self) -> Option<LogVisibility> {
+        self.log_visibility
+    }
 }
 
 impl TryFrom<CanisterSettingsArgs> for CanisterSettings {
     type Error = UpdateSettingsError;
 
     fn try_from(input: CanisterSettingsArgs) -> Result<Self, Self::Error> {
+        CanisterSettings::try_from((input, MAX_MEMORY_ALLOCATION))
+    }
+}
+
+/// Decodes the settings, accepting memory allocations of up to the given
+/// maximum.
+impl TryFrom<(CanisterSettingsArgs, NumBytes)> for CanisterSettings {
+    type Error = UpdateSettingsError;
+
+    fn try_from(input: (CanisterSettingsArgs, NumBytes)) -> Result<Self, Self::Error> {
+        let (input, max_memory_allocation) = input;
         let controller = input.get_controller();
         let compute_allocation = match input.compute_allocation {
             Some(ca) => Some(ComputeAllocation::try_from(ca.0.to_u64().ok_or_else(
@@ -69,11 +103,15 @@ impl TryFrom<CanisterSettingsArgs> for CanisterSettings {
         };
 
         let memory_allocation = match input.memory_allocation {
-            Some(ma) => Some(MemoryAllocation::try_from(NumBytes::from(
-                ma.0.to_u64().ok_or_else(|| {
-                    UpdateSettingsError::MemoryAllocation(InvalidMemoryAllocationError::new(ma))
-                })?,
-            ))?),
+            Some(ma) => Some(MemoryAllocation::try_from_with_max(
+                NumBytes::from(ma.0.to_u64().ok_or_else(|| {
+                    UpdateSettingsError::MemoryAllocation(InvalidMemoryAllocationError::with_max(
+                        ma,
+                        max_memory_allocation,
+                    ))
+                })?),
+                max_memory_allocation,
+            )?),
             None => None,
         };
 
@@ -84,12 +122,29 @@ impl TryFrom<CanisterSettingsArgs> for CanisterSettings {
             None => None,
         };
 
+        let reserved_cycles_limit = match input.reserved_cycles_limit {
+            Some(limit) => Some(Cycles::from(limit.0.to_u128().ok_or(
+                UpdateSettingsError::ReservedCyclesLimitOutOfRange { provided: limit },
+            )?)),
+            None => None,
+        };
+
+        let wasm_memory_limit = match input.wasm_memory_limit {
+            Some(limit) => Some(NumBytes::from(limit.0.to_u64().ok_or(
+                UpdateSettingsError::WasmMemoryLimitOutOfRange { provided: limit },
+            )?)),
+            None => None,
+        };
+
         Ok(CanisterSettings::new(
             controller,
             input.controllers,
             compute_allocation,
             memory_allocation,
             freezing_threshold,
+            reserved_cycles_limit,
+            wasm_memory_limit,
+            input.log_visibility,
         ))
     }
 }
@@ -111,6 +166,9 @@ pub(crate) struct CanisterSettingsBuilder {
     compute_allocation: Option<ComputeAllocation>,
     memory_allocation: Option<MemoryAllocation>,
     freezing_threshold: Option<NumSeconds>,
+    reserved_cycles_limit: Option<Cycles>,
+    wasm_memory_limit: Option<NumBytes>,
+    log_visibility: Option<LogVisibility>,
 }
 
 #[allow(dead_code)]
@@ -122,6 +180,9 @@ impl CanisterSettingsBuilder {
             compute_allocation: None,
             memory_allocation: None,
             freezing_threshold: None,
+            reserved_cycles_limit: None,
+            wasm_memory_limit: None,
+            log_visibility: None,
         }
     }
 
@@ -132,6 +193,9 @@ impl CanisterSettingsBuilder {
             compute_allocation: self.compute_allocation,
             memory_allocation: self.memory_allocation,
             freezing_threshold: self.freezing_threshold,
+            reserved_cycles_limit: self.reserved_cycles_limit,
+            wasm_memory_limit: self.wasm_memory_limit,
+            log_visibility: self.log_visibility,
         }
     }
 
@@ -169,12 +233,35 @@ impl CanisterSettingsBuilder {
             ..self
         }
     }
+
+    pub fn with_reserved_cycles_limit(self, reserved_cycles_limit: Cycles) -> Self {
+        Self {
+            reserved_cycles_limit: Some(reserved_cycles_limit),
+            ..self
+        }
+    }
+
+    pub fn with_wasm_memory_limit(self, wasm_memory_limit: NumBytes) -> Self {
+        Self {
+            wasm_memory_limit: Some(wasm_memory_limit),
+            ..self
+        }
+    }
+
+    pub fn with_log_visibility(self, log_visibility: LogVisibility) -> Self {
+        Self {
+            log_visibility: Some(log_visibility),
+            ..self
+        }
+    }
 }
 
 pub enum UpdateSettingsError {
     ComputeAllocation(InvalidComputeAllocationError),
     MemoryAllocation(InvalidMemoryAllocationError),
     FreezingThresholdOutOfRange { provided: candid::Nat },
+    ReservedCyclesLimitOutOfRange { provided: candid::Nat },
+    WasmMemoryLimitOutOfRange { provided: candid::Nat },
 }
 
 impl From<UpdateSettingsError> for UserError {
@@ -203,6 +290,20 @@ impl From<UpdateSettingsError> for UserError {
                     provided
                 ),
             ),
+            UpdateSettingsError::ReservedCyclesLimitOutOfRange { provided } => UserError::new(
+                ErrorCode::CanisterContractViolation,
+                format!(
+                    "Reserved cycles limit expected to be in the range of [0..2^128-1], got {}",
+                    provided
+                ),
+            ),
+            UpdateSettingsError::WasmMemoryLimitOutOfRange { provided } => UserError::new(
+                ErrorCode::CanisterContractViolation,
+                format!(
+                    "Wasm memory limit expected to be in the range of [0..2^64-1], got {}",
+                    provided
+                ),
+            ),
         }
     }
 }
@@ -6,6 +6,8 @@ use lazy_static::lazy_static;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use ic_base_types::{CanisterId, NumBytes, SubnetId};
+use ic_config::execution_environment::DEFAULT_RESERVED_BALANCE_LIMIT;
+use ic_cycles_account_manager::{CyclesAccountManager, ResourceSaturation};
 use ic_embedders::wasm_executor::{CanisterStateChanges, SliceExecutionOutput};
 use ic_error_types::{ErrorCode, RejectCode, UserError};
 use ic_ic00_types::CanisterStatusType;
@@ -15,8 +17,8 @@ use ic_interfaces::execution_environment::{
 use ic_interfaces::messages::{CanisterCall, CanisterCallOrTask};
 use ic_logger::{error, fatal, warn, ReplicaLogger};
 use ic_replicated_state::{
-    CallContext, CallContextAction, CallOrigin, CanisterState, ExecutionState, NetworkTopology,
-    SystemState,
+    canister_state::system_state::ReservationError, CallContext, CallContextAction, CallOrigin,
+    CanisterState, ExecutionState, NetworkTopology, SystemState,
 };
 use ic_system_api::sandbox_safe_system_state::SystemStateChanges;
 use ic_types::ingress::{IngressState, IngressStatus, WasmResult};
@@ -398,14 +400,40 @@ pub fn update_round_limits(round_limits: &mut RoundLimits, slice: &SliceExecutio
         &round_limits.execution_complexity - &slice.execution_complexity;
 }
 
+/// Reserves cycles for the future storage payments of `allocated_bytes` of
+/// newly allocated best-effort memory. The amount depends on the subnet memory
+/// saturation before the allocation and is zero while the subnet memory usage
+/// stays below the threshold.
+pub(crate) fn reserve_storage_cycles(
+    allocated_bytes: NumBytes,
+    subnet_memory_saturation: &ResourceSaturation,
+    subnet_size: usize,
+    cycles_account_manager: &CyclesAccountManager,
+    system_state: &mut SystemState,
+) -> Result<(), ReservationError> {
+    let reservation_cycles = cycles_account_manager.storage_reservation_cycles(
+        allocated_bytes,
+        subnet_memory_saturation,
+        subnet_size,
+    );
+    let limit = system_state
+        .reserved_balance_limit()
+        .unwrap_or(DEFAULT_RESERVED_BALANCE_LIMIT);
+    system_state.reserve_cycles(reservation_cycles, limit)
+}
+
 /// Tries to apply the given canister changes to the given system state and
 /// subnet available memory. In case of an error, the partially applied changes
 /// are not undone.
+#[allow(clippy::too_many_arguments)]
 fn try_apply_canister_state_changes(
     system_state_changes: SystemStateChanges,
     output: &WasmExecutionOutput,
     system_state: &mut SystemState,
     subnet_available_memory: &mut SubnetAvailableMemory,
+    subnet_memory_saturation: &ResourceSaturation,
+    subnet_size: usize,
+    cycles_account_manager: &CyclesAccountManager,
     time: Time,
     network_topology: &NetworkTopology,
     subnet_id: SubnetId,
@@ -422,7 +450,37 @@ fn try_apply_canister_state_changes(
         MemoryAllocation::Reserved(_) => (),
     }
 
-    system_state_changes.apply_changes(time, system_state, network_topology, subnet_id, log)
+    system_state_changes.apply_changes(time, system_state, network_topology, subnet_id, log)?;
+
+    // Memory covered by a memory allocation has already reserved cycles when
+    // the allocation was set, so only best-effort growth reserves here.
+    if let MemoryAllocation::BestEffort = system_state.memory_allocation {
+        reserve_storage_cycles(
+            output.allocated_bytes,
+            subnet_memory_saturation,
+            subnet_size,
+            cycles_account_manager,
+            system_state,
+        )
+        .map_err(|err| match err {
+            ReservationError::InsufficientCycles {
+                requested,
+                available,
+            } => HypervisorError::InsufficientCyclesInMemoryGrow {
+                bytes: output.allocated_bytes,
+                available,
+                requested,
+            },
+            ReservationError::ReservedLimitExceeded { requested, limit } => {
+                HypervisorError::ReservedCyclesLimitExceededInMemoryGrow {
+                    bytes: output.allocated_bytes,
+                    requested,
+                    limit,
+                }
+            }
+        })?;
+    }
+    Ok(())
 }
 
 /// Applies canister state change after Wasm execution if possible.
@@ -431,15 +489,21 @@ fn try_apply_canister_state_changes(
 /// Potential causes of failure:
 /// - Changes in the environment such as subnet available memory while the
 ///   long-execution with deterministic time slicing was in progress.
+/// - The canister cannot reserve cycles for the memory it allocated while the
+///   subnet memory usage is above the threshold.
 /// - A mismatch between checks dones by the Wasm executor and checks done when
 ///   applying the changes due to a bug.
 /// - An escape from the Wasm sandbox that corrupts the execution output.
+#[allow(clippy::too_many_arguments)]
 pub fn apply_canister_state_changes(
     canister_state_changes: Option<CanisterStateChanges>,
     execution_state: &mut ExecutionState,
     system_state: &mut SystemState,
     output: &mut WasmExecutionOutput,
     round_limits: &mut RoundLimits,
+    subnet_memory_saturation: &ResourceSaturation,
+    subnet_size: usize,
+    cycles_account_manager: &CyclesAccountManager,
     time: Time,
     network_topology: &NetworkTopology,
     subnet_id: SubnetId,
@@ -461,6 +525,9 @@ pub fn apply_canister_state_changes(
             output,
             system_state,
             &mut round_limits.subnet_available_memory,
+            subnet_memory_saturation,
+            subnet_size,
+            cycles_account_manager,
             time,
             network_topology,
             subnet_id,
@@ -488,6 +555,11 @@ pub fn apply_canister_state_changes(
                     HypervisorError::OutOfMemory => {
                         warn!(log, "Failed to apply state changes due to DTS: {}", err)
                     }
+                    HypervisorError::InsufficientCyclesInMemoryGrow { .. }
+                    | HypervisorError::ReservedCyclesLimitExceededInMemoryGrow { .. } => {
+                        // The canister cannot pay for the storage it allocated.
+                        // This is an expected user error.
+                    }
                     _ => {
                         // TODO(RUN-299): Increment a critical error counter here.
                         error!(
@@ -28,6 +28,7 @@ use crate::{
     canister_manager::{
         CanisterManagerError, CanisterMgrConfig, DtsInstallCodeResult, InstallCodeResult,
     },
+    execution::common::reserve_storage_cycles,
     execution_environment::RoundContext,
     CompilationCostHandling, RoundLimits,
 };
@@ -259,6 +260,29 @@ impl InstallCodeHelper {
             round_limits.compute_allocation_used = others + new_compute_allocation.as_percent();
         }
 
+        // Reserve cycles for the future storage payments of the newly
+        // allocated memory. The subnet memory saturation is computed before
+        // the allocation is committed to `round_limits`.
+        let subnet_memory_saturation = round
+            .hypervisor
+            .subnet_memory_saturation(&round_limits.subnet_available_memory);
+        if let Err(err) = reserve_storage_cycles(
+            self.allocated_bytes,
+            &subnet_memory_saturation,
+            original.subnet_size,
+            round.cycles_account_manager,
+            &mut self.canister.system_state,
+        ) {
+            let canister_id = self.canister.canister_id();
+            return finish_err(
+                clean_canister,
+                self.instructions_left(),
+                original,
+                round,
+                CanisterManagerError::StorageReservationFailed { canister_id, err },
+            );
+        }
+
         // After this point `install_code` is guaranteed to succeed.
         // Commit all the remaining state and round limit changes.
 
@@ -367,12 +367,18 @@ impl UpdateHelper {
             }
         }
 
+        let subnet_memory_saturation = round
+            .hypervisor
+            .subnet_memory_saturation(&round_limits.subnet_available_memory);
         apply_canister_state_changes(
             canister_state_changes,
             self.canister.execution_state.as_mut().unwrap(),
             &mut self.canister.system_state,
             &mut output,
             round_limits,
+            &subnet_memory_saturation,
+            original.subnet_size,
+            round.cycles_account_manager,
             round.time,
             round.network_topology,
             round.hypervisor.subnet_id(),
@@ -69,7 +69,7 @@ use ic_types::{
     methods::SystemMethod,
     nominal_cycles::NominalCycles,
     CanisterId, CpuComplexity, Cycles, LongExecutionMode, NumBytes, NumInstructions, SubnetId,
-    Time,
+    Time, MAX_MEMORY_ALLOCATION, MAX_WASM64_MEMORY_ALLOCATION,
 };
 use ic_types::{messages::MessageId, methods::WasmMethod};
 use ic_wasm_types::WasmHash;
@@ -527,7 +527,7 @@ impl ExecutionEnvironment {
                                             None => CanisterSettingsArgs::default(),
                                             Some(settings) => settings,
                                         };
-                                        let result = match CanisterSettings::try_from(settings) {
+                                        let result = match CanisterSettings::try_from((settings, self.max_memory_allocation())) {
                                             Err(err) => Some((Err(err.into()), cycles)),
                                             Ok(settings) =>
                                                 Some(self.create_canister(msg.canister_change_origin(sender_canister_version), cycles, settings, registry_settings.max_number_of_canisters, &mut state, registry_settings.subnet_size, round_limits))
@@ -573,7 +573,10 @@ impl ExecutionEnvironment {
 
                         let canister_id = args.get_canister_id();
                         let sender_canister_version = args.get_sender_canister_version();
-                        let result = match CanisterSettings::try_from(args.settings) {
+                        let result = match CanisterSettings::try_from((
+                            args.settings,
+                            self.max_memory_allocation(),
+                        )) {
                             Err(err) => Err(err.into()),
                             Ok(settings) => self.update_settings(
                                 timestamp_nanos,
@@ -582,6 +585,7 @@ impl ExecutionEnvironment {
                                 canister_id,
                                 &mut state,
                                 round_limits,
+                                registry_settings.subnet_size,
                             ),
                         };
                         // The induction cost of `UpdateSettings` is charged
@@ -637,6 +641,7 @@ impl ExecutionEnvironment {
                             args.get_new_controller(),
                             &mut state,
                             round_limits,
+                            registry_settings.subnet_size,
                         )
                         .map(|()| EmptyBlob.encode())
                         .map_err(|err| err.into()),
@@ -900,7 +905,10 @@ impl ExecutionEnvironment {
                     Ok(args) => {
                         let cycles_amount = args.to_u128();
                         let sender_canister_version = args.get_sender_canister_version();
-                        match CanisterSettings::try_from(args.settings) {
+                        match CanisterSettings::try_from((
+                            args.settings,
+                            self.max_memory_allocation(),
+                        )) {
                             Ok(settings) => self
                                 .canister_manager
                                 .create_canister_with_cycles(
@@ -1214,6 +1222,15 @@ impl ExecutionEnvironment {
         self.config.max_canister_memory_size
     }
 
+    /// Returns the upper limit on the memory allocation of a canister, which
+    /// depends on whether 64-bit Wasm memories are enabled.
+    fn max_memory_allocation(&self) -> NumBytes {
+        match self.config.wasm64 {
+            FlagStatus::Enabled => MAX_WASM64_MEMORY_ALLOCATION,
+            FlagStatus::Disabled => MAX_MEMORY_ALLOCATION,
+        }
+    }
+
     /// Returns the subnet memory capacity.
     pub fn subnet_memory_capacity(&self) -> NumBytes {
         self.config.subnet_memory_capacity
@@ -1277,10 +1294,18 @@ impl ExecutionEnvironment {
         canister_id: CanisterId,
         state: &mut ReplicatedState,
         round_limits: &mut RoundLimits,
+        subnet_size: usize,
     ) -> Result<Vec<u8>, UserError> {
         let canister = get_canister_mut(canister_id, state)?;
         self.canister_manager
-            .update_settings(timestamp_nanos, origin, settings, canister, round_limits)
+            .update_settings(
+                timestamp_nanos,
+                origin,
+                settings,
+                canister,
+                round_limits,
+                subnet_size,
+            )
             .map(|()| EmptyBlob.encode())
             .map_err(|err| err.into())
     }
@@ -1880,12 +1905,14 @@ impl ExecutionEnvironment {
         fn decode_input_and_take_canister(
             msg: &CanisterCall,
             state: &mut ReplicatedState,
+            max_memory_allocation: NumBytes,
         ) -> Result<(InstallCodeContext, CanisterState), UserError> {
             let payload = msg.method_payload();
             let args = InstallCodeArgs::decode(payload)?;
             let install_context = InstallCodeContext::try_from((
                 msg.canister_change_origin(args.get_sender_canister_version()),
                 args,
+                max_memory_allocation,
             ))?;
             let canister = state
                 .take_canister_state(&install_context.canister_id)
@@ -1898,16 +1925,16 @@ impl ExecutionEnvironment {
         // Start logging execution time for `install_code`.
         let timer = Timer::start();
 
-        let (install_context, old_canister) = match decode_input_and_take_canister(&msg, &mut state)
-        {
-            Ok(result) => result,
-            Err(err) => {
-                let refund = msg.take_cycles();
-                let state =
-                    self.finish_subnet_message_execution(state, msg, Err(err), refund, timer);
-                return (state, Some(NumInstructions::from(0)));
-            }
-        };
+        let (install_context, old_canister) =
+            match decode_input_and_take_canister(&msg, &mut state, self.max_memory_allocation()) {
+                Ok(result) => result,
+                Err(err) => {
+                    let refund = msg.take_cycles();
+                    let state =
+                        self.finish_subnet_message_execution(state, msg, Err(err), refund, timer);
+                    return (state, Some(NumInstructions::from(0)));
+                }
+            };
 
         // Check the precondition.
         match old_canister.next_execution() {
@@ -7,9 +7,9 @@ use ic_base_types::{NumBytes, NumSeconds};
 use ic_error_types::{ErrorCode, RejectCode, UserError};
 use ic_ic00_types::{
     self as ic00, CanisterHttpRequestArgs, CanisterIdRecord, CanisterStatusResultV2,
-    CanisterStatusType, DerivationPath, EcdsaCurve, EcdsaKeyId, EmptyBlob, HttpMethod, Method,
-    Payload as Ic00Payload, ProvisionalCreateCanisterWithCyclesArgs, ProvisionalTopUpCanisterArgs,
-    TransformContext, TransformFunc, IC_00,
+    CanisterStatusType, DerivationPath, EcdsaCurve, EcdsaKeyId, EmptyBlob, HttpMethod,
+    LogVisibility, Method, Payload as Ic00Payload, ProvisionalCreateCanisterWithCyclesArgs,
+    ProvisionalTopUpCanisterArgs, TransformContext, TransformFunc, IC_00,
 };
 use ic_registry_routing_table::canister_id_into_u64;
 use ic_registry_routing_table::CanisterIdRange;
@@ -31,7 +31,8 @@ use ic_types::{
     messages::{
         CallbackId, Payload, RejectContext, RequestOrResponse, Response, MAX_RESPONSE_COUNT_BYTES,
     },
-    CanisterId, Cycles, PrincipalId, RegistryVersion,
+    CanisterId, Cycles, PrincipalId, RegistryVersion, MAX_MEMORY_ALLOCATION,
+    MAX_WASM64_MEMORY_ALLOCATION,
 };
 use ic_types_test_utils::ids::{canister_test_id, node_test_id, subnet_test_id, user_test_id};
 use ic_universal_canister::{call_args, wasm};
@@ -1437,6 +1438,258 @@ fn test_allocating_memory_reduces_subnet_available_memory() {
     assert_eq!(ONE_GIB, memory.get_message_memory());
 }
 
+#[test]
+fn growing_wasm_memory_beyond_wasm_memory_limit_fails() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let wat = r#"
+        (module
+            (func (export "canister_update grow")
+                (drop (memory.grow (i32.const 10)))
+            )
+            (memory 1)
+        )"#;
+    let canister_id = test.canister_from_wat(wat).unwrap();
+    let wasm_memory_limit = 5 * WASM_PAGE_SIZE_IN_BYTES as u64;
+    let payload = ic00::UpdateSettingsArgs {
+        canister_id: canister_id.get(),
+        settings: ic00::CanisterSettingsArgsBuilder::new()
+            .with_wasm_memory_limit(wasm_memory_limit)
+            .build(),
+        sender_canister_version: None,
+    }
+    .encode();
+    test.subnet_message(Method::UpdateSettings, payload)
+        .unwrap();
+
+    let result = test.canister_status(canister_id);
+    let status = CanisterStatusResultV2::decode(&get_reply(result)).unwrap();
+    assert_eq!(status.settings().wasm_memory_limit(), wasm_memory_limit);
+
+    let err = test.ingress(canister_id, "grow", vec![]).unwrap_err();
+    assert_eq!(ErrorCode::CanisterOutOfMemory, err.code());
+}
+
+fn update_memory_allocation(
+    test: &mut ExecutionTest,
+    canister_id: CanisterId,
+    memory_allocation: u64,
+) -> Result<WasmResult, UserError> {
+    let payload = ic00::UpdateSettingsArgs {
+        canister_id: canister_id.get(),
+        settings: ic00::CanisterSettingsArgsBuilder::new()
+            .with_memory_allocation(memory_allocation)
+            .build(),
+        sender_canister_version: None,
+    }
+    .encode();
+    test.subnet_message(Method::UpdateSettings, payload)
+}
+
+#[test]
+fn memory_allocation_is_bounded_by_wasm32_limit_without_wasm64() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_id = test.create_canister(Cycles::new(1_000_000_000_000));
+    let err = update_memory_allocation(&mut test, canister_id, MAX_MEMORY_ALLOCATION.get() + 1)
+        .unwrap_err();
+    assert_eq!(ErrorCode::CanisterContractViolation, err.code());
+    assert_eq!(
+        err.description(),
+        format!(
+            "MemoryAllocation expected to be in the range [0..{}], got {}",
+            candid::Nat::from(MAX_MEMORY_ALLOCATION.get()),
+            candid::Nat::from(MAX_MEMORY_ALLOCATION.get() + 1)
+        )
+    );
+}
+
+#[test]
+fn memory_allocation_is_bounded_by_wasm64_limit_with_wasm64() {
+    let mut test = ExecutionTestBuilder::new().with_wasm64().build();
+    let canister_id = test.create_canister(Cycles::new(1_000_000_000_000));
+    let err = update_memory_allocation(
+        &mut test,
+        canister_id,
+        MAX_WASM64_MEMORY_ALLOCATION.get() + 1,
+    )
+    .unwrap_err();
+    assert_eq!(ErrorCode::CanisterContractViolation, err.code());
+    assert_eq!(
+        err.description(),
+        format!(
+            "MemoryAllocation expected to be in the range [0..{}], got {}",
+            candid::Nat::from(MAX_WASM64_MEMORY_ALLOCATION.get()),
+            candid::Nat::from(MAX_WASM64_MEMORY_ALLOCATION.get() + 1)
+        )
+    );
+}
+
+#[test]
+fn update_settings_sets_log_visibility() {
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_id = test.create_canister(Cycles::new(1_000_000_000_000));
+
+    let result = test.canister_status(canister_id);
+    let status = CanisterStatusResultV2::decode(&get_reply(result)).unwrap();
+    assert_eq!(
+        status.settings().log_visibility(),
+        LogVisibility::Controllers
+    );
+
+    let payload = ic00::UpdateSettingsArgs {
+        canister_id: canister_id.get(),
+        settings: ic00::CanisterSettingsArgsBuilder::new()
+            .with_log_visibility(LogVisibility::Public)
+            .build(),
+        sender_canister_version: None,
+    }
+    .encode();
+    test.subnet_message(Method::UpdateSettings, payload)
+        .unwrap();
+
+    assert_eq!(
+        test.canister_state(canister_id).system_state.log_visibility,
+        LogVisibility::Public
+    );
+    let result = test.canister_status(canister_id);
+    let status = CanisterStatusResultV2::decode(&get_reply(result)).unwrap();
+    assert_eq!(status.settings().log_visibility(), LogVisibility::Public);
+}
+
+#[test]
+fn update_settings_fails_if_reservation_leaves_balance_below_freezing_threshold() {
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_total_memory(100 * 1024 * 1024)
+        .with_subnet_memory_threshold(0)
+        .build();
+    let canister_id = test.create_canister(Cycles::new(1_000_000_000_000));
+    let balance_before = test.canister_state(canister_id).system_state.balance();
+    let freezing_threshold_before = test
+        .canister_state(canister_id)
+        .system_state
+        .freeze_threshold;
+
+    // Growing the memory allocation requires a reservation, after which the
+    // balance cannot cover the new freezing threshold.
+    let payload = ic00::UpdateSettingsArgs {
+        canister_id: canister_id.get(),
+        settings: ic00::CanisterSettingsArgsBuilder::new()
+            .with_memory_allocation(10 * 1024 * 1024)
+            .with_freezing_threshold(u64::MAX)
+            .build(),
+        sender_canister_version: None,
+    }
+    .encode();
+    let err = test
+        .subnet_message(Method::UpdateSettings, payload)
+        .unwrap_err();
+
+    assert_eq!(ErrorCode::CanisterOutOfCycles, err.code());
+    assert!(err.description().contains("freezing threshold"));
+    let system_state = &test.canister_state(canister_id).system_state;
+    assert_eq!(system_state.balance(), balance_before);
+    assert_eq!(system_state.reserved_balance(), Cycles::zero());
+    assert_eq!(system_state.freeze_threshold, freezing_threshold_before);
+}
+
+#[test]
+fn install_code_reserves_cycles_above_subnet_memory_threshold() {
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_total_memory(100 * 1024 * 1024)
+        .with_subnet_memory_threshold(0)
+        .build();
+    let wat = r#"
+        (module
+            (func (export "canister_update test"))
+            (memory 10)
+        )"#;
+    let canister_id = test.canister_from_wat(wat).unwrap();
+    assert!(
+        test.canister_state(canister_id)
+            .system_state
+            .reserved_balance()
+            > Cycles::zero()
+    );
+}
+
+#[test]
+fn growing_memory_above_subnet_memory_threshold_reserves_cycles() {
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_total_memory(100 * 1024 * 1024)
+        .with_subnet_memory_threshold(0)
+        .build();
+    let wat = r#"
+        (module
+            (func (export "canister_update grow")
+                (drop (memory.grow (i32.const 10)))
+            )
+            (memory 1)
+        )"#;
+    let canister_id = test.canister_from_wat(wat).unwrap();
+    let reserved_before = test
+        .canister_state(canister_id)
+        .system_state
+        .reserved_balance();
+    let balance_before = test.canister_state(canister_id).system_state.balance();
+
+    test.ingress(canister_id, "grow", vec![]).unwrap();
+
+    let reserved = test
+        .canister_state(canister_id)
+        .system_state
+        .reserved_balance()
+        - reserved_before;
+    assert!(reserved > Cycles::zero());
+    // The reserved cycles come out of the main balance.
+    assert!(test.canister_state(canister_id).system_state.balance() <= balance_before - reserved);
+}
+
+#[test]
+fn growing_memory_fails_when_reserved_cycles_limit_is_exceeded() {
+    let mut test = ExecutionTestBuilder::new()
+        .with_subnet_total_memory(100 * 1024 * 1024)
+        .with_subnet_memory_threshold(0)
+        .build();
+    let wat = r#"
+        (module
+            (func (export "canister_update grow")
+                (drop (memory.grow (i32.const 10)))
+            )
+            (memory 1)
+        )"#;
+    let canister_id = test.canister_from_wat(wat).unwrap();
+    let reserved_before = test
+        .canister_state(canister_id)
+        .system_state
+        .reserved_balance();
+    let payload = ic00::UpdateSettingsArgs {
+        canister_id: canister_id.get(),
+        settings: ic00::CanisterSettingsArgsBuilder::new()
+            .with_reserved_cycles_limit(reserved_before.get())
+            .build(),
+        sender_canister_version: None,
+    }
+    .encode();
+    test.subnet_message(Method::UpdateSettings, payload)
+        .unwrap();
+    let memory_before = test
+        .canister_state(canister_id)
+        .memory_usage(SubnetType::Application);
+
+    let err = test.ingress(canister_id, "grow", vec![]).unwrap_err();
+    assert_eq!(ErrorCode::CanisterOutOfCycles, err.code());
+    assert_eq!(
+        test.canister_state(canister_id)
+            .system_state
+            .reserved_balance(),
+        reserved_before
+    );
+    assert_eq!(
+        test.canister_state(canister_id)
+            .memory_usage(SubnetType::Application),
+        memory_before
+    );
+}
+
 #[test]
 fn execute_canister_http_request() {
     let own_subnet = subnet_test_id(1);
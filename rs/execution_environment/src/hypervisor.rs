@@ -2,13 +2,16 @@ use ic_canister_sandbox_replica_controller::sandboxed_execution_controller::Sand
 use ic_config::execution_environment::MAX_COMPILATION_CACHE_SIZE;
 use ic_config::flag_status::FlagStatus;
 use ic_config::{embedders::Config as EmbeddersConfig, execution_environment::Config};
-use ic_cycles_account_manager::CyclesAccountManager;
+use ic_constants::SMALL_APP_SUBNET_MAX_SIZE;
+use ic_cycles_account_manager::{CyclesAccountManager, ResourceSaturation};
 use ic_embedders::wasm_executor::{WasmExecutionResult, WasmExecutor};
 use ic_embedders::wasm_utils::decoding::decoded_wasm_size;
 use ic_embedders::{wasm_executor::WasmExecutorImpl, WasmExecutionInput, WasmtimeEmbedder};
 use ic_embedders::{CompilationCache, CompilationResult};
-use ic_interfaces::execution_environment::{HypervisorResult, WasmExecutionOutput};
-use ic_logger::ReplicaLogger;
+use ic_interfaces::execution_environment::{
+    HypervisorResult, SubnetAvailableMemory, WasmExecutionOutput,
+};
+use ic_logger::{warn, ReplicaLogger};
 use ic_metrics::buckets::decimal_buckets_with_zero;
 use ic_metrics::{buckets::exponential_buckets, MetricsRegistry};
 use ic_registry_subnet_type::SubnetType;
@@ -118,6 +121,8 @@ pub struct Hypervisor {
     deterministic_time_slicing: FlagStatus,
     cost_to_compile_wasm_instruction: NumInstructions,
     dirty_page_overhead: NumInstructions,
+    subnet_memory_capacity: NumBytes,
+    subnet_memory_threshold: NumBytes,
 }
 
 impl Hypervisor {
@@ -125,6 +130,23 @@ impl Hypervisor {
         self.own_subnet_id
     }
 
+    /// Returns the saturation of the subnet memory given the memory that is
+    /// still available on the subnet.
+    pub(crate) fn subnet_memory_saturation(
+        &self,
+        subnet_available_memory: &SubnetAvailableMemory,
+    ) -> ResourceSaturation {
+        let subnet_memory_usage = self
+            .subnet_memory_capacity
+            .get()
+            .saturating_sub(subnet_available_memory.get_total_memory().max(0) as u64);
+        ResourceSaturation::new(
+            subnet_memory_usage,
+            self.subnet_memory_threshold.get(),
+            self.subnet_memory_capacity.get(),
+        )
+    }
+
     pub fn subnet_type(&self) -> SubnetType {
         self.own_subnet_type
     }
@@ -193,6 +215,7 @@ impl Hypervisor {
             config.query_execution_threads_per_canister;
         embedder_config.feature_flags.rate_limiting_of_debug_prints =
             config.rate_limiting_of_debug_prints;
+        embedder_config.feature_flags.wasm64 = config.wasm64;
         embedder_config.cost_to_compile_wasm_instruction = config.cost_to_compile_wasm_instruction;
         embedder_config.max_sandbox_count = config.max_sandbox_count;
         embedder_config.max_sandbox_idle_time = config.max_sandbox_idle_time;
@@ -200,6 +223,25 @@ impl Hypervisor {
         embedder_config.subnet_type = own_subnet_type;
         embedder_config.dirty_page_overhead = dirty_page_overhead;
 
+        let compilation_cache = match &config.compilation_cache_dir {
+            Some(dir) => CompilationCache::new_with_disk_cache(
+                config.max_compilation_cache_size,
+                dir,
+                config.max_compilation_cache_disk_size,
+                &embedder_config,
+            )
+            .unwrap_or_else(|err| {
+                warn!(
+                    log,
+                    "Failed to open the on-disk compilation cache at {}: {}",
+                    dir.display(),
+                    err
+                );
+                CompilationCache::new(config.max_compilation_cache_size)
+            }),
+            None => CompilationCache::new(config.max_compilation_cache_size),
+        };
+
         let wasm_executor: Arc<dyn WasmExecutor> = match config.canister_sandboxing_flag {
             FlagStatus::Enabled => {
                 let executor = SandboxedExecutionController::new(
@@ -229,10 +271,12 @@ impl Hypervisor {
             own_subnet_type,
             log,
             cycles_account_manager,
-            compilation_cache: Arc::new(CompilationCache::new(config.max_compilation_cache_size)),
+            compilation_cache: Arc::new(compilation_cache),
             deterministic_time_slicing: config.deterministic_time_slicing,
             cost_to_compile_wasm_instruction: config.cost_to_compile_wasm_instruction,
             dirty_page_overhead,
+            subnet_memory_capacity: config.subnet_memory_capacity,
+            subnet_memory_threshold: config.subnet_memory_threshold,
         }
     }
 
@@ -247,6 +291,8 @@ impl Hypervisor {
         deterministic_time_slicing: FlagStatus,
         cost_to_compile_wasm_instruction: NumInstructions,
         dirty_page_overhead: NumInstructions,
+        subnet_memory_capacity: NumBytes,
+        subnet_memory_threshold: NumBytes,
     ) -> Self {
         Self {
             wasm_executor,
@@ -259,6 +305,8 @@ impl Hypervisor {
             deterministic_time_slicing,
             cost_to_compile_wasm_instruction,
             dirty_page_overhead,
+            subnet_memory_capacity,
+            subnet_memory_threshold,
         }
     }
 
@@ -306,12 +354,20 @@ impl Hypervisor {
             }
         };
         update_round_limits(round_limits, &slice);
+        let subnet_memory_saturation =
+            self.subnet_memory_saturation(&round_limits.subnet_available_memory);
+        let subnet_size = network_topology
+            .get_subnet_size(&self.own_subnet_id)
+            .unwrap_or(SMALL_APP_SUBNET_MAX_SIZE);
         apply_canister_state_changes(
             canister_state_changes,
             &mut execution_state,
             &mut system_state,
             &mut output,
             round_limits,
+            &subnet_memory_saturation,
+            subnet_size,
+            &self.cycles_account_manager,
             time,
             network_topology,
             self.own_subnet_id,
@@ -5179,3 +5179,115 @@ fn division_by_zero() {
         format!("Canister {} trapped: integer division by 0", canister_id)
     );
 }
+
+fn wasm64_test() -> ExecutionTest {
+    ExecutionTestBuilder::new()
+        .with_wasm64()
+        .with_initial_canister_cycles(1_000_000_000_000_000)
+        .build()
+}
+
+#[test]
+fn wasm64_loads_and_stores_above_4gib() {
+    let mut test = wasm64_test();
+    let wat = r#"
+        (module
+            (import "ic0" "msg_reply" (func $msg_reply))
+            (import "ic0" "msg_reply_data_append"
+                (func $msg_reply_data_append (param i64 i64)))
+            (func (export "canister_update test")
+                ;; Grow the memory to 4GiB + 64KiB.
+                (drop (memory.grow (i64.const 65536)))
+                (i64.store (i64.const 4294967312) (i64.const 0x0807060504030201))
+                (i64.store (i64.const 4294967320) (i64.load (i64.const 4294967312)))
+                (call $msg_reply_data_append (i64.const 4294967312) (i64.const 16))
+                (call $msg_reply)
+            )
+            (memory i64 1)
+        )"#;
+    let canister_id = test.canister_from_wat(wat).unwrap();
+    let result = test.ingress(canister_id, "test", vec![]).unwrap();
+    assert_eq!(
+        result,
+        WasmResult::Reply(vec![1, 2, 3, 4, 5, 6, 7, 8, 1, 2, 3, 4, 5, 6, 7, 8])
+    );
+}
+
+#[test]
+fn wasm64_stable64_read_and_write_above_4gib() {
+    let mut test = wasm64_test();
+    let wat = r#"
+        (module
+            (import "ic0" "msg_reply" (func $msg_reply))
+            (import "ic0" "msg_reply_data_append"
+                (func $msg_reply_data_append (param i64 i64)))
+            (import "ic0" "stable64_grow" (func $stable64_grow (param i64) (result i64)))
+            (import "ic0" "stable64_write"
+                (func $stable64_write (param $offset i64) (param $src i64) (param $size i64)))
+            (import "ic0" "stable64_read"
+                (func $stable64_read (param $dst i64) (param $offset i64) (param $size i64)))
+            (func (export "canister_update test")
+                ;; Grow the memory to 4GiB + 64KiB.
+                (drop (memory.grow (i64.const 65536)))
+                (drop (call $stable64_grow (i64.const 1)))
+                (i64.store (i64.const 4294967312) (i64.const 0x0807060504030201))
+                (call $stable64_write (i64.const 100) (i64.const 4294967312) (i64.const 8))
+                (call $stable64_read (i64.const 4294967400) (i64.const 100) (i64.const 8))
+                (call $msg_reply_data_append (i64.const 4294967400) (i64.const 8))
+                (call $msg_reply)
+            )
+            (memory i64 1)
+        )"#;
+    let canister_id = test.canister_from_wat(wat).unwrap();
+    let result = test.ingress(canister_id, "test", vec![]).unwrap();
+    assert_eq!(result, WasmResult::Reply(vec![1, 2, 3, 4, 5, 6, 7, 8]));
+}
+
+#[test]
+fn wasm64_msg_arg_data_copy_above_4gib() {
+    let mut test = wasm64_test();
+    let wat = r#"
+        (module
+            (import "ic0" "msg_reply" (func $msg_reply))
+            (import "ic0" "msg_reply_data_append"
+                (func $msg_reply_data_append (param i64 i64)))
+            (import "ic0" "msg_arg_data_size" (func $msg_arg_data_size (result i32)))
+            (import "ic0" "msg_arg_data_copy"
+                (func $msg_arg_data_copy (param $dst i64) (param $offset i64) (param $size i64)))
+            (func (export "canister_update test")
+                ;; Grow the memory to 4GiB + 64KiB.
+                (drop (memory.grow (i64.const 65536)))
+                (call $msg_arg_data_copy
+                    (i64.const 4294967312)
+                    (i64.const 0)
+                    (i64.extend_i32_u (call $msg_arg_data_size)))
+                (call $msg_reply_data_append
+                    (i64.const 4294967312)
+                    (i64.extend_i32_u (call $msg_arg_data_size)))
+                (call $msg_reply)
+            )
+            (memory i64 1)
+        )"#;
+    let canister_id = test.canister_from_wat(wat).unwrap();
+    let result = test.ingress(canister_id, "test", vec![5, 6, 7]).unwrap();
+    assert_eq!(result, WasmResult::Reply(vec![5, 6, 7]));
+}
+
+#[test]
+fn wasm64_system_api_fails_beyond_memory_size() {
+    let mut test = wasm64_test();
+    let wat = r#"
+        (module
+            (import "ic0" "msg_reply" (func $msg_reply))
+            (import "ic0" "msg_reply_data_append"
+                (func $msg_reply_data_append (param i64 i64)))
+            (func (export "canister_update test")
+                (call $msg_reply_data_append (i64.const 4294967312) (i64.const 16))
+                (call $msg_reply)
+            )
+            (memory i64 1)
+        )"#;
+    let canister_id = test.canister_from_wat(wat).unwrap();
+    let err = test.ingress(canister_id, "test", vec![]).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CanisterContractViolation);
+}
@@ -1357,6 +1357,13 @@ impl Scheduler for SchedulerImpl {
                 self.purge_expired_ingress_messages(&mut state);
             }
 
+            // Reject best-effort response calls whose deadline has expired.
+            let current_time = state.time();
+            let timed_out_callbacks = state.time_out_callbacks(current_time);
+            self.metrics
+                .timed_out_callbacks_count
+                .inc_by(timed_out_callbacks);
+
             // See documentation around definition of `heap_delta_estimate` for an
             // explanation.
             if state.metadata.heap_delta_estimate >= self.config.subnet_heap_delta_capacity {
@@ -824,6 +824,8 @@ impl SchedulerTestBuilder {
             deterministic_time_slicing,
             config.cost_to_compile_wasm_instruction,
             SchedulerConfig::application_subnet().dirty_page_overhead,
+            config.subnet_memory_capacity,
+            config.subnet_memory_threshold,
         );
         let hypervisor = Arc::new(hypervisor);
         let ingress_history_writer =
@@ -1262,6 +1264,7 @@ impl TestWasmExecutorCore {
             payment: Cycles::zero(),
             method_name: "update".into(),
             method_payload: encode_message_id_as_payload(call_message_id),
+            deadline: None,
         };
         if let Err(req) = system_state.push_output_request(
             canister_current_memory_usage,
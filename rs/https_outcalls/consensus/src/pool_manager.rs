@@ -17,12 +17,16 @@ use ic_metrics::MetricsRegistry;
 use ic_registry_client_helpers::subnet::SubnetRegistry;
 use ic_replicated_state::ReplicatedState;
 use ic_types::{
-    canister_http::*, consensus::HasHeight, crypto::Signed, messages::CallbackId,
-    replica_config::ReplicaConfig, Height,
+    canister_http::*,
+    consensus::HasHeight,
+    crypto::{CryptoResult, Signed},
+    messages::CallbackId,
+    replica_config::ReplicaConfig,
+    Height, NodeId,
 };
 use std::{
     cell::RefCell,
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     convert::TryInto,
     sync::{Arc, Mutex},
     time::Duration,
@@ -287,47 +291,84 @@ impl CanisterHttpPoolManagerImpl {
             return Vec::new();
         };
 
-        canister_http_pool
-            .get_unvalidated_shares()
-            .filter_map(|share| {
-                let node_is_in_committee = self
-                    .membership
-                    .node_belongs_to_canister_http_committee(
+        // Shares on the same response metadata are verified as a batch, which
+        // is substantially cheaper than verifying them one by one.
+        let mut batches: BTreeMap<_, BTreeMap<NodeId, &CanisterHttpResponseShare>> =
+            BTreeMap::new();
+        let mut change_set = Vec::new();
+        for share in canister_http_pool.get_unvalidated_shares() {
+            let node_is_in_committee = match self
+                .membership
+                .node_belongs_to_canister_http_committee(finalized_height, share.signature.signer)
+            {
+                Ok(node_is_in_committee) => node_is_in_committee,
+                Err(e) => {
+                    warn!(
+                        self.log,
+                        "Unabled to check membership for share at height {}, {:?}",
                         finalized_height,
-                        share.signature.signer,
-                    )
-                    .map_err(|e| {
-                        warn!(
-                            self.log,
-                            "Unabled to check membership for share at height {}, {:?}",
-                            finalized_height,
-                            e
-                        );
                         e
-                    })
-                    .ok()?;
-                if !node_is_in_committee {
-                    return Some(CanisterHttpChangeAction::HandleInvalid(
-                        ic_types::crypto::crypto_hash(share),
-                        "Share signed by node that is not a member of the canister http committee"
-                            .to_string(),
-                    ));
+                    );
+                    continue;
                 }
-                // TODO: more precise error handling
-                if let Err(err) = self.crypto.verify(share, registry_version) {
-                    error!(self.log, "Unable to verify signature of share, {}", err);
+            };
+            if !node_is_in_committee {
+                change_set.push(CanisterHttpChangeAction::HandleInvalid(
+                    ic_types::crypto::crypto_hash(share),
+                    "Share signed by node that is not a member of the canister http committee"
+                        .to_string(),
+                ));
+                continue;
+            }
+            let batch = batches.entry(&share.content).or_default();
+            if batch.contains_key(&share.signature.signer) {
+                // Further shares of a signer on the same metadata are verified
+                // on their own.
+                let result = self.crypto.verify(share, registry_version);
+                change_set.push(self.validation_action(share, result));
+            } else {
+                batch.insert(share.signature.signer, share);
+            }
+        }
 
-                    self.metrics.shares_marked_invalid.inc();
-                    Some(CanisterHttpChangeAction::HandleInvalid(
-                        ic_types::crypto::crypto_hash(share),
-                        format!("Unable to verify signature of share, {}", err),
-                    ))
-                } else {
-                    self.metrics.shares_validated.inc();
-                    Some(CanisterHttpChangeAction::MoveToValidated(share.clone()))
-                }
-            })
-            .collect()
+        for (content, shares) in batches {
+            let signatures: BTreeMap<_, _> = shares
+                .iter()
+                .map(|(signer, share)| (*signer, share.signature.signature.clone()))
+                .collect();
+            let mut results =
+                self.crypto
+                    .verify_basic_sigs_batch(&signatures, content, registry_version);
+            for (signer, share) in shares {
+                let result = results
+                    .remove(&signer)
+                    .unwrap_or_else(|| self.crypto.verify(share, registry_version));
+                change_set.push(self.validation_action(share, result));
+            }
+        }
+        change_set
+    }
+
+    // Returns the change action for a share with the given signature
+    // verification result.
+    fn validation_action(
+        &self,
+        share: &CanisterHttpResponseShare,
+        result: CryptoResult<()>,
+    ) -> CanisterHttpChangeAction {
+        // TODO: more precise error handling
+        if let Err(err) = result {
+            error!(self.log, "Unable to verify signature of share, {}", err);
+
+            self.metrics.shares_marked_invalid.inc();
+            CanisterHttpChangeAction::HandleInvalid(
+                ic_types::crypto::crypto_hash(share),
+                format!("Unable to verify signature of share, {}", err),
+            )
+        } else {
+            self.metrics.shares_validated.inc();
+            CanisterHttpChangeAction::MoveToValidated(share.clone())
+        }
     }
 
     fn generate_change_set(
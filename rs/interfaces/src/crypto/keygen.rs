@@ -4,9 +4,11 @@ pub use errors::*;
 use ic_base_types::NodeId;
 
 use ic_protobuf::registry::crypto::v1::PublicKey as PublicKeyProto;
+use ic_protobuf::registry::crypto::v1::X509PublicKeyCert;
 use ic_types::crypto::{CryptoError, CurrentNodePublicKeys, KeyPurpose};
 use ic_types::registry::RegistryClientError;
 use ic_types::RegistryVersion;
+use std::time::Duration;
 
 /// Methods for checking and retrieving key material.
 pub trait KeyManager {
@@ -71,6 +73,36 @@ pub trait KeyManager {
         &self,
         registry_version: RegistryVersion,
     ) -> Result<IDkgKeyRotationResult, IDkgDealingEncryptionKeyRotationError>;
+
+    /// Rotates the committee signing key and the TLS key material together. This function checks
+    /// whether the local keys are the ones registered at `registry_version`: if not, the local
+    /// keys are returned so that they can be (re-)registered. Otherwise, the keys are rotated if
+    /// the registered committee signing key is older than `key_rotation_period`.
+    ///
+    /// After a rotation, the secret keys of both the new and the previous keys are kept in the
+    /// secret key store, so that the node can still sign at registry versions that contain the
+    /// previous keys. The secret keys of older keys are deleted upon the next rotation.
+    ///
+    /// Returns [`NodeSigningKeysRotationResult::KeysNeedRegistration`] with the keys to be
+    /// registered, or [`NodeSigningKeysRotationResult::LatestRotationTooRecent`] if the local node
+    /// may not yet rotate its keys.
+    ///
+    /// # Errors
+    /// * [`NodeSigningKeysRotationError::KeyGenerationError`] if there was an error
+    ///   generating new keys
+    /// * [`NodeSigningKeysRotationError::RegistryClientError`] if there was an error
+    ///   communicating with the registry
+    /// * [`NodeSigningKeysRotationError::RegistryKeyBadOrMissing`] if the committee signing key
+    ///   or the TLS certificate of the node is missing from the registry
+    /// * [`NodeSigningKeysRotationError::PublicKeyNotFound`] if the local committee signing key
+    ///   or TLS certificate was not found in the public key store
+    /// * [`NodeSigningKeysRotationError::TransientInternalError`] if there was an RPC error
+    ///   communicating with the CSP vault.
+    fn rotate_node_signing_keys(
+        &self,
+        registry_version: RegistryVersion,
+        key_rotation_period: Duration,
+    ) -> Result<NodeSigningKeysRotationResult, NodeSigningKeysRotationError>;
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -162,3 +194,37 @@ impl From<RegistryClientError> for IDkgDealingEncryptionKeyRotationError {
         IDkgDealingEncryptionKeyRotationError::RegistryClientError(registry_client_error)
     }
 }
+
+/// The committee signing public key and the TLS certificate of a node, which are rotated together.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeSigningKeys {
+    pub committee_signing_public_key: PublicKeyProto,
+    pub tls_certificate: X509PublicKeyCert,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NodeSigningKeysRotationResult {
+    /// If no key rotation is necessary because the latest rotation was too recent
+    LatestRotationTooRecent,
+    /// If the keys were rotated (`rotated` is `true`), or if the current local keys still need
+    /// to be registered (`rotated` is `false`)
+    KeysNeedRegistration {
+        keys: NodeSigningKeys,
+        rotated: bool,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub enum NodeSigningKeysRotationError {
+    KeyGenerationError(String),
+    RegistryClientError(RegistryClientError),
+    RegistryKeyBadOrMissing,
+    TransientInternalError(String),
+    PublicKeyNotFound,
+}
+
+impl From<RegistryClientError> for NodeSigningKeysRotationError {
+    fn from(registry_client_error: RegistryClientError) -> Self {
+        NodeSigningKeysRotationError::RegistryClientError(registry_client_error)
+    }
+}
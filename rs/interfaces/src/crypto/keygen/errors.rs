@@ -1,4 +1,4 @@
-use crate::crypto::keygen::IDkgDealingEncryptionKeyRotationError;
+use crate::crypto::keygen::{IDkgDealingEncryptionKeyRotationError, NodeSigningKeysRotationError};
 use ic_types::crypto::CryptoError;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -37,6 +37,16 @@ impl From<CurrentNodePublicKeysError> for IDkgDealingEncryptionKeyRotationError
     }
 }
 
+impl From<CurrentNodePublicKeysError> for NodeSigningKeysRotationError {
+    fn from(e: CurrentNodePublicKeysError) -> NodeSigningKeysRotationError {
+        match e {
+            CurrentNodePublicKeysError::TransientInternalError(details) => {
+                NodeSigningKeysRotationError::TransientInternalError(details)
+            }
+        }
+    }
+}
+
 impl From<CurrentNodePublicKeysError> for CryptoError {
     fn from(e: CurrentNodePublicKeysError) -> CryptoError {
         match e {
@@ -145,6 +145,30 @@ pub trait BasicSigVerifier<T: Signable> {
         message: &T,
         registry_version: RegistryVersion,
     ) -> CryptoResult<()>;
+
+    /// Verifies the basic signatures of several signers on the same `message`.
+    ///
+    /// Implementations may verify all signatures at once, which is
+    /// substantially cheaper than verifying them separately. If such a batch
+    /// does not verify, every signature is verified separately, so that the
+    /// invalid ones can be identified.
+    ///
+    /// Returns the verification result for each signer in `signatures`, with
+    /// the same errors as `verify_basic_sig`.
+    fn verify_basic_sigs_batch(
+        &self,
+        signatures: &BTreeMap<NodeId, BasicSigOf<T>>,
+        message: &T,
+        registry_version: RegistryVersion,
+    ) -> BTreeMap<NodeId, CryptoResult<()>> {
+        signatures
+            .iter()
+            .map(|(signer, signature)| {
+                let result = self.verify_basic_sig(signature, message, *signer, registry_version);
+                (*signer, result)
+            })
+            .collect()
+    }
 }
 
 /// A Crypto Component interface to verify basic signatures by public key.
@@ -260,6 +284,32 @@ pub trait MultiSigVerifier<T: Signable> {
         registry_version: RegistryVersion,
     ) -> CryptoResult<()>;
 
+    /// Verifies the individual multi-signatures of several signers on the
+    /// same `message`, e.g. all notarization shares for a block.
+    ///
+    /// Implementations may verify all signatures at once using a random
+    /// linear combination, which is substantially cheaper than verifying them
+    /// separately. If such a batch does not verify, every signature is
+    /// verified separately, so that the invalid ones can be identified.
+    ///
+    /// Returns the verification result for each signer in `signatures`, with
+    /// the same errors as `verify_multi_sig_individual`.
+    fn verify_multi_sig_individuals_batch(
+        &self,
+        signatures: &BTreeMap<NodeId, IndividualMultiSigOf<T>>,
+        message: &T,
+        registry_version: RegistryVersion,
+    ) -> BTreeMap<NodeId, CryptoResult<()>> {
+        signatures
+            .iter()
+            .map(|(signer, signature)| {
+                let result =
+                    self.verify_multi_sig_individual(signature, message, *signer, registry_version);
+                (*signer, result)
+            })
+            .collect()
+    }
+
     /// Combines individual multi-signature shares.
     ///
     /// The registry version is not needed for the cryptographic scheme we use
@@ -1,6 +1,6 @@
 use ic_base_types::{CanisterIdError, PrincipalIdBlobParseError};
 use ic_error_types::UserError;
-use ic_types::{methods::WasmMethod, CanisterId, CountBytes, Cycles, NumInstructions};
+use ic_types::{methods::WasmMethod, CanisterId, CountBytes, Cycles, NumBytes, NumInstructions};
 use ic_wasm_types::{WasmEngineError, WasmInstrumentationError, WasmValidationError};
 use serde::{Deserialize, Serialize};
 
@@ -135,6 +135,28 @@ pub enum HypervisorError {
     },
     /// A canister has written too much new data in a single message.
     MemoryAccessLimitExceeded(String),
+    /// An attempt was made to grow the Wasm memory of the canister above its
+    /// `wasm_memory_limit` setting.
+    WasmMemoryLimitExceeded {
+        bytes: NumBytes,
+        limit: NumBytes,
+    },
+    /// The canister grew its memory while the subnet memory usage was above
+    /// the threshold and does not have enough cycles to reserve for the
+    /// future storage payments of the newly allocated bytes.
+    InsufficientCyclesInMemoryGrow {
+        bytes: NumBytes,
+        available: Cycles,
+        requested: Cycles,
+    },
+    /// The canister grew its memory while the subnet memory usage was above
+    /// the threshold and reserving cycles for the newly allocated bytes would
+    /// exceed its `reserved_cycles_limit` setting.
+    ReservedCyclesLimitExceededInMemoryGrow {
+        bytes: NumBytes,
+        requested: Cycles,
+        limit: Cycles,
+    },
 }
 
 impl From<WasmInstrumentationError> for HypervisorError {
@@ -320,6 +342,38 @@ impl HypervisorError {
                 format!("Canister exceeded memory access limits: {}", s)
 
             ),
+            Self::WasmMemoryLimitExceeded { bytes, limit } => UserError::new(
+                E::CanisterOutOfMemory,
+                format!(
+                    "Canister {} exceeded its Wasm memory limit: attempted to grow the Wasm memory to {} bytes but the limit is {} bytes",
+                    canister_id, bytes, limit
+                ),
+            ),
+            Self::InsufficientCyclesInMemoryGrow {
+                bytes,
+                available,
+                requested,
+            } => UserError::new(
+                E::CanisterOutOfCycles,
+                format!(
+                    "Canister {} cannot grow memory by {} bytes due to insufficient cycles. \
+                    At least {} additional cycles are required to reserve for future storage payments, \
+                    but only {} cycles are available.",
+                    canister_id, bytes, requested, available
+                ),
+            ),
+            Self::ReservedCyclesLimitExceededInMemoryGrow {
+                bytes,
+                requested,
+                limit,
+            } => UserError::new(
+                E::CanisterOutOfCycles,
+                format!(
+                    "Canister {} cannot grow memory by {} bytes due to its reserved cycles limit. \
+                    The current limit ({}) would be exceeded by {}.",
+                    canister_id, bytes, limit, requested
+                ),
+            ),
         }
     }
 
@@ -350,6 +404,13 @@ impl HypervisorError {
             HypervisorError::Aborted => "Aborted",
             HypervisorError::SliceOverrun { .. } => "SliceOverrun",
             HypervisorError::MemoryAccessLimitExceeded(_) => "MemoryAccessLimitExceeded",
+            HypervisorError::WasmMemoryLimitExceeded { .. } => "WasmMemoryLimitExceeded",
+            HypervisorError::InsufficientCyclesInMemoryGrow { .. } => {
+                "InsufficientCyclesInMemoryGrow"
+            }
+            HypervisorError::ReservedCyclesLimitExceededInMemoryGrow { .. } => {
+                "ReservedCyclesLimitExceededInMemoryGrow"
+            }
         }
     }
 }
@@ -25,7 +25,7 @@ use ic_types::{
         MAX_INTER_CANISTER_PAYLOAD_IN_BYTES_U64,
     },
     xnet::{StreamIndex, StreamIndexedQueue, StreamSlice},
-    SubnetId,
+    SubnetId, Time,
 };
 use prometheus::{Histogram, IntCounter, IntCounterVec, IntGaugeVec};
 use std::{
@@ -77,6 +77,7 @@ const LABEL_VALUE_SUCCESS: &str = "success";
 const LABEL_VALUE_SENDER_SUBNET_MISMATCH: &str = "SenderSubnetMismatch";
 const LABEL_VALUE_RECEIVER_SUBNET_MISMATCH: &str = "ReceiverSubnetMismatch";
 const LABEL_VALUE_CANISTER_MIGRATED: &str = "CanisterMigrated";
+const LABEL_VALUE_DEADLINE_EXPIRED: &str = "DeadlineExpired";
 const LABEL_TYPE: &str = "type";
 const LABEL_VALUE_TYPE_REQUEST: &str = "request";
 const LABEL_VALUE_TYPE_RESPONSE: &str = "response";
@@ -605,6 +606,24 @@ impl StreamHandlerImpl {
 
             let payload_size = msg.payload_size_bytes().get();
             match receiver_host_subnet {
+                // Matching receiver subnet, but the deadline of the best-effort request has
+                // already expired: the caller has given up on it, so reject it.
+                Some(host_subnet)
+                    if host_subnet == self.subnet_id
+                        && has_expired_deadline(&msg, state.time()) =>
+                {
+                    self.observe_inducted_message_status(msg_type, LABEL_VALUE_DEADLINE_EXPIRED);
+                    debug!(
+                        self.log,
+                        "Request deadline expired, generating reject Response for {:?}", &msg
+                    );
+                    stream.push(generate_reject_response(
+                        msg,
+                        RejectCode::SysUnknown,
+                        "Request deadline expired before induction.".to_string(),
+                    ));
+                }
+
                 // Matching receiver subnet, try inducting message.
                 Some(host_subnet) if host_subnet == self.subnet_id => match state.push_input(
                     msg,
@@ -866,6 +885,17 @@ fn generate_reject_response(
     }
 }
 
+/// Returns `true` iff `msg` is a best-effort `Request` whose deadline is not
+/// after `current_time`.
+fn has_expired_deadline(msg: &RequestOrResponse, current_time: Time) -> bool {
+    match msg {
+        RequestOrResponse::Request(request) => request
+            .deadline
+            .map_or(false, |deadline| deadline <= current_time),
+        RequestOrResponse::Response(_) => false,
+    }
+}
+
 /// Maps a `StateError` resulting from a failed induction to a `RejectCode`.
 fn reject_code_for_state_error(err: &StateError) -> RejectCode {
     match err {
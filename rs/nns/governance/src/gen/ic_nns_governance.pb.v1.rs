@@ -104,6 +104,21 @@ pub struct NeuronInfo {
     /// If this neuron is a known neuron, this is data associated with it, including the neuron's name and (optionally) a description.
     #[prost(message, optional, tag = "10")]
     pub known_neuron_data: ::core::option::Option<KnownNeuronData>,
+    /// Voting power of the neuron that counts when deciding proposals. This is
+    /// `voting_power`, reduced if the neuron has not been refreshed recently.
+    /// See \[VotingPowerEconomics\] for details.
+    #[prost(uint64, optional, tag = "11")]
+    pub deciding_voting_power: ::core::option::Option<u64>,
+    /// See \[Neuron::voting_power_refreshed_timestamp_seconds\] for a description.
+    #[prost(uint64, optional, tag = "12")]
+    pub voting_power_refreshed_timestamp_seconds: ::core::option::Option<u64>,
+    /// Whether the neuron is public, see \[Neuron::visibility\].
+    #[prost(enumeration = "Visibility", optional, tag = "13")]
+    pub visibility: ::core::option::Option<i32>,
+    /// The followees of the neuron, per topic. Only populated for public
+    /// neurons.
+    #[prost(map = "int32, message", tag = "14")]
+    pub followees: ::std::collections::HashMap<i32, neuron::Followees>,
 }
 /// A transfer performed from some account to stake a new neuron.
 #[derive(
@@ -257,6 +272,23 @@ pub struct Neuron {
     /// If set, the neuron belongs to the "known neurons". It has been given a name and maybe a description.
     #[prost(message, optional, tag = "18")]
     pub known_neuron_data: ::core::option::Option<KnownNeuronData>,
+    /// The last time that the neuron's controller (or one of its hot keys)
+    /// either voted directly, set following, or explicitly refreshed the
+    /// neuron's voting power. Neurons that have not been refreshed for longer
+    /// than `VotingPowerEconomics.start_reducing_voting_power_after_seconds`
+    /// have their deciding voting power reduced, and eventually their
+    /// following cleared (see `VotingPowerEconomics`).
+    ///
+    /// If unset, the neuron is considered to have been refreshed when
+    /// governance was last upgraded.
+    #[prost(uint64, optional, tag = "22")]
+    pub voting_power_refreshed_timestamp_seconds: ::core::option::Option<u64>,
+    /// Whether the full information of this neuron (e.g., its followees and
+    /// recent ballots) can be read by anyone. Known neurons are always public.
+    ///
+    /// If unset, the neuron is private, unless it is a known neuron.
+    #[prost(enumeration = "Visibility", optional, tag = "23")]
+    pub visibility: ::core::option::Option<i32>,
     /// At any time, at most one of `when_dissolved` and
     /// `dissolve_delay` are specified.
     ///
@@ -845,6 +877,21 @@ pub mod manage_neuron {
         #[prost(bool, tag = "1")]
         pub requested_setting_for_auto_stake_maturity: bool,
     }
+    /// Sets whether the full information of this neuron can be read by anyone.
+    /// Known neurons cannot be made private.
+    #[derive(
+        candid::CandidType,
+        candid::Deserialize,
+        serde::Serialize,
+        comparable::Comparable,
+        Clone,
+        PartialEq,
+        ::prost::Message,
+    )]
+    pub struct SetVisibility {
+        #[prost(enumeration = "super::Visibility", optional, tag = "1")]
+        pub visibility: ::core::option::Option<i32>,
+    }
     /// Commands that only configure a given neuron, but do not interact
     /// with the outside world. They all require the caller to be the
     /// controller of the neuron.
@@ -891,6 +938,8 @@ pub mod manage_neuron {
             LeaveCommunityFund(super::LeaveCommunityFund),
             #[prost(message, tag = "9")]
             ChangeAutoStakeMaturity(super::ChangeAutoStakeMaturity),
+            #[prost(message, tag = "10")]
+            SetVisibility(super::SetVisibility),
         }
     }
     /// Disburse this neuron's stake: transfer the staked ICP to the
@@ -1023,6 +1072,19 @@ pub mod manage_neuron {
         #[prost(uint32, optional, tag = "1")]
         pub percentage_to_stake: ::core::option::Option<u32>,
     }
+    /// Confirm that the neuron's controller is still active, resetting the
+    /// timer after which the neuron's deciding voting power starts being
+    /// reduced. Voting directly or setting following has the same effect.
+    #[derive(
+        candid::CandidType,
+        candid::Deserialize,
+        serde::Serialize,
+        comparable::Comparable,
+        Clone,
+        PartialEq,
+        ::prost::Message,
+    )]
+    pub struct RefreshVotingPower {}
     /// Disburse a portion of this neuron's stake into another neuron.
     /// This allows to split a neuron but with a new dissolve delay
     /// and owned by someone else.
@@ -1214,6 +1276,8 @@ pub mod manage_neuron {
         Merge(Merge),
         #[prost(message, tag = "15")]
         StakeMaturity(StakeMaturity),
+        #[prost(message, tag = "16")]
+        RefreshVotingPower(RefreshVotingPower),
     }
 }
 /// The response of the ManageNeuron command
@@ -1324,6 +1388,16 @@ pub mod manage_neuron_response {
         PartialEq,
         ::prost::Message,
     )]
+    pub struct RefreshVotingPowerResponse {}
+    #[derive(
+        candid::CandidType,
+        candid::Deserialize,
+        serde::Serialize,
+        comparable::Comparable,
+        Clone,
+        PartialEq,
+        ::prost::Message,
+    )]
     pub struct MakeProposalResponse {
         /// The ID of the created proposal
         #[prost(message, optional, tag = "1")]
@@ -1440,6 +1514,8 @@ pub mod manage_neuron_response {
         Merge(MergeResponse),
         #[prost(message, tag = "13")]
         StakeMaturity(StakeMaturityResponse),
+        #[prost(message, tag = "14")]
+        RefreshVotingPower(RefreshVotingPowerResponse),
     }
 }
 #[derive(candid::CandidType, candid::Deserialize, serde::Serialize, comparable::Comparable)]
@@ -1964,6 +2040,40 @@ pub struct NetworkEconomics {
     /// If unspecified or zero, all proposals are kept.
     #[prost(uint32, tag = "10")]
     pub max_proposals_to_keep_per_topic: u32,
+    /// Parameters that control how the voting power of neurons whose
+    /// controllers have not been active for a while is reduced.
+    #[prost(message, optional, tag = "11")]
+    pub voting_power_economics: ::core::option::Option<VotingPowerEconomics>,
+}
+/// Parameters that control the reduction of deciding voting power of
+/// inactive neurons.
+///
+/// A neuron's "potential" voting power is determined by its stake, age and
+/// dissolve delay. Its "deciding" voting power, which is what counts when
+/// proposals are decided, is equal to the potential voting power as long as
+/// the neuron was refreshed (see `Neuron.voting_power_refreshed_timestamp_seconds`)
+/// within the last `start_reducing_voting_power_after_seconds`. After that, it
+/// decreases linearly, reaching zero after a further
+/// `clear_following_after_seconds`, at which point the neuron's following
+/// (other than on the NeuronManagement topic) is cleared.
+#[derive(
+    candid::CandidType,
+    candid::Deserialize,
+    serde::Serialize,
+    comparable::Comparable,
+    Clone,
+    PartialEq,
+    ::prost::Message,
+)]
+pub struct VotingPowerEconomics {
+    /// How long a neuron can go without being refreshed before its deciding
+    /// voting power starts being reduced.
+    #[prost(uint64, optional, tag = "1")]
+    pub start_reducing_voting_power_after_seconds: ::core::option::Option<u64>,
+    /// How long it takes, once the reduction has started, for the deciding
+    /// voting power to reach zero and for the neuron's following to be cleared.
+    #[prost(uint64, optional, tag = "2")]
+    pub clear_following_after_seconds: ::core::option::Option<u64>,
 }
 /// A reward event is an event at which neuron maturity is increased
 #[derive(
@@ -2564,6 +2674,17 @@ pub mod governance {
         pub community_fund_total_maturity_e8s_equivalent: u64,
         #[prost(uint64, tag = "18")]
         pub total_locked_e8s: u64,
+        /// The sum of the potential voting power of all neurons, i.e. ignoring
+        /// any reduction due to inactivity.
+        #[prost(uint64, tag = "19")]
+        pub total_potential_voting_power: u64,
+        /// The sum of the deciding voting power of all neurons.
+        #[prost(uint64, tag = "20")]
+        pub total_deciding_voting_power: u64,
+        /// The number of neurons whose deciding voting power is currently being
+        /// reduced (or is zero) because they have not been refreshed recently.
+        #[prost(uint64, tag = "21")]
+        pub neurons_with_reduced_voting_power_count: u64,
     }
 }
 /// Proposals with restricted voting are not included unless the caller
@@ -2610,6 +2731,23 @@ pub struct ListProposalInfo {
     /// restriction is applied.
     #[prost(enumeration = "ProposalStatus", repeated, tag = "5")]
     pub include_status: ::prost::alloc::vec::Vec<i32>,
+    /// Include proposals made by a neuron in this list. If this list is
+    /// empty, no restriction is applied.
+    #[prost(message, repeated, tag = "6")]
+    pub include_proposer: ::prost::alloc::vec::Vec<::ic_nns_common::pb::v1::NeuronId>,
+    /// Include proposals that have an action type in this list (see
+    /// \[ProposalActionType\]). If this list is empty, no restriction is
+    /// applied.
+    #[prost(enumeration = "ProposalActionType", repeated, tag = "7")]
+    pub include_action_type: ::prost::alloc::vec::Vec<i32>,
+    /// If specified, only return proposals that were made at or after
+    /// this time.
+    #[prost(uint64, optional, tag = "8")]
+    pub min_proposal_timestamp_seconds: ::core::option::Option<u64>,
+    /// If specified, only return proposals that were made at or before
+    /// this time.
+    #[prost(uint64, optional, tag = "9")]
+    pub max_proposal_timestamp_seconds: ::core::option::Option<u64>,
 }
 #[derive(
     candid::CandidType,
@@ -2647,6 +2785,36 @@ pub struct ListNeurons {
     /// neurons that the calling principal is authorized to read.
     #[prost(bool, tag = "2")]
     pub include_neurons_readable_by_caller: bool,
+    /// If specified, at most this many neurons of the "requested list"
+    /// are returned, in increasing order of neuron ID. If no value is
+    /// specified, all the neurons are returned. If 0 or a value greater
+    /// than 500 is specified, 500 will be used.
+    #[prost(uint32, optional, tag = "3")]
+    pub limit: ::core::option::Option<u32>,
+    /// Used to indicate where the next page of neurons should start.
+    /// Should be set to `next_start_page_at` of the previous response;
+    /// only neurons with a greater ID are returned.
+    #[prost(fixed64, optional, tag = "4")]
+    pub start_page_at: ::core::option::Option<u64>,
+    /// Only include neurons that are in one of these states (see
+    /// \[NeuronState\]), e.g., only the dissolving ones. If this list is
+    /// empty, no restriction is applied.
+    #[prost(enumeration = "NeuronState", repeated, tag = "5")]
+    pub include_neuron_states: ::prost::alloc::vec::Vec<i32>,
+    /// If specified, only include neurons that follow this neuron on
+    /// some topic.
+    #[prost(fixed64, optional, tag = "6")]
+    pub followee_neuron_id: ::core::option::Option<u64>,
+    /// If specified, only include neurons that have this principal as
+    /// one of their hot keys.
+    #[prost(message, optional, tag = "7")]
+    pub hot_key: ::core::option::Option<::ic_base_types::PrincipalId>,
+    /// If true, public neurons in the "requested list" are included in
+    /// `ListNeuronsResponse.full_neurons`, even if the caller is not
+    /// otherwise authorized to read them. Such neurons are redacted, i.e.,
+    /// their hot keys, account and transfer are not included.
+    #[prost(bool, optional, tag = "8")]
+    pub include_public_neurons_in_full_neurons: ::core::option::Option<bool>,
 }
 /// A response to a `ListNeurons` request.
 ///
@@ -2668,9 +2836,16 @@ pub struct ListNeuronsResponse {
     /// For each neuron ID in the "requested list", if the neuron exists,
     /// and the caller is authorized to read the full neuron (controller,
     /// hot key, or controller or hot key of some followee on the
-    /// `ManageNeuron` topic).
+    /// `ManageNeuron` topic), or the neuron is public and
+    /// `ListNeurons.include_public_neurons_in_full_neurons` is set (in
+    /// which case the neuron is redacted).
     #[prost(message, repeated, tag = "2")]
     pub full_neurons: ::prost::alloc::vec::Vec<Neuron>,
+    /// If `ListNeurons.limit` was specified and more neurons of the
+    /// "requested list" remain, the value to use as
+    /// `ListNeurons.start_page_at` to get the next page.
+    #[prost(fixed64, optional, tag = "3")]
+    pub next_start_page_at: ::core::option::Option<u64>,
 }
 /// A response to "ListKnownNeurons"
 #[derive(
@@ -3084,6 +3259,47 @@ impl Vote {
         }
     }
 }
+/// Who can read the full information of a neuron, such as its followees and
+/// recent ballots.
+#[derive(
+    candid::CandidType,
+    candid::Deserialize,
+    serde::Serialize,
+    comparable::Comparable,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
+#[repr(i32)]
+pub enum Visibility {
+    /// This exists because proto3 defaults to the 0 value on enums.
+    Unspecified = 0,
+    /// Only principals authorized to read the full neuron (the controller, hot
+    /// keys, and those of its NeuronManagement followees) can read it.
+    Private = 1,
+    /// Anyone can read the full neuron, except for its hot keys, account
+    /// and the transfer that created it.
+    Public = 2,
+}
+impl Visibility {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Visibility::Unspecified => "VISIBILITY_UNSPECIFIED",
+            Visibility::Private => "VISIBILITY_PRIVATE",
+            Visibility::Public => "VISIBILITY_PUBLIC",
+        }
+    }
+}
 /// List of NNS functions that can be called by proposals.
 #[derive(
     candid::CandidType,
@@ -3415,3 +3631,70 @@ impl ProposalRewardStatus {
         }
     }
 }
+/// The type of action of a proposal, i.e., which variant of
+/// `Proposal.action` is set. Used to filter proposals in
+/// `ListProposalInfo`.
+#[derive(
+    candid::CandidType,
+    candid::Deserialize,
+    serde::Serialize,
+    comparable::Comparable,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
+#[repr(i32)]
+pub enum ProposalActionType {
+    Unspecified = 0,
+    ManageNeuron = 1,
+    ManageNetworkEconomics = 2,
+    Motion = 3,
+    ExecuteNnsFunction = 4,
+    ApproveGenesisKyc = 5,
+    AddOrRemoveNodeProvider = 6,
+    RewardNodeProvider = 7,
+    SetDefaultFollowees = 8,
+    RewardNodeProviders = 9,
+    RegisterKnownNeuron = 10,
+    SetSnsTokenSwapOpenTimeWindow = 11,
+    OpenSnsTokenSwap = 12,
+    CreateServiceNervousSystem = 13,
+}
+impl ProposalActionType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ProposalActionType::Unspecified => "PROPOSAL_ACTION_TYPE_UNSPECIFIED",
+            ProposalActionType::ManageNeuron => "PROPOSAL_ACTION_TYPE_MANAGE_NEURON",
+            ProposalActionType::ManageNetworkEconomics => {
+                "PROPOSAL_ACTION_TYPE_MANAGE_NETWORK_ECONOMICS"
+            }
+            ProposalActionType::Motion => "PROPOSAL_ACTION_TYPE_MOTION",
+            ProposalActionType::ExecuteNnsFunction => "PROPOSAL_ACTION_TYPE_EXECUTE_NNS_FUNCTION",
+            ProposalActionType::ApproveGenesisKyc => "PROPOSAL_ACTION_TYPE_APPROVE_GENESIS_KYC",
+            ProposalActionType::AddOrRemoveNodeProvider => {
+                "PROPOSAL_ACTION_TYPE_ADD_OR_REMOVE_NODE_PROVIDER"
+            }
+            ProposalActionType::RewardNodeProvider => "PROPOSAL_ACTION_TYPE_REWARD_NODE_PROVIDER",
+            ProposalActionType::SetDefaultFollowees => "PROPOSAL_ACTION_TYPE_SET_DEFAULT_FOLLOWEES",
+            ProposalActionType::RewardNodeProviders => "PROPOSAL_ACTION_TYPE_REWARD_NODE_PROVIDERS",
+            ProposalActionType::RegisterKnownNeuron => "PROPOSAL_ACTION_TYPE_REGISTER_KNOWN_NEURON",
+            ProposalActionType::SetSnsTokenSwapOpenTimeWindow => {
+                "PROPOSAL_ACTION_TYPE_SET_SNS_TOKEN_SWAP_OPEN_TIME_WINDOW"
+            }
+            ProposalActionType::OpenSnsTokenSwap => "PROPOSAL_ACTION_TYPE_OPEN_SNS_TOKEN_SWAP",
+            ProposalActionType::CreateServiceNervousSystem => {
+                "PROPOSAL_ACTION_TYPE_CREATE_SERVICE_NERVOUS_SYSTEM"
+            }
+        }
+    }
+}
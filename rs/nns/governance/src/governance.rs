@@ -26,16 +26,18 @@ use crate::pb::v1::{
     ListKnownNeuronsResponse, ListNeurons, ListNeuronsResponse, ListProposalInfo,
     ListProposalInfoResponse, ManageNeuron, ManageNeuronResponse,
     MostRecentMonthlyNodeProviderRewards, Motion, NetworkEconomics, Neuron, NeuronInfo,
-    NeuronState, NnsFunction, NodeProvider, OpenSnsTokenSwap, Proposal, ProposalData, ProposalInfo,
-    ProposalRewardStatus, ProposalStatus, RewardEvent, RewardNodeProvider, RewardNodeProviders,
-    SetSnsTokenSwapOpenTimeWindow, SettleCommunityFundParticipation, SwapBackgroundInformation,
-    Tally, Topic, UpdateNodeProvider, Vote, WaitForQuietState,
+    NeuronState, NnsFunction, NodeProvider, OpenSnsTokenSwap, Proposal, ProposalActionType,
+    ProposalData, ProposalInfo, ProposalRewardStatus, ProposalStatus, RewardEvent,
+    RewardNodeProvider, RewardNodeProviders, SetSnsTokenSwapOpenTimeWindow,
+    SettleCommunityFundParticipation, SwapBackgroundInformation, Tally, Topic, UpdateNodeProvider,
+    Visibility, Vote, VotingPowerEconomics, WaitForQuietState,
 };
 
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::convert::{TryFrom, TryInto};
 use std::fmt;
+use std::ops::Bound::{Excluded, Unbounded};
 use std::ops::RangeInclusive;
 use std::str::FromStr;
 use std::string::ToString;
@@ -151,6 +153,10 @@ pub const MAX_NUMBER_OF_NEURONS: usize = 200_000;
 /// The maximum number results returned by the method `list_proposals`.
 pub const MAX_LIST_PROPOSAL_RESULTS: u32 = 100;
 
+/// The maximum number of neurons returned by the method `list_neurons` when
+/// the request specifies a `limit`.
+pub const MAX_LIST_NEURONS_RESULTS: u32 = 500;
+
 /// The number of e8s per ICP;
 const E8S_PER_ICP: u64 = TOKEN_SUBDIVIDABLE_BY;
 
@@ -201,8 +207,37 @@ impl NetworkEconomics {
             minimum_icp_xdr_rate: 100,                                  // 1 XDR
             transaction_fee_e8s: DEFAULT_TRANSFER_FEE.get_e8s(),
             max_proposals_to_keep_per_topic: 100,
+            voting_power_economics: Some(VotingPowerEconomics::with_default_values()),
+        }
+    }
+}
+
+impl VotingPowerEconomics {
+    pub const DEFAULT_START_REDUCING_VOTING_POWER_AFTER_SECONDS: u64 = 6 * ONE_MONTH_SECONDS;
+    pub const DEFAULT_CLEAR_FOLLOWING_AFTER_SECONDS: u64 = ONE_MONTH_SECONDS;
+    /// Shorter periods would reduce the deciding voting power of, and clear the
+    /// following of, neurons that are only briefly inactive.
+    pub const MIN_START_REDUCING_VOTING_POWER_AFTER_SECONDS: u64 = ONE_MONTH_SECONDS;
+    pub const MIN_CLEAR_FOLLOWING_AFTER_SECONDS: u64 = ONE_MONTH_SECONDS;
+
+    pub const fn with_default_values() -> Self {
+        Self {
+            start_reducing_voting_power_after_seconds: Some(
+                Self::DEFAULT_START_REDUCING_VOTING_POWER_AFTER_SECONDS,
+            ),
+            clear_following_after_seconds: Some(Self::DEFAULT_CLEAR_FOLLOWING_AFTER_SECONDS),
         }
     }
+
+    pub fn start_reducing_voting_power_after_seconds(&self) -> u64 {
+        self.start_reducing_voting_power_after_seconds
+            .unwrap_or(Self::DEFAULT_START_REDUCING_VOTING_POWER_AFTER_SECONDS)
+    }
+
+    pub fn clear_following_after_seconds(&self) -> u64 {
+        self.clear_following_after_seconds
+            .unwrap_or(Self::DEFAULT_CLEAR_FOLLOWING_AFTER_SECONDS)
+    }
 }
 
 // Utility to transform a subaccount vector, as stored in the protobuf, into an
@@ -395,6 +430,14 @@ impl ManageNeuronResponse {
         }
     }
 
+    pub fn refresh_voting_power_response() -> Self {
+        ManageNeuronResponse {
+            command: Some(manage_neuron_response::Command::RefreshVotingPower(
+                manage_neuron_response::RefreshVotingPowerResponse {},
+            )),
+        }
+    }
+
     pub fn follow_response() -> Self {
         ManageNeuronResponse {
             command: Some(manage_neuron_response::Command::Follow(
@@ -604,6 +647,25 @@ impl Neuron {
         self.controller.as_ref().map_or(false, |c| c == principal)
     }
 
+    /// Returns true if the full information of this neuron can be read by
+    /// anyone. Known neurons are always public.
+    pub fn is_public(&self) -> bool {
+        self.known_neuron_data.is_some() || self.visibility == Some(Visibility::Public as i32)
+    }
+
+    /// Returns the information of this neuron that can be read by anyone if
+    /// the neuron is public, i.e., the neuron without the fields that allow
+    /// to act on it or to trace its stake (its hot keys, account and the
+    /// transfer that created it).
+    pub fn redacted_for_public(&self) -> Neuron {
+        Neuron {
+            hot_keys: vec![],
+            account: vec![],
+            transfer: None,
+            ..self.clone()
+        }
+    }
+
     /// Returns true if and only if `principal` is authorized to
     /// perform non-privileged operations, like vote and follow,
     /// on behalf of this neuron, i.e., if `principal` is either the
@@ -651,6 +713,58 @@ impl Neuron {
         std::cmp::min(ad_stake, u64::MAX as u128) as u64
     }
 
+    /// Returns the number of seconds since the controller (or a hot key)
+    /// of this neuron last voted directly, set following, or refreshed
+    /// the neuron's voting power.
+    pub fn seconds_since_voting_power_refreshed(&self, now_seconds: u64) -> u64 {
+        let refreshed_timestamp_seconds = self
+            .voting_power_refreshed_timestamp_seconds
+            .unwrap_or(self.created_timestamp_seconds);
+        now_seconds.saturating_sub(refreshed_timestamp_seconds)
+    }
+
+    /// Returns true if the deciding voting power of this neuron is
+    /// currently reduced because it has not been refreshed recently.
+    pub fn is_voting_power_reduced(
+        &self,
+        voting_power_economics: &VotingPowerEconomics,
+        now_seconds: u64,
+    ) -> bool {
+        self.seconds_since_voting_power_refreshed(now_seconds)
+            > voting_power_economics.start_reducing_voting_power_after_seconds()
+    }
+
+    /// Return the deciding voting power of this neuron, i.e., the voting
+    /// power that is used when deciding proposals.
+    ///
+    /// This is equal to `voting_power` if the neuron was refreshed within
+    /// the last `start_reducing_voting_power_after_seconds`. After that, it
+    /// decreases linearly and reaches zero once a further
+    /// `clear_following_after_seconds` has passed.
+    pub fn deciding_voting_power(
+        &self,
+        voting_power_economics: &VotingPowerEconomics,
+        now_seconds: u64,
+    ) -> u64 {
+        let potential_voting_power = self.voting_power(now_seconds);
+        let start_reducing_after_seconds =
+            voting_power_economics.start_reducing_voting_power_after_seconds();
+        let clear_following_after_seconds = voting_power_economics.clear_following_after_seconds();
+
+        let seconds_since_refreshed = self.seconds_since_voting_power_refreshed(now_seconds);
+        if seconds_since_refreshed <= start_reducing_after_seconds {
+            return potential_voting_power;
+        }
+        let reducing_for_seconds = seconds_since_refreshed - start_reducing_after_seconds;
+        if reducing_for_seconds >= clear_following_after_seconds {
+            return 0;
+        }
+        // We compute the reduction in u128 to avoid overflows.
+        let remaining_seconds = (clear_following_after_seconds - reducing_for_seconds) as u128;
+        (potential_voting_power as u128 * remaining_seconds / clear_following_after_seconds as u128)
+            as u64
+    }
+
     /// Given the specified `ballots`: determine how this neuron would
     /// vote on a proposal of `topic` based on which neurons this
     /// neuron follows on this topic (or on the default topic if this
@@ -1068,11 +1182,35 @@ impl Neuron {
                 }
                 Ok(())
             }
+            manage_neuron::configure::Operation::SetVisibility(set_visibility) => {
+                let visibility = set_visibility
+                    .visibility
+                    .and_then(Visibility::from_i32)
+                    .filter(|visibility| *visibility != Visibility::Unspecified)
+                    .ok_or_else(|| {
+                        GovernanceError::new_with_message(
+                            ErrorType::InvalidCommand,
+                            "Operation SetVisibility requires a valid visibility to be specified in the input",
+                        )
+                    })?;
+                if visibility == Visibility::Private && self.known_neuron_data.is_some() {
+                    return Err(GovernanceError::new_with_message(
+                        ErrorType::PreconditionFailed,
+                        "Known neurons must be public.",
+                    ));
+                }
+                self.visibility = Some(visibility as i32);
+                Ok(())
+            }
         }
     }
 
     /// Get the 'public' information associated with this neuron.
-    pub fn get_neuron_info(&self, now_seconds: u64) -> NeuronInfo {
+    pub fn get_neuron_info(
+        &self,
+        voting_power_economics: &VotingPowerEconomics,
+        now_seconds: u64,
+    ) -> NeuronInfo {
         NeuronInfo {
             retrieved_at_timestamp_seconds: now_seconds,
             state: self.state(now_seconds) as i32,
@@ -1084,6 +1222,21 @@ impl Neuron {
             stake_e8s: self.minted_stake_e8s(),
             joined_community_fund_timestamp_seconds: self.joined_community_fund_timestamp_seconds,
             known_neuron_data: self.known_neuron_data.as_ref().cloned(),
+            deciding_voting_power: Some(
+                self.deciding_voting_power(voting_power_economics, now_seconds),
+            ),
+            voting_power_refreshed_timestamp_seconds: self.voting_power_refreshed_timestamp_seconds,
+            visibility: Some(if self.is_public() {
+                Visibility::Public as i32
+            } else {
+                Visibility::Private as i32
+            }),
+            // The following of public neurons can be read by anyone.
+            followees: if self.is_public() {
+                self.followees.clone()
+            } else {
+                HashMap::new()
+            },
         }
     }
 
@@ -1272,6 +1425,34 @@ impl Proposal {
 }
 
 impl Action {
+    /// Returns the type of this action, as used to filter proposals in
+    /// `list_proposals`.
+    pub(crate) fn action_type(&self) -> ProposalActionType {
+        match self {
+            proposal::Action::ManageNeuron(_) => ProposalActionType::ManageNeuron,
+            proposal::Action::ManageNetworkEconomics(_) => {
+                ProposalActionType::ManageNetworkEconomics
+            }
+            proposal::Action::Motion(_) => ProposalActionType::Motion,
+            proposal::Action::ExecuteNnsFunction(_) => ProposalActionType::ExecuteNnsFunction,
+            proposal::Action::ApproveGenesisKyc(_) => ProposalActionType::ApproveGenesisKyc,
+            proposal::Action::AddOrRemoveNodeProvider(_) => {
+                ProposalActionType::AddOrRemoveNodeProvider
+            }
+            proposal::Action::RewardNodeProvider(_) => ProposalActionType::RewardNodeProvider,
+            proposal::Action::SetDefaultFollowees(_) => ProposalActionType::SetDefaultFollowees,
+            proposal::Action::RewardNodeProviders(_) => ProposalActionType::RewardNodeProviders,
+            proposal::Action::RegisterKnownNeuron(_) => ProposalActionType::RegisterKnownNeuron,
+            proposal::Action::SetSnsTokenSwapOpenTimeWindow(_) => {
+                ProposalActionType::SetSnsTokenSwapOpenTimeWindow
+            }
+            proposal::Action::OpenSnsTokenSwap(_) => ProposalActionType::OpenSnsTokenSwap,
+            proposal::Action::CreateServiceNervousSystem(_) => {
+                ProposalActionType::CreateServiceNervousSystem
+            }
+        }
+    }
+
     /// Returns whether proposals with such an action should be allowed to
     /// be submitted when the heap growth potential is low.
     fn allowed_when_resources_are_low(&self) -> bool {
@@ -1914,6 +2095,16 @@ impl GovernanceProto {
         Ok(())
     }
 
+    /// Returns the parameters that control the reduction of deciding voting
+    /// power of inactive neurons, falling back to the default values for any
+    /// that are not set.
+    pub fn voting_power_economics(&self) -> VotingPowerEconomics {
+        self.economics
+            .as_ref()
+            .and_then(|economics| economics.voting_power_economics.clone())
+            .unwrap_or_else(VotingPowerEconomics::with_default_values)
+    }
+
     /// Iterate over all neurons and compute `GovernanceCachedMetrics`
     pub fn compute_cached_metrics(&self, now: u64, icp_supply: Tokens) -> GovernanceCachedMetrics {
         let mut metrics = GovernanceCachedMetrics {
@@ -1927,6 +2118,7 @@ impl GovernanceProto {
         } else {
             0
         };
+        let voting_power_economics = self.voting_power_economics();
 
         for (_, neuron) in self.neurons.iter() {
             metrics.total_staked_e8s += neuron.minted_stake_e8s();
@@ -1954,6 +2146,19 @@ impl GovernanceProto {
                     neuron.minted_stake_e8s();
             }
 
+            // Only neurons that are eligible to vote contribute voting power.
+            if dissolve_delay_seconds >= MIN_DISSOLVE_DELAY_FOR_VOTE_ELIGIBILITY_SECONDS {
+                metrics.total_potential_voting_power = metrics
+                    .total_potential_voting_power
+                    .saturating_add(neuron.voting_power(now));
+                metrics.total_deciding_voting_power = metrics
+                    .total_deciding_voting_power
+                    .saturating_add(neuron.deciding_voting_power(&voting_power_economics, now));
+                if neuron.is_voting_power_reduced(&voting_power_economics, now) {
+                    metrics.neurons_with_reduced_voting_power_count += 1;
+                }
+            }
+
             match neuron.state(now) {
                 NeuronState::Unspecified => (),
                 NeuronState::Spawning => (),
@@ -2156,6 +2361,10 @@ pub struct Governance {
 
     /// The number of proposals after the last time GC was run.
     pub latest_gc_num_proposals: usize,
+
+    /// The time at which the following of inactive neurons was last
+    /// cleared.
+    latest_clear_following_of_inactive_neurons_timestamp_seconds: u64,
 }
 
 pub fn governance_minting_account() -> AccountIdentifier {
@@ -2190,6 +2399,15 @@ impl Governance {
                 latest_round_available_e8s_equivalent: Some(0),
             })
         }
+        // Neurons that predate the reduction of voting power of inactive
+        // neurons are considered to have been refreshed now, so that their
+        // controllers have the full period to refresh them.
+        let now = env.now();
+        for neuron in proto.neurons.values_mut() {
+            if neuron.voting_power_refreshed_timestamp_seconds.is_none() {
+                neuron.voting_power_refreshed_timestamp_seconds = Some(now);
+            }
+        }
 
         let mut gov = Self {
             proto,
@@ -2202,6 +2420,7 @@ impl Governance {
             closest_proposal_deadline_timestamp_seconds: 0,
             latest_gc_timestamp_seconds: 0,
             latest_gc_num_proposals: 0,
+            latest_clear_following_of_inactive_neurons_timestamp_seconds: 0,
         };
 
         gov.initialize_indices();
@@ -2562,34 +2781,104 @@ impl Governance {
     }
 
     /// See `ListNeurons`.
+    ///
+    /// The neurons of the "requested list" are considered in increasing
+    /// order of neuron ID, so that a `limit` and `start_page_at` can be used
+    /// to page through them.
     pub fn list_neurons_by_principal(
         &self,
         req: &ListNeurons,
         caller: &PrincipalId,
     ) -> ListNeuronsResponse {
         let now = self.env.now();
-        let implicitly_requested_neurons = if req.include_neurons_readable_by_caller {
-            self.get_neuron_ids_by_principal(caller)
-        } else {
-            Vec::new()
+        let voting_power_economics = self.proto.voting_power_economics();
+        let mut requested_list: BTreeSet<u64> = req.neuron_ids.iter().copied().collect();
+        if req.include_neurons_readable_by_caller {
+            requested_list.extend(self.get_neuron_ids_by_principal(caller));
+        }
+
+        let include_neuron_states: HashSet<i32> =
+            req.include_neuron_states.iter().copied().collect();
+        let include_public_neurons_in_full_neurons =
+            req.include_public_neurons_in_full_neurons.unwrap_or(false);
+        // Tap into the `topic_followee_index` for the followers of the
+        // followee, on any topic.
+        let followers: Option<HashSet<u64>> = req.followee_neuron_id.map(|followee| {
+            self.topic_followee_index
+                .values()
+                .filter_map(|followee_to_followers| followee_to_followers.get(&followee))
+                .flatten()
+                .copied()
+                .collect()
+        });
+        let filter_all = |id: u64, neuron: &Neuron| -> bool {
+            // Filter out neurons by state.
+            if !(include_neuron_states.is_empty()
+                || include_neuron_states.contains(&(neuron.state(now) as i32)))
+            {
+                return false;
+            }
+            // Filter out neurons that do not follow the followee.
+            if let Some(followers) = &followers {
+                if !followers.contains(&id) {
+                    return false;
+                }
+            }
+            // Filter out neurons that do not have the hot key.
+            if let Some(hot_key) = &req.hot_key {
+                if !neuron.hot_keys.contains(hot_key) {
+                    return false;
+                }
+            }
+            true
         };
-        let requested_list = || {
-            req.neuron_ids
-                .iter()
-                .chain(implicitly_requested_neurons.iter())
+
+        let start = match req.start_page_at {
+            Some(start_page_at) => Excluded(start_page_at),
+            None => Unbounded,
+        };
+        let mut neurons = requested_list
+            .range((start, Unbounded))
+            .filter_map(|id| self.proto.neurons.get(id).map(|neuron| (*id, neuron)))
+            .filter(|(id, neuron)| filter_all(*id, neuron));
+
+        let limit = req.limit.map(|limit| {
+            let limit = if limit == 0 || limit > MAX_LIST_NEURONS_RESULTS {
+                MAX_LIST_NEURONS_RESULTS
+            } else {
+                limit
+            };
+            limit as usize
+        });
+        let page: Vec<(u64, &Neuron)> = match limit {
+            Some(limit) => neurons.by_ref().take(limit).collect(),
+            None => neurons.by_ref().collect(),
+        };
+        // Only point to the next page if there is one.
+        let next_start_page_at = if neurons.next().is_some() {
+            page.last().map(|(id, _)| *id)
+        } else {
+            None
         };
+
         ListNeuronsResponse {
-            neuron_infos: requested_list()
-                .filter_map(|x| {
-                    self.proto
-                        .neurons
-                        .get(x)
-                        .map(|y| (*x, y.get_neuron_info(now)))
-                })
+            neuron_infos: page
+                .iter()
+                .map(|(id, neuron)| (*id, neuron.get_neuron_info(&voting_power_economics, now)))
                 .collect(),
-            full_neurons: requested_list()
-                .filter_map(|x| self.get_full_neuron(&NeuronId { id: *x }, caller).ok())
+            full_neurons: page
+                .iter()
+                .filter_map(|(id, neuron)| {
+                    match self.get_full_neuron(&NeuronId { id: *id }, caller) {
+                        Ok(full_neuron) => Some(full_neuron),
+                        Err(_) if include_public_neurons_in_full_neurons && neuron.is_public() => {
+                            Some(neuron.redacted_for_public())
+                        }
+                        Err(_) => None,
+                    }
+                })
                 .collect(),
+            next_start_page_at,
         }
     }
 
@@ -3078,6 +3367,8 @@ impl Governance {
             joined_community_fund_timestamp_seconds: parent_neuron
                 .joined_community_fund_timestamp_seconds,
             known_neuron_data: None,
+            voting_power_refreshed_timestamp_seconds: Some(creation_timestamp_seconds),
+            visibility: None,
             spawn_at_timestamp_seconds: None,
         };
 
@@ -3358,6 +3649,8 @@ impl Governance {
             // considered part of the community fund.
             joined_community_fund_timestamp_seconds: None,
             known_neuron_data: None,
+            voting_power_refreshed_timestamp_seconds: Some(creation_timestamp_seconds),
+            visibility: None,
         };
 
         // `add_neuron` will verify that `child_neuron.controller` `is_self_authenticating()`, so we don't need to check it here.
@@ -3675,6 +3968,8 @@ impl Governance {
             not_for_profit: false,
             joined_community_fund_timestamp_seconds: None,
             known_neuron_data: None,
+            voting_power_refreshed_timestamp_seconds: Some(creation_timestamp_seconds),
+            visibility: None,
             spawn_at_timestamp_seconds: None,
         };
 
@@ -3783,7 +4078,7 @@ impl Governance {
             .get(&id.id)
             .ok_or_else(|| GovernanceError::new(ErrorType::NotFound))?;
         let now = self.env.now();
-        Ok(neuron.get_neuron_info(now))
+        Ok(neuron.get_neuron_info(&self.proto.voting_power_economics(), now))
     }
 
     /// Returns the neuron info for a neuron identified by id or subaccount.
@@ -3795,7 +4090,7 @@ impl Governance {
     ) -> Result<NeuronInfo, GovernanceError> {
         let neuron = self.find_neuron(by)?;
         let now = self.env.now();
-        Ok(neuron.get_neuron_info(now))
+        Ok(neuron.get_neuron_info(&self.proto.voting_power_economics(), now))
     }
 
     /// Returns the complete neuron data for a given neuron `id` or
@@ -4053,6 +4348,10 @@ impl Governance {
     /// EXECUTE_NNS_FUNCTION_PAYLOAD_LISTING_BYTES_MAX.  The caller can
     /// retrieve dropped payloads by calling `get_proposal_info` for
     /// each proposal of interest.
+    ///
+    /// - Proposals can further be filtered by topic, status, reward
+    /// status, proposer, action type and creation time (see
+    /// `ListProposalInfo`).
     pub fn list_proposals(
         &self,
         caller: &PrincipalId,
@@ -4067,6 +4366,8 @@ impl Governance {
         let include_reward_status: HashSet<i32> =
             req.include_reward_status.iter().cloned().collect();
         let include_status: HashSet<i32> = req.include_status.iter().cloned().collect();
+        let include_proposer: HashSet<u64> = req.include_proposer.iter().map(|x| x.id).collect();
+        let include_action_type: HashSet<i32> = req.include_action_type.iter().cloned().collect();
         let now = self.env.now();
         let filter_all = |data: &ProposalData| -> bool {
             let topic = data.topic();
@@ -4086,6 +4387,33 @@ impl Governance {
             if !(include_status.is_empty() || include_status.contains(&(data.status() as i32))) {
                 return false;
             }
+            // Filter out proposals by proposer.
+            if !(include_proposer.is_empty()
+                || data
+                    .proposer
+                    .as_ref()
+                    .map_or(false, |proposer| include_proposer.contains(&proposer.id)))
+            {
+                return false;
+            }
+            // Filter out proposals by action type.
+            if !(include_action_type.is_empty()
+                || data
+                    .proposal
+                    .as_ref()
+                    .and_then(|proposal| proposal.action.as_ref())
+                    .map_or(false, |action| {
+                        include_action_type.contains(&(action.action_type() as i32))
+                    }))
+            {
+                return false;
+            }
+            // Filter out proposals made after the end of the date range.
+            if let Some(max_proposal_timestamp_seconds) = req.max_proposal_timestamp_seconds {
+                if data.proposal_timestamp_seconds > max_proposal_timestamp_seconds {
+                    return false;
+                }
+            }
             // Filter out proposals by their restricted status.
             self.proposal_is_visible_to_neurons(data, caller_neurons)
         };
@@ -4103,8 +4431,15 @@ impl Governance {
         } else {
             props.range(..)
         };
-        // Now reverse the range, filter, and restrict to 'limit'.
-        let limited_rng = rng.rev().filter(|(_, x)| filter_all(x)).take(limit);
+        // Now reverse the range, filter, and restrict to 'limit'. As proposal
+        // IDs are assigned in order of creation, the iteration can stop at
+        // the first proposal made before the start of the date range.
+        let min_proposal_timestamp_seconds = req.min_proposal_timestamp_seconds.unwrap_or(0);
+        let limited_rng = rng
+            .rev()
+            .take_while(|(_, x)| x.proposal_timestamp_seconds >= min_proposal_timestamp_seconds)
+            .filter(|(_, x)| filter_all(x))
+            .take(limit);
         //
         let proposal_info = limited_rng
             .map(|(_, y)| y)
@@ -4405,6 +4740,8 @@ impl Governance {
                     transfer: None,
                     joined_community_fund_timestamp_seconds: None,
                     known_neuron_data: None,
+                    voting_power_refreshed_timestamp_seconds: Some(now),
+                    visibility: None,
                     spawn_at_timestamp_seconds: None,
                 };
                 self.add_neuron(nid.id, neuron)
@@ -4650,6 +4987,23 @@ impl Governance {
                         economics.max_proposals_to_keep_per_topic =
                             ne.max_proposals_to_keep_per_topic
                     }
+                    if let Some(proposed) = &ne.voting_power_economics {
+                        let voting_power_economics = economics
+                            .voting_power_economics
+                            .get_or_insert_with(VotingPowerEconomics::with_default_values);
+                        if proposed
+                            .start_reducing_voting_power_after_seconds
+                            .unwrap_or(0)
+                            != 0
+                        {
+                            voting_power_economics.start_reducing_voting_power_after_seconds =
+                                proposed.start_reducing_voting_power_after_seconds
+                        }
+                        if proposed.clear_following_after_seconds.unwrap_or(0) != 0 {
+                            voting_power_economics.clear_following_after_seconds =
+                                proposed.clear_following_after_seconds
+                        }
+                    }
                 } else {
                     // If for some reason, we don't have an
                     // 'economics' proto, use the proposed one.
@@ -5346,8 +5700,11 @@ impl Governance {
                 self.validate_create_service_nervous_system(create_service_nervous_system)
             }
 
+            Action::ManageNetworkEconomics(network_economics) => {
+                validate_manage_network_economics(network_economics)
+            }
+
             Action::ManageNeuron(_)
-            | Action::ManageNetworkEconomics(_)
             | Action::ApproveGenesisKyc(_)
             | Action::AddOrRemoveNodeProvider(_)
             | Action::RewardNodeProvider(_)
@@ -5700,11 +6057,12 @@ impl Governance {
             LOG_PREFIX,
             proposal
         );
+        let voting_power_economics = self.proto.voting_power_economics();
         let mut electoral_roll = HashMap::<u64, Ballot>::new();
         let mut total_power: u128 = 0;
         for (k, v) in self.proto.neurons.iter() {
             // If this neuron is eligible to vote, record its
-            // voting power at the time of making the
+            // deciding voting power at the time of making the
             // proposal.
             if v.dissolve_delay_seconds(now_seconds)
                 < MIN_DISSOLVE_DELAY_FOR_VOTE_ELIGIBILITY_SECONDS
@@ -5712,7 +6070,7 @@ impl Governance {
                 // Not eligible due to dissolve delay.
                 continue;
             }
-            let power = v.voting_power(now_seconds);
+            let power = v.deciding_voting_power(&voting_power_economics, now_seconds);
             total_power += power as u128;
             electoral_roll.insert(
                 *k,
@@ -6019,11 +6377,43 @@ impl Governance {
             );
         }
 
+        // Voting directly shows that the neuron's controller (or hot key)
+        // is still active.
+        if let Some(neuron) = self.proto.neurons.get_mut(&neuron_id.id) {
+            neuron.voting_power_refreshed_timestamp_seconds = Some(now_seconds);
+        }
+
         self.process_proposal(proposal_id.id);
 
         Ok(())
     }
 
+    /// Records that the controller (or a hot key) of the neuron is still
+    /// active, so that the neuron keeps its full deciding voting power.
+    fn refresh_voting_power(
+        &mut self,
+        id: &NeuronId,
+        caller: &PrincipalId,
+    ) -> Result<(), GovernanceError> {
+        let now_seconds = self.env.now();
+        let neuron = self.proto.neurons.get_mut(&id.id).ok_or_else(|| {
+            GovernanceError::new_with_message(
+                ErrorType::NotFound,
+                format!("Neuron not found: {}", id.id),
+            )
+        })?;
+        // Check that the caller is authorized, i.e., either the
+        // controller or a registered hot key.
+        if !neuron.is_authorized_to_vote(caller) {
+            return Err(GovernanceError::new_with_message(
+                ErrorType::NotAuthorized,
+                "Caller is not authorized to refresh the voting power of neuron.",
+            ));
+        }
+        neuron.voting_power_refreshed_timestamp_seconds = Some(now_seconds);
+        Ok(())
+    }
+
     /// Add or remove followees for this neuron for a specified topic.
     ///
     /// If the list of followees is empty, remove the followees for
@@ -6039,6 +6429,7 @@ impl Governance {
         // The implementation of this method is complicated by the
         // fact that we have to maintain a reverse index of all follow
         // relationships, i.e., the `topic_followee_index`.
+        let now_seconds = self.env.now();
 
         // Find the neuron to modify.
         let neuron = self.proto.neurons.get_mut(&id.id).ok_or_else(||
@@ -6117,6 +6508,7 @@ impl Governance {
                     let all_followers = cache.entry(followee.id).or_insert_with(BTreeSet::new);
                     all_followers.insert(id.id);
                 }
+                neuron.voting_power_refreshed_timestamp_seconds = Some(now_seconds);
                 Ok(())
             } else {
                 // Attempt to follow for an invalid topic: the set
@@ -6130,6 +6522,7 @@ impl Governance {
         } else {
             // This operation clears the followees for the given topic.
             neuron.followees.remove(&f.topic);
+            neuron.voting_power_refreshed_timestamp_seconds = Some(now_seconds);
             Ok(())
         }
     }
@@ -6355,6 +6748,8 @@ impl Governance {
             recent_ballots: vec![],
             joined_community_fund_timestamp_seconds: None,
             known_neuron_data: None,
+            voting_power_refreshed_timestamp_seconds: Some(now),
+            visibility: None,
             spawn_at_timestamp_seconds: None,
         };
 
@@ -6477,6 +6872,8 @@ impl Governance {
             self.known_neuron_name_set.remove(old_name);
         }
         neuron.known_neuron_data = Some(known_neuron_data.clone());
+        // Known neurons are public.
+        neuron.visibility = Some(Visibility::Public as i32);
         self.known_neuron_name_set
             .insert(known_neuron_data.name.clone());
 
@@ -6583,6 +6980,9 @@ impl Governance {
             Some(manage_neuron::Command::RegisterVote(v)) => self
                 .register_vote(&id, caller, v)
                 .map(|_| ManageNeuronResponse::register_vote_response()),
+            Some(manage_neuron::Command::RefreshVotingPower(_)) => self
+                .refresh_voting_power(&id, caller)
+                .map(|_| ManageNeuronResponse::refresh_voting_power_response()),
             Some(manage_neuron::Command::ClaimOrRefresh(_)) => {
                 panic!("This should have already returned")
             }
@@ -6732,9 +7132,71 @@ impl Governance {
         }
 
         self.maybe_move_staked_maturity();
+        self.maybe_clear_following_of_inactive_neurons();
         self.maybe_gc();
     }
 
+    /// Clears the following (on all topics except NeuronManagement) of
+    /// neurons that have not been refreshed for long enough that their
+    /// deciding voting power has been reduced to zero. Runs at most once
+    /// per day.
+    fn maybe_clear_following_of_inactive_neurons(&mut self) {
+        let now_seconds = self.env.now();
+        if now_seconds
+            < self.latest_clear_following_of_inactive_neurons_timestamp_seconds + ONE_DAY_SECONDS
+        {
+            return;
+        }
+        self.latest_clear_following_of_inactive_neurons_timestamp_seconds = now_seconds;
+
+        let voting_power_economics = self.proto.voting_power_economics();
+        let inactive_after_seconds = voting_power_economics
+            .start_reducing_voting_power_after_seconds()
+            .saturating_add(voting_power_economics.clear_following_after_seconds());
+
+        let neuron_management_topic = Topic::NeuronManagement as i32;
+        let inactive_neuron_ids: Vec<u64> = self
+            .proto
+            .neurons
+            .iter()
+            .filter(|(_, neuron)| {
+                neuron.seconds_since_voting_power_refreshed(now_seconds) >= inactive_after_seconds
+                    && neuron
+                        .followees
+                        .keys()
+                        .any(|topic| *topic != neuron_management_topic)
+            })
+            .map(|(id, _)| *id)
+            .collect();
+
+        for id in &inactive_neuron_ids {
+            let neuron = match self.proto.neurons.get_mut(id) {
+                Some(neuron) => neuron,
+                None => continue,
+            };
+            GovernanceProto::remove_neuron_from_topic_followee_index(
+                &mut self.topic_followee_index,
+                neuron,
+            );
+            neuron
+                .followees
+                .retain(|topic, _| *topic == neuron_management_topic);
+            GovernanceProto::add_neuron_to_topic_followee_index(
+                &mut self.topic_followee_index,
+                neuron,
+            );
+        }
+
+        if !inactive_neuron_ids.is_empty() {
+            println!(
+                "{}Cleared the following of {} inactive neurons at timestamp {} seconds",
+                LOG_PREFIX,
+                inactive_neuron_ids.len(),
+                now_seconds
+            );
+        }
+    }
+
     fn should_update_maturity_modulation(&self) -> bool {
         // Check if we're already updating the neuron maturity modulation.
         let now_seconds = self.env.now();
@@ -7744,6 +8206,52 @@ fn validate_motion(motion: &Motion) -> Result<(), GovernanceError> {
     Ok(())
 }
 
+/// Validates the voting power economics of a ManageNetworkEconomics proposal.
+/// Unset or zero values leave the current values unchanged, all other values
+/// must be at least the respective minimum.
+fn validate_manage_network_economics(
+    network_economics: &NetworkEconomics,
+) -> Result<(), GovernanceError> {
+    let voting_power_economics = match &network_economics.voting_power_economics {
+        Some(voting_power_economics) => voting_power_economics,
+        None => return Ok(()),
+    };
+
+    let mut defects = vec![];
+    for (field_name, value, minimum) in [
+        (
+            "start_reducing_voting_power_after_seconds",
+            voting_power_economics.start_reducing_voting_power_after_seconds,
+            VotingPowerEconomics::MIN_START_REDUCING_VOTING_POWER_AFTER_SECONDS,
+        ),
+        (
+            "clear_following_after_seconds",
+            voting_power_economics.clear_following_after_seconds,
+            VotingPowerEconomics::MIN_CLEAR_FOLLOWING_AFTER_SECONDS,
+        ),
+    ] {
+        match value {
+            Some(value) if value != 0 && value < minimum => defects.push(format!(
+                "voting_power_economics.{} ({}) must be at least {} seconds",
+                field_name, value, minimum
+            )),
+            _ => (),
+        }
+    }
+
+    if defects.is_empty() {
+        Ok(())
+    } else {
+        Err(GovernanceError::new_with_message(
+            ErrorType::InvalidProposal,
+            format!(
+                "ManageNetworkEconomics proposal is invalid: {}",
+                defects.join(", ")
+            ),
+        ))
+    }
+}
+
 /// Always fails, because this type of proposal is obsolete.
 fn validate_set_sns_token_swap_open_time_window(
     action: &SetSnsTokenSwapOpenTimeWindow,
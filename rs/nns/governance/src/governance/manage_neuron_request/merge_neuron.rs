@@ -211,8 +211,9 @@ impl ManageNeuronRequestHandler<manage_neuron::Merge>
         let target_neuron = gov_proxy.get_neuron(&self.target_neuron_id)?.clone();
 
         let now = gov_proxy.now();
-        let source_neuron_info = source_neuron.get_neuron_info(now);
-        let target_neuron_info = target_neuron.get_neuron_info(now);
+        let voting_power_economics = gov_proxy.voting_power_economics();
+        let source_neuron_info = source_neuron.get_neuron_info(&voting_power_economics, now);
+        let target_neuron_info = target_neuron.get_neuron_info(&voting_power_economics, now);
 
         Ok(ManageNeuronResponse::merge_response(MergeResponse {
             source_neuron: Some(source_neuron),
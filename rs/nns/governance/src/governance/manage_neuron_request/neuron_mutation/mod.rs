@@ -7,7 +7,7 @@ use async_trait::async_trait;
 use ic_nns_common::pb::v1::NeuronId;
 
 use crate::governance::Governance;
-use crate::pb::v1::{GovernanceError, Neuron};
+use crate::pb::v1::{GovernanceError, Neuron, VotingPowerEconomics};
 
 pub(crate) mod burn_fees_mutation;
 pub(crate) mod merge_neuron_mutation;
@@ -42,6 +42,16 @@ impl GovernanceMutationProxy<'_> {
         }
     }
 
+    /// Get the parameters that control the reduction of deciding voting power.
+    pub fn voting_power_economics(&self) -> VotingPowerEconomics {
+        match self {
+            GovernanceMutationProxy::Committing(real) => real.proto.voting_power_economics(),
+            GovernanceMutationProxy::Simulating(simulating) => {
+                simulating.real_gov.proto.voting_power_economics()
+            }
+        }
+    }
+
     /// Retrieve a reference to a neuron, if it exists
     pub fn get_neuron(&self, neuron_id: &NeuronId) -> Result<&Neuron, GovernanceError> {
         match self {
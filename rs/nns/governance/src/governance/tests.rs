@@ -54,6 +54,7 @@ const PARAMS: sns_swap_pb::Params = sns_swap_pb::Params {
     neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
         count: 3,
         dissolve_delay_interval_seconds: 7890000, // 3 months
+        vesting_period_seconds: None,
     }),
     sale_delay_seconds: None,
 };
@@ -415,6 +416,7 @@ async fn validate_open_sns_token_swap_params_basket_count_too_small() {
                         NeuronBasketConstructionParameters {
                             count: 0,                                 // Too small
                             dissolve_delay_interval_seconds: 7890000, // 3 months
+                            vesting_period_seconds: None,
                         },
                     ),
                     ..PARAMS.clone()
@@ -437,6 +439,7 @@ async fn validate_open_sns_token_swap_params_zero_dissolve_delay() {
                         NeuronBasketConstructionParameters {
                             count: 12,
                             dissolve_delay_interval_seconds: 0, // Too small
+                            vesting_period_seconds: None,
                         },
                     ),
                     ..PARAMS.clone()
@@ -459,6 +462,7 @@ async fn validate_open_sns_token_swap_params_practically_forever_dissolve_delay(
                         NeuronBasketConstructionParameters {
                             count: 2,
                             dissolve_delay_interval_seconds: u64::MAX, // Will result in overflow
+                            vesting_period_seconds: None,
                         },
                     ),
                     ..PARAMS.clone()
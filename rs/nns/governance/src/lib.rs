@@ -289,6 +289,24 @@ pub fn encode_metrics(
             "Total number of neurons having an invalid stake, e.g. less than the minimum allowed stake.",
         )?;
 
+        w.encode_gauge(
+            "governance_total_potential_voting_power",
+            metrics.total_potential_voting_power as f64,
+            "Total voting power of neurons eligible to vote, ignoring any reduction due to inactivity.",
+        )?;
+
+        w.encode_gauge(
+            "governance_total_deciding_voting_power",
+            metrics.total_deciding_voting_power as f64,
+            "Total voting power of neurons eligible to vote that counts when deciding proposals.",
+        )?;
+
+        w.encode_gauge(
+            "governance_neurons_with_reduced_voting_power_count",
+            metrics.neurons_with_reduced_voting_power_count as f64,
+            "Total number of neurons eligible to vote whose deciding voting power is reduced because they have not been refreshed recently.",
+        )?;
+
         w.encode_histogram(
             "governance_dissolving_neurons_e8s",
             metrics
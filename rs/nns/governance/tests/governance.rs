@@ -45,8 +45,8 @@ use ic_nns_governance::{
             disburse::Amount,
             ChangeAutoStakeMaturity, ClaimOrRefresh, Command, Configure, Disburse,
             DisburseToNeuron, Follow, IncreaseDissolveDelay, JoinCommunityFund, LeaveCommunityFund,
-            Merge, MergeMaturity, NeuronIdOrSubaccount, SetDissolveTimestamp, Spawn, Split,
-            StartDissolving,
+            Merge, MergeMaturity, NeuronIdOrSubaccount, RefreshVotingPower, SetDissolveTimestamp,
+            SetVisibility, Spawn, Split, StartDissolving,
         },
         manage_neuron_response::{self, Command as CommandResponse, MergeMaturityResponse},
         neuron::{self, DissolveState, Followees},
@@ -57,12 +57,12 @@ use ic_nns_governance::{
         ExecuteNnsFunction, Governance as GovernanceProto, GovernanceError, KnownNeuron,
         KnownNeuronData, ListNeurons, ListNeuronsResponse, ListProposalInfo, ManageNeuron,
         ManageNeuronResponse, Motion, NetworkEconomics, Neuron, NeuronState, NnsFunction,
-        NodeProvider, OpenSnsTokenSwap, Proposal, ProposalData,
+        NodeProvider, OpenSnsTokenSwap, Proposal, ProposalActionType, ProposalData,
         ProposalRewardStatus::{self, AcceptVotes, ReadyToSettle},
         ProposalStatus::{self, Rejected},
         RewardEvent, RewardNodeProvider, RewardNodeProviders, SetDefaultFollowees,
         SettleCommunityFundParticipation, SwapBackgroundInformation, Tally, Topic,
-        UpdateNodeProvider, Vote, WaitForQuietState,
+        UpdateNodeProvider, Visibility, Vote, VotingPowerEconomics, WaitForQuietState,
     },
 };
 use ic_sns_root::{GetSnsCanistersSummaryRequest, GetSnsCanistersSummaryResponse};
@@ -72,7 +72,7 @@ use ic_sns_swap::pb::v1::{
 use ic_sns_wasm::pb::v1::{DeployedSns, ListDeployedSnsesRequest, ListDeployedSnsesResponse};
 use icp_ledger::{AccountIdentifier, Memo, Subaccount, Tokens};
 use lazy_static::lazy_static;
-use maplit::{btreemap, hashmap};
+use maplit::{btreemap, btreeset, hashmap};
 use pretty_assertions::{assert_eq, assert_ne};
 use proptest::prelude::{prop_assert, prop_assert_eq, proptest, TestCaseError};
 use rand::{prelude::IteratorRandom, rngs::StdRng, Rng, SeedableRng};
@@ -3811,7 +3811,9 @@ fn create_mature_neuron(dissolved: bool) -> (fake::FakeDriver, Governance, Neuro
         // The neuron state should now be "Dissolved", meaning we can
         // now disburse the neuron.
         assert_eq!(
-            neuron.get_neuron_info(driver.now()).state(),
+            neuron
+                .get_neuron_info(&VotingPowerEconomics::with_default_values(), driver.now())
+                .state(),
             NeuronState::Dissolved
         );
     } else {
@@ -4630,7 +4632,9 @@ fn test_neuron_split_fails() {
         .neuron_minimum_stake_e8s;
 
     assert_eq!(
-        neuron.get_neuron_info(driver.now()).state(),
+        neuron
+            .get_neuron_info(&VotingPowerEconomics::with_default_values(), driver.now())
+            .state(),
         NeuronState::NotDissolving
     );
 
@@ -4735,7 +4739,9 @@ fn test_neuron_split() {
     let transaction_fee = gov.proto.economics.as_ref().unwrap().transaction_fee_e8s;
 
     assert_eq!(
-        neuron.get_neuron_info(driver.now()).state(),
+        neuron
+            .get_neuron_info(&VotingPowerEconomics::with_default_values(), driver.now())
+            .state(),
         NeuronState::NotDissolving
     );
 
@@ -5675,7 +5681,9 @@ fn test_neuron_spawn() {
     let neuron = gov.get_neuron_mut(&id).expect("Neuron did not exist");
 
     assert_eq!(
-        neuron.get_neuron_info(driver.now()).state(),
+        neuron
+            .get_neuron_info(&VotingPowerEconomics::with_default_values(), driver.now())
+            .state(),
         NeuronState::NotDissolving
     );
 
@@ -5838,7 +5846,9 @@ fn test_neuron_spawn_with_subaccount() {
     let neuron = gov.get_neuron_mut(&id).expect("Neuron did not exist");
 
     assert_eq!(
-        neuron.get_neuron_info(driver.now()).state(),
+        neuron
+            .get_neuron_info(&VotingPowerEconomics::with_default_values(), driver.now())
+            .state(),
         NeuronState::NotDissolving
     );
 
@@ -6012,7 +6022,9 @@ fn assert_neuron_spawn_partial(
 
     let neuron = gov.get_neuron_mut(&id).expect("Neuron did not exist");
     assert_eq!(
-        neuron.get_neuron_info(driver.now()).state(),
+        neuron
+            .get_neuron_info(&VotingPowerEconomics::with_default_values(), driver.now())
+            .state(),
         NeuronState::NotDissolving
     );
 
@@ -6344,7 +6356,9 @@ fn test_disburse_to_neuron() {
     // The neuron state should now be "Dissolved", meaning we can
     // now disburse the neuron.
     assert_eq!(
-        parent_neuron.get_neuron_info(driver.now()).state(),
+        parent_neuron
+            .get_neuron_info(&VotingPowerEconomics::with_default_values(), driver.now())
+            .state(),
         NeuronState::Dissolved
     );
 
@@ -7642,6 +7656,7 @@ fn test_default_followees() {
                 neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
                     count: BASKET_COUNT,
                     dissolve_delay_interval_seconds: 30 * ONE_DAY_SECONDS,
+                    vesting_period_seconds: None,
                 }),
                 sale_delay_seconds: None,
             }),
@@ -8568,6 +8583,7 @@ fn test_list_neurons() {
         &ListNeurons {
             include_neurons_readable_by_caller: true,
             neuron_ids: vec![],
+            ..Default::default()
         },
         &p1,
     );
@@ -8588,6 +8604,7 @@ fn test_list_neurons() {
         &ListNeurons {
             include_neurons_readable_by_caller: true,
             neuron_ids: vec![200],
+            ..Default::default()
         },
         &p5,
     );
@@ -8614,6 +8631,7 @@ fn test_list_neurons() {
         &ListNeurons {
             include_neurons_readable_by_caller: true,
             neuron_ids: vec![42, 99],
+            ..Default::default()
         },
         &p4,
     );
@@ -8635,6 +8653,222 @@ fn test_list_neurons() {
     );
 }
 
+// Test that listing of neurons can be paginated, and filtered by neuron
+// state, followee and hot key.
+#[test]
+fn test_list_neurons_with_pagination_and_filters() {
+    let p1 = principal(1);
+    let p2 = principal(2);
+    // Create 10 neurons with IDs 1-10, all controlled by p1. The neurons
+    // with an even ID are dissolving, the others are not.
+    let mut proto = GovernanceProto {
+        neurons: (1..=10)
+            .map(|id| {
+                let dissolve_state = if id % 2 == 0 {
+                    DissolveState::WhenDissolvedTimestampSeconds(1_000)
+                } else {
+                    DissolveState::DissolveDelaySeconds(1_000)
+                };
+                (
+                    id,
+                    Neuron {
+                        id: Some(NeuronId { id }),
+                        controller: Some(p1),
+                        dissolve_state: Some(dissolve_state),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect::<HashMap<u64, Neuron>>(),
+        ..Default::default()
+    };
+    // Neurons 3 and 4 follow neuron 9.
+    for id in [3, 4] {
+        proto.neurons.get_mut(&id).unwrap().followees = hashmap! {
+            Topic::Governance as i32 => Followees {
+                followees: vec![NeuronId { id: 9 }],
+            },
+        };
+    }
+    // Neuron 5 has p2 as hot key.
+    proto.neurons.get_mut(&5).unwrap().hot_keys = vec![p2];
+    let driver = fake::FakeDriver::default().at(100);
+    let gov = Governance::new(
+        proto,
+        driver.get_fake_env(),
+        driver.get_fake_ledger(),
+        driver.get_fake_cmc(),
+    );
+    let listed_ids = |response: &ListNeuronsResponse| -> Vec<u64> {
+        response
+            .full_neurons
+            .iter()
+            .map(|neuron| neuron.id.as_ref().unwrap().id)
+            .collect()
+    };
+
+    // Page through the neurons of p1, 4 at a time.
+    let mut pages = vec![];
+    let mut start_page_at = None;
+    loop {
+        let response = gov.list_neurons_by_principal(
+            &ListNeurons {
+                include_neurons_readable_by_caller: true,
+                limit: Some(4),
+                start_page_at,
+                ..Default::default()
+            },
+            &p1,
+        );
+        assert_eq!(
+            response
+                .neuron_infos
+                .keys()
+                .copied()
+                .collect::<HashSet<u64>>(),
+            listed_ids(&response).into_iter().collect::<HashSet<u64>>()
+        );
+        pages.push(listed_ids(&response));
+        start_page_at = response.next_start_page_at;
+        if start_page_at.is_none() {
+            break;
+        }
+    }
+    assert_eq!(pages, vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8], vec![9, 10]]);
+
+    // Explicitly requested neurons are paginated the same way.
+    let response = gov.list_neurons_by_principal(
+        &ListNeurons {
+            neuron_ids: vec![7, 2, 7, 5],
+            limit: Some(2),
+            ..Default::default()
+        },
+        &p1,
+    );
+    assert_eq!(listed_ids(&response), vec![2, 5]);
+    assert_eq!(response.next_start_page_at, Some(5));
+
+    // Filter by neuron state.
+    let response = gov.list_neurons_by_principal(
+        &ListNeurons {
+            include_neurons_readable_by_caller: true,
+            include_neuron_states: vec![NeuronState::Dissolving as i32],
+            ..Default::default()
+        },
+        &p1,
+    );
+    assert_eq!(listed_ids(&response), vec![2, 4, 6, 8, 10]);
+    assert_eq!(response.next_start_page_at, None);
+
+    // Filter by followee, combined with the neuron state.
+    let response = gov.list_neurons_by_principal(
+        &ListNeurons {
+            include_neurons_readable_by_caller: true,
+            followee_neuron_id: Some(9),
+            include_neuron_states: vec![NeuronState::NotDissolving as i32],
+            ..Default::default()
+        },
+        &p1,
+    );
+    assert_eq!(listed_ids(&response), vec![3]);
+
+    // Filter by hot key.
+    let response = gov.list_neurons_by_principal(
+        &ListNeurons {
+            include_neurons_readable_by_caller: true,
+            hot_key: Some(p2),
+            ..Default::default()
+        },
+        &p1,
+    );
+    assert_eq!(listed_ids(&response), vec![5]);
+}
+
+// Test that proposals can be filtered by proposer, action type and the
+// time at which they were made.
+#[test]
+fn test_list_proposals_with_proposer_action_type_and_time_filters() {
+    let proposal_data = |id: u64, proposer: u64, action: proposal::Action| ProposalData {
+        id: Some(ProposalId { id }),
+        proposer: Some(NeuronId { id: proposer }),
+        proposal: Some(Proposal {
+            title: Some("A Reasonable Title".to_string()),
+            summary: "summary".to_string(),
+            action: Some(action),
+            ..Default::default()
+        }),
+        proposal_timestamp_seconds: 10 * id,
+        ..Default::default()
+    };
+    let motion = || {
+        proposal::Action::Motion(Motion {
+            motion_text: "me like proposals".to_string(),
+        })
+    };
+    let network_economics =
+        || proposal::Action::ManageNetworkEconomics(NetworkEconomics::default());
+    let proto = GovernanceProto {
+        wait_for_quiet_threshold_seconds: 100,
+        economics: Some(NetworkEconomics::with_default_values()),
+        proposals: btreemap! {
+            1 => proposal_data(1, 1, motion()),
+            2 => proposal_data(2, 2, network_economics()),
+            3 => proposal_data(3, 1, network_economics()),
+            4 => proposal_data(4, 2, motion()),
+            5 => proposal_data(5, 1, motion()),
+        },
+        ..Default::default()
+    };
+    let driver = fake::FakeDriver::default().at(100);
+    let gov = Governance::new(
+        proto,
+        driver.get_fake_env(),
+        driver.get_fake_ledger(),
+        driver.get_fake_cmc(),
+    );
+    let list_proposal_ids = |request: ListProposalInfo| -> Vec<u64> {
+        gov.list_proposals(&principal(1), &request)
+            .proposal_info
+            .iter()
+            .map(|x| x.id.unwrap().id)
+            .collect()
+    };
+
+    assert_eq!(
+        list_proposal_ids(ListProposalInfo {
+            include_proposer: vec![NeuronId { id: 1 }],
+            ..Default::default()
+        }),
+        vec![5, 3, 1]
+    );
+    assert_eq!(
+        list_proposal_ids(ListProposalInfo {
+            include_action_type: vec![ProposalActionType::ManageNetworkEconomics as i32],
+            ..Default::default()
+        }),
+        vec![3, 2]
+    );
+    assert_eq!(
+        list_proposal_ids(ListProposalInfo {
+            min_proposal_timestamp_seconds: Some(20),
+            max_proposal_timestamp_seconds: Some(40),
+            ..Default::default()
+        }),
+        vec![4, 3, 2]
+    );
+    // The filters can be combined, and with pagination.
+    assert_eq!(
+        list_proposal_ids(ListProposalInfo {
+            include_proposer: vec![NeuronId { id: 1 }],
+            include_action_type: vec![ProposalActionType::Motion as i32],
+            min_proposal_timestamp_seconds: Some(10),
+            before_proposal: Some(ProposalId { id: 5 }),
+            ..Default::default()
+        }),
+        vec![1]
+    );
+}
+
 #[tokio::test]
 async fn test_max_number_of_proposals_with_ballots() {
     let mut fake_driver = fake::FakeDriver::default();
@@ -9482,6 +9716,9 @@ fn test_compute_cached_metrics() {
         community_fund_total_staked_e8s: 234_000_000,
         community_fund_total_maturity_e8s_equivalent: 450_988_012,
         total_locked_e8s: 34_124_000_100,
+        total_potential_voting_power: 61_484_262_173,
+        total_deciding_voting_power: 61_484_262_173,
+        neurons_with_reduced_voting_power_count: 0,
     };
 
     assert_eq!(expected_metrics, actual_metrics);
@@ -11417,6 +11654,7 @@ lazy_static! {
             sns_swap_pb::params::NeuronBasketConstructionParameters {
                 count: BASKET_COUNT,
                 dissolve_delay_interval_seconds: 7890000, // 3 months
+                vesting_period_seconds: None,
             },
         ),
         sale_delay_seconds: None,
@@ -12575,6 +12813,9 @@ async fn test_metrics() {
         community_fund_total_staked_e8s: 0,
         community_fund_total_maturity_e8s_equivalent: 0,
         total_locked_e8s: 600_000_000,
+        total_potential_voting_power: 637_500_125,
+        total_deciding_voting_power: 637_500_125,
+        neurons_with_reduced_voting_power_count: 0,
     };
     let actual_metrics = gov.compute_cached_metrics(now, Tokens::new(0, 0).unwrap());
     assert_eq!(
@@ -12623,6 +12864,9 @@ async fn test_metrics() {
         community_fund_total_staked_e8s: 0,
         community_fund_total_maturity_e8s_equivalent: 0,
         total_locked_e8s: 600_000_000,
+        total_potential_voting_power: 637_676_811,
+        total_deciding_voting_power: 637_676_811,
+        neurons_with_reduced_voting_power_count: 0,
     };
     let metrics = gov.get_metrics().expect("Error while querying metrics.");
     assert_eq!(
@@ -12645,3 +12889,424 @@ async fn test_metrics() {
         "Invalid locked verification"
     );
 }
+
+fn refresh_voting_power(
+    gov: &mut Governance,
+    caller: PrincipalId,
+    id: u64,
+) -> ManageNeuronResponse {
+    gov.manage_neuron(
+        &caller,
+        &ManageNeuron {
+            id: Some(NeuronId { id }),
+            neuron_id_or_subaccount: None,
+            command: Some(Command::RefreshVotingPower(RefreshVotingPower {})),
+        },
+    )
+    .now_or_never()
+    .unwrap()
+}
+
+#[test]
+fn test_deciding_voting_power_of_inactive_neurons_is_reduced() {
+    let neuron_stake_e8s = 10 * 100_000_000; // 10 ICP
+                                             // Neurons without an age bonus and with the maximum dissolve delay, so
+                                             // that their potential voting power is exactly twice their stake.
+    let neurons = (1..=2)
+        .map(|id| {
+            (
+                id,
+                Neuron {
+                    id: Some(NeuronId { id }),
+                    controller: Some(principal(id)),
+                    cached_neuron_stake_e8s: neuron_stake_e8s,
+                    aging_since_timestamp_seconds: u64::MAX,
+                    dissolve_state: Some(DissolveState::DissolveDelaySeconds(
+                        MAX_DISSOLVE_DELAY_SECONDS,
+                    )),
+                    ..Default::default()
+                },
+            )
+        })
+        .collect::<HashMap<u64, Neuron>>();
+    let proto = GovernanceProto {
+        economics: Some(NetworkEconomics::with_default_values()),
+        neurons,
+        ..Default::default()
+    };
+    let mut driver = fake::FakeDriver::default().at(DEFAULT_TEST_START_TIMESTAMP_SECONDS);
+    let mut gov = Governance::new(
+        proto,
+        driver.get_fake_env(),
+        driver.get_fake_ledger(),
+        driver.get_fake_cmc(),
+    );
+
+    // Half way through the period during which the voting power is reduced.
+    driver.advance_time_by(
+        VotingPowerEconomics::DEFAULT_START_REDUCING_VOTING_POWER_AFTER_SECONDS
+            + VotingPowerEconomics::DEFAULT_CLEAR_FOLLOWING_AFTER_SECONDS / 2,
+    );
+
+    // Only the controller or a hot key can refresh a neuron.
+    let response = refresh_voting_power(&mut gov, principal(2), 1);
+    match response.command.unwrap() {
+        CommandResponse::Error(error) => assert_eq!(error.error_type(), NotAuthorized),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+    let response = refresh_voting_power(&mut gov, principal(1), 1);
+    assert_eq!(
+        response,
+        ManageNeuronResponse {
+            command: Some(CommandResponse::RefreshVotingPower(
+                manage_neuron_response::RefreshVotingPowerResponse {}
+            )),
+        }
+    );
+    assert_eq!(
+        gov.get_neuron(&NeuronId { id: 1 })
+            .unwrap()
+            .voting_power_refreshed_timestamp_seconds,
+        Some(driver.now())
+    );
+
+    let neuron_info = gov.get_neuron_info(&NeuronId { id: 2 }).unwrap();
+    assert_eq!(neuron_info.voting_power, 2 * neuron_stake_e8s);
+    assert_eq!(neuron_info.deciding_voting_power, Some(neuron_stake_e8s));
+
+    // Ballots record the deciding voting power of the neurons.
+    let pid = gov
+        .make_proposal(
+            &NeuronId { id: 1 },
+            &principal(1),
+            &Proposal {
+                title: Some("A Reasonable Title".to_string()),
+                summary: "Summary".to_string(),
+                action: Some(proposal::Action::Motion(Motion {
+                    motion_text: "dummy text".to_string(),
+                })),
+                ..Default::default()
+            },
+        )
+        .now_or_never()
+        .unwrap()
+        .unwrap();
+    let ballots = &gov.get_proposal_data(pid).unwrap().ballots;
+    assert_eq!(ballots.get(&1).unwrap().voting_power, 2 * neuron_stake_e8s);
+    assert_eq!(ballots.get(&2).unwrap().voting_power, neuron_stake_e8s);
+
+    // Once the whole period has passed, the deciding voting power is zero.
+    driver.advance_time_by(VotingPowerEconomics::DEFAULT_CLEAR_FOLLOWING_AFTER_SECONDS / 2);
+    let neuron_info = gov.get_neuron_info(&NeuronId { id: 2 }).unwrap();
+    assert_eq!(neuron_info.voting_power, 2 * neuron_stake_e8s);
+    assert_eq!(neuron_info.deciding_voting_power, Some(0));
+}
+
+#[test]
+fn test_following_of_inactive_neurons_is_cleared() {
+    let followees = |followee_id| Followees {
+        followees: vec![NeuronId { id: followee_id }],
+    };
+    // Neurons 2 and 3 follow neuron 1.
+    let neurons = (1..=3)
+        .map(|id| {
+            let neuron_followees = if id == 1 {
+                HashMap::new()
+            } else {
+                hashmap! {
+                    Topic::Governance as i32 => followees(1),
+                    Topic::NeuronManagement as i32 => followees(1),
+                }
+            };
+            (
+                id,
+                Neuron {
+                    id: Some(NeuronId { id }),
+                    controller: Some(principal(id)),
+                    cached_neuron_stake_e8s: 10 * 100_000_000,
+                    dissolve_state: Some(DissolveState::DissolveDelaySeconds(
+                        MAX_DISSOLVE_DELAY_SECONDS,
+                    )),
+                    followees: neuron_followees,
+                    ..Default::default()
+                },
+            )
+        })
+        .collect::<HashMap<u64, Neuron>>();
+    let proto = GovernanceProto {
+        economics: Some(NetworkEconomics::with_default_values()),
+        neurons,
+        ..Default::default()
+    };
+    let mut driver = fake::FakeDriver::default().at(DEFAULT_TEST_START_TIMESTAMP_SECONDS);
+    let mut gov = Governance::new(
+        proto,
+        driver.get_fake_env(),
+        driver.get_fake_ledger(),
+        driver.get_fake_cmc(),
+    );
+    let inactive_after_seconds =
+        VotingPowerEconomics::DEFAULT_START_REDUCING_VOTING_POWER_AFTER_SECONDS
+            + VotingPowerEconomics::DEFAULT_CLEAR_FOLLOWING_AFTER_SECONDS;
+
+    // Neuron 3 is refreshed shortly before it would become inactive.
+    driver.advance_time_by(inactive_after_seconds - ONE_DAY_SECONDS);
+    refresh_voting_power(&mut gov, principal(3), 3);
+    driver.advance_time_by(ONE_DAY_SECONDS);
+    gov.run_periodic_tasks().now_or_never();
+
+    // Neuron 2 only keeps following on the NeuronManagement topic.
+    assert_eq!(
+        gov.get_neuron(&NeuronId { id: 2 }).unwrap().followees,
+        hashmap! { Topic::NeuronManagement as i32 => followees(1) }
+    );
+    assert_eq!(
+        gov.get_neuron(&NeuronId { id: 3 }).unwrap().followees,
+        hashmap! {
+            Topic::Governance as i32 => followees(1),
+            Topic::NeuronManagement as i32 => followees(1),
+        }
+    );
+    assert_eq!(
+        gov.topic_followee_index
+            .get(&Topic::Governance)
+            .unwrap()
+            .get(&1)
+            .unwrap(),
+        &btreeset! {3}
+    );
+    assert_eq!(
+        gov.topic_followee_index
+            .get(&Topic::NeuronManagement)
+            .unwrap()
+            .get(&1)
+            .unwrap(),
+        &btreeset! {2, 3}
+    );
+}
+
+fn set_visibility(
+    gov: &mut Governance,
+    caller: PrincipalId,
+    id: u64,
+    visibility: Visibility,
+) -> ManageNeuronResponse {
+    gov.manage_neuron(
+        &caller,
+        &ManageNeuron {
+            id: Some(NeuronId { id }),
+            neuron_id_or_subaccount: None,
+            command: Some(Command::Configure(Configure {
+                operation: Some(Operation::SetVisibility(SetVisibility {
+                    visibility: Some(visibility as i32),
+                })),
+            })),
+        },
+    )
+    .now_or_never()
+    .unwrap()
+}
+
+/// Tests that ManageNetworkEconomics proposals cannot set the voting power
+/// economics periods below one month, while leaving them unset (or zero)
+/// remains allowed.
+#[tokio::test]
+async fn test_manage_network_economics_voting_power_economics_minimums() {
+    let driver = fake::FakeDriver::default();
+    let mut gov = Governance::new(
+        fixture_for_following(),
+        driver.get_fake_env(),
+        driver.get_fake_ledger(),
+        driver.get_fake_cmc(),
+    );
+    let proposal = |voting_power_economics| Proposal {
+        title: Some("A Reasonable Title".to_string()),
+        summary: "test".to_string(),
+        action: Some(proposal::Action::ManageNetworkEconomics(NetworkEconomics {
+            voting_power_economics: Some(voting_power_economics),
+            ..Default::default()
+        })),
+        ..Default::default()
+    };
+
+    for voting_power_economics in [
+        VotingPowerEconomics {
+            start_reducing_voting_power_after_seconds: Some(1),
+            clear_following_after_seconds: None,
+        },
+        VotingPowerEconomics {
+            start_reducing_voting_power_after_seconds: Some(ONE_MONTH_SECONDS - 1),
+            clear_following_after_seconds: None,
+        },
+        VotingPowerEconomics {
+            start_reducing_voting_power_after_seconds: None,
+            clear_following_after_seconds: Some(1),
+        },
+        VotingPowerEconomics {
+            start_reducing_voting_power_after_seconds: Some(0),
+            clear_following_after_seconds: Some(ONE_MONTH_SECONDS - 1),
+        },
+    ] {
+        let error = gov
+            .make_proposal(
+                &NeuronId { id: 1 },
+                // Must match neuron 1's serialized_id.
+                &principal(1),
+                &proposal(voting_power_economics.clone()),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.error_type,
+            ErrorType::InvalidProposal as i32,
+            "{:?}: {:?}",
+            voting_power_economics,
+            error
+        );
+    }
+    assert!(gov.proto.proposals.is_empty());
+
+    gov.make_proposal(
+        &NeuronId { id: 1 },
+        &principal(1),
+        &proposal(VotingPowerEconomics {
+            start_reducing_voting_power_after_seconds: Some(ONE_MONTH_SECONDS),
+            clear_following_after_seconds: Some(ONE_MONTH_SECONDS),
+        }),
+    )
+    .await
+    .unwrap();
+}
+
+#[test]
+fn test_public_neurons_can_be_read_by_anyone() {
+    // Neurons 1 and 2 follow neuron 3, which is a known neuron.
+    let neurons = (1..=3)
+        .map(|id| {
+            let followees = if id == 3 {
+                HashMap::new()
+            } else {
+                hashmap! {
+                    Topic::Governance as i32 => Followees {
+                        followees: vec![NeuronId { id: 3 }],
+                    },
+                }
+            };
+            let known_neuron_data = if id == 3 {
+                Some(KnownNeuronData {
+                    name: "Known".to_string(),
+                    description: None,
+                })
+            } else {
+                None
+            };
+            (
+                id,
+                Neuron {
+                    id: Some(NeuronId { id }),
+                    account: vec![id as u8; 32],
+                    controller: Some(principal(id)),
+                    hot_keys: vec![principal(id + 10)],
+                    cached_neuron_stake_e8s: 10 * 100_000_000,
+                    dissolve_state: Some(DissolveState::DissolveDelaySeconds(
+                        MAX_DISSOLVE_DELAY_SECONDS,
+                    )),
+                    followees,
+                    known_neuron_data,
+                    ..Default::default()
+                },
+            )
+        })
+        .collect::<HashMap<u64, Neuron>>();
+    let proto = GovernanceProto {
+        economics: Some(NetworkEconomics::with_default_values()),
+        neurons,
+        ..Default::default()
+    };
+    let driver = fake::FakeDriver::default().at(DEFAULT_TEST_START_TIMESTAMP_SECONDS);
+    let mut gov = Governance::new(
+        proto,
+        driver.get_fake_env(),
+        driver.get_fake_ledger(),
+        driver.get_fake_cmc(),
+    );
+
+    // Neurons are private by default, so their following is not revealed.
+    let neuron_info = gov.get_neuron_info(&NeuronId { id: 1 }).unwrap();
+    assert_eq!(neuron_info.visibility, Some(Visibility::Private as i32));
+    assert_eq!(neuron_info.followees, HashMap::new());
+
+    // Only the controller can change the visibility.
+    let response = set_visibility(&mut gov, principal(2), 1, Visibility::Public);
+    match response.command.unwrap() {
+        CommandResponse::Error(error) => assert_eq!(error.error_type(), NotAuthorized),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+    let response = set_visibility(&mut gov, principal(1), 1, Visibility::Public);
+    assert_eq!(
+        response,
+        ManageNeuronResponse {
+            command: Some(CommandResponse::Configure(
+                manage_neuron_response::ConfigureResponse {}
+            )),
+        }
+    );
+    let neuron_info = gov.get_neuron_info(&NeuronId { id: 1 }).unwrap();
+    assert_eq!(neuron_info.visibility, Some(Visibility::Public as i32));
+    assert_eq!(
+        neuron_info.followees,
+        gov.get_neuron(&NeuronId { id: 1 }).unwrap().followees
+    );
+
+    // Known neurons are public and cannot be made private.
+    let neuron_info = gov.get_neuron_info(&NeuronId { id: 3 }).unwrap();
+    assert_eq!(neuron_info.visibility, Some(Visibility::Public as i32));
+    let response = set_visibility(&mut gov, principal(3), 3, Visibility::Private);
+    match response.command.unwrap() {
+        CommandResponse::Error(error) => assert_eq!(error.error_type(), PreconditionFailed),
+        other => panic!("Unexpected response: {:?}", other),
+    }
+
+    // The full information of public neurons can be listed by anyone, when
+    // requested.
+    let list_full_neuron_ids = |gov: &Governance, include_public: Option<bool>| {
+        gov.list_neurons_by_principal(
+            &ListNeurons {
+                neuron_ids: vec![1, 2, 3],
+                include_public_neurons_in_full_neurons: include_public,
+                ..Default::default()
+            },
+            &principal(4),
+        )
+        .full_neurons
+        .iter()
+        .map(|neuron| neuron.id.as_ref().unwrap().id)
+        .collect::<Vec<u64>>()
+    };
+    assert_eq!(list_full_neuron_ids(&gov, None), Vec::<u64>::new());
+    assert_eq!(list_full_neuron_ids(&gov, Some(true)), vec![1, 3]);
+
+    // Public neurons are redacted for those who cannot otherwise read them,
+    // but not for their controller.
+    let list_neuron_1 = |caller: PrincipalId| {
+        gov.list_neurons_by_principal(
+            &ListNeurons {
+                neuron_ids: vec![1],
+                include_public_neurons_in_full_neurons: Some(true),
+                ..Default::default()
+            },
+            &caller,
+        )
+        .full_neurons
+        .pop()
+        .unwrap()
+    };
+    let neuron = gov.get_neuron(&NeuronId { id: 1 }).unwrap().clone();
+    assert_eq!(list_neuron_1(principal(1)), neuron);
+    let redacted_neuron = list_neuron_1(principal(4));
+    assert_eq!(redacted_neuron.hot_keys, vec![]);
+    assert_eq!(redacted_neuron.account, Vec::<u8>::new());
+    assert_eq!(redacted_neuron.transfer, None);
+    assert_eq!(redacted_neuron.followees, neuron.followees);
+    assert_eq!(redacted_neuron.controller, neuron.controller);
+}
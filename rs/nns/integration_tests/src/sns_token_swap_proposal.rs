@@ -152,6 +152,7 @@ fn make_open_sns_token_swap_proposal(
         neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
             count: 3,
             dissolve_delay_interval_seconds: 7890000, // 3 months
+            vesting_period_seconds: None,
         }),
         sale_delay_seconds: None,
     };
@@ -272,6 +272,8 @@ fn allocate_neuron(id: u64) -> Neuron {
         joined_community_fund_timestamp_seconds: None,
         known_neuron_data: None,
         spawn_at_timestamp_seconds: None,
+        voting_power_refreshed_timestamp_seconds: None,
+        visibility: None,
     }
 }
 
@@ -661,6 +661,7 @@ pub fn list_neurons(state_machine: &mut StateMachine, sender: PrincipalId) -> Li
             Encode!(&ListNeurons {
                 neuron_ids: vec![],
                 include_neurons_readable_by_caller: true,
+                ..Default::default()
             })
             .unwrap(),
         )
@@ -14,7 +14,9 @@ use ic_config::{
     Config,
 };
 use ic_crypto::CryptoComponentForNonReplicaProcess;
-use ic_interfaces::crypto::IDkgKeyRotationResult;
+use ic_interfaces::crypto::{
+    IDkgKeyRotationResult, NodeSigningKeys, NodeSigningKeysRotationResult,
+};
 use ic_interfaces_registry::RegistryClient;
 use ic_logger::{info, warn, ReplicaLogger};
 use ic_nns_constants::REGISTRY_CANISTER_ID;
@@ -29,7 +31,9 @@ use ic_sys::utility_command::UtilityCommand;
 use ic_types::{crypto::KeyPurpose, messages::MessageId, NodeId, RegistryVersion, SubnetId};
 use prost::Message;
 use rand::prelude::*;
-use registry_canister::mutations::do_update_node_directly::UpdateNodeDirectlyPayload;
+use registry_canister::mutations::do_update_node_directly::{
+    UpdateNodeDirectlyPayload, NODE_SIGNING_KEYS_TRANSITION_PERIOD,
+};
 use registry_canister::mutations::node_management::do_add_node::AddNodePayload;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
@@ -196,6 +200,9 @@ impl NodeRegistration {
     /// to generate or register keys are retried.
     pub async fn check_all_keys_registered_otherwise_register(&self, subnet_id: SubnetId) {
         let registry_version = self.registry_client.get_latest_version();
+        self.rotate_node_signing_keys_if_due(registry_version, subnet_id)
+            .await;
+
         // If there is no ECDSA config or no key_ids, ECDSA is disabled.
         // Delta is the key rotation period of a single node, if it is None, key rotation is disabled.
         let delta = match self.get_key_rotation_period(registry_version, subnet_id) {
@@ -250,6 +257,72 @@ impl NodeRegistration {
         }
     }
 
+    /// Rotates the committee signing key and the TLS certificate if the rotation period
+    /// configured in `registration.node_signing_keys_rotation_period_secs` has passed, and
+    /// registers the rotated keys, or previously rotated keys that were not yet registered.
+    /// The keys of the previous rotation are retired first.
+    ///
+    /// Like for the iDKG dealing encryption key, the nodes of a subnet take turns, so that
+    /// the subnet as a whole rotates at most once every Ɣ (see `is_time_to_rotate_in_subnet`).
+    async fn rotate_node_signing_keys_if_due(
+        &self,
+        registry_version: RegistryVersion,
+        subnet_id: SubnetId,
+    ) {
+        let delta = match self
+            .node_config
+            .registration
+            .node_signing_keys_rotation_period_secs
+        {
+            Some(secs) => Duration::from_secs(secs),
+            None => return,
+        };
+        // The previous keys must be retired before the node can rotate again.
+        if self
+            .retire_previous_node_signing_keys_if_due(registry_version)
+            .await
+        {
+            return;
+        }
+        if !self.is_time_to_rotate_key(
+            registry_version,
+            subnet_id,
+            KeyPurpose::CommitteeSigning,
+            delta,
+        ) {
+            return;
+        }
+
+        let key_handler = self.key_handler.clone();
+        match tokio::task::spawn_blocking(move || {
+            key_handler.rotate_node_signing_keys(registry_version, delta)
+        })
+        .await
+        .unwrap()
+        {
+            Ok(NodeSigningKeysRotationResult::KeysNeedRegistration { keys, rotated }) => {
+                if rotated {
+                    info!(
+                        self.log,
+                        "Rotated committee signing key and TLS certificate."
+                    );
+                }
+                match self
+                    .try_to_register_node_signing_keys(registry_version, keys)
+                    .await
+                {
+                    Ok(()) => info!(
+                        self.log,
+                        "Registration attempt of node signing keys finished successfully."
+                    ),
+                    Err(e) => warn!(self.log, "Failed to register node signing keys: {e:?}"),
+                }
+            }
+            Ok(NodeSigningKeysRotationResult::LatestRotationTooRecent) => {}
+            Err(e) => warn!(self.log, "Node signing keys rotation error: {e:?}"),
+        }
+    }
+
     async fn register_key(&self, registry_version: RegistryVersion, idkg_pk: PublicKey) {
         self.metrics
             .observe_key_rotation_status(KeyRotationStatus::Registering);
@@ -287,14 +360,25 @@ impl NodeRegistration {
         registry_version: RegistryVersion,
         subnet_id: SubnetId,
         delta: Duration,
+    ) -> bool {
+        self.is_time_to_rotate_key(
+            registry_version,
+            subnet_id,
+            KeyPurpose::IDkgMEGaEncryption,
+            delta,
+        )
+    }
+
+    fn is_time_to_rotate_key(
+        &self,
+        registry_version: RegistryVersion,
+        subnet_id: SubnetId,
+        key_purpose: KeyPurpose,
+        delta: Duration,
     ) -> bool {
         let own_key_timestamp = self
             .registry_client
-            .get_crypto_key_for_node(
-                self.node_id,
-                KeyPurpose::IDkgMEGaEncryption,
-                registry_version,
-            )
+            .get_crypto_key_for_node(self.node_id, key_purpose, registry_version)
             .unwrap_or_default()
             .and_then(|pk| pk.timestamp);
 
@@ -320,7 +404,7 @@ impl NodeRegistration {
             .into_iter()
             .filter_map(|nid| {
                 self.registry_client
-                    .get_crypto_key_for_node(nid, KeyPurpose::IDkgMEGaEncryption, registry_version)
+                    .get_crypto_key_for_node(nid, key_purpose, registry_version)
                     .unwrap_or_default()
             })
             .filter_map(|pk| pk.timestamp)
@@ -337,6 +421,104 @@ impl NodeRegistration {
     ) -> Result<(), String> {
         info!(self.log, "Trying to register rotated idkg key...");
 
+        self.update_node_directly(
+            registry_version,
+            UpdateNodeDirectlyPayload {
+                idkg_dealing_encryption_pk: Some(protobuf_to_vec(idkg_pk)),
+                committee_signing_pk: None,
+                transport_tls_cert: None,
+                retire_previous_node_signing_keys: None,
+            },
+        )
+        .await
+    }
+
+    async fn try_to_register_node_signing_keys(
+        &self,
+        registry_version: RegistryVersion,
+        keys: NodeSigningKeys,
+    ) -> Result<(), String> {
+        info!(
+            self.log,
+            "Trying to register rotated committee signing key and TLS certificate..."
+        );
+
+        self.update_node_directly(
+            registry_version,
+            UpdateNodeDirectlyPayload {
+                idkg_dealing_encryption_pk: None,
+                committee_signing_pk: Some(protobuf_to_vec(keys.committee_signing_public_key)),
+                transport_tls_cert: Some(protobuf_to_vec(keys.tls_certificate)),
+                retire_previous_node_signing_keys: None,
+            },
+        )
+        .await
+    }
+
+    /// Retires the committee signing key and TLS certificate that this node used before its
+    /// latest rotation, once `NODE_SIGNING_KEYS_TRANSITION_PERIOD` has passed since the rotation.
+    ///
+    /// Returns whether the node still has previous keys in the registry, in which case it must
+    /// not rotate its keys again.
+    async fn retire_previous_node_signing_keys_if_due(
+        &self,
+        registry_version: RegistryVersion,
+    ) -> bool {
+        let has_previous_keys = self
+            .registry_client
+            .get_previous_tls_certificate(self.node_id, registry_version)
+            .unwrap_or_default()
+            .is_some();
+        if !has_previous_keys {
+            return false;
+        }
+        let rotation_time = self
+            .registry_client
+            .get_crypto_key_for_node(self.node_id, KeyPurpose::CommitteeSigning, registry_version)
+            .unwrap_or_default()
+            .and_then(|pk| pk.timestamp)
+            .map(|ts| SystemTime::UNIX_EPOCH + Duration::from_millis(ts));
+        let transition_over = rotation_time
+            .map(|time| time + NODE_SIGNING_KEYS_TRANSITION_PERIOD <= SystemTime::now())
+            .unwrap_or(true);
+        if !transition_over {
+            return true;
+        }
+
+        info!(
+            self.log,
+            "Trying to retire previous committee signing key and TLS certificate..."
+        );
+        match self
+            .update_node_directly(
+                registry_version,
+                UpdateNodeDirectlyPayload {
+                    idkg_dealing_encryption_pk: None,
+                    committee_signing_pk: None,
+                    transport_tls_cert: None,
+                    retire_previous_node_signing_keys: Some(true),
+                },
+            )
+            .await
+        {
+            Ok(()) => info!(
+                self.log,
+                "Retirement attempt of previous node signing keys finished successfully."
+            ),
+            Err(e) => warn!(
+                self.log,
+                "Failed to retire previous node signing keys: {e:?}"
+            ),
+        }
+        true
+    }
+
+    /// Sends `update_node_payload` to the registry canister, signed by this node.
+    async fn update_node_directly(
+        &self,
+        registry_version: RegistryVersion,
+        update_node_payload: UpdateNodeDirectlyPayload,
+    ) -> Result<(), String> {
         let node_id = self.node_id;
         let nns_url = match self
             .get_random_nns_url()
@@ -384,9 +566,6 @@ impl NodeRegistration {
         };
 
         let agent = Agent::new(nns_url.clone(), sender);
-        let update_node_payload = UpdateNodeDirectlyPayload {
-            idkg_dealing_encryption_pk: Some(protobuf_to_vec(idkg_pk)),
-        };
 
         agent
             .execute_update(
@@ -714,8 +893,10 @@ mod tests {
         use ic_interfaces::crypto::ThresholdSigVerifierByPublicKey;
         use ic_interfaces::crypto::{BasicSigner, CheckKeysWithRegistryError};
         use ic_interfaces::crypto::{CurrentNodePublicKeysError, KeyRotationOutcome};
+        use ic_interfaces::crypto::{NodeSigningKeys, NodeSigningKeysRotationError};
         use ic_logger::replica_logger::no_op_logger;
         use ic_metrics::MetricsRegistry;
+        use ic_protobuf::registry::crypto::v1::X509PublicKeyCert;
         use ic_protobuf::registry::subnet::v1::SubnetListRecord;
         use ic_registry_client_fake::FakeRegistryClient;
         use ic_registry_keys::{
@@ -758,6 +939,12 @@ mod tests {
                     &self,
                     registry_version: RegistryVersion,
                 ) -> Result<IDkgKeyRotationResult, IDkgDealingEncryptionKeyRotationError>;
+
+                fn rotate_node_signing_keys(
+                    &self,
+                    registry_version: RegistryVersion,
+                    key_rotation_period: Duration,
+                ) -> Result<NodeSigningKeysRotationResult, NodeSigningKeysRotationError>;
             }
 
             pub trait BasicSigner<MessageId> {
@@ -813,6 +1000,7 @@ mod tests {
                 SetupBuilder {
                     check_keys_with_registry_result: None,
                     rotate_idkg_dealing_encryption_keys_result: None,
+                    rotate_node_signing_keys_result: None,
                     logger: None,
                     without_ecdsa_subnet_config: false,
                     idkg_dealing_encryption_public_key_in_registry: None,
@@ -824,6 +1012,8 @@ mod tests {
             check_keys_with_registry_result: Option<Result<(), CheckKeysWithRegistryError>>,
             rotate_idkg_dealing_encryption_keys_result:
                 Option<Result<IDkgKeyRotationResult, IDkgDealingEncryptionKeyRotationError>>,
+            rotate_node_signing_keys_result:
+                Option<Result<NodeSigningKeysRotationResult, NodeSigningKeysRotationError>>,
             logger: Option<ReplicaLogger>,
             without_ecdsa_subnet_config: bool,
             idkg_dealing_encryption_public_key_in_registry: Option<PublicKey>,
@@ -850,6 +1040,18 @@ mod tests {
                 self
             }
 
+            // Also enables the rotation of node signing keys in the orchestrator config.
+            fn with_rotate_node_signing_keys_result(
+                mut self,
+                rotate_node_signing_keys_result: Result<
+                    NodeSigningKeysRotationResult,
+                    NodeSigningKeysRotationError,
+                >,
+            ) -> Self {
+                self.rotate_node_signing_keys_result = Some(rotate_node_signing_keys_result);
+                self
+            }
+
             fn with_logger(mut self, in_memory_logger: &InMemoryReplicaLogger) -> Self {
                 self.logger = Some(ReplicaLogger::from(in_memory_logger));
                 self
@@ -934,9 +1136,23 @@ mod tests {
                         .times(1)
                         .return_const(rotate_idkg_dealing_encryption_keys_result);
                 }
+                let node_signing_keys_rotation_enabled =
+                    self.rotate_node_signing_keys_result.is_some();
+                if let Some(rotate_node_signing_keys_result) = self.rotate_node_signing_keys_result
+                {
+                    key_handler
+                        .expect_rotate_node_signing_keys()
+                        .times(1)
+                        .return_const(rotate_node_signing_keys_result);
+                }
 
                 let local_store = Arc::new(LocalStoreImpl::new(temp_dir.as_ref()));
-                let node_config = Config::new(temp_dir.into_path());
+                let mut node_config = Config::new(temp_dir.into_path());
+                if node_signing_keys_rotation_enabled {
+                    node_config
+                        .registration
+                        .node_signing_keys_rotation_period_secs = Some(60 * 60 * 24 * 14);
+                }
 
                 let node_registration = NodeRegistration::new(
                     self.logger.unwrap_or_else(|| no_op_logger()),
@@ -1174,6 +1390,58 @@ mod tests {
             );
         }
 
+        #[tokio::test]
+        async fn should_try_to_register_node_signing_keys_if_keys_are_rotated() {
+            let in_memory_logger = InMemoryReplicaLogger::new();
+            let setup = Setup::builder()
+                .without_ecdsa_subnet_config()
+                .with_rotate_node_signing_keys_result(Ok(
+                    NodeSigningKeysRotationResult::KeysNeedRegistration {
+                        keys: NodeSigningKeys {
+                            committee_signing_public_key: PublicKey::default(),
+                            tls_certificate: X509PublicKeyCert::default(),
+                        },
+                        rotated: true,
+                    },
+                ))
+                .with_logger(&in_memory_logger)
+                .build();
+
+            setup
+                .node_registration
+                .check_all_keys_registered_otherwise_register(setup.subnet_id)
+                .await;
+
+            let logs = in_memory_logger.drain_logs();
+            LogEntriesAssert::assert_that(logs).has_only_one_message_containing(
+                &Level::Info,
+                "Trying to register rotated committee signing key and TLS certificate...",
+            );
+        }
+
+        #[tokio::test]
+        async fn should_log_warning_if_node_signing_keys_rotation_fails() {
+            let in_memory_logger = InMemoryReplicaLogger::new();
+            let setup = Setup::builder()
+                .without_ecdsa_subnet_config()
+                .with_rotate_node_signing_keys_result(Err(
+                    NodeSigningKeysRotationError::RegistryKeyBadOrMissing,
+                ))
+                .with_logger(&in_memory_logger)
+                .build();
+
+            setup
+                .node_registration
+                .check_all_keys_registered_otherwise_register(setup.subnet_id)
+                .await;
+
+            let logs = in_memory_logger.drain_logs();
+            LogEntriesAssert::assert_that(logs).has_only_one_message_containing(
+                &Level::Warning,
+                "Node signing keys rotation error: RegistryKeyBadOrMissing",
+            );
+        }
+
         #[tokio::test]
         async fn should_log_error_if_key_rotation_returns_key_generation_error() {
             let in_memory_logger = InMemoryReplicaLogger::new();
@@ -230,6 +230,7 @@ mod tests {
                 attribute: ArtifactAttribute::FileTreeSync("".to_string()),
                 size: 0,
                 integrity_hash: CryptoHash(Vec::from(msg.id.to_be_bytes())),
+                chunk_tree_root: None,
             }
         }
     }
@@ -32,6 +32,7 @@ use ic_types::{
     artifact::ArtifactId,
     chunkable::Chunkable,
     crypto::CryptoHash,
+    multi_chunked::MultiChunked,
     p2p::{GossipAdvert, MAX_ARTIFACT_TIMEOUT},
     NodeId,
 };
@@ -173,7 +174,14 @@ impl ArtifactDownloadList for ArtifactDownloadListImpl {
         if !self.artifacts.contains_key(&advert.integrity_hash) {
             let artifact_id = &advert.artifact_id;
 
-            if let Some(chunk_tracker) = artifact_manager.get_chunk_tracker(&advert.artifact_id) {
+            // Large artifacts advertised with a chunk tree are tracked by P2P
+            // directly, so that their chunks can be verified individually and
+            // fetched from all peers that advertised them.
+            let chunk_tracker = match MultiChunked::from_advert(advert) {
+                Some(tracker) => Some(Box::new(tracker) as Box<dyn Chunkable + Send + Sync>),
+                None => artifact_manager.get_chunk_tracker(&advert.artifact_id),
+            };
+            if let Some(chunk_tracker) = chunk_tracker {
                 let requested_instant = Instant::now();
                 // Calculate the worst-case time estimate for the artifact download, which
                 // assumes that all chunks for the artifact will time out for
@@ -281,9 +289,11 @@ mod tests {
     use crate::download_management::tests::TestArtifactManager;
     use ic_test_utilities::p2p::p2p_test_setup_logger;
     use ic_test_utilities::types::ids::node_test_id;
-    use ic_types::artifact::ArtifactAttribute;
+    use ic_types::artifact::{ArtifactAttribute, DkgMessageAttribute};
     use ic_types::crypto::CryptoHash;
+    use ic_types::multi_chunked::ARTIFACT_CHUNK_SIZE;
     use ic_types::p2p;
+    use ic_types::Height;
     use std::convert::TryFrom;
 
     /// The function schedules the download of the given number of adverts.
@@ -301,6 +311,7 @@ mod tests {
                 attribute: ArtifactAttribute::FileTreeSync(advert_id.to_string()),
                 size: 0,
                 integrity_hash: CryptoHash(vec![u8::try_from(advert_id).unwrap()]),
+                chunk_tree_root: None,
             };
             let tracker = artifact_download_list
                 .schedule_download(
@@ -379,4 +390,49 @@ mod tests {
         }
         assert_eq!(artifact_download_list.artifacts.len(), 0);
     }
+
+    /// The function tests that large artifacts advertised with a chunk tree
+    /// root are tracked in pieces rather than by the client's tracker.
+    #[test]
+    fn download_list_multi_chunked_test() {
+        let artifact_manager = TestArtifactManager { num_chunks: 1 };
+        let logger = p2p_test_setup_logger();
+        let log: ReplicaLogger = logger.root.clone().into();
+        let mut artifact_download_list = ArtifactDownloadListImpl::new(log);
+        let gossip_config = p2p::build_default_gossip_config();
+
+        let mut gossip_advert = GossipAdvert {
+            artifact_id: ArtifactId::DkgMessage(CryptoHash(vec![1]).into()),
+            attribute: ArtifactAttribute::DkgMessage(DkgMessageAttribute {
+                interval_start_height: Height::from(0),
+            }),
+            size: 3 * ARTIFACT_CHUNK_SIZE,
+            integrity_hash: CryptoHash(vec![1]),
+            chunk_tree_root: Some(CryptoHash(vec![0; 32])),
+        };
+        let tracker = artifact_download_list
+            .schedule_download(
+                node_test_id(0),
+                &gossip_advert,
+                &gossip_config,
+                1,
+                &artifact_manager,
+            )
+            .unwrap();
+        assert_eq!(tracker.chunkable.chunks_to_download().count(), 3);
+
+        // Without a chunk tree root the client's tracker is used.
+        gossip_advert.integrity_hash = CryptoHash(vec![2]);
+        gossip_advert.chunk_tree_root = None;
+        let tracker = artifact_download_list
+            .schedule_download(
+                node_test_id(0),
+                &gossip_advert,
+                &gossip_config,
+                1,
+                &artifact_manager,
+            )
+            .unwrap();
+        assert_eq!(tracker.chunkable.chunks_to_download().count(), 1);
+    }
 }
@@ -83,7 +83,7 @@ use ic_logger::{info, trace, warn};
 use ic_protobuf::{p2p::v1 as pb, proxy::ProtoProxy};
 use ic_types::{
     artifact::{Artifact, ArtifactFilter, ArtifactId, ArtifactTag},
-    chunkable::{ArtifactErrorCode, ChunkId},
+    chunkable::{ArtifactErrorCode, ChunkId, CHUNKID_UNIT_CHUNK},
     crypto::CryptoHash,
     p2p::GossipAdvert,
     NodeId, RegistryVersion,
@@ -260,6 +260,7 @@ impl GossipImpl {
         let artifact_tracker = artifact_tracker.unwrap();
 
         // Feed the chunk to the tracker.
+        let mut chunk_tree_rejected = false;
         let completed_artifact = match artifact_tracker
             .chunkable
             .add_chunk(gossip_chunk.artifact_chunk.unwrap())
@@ -276,10 +277,35 @@ impl GossipImpl {
                     &peer_id
                 );
                 self.metrics.chunks_verification_failed.inc();
+                // A multi-chunked artifact falls back to a single chunk once its
+                // verified pieces do not make up the artifact, i.e., once the
+                // chunk tree it is downloaded with turns out to be bogus.
+                chunk_tree_rejected = gossip_chunk.request.chunk_id
+                    != ChunkId::from(CHUNKID_UNIT_CHUNK)
+                    && artifact_tracker.chunkable.chunks_to_download().next()
+                        == Some(ChunkId::from(CHUNKID_UNIT_CHUNK));
                 None
             }
         };
 
+        // Restart the download with the chunk tree advertised by the next most
+        // peers, or as a single chunk if there is none.
+        if chunk_tree_rejected {
+            let rejected = self
+                .prioritizer
+                .get_advert_tracker(
+                    &gossip_chunk.request.artifact_id,
+                    &gossip_chunk.request.integrity_hash,
+                )
+                .map_or(false, |advert_tracker| {
+                    advert_tracker.write().unwrap().reject_chunk_tree()
+                });
+            if rejected {
+                artifacts_under_construction.remove_tracker(&gossip_chunk.request.integrity_hash);
+            }
+            return;
+        }
+
         // Return if the artifact is complete.
         if completed_artifact.is_none() {
             return;
@@ -727,6 +753,11 @@ impl GossipImpl {
             None?
         }
 
+        // Skip if the peer cannot serve this piece of a multi-chunked artifact.
+        if !advert_tracker.can_serve(chunk_id, &peer_id) {
+            None?
+        }
+
         let chunk_request = GossipChunkRequest {
             artifact_id: advert_tracker.advert().artifact_id.clone(),
             integrity_hash: advert_tracker.advert().integrity_hash.clone(),
@@ -805,6 +836,15 @@ impl GossipImpl {
             let mut advert_tracker = advert_tracker.write().unwrap();
             let advert_tracker = advert_tracker.deref_mut();
 
+            // Select the chunk tree before the download of the artifact begins,
+            // so that all its pieces are verified against the same chunk tree.
+            if artifacts_under_construction
+                .get_tracker(&advert_tracker.advert().integrity_hash)
+                .is_none()
+            {
+                advert_tracker.select_chunk_tree();
+            }
+
             // Try to begin a download for the artifact and collect its chunk requests.
             if let Some(artifact_tracker) = artifacts_under_construction.schedule_download(
                 peer_id,
@@ -979,7 +1019,6 @@ pub mod tests {
     use ic_interfaces::artifact_manager::{ArtifactManager, OnArtifactError};
     use ic_interfaces::consensus_pool::ConsensusPoolCache;
     use ic_interfaces_registry::RegistryClient;
-    use ic_interfaces_transport::TransportChannelId;
     use ic_logger::{LoggerImpl, ReplicaLogger};
     use ic_metrics::MetricsRegistry;
     use ic_registry_client_fake::FakeRegistryClient;
@@ -1160,7 +1199,7 @@ pub mod tests {
         // Set up the prioritizer.
         let metrics_registry = MetricsRegistry::new();
 
-        let transport_channels = vec![TransportChannelId::from(0)];
+        let transport_channels = crate::utils::transport_channels();
 
         // Create fake peers.
         let artifact_manager = Arc::new(artifact_manager);
@@ -1215,6 +1254,7 @@ pub mod tests {
                 attribute: ArtifactAttribute::FileTreeSync(advert_id.to_string()),
                 size: 0,
                 integrity_hash: CryptoHash(Vec::from(advert_id.to_be_bytes())),
+                chunk_tree_root: None,
             };
             gossip.on_advert(gossip_advert, node_id)
         }
@@ -1633,6 +1673,7 @@ pub mod tests {
                 attribute: ArtifactAttribute::DkgMessage(attribute),
                 size: 0,
                 integrity_hash: ic_types::crypto::crypto_hash(&msg).get(),
+                chunk_tree_root: None,
             };
             result.push(gossip_advert);
         }
@@ -17,7 +17,7 @@ use crate::metrics::DownloadPrioritizerMetrics;
 use ic_interfaces::artifact_manager::ArtifactManager;
 use ic_types::{
     artifact::{ArtifactId, ArtifactPriorityFn, ArtifactTag, Priority},
-    chunkable::ChunkId,
+    chunkable::{ChunkId, CHUNKID_UNIT_CHUNK},
     crypto::CryptoHash,
     p2p::GossipAdvert,
     NodeId,
@@ -150,12 +150,22 @@ struct DownloadAttempt {
 /// Per chunk download attempts tracker data structure
 type DownloadAttemptMap = BTreeMap<ChunkId, DownloadAttempt>;
 
+/// Chunk tree root and size an artifact was advertised with. Both come from
+/// the advertising peer, so peers are only trusted to serve pieces of the
+/// chunk tree they advertised themselves.
+type ChunkTree = (CryptoHash, usize);
+
 /// A struct that holds information for tracking the state of an advert
 pub(crate) struct AdvertTracker {
     /// Advert for this tracker
     advert: GossipAdvert,
     /// Peers that have advertised this advert
     peers: HashSet<NodeId>,
+    /// Peers that can serve the artifact in pieces, per chunk tree root and
+    /// size they advertised it with
+    chunk_peers: BTreeMap<ChunkTree, HashSet<NodeId>>,
+    /// Chunk trees whose pieces did not make up the artifact
+    rejected_chunk_trees: BTreeSet<ChunkTree>,
     /// Per chunk download attempt history map
     download_attempt_map: DownloadAttemptMap,
     /// Priority as computed by the last priority function
@@ -243,7 +253,12 @@ impl DownloadAttemptTracker for AdvertTracker {
             .download_attempt_map
             .entry(chunk_id)
             .or_insert_with(Default::default);
-        !attempt.in_progress && attempt.peers.len() == self.peers.len()
+        let advertisers = if Self::is_piece(&self.advert, chunk_id) {
+            self.chunk_tree_peers().map_or(0, |peers| peers.len())
+        } else {
+            self.peers.len()
+        };
+        !attempt.in_progress && attempt.peers.len() == advertisers
     }
 
     fn peer_attempted(&self, chunk_id: ChunkId, node_id: &NodeId) -> bool {
@@ -282,6 +297,73 @@ impl AdvertTracker {
         &self.peers
     }
 
+    /// Returns true if the given peer can serve the given chunk. Only peers
+    /// that advertised the artifact with the selected chunk tree can serve its
+    /// pieces; all other peers only serve the whole artifact.
+    pub fn can_serve(&self, chunk_id: ChunkId, peer_id: &NodeId) -> bool {
+        !Self::is_piece(&self.advert, chunk_id)
+            || self
+                .chunk_tree_peers()
+                .map_or(false, |peers| peers.contains(peer_id))
+    }
+
+    /// Selects the chunk tree the artifact is downloaded with, i.e., the one
+    /// advertised by the most peers among those that were not rejected. The
+    /// artifact is downloaded as a single chunk if there is none.
+    ///
+    /// Must only be called while the artifact is not being downloaded, so
+    /// that all its pieces are verified against the same chunk tree.
+    pub fn select_chunk_tree(&mut self) {
+        let selected = self
+            .chunk_peers
+            .iter()
+            .filter(|(chunk_tree, _)| !self.rejected_chunk_trees.contains(chunk_tree))
+            .max_by_key(|(_, peers)| peers.len())
+            .map(|(chunk_tree, _)| chunk_tree.clone());
+        match selected {
+            Some((root, size)) => {
+                self.advert.chunk_tree_root = Some(root);
+                self.advert.size = size;
+            }
+            None => self.advert.chunk_tree_root = None,
+        }
+    }
+
+    /// Rejects the selected chunk tree after its pieces did not make up the
+    /// artifact, so that the next download falls back to the chunk tree
+    /// advertised by the next most peers, or to a single chunk. Returns false
+    /// if the artifact is not downloaded in pieces.
+    pub fn reject_chunk_tree(&mut self) -> bool {
+        let root = match self.advert.chunk_tree_root.take() {
+            Some(root) => root,
+            None => return false,
+        };
+        self.rejected_chunk_trees.insert((root, self.advert.size));
+        let unit_chunk_id = ChunkId::from(CHUNKID_UNIT_CHUNK);
+        self.download_attempt_map
+            .retain(|chunk_id, _| *chunk_id == unit_chunk_id);
+        true
+    }
+
+    // Returns the peers that advertised the selected chunk tree.
+    fn chunk_tree_peers(&self) -> Option<&HashSet<NodeId>> {
+        let root = self.advert.chunk_tree_root.clone()?;
+        self.chunk_peers.get(&(root, self.advert.size))
+    }
+
+    // Removes the peer from the advertisers of its chunk tree.
+    fn remove_chunk_peer(&mut self, peer_id: &NodeId) {
+        self.chunk_peers.retain(|_, peers| {
+            peers.remove(peer_id);
+            !peers.is_empty()
+        });
+    }
+
+    // Returns true if `chunk_id` denotes a piece of a multi-chunked artifact.
+    fn is_piece(advert: &GossipAdvert, chunk_id: ChunkId) -> bool {
+        advert.chunk_tree_root.is_some() && chunk_id != ChunkId::from(CHUNKID_UNIT_CHUNK)
+    }
+
     /// Returns the DownloadAttemptTracker for a chunk
     fn get_download_attempt_tracker(&mut self, chunk_id: ChunkId) -> &mut DownloadAttempt {
         self.download_attempt_map
@@ -455,6 +537,10 @@ impl DownloadPrioritizer for DownloadPrioritizerImpl {
         }
         let integrity_hash = advert.integrity_hash.clone();
         let integrity_hash_peer_index = advert.integrity_hash.clone();
+        let chunk_tree = advert
+            .chunk_tree_root
+            .clone()
+            .map(|root| (root, advert.size));
 
         // Insert into the client advert map
         let advert_tracker = client.advert_map.entry(integrity_hash).or_insert_with(|| {
@@ -462,6 +548,8 @@ impl DownloadPrioritizer for DownloadPrioritizerImpl {
                 advert,
                 priority,
                 peers: Default::default(),
+                chunk_peers: Default::default(),
+                rejected_chunk_trees: Default::default(),
                 download_attempt_map: Default::default(),
             }))
         });
@@ -488,6 +576,13 @@ impl DownloadPrioritizer for DownloadPrioritizerImpl {
         // Track the peer in the advert
         let mut advert_tracker = advert_tracker.write().unwrap();
         advert_tracker.peers.insert(peer_id);
+        if let Some(chunk_tree) = chunk_tree {
+            advert_tracker
+                .chunk_peers
+                .entry(chunk_tree)
+                .or_insert_with(Default::default)
+                .insert(peer_id);
+        }
         Ok(())
     }
 
@@ -643,6 +738,7 @@ impl DownloadPrioritizer for DownloadPrioritizerImpl {
                     .dec();
             }
             advert_tracker.peers.remove(peer_id);
+            advert_tracker.remove_chunk_peer(peer_id);
             advert_tracker.peers.len()
         };
 
@@ -688,6 +784,7 @@ impl DownloadPrioritizer for DownloadPrioritizerImpl {
                         ])
                         .dec();
                     advert_tracker.peers.remove(peer_id);
+                    advert_tracker.remove_chunk_peer(peer_id);
                     if advert_tracker.peers.is_empty() {
                         client_advert_map
                             .get_mut(&(&advert_tracker.advert.artifact_id).into())
@@ -885,6 +982,7 @@ pub(crate) mod test {
             size: 0,
             // Integrity hash is not checked in the tests here
             integrity_hash: CryptoHash(vec![id as u8]),
+            chunk_tree_root: None,
         }
     }
 
@@ -1314,6 +1412,7 @@ pub(crate) mod test {
                         attribute: ArtifactAttribute::FileTreeSync(advert_id.to_string()),
                         size: 0,
                         integrity_hash: CryptoHash(vec![]),
+                        chunk_tree_root: None,
                     },
                     node_test_id(peer_id),
                 )
@@ -1420,4 +1519,78 @@ pub(crate) mod test {
                 assert!(tracker.is_in_progress(chunk_id0));
             });
     }
+
+    /// Tests that pieces of a multi-chunked artifact are only requested from
+    /// peers that advertised the chunk tree advertised by the most peers, even
+    /// if another chunk tree was advertised first, and that rejected chunk
+    /// trees fall back to the next one and finally to a single chunk. The whole
+    /// artifact can be requested from any advertiser.
+    #[test]
+    fn multi_chunk_pieces_only_from_capable_peers() {
+        let artifact_manager = ArtifactManagerImpl::default();
+        let download_prioritizer: DownloadPrioritizerImpl = DownloadPrioritizerImpl::new(
+            &artifact_manager,
+            DownloadPrioritizerMetrics::new(&MetricsRegistry::new()),
+        );
+        let root = CryptoHash(vec![1; 32]);
+        let bogus_root = CryptoHash(vec![2; 32]);
+        for (peer, chunk_tree_root) in [
+            (0, Some(bogus_root.clone())),
+            (1, None),
+            (2, Some(root.clone())),
+            (3, Some(root.clone())),
+        ] {
+            let mut gossip_advert = make_gossip_advert(0);
+            gossip_advert.chunk_tree_root = chunk_tree_root;
+            download_prioritizer
+                .add_advert(gossip_advert, node_test_id(peer))
+                .unwrap();
+        }
+
+        let unit_chunk_id = ChunkId::from(CHUNKID_UNIT_CHUNK);
+        let piece_id = ChunkId::from(1);
+        let tracker = download_prioritizer
+            .get_advert_tracker(
+                &ArtifactId::FileTreeSync(0.to_string()),
+                &CryptoHash(vec![0]),
+            )
+            .unwrap();
+        let mut tracker = tracker.write().unwrap();
+        tracker.select_chunk_tree();
+        assert_eq!(tracker.advert().chunk_tree_root, Some(root));
+        for peer in 0..4 {
+            assert!(tracker.can_serve(unit_chunk_id, &node_test_id(peer)));
+            assert_eq!(
+                tracker.can_serve(piece_id, &node_test_id(peer)),
+                peer == 2 || peer == 3
+            );
+        }
+
+        // The attempt round of a piece completes once all capable peers were
+        // asked for it.
+        tracker.record_attempt(piece_id, &node_test_id(2));
+        assert!(!tracker.is_attempts_round_complete(piece_id));
+        tracker.record_attempt(piece_id, &node_test_id(3));
+        assert!(tracker.is_attempts_round_complete(piece_id));
+        tracker.record_attempt(unit_chunk_id, &node_test_id(2));
+        assert!(!tracker.is_attempts_round_complete(unit_chunk_id));
+
+        // Once rejected, the chunk tree advertised by the next most peers is
+        // used, with a fresh attempt round for its pieces.
+        assert!(tracker.reject_chunk_tree());
+        tracker.select_chunk_tree();
+        assert_eq!(tracker.advert().chunk_tree_root, Some(bogus_root));
+        assert!(!tracker.peer_attempted(piece_id, &node_test_id(2)));
+        assert!(tracker.peer_attempted(unit_chunk_id, &node_test_id(2)));
+        for peer in 0..4 {
+            assert_eq!(tracker.can_serve(piece_id, &node_test_id(peer)), peer == 0);
+        }
+
+        // Once all chunk trees are rejected, the artifact is downloaded as a
+        // single chunk.
+        assert!(tracker.reject_chunk_tree());
+        tracker.select_chunk_tree();
+        assert_eq!(tracker.advert().chunk_tree_root, None);
+        assert!(!tracker.reject_chunk_tree());
+    }
 }
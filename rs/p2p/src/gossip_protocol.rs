@@ -68,7 +68,14 @@ use ic_logger::{info, replica_logger::ReplicaLogger};
 use ic_metrics::MetricsRegistry;
 use ic_protobuf::registry::subnet::v1::GossipConfig;
 use ic_registry_client_helpers::subnet::SubnetRegistry;
-use ic_types::{artifact::ArtifactFilter, crypto::CryptoHash, p2p::GossipAdvert, NodeId, SubnetId};
+use ic_types::{
+    artifact::{ArtifactFilter, ArtifactId, ArtifactTag},
+    chunkable::{ArtifactChunk, ArtifactChunkData, ChunkId, ChunkableArtifact, CHUNKID_UNIT_CHUNK},
+    crypto::CryptoHash,
+    multi_chunked::ChunkedArtifact,
+    p2p::GossipAdvert,
+    NodeId, SubnetId,
+};
 use lru::LruCache;
 use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
@@ -127,6 +134,9 @@ pub trait Gossip {
 /// The cache used to check if a certain artifact has been received recently.
 pub(crate) type ReceiveCheckCache = LruCache<CryptoHash, ()>;
 
+/// Number of large artifacts whose pieces are kept ready to be served.
+const CHUNKED_ARTIFACTS_CACHE_SIZE: usize = 8;
+
 /// The canonical implementation of the `GossipMessage` trait.
 pub(crate) struct GossipImpl {
     /// The artifact manager used to handle received artifacts.
@@ -167,6 +177,9 @@ pub(crate) struct GossipImpl {
     pub registry_refresh_instant: Mutex<Instant>,
     /// The last retransmission request time.
     pub retransmission_request_instant: Mutex<Instant>,
+    /// The pieces of recently requested large artifacts, so that serving all
+    /// pieces of an artifact serializes and hashes it only once.
+    pub chunked_artifacts: Mutex<LruCache<ArtifactId, Arc<ChunkedArtifact>>>,
 }
 
 impl GossipImpl {
@@ -211,10 +224,45 @@ impl GossipImpl {
             pfn_invocation_instant: Mutex::new(Instant::now()),
             registry_refresh_instant: Mutex::new(Instant::now()),
             retransmission_request_instant: Mutex::new(Instant::now()),
+            chunked_artifacts: Mutex::new(LruCache::new(CHUNKED_ARTIFACTS_CACHE_SIZE)),
         };
         gossip.refresh_topology();
         gossip
     }
+
+    /// Returns the requested chunk of the given artifact. The pieces of large
+    /// consensus and DKG artifacts are served from a cache of their chunk
+    /// trees, rather than serializing and hashing the artifact per piece.
+    fn get_artifact_chunk(
+        &self,
+        artifact: Box<dyn ChunkableArtifact + '_>,
+        artifact_id: &ArtifactId,
+        chunk_id: ChunkId,
+    ) -> Option<ArtifactChunk> {
+        let unit_chunk_id = ChunkId::from(CHUNKID_UNIT_CHUNK);
+        match ArtifactTag::from(artifact_id) {
+            ArtifactTag::ConsensusArtifact | ArtifactTag::DkgArtifact
+                if chunk_id != unit_chunk_id => {}
+            _ => return artifact.get_chunk(chunk_id),
+        }
+
+        let cached = self.chunked_artifacts.lock().get(artifact_id).cloned();
+        let chunked = match cached {
+            Some(chunked) => chunked,
+            None => {
+                let whole = match artifact.get_chunk(unit_chunk_id)?.artifact_chunk_data {
+                    ArtifactChunkData::UnitChunkData(whole) => whole,
+                    _ => return None,
+                };
+                let chunked = Arc::new(ChunkedArtifact::from_artifact(&whole)?);
+                self.chunked_artifacts
+                    .lock()
+                    .put(artifact_id.clone(), chunked.clone());
+                chunked
+            }
+        };
+        chunked.get_chunk(chunk_id)
+    }
 }
 
 /// Canonical Implementation for the *Gossip* trait.
@@ -262,12 +310,14 @@ impl Gossip for GossipImpl {
             .artifact_manager
             .get_validated_by_identifier(&chunk_request.artifact_id)
         {
-            Some(artifact) => artifact.get_chunk(chunk_request.chunk_id).ok_or_else(|| {
-                self.gossip_metrics.requested_chunks_not_found.inc();
-                P2PError {
-                    p2p_error_code: P2PErrorCode::NotFound,
-                }
-            }),
+            Some(artifact) => self
+                .get_artifact_chunk(artifact, &chunk_request.artifact_id, chunk_request.chunk_id)
+                .ok_or_else(|| {
+                    self.gossip_metrics.requested_chunks_not_found.inc();
+                    P2PError {
+                        p2p_error_code: P2PErrorCode::NotFound,
+                    }
+                }),
             None => {
                 self.gossip_metrics.requested_chunks_not_found.inc();
                 Err(P2PError {
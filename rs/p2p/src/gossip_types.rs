@@ -51,14 +51,26 @@ pub(crate) enum GossipMessage {
     RetransmissionRequest(ArtifactFilter),
 }
 
+impl GossipMessage {
+    /// Returns the index of the transport channel the message is sent on.
+    /// Every message type has its own channel, see
+    /// `utils::NUM_TRANSPORT_CHANNELS`.
+    pub(crate) fn channel_index(&self) -> usize {
+        match self {
+            GossipMessage::Advert(_) => 0,
+            GossipMessage::ChunkRequest(_) => 1,
+            GossipMessage::Chunk(_) => 2,
+            GossipMessage::RetransmissionRequest(_) => 3,
+        }
+    }
+}
+
 /// A *Gossip* message can be converted into a
 /// `TransportChannelId`.
 impl From<&GossipMessage> for TransportChannelId {
     /// The method returns the flow tag corresponding to the gossip message.
-    ///
-    /// Currently, the flow tag is always 0.
-    fn from(_: &GossipMessage) -> Self {
-        TransportChannelId::from(0)
+    fn from(message: &GossipMessage) -> Self {
+        TransportChannelId::from(message.channel_index())
     }
 }
 
@@ -97,7 +97,7 @@
 use ic_config::transport::TransportConfig;
 use ic_interfaces::{artifact_manager::ArtifactManager, consensus_pool::ConsensusPoolCache};
 use ic_interfaces_registry::RegistryClient;
-use ic_interfaces_transport::{Transport, TransportChannelId};
+use ic_interfaces_transport::Transport;
 use ic_logger::ReplicaLogger;
 use ic_metrics::MetricsRegistry;
 use ic_types::{p2p::GossipAdvert, NodeId, SubnetId};
@@ -133,6 +133,19 @@ pub(crate) mod utils {
     use crate::gossip_types::GossipMessage;
     use ic_interfaces_transport::TransportChannelId;
 
+    /// The number of transport channels used by P2P. Adverts, chunk requests,
+    /// chunks and retransmission requests are each sent on their own channel,
+    /// so that a large chunk does not delay the small messages queued behind
+    /// it.
+    pub(crate) const NUM_TRANSPORT_CHANNELS: usize = 4;
+
+    /// Returns the transport channels used by P2P.
+    pub(crate) fn transport_channels() -> Vec<TransportChannelId> {
+        (0..NUM_TRANSPORT_CHANNELS)
+            .map(TransportChannelId::from)
+            .collect()
+    }
+
     /// An ordered collection of transport channels.
     pub(crate) struct TransportChannelIdMapper {
         transport_channels: Vec<TransportChannelId>,
@@ -141,13 +154,60 @@ pub(crate) mod utils {
     impl TransportChannelIdMapper {
         /// The function creates a new TransportChannelIdMapper instance.
         pub(crate) fn new(transport_channels: Vec<TransportChannelId>) -> Self {
-            assert_eq!(transport_channels.len(), 1);
+            assert_eq!(transport_channels.len(), NUM_TRANSPORT_CHANNELS);
             Self { transport_channels }
         }
 
         /// The function returns the flow tag of the flow the message maps to.
-        pub(crate) fn map(&self, _msg: &GossipMessage) -> TransportChannelId {
-            self.transport_channels[0]
+        pub(crate) fn map(&self, msg: &GossipMessage) -> TransportChannelId {
+            self.transport_channels[msg.channel_index()]
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::{
+            gossip_types::{GossipChunk, GossipChunkRequest},
+            P2PError, P2PErrorCode,
+        };
+        use ic_types::{
+            artifact::{ArtifactAttribute, ArtifactFilter, ArtifactId},
+            chunkable::ChunkId,
+            crypto::CryptoHash,
+            p2p::GossipAdvert,
+        };
+        use std::collections::BTreeSet;
+
+        #[test]
+        fn every_message_type_maps_to_its_own_channel() {
+            let mapper = TransportChannelIdMapper::new(transport_channels());
+            let artifact_id = ArtifactId::FileTreeSync("1".to_string());
+            let request = GossipChunkRequest {
+                artifact_id: artifact_id.clone(),
+                integrity_hash: CryptoHash(vec![1]),
+                chunk_id: ChunkId::new(0),
+            };
+            let messages = [
+                GossipMessage::Advert(GossipAdvert {
+                    artifact_id,
+                    attribute: ArtifactAttribute::FileTreeSync("1".to_string()),
+                    size: 0,
+                    integrity_hash: CryptoHash(vec![1]),
+                    chunk_tree_root: None,
+                }),
+                GossipMessage::ChunkRequest(request.clone()),
+                GossipMessage::Chunk(GossipChunk {
+                    request,
+                    artifact_chunk: Err(P2PError {
+                        p2p_error_code: P2PErrorCode::NotFound,
+                    }),
+                }),
+                GossipMessage::RetransmissionRequest(ArtifactFilter::default()),
+            ];
+
+            let channels: BTreeSet<_> = messages.iter().map(|msg| mapper.map(msg)).collect();
+            assert_eq!(channels.len(), NUM_TRANSPORT_CHANNELS);
         }
     }
 }
@@ -160,14 +220,19 @@ pub fn start_p2p(
     rt_handle: tokio::runtime::Handle,
     node_id: NodeId,
     subnet_id: SubnetId,
-    _transport_config: TransportConfig,
+    transport_config: TransportConfig,
     registry_client: Arc<dyn RegistryClient>,
     transport: Arc<dyn Transport>,
     consensus_pool_cache: Arc<dyn ConsensusPoolCache>,
     artifact_manager: Arc<dyn ArtifactManager>,
     advert_receiver: Receiver<GossipAdvert>,
 ) {
-    let p2p_transport_channels = vec![TransportChannelId::from(0)];
+    assert!(
+        transport_config.max_streams >= utils::NUM_TRANSPORT_CHANNELS,
+        "Transport must provide at least {} channels",
+        utils::NUM_TRANSPORT_CHANNELS
+    );
+    let p2p_transport_channels = utils::transport_channels();
     let gossip = Arc::new(gossip_protocol::GossipImpl::new(
         node_id,
         subnet_id,
@@ -54,6 +54,7 @@ impl ArtifactProcessor<TestArtifact> for ArtifactChunkingTestImpl {
                 size: 0,
                 id: artifact.id.clone(),
                 integrity_hash: CryptoHash(artifact_id.clone().into_bytes()),
+                chunk_tree_root: None,
             })
             .collect::<Vec<_>>();
         let changed = if !adverts.is_empty() {
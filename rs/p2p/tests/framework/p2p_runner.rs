@@ -115,6 +115,7 @@ fn execute_test(
             subnet_id,
             Some(transport),
             Arc::new(FakeTlsHandshake::new()),
+            Arc::new(FakeTlsHandshake::new()),
             sev_handshake,
             Arc::clone(&state_manager) as Arc<_>,
             Arc::clone(&state_manager) as Arc<_>,
@@ -279,6 +280,7 @@ fn execute_test_chunking_pool(
             subnet_id,
             Some(transport),
             Arc::new(FakeTlsHandshake::new()),
+            Arc::new(FakeTlsHandshake::new()),
             sev_handshake,
             Arc::clone(&state_manager) as Arc<_>,
             Arc::clone(&state_manager) as Arc<_>,
@@ -33,4 +33,14 @@ pub struct NodePublicKeys {
     #[prost(message, repeated, tag = "7")]
     pub idkg_dealing_encryption_pks:
         ::prost::alloc::vec::Vec<super::super::registry::crypto::v1::PublicKey>,
+    /// The committee signing public key and TLS certificate that were current
+    /// before the latest rotation. Their secret keys are kept until the next
+    /// rotation, so that the node can still sign for registry versions at which
+    /// the rotated keys are not yet registered.
+    #[prost(message, optional, tag = "8")]
+    pub previous_committee_signing_pk:
+        ::core::option::Option<super::super::registry::crypto::v1::PublicKey>,
+    #[prost(message, optional, tag = "9")]
+    pub previous_tls_certificate:
+        ::core::option::Option<super::super::registry::crypto::v1::X509PublicKeyCert>,
 }
@@ -33,6 +33,9 @@ pub struct GossipAdvert {
     pub artifact_id: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes = "vec", tag = "4")]
     pub integrity_hash: ::prost::alloc::vec::Vec<u8>,
+    /// Empty unless the artifact is downloaded in pieces.
+    #[prost(bytes = "vec", tag = "5")]
+    pub chunk_tree_root: ::prost::alloc::vec::Vec<u8>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
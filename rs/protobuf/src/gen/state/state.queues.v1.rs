@@ -51,6 +51,10 @@ pub struct Request {
     pub method_payload: ::prost::alloc::vec::Vec<u8>,
     #[prost(message, optional, tag = "7")]
     pub cycles_payment: ::core::option::Option<Cycles>,
+    /// Deadline of a best-effort response call, in nanoseconds since the Unix
+    /// epoch. Zero means no deadline.
+    #[prost(uint64, tag = "8")]
+    pub deadline: u64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -144,6 +148,18 @@ pub struct QueueEntry {
     #[prost(message, optional, tag = "2")]
     pub queue: ::core::option::Option<InputOutputQueue>,
 }
+/// An outstanding best-effort response call.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BestEffortCallback {
+    #[prost(uint64, tag = "1")]
+    pub callback_id: u64,
+    #[prost(message, optional, tag = "2")]
+    pub respondent: ::core::option::Option<super::super::super::types::v1::CanisterId>,
+    /// Call deadline, in nanoseconds since the Unix epoch.
+    #[prost(uint64, tag = "3")]
+    pub deadline: u64,
+}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CanisterQueues {
@@ -166,6 +182,11 @@ pub struct CanisterQueues {
     #[prost(message, repeated, tag = "8")]
     pub remote_subnet_input_schedule:
         ::prost::alloc::vec::Vec<super::super::super::types::v1::CanisterId>,
+    #[prost(message, repeated, tag = "9")]
+    pub best_effort_callbacks: ::prost::alloc::vec::Vec<BestEffortCallback>,
+    /// Best-effort callbacks already resolved with a synthetic reject.
+    #[prost(uint64, repeated, tag = "10")]
+    pub timed_out_callbacks: ::prost::alloc::vec::Vec<u64>,
 }
 /// Nested message and enum types in `CanisterQueues`.
 pub mod canister_queues {
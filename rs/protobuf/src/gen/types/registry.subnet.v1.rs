@@ -334,6 +334,10 @@ pub struct SubnetFeatures {
     /// Controls whether to collect/publish data to the onchain observability canister
     #[prost(bool, optional, tag = "8")]
     pub onchain_observability: ::core::option::Option<bool>,
+    /// Controls whether the nodes of this subnet talk to each other over QUIC
+    /// instead of TLS-on-TCP. It takes effect when the replicas restart.
+    #[prost(bool, optional, tag = "9")]
+    pub quic_transport: ::core::option::Option<bool>,
 }
 /// Per subnet ECDSA configuration
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -38,6 +38,10 @@ fn impl_proposal_metadata(ast: &syn::DeriveInput) -> TokenStream {
             fn is_json(&self) -> bool {
                 self.json
             }
+
+            fn registry_local_store(&self) -> Option<PathBuf> {
+                self.registry_local_store.clone()
+            }
         }
     };
     gen.into()
@@ -94,6 +98,14 @@ pub fn derive_common_proposal_fields(_: TokenStream, item: TokenStream) -> Token
                             /// If set, JSON output will be printed for --dry-run
                             #[clap(long)]
                             pub json: bool,
+
+                            /// If set together with --dry-run, the proposal is also executed
+                            /// against the registry in this registry local store. This runs the
+                            /// registry invariant checks and prints a diff of the records that
+                            /// the proposal would change. Proposals that are not executed by the
+                            /// registry canister only print a notice.
+                            #[clap(long, requires = "dry-run")]
+                            pub registry_local_store: Option<PathBuf>,
                     });
                     stream.extend(gen);
                     stream.extend(group.stream());
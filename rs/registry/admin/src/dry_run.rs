@@ -0,0 +1,406 @@
+//! Local execution of registry-mutating proposals for `--dry-run`.
+//!
+//! The payload of a proposal is applied to a copy of the registry that is
+//! rebuilt from a registry local store, using the code that the registry
+//! canister runs when the proposal is executed. This includes the registry
+//! invariant checks, so that invalid proposals are caught before they are
+//! submitted.
+
+use candid::{CandidType, Decode, Encode};
+use ic_nns_constants::REGISTRY_CANISTER_ID;
+use ic_nns_governance::pb::v1::NnsFunction;
+use ic_protobuf::registry::{
+    crypto::v1::{EcdsaSigningSubnetList, PublicKey, X509PublicKeyCert},
+    dc::v1::DataCenterRecord,
+    firewall::v1::{FirewallConfig, FirewallRuleSet},
+    hostos_version::v1::HostOsVersionRecord,
+    node::v1::NodeRecord,
+    node_operator::v1::NodeOperatorRecord,
+    node_rewards::v2::NodeRewardsTable,
+    provisional_whitelist::v1::ProvisionalWhitelist,
+    replica_version::v1::{BlessedReplicaVersions, ReplicaVersionRecord},
+    routing_table::v1::{CanisterMigrations, RoutingTable},
+    subnet::v1::{CatchUpPackageContents, SubnetListRecord, SubnetRecord},
+    unassigned_nodes_config::v1::UnassignedNodesConfigRecord,
+};
+use ic_registry_keys::{
+    make_blessed_replica_versions_key, make_canister_migrations_record_key,
+    make_firewall_config_record_key, make_provisional_whitelist_record_key,
+    make_routing_table_record_key, make_subnet_list_record_key,
+    make_unassigned_nodes_config_record_key, CRYPTO_RECORD_KEY_PREFIX,
+    CRYPTO_THRESHOLD_SIGNING_KEY_PREFIX, CRYPTO_TLS_CERT_KEY_PREFIX, DATA_CENTER_KEY_PREFIX,
+    ECDSA_SIGNING_SUBNET_LIST_KEY_PREFIX, HOSTOS_VERSION_KEY_PREFIX,
+    NODE_OPERATOR_RECORD_KEY_PREFIX, NODE_RECORD_KEY_PREFIX, NODE_REWARDS_TABLE_KEY,
+    REPLICA_VERSION_KEY_PREFIX, SUBNET_RECORD_KEY_PREFIX,
+};
+use ic_registry_local_store::{Changelog, LocalStoreImpl, LocalStoreReader};
+use ic_registry_transport::{delete, pb::v1::RegistryAtomicMutateRequest, upsert};
+use ic_types::RegistryVersion;
+use prost::Message;
+use registry_canister::{
+    pb::v1::{
+        registry_stable_storage::Version as ReprVersion, ChangelogEntry, RegistryStableStorage,
+    },
+    registry::Registry,
+};
+use serde::de::DeserializeOwned;
+use std::{any::Any, fmt::Debug, panic, path::Path};
+
+/// A registry record that is changed by a proposal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct RecordChange {
+    pub key: String,
+    /// The value before the proposal is executed, or `None` if the record
+    /// does not exist yet.
+    pub before: Option<Vec<u8>>,
+    /// The value after the proposal is executed, or `None` if the record is
+    /// deleted.
+    pub after: Option<Vec<u8>>,
+}
+
+/// The outcome of executing a proposal against a local copy of the registry.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum DryRunOutcome {
+    /// The proposal passes all the checks of the registry canister and
+    /// changes these records.
+    Changes(Vec<RecordChange>),
+    /// The proposal is not executed by the registry canister.
+    NotARegistryProposal,
+    /// The proposal is executed by the given method of the registry canister,
+    /// which cannot run locally, e.g. because it calls other canisters.
+    Unsupported(String),
+}
+
+/// Rebuilds the registry from the changelog in the registry local store at
+/// `path`.
+pub(crate) fn registry_from_local_store(path: &Path) -> Registry {
+    let changelog = LocalStoreImpl::new(path)
+        .get_changelog_since_version(RegistryVersion::from(0))
+        .unwrap_or_else(|err| {
+            panic!(
+                "Cannot read the registry local store at {:?}: {}",
+                path, err
+            )
+        });
+    registry_from_changelog(changelog)
+}
+
+/// Rebuilds the registry from a changelog, the first entry of which is
+/// version 1.
+pub(crate) fn registry_from_changelog(changelog: Changelog) -> Registry {
+    let changelog = changelog
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let mutations = entry
+                .into_iter()
+                .map(|key_mutation| match key_mutation.value {
+                    Some(value) => upsert(key_mutation.key, value),
+                    None => delete(key_mutation.key),
+                })
+                .collect();
+            ChangelogEntry {
+                version: index as u64 + 1,
+                encoded_mutation: RegistryAtomicMutateRequest {
+                    mutations,
+                    preconditions: vec![],
+                }
+                .encode_to_vec(),
+            }
+        })
+        .collect();
+
+    let mut registry = Registry::new();
+    registry.from_serializable_form(RegistryStableStorage {
+        version: ReprVersion::Version1 as i32,
+        deltas: vec![],
+        changelog,
+    });
+    registry
+}
+
+/// Executes the proposal with the given function and candid-encoded payload
+/// against `registry`, the same way the registry canister would.
+///
+/// Returns an error if the registry canister would reject the proposal, e.g.
+/// because the registry invariants would not hold after its execution.
+pub(crate) fn execute_proposal(
+    registry: &mut Registry,
+    nns_function: NnsFunction,
+    payload: &[u8],
+) -> Result<DryRunOutcome, String> {
+    let method = match nns_function.canister_and_function() {
+        Ok((canister_id, method)) if canister_id == REGISTRY_CANISTER_ID => method,
+        _ => return Ok(DryRunOutcome::NotARegistryProposal),
+    };
+    let version_before = registry.latest_version();
+
+    // The registry canister panics when it rejects a proposal. The panic is
+    // reported as an error, so the default panic message is not printed.
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        execute_registry_method(registry, method, payload)
+    }));
+    panic::set_hook(default_hook);
+
+    match result {
+        Ok(Some(Ok(()))) => Ok(DryRunOutcome::Changes(registry_changes(
+            registry,
+            version_before,
+        ))),
+        Ok(Some(Err(err))) => Err(err),
+        Ok(None) => Ok(DryRunOutcome::Unsupported(method.to_string())),
+        Err(panic) => Err(panic_message(panic)),
+    }
+}
+
+/// Calls the implementation of the registry canister method `method`, or
+/// returns `None` if the method cannot be executed locally.
+fn execute_registry_method(
+    registry: &mut Registry,
+    method: &str,
+    payload: &[u8],
+) -> Option<Result<(), String>> {
+    match method {
+        "add_node_operator" => registry.do_add_node_operator(decode_payload(payload)),
+        "add_nodes_to_subnet" => registry.do_add_nodes_to_subnet(decode_payload(payload)),
+        "remove_nodes_from_subnet" => registry.do_remove_nodes_from_subnet(decode_payload(payload)),
+        "change_subnet_membership" => registry.do_change_subnet_membership(decode_payload(payload)),
+        "bless_replica_version" => registry.do_bless_replica_version(decode_payload(payload)),
+        "retire_replica_version" => registry.do_retire_replica_version(decode_payload(payload)),
+        "update_elected_replica_versions" => {
+            registry.do_update_elected_replica_versions(decode_payload(payload))
+        }
+        "update_node_operator_config" => {
+            registry.do_update_node_operator_config(decode_payload(payload))
+        }
+        "update_subnet_replica_version" => {
+            registry.do_update_subnet_replica_version(decode_payload(payload))
+        }
+        "add_hostos_version" => registry.do_add_hostos_version(decode_payload(payload)),
+        "update_nodes_hostos_version" => {
+            registry.do_update_nodes_hostos_version(decode_payload(payload))
+        }
+        "update_subnet" => registry.do_update_subnet(decode_payload(payload)),
+        "clear_provisional_whitelist" => registry.do_clear_provisional_whitelist(),
+        "set_firewall_config" => registry.do_set_firewall_config(decode_payload(payload)),
+        "add_firewall_rules" => registry.do_add_firewall_rules(decode_payload(payload)),
+        "remove_firewall_rules" => registry.do_remove_firewall_rules(decode_payload(payload)),
+        "update_firewall_rules" => registry.do_update_firewall_rules(decode_payload(payload)),
+        "remove_nodes" => registry.do_remove_nodes(decode_payload(payload)),
+        "update_node_rewards_table" => {
+            registry.do_update_node_rewards_table(decode_payload(payload))
+        }
+        "add_or_remove_data_centers" => {
+            registry.do_add_or_remove_data_centers(decode_payload(payload))
+        }
+        "update_unassigned_nodes_config" => {
+            registry.do_update_unassigned_nodes_config(decode_payload(payload))
+        }
+        "remove_node_operators" => registry.do_remove_node_operators(decode_payload(payload)),
+        "reroute_canister_ranges" => {
+            return Some(registry.reroute_canister_ranges(decode_payload(payload)))
+        }
+        "prepare_canister_migration" => {
+            return Some(registry.prepare_canister_migration(decode_payload(payload)))
+        }
+        "complete_canister_migration" => {
+            return Some(registry.complete_canister_migration(decode_payload(payload)))
+        }
+        // Creating and recovering subnets calls the management canister.
+        _ => return None,
+    }
+    Some(Ok(()))
+}
+
+fn decode_payload<T: CandidType + DeserializeOwned>(payload: &[u8]) -> T {
+    Decode!(payload, T).unwrap_or_else(|err| panic!("Cannot decode the proposal payload: {}", err))
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else {
+        "The registry canister would reject the proposal.".to_string()
+    }
+}
+
+/// Returns the records that changed since `since_version`, ordered by key.
+pub(crate) fn registry_changes(registry: &Registry, since_version: u64) -> Vec<RecordChange> {
+    registry
+        .get_changes_since(since_version, None)
+        .into_iter()
+        .map(|delta| RecordChange {
+            key: String::from_utf8_lossy(&delta.key).to_string(),
+            before: registry
+                .get(&delta.key, since_version)
+                .map(|value| value.value.clone()),
+            // The values of a delta are ordered from the latest to the oldest.
+            after: delta
+                .values
+                .first()
+                .filter(|value| !value.deletion_marker)
+                .map(|value| value.value.clone()),
+        })
+        .filter(|change| change.before != change.after)
+        .collect()
+}
+
+/// Formats the changed records as a human-readable diff.
+pub(crate) fn format_changes(changes: &[RecordChange]) -> String {
+    let mut output = String::new();
+    for change in changes {
+        let (action, before, after) = match (&change.before, &change.after) {
+            (None, Some(after)) => ("Added", String::new(), format_value(&change.key, after)),
+            (Some(before), None) => ("Deleted", format_value(&change.key, before), String::new()),
+            (Some(before), Some(after)) => (
+                "Changed",
+                format_value(&change.key, before),
+                format_value(&change.key, after),
+            ),
+            (None, None) => continue,
+        };
+        output.push_str(&format!("{} {}:\n", action, change.key));
+        for line in diff_lines(&before, &after) {
+            output.push_str(&format!("    {}\n", line));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Decodes a registry value based on its key, falling back to hex for
+/// unknown keys.
+fn format_value(key: &str, value: &[u8]) -> String {
+    fn decode<T: Message + Default + Debug>(value: &[u8]) -> String {
+        match T::decode(value) {
+            Ok(record) => format!("{:#?}", record),
+            Err(_) => hex::encode(value),
+        }
+    }
+
+    if key.starts_with(SUBNET_RECORD_KEY_PREFIX) {
+        decode::<SubnetRecord>(value)
+    } else if key == make_subnet_list_record_key() {
+        decode::<SubnetListRecord>(value)
+    } else if key.starts_with("catch_up_package_contents_") {
+        decode::<CatchUpPackageContents>(value)
+    } else if key.starts_with(NODE_RECORD_KEY_PREFIX) {
+        decode::<NodeRecord>(value)
+    } else if key.starts_with(NODE_OPERATOR_RECORD_KEY_PREFIX) {
+        decode::<NodeOperatorRecord>(value)
+    } else if key.starts_with(REPLICA_VERSION_KEY_PREFIX) {
+        decode::<ReplicaVersionRecord>(value)
+    } else if key == make_blessed_replica_versions_key() {
+        decode::<BlessedReplicaVersions>(value)
+    } else if key.starts_with(HOSTOS_VERSION_KEY_PREFIX) {
+        decode::<HostOsVersionRecord>(value)
+    } else if key == make_routing_table_record_key() {
+        decode::<RoutingTable>(value)
+    } else if key == make_canister_migrations_record_key() {
+        decode::<CanisterMigrations>(value)
+    } else if key == make_provisional_whitelist_record_key() {
+        decode::<ProvisionalWhitelist>(value)
+    } else if key == make_firewall_config_record_key() {
+        decode::<FirewallConfig>(value)
+    } else if key.starts_with("firewall_rules_") {
+        decode::<FirewallRuleSet>(value)
+    } else if key.starts_with(DATA_CENTER_KEY_PREFIX) {
+        decode::<DataCenterRecord>(value)
+    } else if key == NODE_REWARDS_TABLE_KEY {
+        decode::<NodeRewardsTable>(value)
+    } else if key == make_unassigned_nodes_config_record_key() {
+        decode::<UnassignedNodesConfigRecord>(value)
+    } else if key.starts_with(ECDSA_SIGNING_SUBNET_LIST_KEY_PREFIX) {
+        decode::<EcdsaSigningSubnetList>(value)
+    } else if key.starts_with(CRYPTO_TLS_CERT_KEY_PREFIX) {
+        decode::<X509PublicKeyCert>(value)
+    } else if key.starts_with(CRYPTO_RECORD_KEY_PREFIX)
+        || key.starts_with(CRYPTO_THRESHOLD_SIGNING_KEY_PREFIX)
+    {
+        decode::<PublicKey>(value)
+    } else {
+        hex::encode(value)
+    }
+}
+
+/// Computes a line-based diff of `before` and `after` from the longest common
+/// subsequence of their lines. Lines are prefixed with "- " if they are only
+/// in `before`, "+ " if they are only in `after`, and "  " otherwise.
+pub(crate) fn diff_lines(before: &str, after: &str) -> Vec<String> {
+    let before: Vec<&str> = before.lines().collect();
+    let after: Vec<&str> = after.lines().collect();
+
+    // lcs[i][j] is the length of the longest common subsequence of
+    // before[i..] and after[j..].
+    let mut lcs = vec![vec![0; after.len() + 1]; before.len() + 1];
+    for i in (0..before.len()).rev() {
+        for j in (0..after.len()).rev() {
+            lcs[i][j] = if before[i] == after[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < before.len() || j < after.len() {
+        if i < before.len() && j < after.len() && before[i] == after[j] {
+            diff.push(format!("  {}", before[i]));
+            i += 1;
+            j += 1;
+        } else if i < before.len() && (j == after.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(format!("- {}", before[i]));
+            i += 1;
+        } else {
+            diff.push(format!("+ {}", after[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+/// Executes the proposal against the registry in the local store at
+/// `local_store_path` and prints the records it would change. Exits with an
+/// error if the registry canister would reject the proposal.
+pub(crate) fn print_dry_run_against_local_store<C: CandidType>(
+    local_store_path: &Path,
+    nns_function: NnsFunction,
+    payload: &C,
+) {
+    let mut registry = registry_from_local_store(local_store_path);
+    println!(
+        "\nExecuting the proposal against the registry local store at {:?} (version {}).",
+        local_store_path,
+        registry.latest_version()
+    );
+    let payload = Encode!(payload).expect("Couldn't candid-encode the proposal payload.");
+
+    match execute_proposal(&mut registry, nns_function, &payload) {
+        Ok(DryRunOutcome::Changes(changes)) => {
+            println!(
+                "The proposal passes the registry invariant checks and changes {} record(s).\n",
+                changes.len()
+            );
+            print!("{}", format_changes(&changes));
+        }
+        Ok(DryRunOutcome::NotARegistryProposal) => {
+            println!("The proposal does not mutate the registry.");
+        }
+        Ok(DryRunOutcome::Unsupported(method)) => {
+            println!(
+                "The registry method {} cannot be executed locally, as it calls other canisters.",
+                method
+            );
+        }
+        Err(err) => {
+            eprintln!("The registry canister would reject the proposal: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
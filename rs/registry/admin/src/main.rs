@@ -1,6 +1,7 @@
 //! Command-line utility to help submitting proposals to modify the IC's NNS.
 //!
 //! TODO(NNS1-902) Move this utility to `rs/nns`.
+mod dry_run;
 mod types;
 
 extern crate chrono;
@@ -667,6 +668,7 @@ pub trait ProposalMetadata {
     fn proposer_and_sender(&self, sender: Sender) -> (NeuronId, Sender);
     fn is_dry_run(&self) -> bool;
     fn is_json(&self) -> bool;
+    fn registry_local_store(&self) -> Option<PathBuf>;
 }
 
 /// Trait to extract the title for proposal type.
@@ -2662,6 +2664,12 @@ struct ProposeToOpenSnsTokenSwap {
     /// `(i * dissolve_delay_interval_seconds) + rand(0..dissolve_delay_interval_seconds)`
     neuron_basket_dissolve_delay_interval_seconds: u64,
 
+    #[clap(long)]
+    /// An optional vesting period, in seconds, of each neuron in the basket.
+    /// While a neuron is vesting, it cannot be dissolved and its dissolve delay
+    /// cannot be decreased.
+    neuron_basket_vesting_period_seconds: Option<u64>,
+
     #[clap(long)]
     /// The amount that the community fund will collectively spend in maturity on
     /// the swap.
@@ -2686,6 +2694,7 @@ impl From<&ProposeToOpenSnsTokenSwap> for OpenSnsTokenSwap {
             sns_token_e8s,
             neuron_basket_count,
             neuron_basket_dissolve_delay_interval_seconds,
+            neuron_basket_vesting_period_seconds,
             community_fund_investment_e8s,
             sale_delay_seconds,
             // General proposal fields.  These are listed explicitly
@@ -2698,6 +2707,7 @@ impl From<&ProposeToOpenSnsTokenSwap> for OpenSnsTokenSwap {
             summary_file: _,
             dry_run: _,
             json: _,
+            registry_local_store: _,
         } = (*cli_proposal).clone();
         OpenSnsTokenSwap {
             target_swap_canister_id: Some(target_swap_canister_id),
@@ -2712,6 +2722,7 @@ impl From<&ProposeToOpenSnsTokenSwap> for OpenSnsTokenSwap {
                 neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
                     count: neuron_basket_count,
                     dissolve_delay_interval_seconds: neuron_basket_dissolve_delay_interval_seconds,
+                    vesting_period_seconds: neuron_basket_vesting_period_seconds,
                 }),
                 sale_delay_seconds,
             }),
@@ -3967,6 +3978,7 @@ impl TryFrom<ProposeToCreateServiceNervousSystemCmd> for CreateServiceNervousSys
             summary_file: _,
             dry_run: _,
             json: _,
+            registry_local_store: _,
         } = cmd;
 
         let name = Some(name);
@@ -4180,6 +4192,7 @@ async fn propose_to_create_service_nervous_system(
     print_proposal(&proposal, &cmd);
 
     if is_dry_run {
+        print_registry_local_store_not_used(&cmd);
         return;
     }
 
@@ -5472,6 +5485,7 @@ async fn main() {
             if cmd.is_dry_run() {
                 let payload = cmd.payload(agent.url.clone()).await;
                 print_insert_sns_wasm_upgrade_path_entries_payload(payload);
+                print_registry_local_store_not_used(&cmd);
                 return;
             }
 
@@ -5723,6 +5737,9 @@ async fn propose_external_proposal_from_command<
     print_proposal(&payload, &cmd);
 
     if cmd.is_dry_run() {
+        if let Some(local_store_path) = cmd.registry_local_store() {
+            dry_run::print_dry_run_against_local_store(&local_store_path, nns_function, &payload);
+        }
         return;
     }
 
@@ -6240,6 +6257,7 @@ async fn propose_to_open_sns_token_swap(
         print_proposal(&payload, &cmd);
 
         if cmd.is_dry_run() {
+            print_registry_local_store_not_used(&cmd);
             return Ok(None);
         }
 
@@ -6317,6 +6335,7 @@ async fn propose_to_add_or_remove_node_provider(
     print_proposal(&payload, &cmd);
 
     if cmd.is_dry_run() {
+        print_registry_local_store_not_used(&cmd);
         return;
     }
 
@@ -6842,3 +6861,15 @@ fn print_proposal<T: Serialize + Debug, Command: ProposalMetadata + ProposalTitl
         println!("Payload: {:#?}", payload);
     }
 }
+
+/// Notifies the user that `--registry-local-store` has no effect for a
+/// proposal that is not executed by the registry canister. The notice goes to
+/// stderr so that the `--json` output stays parseable.
+fn print_registry_local_store_not_used<Command: ProposalMetadata>(cmd: &Command) {
+    if let Some(local_store_path) = cmd.registry_local_store() {
+        eprintln!(
+            "\nThis is not a registry proposal, so it is not executed against the registry local store at {:?}.",
+            local_store_path
+        );
+    }
+}
@@ -336,3 +336,86 @@ fn convert_from_flags_to_create_service_nervous_system() {
         }
     );
 }
+
+#[test]
+fn test_dry_run_diff_lines() {
+    assert_eq!(
+        dry_run::diff_lines("a\nb\nc", "a\nx\nc\nd"),
+        vec!["  a", "- b", "+ x", "  c", "+ d"]
+    );
+    assert_eq!(dry_run::diff_lines("", "a"), vec!["+ a"]);
+    assert_eq!(dry_run::diff_lines("a", ""), vec!["- a"]);
+}
+
+#[test]
+fn test_dry_run_registry_changes() {
+    let key_mutation = |key: &str, value: Option<&[u8]>| KeyMutation {
+        key: key.to_string(),
+        value: value.map(|value| value.to_vec()),
+    };
+    let registry = dry_run::registry_from_changelog(vec![
+        vec![
+            key_mutation("a", Some(b"1")),
+            key_mutation("b", Some(b"2")),
+            key_mutation("d", Some(b"5")),
+        ],
+        vec![
+            key_mutation("a", Some(b"3")),
+            key_mutation("b", None),
+            key_mutation("c", Some(b"4")),
+            key_mutation("d", Some(b"5")),
+        ],
+    ]);
+
+    // Records whose value does not change are omitted.
+    assert_eq!(
+        dry_run::registry_changes(&registry, 1),
+        vec![
+            dry_run::RecordChange {
+                key: "a".to_string(),
+                before: Some(b"1".to_vec()),
+                after: Some(b"3".to_vec()),
+            },
+            dry_run::RecordChange {
+                key: "b".to_string(),
+                before: Some(b"2".to_vec()),
+                after: None,
+            },
+            dry_run::RecordChange {
+                key: "c".to_string(),
+                before: None,
+                after: Some(b"4".to_vec()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_dry_run_execute_proposal() {
+    let mut registry = dry_run::registry_from_changelog(vec![]);
+
+    // Proposals that are not executed by the registry canister are not
+    // checked.
+    assert_eq!(
+        dry_run::execute_proposal(
+            &mut registry,
+            NnsFunction::IcpXdrConversionRate,
+            &Encode!(&()).unwrap()
+        ),
+        Ok(dry_run::DryRunOutcome::NotARegistryProposal)
+    );
+
+    // The registry canister rejects upgrading a subnet to a version that is
+    // not blessed.
+    let payload = UpdateSubnetReplicaVersionPayload {
+        subnet_id: PrincipalId::new_subnet_test_id(1),
+        replica_version_id: "version".to_string(),
+    };
+    assert!(dry_run::execute_proposal(
+        &mut registry,
+        NnsFunction::UpdateSubnetReplicaVersion,
+        &Encode!(&payload).unwrap()
+    )
+    .is_err());
+    assert_eq!(registry.latest_version(), 0);
+}
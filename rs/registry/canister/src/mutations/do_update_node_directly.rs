@@ -7,11 +7,19 @@ use prost::Message;
 use candid::{CandidType, Deserialize};
 use dfn_core::api::now;
 use ic_base_types::NodeId;
-use ic_crypto_node_key_validation::ValidIDkgDealingEncryptionPublicKey;
+use ic_crypto_node_key_validation::{
+    ValidCommitteeSigningPublicKey, ValidIDkgDealingEncryptionPublicKey, ValidTlsCertificate,
+};
 use ic_nns_common::registry::get_subnet_ids_from_subnet_list;
-use ic_protobuf::registry::{crypto::v1::PublicKey, subnet::v1::SubnetRecord};
-use ic_registry_keys::{make_crypto_node_key, make_node_record_key};
-use ic_registry_transport::update;
+use ic_protobuf::registry::{
+    crypto::v1::{PublicKey, X509PublicKeyCert},
+    subnet::v1::SubnetRecord,
+};
+use ic_registry_keys::{
+    make_crypto_node_key, make_crypto_tls_cert_key, make_node_record_key,
+    make_previous_crypto_node_key, make_previous_crypto_tls_cert_key,
+};
+use ic_registry_transport::{delete, update, upsert};
 use ic_types::{crypto::KeyPurpose, PrincipalId};
 
 // Since nodes update their keys in turn, every potential update delay will carry over to all
@@ -21,6 +29,18 @@ use ic_types::{crypto::KeyPurpose, PrincipalId};
 // it's own expiration timestamp, they won't update too early.
 const DELAY_COMPENSATION: f64 = 0.85;
 
+// The minimum time between two rotations of a node's committee signing key and TLS certificate.
+// Nodes configured with a shorter rotation period keep retrying until this period has passed.
+// Within a subnet, the nodes take turns, so that the subnet as a whole rotates at most once every
+// `MIN_NODE_SIGNING_KEYS_ROTATION_PERIOD / subnet_size`.
+const MIN_NODE_SIGNING_KEYS_ROTATION_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The time after a rotation during which a node's previous committee signing key and TLS
+/// certificate are kept in the registry next to the new ones, so that peers that have not yet
+/// caught up with the rotation can still authenticate the node. Only afterwards can the previous
+/// keys be retired, and only once they are retired can the node rotate again.
+pub const NODE_SIGNING_KEYS_TRANSITION_PERIOD: Duration = Duration::from_secs(60 * 60);
+
 impl Registry {
     /// Updates an existing node's config in the registry.
     ///
@@ -28,6 +48,10 @@ impl Registry {
     /// The update is only executed, if the previous key does not exist at all or is older than
     /// `ecdsa_config.idkg_key_rotation_period_ms` and the most recent key update in the node's
     /// subnet happened more than `ecdsa_config.idkg_key_rotation_period_ms / subnet_size` ago.
+    ///
+    /// If the payload contains a committee signing key and a TLS certificate, these replace
+    /// the node's current ones instead (see `do_rotate_node_signing_keys`), and if it asks to
+    /// retire the previous ones, these are removed (see `do_retire_previous_node_signing_keys`).
     pub fn do_update_node_directly(
         &mut self,
         payload: UpdateNodeDirectlyPayload,
@@ -53,6 +77,13 @@ impl Registry {
             "{}do_update_node_directly: Node Id {:} not found in the registry, aborting node update.",
             LOG_PREFIX, node_id))?;
 
+        if payload.retire_previous_node_signing_keys == Some(true) {
+            return self.do_retire_previous_node_signing_keys(now, node_id, payload);
+        }
+        if payload.committee_signing_pk.is_some() || payload.transport_tls_cert.is_some() {
+            return self.do_rotate_node_signing_keys(now, node_id, payload);
+        }
+
         // 2. Disallow updating if the node is not on an ECDSA subnet or key rotation is disabled.
         let subnet_record = self.get_subnet_from_node_id_or_panic(node_id);
         let subnet_size = subnet_record.membership.len();
@@ -108,7 +139,9 @@ impl Registry {
         // 4. Disallow updating if the most recent key update on the subnet is not old enough.
         //    If the node has no timestamp, skip all checks.
         if previous_timestamp_set {
-            if let Some(last_key_update_timestamp) = self.last_key_update_on_subnet(subnet_record) {
+            if let Some(last_key_update_timestamp) =
+                self.last_key_update_on_subnet(subnet_record, KeyPurpose::IDkgMEGaEncryption)
+            {
                 // The node is on ECDSA subnet, and has a timestamp
                 let key_rotation_period_on_subnet =
                     (idkg_key_rotation_period_ms as f64 / subnet_size as f64 * DELAY_COMPENSATION)
@@ -158,30 +191,257 @@ impl Registry {
         Ok(())
     }
 
-    fn get_subnet_from_node_id_or_panic(&self, node_id: NodeId) -> SubnetRecord {
+    /// Replaces the node's committee signing key and TLS certificate with rotated ones.
+    ///
+    /// Both keys must be provided, and the update is only executed if
+    /// - the keys of the node's previous rotation have been retired,
+    /// - the node's current committee signing key has no timestamp or is older than
+    ///   `MIN_NODE_SIGNING_KEYS_ROTATION_PERIOD`, and
+    /// - no node in the node's subnet rotated its committee signing key in the last
+    ///   `MIN_NODE_SIGNING_KEYS_ROTATION_PERIOD / subnet_size`.
+    ///
+    /// The current keys are kept as the node's previous keys, next to the new ones, until they
+    /// are retired with `do_retire_previous_node_signing_keys`.
+    fn do_rotate_node_signing_keys(
+        &mut self,
+        now: SystemTime,
+        node_id: NodeId,
+        payload: UpdateNodeDirectlyPayload,
+    ) -> Result<(), String> {
+        if payload.idkg_dealing_encryption_pk.is_some() {
+            return Err(
+                "idkg_dealing_encryption_pk cannot be updated together with the committee signing key and TLS certificate"
+                    .to_string(),
+            );
+        }
+        let (committee_signing_pk, transport_tls_cert) =
+            match (payload.committee_signing_pk, payload.transport_tls_cert) {
+                (Some(committee_signing_pk), Some(transport_tls_cert)) => {
+                    (committee_signing_pk, transport_tls_cert)
+                }
+                _ => {
+                    return Err(
+                        "committee_signing_pk and transport_tls_cert must be updated together"
+                            .to_string(),
+                    )
+                }
+            };
+
+        let duration_since_unix_epoch = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|err| format!("couldn't get time since unix epoch: {}", err))?;
+
+        // Disallow updating while the keys of the previous rotation are still in transition.
+        if self.has_previous_node_signing_keys(node_id) {
+            return Err(
+                "the previous committee signing key and TLS certificate of this node have not been retired yet"
+                    .to_string(),
+            );
+        }
+
+        // Disallow updating if the existing committee signing key is sufficiently fresh.
+        let committee_signing_pk_key = make_crypto_node_key(node_id, KeyPurpose::CommitteeSigning);
+        let tls_cert_key = make_crypto_tls_cert_key(node_id);
+        let current_committee_signing_pk = self
+            .get(committee_signing_pk_key.as_bytes(), self.latest_version())
+            .cloned();
+        let current_tls_cert = self
+            .get(tls_cert_key.as_bytes(), self.latest_version())
+            .cloned();
+        if let Some(record) = &current_committee_signing_pk {
+            let pk = PublicKey::decode(record.value.as_slice()).map_err(|e| {
+                format!(
+                    "committee_signing_pk is not in the expected format: {:?}",
+                    e
+                )
+            })?;
+            if let Some(last_update_timestamp) = pk.timestamp {
+                let sum = last_update_timestamp
+                    .checked_add(MIN_NODE_SIGNING_KEYS_ROTATION_PERIOD.as_millis() as u64)
+                    .ok_or_else(|| {
+                        "Integer overflow when adding key rotation period.".to_string()
+                    })?;
+                if Duration::from_millis(sum) > duration_since_unix_epoch {
+                    return Err(
+                        "the committee signing key of this node is sufficiently fresh".to_string(),
+                    );
+                }
+            }
+        }
+
+        // Disallow updating if another node of the subnet rotated its keys recently.
+        if let Some(subnet_record) = self.get_subnet_from_node_id(node_id) {
+            let subnet_size = subnet_record.membership.len();
+            if let Some(last_rotation_timestamp) =
+                self.last_key_update_on_subnet(subnet_record, KeyPurpose::CommitteeSigning)
+            {
+                let rotation_period_on_subnet =
+                    (MIN_NODE_SIGNING_KEYS_ROTATION_PERIOD.as_millis() as f64 / subnet_size as f64
+                        * DELAY_COMPENSATION) as u64;
+                let sum = last_rotation_timestamp
+                    .checked_add(rotation_period_on_subnet)
+                    .ok_or_else(|| {
+                        "Integer overflow when adding key rotation period on subnet.".to_string()
+                    })?;
+                if Duration::from_millis(sum) > duration_since_unix_epoch {
+                    return Err(
+                        "the subnet had a committee signing key rotation recently".to_string()
+                    );
+                }
+            }
+        }
+
+        let valid_committee_signing_pk = {
+            let mut pk = PublicKey::decode(&committee_signing_pk[..]).map_err(|e| {
+                format!(
+                    "committee_signing_pk is not in the expected format: {:?}",
+                    e
+                )
+            })?;
+            // Set the key timestamp to the current time.
+            pk.timestamp = Some(duration_since_unix_epoch.as_millis() as u64);
+            ValidCommitteeSigningPublicKey::try_from(pk)
+                .map_err(|e| format!("key validation failed: {}", e))?
+        };
+        let valid_tls_certificate = {
+            let cert = X509PublicKeyCert::decode(&transport_tls_cert[..]).map_err(|e| {
+                format!("transport_tls_cert is not in the expected format: {:?}", e)
+            })?;
+            ValidTlsCertificate::try_from((cert, node_id))
+                .map_err(|e| format!("certificate validation failed: {}", e))?
+        };
+
+        // Keep the current keys next to the new ones until they are retired.
+        let mut mutations = vec![];
+        if let Some(record) = current_committee_signing_pk {
+            mutations.push(upsert(
+                make_previous_crypto_node_key(node_id, KeyPurpose::CommitteeSigning).as_bytes(),
+                record.value,
+            ));
+        }
+        if let Some(record) = current_tls_cert {
+            mutations.push(upsert(
+                make_previous_crypto_tls_cert_key(node_id).as_bytes(),
+                record.value,
+            ));
+        }
+        mutations.push(update(
+            committee_signing_pk_key.as_bytes(),
+            encode_or_panic(valid_committee_signing_pk.get()),
+        ));
+        mutations.push(update(
+            tls_cert_key.as_bytes(),
+            encode_or_panic(valid_tls_certificate.get()),
+        ));
+
+        self.maybe_apply_mutation_internal(mutations);
+
+        Ok(())
+    }
+
+    /// Removes the committee signing key and TLS certificate that the node used before its
+    /// latest rotation.
+    ///
+    /// The update is only executed once `NODE_SIGNING_KEYS_TRANSITION_PERIOD` has passed since
+    /// the rotation, i.e., since the timestamp of the node's current committee signing key.
+    fn do_retire_previous_node_signing_keys(
+        &mut self,
+        now: SystemTime,
+        node_id: NodeId,
+        payload: UpdateNodeDirectlyPayload,
+    ) -> Result<(), String> {
+        if payload.idkg_dealing_encryption_pk.is_some()
+            || payload.committee_signing_pk.is_some()
+            || payload.transport_tls_cert.is_some()
+        {
+            return Err(
+                "previous keys cannot be retired together with an update of the node's keys"
+                    .to_string(),
+            );
+        }
+        if !self.has_previous_node_signing_keys(node_id) {
+            return Err(
+                "this node has no previous committee signing key and TLS certificate to retire"
+                    .to_string(),
+            );
+        }
+
+        let duration_since_unix_epoch = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|err| format!("couldn't get time since unix epoch: {}", err))?;
+        let committee_signing_pk_key = make_crypto_node_key(node_id, KeyPurpose::CommitteeSigning);
+        let rotation_timestamp = self
+            .get(committee_signing_pk_key.as_bytes(), self.latest_version())
+            .and_then(|record| PublicKey::decode(record.value.as_slice()).ok())
+            .and_then(|pk| pk.timestamp)
+            .ok_or_else(|| {
+                "the committee signing key of this node has no rotation timestamp".to_string()
+            })?;
+        let sum = rotation_timestamp
+            .checked_add(NODE_SIGNING_KEYS_TRANSITION_PERIOD.as_millis() as u64)
+            .ok_or_else(|| "Integer overflow when adding key transition period.".to_string())?;
+        if Duration::from_millis(sum) > duration_since_unix_epoch {
+            return Err(
+                "the committee signing key and TLS certificate of this node are still in transition"
+                    .to_string(),
+            );
+        }
+
+        let mutations = [
+            make_previous_crypto_node_key(node_id, KeyPurpose::CommitteeSigning),
+            make_previous_crypto_tls_cert_key(node_id),
+        ]
+        .iter()
+        .filter(|key| self.get(key.as_bytes(), self.latest_version()).is_some())
+        .map(delete)
+        .collect();
+
+        self.maybe_apply_mutation_internal(mutations);
+
+        Ok(())
+    }
+
+    fn has_previous_node_signing_keys(&self, node_id: NodeId) -> bool {
+        [
+            make_previous_crypto_node_key(node_id, KeyPurpose::CommitteeSigning),
+            make_previous_crypto_tls_cert_key(node_id),
+        ]
+        .iter()
+        .any(|key| self.get(key.as_bytes(), self.latest_version()).is_some())
+    }
+
+    fn get_subnet_from_node_id(&self, node_id: NodeId) -> Option<SubnetRecord> {
         get_subnet_ids_from_subnet_list(self.get_subnet_list_record())
             .into_iter()
             .map(|subnet_id| self.get_subnet_or_panic(subnet_id))
             .find(|subnet_record| subnet_record.membership.contains(&node_id.get().to_vec()))
-            .unwrap_or_else(|| {
-                panic!(
-                    "{}subnet record for node {:} not found in the registry.",
-                    LOG_PREFIX, node_id
-                )
-            })
     }
 
-    // Get the latest idkg encryption key timestamp of all nodes in the given subnet record
-    fn last_key_update_on_subnet(&self, subnet_record: SubnetRecord) -> Option<u64> {
+    fn get_subnet_from_node_id_or_panic(&self, node_id: NodeId) -> SubnetRecord {
+        self.get_subnet_from_node_id(node_id).unwrap_or_else(|| {
+            panic!(
+                "{}subnet record for node {:} not found in the registry.",
+                LOG_PREFIX, node_id
+            )
+        })
+    }
+
+    // Get the latest timestamp of the keys with the given purpose of all nodes in the given
+    // subnet record
+    fn last_key_update_on_subnet(
+        &self,
+        subnet_record: SubnetRecord,
+        key_purpose: KeyPurpose,
+    ) -> Option<u64> {
         subnet_record
             .membership
             .into_iter()
             .filter_map(|node_id| {
-                let idkg_pk_key = make_crypto_node_key(
+                let pk_key = make_crypto_node_key(
                     NodeId::from(PrincipalId::try_from(node_id.as_slice()).unwrap_or_default()),
-                    KeyPurpose::IDkgMEGaEncryption,
+                    key_purpose,
                 );
-                self.get(idkg_pk_key.as_bytes(), self.latest_version())
+                self.get(pk_key.as_bytes(), self.latest_version())
             })
             .filter_map(|value| {
                 PublicKey::decode(value.value.as_slice())
@@ -193,9 +453,16 @@ impl Registry {
 }
 
 /// The payload of an request to update keys of the existing node.
+///
+/// Either the iDKG dealing encryption key, or both the committee signing key and the TLS
+/// certificate, are updated, or the committee signing key and TLS certificate that the node
+/// used before its latest rotation are retired.
 #[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct UpdateNodeDirectlyPayload {
     pub idkg_dealing_encryption_pk: Option<Vec<u8>>,
+    pub committee_signing_pk: Option<Vec<u8>>,
+    pub transport_tls_cert: Option<Vec<u8>>,
+    pub retire_previous_node_signing_keys: Option<bool>,
 }
 
 #[cfg(test)]
@@ -205,10 +472,12 @@ mod test {
         add_fake_subnet, get_invariant_compliant_subnet_record, invariant_compliant_registry,
         prepare_registry_with_nodes,
     };
+    use crate::mutations::node_management::do_add_node::connection_endpoint_from_string;
     use ic_config::crypto::CryptoConfig;
     use ic_crypto_node_key_generation::generate_node_keys_once;
     use ic_crypto_node_key_validation::ValidNodePublicKeys;
     use ic_ic00_types::{EcdsaCurve, EcdsaKeyId};
+    use ic_protobuf::registry::node::v1::NodeRecord;
     use ic_protobuf::registry::subnet::v1::SubnetRecord;
     use ic_registry_subnet_features::{EcdsaConfig, DEFAULT_ECDSA_MAX_QUEUE_SIZE};
     use ic_registry_transport::insert;
@@ -243,6 +512,9 @@ mod test {
                 node_id,
                 UpdateNodeDirectlyPayload {
                     idkg_dealing_encryption_pk: Some(protobuf_to_vec(pk)),
+                    committee_signing_pk: None,
+                    transport_tls_cert: None,
+                    retire_previous_node_signing_keys: None,
                 },
             )
             .unwrap();
@@ -279,6 +551,9 @@ mod test {
                 node_ids[0],
                 UpdateNodeDirectlyPayload {
                     idkg_dealing_encryption_pk: Some(protobuf_to_vec(pk)),
+                    committee_signing_pk: None,
+                    transport_tls_cert: None,
+                    retire_previous_node_signing_keys: None,
                 },
             )
             .unwrap();
@@ -330,6 +605,9 @@ mod test {
                 node_ids[0],
                 UpdateNodeDirectlyPayload {
                     idkg_dealing_encryption_pk: Some(protobuf_to_vec(pk)),
+                    committee_signing_pk: None,
+                    transport_tls_cert: None,
+                    retire_previous_node_signing_keys: None,
                 },
             )
             .unwrap();
@@ -378,6 +656,9 @@ mod test {
             node_ids[0],
             UpdateNodeDirectlyPayload {
                 idkg_dealing_encryption_pk: Default::default(),
+                committee_signing_pk: None,
+                transport_tls_cert: None,
+                retire_previous_node_signing_keys: None,
             },
         ) {
             Err(msg) if msg.contains("KeyValidationError") => {}
@@ -389,6 +670,9 @@ mod test {
             node_ids[0],
             UpdateNodeDirectlyPayload {
                 idkg_dealing_encryption_pk: Some(vec![1]),
+                committee_signing_pk: None,
+                transport_tls_cert: None,
+                retire_previous_node_signing_keys: None,
             },
         ) {
             Err(msg) if msg.contains("DecodeError") => {}
@@ -467,6 +751,9 @@ mod test {
                 node_ids[0],
                 UpdateNodeDirectlyPayload {
                     idkg_dealing_encryption_pk: Some(protobuf_to_vec(pk2.clone())),
+                    committee_signing_pk: None,
+                    transport_tls_cert: None,
+                    retire_previous_node_signing_keys: None,
                 }
             ),
             Ok(())
@@ -482,6 +769,9 @@ mod test {
                 node_ids[1],
                 UpdateNodeDirectlyPayload {
                     idkg_dealing_encryption_pk: Some(protobuf_to_vec(pk2.clone())),
+                    committee_signing_pk: None,
+                    transport_tls_cert: None,
+                    retire_previous_node_signing_keys: None,
                 }
             ),
             Err("the ECDSA subnet had a key update recently".to_string())
@@ -499,6 +789,9 @@ mod test {
                 node_ids[0],
                 UpdateNodeDirectlyPayload {
                     idkg_dealing_encryption_pk: Some(protobuf_to_vec(pk2.clone())),
+                    committee_signing_pk: None,
+                    transport_tls_cert: None,
+                    retire_previous_node_signing_keys: None,
                 }
             ),
             Err("the key of this node is sufficiently fresh".to_string())
@@ -512,8 +805,310 @@ mod test {
                 node_ids[1],
                 UpdateNodeDirectlyPayload {
                     idkg_dealing_encryption_pk: Some(protobuf_to_vec(pk2.clone())),
+                    committee_signing_pk: None,
+                    transport_tls_cert: None,
+                    retire_previous_node_signing_keys: None,
+                }
+            ),
+            Ok(())
+        );
+    }
+
+    fn registry_with_node(keys: &ValidNodePublicKeys) -> Registry {
+        let mut registry = invariant_compliant_registry(0);
+        registry.maybe_apply_mutation_internal(vec![insert(
+            make_node_record_key(keys.node_id()).as_bytes(),
+            encode_or_panic(&NodeRecord {
+                xnet: Some(connection_endpoint_from_string("128.0.200.1:1234")),
+                http: Some(connection_endpoint_from_string("128.0.200.1:4321")),
+                node_operator_id: PrincipalId::new_user_test_id(999).into_vec(),
+                ..Default::default()
+            }),
+        )]);
+        registry
+    }
+
+    #[test]
+    fn should_rotate_committee_signing_key_and_tls_certificate() {
+        let keys = valid_node_public_keys();
+        let node_id = keys.node_id();
+        let mut registry = registry_with_node(&keys);
+        let payload = UpdateNodeDirectlyPayload {
+            idkg_dealing_encryption_pk: None,
+            committee_signing_pk: Some(protobuf_to_vec(keys.committee_signing_key().clone())),
+            transport_tls_cert: Some(protobuf_to_vec(keys.tls_certificate().clone())),
+            retire_previous_node_signing_keys: None,
+        };
+        let mut now = SystemTime::now();
+
+        assert_eq!(
+            registry.do_update_node(now, node_id, payload.clone()),
+            Ok(())
+        );
+
+        let committee_signing_pk = PublicKey::decode(
+            registry
+                .get(
+                    make_crypto_node_key(node_id, KeyPurpose::CommitteeSigning).as_bytes(),
+                    registry.latest_version(),
+                )
+                .expect("committee signing key not found")
+                .value
+                .as_slice(),
+        )
+        .unwrap();
+        assert_eq!(
+            committee_signing_pk.timestamp,
+            Some(
+                now.duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as u64
+            )
+        );
+        let tls_certificate = X509PublicKeyCert::decode(
+            registry
+                .get(
+                    make_crypto_tls_cert_key(node_id).as_bytes(),
+                    registry.latest_version(),
+                )
+                .expect("TLS certificate not found")
+                .value
+                .as_slice(),
+        )
+        .unwrap();
+        assert_eq!(&tls_certificate, keys.tls_certificate());
+
+        // try to rotate again too early
+        now = now.add(Duration::from_secs(60));
+        assert_eq!(
+            registry.do_update_node(now, node_id, payload.clone()),
+            Err("the committee signing key of this node is sufficiently fresh".to_string())
+        );
+
+        // minimum rotation period passes
+        now = now.add(MIN_NODE_SIGNING_KEYS_ROTATION_PERIOD);
+        assert_eq!(registry.do_update_node(now, node_id, payload), Ok(()));
+    }
+
+    #[test]
+    fn should_fail_to_rotate_committee_signing_key_without_tls_certificate() {
+        let keys = valid_node_public_keys();
+        let node_id = keys.node_id();
+        let mut registry = registry_with_node(&keys);
+
+        assert_eq!(
+            registry.do_update_node(
+                SystemTime::now(),
+                node_id,
+                UpdateNodeDirectlyPayload {
+                    idkg_dealing_encryption_pk: None,
+                    committee_signing_pk: Some(protobuf_to_vec(
+                        keys.committee_signing_key().clone()
+                    )),
+                    transport_tls_cert: None,
+                    retire_previous_node_signing_keys: None,
                 }
             ),
+            Err("committee_signing_pk and transport_tls_cert must be updated together".to_string())
+        );
+    }
+
+    #[test]
+    fn should_fail_to_rotate_tls_certificate_of_another_node() {
+        let keys = valid_node_public_keys();
+        let other_keys = valid_node_public_keys();
+        let node_id = keys.node_id();
+        let mut registry = registry_with_node(&keys);
+
+        let result = registry.do_update_node(
+            SystemTime::now(),
+            node_id,
+            UpdateNodeDirectlyPayload {
+                idkg_dealing_encryption_pk: None,
+                committee_signing_pk: Some(protobuf_to_vec(keys.committee_signing_key().clone())),
+                transport_tls_cert: Some(protobuf_to_vec(other_keys.tls_certificate().clone())),
+                retire_previous_node_signing_keys: None,
+            },
+        );
+
+        assert!(
+            matches!(&result, Err(e) if e.starts_with("certificate validation failed")),
+            "unexpected result: {:?}",
+            result
+        );
+    }
+
+    fn rotation_payload(keys: &ValidNodePublicKeys) -> UpdateNodeDirectlyPayload {
+        UpdateNodeDirectlyPayload {
+            idkg_dealing_encryption_pk: None,
+            committee_signing_pk: Some(protobuf_to_vec(keys.committee_signing_key().clone())),
+            transport_tls_cert: Some(protobuf_to_vec(keys.tls_certificate().clone())),
+            retire_previous_node_signing_keys: None,
+        }
+    }
+
+    fn retirement_payload() -> UpdateNodeDirectlyPayload {
+        UpdateNodeDirectlyPayload {
+            idkg_dealing_encryption_pk: None,
+            committee_signing_pk: None,
+            transport_tls_cert: None,
+            retire_previous_node_signing_keys: Some(true),
+        }
+    }
+
+    #[test]
+    fn should_keep_previous_node_signing_keys_until_retired() {
+        let keys = valid_node_public_keys();
+        let node_id = keys.node_id();
+        let mut registry = registry_with_node(&keys);
+        let previous_tls_cert_key = make_previous_crypto_tls_cert_key(node_id);
+        let previous_committee_signing_pk_key =
+            make_previous_crypto_node_key(node_id, KeyPurpose::CommitteeSigning);
+        let first_rotation = SystemTime::now();
+        assert_eq!(
+            registry.do_update_node(first_rotation, node_id, rotation_payload(&keys)),
+            Ok(())
+        );
+        let rotated_committee_signing_pk = registry
+            .get(
+                make_crypto_node_key(node_id, KeyPurpose::CommitteeSigning).as_bytes(),
+                registry.latest_version(),
+            )
+            .expect("committee signing key not found")
+            .value
+            .clone();
+
+        let second_rotation = first_rotation.add(MIN_NODE_SIGNING_KEYS_ROTATION_PERIOD);
+        assert_eq!(
+            registry.do_update_node(second_rotation, node_id, rotation_payload(&keys)),
+            Ok(())
+        );
+
+        // The keys of the first rotation are kept next to the new ones.
+        assert_eq!(
+            registry
+                .get(
+                    previous_committee_signing_pk_key.as_bytes(),
+                    registry.latest_version()
+                )
+                .map(|record| record.value.clone()),
+            Some(rotated_committee_signing_pk)
+        );
+        assert!(registry
+            .get(previous_tls_cert_key.as_bytes(), registry.latest_version())
+            .is_some());
+
+        // The previous keys cannot be retired during the transition period.
+        assert_eq!(
+            registry.do_update_node(
+                second_rotation.add(Duration::from_secs(60)),
+                node_id,
+                retirement_payload()
+            ),
+            Err(
+                "the committee signing key and TLS certificate of this node are still in transition"
+                    .to_string()
+            )
+        );
+
+        // The node cannot rotate again before the previous keys are retired.
+        let third_rotation = second_rotation.add(MIN_NODE_SIGNING_KEYS_ROTATION_PERIOD);
+        assert_eq!(
+            registry.do_update_node(third_rotation, node_id, rotation_payload(&keys)),
+            Err(
+                "the previous committee signing key and TLS certificate of this node have not been retired yet"
+                    .to_string()
+            )
+        );
+
+        assert_eq!(
+            registry.do_update_node(
+                second_rotation.add(NODE_SIGNING_KEYS_TRANSITION_PERIOD),
+                node_id,
+                retirement_payload()
+            ),
+            Ok(())
+        );
+        for key in [&previous_committee_signing_pk_key, &previous_tls_cert_key] {
+            assert!(registry
+                .get(key.as_bytes(), registry.latest_version())
+                .is_none());
+        }
+        assert_eq!(
+            registry.do_update_node(
+                second_rotation.add(NODE_SIGNING_KEYS_TRANSITION_PERIOD),
+                node_id,
+                retirement_payload()
+            ),
+            Err(
+                "this node has no previous committee signing key and TLS certificate to retire"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            registry.do_update_node(third_rotation, node_id, rotation_payload(&keys)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn should_limit_node_signing_keys_rotations_on_subnet() {
+        let keys: Vec<_> = (0..2).map(|_| valid_node_public_keys()).collect();
+        let node_ids: Vec<_> = keys.iter().map(|keys| keys.node_id()).collect();
+        let mut registry = invariant_compliant_registry(0);
+        for (i, keys) in keys.iter().enumerate() {
+            registry.maybe_apply_mutation_internal(vec![
+                insert(
+                    make_crypto_node_key(keys.node_id(), KeyPurpose::DkgDealingEncryption)
+                        .as_bytes(),
+                    encode_or_panic(keys.dkg_dealing_encryption_key()),
+                ),
+                insert(
+                    make_node_record_key(keys.node_id()).as_bytes(),
+                    encode_or_panic(&NodeRecord {
+                        xnet: Some(connection_endpoint_from_string(&format!(
+                            "128.0.{}.1:1234",
+                            200 + i
+                        ))),
+                        http: Some(connection_endpoint_from_string(&format!(
+                            "128.0.{}.1:4321",
+                            200 + i
+                        ))),
+                        node_operator_id: PrincipalId::new_user_test_id(999).into_vec(),
+                        ..Default::default()
+                    }),
+                ),
+            ]);
+        }
+        let mut subnet_list_record = registry.get_subnet_list_record();
+        registry.maybe_apply_mutation_internal(add_fake_subnet(
+            subnet_test_id(1000),
+            &mut subnet_list_record,
+            get_invariant_compliant_subnet_record(node_ids.clone()),
+        ));
+
+        let now = SystemTime::now();
+        assert_eq!(
+            registry.do_update_node(now, node_ids[0], rotation_payload(&keys[0])),
+            Ok(())
+        );
+
+        // With two nodes on the subnet, the second one has to wait for half of the rotation
+        // period (minus the delay compensation).
+        assert_eq!(
+            registry.do_update_node(
+                now.add(Duration::from_secs(60)),
+                node_ids[1],
+                rotation_payload(&keys[1])
+            ),
+            Err("the subnet had a committee signing key rotation recently".to_string())
+        );
+        assert_eq!(
+            registry.do_update_node(
+                now.add(MIN_NODE_SIGNING_KEYS_ROTATION_PERIOD / 2),
+                node_ids[1],
+                rotation_payload(&keys[1])
+            ),
             Ok(())
         );
     }
@@ -421,6 +421,7 @@ mod tests {
                 http_requests: false,
                 sev_status: None,
                 onchain_observability: None,
+                quic_transport: None,
             }),
             ecdsa_config: Some(EcdsaConfig {
                 quadruples_to_create_in_advance: 10,
@@ -541,6 +542,7 @@ mod tests {
                 http_requests: false,
                 sev_status: None,
                 onchain_observability: None,
+                quic_transport: None,
             }),
             ecdsa_config: Some(EcdsaConfig {
                 quadruples_to_create_in_advance: 10,
@@ -590,6 +592,7 @@ mod tests {
                         http_requests: false,
                         sev_status: None,
                         onchain_observability: None,
+                        quic_transport: None,
                     }
                     .into()
                 ),
@@ -1164,6 +1167,7 @@ mod tests {
             http_requests: false,
             sev_status: Some(SevFeatureStatus::SecureEnabled),
             onchain_observability: None,
+            quic_transport: None,
         });
 
         // Should panic because we are changing SubnetFeatures
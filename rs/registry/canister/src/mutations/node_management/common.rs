@@ -7,8 +7,8 @@ use ic_protobuf::registry::{
 };
 use ic_registry_keys::{
     make_crypto_node_key, make_crypto_tls_cert_key, make_firewall_rules_record_key,
-    make_node_operator_record_key, make_node_record_key, make_subnet_list_record_key,
-    FirewallRulesScope,
+    make_node_operator_record_key, make_node_record_key, make_previous_crypto_node_key,
+    make_previous_crypto_tls_cert_key, make_subnet_list_record_key, FirewallRulesScope,
 };
 use ic_registry_transport::pb::v1::{RegistryMutation, RegistryValue};
 use ic_registry_transport::{delete, insert, update};
@@ -162,6 +162,9 @@ pub fn make_remove_node_registry_mutations(
     let dkg_dealing_key = make_crypto_node_key(node_id, KeyPurpose::DkgDealingEncryption);
     let tls_cert_key = make_crypto_tls_cert_key(node_id);
     let idkg_dealing_key = make_crypto_node_key(node_id, KeyPurpose::IDkgMEGaEncryption);
+    let previous_committee_signing_key =
+        make_previous_crypto_node_key(node_id, KeyPurpose::CommitteeSigning);
+    let previous_tls_cert_key = make_previous_crypto_tls_cert_key(node_id);
     let firewall_ruleset_key = make_firewall_rules_record_key(&FirewallRulesScope::Node(node_id));
 
     let keys_to_maybe_remove = vec![
@@ -171,6 +174,8 @@ pub fn make_remove_node_registry_mutations(
         dkg_dealing_key,
         tls_cert_key,
         idkg_dealing_key,
+        previous_committee_signing_key,
+        previous_tls_cert_key,
         firewall_ruleset_key,
     ];
 
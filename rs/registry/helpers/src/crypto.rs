@@ -10,7 +10,7 @@ use ic_protobuf::registry::{
 use ic_registry_keys::make_crypto_node_key;
 use ic_registry_keys::{
     make_catch_up_package_contents_key, make_crypto_threshold_signing_pubkey_key,
-    make_crypto_tls_cert_key,
+    make_crypto_tls_cert_key, make_previous_crypto_tls_cert_key,
 };
 use ic_types::crypto::threshold_sig::{
     ni_dkg::{
@@ -52,6 +52,14 @@ pub trait CryptoRegistry {
         version: RegistryVersion,
     ) -> RegistryClientResult<X509PublicKeyCert>;
 
+    /// Returns the TLS certificate that the node used before its latest
+    /// rotation, if that certificate has not been retired yet.
+    fn get_previous_tls_certificate(
+        &self,
+        node_id: NodeId,
+        version: RegistryVersion,
+    ) -> RegistryClientResult<X509PublicKeyCert>;
+
     /// Returns initial DKG key material for the subnet and the registry
     /// version, at which this key material was inserted. This registry
     /// version will be used in the genesis summary.
@@ -105,6 +113,15 @@ impl<T: RegistryClient + ?Sized> CryptoRegistry for T {
         deserialize_registry_value::<X509PublicKeyCert>(bytes)
     }
 
+    fn get_previous_tls_certificate(
+        &self,
+        node_id: NodeId,
+        version: RegistryVersion,
+    ) -> RegistryClientResult<X509PublicKeyCert> {
+        let bytes = self.get_value(&make_previous_crypto_tls_cert_key(node_id), version);
+        deserialize_registry_value::<X509PublicKeyCert>(bytes)
+    }
+
     fn get_initial_dkg_transcripts(
         &self,
         subnet_id: SubnetId,
@@ -29,6 +29,8 @@ pub const HOSTOS_VERSION_KEY_PREFIX: &str = "hostos_version_";
 pub const SUBNET_RECORD_KEY_PREFIX: &str = "subnet_record_";
 pub const CRYPTO_RECORD_KEY_PREFIX: &str = "crypto_record_";
 pub const CRYPTO_TLS_CERT_KEY_PREFIX: &str = "crypto_tls_cert_";
+pub const PREVIOUS_CRYPTO_RECORD_KEY_PREFIX: &str = "previous_crypto_record_";
+pub const PREVIOUS_CRYPTO_TLS_CERT_KEY_PREFIX: &str = "previous_crypto_tls_cert_";
 pub const CRYPTO_THRESHOLD_SIGNING_KEY_PREFIX: &str = "crypto_threshold_signing_public_key_";
 pub const DATA_CENTER_KEY_PREFIX: &str = "data_center_record_";
 pub const ECDSA_SIGNING_SUBNET_LIST_KEY_PREFIX: &str = "key_id_";
@@ -216,6 +218,12 @@ pub fn maybe_parse_crypto_tls_cert_key(key: &str) -> Option<NodeId> {
     }
 }
 
+/// Makes a key for the registry entry holding the TLS certificate that a node
+/// used before its latest rotation, until that certificate is retired.
+pub fn make_previous_crypto_tls_cert_key(node_id: NodeId) -> String {
+    format!("{}{}", PREVIOUS_CRYPTO_TLS_CERT_KEY_PREFIX, node_id.get())
+}
+
 /// Makes a key for a NodeRecord registry entry.
 pub fn make_node_record_key(node_id: NodeId) -> String {
     format!("{}{}", NODE_RECORD_KEY_PREFIX, node_id.get())
@@ -277,6 +285,17 @@ pub fn make_crypto_node_key(node_id: NodeId, key_purpose: KeyPurpose) -> String
     )
 }
 
+/// Makes a key for the registry entry holding the crypto key that a node used
+/// for `key_purpose` before its latest rotation, until that key is retired.
+pub fn make_previous_crypto_node_key(node_id: NodeId, key_purpose: KeyPurpose) -> String {
+    format!(
+        "{}{}_{}",
+        PREVIOUS_CRYPTO_RECORD_KEY_PREFIX,
+        node_id.get(),
+        key_purpose as usize
+    )
+}
+
 // If `key` starts with `CRYPTO_RECORD_KEY_PREFIX`, tries to parse it to get
 // NodeId and KeyPurpose. If parsing is successful, returns Some((node_id,
 // key_purpose)), otherwise returns None.
@@ -22,12 +22,20 @@ pub struct SubnetFeatures {
     // This feature flag controls whether the onchain observability adapter systemd process collects data
     // and sends it to the onchain observability canister.
     pub onchain_observability: Option<bool>,
+
+    /// This feature flag controls whether the nodes of this subnet talk to
+    /// each other over QUIC instead of TLS-on-TCP.
+    pub quic_transport: Option<bool>,
 }
 
 impl SubnetFeatures {
     pub fn sev_status(&self) -> SevFeatureStatus {
         self.sev_status.unwrap_or(SevFeatureStatus::Disabled)
     }
+
+    pub fn quic_transport(&self) -> bool {
+        self.quic_transport.unwrap_or(false)
+    }
 }
 
 impl From<SubnetFeatures> for pb::SubnetFeatures {
@@ -43,6 +51,7 @@ impl From<SubnetFeatures> for pb::SubnetFeatures {
                 SevFeatureStatus::SecureEnabled => 4,
             }),
             onchain_observability: features.onchain_observability,
+            quic_transport: features.quic_transport,
         }
     }
 }
@@ -60,6 +69,7 @@ impl From<pb::SubnetFeatures> for SubnetFeatures {
                 _ => SevFeatureStatus::Disabled,
             }),
             onchain_observability: features.onchain_observability,
+            quic_transport: features.quic_transport,
         }
     }
 }
@@ -80,6 +90,7 @@ impl FromStr for SubnetFeatures {
                 "canister_sandboxing" => features.canister_sandboxing = true,
                 "http_requests" => features.http_requests = true,
                 "onchain_observability" => features.onchain_observability = Some(true),
+                "quic_transport" => features.quic_transport = Some(true),
                 _ => return Err(format!("Unknown feature {:?} in {:?}", feature, string)),
             }
         }
@@ -14,6 +14,7 @@ use ic_artifact_pool::{
     ecdsa_pool::EcdsaPoolImpl,
     ensure_persistent_pool_replica_version_compatibility,
     ingress_pool::{IngressPoolImpl, IngressPrioritizer},
+    pool_migration::migrate_pool_if_needed,
 };
 use ic_config::{
     artifact_pool::ArtifactPoolConfig, consensus::ConsensusConfig, transport::TransportConfig,
@@ -26,7 +27,7 @@ use ic_consensus::{
 use ic_consensus_utils::{
     crypto::ConsensusCrypto, membership::Membership, pool_reader::PoolReader,
 };
-use ic_crypto_tls_interfaces::{TlsHandshake, TlsStream};
+use ic_crypto_tls_interfaces::{TlsConfig, TlsHandshake, TlsStream};
 use ic_cycles_account_manager::CyclesAccountManager;
 use ic_https_outcalls_consensus::{
     gossip::CanisterHttpGossipImpl, payload_builder::CanisterHttpPayloadBuilderImpl,
@@ -55,7 +56,7 @@ use ic_protobuf::types::v1 as pb;
 use ic_registry_client_helpers::subnet::SubnetRegistry;
 use ic_replicated_state::ReplicatedState;
 use ic_state_manager::state_sync::{StateSync, StateSyncArtifact};
-use ic_transport::transport::create_transport;
+use ic_transport::transport::{create_quic_transport, create_transport};
 use ic_types::{
     artifact::{Advert, ArtifactKind, ArtifactTag, FileTreeSyncAttribute},
     artifact_kind::{
@@ -120,6 +121,7 @@ pub fn create_networking_stack(
     // constructs it from the 'transport_config'.
     transport: Option<Arc<dyn Transport>>,
     tls_handshake: Arc<dyn TlsHandshake + Send + Sync>,
+    tls_config: Arc<dyn TlsConfig + Send + Sync>,
     sev_handshake: Arc<dyn ValidateAttestedStream<Box<dyn TlsStream>> + Send + Sync>,
     state_manager: Arc<dyn StateManager<State = ReplicatedState>>,
     state_reader: Arc<dyn StateReader<State = ReplicatedState>>,
@@ -176,8 +178,16 @@ pub fn create_networking_stack(
     );
     let artifact_manager = artifact_manager.unwrap();
 
-    let transport = transport.unwrap_or_else(|| {
-        create_transport(
+    // All nodes of a subnet must use the same protocol, so it is taken from the
+    // subnet record rather than from the local config.
+    let quic_transport = registry_client
+        .get_features(subnet_id, registry_client.get_latest_version())
+        .ok()
+        .flatten()
+        .map(|features| features.quic_transport())
+        .unwrap_or(false);
+    let transport = transport.unwrap_or_else(|| match quic_transport {
+        false => create_transport(
             node_id,
             transport_config.clone(),
             registry_client.get_latest_version(),
@@ -188,7 +198,18 @@ pub fn create_networking_stack(
             rt_handle.clone(),
             log.clone(),
             false,
-        )
+        ),
+        true => create_quic_transport(
+            node_id,
+            transport_config.clone(),
+            registry_client.get_latest_version(),
+            oldest_registry_version_in_use,
+            metrics_registry.clone(),
+            tls_config,
+            sev_handshake,
+            rt_handle.clone(),
+            log.clone(),
+        ),
     });
 
     let ingress_event_handler = {
@@ -536,6 +557,11 @@ pub fn init_artifact_pools(
     cup: CatchUpPackage,
 ) -> ArtifactPools {
     ensure_persistent_pool_replica_version_compatibility(config.persistent_pool_db_path());
+    // If the node was switched to a different persistent pool backend, carry
+    // over the existing artifacts instead of starting from an empty pool.
+    if let Err(err) = migrate_pool_if_needed(&config, &log) {
+        panic!("Failed to migrate the persistent pool: {}", err);
+    }
 
     let ingress_pool = Arc::new(RwLock::new(IngressPoolImpl::new(
         node_id,
@@ -607,6 +633,7 @@ impl ArtifactKind for TestArtifact {
             size: 0,
             id: msg.id.clone(),
             integrity_hash: CryptoHash(msg.id.clone().into_bytes()),
+            chunk_tree_root: None,
         }
     }
 }
@@ -230,6 +230,7 @@ pub fn construct_ic_stack(
         subnet_id,
         None,
         Arc::clone(&crypto) as Arc<_>,
+        Arc::clone(&crypto) as Arc<_>,
         sev_handshake,
         Arc::clone(&state_manager) as Arc<_>,
         Arc::clone(&state_manager) as Arc<_>,
@@ -1,11 +1,11 @@
 use assert_matches::assert_matches;
 use candid::Encode;
-use ic_config::Config;
+use ic_config::{execution_environment::DEFAULT_RESERVED_BALANCE_LIMIT, Config};
 use ic_error_types::{ErrorCode, RejectCode};
 use ic_ic00_types::{
     self as ic00, CanisterIdRecord, CanisterInstallMode, CanisterSettingsArgsBuilder,
-    CanisterStatusResultV2, CanisterStatusType, EmptyBlob, InstallCodeArgs, Method, Payload,
-    UpdateSettingsArgs, IC_00,
+    CanisterStatusResultV2, CanisterStatusType, EmptyBlob, InstallCodeArgs, LogVisibility, Method,
+    Payload, UpdateSettingsArgs, IC_00,
 };
 use ic_registry_provisional_whitelist::ProvisionalWhitelist;
 use ic_replica_tests as utils;
@@ -710,6 +710,10 @@ fn can_get_canister_information() {
                 None,
                 2592000,
                 0u128,
+                0u128,
+                DEFAULT_RESERVED_BALANCE_LIMIT.get(),
+                None,
+                LogVisibility::Controllers,
             )
         );
 
@@ -761,6 +765,10 @@ fn can_get_canister_information() {
                     None,
                     259200,
                     0u128,
+                    0u128,
+                    DEFAULT_RESERVED_BALANCE_LIMIT.get(),
+                    None,
+                    LogVisibility::Controllers,
                 ),
                 CanisterStatusResultV2::decode(&res).unwrap(),
                 2 * BALANCE_EPSILON,
@@ -15,7 +15,7 @@ use ic_protobuf::{
 };
 use ic_types::{
     messages::{
-        Ingress, Payload, RejectContext, Request, RequestOrResponse, Response,
+        CallbackId, Ingress, Payload, RejectContext, Request, RequestOrResponse, Response,
         MAX_RESPONSE_COUNT_BYTES,
     },
     xnet::{QueueId, SessionId},
@@ -23,7 +23,7 @@ use ic_types::{
 };
 use queue::{IngressQueue, InputQueue, OutputQueue};
 use std::{
-    collections::{BTreeMap, HashSet, VecDeque},
+    collections::{BTreeMap, BTreeSet, HashSet, VecDeque},
     convert::{From, TryFrom},
     ops::{AddAssign, SubAssign},
     sync::Arc,
@@ -116,6 +116,19 @@ pub struct CanisterQueues {
 
     /// Round-robin across ingress and cross-net input queues for pop_input().
     next_input_queue: NextInputQueue,
+
+    /// Outstanding best-effort response calls (i.e. requests with a deadline),
+    /// mapping the callback ID to the respondent and the call deadline.
+    best_effort_callbacks: BTreeMap<CallbackId, (CanisterId, Time)>,
+
+    /// Priority queue of `best_effort_callbacks` ordered by deadline. Not
+    /// persisted, reconstructed from `best_effort_callbacks` on decode.
+    best_effort_deadlines: BTreeSet<(Time, CallbackId)>,
+
+    /// Best-effort callbacks that were already resolved with a synthetic
+    /// `SYS_UNKNOWN` reject. Any late response for one of these callbacks is
+    /// silently dropped.
+    timed_out_callbacks: BTreeSet<CallbackId>,
 }
 
 /// Circular iterator that consumes output queue messages: loops over output
@@ -342,6 +355,15 @@ impl CanisterQueues {
         msg: RequestOrResponse,
         input_queue_type: InputQueueType,
     ) -> Result<(), (StateError, RequestOrResponse)> {
+        if let RequestOrResponse::Response(response) = &msg {
+            // The callback was already resolved with a synthetic reject, drop the
+            // late response. Callers that hold the canister's balance are expected
+            // to have taken the refund via `take_late_response_refund()` first.
+            if self.take_late_response_refund(response).is_some() {
+                return Ok(());
+            }
+        }
+
         let sender = msg.sender();
         let input_queue = match msg {
             RequestOrResponse::Request(_) => {
@@ -364,6 +386,10 @@ impl CanisterQueues {
         let iq_stats_delta = InputQueuesStats::stats_delta(QueueOp::Push, &msg);
         let mu_stats_delta = MemoryUsageStats::stats_delta(QueueOp::Push, &msg);
 
+        let best_effort_callback = match &msg {
+            RequestOrResponse::Response(response) => Some(response.originator_reply_callback),
+            RequestOrResponse::Request(_) => None,
+        };
         input_queue.push(msg)?;
 
         // Add sender canister ID to the input schedule queue if it isn't already there.
@@ -378,11 +404,31 @@ impl CanisterQueues {
 
         self.input_queues_stats += iq_stats_delta;
         self.memory_usage_stats += mu_stats_delta;
+        if let Some(callback_id) = best_effort_callback {
+            self.remove_best_effort_callback(callback_id);
+        }
         debug_assert!(self.stats_ok());
 
         Ok(())
     }
 
+    /// Drops `response` if it is a late response for a best-effort callback that
+    /// was already resolved with a synthetic reject, returning the refund it
+    /// carries so that it can be credited to the canister.
+    ///
+    /// Returns `None` if the callback did not time out, in which case the
+    /// response should be pushed as usual.
+    pub(super) fn take_late_response_refund(&mut self, response: &Response) -> Option<Cycles> {
+        if self
+            .timed_out_callbacks
+            .remove(&response.originator_reply_callback)
+        {
+            Some(response.refund)
+        } else {
+            None
+        }
+    }
+
     /// Pops the next canister-to-canister message from `input_queues`.
     ///
     /// Note: We pop senders from the head of `input_schedule` and insert them
@@ -582,11 +628,20 @@ impl CanisterQueues {
         let mu_stats_delta = MemoryUsageStats::request_stats_delta(QueueOp::Push, &msg);
         let oq_stats_delta =
             OutputQueuesStats::stats_delta(&RequestOrResponse::Request(msg.clone()));
+        let best_effort_callback = msg
+            .deadline
+            .map(|deadline| (msg.sender_reply_callback, msg.receiver, deadline));
 
         output_queue
             .push_request(msg, time + REQUEST_LIFETIME)
             .expect("cannot fail due to the checks above");
 
+        if let Some((callback_id, respondent, deadline)) = best_effort_callback {
+            self.best_effort_callbacks
+                .insert(callback_id, (respondent, deadline));
+            self.best_effort_deadlines.insert((deadline, callback_id));
+        }
+
         self.input_queues_stats.reserved_slots += 1;
         self.output_queues_stats += oq_stats_delta;
         self.memory_usage_stats += mu_stats_delta;
@@ -687,7 +742,13 @@ impl CanisterQueues {
     /// Tries to induct a message from the output queue to `own_canister_id`
     /// into the input queue from `own_canister_id`. Returns `Err(())` if there
     /// was no message to induct or the input queue was full.
-    pub(super) fn induct_message_to_self(&mut self, own_canister_id: CanisterId) -> Result<(), ()> {
+    ///
+    /// A late response for a timed out callback is dropped instead, and its
+    /// refund is returned for the caller to credit.
+    pub(super) fn induct_message_to_self(
+        &mut self,
+        own_canister_id: CanisterId,
+    ) -> Result<Option<Cycles>, ()> {
         let msg = self
             .canister_queues
             .get(&own_canister_id)
@@ -695,8 +756,14 @@ impl CanisterQueues {
             .ok_or(())?
             .clone();
 
-        self.push_input(msg, InputQueueType::LocalSubnet)
-            .map_err(|_| ())?;
+        let refund = match &msg {
+            RequestOrResponse::Response(response) => self.take_late_response_refund(response),
+            RequestOrResponse::Request(_) => None,
+        };
+        if refund.is_none() {
+            self.push_input(msg, InputQueueType::LocalSubnet)
+                .map_err(|_| ())?;
+        }
 
         let msg = self
             .canister_queues
@@ -710,7 +777,7 @@ impl CanisterQueues {
         self.memory_usage_stats -= MemoryUsageStats::stats_delta(QueueOp::Pop, &msg);
         debug_assert!(self.stats_ok());
 
-        Ok(())
+        Ok(refund)
     }
 
     /// Returns the number of enqueued ingress messages.
@@ -832,7 +899,10 @@ impl CanisterQueues {
         // Reset all fields to default if we have no messages. This is so that an empty
         // `CanisterQueues` serializes as an empty byte array (and there is no need to
         // persist it explicitly).
-        if self.canister_queues.is_empty() && self.ingress_queue.is_empty() {
+        if self.canister_queues.is_empty()
+            && self.ingress_queue.is_empty()
+            && self.timed_out_callbacks.is_empty()
+        {
             // The schedules and stats will already have default (zero) values, only
             // `next_input_queue` must be reset explicitly.
             self.next_input_queue = Default::default();
@@ -1011,23 +1081,42 @@ impl CanisterQueues {
     /// previously empty queue also requires the full set of local canisters to decide whether
     /// the destination canister was local or remote.
     ///
-    /// Returns the number of requests that were timed out.
+    /// If the request's callback was already timed out, no reject response is
+    /// enqueued and the cycles attached to the request are returned instead, to be
+    /// credited back to the canister's balance.
+    ///
+    /// Returns the number of requests that were timed out and the cycles to refund.
     pub fn time_out_requests(
         &mut self,
         current_time: Time,
         own_canister_id: &CanisterId,
         local_canisters: &BTreeMap<CanisterId, CanisterState>,
-    ) -> u64 {
+    ) -> (u64, Cycles) {
         let mut timed_out_requests_count = 0;
+        let mut cycles_to_refund = Cycles::zero();
         for (canister_id, (input_queue, output_queue)) in self.canister_queues.iter_mut() {
             for request in output_queue.time_out_requests(current_time) {
                 let response = generate_timeout_response(&request);
+                let callback_id = request.sender_reply_callback;
+                let payment = request.payment;
 
                 // Request was dropped, update stats.
                 let request = RequestOrResponse::Request(request);
                 self.memory_usage_stats -= MemoryUsageStats::stats_delta(QueueOp::Pop, &request);
                 self.output_queues_stats -= OutputQueuesStats::stats_delta(&request);
 
+                // The callback was already resolved with a synthetic reject, whose
+                // reserved slot was already consumed: nothing left to enqueue. The
+                // request never left the canister, so its payment is refunded.
+                if self.timed_out_callbacks.remove(&callback_id) {
+                    cycles_to_refund += payment;
+                    timed_out_requests_count += 1;
+                    continue;
+                }
+                if let Some((_, deadline)) = self.best_effort_callbacks.remove(&callback_id) {
+                    self.best_effort_deadlines.remove(&(deadline, callback_id));
+                }
+
                 // Push response, update stats.
                 let iq_stats_delta = InputQueuesStats::stats_delta(QueueOp::Push, &response);
                 let mu_stats_delta = MemoryUsageStats::stats_delta(QueueOp::Push, &response);
@@ -1051,7 +1140,83 @@ impl CanisterQueues {
         debug_assert!(self.stats_ok());
         debug_assert!(self.schedules_ok(own_canister_id, local_canisters));
 
-        timed_out_requests_count
+        (timed_out_requests_count, cycles_to_refund)
+    }
+
+    /// Queries whether any best-effort response call has an expired deadline.
+    pub fn has_expired_callback_deadlines(&self, current_time: Time) -> bool {
+        match self.best_effort_deadlines.iter().next() {
+            Some((deadline, _)) => *deadline <= current_time,
+            None => false,
+        }
+    }
+
+    /// Times out best-effort response calls whose deadline has expired, given a
+    /// current time, by enqueuing a `SYS_UNKNOWN` reject response into the slot
+    /// reserved on the matching `InputQueue`. Any late response for a timed out
+    /// callback is subsequently dropped.
+    ///
+    /// Updating the correct input queues schedule after enqueuing a reject response into a
+    /// previously empty queue also requires the full set of local canisters to decide whether
+    /// the respondent canister was local or remote.
+    ///
+    /// Returns the number of callbacks that were timed out.
+    pub fn time_out_callbacks(
+        &mut self,
+        current_time: Time,
+        own_canister_id: &CanisterId,
+        local_canisters: &BTreeMap<CanisterId, CanisterState>,
+    ) -> u64 {
+        let mut timed_out_callbacks_count = 0;
+        while let Some(&(deadline, callback_id)) = self.best_effort_deadlines.iter().next() {
+            if deadline > current_time {
+                break;
+            }
+            self.best_effort_deadlines.remove(&(deadline, callback_id));
+            let (respondent, _) = self
+                .best_effort_callbacks
+                .remove(&callback_id)
+                .expect("best-effort deadline without matching callback");
+
+            let response =
+                generate_callback_timeout_response(*own_canister_id, respondent, callback_id);
+            let (input_queue, _) = self
+                .canister_queues
+                .get_mut(&respondent)
+                .expect("no queues for outstanding best-effort call");
+
+            // Push response into the reserved slot, update stats.
+            let iq_stats_delta = InputQueuesStats::stats_delta(QueueOp::Push, &response);
+            let mu_stats_delta = MemoryUsageStats::stats_delta(QueueOp::Push, &response);
+            input_queue.push(response).unwrap();
+            self.input_queues_stats += iq_stats_delta;
+            self.memory_usage_stats += mu_stats_delta;
+
+            // If this was a previously empty input queue, add it to input queue schedule.
+            if input_queue.num_messages() == 1 {
+                if &respondent == own_canister_id || local_canisters.contains_key(&respondent) {
+                    self.local_subnet_input_schedule.push_back(respondent);
+                } else {
+                    self.remote_subnet_input_schedule.push_back(respondent);
+                }
+            }
+
+            self.timed_out_callbacks.insert(callback_id);
+            timed_out_callbacks_count += 1;
+        }
+
+        debug_assert!(self.stats_ok());
+        debug_assert!(self.schedules_ok(own_canister_id, local_canisters));
+
+        timed_out_callbacks_count
+    }
+
+    /// Stops tracking the given best-effort callback (if it is one), e.g.
+    /// because a response for it was enqueued.
+    fn remove_best_effort_callback(&mut self, callback_id: CallbackId) {
+        if let Some((_, deadline)) = self.best_effort_callbacks.remove(&callback_id) {
+            self.best_effort_deadlines.remove(&(deadline, callback_id));
+        }
     }
 
     /// Re-partitions `self.local_subnet_input_schedule` and
@@ -1113,6 +1278,27 @@ fn generate_timeout_response(request: &Arc<Request>) -> RequestOrResponse {
     }))
 }
 
+/// Generates a `SYS_UNKNOWN` reject response for a best-effort response call
+/// whose deadline expired. Nothing is refunded, as the outcome of the call is
+/// unknown.
+fn generate_callback_timeout_response(
+    originator: CanisterId,
+    respondent: CanisterId,
+    callback_id: CallbackId,
+) -> RequestOrResponse {
+    RequestOrResponse::Response(Arc::new(Response {
+        originator,
+        respondent,
+        originator_reply_callback: callback_id,
+        refund: Cycles::zero(),
+        response_payload: Payload::Reject(RejectContext::new_with_message_length_limit(
+            RejectCode::SysUnknown,
+            "Call deadline has expired.".to_string(),
+            MR_SYNTHETIC_REJECT_MESSAGE_MAX_LEN,
+        )),
+    }))
+}
+
 impl From<&CanisterQueues> for pb_queues::CanisterQueues {
     fn from(item: &CanisterQueues) -> Self {
         Self {
@@ -1152,6 +1338,22 @@ impl From<&CanisterQueues> for pb_queues::CanisterQueues {
                 .iter()
                 .map(|canid| pb_types::CanisterId::from(*canid))
                 .collect(),
+            best_effort_callbacks: item
+                .best_effort_callbacks
+                .iter()
+                .map(
+                    |(callback_id, (respondent, deadline))| pb_queues::BestEffortCallback {
+                        callback_id: callback_id.get(),
+                        respondent: Some(pb_types::CanisterId::from(*respondent)),
+                        deadline: deadline.as_nanos_since_unix_epoch(),
+                    },
+                )
+                .collect(),
+            timed_out_callbacks: item
+                .timed_out_callbacks
+                .iter()
+                .map(|callback_id| callback_id.get())
+                .collect(),
         }
     }
 }
@@ -1213,6 +1415,24 @@ impl TryFrom<pb_queues::CanisterQueues> for CanisterQueues {
             remote_subnet_input_schedule.push_back(c);
         }
 
+        let mut best_effort_callbacks = BTreeMap::new();
+        let mut best_effort_deadlines = BTreeSet::new();
+        for callback in item.best_effort_callbacks.into_iter() {
+            let callback_id = CallbackId::from(callback.callback_id);
+            let respondent = try_from_option_field(
+                callback.respondent,
+                "CanisterQueues::best_effort_callbacks::respondent",
+            )?;
+            let deadline = Time::from_nanos_since_unix_epoch(callback.deadline);
+            best_effort_callbacks.insert(callback_id, (respondent, deadline));
+            best_effort_deadlines.insert((deadline, callback_id));
+        }
+        let timed_out_callbacks = item
+            .timed_out_callbacks
+            .into_iter()
+            .map(CallbackId::from)
+            .collect();
+
         Ok(Self {
             ingress_queue: IngressQueue::try_from(item.ingress_queue)?,
             canister_queues,
@@ -1222,6 +1442,9 @@ impl TryFrom<pb_queues::CanisterQueues> for CanisterQueues {
             next_input_queue,
             local_subnet_input_schedule,
             remote_subnet_input_schedule,
+            best_effort_callbacks,
+            best_effort_deadlines,
+            timed_out_callbacks,
         })
     }
 }
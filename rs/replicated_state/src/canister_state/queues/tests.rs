@@ -104,11 +104,13 @@ impl CanisterQueuesFixture {
                 CanisterState::new(system_state, None, scheduler_state)
             }
         };
-        self.queues.time_out_requests(
-            Time::from_nanos_since_unix_epoch(u64::MAX),
-            &self.this,
-            &local_canisters,
-        )
+        self.queues
+            .time_out_requests(
+                Time::from_nanos_since_unix_epoch(u64::MAX),
+                &self.this,
+                &local_canisters,
+            )
+            .0
     }
 
     fn available_output_request_slots(&self) -> usize {
@@ -1809,6 +1811,7 @@ fn time_out_requests_pushes_correct_reject_responses() {
                     payment: Cycles::from(cycles as u64),
                     method_name: "No-Op".to_string(),
                     method_payload: vec![],
+                    deadline: None,
                 }),
                 deadline,
             )
@@ -1830,7 +1833,7 @@ fn time_out_requests_pushes_correct_reject_responses() {
 
     let current_time = deadline1 + REQUEST_LIFETIME;
     assert_eq!(
-        3,
+        (3, Cycles::zero()),
         canister_queues.time_out_requests(current_time, &own_canister_id, &local_canisters),
     );
 
@@ -1882,7 +1885,7 @@ fn time_out_requests_pushes_correct_reject_responses() {
 
     let current_time = deadline2 + REQUEST_LIFETIME;
     assert_eq!(
-        1,
+        (1, Cycles::zero()),
         canister_queues.time_out_requests(current_time, &own_canister_id, &local_canisters),
     );
 
@@ -1898,3 +1901,212 @@ fn time_out_requests_pushes_correct_reject_responses() {
         VecDeque::from(vec![remote_canister_id]),
     );
 }
+
+/// Tests that `time_out_callbacks` resolves expired best-effort calls with a
+/// `SYS_UNKNOWN` reject response and that late responses are dropped.
+#[test]
+fn time_out_callbacks_pushes_sys_unknown_reject_and_drops_late_response() {
+    let mut canister_queues = CanisterQueues::default();
+
+    let own_canister_id = canister_test_id(67);
+    let remote_canister_id = canister_test_id(97);
+    let deadline = Time::from_nanos_since_unix_epoch(1000);
+
+    for (callback_id, deadline) in [(1, Some(deadline)), (2, None)] {
+        let mut builder = RequestBuilder::default()
+            .sender(own_canister_id)
+            .receiver(remote_canister_id)
+            .sender_reply_callback(CallbackId::from(callback_id))
+            .payment(Cycles::new(7));
+        if let Some(deadline) = deadline {
+            builder = builder.deadline(deadline);
+        }
+        canister_queues
+            .push_output_request(builder.build().into(), mock_time())
+            .unwrap();
+    }
+    let local_canisters = BTreeMap::new();
+
+    // Nothing times out before the deadline.
+    assert!(!canister_queues.has_expired_callback_deadlines(deadline - Duration::from_nanos(1)));
+    assert_eq!(
+        0,
+        canister_queues.time_out_callbacks(
+            deadline - Duration::from_nanos(1),
+            &own_canister_id,
+            &local_canisters
+        )
+    );
+
+    assert!(canister_queues.has_expired_callback_deadlines(deadline));
+    assert_eq!(
+        1,
+        canister_queues.time_out_callbacks(deadline, &own_canister_id, &local_canisters)
+    );
+    assert!(!canister_queues.has_expired_callback_deadlines(deadline));
+
+    // The request itself is still enqueued, a reject response is in the input queue.
+    assert_eq!(2, canister_queues.output_queues_message_count());
+    assert_eq!(1, canister_queues.input_queues_message_count());
+    assert_eq!(1, canister_queues.input_queues_reservation_count());
+    assert_eq!(
+        VecDeque::from(vec![remote_canister_id]),
+        canister_queues.remote_subnet_input_schedule
+    );
+    assert_eq!(
+        Some(CanisterMessage::Response(Arc::new(Response {
+            originator: own_canister_id,
+            respondent: remote_canister_id,
+            originator_reply_callback: CallbackId::from(1),
+            refund: Cycles::zero(),
+            response_payload: Payload::Reject(RejectContext::new_with_message_length_limit(
+                RejectCode::SysUnknown,
+                "Call deadline has expired.".to_string(),
+                MR_SYNTHETIC_REJECT_MESSAGE_MAX_LEN
+            ))
+        }))),
+        canister_queues.pop_input()
+    );
+
+    // A late response for the timed out callback is silently dropped.
+    let late_response = ResponseBuilder::default()
+        .originator(own_canister_id)
+        .respondent(remote_canister_id)
+        .originator_reply_callback(CallbackId::from(1))
+        .build();
+    canister_queues
+        .push_input(late_response.into(), InputQueueType::RemoteSubnet)
+        .unwrap();
+    assert_eq!(0, canister_queues.input_queues_message_count());
+    assert!(canister_queues.timed_out_callbacks.is_empty());
+
+    // The response to the regular call is enqueued as usual.
+    let response = ResponseBuilder::default()
+        .originator(own_canister_id)
+        .respondent(remote_canister_id)
+        .originator_reply_callback(CallbackId::from(2))
+        .build();
+    canister_queues
+        .push_input(response.into(), InputQueueType::RemoteSubnet)
+        .unwrap();
+    assert_eq!(1, canister_queues.input_queues_message_count());
+}
+
+/// Tests that timing out an output request whose best-effort callback had
+/// already timed out drops the request without enqueuing a second reject
+/// response and refunds the cycles attached to it.
+#[test]
+fn time_out_requests_after_time_out_callbacks_refunds_payment() {
+    let mut canister_queues = CanisterQueues::default();
+
+    let own_canister_id = canister_test_id(67);
+    let remote_canister_id = canister_test_id(97);
+    let deadline = Time::from_nanos_since_unix_epoch(1000);
+
+    canister_queues
+        .push_output_request(
+            RequestBuilder::default()
+                .sender(own_canister_id)
+                .receiver(remote_canister_id)
+                .sender_reply_callback(CallbackId::from(1))
+                .payment(Cycles::new(7))
+                .deadline(deadline)
+                .build()
+                .into(),
+            mock_time(),
+        )
+        .unwrap();
+    let local_canisters = BTreeMap::new();
+
+    // The callback times out first, consuming the reserved slot.
+    assert_eq!(
+        1,
+        canister_queues.time_out_callbacks(deadline, &own_canister_id, &local_canisters)
+    );
+    assert_eq!(1, canister_queues.output_queues_message_count());
+    assert_eq!(1, canister_queues.input_queues_message_count());
+    assert_eq!(0, canister_queues.input_queues_reservation_count());
+
+    // The output request times out next: it is dropped and its payment refunded.
+    let current_time = mock_time() + REQUEST_LIFETIME;
+    assert!(canister_queues.has_expired_deadlines(current_time));
+    assert_eq!(
+        (1, Cycles::new(7)),
+        canister_queues.time_out_requests(current_time, &own_canister_id, &local_canisters)
+    );
+    assert_eq!(0, canister_queues.output_queues_message_count());
+    assert_eq!(1, canister_queues.input_queues_message_count());
+    assert!(canister_queues.timed_out_callbacks.is_empty());
+    assert!(canister_queues.stats_ok());
+}
+
+/// Tests that a response received before the deadline stops tracking the
+/// best-effort call; and that best-effort calls survive an encode-decode
+/// roundtrip.
+#[test]
+fn best_effort_callbacks_encode_roundtrip_and_response_before_deadline() {
+    let mut canister_queues = CanisterQueues::default();
+
+    let own_canister_id = canister_test_id(67);
+    let other_canister_id = canister_test_id(79);
+    let deadline = Time::from_nanos_since_unix_epoch(1000);
+
+    for callback_id in [1, 2] {
+        canister_queues
+            .push_output_request(
+                RequestBuilder::default()
+                    .sender(own_canister_id)
+                    .receiver(other_canister_id)
+                    .sender_reply_callback(CallbackId::from(callback_id))
+                    .deadline(deadline)
+                    .build()
+                    .into(),
+                mock_time(),
+            )
+            .unwrap();
+    }
+    let local_canisters = BTreeMap::new();
+    assert_eq!(
+        2,
+        canister_queues.time_out_callbacks(deadline, &own_canister_id, &local_canisters)
+    );
+
+    let encoded: pb_queues::CanisterQueues = (&canister_queues).into();
+    let decoded: CanisterQueues = encoded.try_into().unwrap();
+    assert_eq!(canister_queues, decoded);
+    assert_eq!(2, decoded.timed_out_callbacks.len());
+
+    let mut canister_queues = CanisterQueues::default();
+    canister_queues
+        .push_output_request(
+            RequestBuilder::default()
+                .sender(own_canister_id)
+                .receiver(other_canister_id)
+                .sender_reply_callback(CallbackId::from(3))
+                .deadline(deadline)
+                .build()
+                .into(),
+            mock_time(),
+        )
+        .unwrap();
+    let encoded: pb_queues::CanisterQueues = (&canister_queues).into();
+    let decoded: CanisterQueues = encoded.try_into().unwrap();
+    assert_eq!(canister_queues, decoded);
+
+    canister_queues
+        .push_input(
+            ResponseBuilder::default()
+                .originator(own_canister_id)
+                .respondent(other_canister_id)
+                .originator_reply_callback(CallbackId::from(3))
+                .build()
+                .into(),
+            InputQueueType::LocalSubnet,
+        )
+        .unwrap();
+    assert!(!canister_queues.has_expired_callback_deadlines(deadline));
+    assert_eq!(
+        0,
+        canister_queues.time_out_callbacks(deadline, &own_canister_id, &local_canisters)
+    );
+}
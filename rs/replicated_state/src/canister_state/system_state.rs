@@ -6,7 +6,7 @@ pub use crate::canister_state::queues::CanisterOutputQueuesIterator;
 use crate::{CanisterQueues, CanisterState, InputQueueType, StateError};
 pub use call_context_manager::{CallContext, CallContextAction, CallContextManager, CallOrigin};
 use ic_base_types::NumSeconds;
-use ic_ic00_types::{CanisterChange, CanisterChangeDetails, CanisterChangeOrigin};
+use ic_ic00_types::{CanisterChange, CanisterChangeDetails, CanisterChangeOrigin, LogVisibility};
 use ic_interfaces::messages::{CanisterCall, CanisterMessage, CanisterMessageOrTask, CanisterTask};
 use ic_logger::{error, ReplicaLogger};
 use ic_protobuf::{
@@ -309,6 +309,58 @@ pub struct SystemState {
 
     /// Canister history.
     canister_history: CanisterHistory,
+
+    /// Cycles reserved for future storage payments of the canister.
+    ///
+    /// Cycles are moved from `cycles_balance` into the reserved balance when
+    /// the canister allocates new memory while the subnet memory usage is
+    /// above its threshold. Storage payments are charged from the reserved
+    /// balance first. The reserved cycles cannot be withdrawn or transferred.
+    reserved_balance: Cycles,
+
+    /// The user-configured upper limit on `reserved_balance`. `None` means
+    /// that the default limit applies.
+    reserved_balance_limit: Option<Cycles>,
+
+    /// The upper limit on the Wasm memory (heap) of the canister. A
+    /// `memory.grow` that would make the heap exceed the limit traps.
+    /// `None` means that there is no limit.
+    pub wasm_memory_limit: Option<NumBytes>,
+
+    /// Who can read the logs of the canister.
+    pub log_visibility: LogVisibility,
+}
+
+/// Errors that can occur when reserving cycles for future storage payments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReservationError {
+    /// The main balance does not have enough cycles for the reservation.
+    InsufficientCycles {
+        requested: Cycles,
+        available: Cycles,
+    },
+    /// The reservation would make the reserved balance exceed its limit.
+    ReservedLimitExceeded { requested: Cycles, limit: Cycles },
+}
+
+impl std::fmt::Display for ReservationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReservationError::InsufficientCycles {
+                requested,
+                available,
+            } => write!(
+                f,
+                "Cannot reserve {} cycles: only {} cycles are available in the main balance",
+                requested, available
+            ),
+            ReservationError::ReservedLimitExceeded { requested, limit } => write!(
+                f,
+                "Cannot reserve {} cycles: the reserved balance would exceed its limit of {} cycles",
+                requested, limit
+            ),
+        }
+    }
 }
 
 /// A wrapper around the different canister statuses.
@@ -686,6 +738,10 @@ impl SystemState {
             global_timer: CanisterTimer::Inactive,
             canister_version: 0,
             canister_history: CanisterHistory::default(),
+            reserved_balance: Cycles::zero(),
+            reserved_balance_limit: None,
+            wasm_memory_limit: None,
+            log_visibility: LogVisibility::default(),
         }
     }
 
@@ -721,6 +777,10 @@ impl SystemState {
         global_timer: CanisterTimer,
         canister_version: u64,
         canister_history: CanisterHistory,
+        reserved_balance: Cycles,
+        reserved_balance_limit: Option<Cycles>,
+        wasm_memory_limit: Option<NumBytes>,
+        log_visibility: LogVisibility,
     ) -> Self {
         Self {
             controllers,
@@ -737,6 +797,10 @@ impl SystemState {
             global_timer,
             canister_version,
             canister_history,
+            reserved_balance,
+            reserved_balance_limit,
+            wasm_memory_limit,
+            log_visibility,
         }
     }
 
@@ -749,6 +813,60 @@ impl SystemState {
         self.cycles_balance
     }
 
+    /// Returns the amount of cycles reserved for future storage payments.
+    pub fn reserved_balance(&self) -> Cycles {
+        self.reserved_balance
+    }
+
+    /// Returns the user-configured limit on the reserved balance, if any.
+    pub fn reserved_balance_limit(&self) -> Option<Cycles> {
+        self.reserved_balance_limit
+    }
+
+    /// Sets the user-configured limit on the reserved balance.
+    pub fn set_reserved_balance_limit(&mut self, limit: Cycles) {
+        self.reserved_balance_limit = Some(limit);
+    }
+
+    /// Moves `amount` cycles from the main balance to the reserved balance.
+    ///
+    /// Fails without changing the state if the main balance is too low or if
+    /// the resulting reserved balance would exceed `limit`.
+    pub fn reserve_cycles(
+        &mut self,
+        amount: Cycles,
+        limit: Cycles,
+    ) -> Result<(), ReservationError> {
+        if amount == Cycles::zero() {
+            return Ok(());
+        }
+        if amount > self.debited_balance() {
+            return Err(ReservationError::InsufficientCycles {
+                requested: amount,
+                available: self.debited_balance(),
+            });
+        }
+        if self.reserved_balance + amount > limit {
+            return Err(ReservationError::ReservedLimitExceeded {
+                requested: amount,
+                limit,
+            });
+        }
+        self.cycles_balance -= amount;
+        self.reserved_balance += amount;
+        Ok(())
+    }
+
+    /// Consumes up to `amount` cycles from the reserved balance and returns
+    /// the part of `amount` that could not be covered by it.
+    pub fn consume_reserved_cycles(&mut self, amount: Cycles, use_case: CyclesUseCase) -> Cycles {
+        let covered = self.reserved_balance.min(amount);
+        self.reserved_balance -= covered;
+        self.observe_consumed_cycles(covered);
+        self.observe_consumed_cycles_with_use_case(covered, use_case, ConsumingCycles::Yes);
+        amount - covered
+    }
+
     /// Returns the balance after applying the pending 'ingress_induction_cycles_debit'.
     /// Returns 0 if the balance is smaller than the pending 'ingress_induction_cycles_debit'.
     pub fn debited_balance(&self) -> Cycles {
@@ -955,6 +1073,10 @@ impl SystemState {
     ///  * `CanisterStopped` if the canister is stopped.
     ///  * `NonMatchingResponse` if the callback is not found or the respondent
     ///    does not match.
+    ///
+    /// A late response for a best-effort callback that already timed out is
+    /// dropped regardless of the status, and its refund is credited to the
+    /// canister.
     pub(crate) fn push_input(
         &mut self,
         msg: RequestOrResponse,
@@ -971,6 +1093,15 @@ impl SystemState {
             msg.receiver()
         );
 
+        // The callback was already resolved (and possibly unregistered), so the
+        // response cannot be validated against it.
+        if let RequestOrResponse::Response(response) = &msg {
+            if let Some(refund) = self.queues.take_late_response_refund(response) {
+                self.add_cycles(refund, CyclesUseCase::NonConsumed);
+                return Ok(());
+            }
+        }
+
         match (&msg, &self.status) {
             // Requests and responses are both rejected when stopped.
             (_, CanisterStatus::Stopped { .. }) => {
@@ -1140,12 +1271,10 @@ impl SystemState {
             }
 
             // Attempt inducting `msg`. May fail if the input queue is full.
-            if self
-                .queues
-                .induct_message_to_self(self.canister_id)
-                .is_err()
-            {
-                return;
+            match self.queues.induct_message_to_self(self.canister_id) {
+                Ok(Some(refund)) => self.add_cycles(refund, CyclesUseCase::NonConsumed),
+                Ok(None) => {}
+                Err(()) => return,
             }
 
             // Adjust both `available_memory` and `subnet_available_memory` by
@@ -1170,8 +1299,9 @@ impl SystemState {
         self.queues.has_expired_deadlines(current_time)
     }
 
-    /// Times out requests in the `OutputQueues` of `self.queues`. Returns the number of requests
-    /// that were timed out.
+    /// Times out requests in the `OutputQueues` of `self.queues`, crediting back
+    /// the payment of any dropped request whose callback had already timed out.
+    /// Returns the number of requests that were timed out.
     ///
     /// See [`CanisterQueues::time_out_requests`] for further details.
     pub fn time_out_requests(
@@ -1179,9 +1309,32 @@ impl SystemState {
         current_time: Time,
         own_canister_id: &CanisterId,
         local_canisters: &BTreeMap<CanisterId, CanisterState>,
+    ) -> u64 {
+        let (timed_out_requests_count, cycles_to_refund) =
+            self.queues
+                .time_out_requests(current_time, own_canister_id, local_canisters);
+        self.add_cycles(cycles_to_refund, CyclesUseCase::NonConsumed);
+        timed_out_requests_count
+    }
+
+    /// Queries whether any best-effort response call in `self.queues` has an
+    /// expired deadline.
+    pub fn has_expired_callback_deadlines(&self, current_time: Time) -> bool {
+        self.queues.has_expired_callback_deadlines(current_time)
+    }
+
+    /// Times out best-effort response calls with expired deadlines. Returns the
+    /// number of callbacks that were timed out.
+    ///
+    /// See [`CanisterQueues::time_out_callbacks`] for further details.
+    pub fn time_out_callbacks(
+        &mut self,
+        current_time: Time,
+        own_canister_id: &CanisterId,
+        local_canisters: &BTreeMap<CanisterId, CanisterState>,
     ) -> u64 {
         self.queues
-            .time_out_requests(current_time, own_canister_id, local_canisters)
+            .time_out_callbacks(current_time, own_canister_id, local_canisters)
     }
 
     /// Re-partitions the local and remote input schedules of `self.queues`
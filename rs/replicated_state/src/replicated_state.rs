@@ -835,6 +835,39 @@ impl ReplicatedState {
         timed_out_requests_count
     }
 
+    /// Times out all best-effort response calls with expired deadlines across all
+    /// canisters, enqueuing a `SYS_UNKNOWN` reject response for each. Returns the
+    /// number of timed out callbacks.
+    ///
+    /// See `CanisterQueues::time_out_callbacks` for further details.
+    pub fn time_out_callbacks(&mut self, current_time: Time) -> u64 {
+        // Same remove-call-replace approach as `time_out_requests()`, applied only
+        // to the canisters with expired callback deadlines.
+        let canister_ids_with_expired_deadlines = self
+            .canister_states
+            .iter()
+            .filter(|(_, canister_state)| {
+                canister_state
+                    .system_state
+                    .has_expired_callback_deadlines(current_time)
+            })
+            .map(|(canister_id, _)| *canister_id)
+            .collect::<Vec<_>>();
+
+        let mut timed_out_callbacks_count = 0;
+        for canister_id in canister_ids_with_expired_deadlines {
+            let mut canister = self.canister_states.remove(&canister_id).unwrap();
+            timed_out_callbacks_count += canister.system_state.time_out_callbacks(
+                current_time,
+                &canister_id,
+                &self.canister_states,
+            );
+            self.canister_states.insert(canister_id, canister);
+        }
+
+        timed_out_callbacks_count
+    }
+
     /// Splits the replicated state as part of subnet splitting phase 1, retaining
     /// only the canisters of `new_subnet_id` (as determined by the provided routing
     /// table).
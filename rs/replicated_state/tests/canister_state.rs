@@ -10,8 +10,9 @@ use ic_test_utilities::{
 use ic_types::{
     messages::{CallbackId, Request, RequestOrResponse},
     xnet::QueueId,
+    Cycles, Time,
 };
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 const CANISTER_ID: CanisterId = CanisterId::from_u64(0);
 const OTHER_CANISTER_ID: CanisterId = CanisterId::from_u64(1);
@@ -255,3 +256,60 @@ fn validate_responses_against_callback_details() {
         .push_input(input_response_from(canister_b_id, callback_id_1))
         .unwrap();
 }
+
+#[test]
+fn late_response_for_timed_out_callback_refunds_cycles() {
+    let mut fixture = CanisterFixture::running();
+    fixture.register_default_callback();
+    let deadline = Time::from_nanos_since_unix_epoch(1000);
+
+    // Best-effort request from this canister to the other canister.
+    fixture
+        .push_output_request(
+            RequestBuilder::new()
+                .sender(CANISTER_ID)
+                .receiver(OTHER_CANISTER_ID)
+                .sender_reply_callback(CallbackId::from(CALLBACK_ID_RAW))
+                .deadline(deadline)
+                .build(),
+        )
+        .unwrap();
+    fixture.pop_output().unwrap();
+
+    // The callback times out and is resolved with a synthetic reject.
+    assert_eq!(
+        1,
+        fixture.canister_state.system_state.time_out_callbacks(
+            deadline,
+            &CANISTER_ID,
+            &BTreeMap::new()
+        )
+    );
+    let balance_before = fixture.canister_state.system_state.balance();
+    let input_messages_before = fixture
+        .canister_state
+        .system_state
+        .queues()
+        .input_queues_message_count();
+
+    // The late response is dropped and its refund is credited to the canister.
+    let late_response = ResponseBuilder::new()
+        .originator(CANISTER_ID)
+        .respondent(OTHER_CANISTER_ID)
+        .originator_reply_callback(CallbackId::from(CALLBACK_ID_RAW))
+        .refund(Cycles::new(1_000))
+        .build();
+    fixture.push_input(late_response.into()).unwrap();
+    assert_eq!(
+        balance_before + Cycles::new(1_000),
+        fixture.canister_state.system_state.balance()
+    );
+    assert_eq!(
+        input_messages_before,
+        fixture
+            .canister_state
+            .system_state
+            .queues()
+            .input_queues_message_count()
+    );
+}
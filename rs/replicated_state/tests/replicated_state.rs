@@ -755,6 +755,48 @@ fn time_out_requests_updates_subnet_input_schedules_correctly() {
     );
 }
 
+#[test]
+fn time_out_requests_refunds_payment_of_request_with_timed_out_callback() {
+    let mut fixture = ReplicatedStateFixture::new();
+    let remote_canister_id = CanisterId::from_u64(123);
+    let deadline = Time::from_nanos_since_unix_epoch(1000);
+
+    let request = RequestBuilder::default()
+        .sender(CANISTER_ID)
+        .receiver(remote_canister_id)
+        .payment(Cycles::new(1_000))
+        .deadline(deadline)
+        .build();
+    fixture.push_output_request(request, mock_time()).unwrap();
+    let balance_before = fixture
+        .state
+        .canister_state(&CANISTER_ID)
+        .unwrap()
+        .system_state
+        .balance();
+
+    // The best-effort callback times out before the output request does.
+    assert_eq!(1, fixture.state.time_out_callbacks(deadline));
+    assert_eq!(
+        1,
+        fixture
+            .state
+            .time_out_requests(Time::from_nanos_since_unix_epoch(u64::MAX)),
+    );
+
+    // The request was dropped without ever leaving the subnet: its payment is
+    // credited back to the canister.
+    assert_eq!(
+        balance_before + Cycles::new(1_000),
+        fixture
+            .state
+            .canister_state(&CANISTER_ID)
+            .unwrap()
+            .system_state
+            .balance()
+    );
+}
+
 #[test]
 fn split() {
     // We will be splitting subnet A into A' and B. C is a third-party subnet.
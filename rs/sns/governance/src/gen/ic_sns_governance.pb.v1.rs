@@ -48,12 +48,20 @@ pub struct ProposalId {
     ::prost::Message,
 )]
 pub struct DisburseMaturityInProgress {
+    /// The amount of maturity in e8s that has been deducted from the neuron. The
+    /// maturity modulation is applied to this amount when the disbursement is
+    /// finalized, so the amount of tokens that are eventually minted may differ.
     #[prost(uint64, tag = "1")]
     pub amount_e8s: u64,
     #[prost(uint64, tag = "2")]
     pub timestamp_of_disbursement_seconds: u64,
     #[prost(message, optional, tag = "3")]
     pub account_to_disburse_to: ::core::option::Option<Account>,
+    /// The time after which the disbursement is finalized, i.e., the (modulated)
+    /// amount is minted to `account_to_disburse_to`. If not set, the disbursement
+    /// is finalized seven days after `timestamp_of_disbursement_seconds`.
+    #[prost(uint64, optional, tag = "4")]
+    pub finalize_disbursement_timestamp_seconds: ::core::option::Option<u64>,
 }
 /// A neuron in the governance system.
 #[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
@@ -148,6 +156,15 @@ pub struct Neuron {
     /// entry\[i\].timestamp_of_disbursement_seconds <= entry\[i+1\].timestamp_of_disbursement_seconds
     #[prost(message, repeated, tag = "18")]
     pub disburse_maturity_in_progress: ::prost::alloc::vec::Vec<DisburseMaturityInProgress>,
+    /// The neuron's followees per proposal topic, specified as a map of topics
+    /// to followees neuron IDs. The map's keys are `Topic` values represented by
+    /// integers as Protobuf does not support enum keys in maps.
+    ///
+    /// Following on a proposal's function (see `followees`) takes precedence over
+    /// following on its topic, which in turn takes precedence over the catch-all
+    /// following on the Unspecified function.
+    #[prost(btree_map = "int32, message", tag = "19")]
+    pub topic_followees: ::prost::alloc::collections::BTreeMap<i32, neuron::Followees>,
     /// The neuron's dissolve state, specifying whether the neuron is dissolving,
     /// non-dissolving, or dissolved.
     ///
@@ -290,6 +307,10 @@ pub mod nervous_system_function {
         /// <method_name>(proposal_data: ProposalData) -> Result<String, String>
         #[prost(string, optional, tag = "5")]
         pub validator_method_name: ::core::option::Option<::prost::alloc::string::String>,
+        /// The topic of proposals executing this function, for the purposes of
+        /// following. If unset, TOPIC_APPLICATION_BUSINESS_LOGIC is used.
+        #[prost(enumeration = "super::Topic", optional, tag = "6")]
+        pub topic: ::core::option::Option<i32>,
     }
     #[derive(
         candid::CandidType,
@@ -437,6 +458,59 @@ pub mod transfer_sns_treasury_funds {
         }
     }
 }
+/// A proposal to mint SNS tokens to (optionally a Subaccount of) the
+/// target principal.
+#[derive(
+    candid::CandidType,
+    candid::Deserialize,
+    comparable::Comparable,
+    Clone,
+    PartialEq,
+    ::prost::Message,
+)]
+pub struct MintSnsTokens {
+    /// The amount to mint, in e8s. Must not exceed the caps defined by
+    /// NervousSystemParameters.max_mint_sns_tokens_basis_points_of_total_supply
+    /// and NervousSystemParameters.max_mint_sns_tokens_basis_points_of_total_supply_per_month.
+    #[prost(uint64, optional, tag = "1")]
+    pub amount_e8s: ::core::option::Option<u64>,
+    /// An optional memo to use for the mint transaction.
+    #[prost(uint64, optional, tag = "2")]
+    pub memo: ::core::option::Option<u64>,
+    /// The principal to mint the tokens to.
+    #[prost(message, optional, tag = "3")]
+    pub to_principal: ::core::option::Option<::ic_base_types::PrincipalId>,
+    /// An (optional) Subaccount of the principal to mint the tokens to.
+    #[prost(message, optional, tag = "4")]
+    pub to_subaccount: ::core::option::Option<Subaccount>,
+}
+/// A proposal function to change the parameters of the SNS ledger, which
+/// is done by upgrading the ledger canister (to its current wasm) with new
+/// upgrade arguments.
+/// Fields with None values will remain unchanged.
+#[derive(
+    candid::CandidType,
+    candid::Deserialize,
+    comparable::Comparable,
+    Clone,
+    PartialEq,
+    ::prost::Message,
+)]
+pub struct ManageLedgerParameters {
+    /// The new transfer fee, in e8s.
+    #[prost(uint64, optional, tag = "1")]
+    pub transfer_fee: ::core::option::Option<u64>,
+    /// The new token name, must be between 4 and 255 characters.
+    #[prost(string, optional, tag = "2")]
+    pub token_name: ::core::option::Option<::prost::alloc::string::String>,
+    /// The new token symbol, must be between 3 and 10 characters.
+    #[prost(string, optional, tag = "3")]
+    pub token_symbol: ::core::option::Option<::prost::alloc::string::String>,
+    /// The new token logo, as a base64 encoded PNG. Max length is 341334
+    /// characters, roughly 256 Kb.
+    #[prost(string, optional, tag = "4")]
+    pub token_logo: ::core::option::Option<::prost::alloc::string::String>,
+}
 /// A proposal function to change the values of SNS metadata.
 /// Fields with None values will remain unchanged.
 #[derive(
@@ -508,6 +582,32 @@ pub struct DeregisterDappCanisters {
     #[prost(message, repeated, tag = "2")]
     pub new_controllers: ::prost::alloc::vec::Vec<::ic_base_types::PrincipalId>,
 }
+/// A proposal to change the settings of a list of registered dapp canisters.
+/// Fields with None values will remain unchanged.
+#[derive(
+    candid::CandidType,
+    candid::Deserialize,
+    comparable::Comparable,
+    Clone,
+    PartialEq,
+    ::prost::Message,
+)]
+pub struct ManageDappCanisterSettings {
+    /// The canister IDs of the dapp canisters to change the settings of. All of
+    /// them must be registered with the SNS root canister.
+    /// At least one canister ID is required.
+    #[prost(message, repeated, tag = "1")]
+    pub canister_ids: ::prost::alloc::vec::Vec<::ic_base_types::PrincipalId>,
+    /// The new compute allocation, as a percentage.
+    #[prost(uint64, optional, tag = "2")]
+    pub compute_allocation: ::core::option::Option<u64>,
+    /// The new memory allocation, in bytes.
+    #[prost(uint64, optional, tag = "3")]
+    pub memory_allocation: ::core::option::Option<u64>,
+    /// The new freezing threshold, in seconds.
+    #[prost(uint64, optional, tag = "4")]
+    pub freezing_threshold: ::core::option::Option<u64>,
+}
 /// A proposal is the immutable input of a proposal submission.
 #[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
 #[compare_default]
@@ -536,7 +636,7 @@ pub struct Proposal {
     /// of this mapping.
     #[prost(
         oneof = "proposal::Action",
-        tags = "4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15"
+        tags = "4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18"
     )]
     pub action: ::core::option::Option<proposal::Action>,
 }
@@ -626,6 +726,21 @@ pub mod proposal {
         /// Id = 11.
         #[prost(message, tag = "15")]
         DeregisterDappCanisters(super::DeregisterDappCanisters),
+        /// Mint SNS tokens to an account.
+        ///
+        /// Id = 12.
+        #[prost(message, tag = "16")]
+        MintSnsTokens(super::MintSnsTokens),
+        /// Change some parameters on the SNS ledger.
+        ///
+        /// Id = 13.
+        #[prost(message, tag = "17")]
+        ManageLedgerParameters(super::ManageLedgerParameters),
+        /// Change the settings of one or more registered dapp canister(s).
+        ///
+        /// Id = 14.
+        #[prost(message, tag = "18")]
+        ManageDappCanisterSettings(super::ManageDappCanisterSettings),
     }
 }
 #[derive(candid::CandidType, candid::Deserialize, comparable::Comparable)]
@@ -816,6 +931,11 @@ pub struct ProposalData {
     /// Id 7 - UpgradeSnsToNextVersion proposals.
     /// Id 8 - ManageSnsMetadata proposals.
     /// Id 9 - TransferSnsTreasuryFunds proposals.
+    /// Id 10 - RegisterDappCanisters proposals.
+    /// Id 11 - DeregisterDappCanisters proposals.
+    /// Id 12 - MintSnsTokens proposals.
+    /// Id 13 - ManageLedgerParameters proposals.
+    /// Id 14 - ManageDappCanisterSettings proposals.
     #[prost(uint64, tag = "1")]
     pub action: u64,
     /// This is stored here temporarily. It is also stored on the map
@@ -923,6 +1043,10 @@ pub struct ProposalData {
     /// accepted, it is considered "ready to settle".
     #[prost(uint64, optional, tag = "19")]
     pub reward_event_end_timestamp_seconds: ::core::option::Option<u64>,
+    /// The topic of the proposal, derived from its action when the proposal is
+    /// made. Unset for proposals made before topics were introduced.
+    #[prost(enumeration = "Topic", optional, tag = "20")]
+    pub topic: ::core::option::Option<i32>,
 }
 /// The nervous system's parameters, which are parameters that can be changed, via proposals,
 /// by each nervous system community.
@@ -1077,6 +1201,16 @@ pub struct NervousSystemParameters {
     /// (enabled) agree.
     #[prost(bool, optional, tag = "22")]
     pub maturity_modulation_disabled: ::core::option::Option<bool>,
+    /// The maximum amount of SNS tokens that a single MintSnsTokens proposal can
+    /// mint, in basis points of the total supply at the time of execution.
+    #[prost(uint64, optional, tag = "23")]
+    pub max_mint_sns_tokens_basis_points_of_total_supply: ::core::option::Option<u64>,
+    /// The maximum amount of SNS tokens that all MintSnsTokens proposals executed
+    /// within the last month can mint together, in basis points of the total
+    /// supply at the time of execution.
+    #[prost(uint64, optional, tag = "24")]
+    pub max_mint_sns_tokens_basis_points_of_total_supply_per_month:
+        ::core::option::Option<u64>,
 }
 #[derive(
     candid::CandidType,
@@ -1323,6 +1457,10 @@ pub struct Governance {
     pub is_finalizing_disburse_maturity: ::core::option::Option<bool>,
     #[prost(message, optional, tag = "26")]
     pub maturity_modulation: ::core::option::Option<governance::MaturityModulation>,
+    /// The SNS tokens minted by MintSnsTokens proposals within the last month, used to
+    /// enforce NervousSystemParameters.max_mint_sns_tokens_basis_points_of_total_supply_per_month.
+    #[prost(message, repeated, tag = "27")]
+    pub recent_sns_token_mints: ::prost::alloc::vec::Vec<governance::SnsTokenMint>,
 }
 /// Nested message and enum types in `Governance`.
 pub mod governance {
@@ -1575,6 +1713,23 @@ pub mod governance {
         #[prost(uint64, optional, tag = "2")]
         pub updated_at_timestamp_seconds: ::core::option::Option<u64>,
     }
+    /// SNS tokens minted by a MintSnsTokens proposal.
+    #[derive(
+        candid::CandidType,
+        candid::Deserialize,
+        comparable::Comparable,
+        Clone,
+        PartialEq,
+        ::prost::Message,
+    )]
+    pub struct SnsTokenMint {
+        /// When the tokens were minted (seconds since UNIX epoch).
+        #[prost(uint64, tag = "1")]
+        pub timestamp_seconds: u64,
+        /// The amount of minted tokens, in e8s.
+        #[prost(uint64, tag = "2")]
+        pub amount_e8s: u64,
+    }
     #[derive(
         candid::CandidType,
         candid::Deserialize,
@@ -2012,6 +2167,11 @@ pub mod manage_neuron {
     /// then it becomes a catch-all follow rule, which will be used to vote
     /// automatically on proposals with actions for which no
     /// specific rule has been specified.
+    ///
+    /// If `topic` is set, the follow relation is defined for all proposals of
+    /// that topic instead, and `function_id` must be 0. A follow relation on a
+    /// proposal's function takes precedence over one on its topic, which in turn
+    /// takes precedence over the catch-all rule.
     #[derive(
         candid::CandidType,
         candid::Deserialize,
@@ -2028,6 +2188,9 @@ pub mod manage_neuron {
         /// The list of followee neurons, specified by their neuron ID.
         #[prost(message, repeated, tag = "2")]
         pub followees: ::prost::alloc::vec::Vec<super::NeuronId>,
+        /// The topic defining for which proposals this follow relation is relevant.
+        #[prost(enumeration = "super::Topic", optional, tag = "3")]
+        pub topic: ::core::option::Option<i32>,
     }
     /// The operation that registers a given vote from the neuron for a given
     /// proposal (a directly cast vote as opposed to a vote that is cast as
@@ -2252,9 +2415,14 @@ pub mod manage_neuron_response {
         ::prost::Message,
     )]
     pub struct DisburseMaturityResponse {
-        /// The amount disbursed in e8s of the governance token.
+        /// The amount disbursed in e8s of the governance token, estimated using the
+        /// maturity modulation at the time of the request. The amount that is
+        /// actually minted is determined when the disbursement is finalized.
         #[prost(uint64, tag = "2")]
         pub amount_disbursed_e8s: u64,
+        /// The amount of maturity in e8s that was deducted from the neuron.
+        #[prost(uint64, optional, tag = "3")]
+        pub amount_deducted_e8s: ::core::option::Option<u64>,
     }
     #[derive(
         candid::CandidType,
@@ -2716,6 +2884,11 @@ pub mod claim_swap_neurons_request {
         /// relations can be added after neuron creation.
         #[prost(message, repeated, tag = "8")]
         pub followees: ::prost::alloc::vec::Vec<super::NeuronId>,
+        /// The vesting period in seconds that the created Neuron will have. While a
+        /// Neuron is vesting, it cannot be dissolved and its dissolve delay cannot be
+        /// decreased. If unset, the Neuron does not vest.
+        #[prost(uint64, optional, tag = "9")]
+        pub vesting_period_seconds: ::core::option::Option<u64>,
     }
 }
 /// The response for the `claim_swap_neurons` method.
@@ -2924,6 +3097,63 @@ impl NeuronPermissionType {
     }
 }
 /// The types of votes a neuron can issue.
+/// The topics that proposals are grouped into, for the purposes of following.
+///
+/// Each native action belongs to a fixed topic (see `topic_for_function_id` in
+/// src/types.rs), and each GenericNervousSystemFunction specifies its topic
+/// when it is added. This allows a neuron to follow other neurons on e.g. all
+/// treasury proposals, including on functions that are added later.
+#[derive(
+    candid::CandidType,
+    candid::Deserialize,
+    comparable::Comparable,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
+#[repr(i32)]
+pub enum Topic {
+    Unspecified = 0,
+    /// Proposals to change the parameters and metadata of the SNS and its ledger.
+    DaoCommunitySettings = 1,
+    /// Proposals to upgrade the SNS canisters.
+    SnsFrameworkManagement = 2,
+    /// Proposals to upgrade, register and manage the dapp canisters.
+    DappCanisterManagement = 3,
+    /// Proposals that call the dapp's generic nervous system functions.
+    ApplicationBusinessLogic = 4,
+    /// Motion proposals.
+    Governance = 5,
+    /// Proposals to transfer or mint SNS and ICP tokens.
+    TreasuryAssetManagement = 6,
+    /// Proposals that can take the dapp out of the SNS's control, or change
+    /// which functions can be executed by proposal.
+    CriticalDappOperations = 7,
+}
+impl Topic {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Topic::Unspecified => "TOPIC_UNSPECIFIED",
+            Topic::DaoCommunitySettings => "TOPIC_DAO_COMMUNITY_SETTINGS",
+            Topic::SnsFrameworkManagement => "TOPIC_SNS_FRAMEWORK_MANAGEMENT",
+            Topic::DappCanisterManagement => "TOPIC_DAPP_CANISTER_MANAGEMENT",
+            Topic::ApplicationBusinessLogic => "TOPIC_APPLICATION_BUSINESS_LOGIC",
+            Topic::Governance => "TOPIC_GOVERNANCE",
+            Topic::TreasuryAssetManagement => "TOPIC_TREASURY_ASSET_MANAGEMENT",
+            Topic::CriticalDappOperations => "TOPIC_CRITICAL_DAPP_OPERATIONS",
+        }
+    }
+}
 #[derive(
     candid::CandidType,
     candid::Deserialize,
@@ -5,7 +5,10 @@ use crate::{
         get_canister_id, perform_execute_generic_nervous_system_function_call,
         upgrade_canister_directly,
     },
-    ledger::ICRC1Ledger,
+    ledger::{
+        ICRC1Ledger, LedgerArgument, LedgerUpgradeArgs, LEDGER_DERIVED_METADATA_KEYS,
+        LEDGER_METADATA_LOGO_KEY,
+    },
     logs::{ERROR, INFO},
     neuron::{
         NeuronState, RemovePermissionsStatus, DEFAULT_VOTING_POWER_PERCENTAGE_MULTIPLIER,
@@ -13,6 +16,7 @@ use crate::{
     },
     pb::{
         sns_root_types::{
+            ManageDappCanisterSettingsRequest, ManageDappCanisterSettingsResponse,
             RegisterDappCanistersRequest, RegisterDappCanistersResponse, SetDappControllersRequest,
             SetDappControllersResponse,
         },
@@ -22,7 +26,7 @@ use crate::{
             governance::{
                 self, neuron_in_flight_command,
                 neuron_in_flight_command::Command as InFlightCommand, MaturityModulation,
-                NeuronInFlightCommand, SnsMetadata, UpgradeInProgress, Version,
+                NeuronInFlightCommand, SnsMetadata, SnsTokenMint, UpgradeInProgress, Version,
             },
             governance_error::ErrorType,
             manage_neuron::{
@@ -45,13 +49,14 @@ use crate::{
             GetModeResponse, GetNeuron, GetNeuronResponse, GetProposal, GetProposalResponse,
             GetSnsInitializationParametersRequest, GetSnsInitializationParametersResponse,
             Governance as GovernanceProto, GovernanceError, ListNervousSystemFunctionsResponse,
-            ListNeurons, ListNeuronsResponse, ListProposals, ListProposalsResponse, ManageNeuron,
-            ManageNeuronResponse, ManageSnsMetadata, NervousSystemFunction,
-            NervousSystemParameters, Neuron, NeuronId, NeuronPermission, NeuronPermissionList,
-            NeuronPermissionType, Proposal, ProposalData, ProposalDecisionStatus, ProposalId,
-            ProposalRewardStatus, RegisterDappCanisters, RewardEvent, Tally,
-            TransferSnsTreasuryFunds, UpgradeSnsControlledCanister, UpgradeSnsToNextVersion, Vote,
-            VotingRewardsParameters, WaitForQuietState,
+            ListNeurons, ListNeuronsResponse, ListProposals, ListProposalsResponse,
+            ManageDappCanisterSettings, ManageLedgerParameters, ManageNeuron, ManageNeuronResponse,
+            ManageSnsMetadata, MintSnsTokens, NervousSystemFunction, NervousSystemParameters,
+            Neuron, NeuronId, NeuronPermission, NeuronPermissionList, NeuronPermissionType,
+            Proposal, ProposalData, ProposalDecisionStatus, ProposalId, ProposalRewardStatus,
+            RegisterDappCanisters, RewardEvent, Tally, Topic, TransferSnsTreasuryFunds,
+            UpgradeSnsControlledCanister, UpgradeSnsToNextVersion, Vote, VotingRewardsParameters,
+            WaitForQuietState,
         },
     },
     proposal::{
@@ -62,7 +67,10 @@ use crate::{
         get_all_sns_canisters, get_running_version, get_upgrade_params, get_wasm, SnsCanisterType,
         UpgradeSnsParams,
     },
-    types::{is_registered_function_id, Environment, HeapGrowthPotential, LedgerUpdateLock},
+    types::{
+        is_registered_function_id, topic_for_function_id, Environment, HeapGrowthPotential,
+        LedgerUpdateLock, ONE_MONTH_SECONDS,
+    },
 };
 use candid::{Decode, Encode};
 use dfn_core::api::{spawn, CanisterId};
@@ -80,6 +88,7 @@ use ic_nervous_system_common::{
 use ic_nervous_system_root::change_canister::ChangeCanisterProposal;
 use ic_nns_constants::LEDGER_CANISTER_ID as NNS_LEDGER_CANISTER_ID;
 use icp_ledger::DEFAULT_TRANSFER_FEE as NNS_DEFAULT_TRANSFER_FEE;
+use icrc_ledger_types::icrc::generic_metadata_value::MetadataValue;
 use icrc_ledger_types::icrc1::account::{Account, Subaccount};
 use lazy_static::lazy_static;
 use maplit::hashset;
@@ -119,7 +128,9 @@ pub const EXECUTE_NERVOUS_SYSTEM_FUNCTION_PAYLOAD_LISTING_BYTES_MAX: usize = 100
 const MAX_HEAP_SIZE_IN_KIB: usize = 4 * 1024 * 1024;
 const WASM32_PAGE_SIZE_IN_KIB: usize = 64;
 pub const ONE_DAY_SECONDS: u64 = 24 * 60 * 60;
-const SEVEN_DAYS_IN_SECONDS: u64 = 7 * 24 * 3600;
+/// The time between a neuron's maturity being deducted for a disbursement and the
+/// (modulated) amount being minted to the target account.
+const MATURITY_DISBURSEMENT_DELAY_SECONDS: u64 = 7 * 24 * 3600;
 
 /// The max number of wasm32 pages for the heap after which we consider that there
 /// is a risk to the ability to grow the heap.
@@ -268,6 +279,60 @@ impl GovernanceProto {
         }
     }
 
+    /// Builds an index that maps proposal topics to (followee) neuron IDs to these neuron's
+    /// followers. The resulting index is a map
+    /// Topic -> (followee's neuron ID) -> set of followers' neuron IDs.
+    ///
+    /// This is the same as `build_function_followee_index`, but for the neurons'
+    /// `topic_followees`.
+    pub fn build_topic_followee_index(
+        neurons: &BTreeMap<String, Neuron>,
+    ) -> BTreeMap<i32, BTreeMap<String, BTreeSet<NeuronId>>> {
+        let mut topic_followee_index = BTreeMap::new();
+        for neuron in neurons.values() {
+            GovernanceProto::add_neuron_to_topic_followee_index(&mut topic_followee_index, neuron);
+        }
+        topic_followee_index
+    }
+
+    /// Adds a neuron to the topic_followee_index.
+    pub fn add_neuron_to_topic_followee_index(
+        index: &mut BTreeMap<i32, BTreeMap<String, BTreeSet<NeuronId>>>,
+        neuron: &Neuron,
+    ) {
+        let neuron_id = neuron.id.as_ref().expect("Neuron must have a NeuronId");
+        for (topic, followees) in neuron.topic_followees.iter() {
+            let followee_index = index.entry(*topic).or_insert_with(BTreeMap::new);
+            for followee in followees.followees.iter() {
+                followee_index
+                    .entry(followee.to_string())
+                    .or_insert_with(BTreeSet::new)
+                    .insert(neuron_id.clone());
+            }
+        }
+    }
+
+    /// Removes a neuron from the topic_followee_index.
+    pub fn remove_neuron_from_topic_followee_index(
+        index: &mut BTreeMap<i32, BTreeMap<String, BTreeSet<NeuronId>>>,
+        neuron: &Neuron,
+    ) {
+        let neuron_id = neuron.id.as_ref().expect("Neuron must have an id");
+        for (topic, followees) in neuron.topic_followees.iter() {
+            if let Some(followee_index) = index.get_mut(topic) {
+                for followee in followees.followees.iter() {
+                    let nid = followee.to_string();
+                    if let Some(followee_set) = followee_index.get_mut(&nid) {
+                        followee_set.remove(neuron_id);
+                        if followee_set.is_empty() {
+                            followee_index.remove(&nid);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     /// Iterate through one neuron and add all the principals that have some permission on this
     /// neuron to the index that maps principalIDs to a set of neurons for which the principal
     /// has some permissions.
@@ -649,6 +714,16 @@ pub struct Governance {
     /// Function ID -> (followee's neuron ID) -> set of followers' neuron IDs.
     pub function_followee_index: BTreeMap<u64, BTreeMap<String, BTreeSet<NeuronId>>>,
 
+    /// Cached data structure that (for each proposal topic) maps a followee to
+    /// the set of its followers. It is the inverse of the mapping from follower
+    /// to followees per topic that is stored in each (follower) neuron.
+    ///
+    /// This is a cached index and will be removed and recreated when the state
+    /// is saved and restored.
+    ///
+    /// Topic -> (followee's neuron ID) -> set of followers' neuron IDs.
+    pub topic_followee_index: BTreeMap<i32, BTreeMap<String, BTreeSet<NeuronId>>>,
+
     /// Maps Principals to the Neuron IDs of all Neurons for which this principal
     /// has some permissions, i.e., all neurons that have this principal associated
     /// with a NeuronPermissionType for the Neuron.
@@ -718,6 +793,7 @@ impl Governance {
             nns_ledger,
             cmc,
             function_followee_index: BTreeMap::new(),
+            topic_followee_index: BTreeMap::new(),
             principal_to_neuron_ids_index: BTreeMap::new(),
             closest_proposal_deadline_timestamp_seconds: 0,
             latest_gc_timestamp_seconds: 0,
@@ -776,6 +852,8 @@ impl Governance {
         self.function_followee_index = self
             .proto
             .build_function_followee_index(&self.proto.neurons);
+        self.topic_followee_index =
+            GovernanceProto::build_topic_followee_index(&self.proto.neurons);
         self.principal_to_neuron_ids_index = self
             .proto
             .build_principal_to_neuron_ids_index(&self.proto.neurons);
@@ -886,7 +964,8 @@ impl Governance {
     }
 
     /// Adds a neuron to the list of neurons and updates the indices
-    /// `principal_to_neuron_ids_index` and `function_followee_index`.
+    /// `principal_to_neuron_ids_index`, `function_followee_index` and
+    /// `topic_followee_index`.
     ///
     /// Preconditions:
     /// - the heap can still grow
@@ -926,13 +1005,19 @@ impl Governance {
             &neuron,
         );
 
+        GovernanceProto::add_neuron_to_topic_followee_index(
+            &mut self.topic_followee_index,
+            &neuron,
+        );
+
         self.proto.neurons.insert(neuron_id.to_string(), neuron);
 
         Ok(())
     }
 
     /// Removes a neuron from the list of neurons and updates the indices
-    /// `principal_to_neuron_ids_index` and `function_followee_index`.
+    /// `principal_to_neuron_ids_index`, `function_followee_index` and
+    /// `topic_followee_index`.
     ///
     /// Preconditions:
     /// - the given `neuron_id` exists in `self.proto.neurons`
@@ -961,6 +1046,11 @@ impl Governance {
             &neuron,
         );
 
+        GovernanceProto::remove_neuron_from_topic_followee_index(
+            &mut self.topic_followee_index,
+            &neuron,
+        );
+
         self.proto.neurons.remove(&neuron_id.to_string());
 
         Ok(())
@@ -1320,6 +1410,7 @@ impl Governance {
                 auto_stake_maturity: parent_neuron.auto_stake_maturity,
                 vesting_period_seconds: None,
                 disburse_maturity_in_progress: vec![],
+                topic_followees: parent_neuron.topic_followees.clone(),
             };
 
             // Add the child neuron's id to the set of neurons with ongoing operations.
@@ -1571,6 +1662,11 @@ impl Governance {
     /// account is used.
     /// The caller can choose a percentage of maturity to disburse.
     ///
+    /// The maturity is deducted from the neuron right away, but the tokens are
+    /// only minted once the disbursement is finalized by the periodic task,
+    /// `MATURITY_DISBURSEMENT_DELAY_SECONDS` later. The maturity modulation in
+    /// effect at that time is applied to the deducted amount.
+    ///
     /// Pre-conditions:
     /// - The neuron exists
     /// - The caller is authorized to perform this neuron operation
@@ -1623,29 +1719,12 @@ impl Governance {
             .checked_mul(disburse_maturity.percentage_to_disburse as u64)
             .expect("Overflow while processing maturity to disburse.")
             .checked_div(100)
-            .expect("Error when processing maturity to disburse.")
-            as u128;
-
-        // Modulate maturity_to_deduct. That is, multiply by 1 + X where
-        // X = maturity_modulation_basis_points / 10_000.
-        //
-        // From the fact that maturity_to_deduct is converted from u64 to u128,
-        // it should not be possible that any of the lines that look like they
-        // might panic at face value actually panic.
-        let maturity_to_disburse: u64 = u64::try_from(
-            maturity_to_deduct
-                .checked_mul(
-                    (BASIS_POINTS_PER_UNITY as i32 + maturity_modulation_basis_points)
-                        .try_into()
-                        .unwrap(),
-                )
-                .unwrap()
-                .checked_div(BASIS_POINTS_PER_UNITY as u128)
-                .unwrap(),
-        )
-        .expect("Couldn't convert maturity to u64");
+            .expect("Error when processing maturity to disburse.");
 
-        let maturity_to_deduct = maturity_to_deduct as u64;
+        // The modulation is applied again when the disbursement is finalized, so
+        // this is only an estimate of the amount that will be disbursed.
+        let maturity_to_disburse =
+            apply_maturity_modulation(maturity_to_deduct, maturity_modulation_basis_points);
 
         let transaction_fee_e8s = self.transaction_fee_e8s_or_panic();
         if maturity_to_disburse < transaction_fee_e8s {
@@ -1659,10 +1738,14 @@ impl Governance {
             ));
         }
 
+        let now_seconds = self.env.now();
         let disbursement_in_progress = DisburseMaturityInProgress {
-            amount_e8s: maturity_to_disburse,
-            timestamp_of_disbursement_seconds: self.env.now(),
+            amount_e8s: maturity_to_deduct,
+            timestamp_of_disbursement_seconds: now_seconds,
             account_to_disburse_to: Some(to_account_proto),
+            finalize_disbursement_timestamp_seconds: Some(
+                now_seconds + MATURITY_DISBURSEMENT_DELAY_SECONDS,
+            ),
         };
 
         // Re-borrow the neuron mutably to update now that the maturity has been
@@ -1677,6 +1760,7 @@ impl Governance {
 
         Ok(DisburseMaturityResponse {
             amount_disbursed_e8s: maturity_to_disburse,
+            amount_deducted_e8s: Some(maturity_to_deduct),
         })
     }
 
@@ -2098,6 +2182,15 @@ impl Governance {
             Action::TransferSnsTreasuryFunds(transfer) => {
                 self.perform_transfer_sns_treasury_funds(transfer).await
             }
+            Action::MintSnsTokens(mint) => self.perform_mint_sns_tokens(mint).await,
+            Action::ManageLedgerParameters(manage_ledger_parameters) => {
+                self.perform_manage_ledger_parameters(proposal_id, manage_ledger_parameters)
+                    .await
+            }
+            Action::ManageDappCanisterSettings(manage_dapp_canister_settings) => {
+                self.perform_manage_dapp_canister_settings(manage_dapp_canister_settings)
+                    .await
+            }
             // This should not be possible, because Proposal validation is performed when
             // a proposal is first made.
             Action::Unspecified(_) => Err(GovernanceError::new_with_message(
@@ -2295,6 +2388,65 @@ impl Governance {
             })
     }
 
+    /// Changes the settings of registered dapp canisters via root.
+    /// Root rejects the request if any of the canisters is not registered.
+    async fn perform_manage_dapp_canister_settings(
+        &self,
+        manage_dapp_canister_settings: ManageDappCanisterSettings,
+    ) -> Result<(), GovernanceError> {
+        let payload = candid::Encode!(&ManageDappCanisterSettingsRequest::from(
+            manage_dapp_canister_settings.clone()
+        ))
+        .map_err(|err| {
+            GovernanceError::new_with_message(
+                ErrorType::InvalidProposal,
+                format!("Could not encode ManageDappCanisterSettingsRequest: {err:?}"),
+            )
+        })?;
+        self.env
+            .call_canister(
+                self.proto.root_canister_id_or_panic(),
+                "manage_dapp_canister_settings",
+                payload,
+            )
+            .await
+            // Convert to return type.
+            .map_err(|err| {
+                GovernanceError::new_with_message(
+                    ErrorType::External,
+                    format!("Canister method call failed: {err:?}"),
+                )
+            })
+            // Make sure all canisters' settings were changed.
+            .and_then(
+                |reply| match candid::Decode!(&reply, ManageDappCanisterSettingsResponse) {
+                    Ok(ManageDappCanisterSettingsResponse {
+                        failure_reason: None,
+                    }) => {
+                        log!(
+                            INFO,
+                            "Changed the settings of the following dapp canisters: {:?}.",
+                            manage_dapp_canister_settings.canister_ids
+                        );
+                        Ok(())
+                    }
+                    Ok(ManageDappCanisterSettingsResponse {
+                        failure_reason: Some(failure_reason),
+                    }) => Err(GovernanceError::new_with_message(
+                        ErrorType::External,
+                        format!(
+                            "Failed to change the settings of dapp canisters {:?}: {}",
+                            manage_dapp_canister_settings.canister_ids, failure_reason
+                        ),
+                    )),
+                    Err(_) => Err(GovernanceError::new_with_message(
+                        ErrorType::External,
+                        "Could not decode ManageDappCanisterSettingsResponse".to_string(),
+                    )),
+                },
+            )
+    }
+
     // Make a change to the values of Sns Metadata
     fn perform_manage_sns_metadata(
         &mut self,
@@ -2661,6 +2813,244 @@ impl Governance {
         }
     }
 
+    /// Executes a MintSnsTokens proposal by making a minting transfer from the
+    /// governance canister's main account (which is the minting account of the
+    /// SNS ledger). Fails if the amount exceeds the caps defined by the nervous
+    /// system parameters, i.e., the share of the current total supply that can be
+    /// minted by a single proposal, or within a month (including this proposal).
+    async fn perform_mint_sns_tokens(
+        &mut self,
+        mint: MintSnsTokens,
+    ) -> Result<(), GovernanceError> {
+        let amount_e8s = mint.amount_e8s.ok_or_else(|| {
+            GovernanceError::new_with_message(
+                ErrorType::InvalidProposal,
+                "Expected MintSnsTokens to have an amount_e8s.",
+            )
+        })?;
+        let to = Account {
+            owner: mint
+                .to_principal
+                .expect("Expected mint to have a target principal")
+                .0,
+            subaccount: mint.to_subaccount.as_ref().map(|s| {
+                bytes_to_subaccount(&s.subaccount[..])
+                    .expect("Couldn't transform mint.to_subaccount to Subaccount")
+            }),
+        };
+
+        let total_supply_e8s = self
+            .ledger
+            .total_supply()
+            .await
+            .map_err(|e| {
+                GovernanceError::new_with_message(
+                    ErrorType::External,
+                    format!("Could not get the total supply of SNS tokens: {}", e),
+                )
+            })?
+            .get_e8s();
+        let parameters = self.nervous_system_parameters_or_panic();
+        let max_basis_points =
+            parameters.max_mint_sns_tokens_basis_points_of_total_supply_or_default();
+        let max_basis_points_per_month =
+            parameters.max_mint_sns_tokens_basis_points_of_total_supply_per_month_or_default();
+        let basis_points_of_total_supply = |basis_points: u64| {
+            (total_supply_e8s as u128 * basis_points as u128 / BASIS_POINTS_PER_UNITY as u128)
+                as u64
+        };
+
+        let max_amount_e8s = basis_points_of_total_supply(max_basis_points);
+        if amount_e8s > max_amount_e8s {
+            return Err(GovernanceError::new_with_message(
+                ErrorType::PreconditionFailed,
+                format!(
+                    "Cannot mint {} e8s, as at most {} e8s ({} basis points of the total \
+                     supply of {} e8s) can be minted by a single proposal.",
+                    amount_e8s, max_amount_e8s, max_basis_points, total_supply_e8s
+                ),
+            ));
+        }
+
+        // Mints that happened more than a month ago no longer count towards the
+        // monthly cap.
+        let now = self.env.now();
+        self.proto
+            .recent_sns_token_mints
+            .retain(|m| m.timestamp_seconds.saturating_add(ONE_MONTH_SECONDS) > now);
+        let minted_in_last_month_e8s: u64 = self
+            .proto
+            .recent_sns_token_mints
+            .iter()
+            .map(|m| m.amount_e8s)
+            .sum();
+        let max_amount_per_month_e8s = basis_points_of_total_supply(max_basis_points_per_month);
+        if minted_in_last_month_e8s.saturating_add(amount_e8s) > max_amount_per_month_e8s {
+            return Err(GovernanceError::new_with_message(
+                ErrorType::PreconditionFailed,
+                format!(
+                    "Cannot mint {} e8s, as {} e8s have already been minted within the last \
+                     month and at most {} e8s ({} basis points of the total supply of {} e8s) \
+                     can be minted within a month.",
+                    amount_e8s,
+                    minted_in_last_month_e8s,
+                    max_amount_per_month_e8s,
+                    max_basis_points_per_month,
+                    total_supply_e8s
+                ),
+            ));
+        }
+
+        // Record the mint before making the transfer, so that mints of concurrently
+        // executed proposals are counted towards the monthly cap.
+        let sns_token_mint = SnsTokenMint {
+            timestamp_seconds: now,
+            amount_e8s,
+        };
+        self.proto
+            .recent_sns_token_mints
+            .push(sns_token_mint.clone());
+
+        #[rustfmt::skip]
+        let result = self
+            .ledger
+            .transfer_funds(
+                amount_e8s,
+                0, // Minting transfer don't pay a fee
+                None, // This is a minting transfer, no 'from' account is needed
+                to,
+                mint.memo.unwrap_or(0),
+            )
+            .await;
+        result.map(|_| ()).map_err(|e| {
+            // The tokens were not minted, so they must not count towards the cap.
+            if let Some(index) = self
+                .proto
+                .recent_sns_token_mints
+                .iter()
+                .position(|m| *m == sns_token_mint)
+            {
+                self.proto.recent_sns_token_mints.remove(index);
+            }
+            GovernanceError::new_with_message(
+                ErrorType::External,
+                format!("Error minting SNS tokens: {}", e),
+            )
+        })
+    }
+
+    /// Executes a ManageLedgerParameters proposal by upgrading the SNS ledger
+    /// (through root) to its currently deployed wasm, with upgrade arguments
+    /// that contain the new ledger parameters.
+    async fn perform_manage_ledger_parameters(
+        &mut self,
+        proposal_id: u64,
+        manage_ledger_parameters: ManageLedgerParameters,
+    ) -> Result<(), GovernanceError> {
+        err_if_another_upgrade_is_in_progress(&self.proto.proposals, proposal_id)?;
+
+        let current_version = self.proto.deployed_version.clone().ok_or_else(|| {
+            GovernanceError::new_with_message(
+                ErrorType::PreconditionFailed,
+                "Could not execute proposal: no version is set in Governance.",
+            )
+        })?;
+        let ledger_wasm = get_wasm(
+            &*self.env,
+            current_version.ledger_wasm_hash,
+            SnsCanisterType::Ledger,
+        )
+        .await
+        .map_err(|e| {
+            GovernanceError::new_with_message(
+                ErrorType::External,
+                format!("Could not execute proposal: {}", e),
+            )
+        })?
+        .wasm;
+
+        let ManageLedgerParameters {
+            transfer_fee,
+            token_name,
+            token_symbol,
+            token_logo,
+        } = manage_ledger_parameters;
+        // The ledger replaces all of its metadata on upgrade, so the new logo is
+        // merged into the existing metadata.
+        let metadata = match token_logo {
+            Some(token_logo) => Some(self.ledger_metadata_with_logo(token_logo).await?),
+            None => None,
+        };
+        let ledger_upgrade_arg = LedgerArgument::Upgrade(Some(LedgerUpgradeArgs {
+            metadata,
+            token_name,
+            token_symbol,
+            transfer_fee,
+        }));
+
+        self.upgrade_non_root_canister(
+            self.proto.ledger_canister_id_or_panic(),
+            ledger_wasm,
+            Encode!(&ledger_upgrade_arg).unwrap(),
+            CanisterInstallMode::Upgrade,
+        )
+        .await?;
+
+        // Governance pays the ledger's transfer fee (e.g., when disbursing neurons),
+        // so it must use the new fee from now on.
+        if let Some(transfer_fee) = transfer_fee {
+            if let Some(parameters) = self.proto.parameters.as_mut() {
+                parameters.transaction_fee_e8s = Some(transfer_fee);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the SNS ledger's current (stored) metadata, with the logo set to
+    /// `token_logo`.
+    async fn ledger_metadata_with_logo(
+        &self,
+        token_logo: String,
+    ) -> Result<Vec<(String, MetadataValue)>, GovernanceError> {
+        let response = self
+            .env
+            .call_canister(
+                self.proto.ledger_canister_id_or_panic(),
+                "icrc1_metadata",
+                Encode!().unwrap(),
+            )
+            .await
+            .map_err(|(code, message)| {
+                GovernanceError::new_with_message(
+                    ErrorType::External,
+                    format!(
+                        "Could not get the metadata of the SNS ledger. Code: {:?}, message: {}",
+                        code, message
+                    ),
+                )
+            })?;
+        let mut metadata = Decode!(&response, Vec<(String, MetadataValue)>).map_err(|e| {
+            GovernanceError::new_with_message(
+                ErrorType::External,
+                format!("Could not decode the metadata of the SNS ledger: {}", e),
+            )
+        })?;
+
+        // The derived entries are not part of the ledger's stored metadata and
+        // must not be passed back to it.
+        metadata.retain(|(key, _)| !LEDGER_DERIVED_METADATA_KEYS.contains(&key.as_str()));
+        let logo = MetadataValue::Text(token_logo);
+        match metadata
+            .iter_mut()
+            .find(|(key, _)| key == LEDGER_METADATA_LOGO_KEY)
+        {
+            Some((_, value)) => *value = logo,
+            None => metadata.push((LEDGER_METADATA_LOGO_KEY.to_string(), logo)),
+        }
+        Ok(metadata)
+    }
+
     // Returns an option with the NervousSystemParameters
     fn nervous_system_parameters(&self) -> Option<&NervousSystemParameters> {
         self.proto.parameters.as_ref()
@@ -2969,6 +3359,11 @@ impl Governance {
             // Compute whether the proposal is eligible for rewards
             let is_eligible_for_rewards =
                 self.voting_rewards_parameters_or_panic().rewards_enabled();
+            let topic = topic_for_function_id(
+                u64::from(action),
+                &self.proto.id_to_nervous_system_functions,
+            );
+
             // Create the proposal.
             let mut proposal_data = ProposalData {
                 action: u64::from(action),
@@ -2982,6 +3377,7 @@ impl Governance {
                 is_eligible_for_rewards,
                 initial_voting_period_seconds,
                 wait_for_quiet_deadline_increase_seconds,
+                topic: topic.map(|topic| topic as i32),
                 // Writing these explicitly so that we have to make a conscious decision
                 // about what to do when adding a new field to `ProposalData`.
                 latest_tally: ProposalData::default().latest_tally,
@@ -3018,7 +3414,9 @@ impl Governance {
                 proposer_id,
                 Vote::Yes,
                 function_id,
+                topic,
                 &self.function_followee_index,
+                &self.topic_followee_index,
                 &self.proto.neurons,
                 now_seconds,
                 &mut proposal_data.ballots,
@@ -3034,7 +3432,8 @@ impl Governance {
     /// Registers the vote `vote_of_neuron` for the neuron `voting_neuron_id`
     /// and cascades voting according to the following relationship given in
     /// function_followee_index that (for each action) maps a followee to
-    /// the set of followers.
+    /// the set of followers, and in topic_followee_index that does the same
+    /// for each topic.
     ///
     /// This method should only be called with `vote_of_neuron` being `yes`
     /// or `no`.
@@ -3043,7 +3442,9 @@ impl Governance {
         voting_neuron_id: &NeuronId,
         vote_of_neuron: Vote,
         function_id: u64,
+        topic: Option<Topic>,
         function_followee_index: &BTreeMap<u64, BTreeMap<String, BTreeSet<NeuronId>>>,
+        topic_followee_index: &BTreeMap<i32, BTreeMap<String, BTreeSet<NeuronId>>>,
         neurons: &BTreeMap<String, Neuron>,
         now_seconds: u64,
         ballots: &mut BTreeMap<String, Ballot>, // This is ultimately what gets changed.
@@ -3052,7 +3453,9 @@ impl Governance {
         // voted on.
         let unspecified_function_id = u64::from(&Action::Unspecified(Empty {}));
         assert!(function_id != unspecified_function_id);
-        // The follow graph is the union of these two "successor list" tables.
+        // The follow graph is the union of these three "successor list" tables.
+        // Which of them actually sways a follower is decided by
+        // Neuron::would_follow_ballots.
         let empty_neuron_id_to_follower_neuron_ids = BTreeMap::new();
         let neuron_id_to_follower_neuron_ids_on_function = function_followee_index
             .get(&function_id)
@@ -3060,6 +3463,9 @@ impl Governance {
         let neuron_id_to_blanket_follower_neuron_ids = function_followee_index
             .get(&unspecified_function_id)
             .unwrap_or(&empty_neuron_id_to_follower_neuron_ids);
+        let neuron_id_to_follower_neuron_ids_on_topic = topic
+            .and_then(|topic| topic_followee_index.get(&(topic as i32)))
+            .unwrap_or(&empty_neuron_id_to_follower_neuron_ids);
 
         // Traverse the follow graph using breadth first search (BFS).
 
@@ -3112,11 +3518,16 @@ impl Governance {
                     .get(current_neuron_id)
                     .cloned()
                     .unwrap_or_else(|| BTreeSet::new());
+                let mut topic_follower_neuron_ids = neuron_id_to_follower_neuron_ids_on_topic
+                    .get(current_neuron_id)
+                    .cloned()
+                    .unwrap_or_else(|| BTreeSet::new());
                 let mut blanket_follower_neuron_ids = neuron_id_to_blanket_follower_neuron_ids
                     .get(current_neuron_id)
                     .cloned()
                     .unwrap_or_else(|| BTreeSet::new());
                 follower_neuron_ids.append(&mut specific_follower_neuron_ids);
+                follower_neuron_ids.append(&mut topic_follower_neuron_ids);
                 follower_neuron_ids.append(&mut blanket_follower_neuron_ids);
             }
 
@@ -3143,7 +3554,8 @@ impl Governance {
                     }
                 };
 
-                let follower_vote = follower_neuron.would_follow_ballots(function_id, ballots);
+                let follower_vote =
+                    follower_neuron.would_follow_ballots(function_id, topic, ballots);
                 if follower_vote != Vote::Unspecified {
                     // follower_neuron would be swayed by its followees!
                     //
@@ -3240,12 +3652,18 @@ impl Governance {
 
             // Update ballots.
             let function_id = u64::from(action);
+            // Proposals made before topics were introduced have no topic.
+            let topic = proposal.topic.and_then(Topic::from_i32).or_else(|| {
+                topic_for_function_id(function_id, &self.proto.id_to_nervous_system_functions)
+            });
             Governance::cast_vote_and_cascade_follow(
                 proposal_id,
                 neuron_id,
                 vote,
                 function_id,
+                topic,
                 &self.function_followee_index,
+                &self.topic_followee_index,
                 &self.proto.neurons,
                 now_seconds,
                 &mut proposal.ballots,
@@ -3264,6 +3682,9 @@ impl Governance {
     /// current list of followees for the given function_id with the
     /// provided list. Note that the list is replaced, not added to.
     ///
+    /// If a topic is specified, the same is done for the followees of that
+    /// topic instead (see `follow_topic`).
+    ///
     /// Preconditions:
     /// - the follower neuron exists
     /// - the caller has the permission to change followers (same authorization
@@ -3307,6 +3728,31 @@ impl Governance {
                 ));
             }
 
+            if let Some(topic) = f.topic {
+                let topic = Topic::from_i32(topic)
+                    .filter(|topic| *topic != Topic::Unspecified)
+                    .ok_or_else(|| {
+                        GovernanceError::new_with_message(
+                            ErrorType::InvalidCommand,
+                            format!("Invalid topic: {}", topic),
+                        )
+                    })?;
+                if f.function_id != u64::from(&Action::Unspecified(Empty {})) {
+                    return Err(GovernanceError::new_with_message(
+                        ErrorType::InvalidCommand,
+                        "A Follow command must not specify both a topic and a function_id.",
+                    ));
+                }
+                Self::follow_topic(
+                    &mut self.topic_followee_index,
+                    neuron,
+                    id,
+                    topic,
+                    &f.followees,
+                );
+                return Ok(());
+            }
+
             if !is_registered_function_id(f.function_id, &self.proto.id_to_nervous_system_functions)
             {
                 return Err(GovernanceError::new_with_message(
@@ -3369,6 +3815,52 @@ impl Governance {
         })
     }
 
+    /// Replaces the followees of `neuron` (whose id is `id`) for `topic` with
+    /// `followees`, keeping `topic_followee_index` up to date. An empty list
+    /// of followees removes the neuron's following on the topic.
+    fn follow_topic(
+        topic_followee_index: &mut BTreeMap<i32, BTreeMap<String, BTreeSet<NeuronId>>>,
+        neuron: &mut Neuron,
+        id: &NeuronId,
+        topic: Topic,
+        followees: &[NeuronId],
+    ) {
+        let topic = topic as i32;
+
+        // First, remove the current followees for this neuron and this topic
+        // from the index.
+        if let Some(neuron_followees) = neuron.topic_followees.get(&topic) {
+            if let Some(followee_index) = topic_followee_index.get_mut(&topic) {
+                for followee in &neuron_followees.followees {
+                    if let Some(all_followers) = followee_index.get_mut(&followee.to_string()) {
+                        all_followers.remove(id);
+                    }
+                }
+            }
+        }
+
+        if followees.is_empty() {
+            neuron.topic_followees.remove(&topic);
+            return;
+        }
+
+        neuron.topic_followees.insert(
+            topic,
+            Followees {
+                followees: followees.to_vec(),
+            },
+        );
+        let cache = topic_followee_index
+            .entry(topic)
+            .or_insert_with(BTreeMap::new);
+        for followee in followees {
+            cache
+                .entry(followee.to_string())
+                .or_insert_with(BTreeSet::new)
+                .insert(id.clone());
+        }
+    }
+
     /// Configures a given neuron (specified by the given neuron id).
     /// Specifically, this allows to stop and start dissolving a neuron
     /// as well as to increase a neuron's dissolve delay.
@@ -3551,6 +4043,7 @@ impl Governance {
             auto_stake_maturity: None,
             vesting_period_seconds: None,
             disburse_maturity_in_progress: vec![],
+            topic_followees: BTreeMap::new(),
         };
 
         // This also verifies that there are not too many neurons already.
@@ -3703,8 +4196,9 @@ impl Governance {
                 source_nns_neuron_id: neuron_parameter.source_nns_neuron_id,
                 staked_maturity_e8s_equivalent: None,
                 auto_stake_maturity: neuron_parameter.construct_auto_staking_maturity(),
-                vesting_period_seconds: None,
+                vesting_period_seconds: neuron_parameter.vesting_period_seconds,
                 disburse_maturity_in_progress: vec![],
+                topic_followees: BTreeMap::new(),
             };
 
             // Add the neuron to the various data structures and indexes to support neurons. This
@@ -4116,9 +4610,21 @@ impl Governance {
         if !self.can_finalize_disburse_maturity() {
             return;
         }
+        let maturity_modulation_basis_points =
+            match self.proto.effective_maturity_modulation_basis_points() {
+                Ok(maturity_modulation_basis_points) => maturity_modulation_basis_points,
+                Err(e) => {
+                    log!(
+                        ERROR,
+                        "Unable to determine the maturity modulation, not finalizing \
+                         any maturity disbursements: {}",
+                        e
+                    );
+                    return;
+                }
+            };
         self.proto.is_finalizing_disburse_maturity = Some(true);
         let now_seconds = self.env.now();
-        let disbursal_delay_elapsed_seconds = now_seconds - SEVEN_DAYS_IN_SECONDS;
         // Filter all the neurons that have some disbursing maturity in progress.
         let neurons_with_disbursal: Vec<Neuron> = self
             .proto
@@ -4131,7 +4637,11 @@ impl Governance {
             if !neuron.disburse_maturity_in_progress.is_empty() {
                 // The first entry is the oldest one, check whether it can be completed.
                 let d = neuron.disburse_maturity_in_progress[0].clone();
-                if d.timestamp_of_disbursement_seconds < disbursal_delay_elapsed_seconds {
+                let finalize_disbursement_timestamp_seconds =
+                    d.finalize_disbursement_timestamp_seconds.unwrap_or(
+                        d.timestamp_of_disbursement_seconds + MATURITY_DISBURSEMENT_DELAY_SECONDS,
+                    );
+                if finalize_disbursement_timestamp_seconds < now_seconds {
                     let neuron_id = match neuron.id.as_ref() {
                         None => {
                             log!(ERROR, "NeuronId is not set for neuron. This should never happen. Cannot disburse.");
@@ -4139,8 +4649,16 @@ impl Governance {
                         }
                         Some(id) => id,
                     };
-                    // TODO(NNS1-1708) add modulation
-                    let maturity_to_disburse_after_modulation_e8s = d.amount_e8s;
+                    // Entries without a finalization timestamp were created when the
+                    // modulation was applied at the time of the request.
+                    let maturity_to_disburse_after_modulation_e8s = if d
+                        .finalize_disbursement_timestamp_seconds
+                        .is_some()
+                    {
+                        apply_maturity_modulation(d.amount_e8s, maturity_modulation_basis_points)
+                    } else {
+                        d.amount_e8s
+                    };
                     let fdm = FinalizeDisburseMaturity {
                         amount_to_be_disbursed_e8s: maturity_to_disburse_after_modulation_e8s,
                         to_account: d.account_to_disburse_to.clone(),
@@ -5160,9 +5678,10 @@ fn err_if_another_upgrade_is_in_progress(
     id_to_proposal_data: &BTreeMap</* proposal ID */ u64, ProposalData>,
     executing_proposal_id: u64,
 ) -> Result<(), GovernanceError> {
-    let upgrade_action_ids: [u64; 2] = [
+    let upgrade_action_ids: [u64; 3] = [
         (&Action::UpgradeSnsControlledCanister(UpgradeSnsControlledCanister::default())).into(),
         (&Action::UpgradeSnsToNextVersion(UpgradeSnsToNextVersion::default())).into(),
+        (&Action::ManageLedgerParameters(ManageLedgerParameters::default())).into(),
     ];
 
     for (other_proposal_id, proposal_data) in id_to_proposal_data {
@@ -5239,6 +5758,26 @@ fn get_neuron_id_from_memo_and_controller(
     ))
 }
 
+/// Modulates `amount_e8s`. That is, multiplies it by 1 + X where
+/// X = maturity_modulation_basis_points / 10_000.
+fn apply_maturity_modulation(amount_e8s: u64, maturity_modulation_basis_points: i32) -> u64 {
+    // From the fact that amount_e8s is converted from u64 to u128, it should not be
+    // possible that any of the lines that look like they might panic at face value
+    // actually panic.
+    u64::try_from(
+        (amount_e8s as u128)
+            .checked_mul(
+                (BASIS_POINTS_PER_UNITY as i32 + maturity_modulation_basis_points)
+                    .try_into()
+                    .unwrap(),
+            )
+            .unwrap()
+            .checked_div(BASIS_POINTS_PER_UNITY as u128)
+            .unwrap(),
+    )
+    .expect("Couldn't convert maturity to u64")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -5641,6 +6180,7 @@ mod tests {
                         target_method_name: Some("test_method".to_string()),
                         validator_canister_id: Some(CanisterId::from_u64(1).get()),
                         validator_method_name: Some("test_validator_method".to_string()),
+                        topic: None,
                     },
                 )),
             },
@@ -8278,12 +8818,378 @@ mod tests {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(CanisterId::from(100).get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
         assert_is_ok!(governance.perform_add_generic_nervous_system_function(valid));
     }
 
+    type RecordedTransfer = (u64, u64, Option<Subaccount>, Account, u64);
+
+    /// A ledger with a fixed total supply that records all transfers.
+    struct RecordingLedger {
+        total_supply_e8s: u64,
+        transfers: Arc<Mutex<Vec<RecordedTransfer>>>,
+    }
+
+    #[async_trait]
+    impl ICRC1Ledger for RecordingLedger {
+        async fn transfer_funds(
+            &self,
+            amount_e8s: u64,
+            fee_e8s: u64,
+            from_subaccount: Option<Subaccount>,
+            to: Account,
+            memo: u64,
+        ) -> Result<u64, NervousSystemError> {
+            self.transfers
+                .lock()
+                .unwrap()
+                .push((amount_e8s, fee_e8s, from_subaccount, to, memo));
+            Ok(0)
+        }
+
+        async fn total_supply(&self) -> Result<Tokens, NervousSystemError> {
+            Ok(Tokens::from_e8s(self.total_supply_e8s))
+        }
+
+        async fn account_balance(&self, _account: Account) -> Result<Tokens, NervousSystemError> {
+            unimplemented!()
+        }
+
+        fn canister_id(&self) -> CanisterId {
+            *TEST_LEDGER_CANISTER_ID
+        }
+    }
+
+    fn governance_with_recording_ledger(
+        total_supply_e8s: u64,
+    ) -> (Governance, Arc<Mutex<Vec<RecordedTransfer>>>) {
+        let transfers = Arc::new(Mutex::new(vec![]));
+        let governance = Governance::new(
+            basic_governance_proto().try_into().unwrap(),
+            Box::<NativeEnvironment>::default(),
+            Box::new(RecordingLedger {
+                total_supply_e8s,
+                transfers: Arc::clone(&transfers),
+            }),
+            Box::new(DoNothingLedger {}),
+            Box::new(FakeCmc::new()),
+        );
+        (governance, transfers)
+    }
+
+    #[test]
+    fn test_mint_sns_tokens_makes_minting_transfer_to_target_account() {
+        let (mut governance, transfers) = governance_with_recording_ledger(1_000 * E8);
+        let to_principal = PrincipalId::new_user_test_id(42);
+
+        let result = governance
+            .perform_mint_sns_tokens(MintSnsTokens {
+                amount_e8s: Some(100 * E8),
+                memo: Some(7),
+                to_principal: Some(to_principal),
+                to_subaccount: Some(crate::pb::v1::Subaccount {
+                    subaccount: vec![1; 32],
+                }),
+            })
+            .now_or_never()
+            .unwrap();
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            *transfers.lock().unwrap(),
+            vec![(
+                100 * E8,
+                0,
+                None,
+                Account {
+                    owner: to_principal.0,
+                    subaccount: Some([1; 32]),
+                },
+                7
+            )]
+        );
+    }
+
+    #[test]
+    fn test_mint_sns_tokens_fails_if_amount_exceeds_cap() {
+        let (mut governance, transfers) = governance_with_recording_ledger(1_000 * E8);
+
+        let result = governance
+            .perform_mint_sns_tokens(MintSnsTokens {
+                amount_e8s: Some(100 * E8 + 1),
+                memo: None,
+                to_principal: Some(PrincipalId::new_user_test_id(42)),
+                to_subaccount: None,
+            })
+            .now_or_never()
+            .unwrap();
+
+        assert_matches!(
+            result,
+            Err(GovernanceError { error_type, error_message })
+                if error_type == ErrorType::PreconditionFailed as i32
+                    && error_message.contains("at most 10000000000 e8s")
+        );
+        assert!(transfers.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_mint_sns_tokens_fails_if_monthly_cap_would_be_exceeded() {
+        let env = NativeEnvironment::default();
+        let now = env.now;
+        let transfers = Arc::new(Mutex::new(vec![]));
+        let mut governance = Governance::new(
+            GovernanceProto {
+                recent_sns_token_mints: vec![
+                    // Minted more than a month ago, so no longer counted.
+                    SnsTokenMint {
+                        timestamp_seconds: now - ONE_MONTH_SECONDS,
+                        amount_e8s: 200 * E8,
+                    },
+                    SnsTokenMint {
+                        timestamp_seconds: now - 1,
+                        amount_e8s: 150 * E8,
+                    },
+                ],
+                ..basic_governance_proto()
+            }
+            .try_into()
+            .unwrap(),
+            Box::new(env),
+            Box::new(RecordingLedger {
+                total_supply_e8s: 1_000 * E8,
+                transfers: Arc::clone(&transfers),
+            }),
+            Box::new(DoNothingLedger {}),
+            Box::new(FakeCmc::new()),
+        );
+        let mint = |amount_e8s| MintSnsTokens {
+            amount_e8s: Some(amount_e8s),
+            memo: None,
+            to_principal: Some(PrincipalId::new_user_test_id(42)),
+            to_subaccount: None,
+        };
+
+        // By default, at most 2000 basis points of the total supply can be minted
+        // within a month, i.e., 200 tokens, of which 150 have already been minted.
+        let result = governance
+            .perform_mint_sns_tokens(mint(50 * E8 + 1))
+            .now_or_never()
+            .unwrap();
+        assert_matches!(
+            result,
+            Err(GovernanceError { error_type, error_message })
+                if error_type == ErrorType::PreconditionFailed as i32
+                    && error_message.contains("15000000000 e8s have already been minted")
+        );
+        assert!(transfers.lock().unwrap().is_empty());
+
+        let result = governance
+            .perform_mint_sns_tokens(mint(50 * E8))
+            .now_or_never()
+            .unwrap();
+        assert_eq!(result, Ok(()));
+        assert_eq!(transfers.lock().unwrap().len(), 1);
+        assert_eq!(
+            governance.proto.recent_sns_token_mints,
+            vec![
+                SnsTokenMint {
+                    timestamp_seconds: now - 1,
+                    amount_e8s: 150 * E8,
+                },
+                SnsTokenMint {
+                    timestamp_seconds: now,
+                    amount_e8s: 50 * E8,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_manage_ledger_parameters_upgrades_ledger_and_updates_transaction_fee() {
+        let root_canister_id = *TEST_ROOT_CANISTER_ID;
+        let ledger_canister_id = *TEST_LEDGER_CANISTER_ID;
+        let current_version = SnsVersion {
+            root_wasm_hash: vec![1, 2, 3],
+            governance_wasm_hash: vec![2, 3, 4],
+            ledger_wasm_hash: vec![3, 4, 5],
+            swap_wasm_hash: vec![4, 5, 6],
+            archive_wasm_hash: vec![5, 6, 7],
+            index_wasm_hash: vec![6, 7, 8],
+        };
+        let ledger_wasm = vec![9, 8, 7, 6, 5, 4, 3, 2];
+        let token_logo = "data:image/png;base64,aGVsbG8gZnJvbSBkZmluaXR5IQ==".to_string();
+
+        let mut env = NativeEnvironment::new(Some(*TEST_GOVERNANCE_CANISTER_ID));
+        env.default_canister_call_response =
+            Err((Some(1), "Oh no something was not covered!".to_string()));
+        env.set_call_canister_response(
+            SNS_WASM_CANISTER_ID,
+            "get_wasm",
+            Encode!(&GetWasmRequest {
+                hash: current_version.ledger_wasm_hash.clone()
+            })
+            .unwrap(),
+            Ok(Encode!(&GetWasmResponse {
+                wasm: Some(SnsWasm {
+                    wasm: ledger_wasm.clone(),
+                    canister_type: SnsCanisterType::Ledger.into(),
+                })
+            })
+            .unwrap()),
+        );
+        // The new logo replaces the existing one, the other stored metadata is kept
+        // and the metadata that the ledger derives from its other fields is dropped.
+        env.set_call_canister_response(
+            ledger_canister_id,
+            "icrc1_metadata",
+            Encode!().unwrap(),
+            Ok(Encode!(&vec![
+                MetadataValue::entry(LEDGER_METADATA_LOGO_KEY, "data:image/png;base64,b2xk"),
+                MetadataValue::entry("icrc1:decimals", 8_u64),
+                MetadataValue::entry("icrc1:name", "Token"),
+                MetadataValue::entry("icrc1:symbol", "OLD"),
+                MetadataValue::entry("icrc1:fee", 10_000_u64),
+                MetadataValue::entry("icrc1:max_memo_length", 32_u64),
+                MetadataValue::entry("custom:website", "https://example.com"),
+            ])
+            .unwrap()),
+        );
+        let expected_ledger_upgrade_arg = LedgerArgument::Upgrade(Some(LedgerUpgradeArgs {
+            metadata: Some(vec![
+                MetadataValue::entry(LEDGER_METADATA_LOGO_KEY, token_logo.clone()),
+                MetadataValue::entry("custom:website", "https://example.com"),
+            ]),
+            token_name: None,
+            token_symbol: Some("NEW".to_string()),
+            transfer_fee: Some(42),
+        }));
+        env.require_call_canister_invocation(
+            root_canister_id,
+            "change_canister",
+            Encode!(&ChangeCanisterProposal::new(
+                true,
+                CanisterInstallMode::Upgrade,
+                ledger_canister_id
+            )
+            .with_wasm(ledger_wasm)
+            .with_arg(Encode!(&expected_ledger_upgrade_arg).unwrap()))
+            .unwrap(),
+            Some(Ok(Encode!().unwrap())),
+        );
+        let assert_required_calls = env.get_assert_required_calls_fn();
+
+        let mut governance = Governance::new(
+            GovernanceProto {
+                root_canister_id: Some(root_canister_id.get()),
+                ledger_canister_id: Some(ledger_canister_id.get()),
+                deployed_version: Some(current_version.into()),
+                ..basic_governance_proto()
+            }
+            .try_into()
+            .unwrap(),
+            Box::new(env),
+            Box::new(DoNothingLedger {}),
+            Box::new(DoNothingLedger {}),
+            Box::new(FakeCmc::new()),
+        );
+
+        let result = governance
+            .perform_manage_ledger_parameters(
+                1,
+                ManageLedgerParameters {
+                    transfer_fee: Some(42),
+                    token_name: None,
+                    token_symbol: Some("NEW".to_string()),
+                    token_logo: Some(token_logo),
+                },
+            )
+            .now_or_never()
+            .unwrap();
+
+        assert_eq!(result, Ok(()));
+        assert_required_calls();
+        assert_eq!(governance.transaction_fee_e8s_or_panic(), 42);
+    }
+
+    #[test]
+    fn test_manage_dapp_canister_settings_calls_root_and_reports_failures() {
+        let root_canister_id = *TEST_ROOT_CANISTER_ID;
+        let manage_dapp_canister_settings = ManageDappCanisterSettings {
+            canister_ids: vec![
+                PrincipalId::new_user_test_id(1),
+                PrincipalId::new_user_test_id(2),
+            ],
+            compute_allocation: None,
+            memory_allocation: Some(1_000_000),
+            freezing_threshold: Some(2_592_000),
+        };
+        let expected_request = Encode!(&ManageDappCanisterSettingsRequest {
+            canister_ids: manage_dapp_canister_settings.canister_ids.clone(),
+            compute_allocation: None,
+            memory_allocation: Some(1_000_000),
+            freezing_threshold: Some(2_592_000),
+        })
+        .unwrap();
+
+        for (root_response, expect_success) in [
+            (
+                ManageDappCanisterSettingsResponse {
+                    failure_reason: None,
+                },
+                true,
+            ),
+            (
+                ManageDappCanisterSettingsResponse {
+                    failure_reason: Some("Canister not found".to_string()),
+                },
+                false,
+            ),
+        ] {
+            let mut env = NativeEnvironment::new(Some(*TEST_GOVERNANCE_CANISTER_ID));
+            env.require_call_canister_invocation(
+                root_canister_id,
+                "manage_dapp_canister_settings",
+                expected_request.clone(),
+                Some(Ok(Encode!(&root_response).unwrap())),
+            );
+            let assert_required_calls = env.get_assert_required_calls_fn();
+
+            let governance = Governance::new(
+                GovernanceProto {
+                    root_canister_id: Some(root_canister_id.get()),
+                    ..basic_governance_proto()
+                }
+                .try_into()
+                .unwrap(),
+                Box::new(env),
+                Box::new(DoNothingLedger {}),
+                Box::new(DoNothingLedger {}),
+                Box::new(FakeCmc::new()),
+            );
+
+            let result = governance
+                .perform_manage_dapp_canister_settings(manage_dapp_canister_settings.clone())
+                .now_or_never()
+                .unwrap();
+
+            assert_required_calls();
+            if expect_success {
+                assert_eq!(result, Ok(()));
+            } else {
+                assert_matches!(
+                    result,
+                    Err(GovernanceError { error_type, error_message })
+                        if error_type == ErrorType::External as i32
+                            && error_message.contains("Canister not found")
+                );
+            }
+        }
+    }
+
     fn default_governance_with_proto(governance_proto: GovernanceProto) -> Governance {
         Governance::new(
             governance_proto
@@ -8859,6 +9765,54 @@ mod tests {
         assert_eq!(neuron.maturity_e8s_equivalent, remaining_maturity);
     }
 
+    #[test]
+    fn test_disburse_maturity_defers_maturity_modulation() {
+        // Step 1: Prepare the world and parameters.
+        let earned_maturity_e8s = 1_000_000;
+        let mut setup = prepare_setup_for_disburse_maturity_tests(earned_maturity_e8s);
+        setup.governance.proto.maturity_modulation = Some(MaturityModulation {
+            current_basis_points: Some(-200),
+            updated_at_timestamp_seconds: Some(1),
+        });
+
+        // Step 2: Run code under test.
+        let disburse_maturity = DisburseMaturity {
+            percentage_to_disburse: 100,
+            to_account: None,
+        };
+        let response = setup
+            .governance
+            .disburse_maturity(&setup.neuron_id, &setup.controller, &disburse_maturity)
+            .expect("Disburse maturity failed");
+
+        // Step 3: Inspect result(s).
+        assert_eq!(response.amount_disbursed_e8s, 980_000);
+        assert_eq!(response.amount_deducted_e8s, Some(earned_maturity_e8s));
+        let neuron = setup
+            .governance
+            .get_neuron_result(&setup.neuron_id)
+            .unwrap();
+        assert_eq!(neuron.maturity_e8s_equivalent, 0);
+        assert_eq!(neuron.disburse_maturity_in_progress.len(), 1);
+        // The modulation is only applied when the disbursement is finalized.
+        let in_progress = &neuron.disburse_maturity_in_progress[0];
+        assert_eq!(in_progress.amount_e8s, earned_maturity_e8s);
+        assert_eq!(
+            in_progress.finalize_disbursement_timestamp_seconds,
+            Some(
+                in_progress.timestamp_of_disbursement_seconds + MATURITY_DISBURSEMENT_DELAY_SECONDS
+            )
+        );
+    }
+
+    #[test]
+    fn test_apply_maturity_modulation() {
+        assert_eq!(apply_maturity_modulation(E8, 0), E8);
+        assert_eq!(apply_maturity_modulation(E8, 500), 105_000_000);
+        assert_eq!(apply_maturity_modulation(E8, -500), 95_000_000);
+        assert_eq!(apply_maturity_modulation(u64::MAX, 0), u64::MAX);
+    }
+
     #[test]
     fn test_disburse_maturity_fails_on_non_existing_neuron() {
         // Step 1: Prepare the world and parameters.
@@ -9261,6 +10215,7 @@ mod tests {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(CanisterId::from(1).get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
@@ -9283,6 +10238,7 @@ mod tests {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(invalid_canister_target.get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use candid::types::number::Nat;
-use candid::Principal;
+use candid::{CandidType, Principal};
 use dfn_candid::{ArgumentDecoder, ArgumentEncoder};
 use dfn_core::CanisterId;
 use ic_base_types::PrincipalId;
@@ -8,9 +8,44 @@ use ic_icrc1_client::{ICRC1Client, Runtime};
 use ic_ledger_core::{block::BlockIndex, Tokens};
 pub use ic_nervous_system_common::ledger::ICRC1Ledger;
 use ic_nervous_system_common::NervousSystemError;
+use icrc_ledger_types::icrc::generic_metadata_value::MetadataValue;
 use icrc_ledger_types::icrc1::account::{Account, Subaccount};
 use icrc_ledger_types::icrc1::transfer::{Memo, TransferArg};
 
+/// The metadata key under which the SNS ledger stores the token logo.
+pub const LEDGER_METADATA_LOGO_KEY: &str = "icrc1:logo";
+
+/// The metadata keys that the SNS ledger's `icrc1_metadata` derives from its
+/// other fields (and that are thus not part of its stored metadata).
+pub const LEDGER_DERIVED_METADATA_KEYS: [&str; 5] = [
+    "icrc1:decimals",
+    "icrc1:name",
+    "icrc1:symbol",
+    "icrc1:fee",
+    "icrc1:max_memo_length",
+];
+
+/// The argument passed to the SNS ledger canister when it is upgraded.
+///
+/// This mirrors (the upgrade part of) `ic_icrc1_ledger::LedgerArgument`, so
+/// that governance does not need to depend on the ledger canister crate.
+#[derive(CandidType, Clone, Debug, PartialEq, Eq)]
+pub enum LedgerArgument {
+    Upgrade(Option<LedgerUpgradeArgs>),
+}
+
+/// Mirrors (a subset of) `ic_icrc1_ledger::UpgradeArgs`. Fields with None values
+/// remain unchanged on the ledger.
+#[derive(CandidType, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LedgerUpgradeArgs {
+    /// Note that the ledger replaces all of its stored metadata with this value,
+    /// so it must contain the existing entries that are to be kept.
+    pub metadata: Option<Vec<(String, MetadataValue)>>,
+    pub token_name: Option<String>,
+    pub token_symbol: Option<String>,
+    pub transfer_fee: Option<u64>,
+}
+
 // A ICRC1 client runtime that uses dfn_* functionalities
 struct DfnRuntime {}
 
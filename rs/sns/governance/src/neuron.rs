@@ -3,7 +3,7 @@ use crate::pb::v1::neuron::DissolveState;
 use crate::pb::v1::proposal::Action;
 use crate::pb::v1::{
     manage_neuron, Ballot, Empty, GovernanceError, Neuron, NeuronId, NeuronPermission,
-    NeuronPermissionList, NeuronPermissionType, Vote,
+    NeuronPermissionList, NeuronPermissionType, Topic, Vote,
 };
 use ic_base_types::PrincipalId;
 use icrc_ledger_types::icrc1::account::Subaccount;
@@ -231,21 +231,29 @@ impl Neuron {
 
     /// Given the specified `ballots`, determine how the neuron would
     /// vote on a proposal of `action` based on which neurons this
-    /// neuron follows on this action (or on the default action if this
-    /// neuron doesn't specify any followees for `action`).
+    /// neuron follows on this action (or on the action's `topic` if this
+    /// neuron doesn't specify any followees for `action`, or on the default
+    /// action if it doesn't specify any followees for the topic either).
     pub(crate) fn would_follow_ballots(
         &self,
         action: u64,
+        topic: Option<Topic>,
         ballots: &BTreeMap<String, Ballot>,
     ) -> Vote {
         // Compute the list of followees for this action. If no
         // following is specified for the action, use the followees
-        // from the 'Unspecified' action.
+        // for its topic, and failing that, the followees from the
+        // 'Unspecified' action.
         let unspecified_key = u64::from(&Action::Unspecified(Empty {}));
         if let Some(followees) = self
             .followees
             .get(&(action))
             .filter(|followees| !followees.followees.is_empty())
+            .or_else(|| {
+                topic
+                    .and_then(|topic| self.topic_followees.get(&(topic as i32)))
+                    .filter(|followees| !followees.followees.is_empty())
+            })
             .or_else(|| self.followees.get(&unspecified_key))
             // extract plain vector from 'Followees' proto
             .map(|x| &x.followees)
@@ -1065,4 +1073,70 @@ mod tests {
         let voting_power_stake: u64 = neuron.voting_power_stake_e8s();
         assert_eq!(voting_power_stake, 100 - 10 + 50);
     }
+
+    /// Tests that following on a function takes precedence over following on
+    /// its topic, which takes precedence over catch-all following.
+    #[test]
+    fn test_would_follow_ballots_precedence() {
+        use crate::pb::v1::neuron::Followees;
+        use crate::types::native_action_ids;
+
+        let followee = |id: u8| NeuronId { id: vec![id] };
+        let followees = |id: u8| Followees {
+            followees: vec![followee(id)],
+        };
+        let ballots: BTreeMap<String, Ballot> = [(1, Vote::Yes), (2, Vote::No), (3, Vote::Yes)]
+            .into_iter()
+            .map(|(id, vote)| {
+                (
+                    followee(id).to_string(),
+                    Ballot {
+                        vote: vote as i32,
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+
+        let mut neuron = Neuron {
+            followees: BTreeMap::from([(native_action_ids::UNSPECIFIED, followees(1))]),
+            topic_followees: BTreeMap::from([(
+                Topic::TreasuryAssetManagement as i32,
+                followees(2),
+            )]),
+            ..Default::default()
+        };
+
+        // The topic's followees take precedence over the catch-all followees.
+        assert_eq!(
+            neuron.would_follow_ballots(
+                native_action_ids::MINT_SNS_TOKENS,
+                Some(Topic::TreasuryAssetManagement),
+                &ballots
+            ),
+            Vote::No
+        );
+        // Proposals of other topics fall back to the catch-all followees.
+        assert_eq!(
+            neuron.would_follow_ballots(
+                native_action_ids::MOTION,
+                Some(Topic::Governance),
+                &ballots
+            ),
+            Vote::Yes
+        );
+
+        // The function's followees take precedence over the topic's followees.
+        neuron
+            .followees
+            .insert(native_action_ids::MINT_SNS_TOKENS, followees(3));
+        assert_eq!(
+            neuron.would_follow_ballots(
+                native_action_ids::MINT_SNS_TOKENS,
+                Some(Topic::TreasuryAssetManagement),
+                &ballots
+            ),
+            Vote::Yes
+        );
+    }
 }
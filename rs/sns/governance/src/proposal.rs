@@ -7,18 +7,20 @@ use crate::pb::v1::proposal::Action;
 use crate::pb::v1::transfer_sns_treasury_funds::TransferFrom;
 use crate::pb::v1::{
     proposal, DeregisterDappCanisters, ExecuteGenericNervousSystemFunction, Governance,
-    ManageSnsMetadata, Motion, NervousSystemFunction, NervousSystemParameters, Proposal,
-    ProposalData, ProposalDecisionStatus, ProposalRewardStatus, RegisterDappCanisters, Tally,
-    TransferSnsTreasuryFunds, UpgradeSnsControlledCanister, UpgradeSnsToNextVersion, Vote,
+    ManageDappCanisterSettings, ManageLedgerParameters, ManageSnsMetadata, MintSnsTokens, Motion,
+    NervousSystemFunction, NervousSystemParameters, Proposal, ProposalData, ProposalDecisionStatus,
+    ProposalRewardStatus, RegisterDappCanisters, Tally, Topic, TransferSnsTreasuryFunds,
+    UpgradeSnsControlledCanister, UpgradeSnsToNextVersion, Vote,
 };
 
-use crate::sns_upgrade::{get_upgrade_params, UpgradeSnsParams};
-use crate::types::{Environment, DEFAULT_TRANSFER_FEE};
+use crate::sns_upgrade::{get_all_sns_canisters, get_upgrade_params, UpgradeSnsParams};
+use crate::types::{native_action_ids, Environment, DEFAULT_TRANSFER_FEE};
 use crate::{validate_chars_count, validate_len, validate_required_field};
 use dfn_core::api::CanisterId;
 use ic_base_types::PrincipalId;
 use ic_canister_log::log;
 use ic_crypto_sha::Sha256;
+use ic_nervous_system_common::BASIS_POINTS_PER_UNITY;
 use icp_ledger::DEFAULT_TRANSFER_FEE as NNS_DEFAULT_TRANSFER_FEE;
 use icrc_ledger_types::icrc1::account::Account;
 use std::collections::{BTreeMap, HashSet};
@@ -58,6 +60,27 @@ pub const MAX_NUMBER_OF_GENERIC_NERVOUS_SYSTEM_FUNCTIONS: usize = 200_000;
 /// RegisterDappCanisters proposal.
 pub const MAX_NUMBER_OF_DAPPS_TO_REGISTER_PER_PROPOSAL: usize = 1_000;
 
+/// The minimum share of the total voting power, in basis points, that must vote in
+/// favor of a critical proposal (see `ProposalData::is_critical`) for it to be adopted.
+pub const CRITICAL_PROPOSAL_MIN_YES_BASIS_POINTS_OF_TOTAL: u64 = 2_000;
+
+/// The minimum share of the exercised voting power (i.e., of the yes and no votes), in
+/// basis points, that must vote in favor of a critical proposal for it to be adopted.
+pub const CRITICAL_PROPOSAL_MIN_YES_BASIS_POINTS_OF_EXERCISED: u64 = 6_700;
+
+/// The minimum number of characters of a token name set by a ManageLedgerParameters
+/// proposal.
+pub const LEDGER_TOKEN_NAME_CHARS_MIN: usize = 4;
+/// The maximum number of characters of a token name set by a ManageLedgerParameters
+/// proposal.
+pub const LEDGER_TOKEN_NAME_CHARS_MAX: usize = 255;
+/// The minimum number of characters of a token symbol set by a ManageLedgerParameters
+/// proposal.
+pub const LEDGER_TOKEN_SYMBOL_CHARS_MIN: usize = 3;
+/// The maximum number of characters of a token symbol set by a ManageLedgerParameters
+/// proposal.
+pub const LEDGER_TOKEN_SYMBOL_CHARS_MAX: usize = 10;
+
 impl Proposal {
     /// Returns whether a proposal is allowed to be submitted when
     /// the heap growth potential is low.
@@ -238,6 +261,27 @@ pub async fn validate_and_render_action(
                 .unwrap_or(DEFAULT_TRANSFER_FEE.get_e8s());
             validate_and_render_transfer_sns_treasury_funds(transfer, sns_transfer_fee_e8s)
         }
+        proposal::Action::MintSnsTokens(mint) => {
+            let parameters = governance_proto.parameters.clone().unwrap_or_default();
+            validate_and_render_mint_sns_tokens(mint, &parameters)
+        }
+        proposal::Action::ManageLedgerParameters(manage_ledger_parameters) => {
+            validate_and_render_manage_ledger_parameters(
+                manage_ledger_parameters,
+                current_parameters,
+            )
+        }
+        proposal::Action::ManageDappCanisterSettings(manage_dapp_canister_settings) => {
+            let registered_dapp_canister_ids = get_all_sns_canisters(env, root_canister_id)
+                .await?
+                .dapps
+                .into_iter()
+                .collect::<HashSet<_>>();
+            validate_and_render_manage_dapp_canister_settings(
+                manage_dapp_canister_settings,
+                &registered_dapp_canister_ids,
+            )
+        }
     }
 }
 
@@ -362,6 +406,132 @@ fn validate_and_render_transfer_sns_treasury_funds(
     ))
 }
 
+/// Validates and renders a proposal with action MintSnsTokens.
+///
+/// The caps on the amount defined by the nervous system parameters depend on the
+/// total supply, and are therefore only enforced when the proposal is executed.
+fn validate_and_render_mint_sns_tokens(
+    mint: &MintSnsTokens,
+    parameters: &NervousSystemParameters,
+) -> Result<String, String> {
+    let mut defects: Vec<String> = vec![];
+
+    let amount_e8s = match mint.amount_e8s {
+        Some(0) => {
+            defects.push("The amount to mint must be greater than zero.".to_string());
+            0
+        }
+        Some(amount_e8s) => amount_e8s,
+        None => {
+            defects.push("Must specify the amount to mint.".to_string());
+            0
+        }
+    };
+
+    let to_principal = if let Some(to_principal) = mint.to_principal {
+        if to_principal == PrincipalId::new_anonymous() {
+            defects.push("Principal must not be anonymous.".to_string());
+        }
+        to_principal
+    } else {
+        defects.push("Must specify a principal to mint the tokens to.".to_string());
+        PrincipalId::new_anonymous()
+    };
+
+    let to_subaccount = match &mint.to_subaccount {
+        None => None,
+        Some(s) => match bytes_to_subaccount(&s.subaccount[..]) {
+            Ok(s) => Some(s),
+            Err(e) => {
+                defects.push(e.error_message);
+                None
+            }
+        },
+    };
+
+    // Generate final report.
+    if !defects.is_empty() {
+        return Err(format!(
+            "MintSnsTokens proposal was invalid for the following reason(s):\n{}",
+            defects.join("\n"),
+        ));
+    }
+
+    let to_account = Account {
+        owner: to_principal.0,
+        subaccount: to_subaccount,
+    };
+
+    Ok(format!(
+        r"# Proposal to mint SNS tokens:
+## Amount (e8s): {}
+## Target principal: {}
+## Target account: {}
+## Memo: {}
+## Cap: at most {} basis points of the total supply at the time of execution, and at most {} basis points within a month",
+        amount_e8s,
+        to_principal,
+        to_account,
+        mint.memo.unwrap_or(0),
+        parameters.max_mint_sns_tokens_basis_points_of_total_supply_or_default(),
+        parameters.max_mint_sns_tokens_basis_points_of_total_supply_per_month_or_default(),
+    ))
+}
+
+/// Validates and renders a proposal with action ManageLedgerParameters.
+fn validate_and_render_manage_ledger_parameters(
+    manage_ledger_parameters: &ManageLedgerParameters,
+    current_parameters: &NervousSystemParameters,
+) -> Result<String, String> {
+    let mut render = "# Proposal to change ledger parameters:\n".to_string();
+    let mut no_change = true;
+    if let Some(transfer_fee) = manage_ledger_parameters.transfer_fee {
+        // The transfer fee is subject to the same limits as the transaction fee
+        // of the nervous system parameters.
+        NervousSystemParameters {
+            transaction_fee_e8s: Some(transfer_fee),
+            ..Default::default()
+        }
+        .inherit_from(current_parameters)
+        .validate()?;
+        render += &format!("# New transfer fee (e8s): {}\n", transfer_fee);
+        no_change = false;
+    }
+    if let Some(token_name) = &manage_ledger_parameters.token_name {
+        validate_chars_count(
+            "token_name",
+            token_name,
+            LEDGER_TOKEN_NAME_CHARS_MIN,
+            LEDGER_TOKEN_NAME_CHARS_MAX,
+        )?;
+        render += &format!("# New token name: {}\n", token_name);
+        no_change = false;
+    }
+    if let Some(token_symbol) = &manage_ledger_parameters.token_symbol {
+        validate_chars_count(
+            "token_symbol",
+            token_symbol,
+            LEDGER_TOKEN_SYMBOL_CHARS_MIN,
+            LEDGER_TOKEN_SYMBOL_CHARS_MAX,
+        )?;
+        render += &format!("# New token symbol: {}\n", token_symbol);
+        no_change = false;
+    }
+    if let Some(token_logo) = &manage_ledger_parameters.token_logo {
+        SnsMetadata::validate_logo(token_logo)?;
+        render += &format!("# New token logo (base64 encoding): \n {}", token_logo);
+        no_change = false;
+    }
+    if no_change {
+        Err(
+            "Error: ManageLedgerParameters must change at least one value, all values are None"
+                .to_string(),
+        )
+    } else {
+        Ok(render)
+    }
+}
+
 /// Validates and renders a proposal with action UpgradeSnsControlledCanister.
 fn validate_and_render_upgrade_sns_controlled_canister(
     upgrade: &UpgradeSnsControlledCanister,
@@ -570,6 +740,7 @@ impl TryFrom<&NervousSystemFunction> for ValidGenericNervousSystemFunction {
                 target_method_name,
                 validator_canister_id,
                 validator_method_name,
+                topic,
             })) => {
                 // Validate the target_canister_id field.
                 let target_canister_id =
@@ -593,6 +764,12 @@ impl TryFrom<&NervousSystemFunction> for ValidGenericNervousSystemFunction {
                     defects.push("validator_method_name was empty.".to_string());
                 }
 
+                if let Some(topic) = topic {
+                    if Topic::from_i32(*topic).is_none() {
+                        defects.push(format!("topic ({topic}) is not a valid Topic."));
+                    }
+                }
+
                 if !defects.is_empty() {
                     return Err(format!(
                         "ExecuteNervousSystemFunction was invalid for the following reason(s):\n{}",
@@ -829,6 +1006,87 @@ fn validate_and_render_deregister_dapp_canisters(
     }
 }
 
+/// Validates and renders a proposal with action ManageDappCanisterSettings.
+///
+/// Every target canister must be registered with SNS root, i.e. be in
+/// `registered_dapp_canister_ids`. SNS root checks this again when the proposal
+/// is executed, since canisters may be deregistered in the meantime.
+fn validate_and_render_manage_dapp_canister_settings(
+    manage_dapp_canister_settings: &ManageDappCanisterSettings,
+    registered_dapp_canister_ids: &HashSet<PrincipalId>,
+) -> Result<String, String> {
+    let ManageDappCanisterSettings {
+        canister_ids,
+        compute_allocation,
+        memory_allocation,
+        freezing_threshold,
+    } = manage_dapp_canister_settings;
+
+    let mut defects = vec![];
+
+    if canister_ids.is_empty() {
+        defects.push("Must specify at least one canister id.".to_string());
+    }
+
+    if canister_ids.len() > MAX_NUMBER_OF_DAPPS_TO_REGISTER_PER_PROPOSAL {
+        defects.push(format!(
+            "Cannot specify more than {MAX_NUMBER_OF_DAPPS_TO_REGISTER_PER_PROPOSAL} canister ids."
+        ));
+    }
+
+    let unregistered_canister_ids = canister_ids
+        .iter()
+        .filter(|canister_id| !registered_dapp_canister_ids.contains(canister_id))
+        .map(|canister_id| canister_id.to_string())
+        .collect::<Vec<_>>();
+    if !unregistered_canister_ids.is_empty() {
+        defects.push(format!(
+            "The following canisters are not registered dapp canisters: {}",
+            unregistered_canister_ids.join(", ")
+        ));
+    }
+
+    if compute_allocation.is_none() && memory_allocation.is_none() && freezing_threshold.is_none() {
+        defects.push("Must specify at least one setting to change.".to_string());
+    }
+
+    if let Some(compute_allocation) = compute_allocation {
+        if *compute_allocation > 100 {
+            defects.push(format!(
+                "The compute allocation must be a percentage between 0 and 100, \
+                 but was {compute_allocation}."
+            ));
+        }
+    }
+
+    if !defects.is_empty() {
+        return Err(format!(
+            "ManageDappCanisterSettings proposal was invalid for the following reason(s):\n{}",
+            defects.join("\n"),
+        ));
+    }
+
+    let mut render = "# Proposal to change the settings of dapp canisters:\n".to_string();
+    if let Some(compute_allocation) = compute_allocation {
+        render += &format!("# New compute allocation (%): {compute_allocation}\n");
+    }
+    if let Some(memory_allocation) = memory_allocation {
+        render += &format!("# New memory allocation (bytes): {memory_allocation}\n");
+    }
+    if let Some(freezing_threshold) = freezing_threshold {
+        render += &format!("# New freezing threshold (seconds): {freezing_threshold}\n");
+    }
+    render += &format!(
+        "## Canisters:\n- {}",
+        canister_ids
+            .iter()
+            .map(|canister_id| canister_id.to_string())
+            .collect::<Vec<_>>()
+            .join("\n- ")
+    );
+    Ok(render)
+}
+
 // Validates and renders a proposal with action ManageSnsMetadata.
 pub fn validate_and_render_manage_sns_metadata(
     manage_sns_metadata: &ManageSnsMetadata,
@@ -865,6 +1123,41 @@ pub fn validate_and_render_manage_sns_metadata(
     }
 }
 
+/// Returns whether the yes votes of `tally` suffice for a proposal to be adopted,
+/// disregarding the voting power that has not voted (yet).
+fn tally_favors_yes(is_critical: bool, tally: &Tally) -> bool {
+    if is_critical {
+        let exercised = tally.yes as u128 + tally.no as u128;
+        tally.yes > 0
+            && tally.yes as u128 * BASIS_POINTS_PER_UNITY as u128
+                >= exercised * CRITICAL_PROPOSAL_MIN_YES_BASIS_POINTS_OF_EXERCISED as u128
+    } else {
+        tally.yes > tally.no
+    }
+}
+
+/// Returns whether the votes cast so far decide a proposal, no matter how the
+/// remaining voting power votes.
+fn is_decided_by_votes_cast(is_critical: bool, tally: &Tally) -> bool {
+    if is_critical {
+        // A critical proposal is adopted if the yes votes make up the required share
+        // of the exercised voting power even if all remaining voting power votes no,
+        // and rejected if they cannot reach it even if all remaining voting power
+        // votes yes.
+        let required =
+            tally.total as u128 * CRITICAL_PROPOSAL_MIN_YES_BASIS_POINTS_OF_EXERCISED as u128;
+        let yes = tally.yes as u128 * BASIS_POINTS_PER_UNITY as u128;
+        let max_yes = tally.total.saturating_sub(tally.no) as u128 * BASIS_POINTS_PER_UNITY as u128;
+        yes >= required || max_yes < required
+    } else {
+        // A proposal is adopted if strictly more than half of the votes are 'yes' and
+        // rejected if at least half of the votes are 'no'. The conditions are described
+        // as below to avoid overflow. In the absence of overflow, the below is equivalent
+        // to (2 * yes > total) || (2 * no >= total).
+        (tally.yes > tally.total - tally.yes) || (tally.no >= tally.total - tally.no)
+    }
+}
+
 impl ProposalData {
     /// Returns the proposal's decision status. See [ProposalDecisionStatus] in the SNS's
     /// proto for more information.
@@ -952,6 +1245,7 @@ impl ProposalData {
         old_tally: &Tally,
         new_tally: &Tally,
     ) {
+        let is_critical = self.is_critical();
         let wait_for_quiet_state = self
             .wait_for_quiet_state
             .as_mut()
@@ -962,22 +1256,13 @@ impl ProposalData {
         // and no are slightly different, because yes needs a majority to succeed, while
         // no only needs a tie.
         let current_deadline = wait_for_quiet_state.current_deadline_timestamp_seconds;
-        let deciding_amount_yes = new_tally.total / 2 + 1;
-        let deciding_amount_no = (new_tally.total + 1) / 2;
-        if new_tally.yes >= deciding_amount_yes
-            || new_tally.no >= deciding_amount_no
-            || now_seconds > current_deadline
-        {
+        if is_decided_by_votes_cast(is_critical, new_tally) || now_seconds > current_deadline {
             return;
         }
 
-        // Returns whether the tally result has turned, i.e. if the result now
+        // Checks whether the tally result has turned, i.e. if the result now
         // favors yes, but it used to favor no or vice versa.
-        fn vote_has_turned(old_tally: &Tally, new_tally: &Tally) -> bool {
-            (old_tally.yes > old_tally.no && new_tally.yes <= new_tally.no)
-                || (old_tally.yes <= old_tally.no && new_tally.yes > new_tally.no)
-        }
-        if !vote_has_turned(old_tally, new_tally) {
+        if tally_favors_yes(is_critical, old_tally) == tally_favors_yes(is_critical, new_tally) {
             return;
         }
 
@@ -1088,26 +1373,39 @@ impl ProposalData {
     /// Returns true if the proposal meets the conditions to be accepted, also called "adopted".
     /// The result is only meaningful if a decision on the proposal's result can be made, i.e.,
     /// either there is a majority of yes-votes or the proposal's deadline has passed.
+    ///
+    /// Critical proposals require a supermajority, see
+    /// CRITICAL_PROPOSAL_MIN_YES_BASIS_POINTS_OF_TOTAL and
+    /// CRITICAL_PROPOSAL_MIN_YES_BASIS_POINTS_OF_EXERCISED.
     pub fn is_accepted(&self) -> bool {
         if let Some(tally) = self.latest_tally.as_ref() {
-            (tally.yes as f64 >= tally.total as f64 * MIN_NUMBER_VOTES_FOR_PROPOSAL_RATIO)
-                && tally.yes > tally.no
+            if self.is_critical() {
+                let yes = tally.yes as u128 * BASIS_POINTS_PER_UNITY as u128;
+                yes >= tally.total as u128 * CRITICAL_PROPOSAL_MIN_YES_BASIS_POINTS_OF_TOTAL as u128
+                    && tally_favors_yes(true, tally)
+            } else {
+                (tally.yes as f64 >= tally.total as f64 * MIN_NUMBER_VOTES_FOR_PROPOSAL_RATIO)
+                    && tally.yes > tally.no
+            }
         } else {
             false
         }
     }
 
+    /// Returns whether the proposal is critical, i.e., whether it requires a
+    /// supermajority to be adopted. This is the case for MintSnsTokens proposals,
+    /// as minting dilutes all token holders.
+    pub fn is_critical(&self) -> bool {
+        self.action == native_action_ids::MINT_SNS_TOKENS
+    }
+
     /// Returns true if a decision can be made right now to adopt or reject the proposal.
     /// The proposal must be tallied prior to calling this method.
     pub(crate) fn can_make_decision(&self, now_seconds: u64) -> bool {
         if let Some(tally) = &self.latest_tally {
             // Even when a proposal's deadline has not passed, a proposal is
-            // adopted if strictly more than half of the votes are 'yes' and
-            // rejected if at least half of the votes are 'no'. The conditions
-            // are described as below to avoid overflow. In the absence of overflow,
-            // the below is equivalent to (2 * yes > total) || (2 * no >= total).
-            let majority =
-                (tally.yes > tally.total - tally.yes) || (tally.no >= tally.total - tally.no);
+            // decided if the votes cast so far determine the result.
+            let majority = is_decided_by_votes_cast(self.is_critical(), tally);
             let expired = !self.accepts_vote(now_seconds);
             let decision_reason = match (majority, expired) {
                 (true, true) => Some("majority and expiration"),
@@ -1234,6 +1532,7 @@ mod tests {
             sns_initialization_parameters: "".to_string(),
             is_finalizing_disburse_maturity: None,
             maturity_modulation: None,
+            recent_sns_token_mints: vec![],
         }
     }
 
@@ -1493,6 +1792,7 @@ mod tests {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(CanisterId::from_u64(1).get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
@@ -1690,6 +1990,7 @@ mod tests {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(CanisterId::from_u64(1).get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
@@ -1736,6 +2037,7 @@ mod tests {
                         target_method_name: Some("test_method".to_string()),
                         validator_canister_id: Some(CanisterId::from_u64(i as u64).get()),
                         validator_method_name: Some("test_validator_method".to_string()),
+                        topic: None,
                     },
                 )),
             };
@@ -1752,6 +2054,7 @@ mod tests {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(CanisterId::from_u64(u64::MAX).get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
@@ -2151,6 +2454,7 @@ Version {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(CanisterId::from(1).get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
@@ -2172,6 +2476,7 @@ Version {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(CanisterId::from(1).get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
@@ -2191,6 +2496,7 @@ Version {
                     target_method_name: Some("test_method".to_string()),
                     validator_canister_id: Some(CanisterId::ic_00().get()),
                     validator_method_name: Some("test_validator_method".to_string()),
+                    topic: None,
                 },
             )),
         };
@@ -2559,4 +2865,281 @@ Version {
             );
         }
     }
+
+    fn basic_mint_sns_tokens() -> MintSnsTokens {
+        MintSnsTokens {
+            amount_e8s: Some(1000000),
+            memo: Some(1000),
+            to_principal: Some(basic_principal_id()),
+            to_subaccount: None,
+        }
+    }
+
+    #[test]
+    fn validate_and_render_mint_sns_tokens_renders_for_valid_inputs() {
+        assert_eq!(
+            validate_and_render_mint_sns_tokens(
+                &basic_mint_sns_tokens(),
+                &NervousSystemParameters::with_default_values()
+            )
+            .unwrap(),
+            r"# Proposal to mint SNS tokens:
+## Amount (e8s): 1000000
+## Target principal: bg4sm-wzk
+## Target account: bg4sm-wzk
+## Memo: 1000
+## Cap: at most 1000 basis points of the total supply at the time of execution, and at most 2000 basis points within a month"
+        );
+    }
+
+    #[test]
+    fn validate_and_render_mint_sns_tokens_reports_all_defects() {
+        assert_eq!(
+            validate_and_render_mint_sns_tokens(
+                &MintSnsTokens {
+                    amount_e8s: Some(0),
+                    to_principal: None,
+                    ..basic_mint_sns_tokens()
+                },
+                &NervousSystemParameters::with_default_values(),
+            ),
+            Err(
+                "MintSnsTokens proposal was invalid for the following reason(s):\n\
+                 The amount to mint must be greater than zero.\n\
+                 Must specify a principal to mint the tokens to."
+                    .to_string()
+            )
+        );
+        assert_is_err(validate_and_render_mint_sns_tokens(
+            &MintSnsTokens {
+                amount_e8s: None,
+                ..basic_mint_sns_tokens()
+            },
+            &NervousSystemParameters::with_default_values(),
+        ));
+        assert_is_err(validate_and_render_mint_sns_tokens(
+            &MintSnsTokens {
+                to_principal: Some(PrincipalId::new_anonymous()),
+                ..basic_mint_sns_tokens()
+            },
+            &NervousSystemParameters::with_default_values(),
+        ));
+        assert_is_err(validate_and_render_mint_sns_tokens(
+            &MintSnsTokens {
+                to_subaccount: Some(Subaccount {
+                    subaccount: vec![0; 31],
+                }),
+                ..basic_mint_sns_tokens()
+            },
+            &NervousSystemParameters::with_default_values(),
+        ));
+    }
+
+    #[test]
+    fn validate_and_render_manage_ledger_parameters_renders_changed_values() {
+        assert_eq!(
+            validate_default_action(&Some(proposal::Action::ManageLedgerParameters(
+                ManageLedgerParameters {
+                    transfer_fee: Some(100),
+                    token_name: None,
+                    token_symbol: Some("NEW".to_string()),
+                    token_logo: None,
+                }
+            ))),
+            Ok("# Proposal to change ledger parameters:\n\
+                # New transfer fee (e8s): 100\n\
+                # New token symbol: NEW\n"
+                .to_string())
+        );
+    }
+
+    #[test]
+    fn validate_and_render_manage_ledger_parameters_must_change_at_least_one_value() {
+        assert_is_err(validate_and_render_manage_ledger_parameters(
+            &ManageLedgerParameters::default(),
+            &NervousSystemParameters::with_default_values(),
+        ));
+    }
+
+    #[test]
+    fn validate_and_render_manage_ledger_parameters_rejects_invalid_values() {
+        for invalid in [
+            ManageLedgerParameters {
+                token_name: Some("abc".to_string()),
+                ..Default::default()
+            },
+            ManageLedgerParameters {
+                token_symbol: Some("AB".to_string()),
+                ..Default::default()
+            },
+            ManageLedgerParameters {
+                token_symbol: Some("ABCDEFGHIJK".to_string()),
+                ..Default::default()
+            },
+            ManageLedgerParameters {
+                token_logo: Some("not a logo".to_string()),
+                ..Default::default()
+            },
+        ] {
+            assert_is_err(validate_and_render_manage_ledger_parameters(
+                &invalid,
+                &NervousSystemParameters::with_default_values(),
+            ));
+        }
+    }
+
+    #[test]
+    fn validate_and_render_manage_ledger_parameters_rejects_transfer_fee_out_of_bounds() {
+        let parameters = NervousSystemParameters::with_default_values();
+        let neuron_minimum_stake_e8s = parameters.neuron_minimum_stake_e8s.unwrap();
+        for transfer_fee in [neuron_minimum_stake_e8s, u64::MAX] {
+            assert_is_err(validate_and_render_manage_ledger_parameters(
+                &ManageLedgerParameters {
+                    transfer_fee: Some(transfer_fee),
+                    ..Default::default()
+                },
+                &parameters,
+            ));
+        }
+        assert_is_ok(validate_and_render_manage_ledger_parameters(
+            &ManageLedgerParameters {
+                transfer_fee: Some(neuron_minimum_stake_e8s - 1),
+                ..Default::default()
+            },
+            &parameters,
+        ));
+    }
+
+    #[test]
+    fn validate_and_render_manage_dapp_canister_settings_renders_for_registered_canisters() {
+        let registered_dapp_canister_ids = hashset! {
+            PrincipalId::new_user_test_id(1),
+            PrincipalId::new_user_test_id(2),
+        };
+
+        assert_eq!(
+            validate_and_render_manage_dapp_canister_settings(
+                &ManageDappCanisterSettings {
+                    canister_ids: vec![PrincipalId::new_user_test_id(1)],
+                    compute_allocation: None,
+                    memory_allocation: Some(1_000_000),
+                    freezing_threshold: Some(2_592_000),
+                },
+                &registered_dapp_canister_ids,
+            ),
+            Ok(format!(
+                "# Proposal to change the settings of dapp canisters:\n\
+                 # New memory allocation (bytes): 1000000\n\
+                 # New freezing threshold (seconds): 2592000\n\
+                 ## Canisters:\n\
+                 - {}",
+                PrincipalId::new_user_test_id(1)
+            ))
+        );
+    }
+
+    #[test]
+    fn validate_and_render_manage_dapp_canister_settings_rejects_unregistered_canisters() {
+        let registered_dapp_canister_ids = hashset! { PrincipalId::new_user_test_id(1) };
+        let unregistered_canister_id = PrincipalId::new_user_test_id(3);
+
+        let err = validate_and_render_manage_dapp_canister_settings(
+            &ManageDappCanisterSettings {
+                canister_ids: vec![PrincipalId::new_user_test_id(1), unregistered_canister_id],
+                compute_allocation: Some(10),
+                memory_allocation: None,
+                freezing_threshold: None,
+            },
+            &registered_dapp_canister_ids,
+        )
+        .unwrap_err();
+
+        assert!(err.contains(&unregistered_canister_id.to_string()), "{err}");
+    }
+
+    #[test]
+    fn validate_and_render_manage_dapp_canister_settings_reports_all_defects() {
+        let err = validate_and_render_manage_dapp_canister_settings(
+            &ManageDappCanisterSettings::default(),
+            &HashSet::new(),
+        )
+        .unwrap_err();
+        assert!(err.contains("at least one canister id"), "{err}");
+        assert!(err.contains("at least one setting"), "{err}");
+
+        assert_is_err(validate_and_render_manage_dapp_canister_settings(
+            &ManageDappCanisterSettings {
+                canister_ids: vec![PrincipalId::new_user_test_id(1)],
+                compute_allocation: Some(101),
+                memory_allocation: None,
+                freezing_threshold: None,
+            },
+            &hashset! { PrincipalId::new_user_test_id(1) },
+        ));
+    }
+
+    fn proposal_data_with_tally(action: u64, yes: u64, no: u64, total: u64) -> ProposalData {
+        ProposalData {
+            action,
+            proposal_creation_timestamp_seconds: 0,
+            initial_voting_period_seconds: 100,
+            latest_tally: Some(Tally {
+                timestamp_seconds: 0,
+                yes,
+                no,
+                total,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn mint_sns_tokens_proposals_are_critical() {
+        assert!(
+            proposal_data_with_tally(native_action_ids::MINT_SNS_TOKENS, 0, 0, 1).is_critical()
+        );
+        assert!(!proposal_data_with_tally(native_action_ids::MOTION, 0, 0, 1).is_critical());
+    }
+
+    #[test]
+    fn critical_proposals_require_a_supermajority_to_be_accepted() {
+        let critical = |yes, no, total| {
+            proposal_data_with_tally(native_action_ids::MINT_SNS_TOKENS, yes, no, total)
+        };
+
+        // A simple majority does not suffice.
+        assert!(proposal_data_with_tally(native_action_ids::MOTION, 60, 40, 100).is_accepted());
+        assert!(!critical(60, 40, 100).is_accepted());
+        // Two thirds of the exercised voting power do.
+        assert!(critical(67, 33, 100).is_accepted());
+        // But only if the yes votes make up a fifth of the total voting power.
+        assert!(!critical(19, 0, 100).is_accepted());
+        assert!(critical(20, 0, 100).is_accepted());
+        // Nobody voting does not adopt the proposal.
+        assert!(!critical(0, 0, 0).is_accepted());
+    }
+
+    #[test]
+    fn critical_proposals_are_decided_early_only_by_a_supermajority() {
+        let critical = |yes, no, total| {
+            proposal_data_with_tally(native_action_ids::MINT_SNS_TOKENS, yes, no, total)
+        };
+        let before_deadline = 50;
+
+        // An absolute majority decides a normal proposal, but not a critical one.
+        assert!(
+            proposal_data_with_tally(native_action_ids::MOTION, 51, 0, 100)
+                .can_make_decision(before_deadline)
+        );
+        assert!(!critical(51, 0, 100).can_make_decision(before_deadline));
+        // Yes votes of two thirds of the total voting power adopt it early.
+        let adopted = critical(67, 0, 100);
+        assert!(adopted.can_make_decision(before_deadline));
+        assert!(adopted.is_accepted());
+        // No votes of more than a third of the total voting power reject it early.
+        let rejected = critical(0, 34, 100);
+        assert!(rejected.can_make_decision(before_deadline));
+        assert!(!rejected.is_accepted());
+        assert!(!critical(0, 33, 100).can_make_decision(before_deadline));
+    }
 }
@@ -17,15 +17,16 @@ use crate::{
         proposal::Action,
         ClaimSwapNeuronsError, ClaimSwapNeuronsResponse, ClaimedSwapNeuronStatus,
         DeregisterDappCanisters, Empty, ExecuteGenericNervousSystemFunction, GovernanceError,
-        ManageNeuronResponse, Motion, NervousSystemFunction, NervousSystemParameters, Neuron,
-        NeuronId, NeuronPermission, NeuronPermissionList, NeuronPermissionType, ProposalId,
-        RegisterDappCanisters, RewardEvent, TransferSnsTreasuryFunds, UpgradeSnsControlledCanister,
-        UpgradeSnsToNextVersion, Vote, VotingRewardsParameters,
+        ManageDappCanisterSettings, ManageLedgerParameters, ManageNeuronResponse, MintSnsTokens,
+        Motion, NervousSystemFunction, NervousSystemParameters, Neuron, NeuronId, NeuronPermission,
+        NeuronPermissionList, NeuronPermissionType, ProposalId, RegisterDappCanisters, RewardEvent,
+        Topic, TransferSnsTreasuryFunds, UpgradeSnsControlledCanister, UpgradeSnsToNextVersion,
+        Vote, VotingRewardsParameters,
     },
     pb::{
         sns_root_types::{
-            set_dapp_controllers_request::CanisterIds, RegisterDappCanistersRequest,
-            SetDappControllersRequest,
+            set_dapp_controllers_request::CanisterIds, ManageDappCanisterSettingsRequest,
+            RegisterDappCanistersRequest, SetDappControllersRequest,
         },
         v1::DefaultFollowees,
     },
@@ -37,7 +38,7 @@ use ic_canister_log::log;
 use ic_crypto_sha::Sha256;
 use ic_ic00_types::CanisterInstallModeError;
 use ic_ledger_core::{tokens::Tokens, tokens::TOKEN_SUBDIVIDABLE_BY};
-use ic_nervous_system_common::{validate_proposal_url, NervousSystemError};
+use ic_nervous_system_common::{validate_proposal_url, NervousSystemError, BASIS_POINTS_PER_UNITY};
 use maplit::btreemap;
 use std::{
     collections::{BTreeMap, BTreeSet, HashSet},
@@ -98,6 +99,58 @@ pub mod native_action_ids {
 
     /// DeregisterDappCanisters Action.
     pub const DEREGISTER_DAPP_CANISTERS: u64 = 11;
+
+    /// MintSnsTokens Action.
+    pub const MINT_SNS_TOKENS: u64 = 12;
+
+    /// ManageLedgerParameters Action.
+    pub const MANAGE_LEDGER_PARAMETERS: u64 = 13;
+
+    /// ManageDappCanisterSettings Action.
+    pub const MANAGE_DAPP_CANISTER_SETTINGS: u64 = 14;
+}
+
+/// Returns the topic of proposals with the given function id, for the purposes
+/// of following.
+///
+/// Native actions have a fixed topic. A GenericNervousSystemFunction has the
+/// topic it was added with, or ApplicationBusinessLogic if it was added without
+/// one. Returns None for the Unspecified action and for ids that are neither
+/// native actions nor registered GenericNervousSystemFunctions.
+pub fn topic_for_function_id(
+    function_id: u64,
+    id_to_nervous_system_functions: &BTreeMap<u64, NervousSystemFunction>,
+) -> Option<Topic> {
+    use native_action_ids::*;
+
+    let topic = match function_id {
+        UNSPECIFIED => return None,
+        MOTION => Topic::Governance,
+        MANAGE_NERVOUS_SYSTEM_PARAMETERS | MANAGE_SNS_METADATA | MANAGE_LEDGER_PARAMETERS => {
+            Topic::DaoCommunitySettings
+        }
+        UPGRADE_SNS_CONTROLLER_CANISTER
+        | REGISTER_DAPP_CANISTERS
+        | MANAGE_DAPP_CANISTER_SETTINGS => Topic::DappCanisterManagement,
+        ADD_GENERIC_NERVOUS_SYSTEM_FUNCTION
+        | REMOVE_GENERIC_NERVOUS_SYSTEM_FUNCTION
+        | DEREGISTER_DAPP_CANISTERS => Topic::CriticalDappOperations,
+        UPGRADE_SNS_TO_NEXT_VERSION => Topic::SnsFrameworkManagement,
+        TRANSFER_SNS_TREASURY_FUNDS | MINT_SNS_TOKENS => Topic::TreasuryAssetManagement,
+        _ => match id_to_nervous_system_functions
+            .get(&function_id)
+            .and_then(|function| function.function_type.as_ref())
+        {
+            Some(FunctionType::GenericNervousSystemFunction(generic)) => generic
+                .topic
+                .and_then(Topic::from_i32)
+                .filter(|topic| *topic != Topic::Unspecified)
+                .unwrap_or(Topic::ApplicationBusinessLogic),
+            _ => return None,
+        },
+    };
+
+    Some(topic)
 }
 
 impl governance::Mode {
@@ -223,6 +276,24 @@ impl governance::Mode {
                 )
             )),
 
+            Action::MintSnsTokens(_) => Err(GovernanceError::new_with_message(
+                ErrorType::PreconditionFailed,
+                format!(
+                    "MintSnsTokens proposals are not allowed while \
+                        governance is in PreInitializationSwap mode: {:#?}",
+                    action
+                )
+            )),
+
+            Action::ManageLedgerParameters(_) => Err(GovernanceError::new_with_message(
+                ErrorType::PreconditionFailed,
+                format!(
+                    "ManageLedgerParameters proposals are not allowed while \
+                        governance is in PreInitializationSwap mode: {:#?}",
+                    action
+                )
+            )),
+
             _ => Ok(()),
         }
     }
@@ -343,6 +414,14 @@ impl NervousSystemParameters {
     /// to an over-concentration of voting power. The value used by the NNS is 25.
     pub const MAX_AGE_BONUS_PERCENTAGE_CEILING: u64 = 400;
 
+    /// The default for `max_mint_sns_tokens_basis_points_of_total_supply`, which
+    /// applies if the parameter is not set.
+    pub const DEFAULT_MAX_MINT_SNS_TOKENS_BASIS_POINTS_OF_TOTAL_SUPPLY: u64 = 1_000;
+
+    /// The default for `max_mint_sns_tokens_basis_points_of_total_supply_per_month`,
+    /// which applies if the parameter is not set.
+    pub const DEFAULT_MAX_MINT_SNS_TOKENS_BASIS_POINTS_OF_TOTAL_SUPPLY_PER_MONTH: u64 = 2_000;
+
     /// These are the permissions that must be present in
     /// `neuron_claimer_permissions`.
     /// Permissions not in this list can be added after the SNS is created via a
@@ -379,6 +458,12 @@ impl NervousSystemParameters {
             max_dissolve_delay_bonus_percentage: Some(100),
             max_age_bonus_percentage: Some(25),
             maturity_modulation_disabled: Some(false),
+            max_mint_sns_tokens_basis_points_of_total_supply: Some(
+                Self::DEFAULT_MAX_MINT_SNS_TOKENS_BASIS_POINTS_OF_TOTAL_SUPPLY,
+            ),
+            max_mint_sns_tokens_basis_points_of_total_supply_per_month: Some(
+                Self::DEFAULT_MAX_MINT_SNS_TOKENS_BASIS_POINTS_OF_TOTAL_SUPPLY_PER_MONTH,
+            ),
         }
     }
 
@@ -447,9 +532,29 @@ impl NervousSystemParameters {
             maturity_modulation_disabled: self
                 .maturity_modulation_disabled
                 .or(base.maturity_modulation_disabled),
+            max_mint_sns_tokens_basis_points_of_total_supply: self
+                .max_mint_sns_tokens_basis_points_of_total_supply
+                .or(base.max_mint_sns_tokens_basis_points_of_total_supply),
+            max_mint_sns_tokens_basis_points_of_total_supply_per_month: self
+                .max_mint_sns_tokens_basis_points_of_total_supply_per_month
+                .or(base.max_mint_sns_tokens_basis_points_of_total_supply_per_month),
         }
     }
 
+    /// Returns the maximum amount of SNS tokens that a single MintSnsTokens proposal
+    /// can mint, in basis points of the total supply.
+    pub fn max_mint_sns_tokens_basis_points_of_total_supply_or_default(&self) -> u64 {
+        self.max_mint_sns_tokens_basis_points_of_total_supply
+            .unwrap_or(Self::DEFAULT_MAX_MINT_SNS_TOKENS_BASIS_POINTS_OF_TOTAL_SUPPLY)
+    }
+
+    /// Returns the maximum amount of SNS tokens that MintSnsTokens proposals can
+    /// mint within a month, in basis points of the total supply.
+    pub fn max_mint_sns_tokens_basis_points_of_total_supply_per_month_or_default(&self) -> u64 {
+        self.max_mint_sns_tokens_basis_points_of_total_supply_per_month
+            .unwrap_or(Self::DEFAULT_MAX_MINT_SNS_TOKENS_BASIS_POINTS_OF_TOTAL_SUPPLY_PER_MONTH)
+    }
+
     /// This validates that the `NervousSystemParameters` are well-formed.
     pub fn validate(&self) -> Result<(), String> {
         self.validate_reject_cost_e8s()?;
@@ -471,6 +576,7 @@ impl NervousSystemParameters {
         self.validate_voting_rewards_parameters()?;
         self.validate_max_dissolve_delay_bonus_percentage()?;
         self.validate_max_age_bonus_percentage()?;
+        self.validate_max_mint_sns_tokens_basis_points()?;
 
         Ok(())
     }
@@ -820,6 +926,31 @@ impl NervousSystemParameters {
         }
     }
 
+    /// Validates that the nervous system parameters max_mint_sns_tokens_basis_points_of_total_supply
+    /// and max_mint_sns_tokens_basis_points_of_total_supply_per_month are well-formed.
+    /// Both are optional, as defaults apply if they are not set.
+    fn validate_max_mint_sns_tokens_basis_points(&self) -> Result<(), String> {
+        let per_proposal = self.max_mint_sns_tokens_basis_points_of_total_supply_or_default();
+        let per_month =
+            self.max_mint_sns_tokens_basis_points_of_total_supply_per_month_or_default();
+
+        if per_month > BASIS_POINTS_PER_UNITY {
+            Err(format!(
+                "NervousSystemParameters.max_mint_sns_tokens_basis_points_of_total_supply_per_month \
+                 must be at most {}",
+                BASIS_POINTS_PER_UNITY
+            ))
+        } else if per_proposal > per_month {
+            Err(
+                "NervousSystemParameters.max_mint_sns_tokens_basis_points_of_total_supply must be \
+                 at most max_mint_sns_tokens_basis_points_of_total_supply_per_month"
+                    .to_string(),
+            )
+        } else {
+            Ok(())
+        }
+    }
+
     /// Given a NeuronPermissionList, check whether the provided list can be
     /// granted given the `NervousSystemParameters::neuron_grantable_permissions`.
     /// Format a useful error if not.
@@ -1057,6 +1188,30 @@ impl From<Action> for NervousSystemFunction {
                 ),
                 function_type: Some(FunctionType::NativeNervousSystemFunction(Empty {})),
             },
+            Action::MintSnsTokens(_) => NervousSystemFunction {
+                id: native_action_ids::MINT_SNS_TOKENS,
+                name: "Mint SNS Tokens".to_string(),
+                description: Some(
+                    "Proposal to mint SNS tokens to a specified recipient.".to_string(),
+                ),
+                function_type: Some(FunctionType::NativeNervousSystemFunction(Empty {})),
+            },
+            Action::ManageLedgerParameters(_) => NervousSystemFunction {
+                id: native_action_ids::MANAGE_LEDGER_PARAMETERS,
+                name: "Manage ledger parameters".to_string(),
+                description: Some(
+                    "Proposal to change some parameters in the ledger canister.".to_string(),
+                ),
+                function_type: Some(FunctionType::NativeNervousSystemFunction(Empty {})),
+            },
+            Action::ManageDappCanisterSettings(_) => NervousSystemFunction {
+                id: native_action_ids::MANAGE_DAPP_CANISTER_SETTINGS,
+                name: "Manage dapp canister settings".to_string(),
+                description: Some(
+                    "Proposal to change the settings of registered dapp canisters.".to_string(),
+                ),
+                function_type: Some(FunctionType::NativeNervousSystemFunction(Empty {})),
+            },
         }
     }
 }
@@ -1420,6 +1575,9 @@ impl Action {
             Action::ExecuteGenericNervousSystemFunction(action) => {
                 Action::ExecuteGenericNervousSystemFunction(action.strip_large_fields())
             }
+            Action::ManageLedgerParameters(action) => {
+                Action::ManageLedgerParameters(action.strip_large_fields())
+            }
             action => action.clone(),
         }
     }
@@ -1453,6 +1611,20 @@ impl ExecuteGenericNervousSystemFunction {
     }
 }
 
+impl ManageLedgerParameters {
+    // Returns a clone of self, except that the token logo, which can be up to
+    // roughly 256 Kb, is replaced with a textual summary of its contents. See
+    // summarize_blob_field.
+    pub(crate) fn strip_large_fields(&self) -> Self {
+        Self {
+            token_logo: self.token_logo.as_ref().map(|token_logo| {
+                String::from_utf8_lossy(&summarize_blob_field(token_logo.as_bytes())).into_owned()
+            }),
+            ..self.clone()
+        }
+    }
+}
+
 /// If blob is of length <= 64 (bytes), a copy is returned. Otherwise, a (UTF-8
 /// encoded) human-readable textual summary is returned. This summary is
 /// guaranteed to be of length > 64. Therefore, it is always possible to
@@ -1516,6 +1688,11 @@ impl From<&Action> for u64 {
             Action::DeregisterDappCanisters(_) => native_action_ids::DEREGISTER_DAPP_CANISTERS,
             Action::ManageSnsMetadata(_) => native_action_ids::MANAGE_SNS_METADATA,
             Action::TransferSnsTreasuryFunds(_) => native_action_ids::TRANSFER_SNS_TREASURY_FUNDS,
+            Action::MintSnsTokens(_) => native_action_ids::MINT_SNS_TOKENS,
+            Action::ManageLedgerParameters(_) => native_action_ids::MANAGE_LEDGER_PARAMETERS,
+            Action::ManageDappCanisterSettings(_) => {
+                native_action_ids::MANAGE_DAPP_CANISTER_SETTINGS
+            }
         }
     }
 }
@@ -1660,6 +1837,7 @@ impl NeuronParameters {
             hotkey: _,
             source_nns_neuron_id: _,
             followees,
+            vesting_period_seconds: _,
         } = self;
 
         if neuron_id.is_none() {
@@ -1913,6 +2091,25 @@ impl From<DeregisterDappCanisters> for SetDappControllersRequest {
     }
 }
 
+impl From<ManageDappCanisterSettings> for ManageDappCanisterSettingsRequest {
+    fn from(
+        manage_dapp_canister_settings: ManageDappCanisterSettings,
+    ) -> ManageDappCanisterSettingsRequest {
+        let ManageDappCanisterSettings {
+            canister_ids,
+            compute_allocation,
+            memory_allocation,
+            freezing_threshold,
+        } = manage_dapp_canister_settings;
+        ManageDappCanisterSettingsRequest {
+            canister_ids,
+            compute_allocation,
+            memory_allocation,
+            freezing_threshold,
+        }
+    }
+}
+
 impl Motion {
     pub fn new(text: &str) -> Self {
         Motion {
@@ -1961,6 +2158,24 @@ impl From<TransferSnsTreasuryFunds> for Action {
     }
 }
 
+impl From<MintSnsTokens> for Action {
+    fn from(mint_sns_tokens: MintSnsTokens) -> Action {
+        Action::MintSnsTokens(mint_sns_tokens)
+    }
+}
+
+impl From<ManageLedgerParameters> for Action {
+    fn from(manage_ledger_parameters: ManageLedgerParameters) -> Action {
+        Action::ManageLedgerParameters(manage_ledger_parameters)
+    }
+}
+
+impl From<ManageDappCanisterSettings> for Action {
+    fn from(manage_dapp_canister_settings: ManageDappCanisterSettings) -> Action {
+        Action::ManageDappCanisterSettings(manage_dapp_canister_settings)
+    }
+}
+
 impl From<RegisterDappCanisters> for Action {
     fn from(register_dapp_canisters: RegisterDappCanisters) -> Action {
         Action::RegisterDappCanisters(register_dapp_canisters)
@@ -2358,6 +2573,17 @@ pub(crate) mod tests {
                 }),
                 ..NervousSystemParameters::with_default_values()
             },
+            NervousSystemParameters {
+                max_mint_sns_tokens_basis_points_of_total_supply_per_month: Some(
+                    BASIS_POINTS_PER_UNITY + 1,
+                ),
+                ..NervousSystemParameters::with_default_values()
+            },
+            NervousSystemParameters {
+                max_mint_sns_tokens_basis_points_of_total_supply: Some(3_000),
+                max_mint_sns_tokens_basis_points_of_total_supply_per_month: Some(2_000),
+                ..NervousSystemParameters::with_default_values()
+            },
         ];
 
         for params in invalid_params {
@@ -2527,7 +2753,9 @@ pub(crate) mod tests {
 
             let disallowed_in_pre_initialization_swap = vec! [
                 Action::ManageNervousSystemParameters(Default::default()),
-                Action::TransferSnsTreasuryFunds(Default::default())
+                Action::TransferSnsTreasuryFunds(Default::default()),
+                Action::MintSnsTokens(Default::default()),
+                Action::ManageLedgerParameters(Default::default()),
             ];
 
             // Conditionally allow: No targetting SNS canisters.
@@ -2565,6 +2793,7 @@ pub(crate) mod tests {
                         target_method_name: Some("Foo".to_string()),
                         validator_canister_id: Some(*target_canister_id),
                         validator_method_name: Some("Bar".to_string()),
+                        topic: None,
                     })),
                 }
             }
@@ -2875,6 +3104,7 @@ pub(crate) mod tests {
                 source_nns_neuron_id: None,
                 neuron_id: Some(NeuronId::new_test_neuron_id(0)),
                 followees: vec![NeuronId::new_test_neuron_id(1)],
+                vesting_period_seconds: None,
             }
         }
     }
@@ -3159,4 +3389,55 @@ pub(crate) mod tests {
             execute_generic_nervous_system_function_proposal,
         );
     }
+
+    #[test]
+    fn test_topic_for_function_id() {
+        // Every native action except Unspecified has a topic.
+        for action in Action::iter() {
+            let function_id = u64::from(&action);
+            let topic = topic_for_function_id(function_id, &BTreeMap::new());
+            match action {
+                Action::Unspecified(_) | Action::ExecuteGenericNervousSystemFunction(_) => {
+                    assert_eq!(topic, None, "{action:?}")
+                }
+                _ => assert!(topic.is_some(), "{action:?} has no topic"),
+            }
+        }
+        assert_eq!(
+            topic_for_function_id(native_action_ids::MINT_SNS_TOKENS, &BTreeMap::new()),
+            Some(Topic::TreasuryAssetManagement)
+        );
+
+        let generic_function = |id, topic: Option<Topic>| NervousSystemFunction {
+            id,
+            name: format!("function {id}"),
+            description: None,
+            function_type: Some(FunctionType::GenericNervousSystemFunction(
+                GenericNervousSystemFunction {
+                    target_canister_id: Some(PrincipalId::new_user_test_id(1)),
+                    target_method_name: Some("target".to_string()),
+                    validator_canister_id: Some(PrincipalId::new_user_test_id(1)),
+                    validator_method_name: Some("validator".to_string()),
+                    topic: topic.map(|topic| topic as i32),
+                },
+            )),
+        };
+        let id_to_nervous_system_functions = btreemap! {
+            1000 => generic_function(1000, Some(Topic::CriticalDappOperations)),
+            1001 => generic_function(1001, None),
+        };
+
+        assert_eq!(
+            topic_for_function_id(1000, &id_to_nervous_system_functions),
+            Some(Topic::CriticalDappOperations)
+        );
+        assert_eq!(
+            topic_for_function_id(1001, &id_to_nervous_system_functions),
+            Some(Topic::ApplicationBusinessLogic)
+        );
+        assert_eq!(
+            topic_for_function_id(1002, &id_to_nervous_system_functions),
+            None
+        );
+    }
 }
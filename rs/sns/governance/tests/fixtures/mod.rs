@@ -22,7 +22,8 @@ use ic_sns_governance::{
         proposal::Action,
         GetNeuron, GetProposal, Governance as GovernanceProto, GovernanceError, ManageNeuron,
         ManageNeuronResponse, NervousSystemParameters, Neuron, NeuronId, NeuronPermission,
-        NeuronPermissionList, NeuronPermissionType, Proposal, ProposalData, ProposalId, Vote,
+        NeuronPermissionList, NeuronPermissionType, Proposal, ProposalData, ProposalId, Topic,
+        Vote,
     },
     types::Environment,
 };
@@ -762,6 +763,7 @@ impl GovernanceCanisterFixture {
             manage_neuron::Command::Follow(manage_neuron::Follow {
                 function_id,
                 followees,
+                topic: None,
             }),
             caller,
         );
@@ -773,6 +775,30 @@ impl GovernanceCanisterFixture {
         }
     }
 
+    pub fn follow_topic(
+        &mut self,
+        target_neuron: &NeuronId,
+        topic: Topic,
+        followees: Vec<NeuronId>,
+        caller: PrincipalId,
+    ) -> Result<FollowResponse, GovernanceError> {
+        let response = self.manage_neuron(
+            target_neuron,
+            manage_neuron::Command::Follow(manage_neuron::Follow {
+                function_id: 0,
+                followees,
+                topic: Some(topic as i32),
+            }),
+            caller,
+        );
+
+        match response.command.unwrap() {
+            manage_neuron_response::Command::Follow(follow_response) => Ok(follow_response),
+            manage_neuron_response::Command::Error(governance_error) => Err(governance_error),
+            _ => panic!("Unexpected command response when setting a topic follow relationship"),
+        }
+    }
+
     pub fn vote(
         &mut self,
         target_neuron: &NeuronId,
@@ -19,6 +19,7 @@ use ic_sns_governance::{
         v1::{
             claim_swap_neurons_request::NeuronParameters,
             claim_swap_neurons_response::{ClaimSwapNeuronsResult, ClaimedSwapNeurons, SwapNeuron},
+            governance::MaturityModulation,
             governance_error::ErrorType,
             manage_neuron::{
                 self, claim_or_refresh, configure::Operation, AddNeuronPermissions, ClaimOrRefresh,
@@ -36,7 +37,7 @@ use ic_sns_governance::{
             ClaimSwapNeuronsResponse, ClaimedSwapNeuronStatus, DeregisterDappCanisters, Empty,
             GovernanceError, ManageNeuronResponse, Motion, Neuron, NeuronId, NeuronPermission,
             NeuronPermissionList, NeuronPermissionType, Proposal, ProposalData, ProposalId,
-            RegisterDappCanisters, Vote, WaitForQuietState,
+            RegisterDappCanisters, Topic, Vote, WaitForQuietState,
         },
     },
     types::{native_action_ids, ONE_DAY_SECONDS, ONE_MONTH_SECONDS},
@@ -682,6 +683,7 @@ fn test_vesting_neuron_manage_neuron_operations() {
 
     let disburse_maturity_response = DisburseMaturityResponse {
         amount_disbursed_e8s: 100000,
+        amount_deducted_e8s: Some(100000),
     };
 
     let merge_maturity = MergeMaturity {
@@ -1828,6 +1830,7 @@ fn test_claim_swap_neurons_succeeds() {
         dissolve_delay_seconds: Some(0),
         source_nns_neuron_id: None,
         followees: vec![NeuronId::new_test_neuron_id(10)],
+        vesting_period_seconds: Some(ONE_MONTH_SECONDS),
     };
 
     let cf_participant_neuron_params = NeuronParameters {
@@ -1838,6 +1841,7 @@ fn test_claim_swap_neurons_succeeds() {
         dissolve_delay_seconds: Some(ONE_MONTH_SECONDS),
         source_nns_neuron_id: Some(2),
         followees: vec![NeuronId::new_test_neuron_id(20)],
+        vesting_period_seconds: None,
     };
 
     let request = ClaimSwapNeuronsRequest {
@@ -1902,6 +1906,10 @@ fn test_claim_swap_neurons_succeeds() {
     assert_eq!(direct_participant_neuron.maturity_e8s_equivalent, 0);
     assert_eq!(direct_participant_neuron.neuron_fees_e8s, 0);
     assert_eq!(direct_participant_neuron.auto_stake_maturity, None);
+    assert_eq!(
+        direct_participant_neuron.vesting_period_seconds,
+        Some(ONE_MONTH_SECONDS)
+    );
     for followees in direct_participant_neuron.followees.values() {
         assert_eq!(
             followees.followees,
@@ -1933,6 +1941,7 @@ fn test_claim_swap_neurons_succeeds() {
     assert_eq!(cf_participant_neuron.maturity_e8s_equivalent, 0);
     assert_eq!(cf_participant_neuron.neuron_fees_e8s, 0);
     assert_eq!(cf_participant_neuron.auto_stake_maturity, Some(true));
+    assert_eq!(cf_participant_neuron.vesting_period_seconds, None);
     for followees in cf_participant_neuron.followees.values() {
         assert_eq!(followees.followees, cf_participant_neuron_params.followees);
     }
@@ -2365,6 +2374,144 @@ fn test_neurons_can_follow_themselves() {
         .is_err());
 }
 
+/// Test that a neuron following on a topic votes along with its followee on
+/// proposals of that topic, and that per-function following takes precedence.
+#[test]
+fn test_neurons_can_follow_on_topics() {
+    let followee_principal_id = PrincipalId::new_user_test_id(1000);
+    let followee_neuron_id = neuron_id(followee_principal_id, /*memo*/ 0);
+
+    let follower_principal_id = PrincipalId::new_user_test_id(1001);
+    let follower_neuron_id = neuron_id(follower_principal_id, /*memo*/ 0);
+
+    let proposer_principal_id = PrincipalId::new_user_test_id(1002);
+    let proposer_neuron_id = neuron_id(proposer_principal_id, /*memo*/ 0);
+
+    let mut canister_fixture = GovernanceCanisterFixtureBuilder::new()
+        .add_neuron(
+            NeuronBuilder::new(
+                followee_neuron_id.clone(),
+                E8,
+                NeuronPermission::all(&followee_principal_id),
+            )
+            .set_dissolve_delay(15778801),
+        )
+        .add_neuron(
+            NeuronBuilder::new(
+                follower_neuron_id.clone(),
+                E8,
+                NeuronPermission::all(&follower_principal_id),
+            )
+            .set_dissolve_delay(15778801),
+        )
+        .add_neuron(
+            NeuronBuilder::new(
+                proposer_neuron_id.clone(),
+                E8,
+                NeuronPermission::all(&proposer_principal_id),
+            )
+            .set_dissolve_delay(15778801),
+        )
+        .create();
+
+    // Following on an unspecified topic is rejected.
+    assert!(canister_fixture
+        .follow_topic(
+            &follower_neuron_id,
+            Topic::Unspecified,
+            vec![followee_neuron_id.clone()],
+            follower_principal_id
+        )
+        .is_err());
+
+    // The follower neuron follows the followee neuron on the Governance topic,
+    // which covers Motion proposals.
+    assert!(canister_fixture
+        .follow_topic(
+            &follower_neuron_id,
+            Topic::Governance,
+            vec![followee_neuron_id.clone()],
+            follower_principal_id
+        )
+        .is_ok());
+
+    let follower_neuron = canister_fixture.get_neuron(&follower_neuron_id);
+    assert_eq!(
+        follower_neuron.topic_followees,
+        btreemap! {
+            Topic::Governance as i32 => neuron::Followees {
+                followees: vec![followee_neuron_id.clone()]
+            }
+        }
+    );
+
+    let (proposal_id, _) = canister_fixture
+        .make_default_proposal(
+            &proposer_neuron_id,
+            Motion {
+                motion_text: "Test topic following".to_string(),
+            },
+            proposer_principal_id,
+        )
+        .unwrap();
+    assert_eq!(
+        canister_fixture.get_proposal_or_panic(proposal_id).topic,
+        Some(Topic::Governance as i32)
+    );
+
+    // Vote with the followee neuron, this should result in the follower neuron voting
+    assert!(canister_fixture
+        .vote(
+            &followee_neuron_id,
+            proposal_id,
+            Vote::Yes,
+            followee_principal_id
+        )
+        .is_ok());
+    let proposal_data = canister_fixture.get_proposal_or_panic(proposal_id);
+    let follower_ballot = proposal_data
+        .ballots
+        .get(&follower_neuron_id.to_string())
+        .expect("Expected the follower neuron to have a ballot");
+    assert_eq!(follower_ballot.vote, Vote::Yes as i32);
+
+    // The follower neuron now follows itself on Motion proposals, which takes
+    // precedence over the topic following.
+    assert!(canister_fixture
+        .follow(
+            &follower_neuron_id,
+            native_action_ids::MOTION,
+            vec![follower_neuron_id.clone()],
+            follower_principal_id,
+        )
+        .is_ok());
+
+    let (proposal_id, _) = canister_fixture
+        .make_default_proposal(
+            &proposer_neuron_id,
+            Motion {
+                motion_text: "Test topic following precedence".to_string(),
+            },
+            proposer_principal_id,
+        )
+        .unwrap();
+
+    assert!(canister_fixture
+        .vote(
+            &followee_neuron_id,
+            proposal_id,
+            Vote::Yes,
+            followee_principal_id
+        )
+        .is_ok());
+    let proposal_data = canister_fixture.get_proposal_or_panic(proposal_id);
+    let follower_ballot = proposal_data
+        .ballots
+        .get(&follower_neuron_id.to_string())
+        .expect("Expected the follower neuron to have a ballot");
+    assert_eq!(follower_ballot.vote, Vote::Unspecified as i32);
+}
+
 // Same as the previous test, but wait_for_quiet_state is None.
 #[test]
 fn test_register_vote_happy_no_wait_for_quiet() {
@@ -2503,6 +2650,71 @@ async fn test_disburse_maturity_with_modulation_succeeds() {
     assert_disburse_maturity_with_modulation_disburses_correctly(E8, -300, 97_000_000).await;
 }
 
+/// Tests that the maturity modulation in effect when a disbursement is finalized, rather than
+/// the one in effect when it was requested, determines the amount that is minted.
+#[test]
+fn test_disburse_maturity_applies_modulation_at_finalization() {
+    let user_principal = PrincipalId::new_user_test_id(1000);
+    let neuron_id = neuron_id(user_principal, /*memo*/ 0);
+
+    let mut canister_fixture = GovernanceCanisterFixtureBuilder::new()
+        .add_neuron(
+            NeuronBuilder::new(
+                neuron_id.clone(),
+                E8,
+                NeuronPermission::all(&user_principal),
+            )
+            .set_maturity(E8),
+        )
+        .set_maturity_modulation(200)
+        .create();
+    canister_fixture.run_periodic_tasks();
+
+    let destination_account = icrc_ledger_types::icrc1::account::Account {
+        owner: user_principal.into(),
+        subaccount: None,
+    };
+
+    let manage_neuron_response = canister_fixture.manage_neuron(
+        &neuron_id,
+        manage_neuron::Command::DisburseMaturity(DisburseMaturity {
+            percentage_to_disburse: 100,
+            to_account: None,
+        }),
+        user_principal,
+    );
+    match manage_neuron_response.command.unwrap() {
+        CommandResponse::DisburseMaturity(response) => {
+            assert_eq!(response.amount_disbursed_e8s, 102_000_000);
+            assert_eq!(response.amount_deducted_e8s, Some(E8));
+        }
+        CommandResponse::Error(error) => {
+            panic!("Unexpected error when disbursing maturity: {}", error)
+        }
+        _ => panic!("Unexpected command response when disbursing maturity"),
+    };
+
+    // The pending disbursement is visible in the neuron and has not been modulated yet.
+    let neuron = canister_fixture.get_neuron(&neuron_id);
+    assert_eq!(neuron.disburse_maturity_in_progress.len(), 1);
+    assert_eq!(neuron.disburse_maturity_in_progress[0].amount_e8s, E8);
+
+    // The maturity modulation drops before the disbursement is finalized.
+    canister_fixture.advance_time_by(7 * SECONDS_PER_DAY + 1);
+    canister_fixture.governance.proto.maturity_modulation = Some(MaturityModulation {
+        current_basis_points: Some(-300),
+        updated_at_timestamp_seconds: Some(canister_fixture.now()),
+    });
+    canister_fixture.run_periodic_tasks();
+
+    let neuron = canister_fixture.get_neuron(&neuron_id);
+    assert!(neuron.disburse_maturity_in_progress.is_empty());
+    assert_eq!(
+        canister_fixture.get_account_balance(&destination_account, TargetLedger::Sns),
+        97_000_000
+    );
+}
+
 /// Assert that the expected disbursement happens given an initial amount of maturity (of a neuron)
 /// and a maturity modulation value.
 async fn assert_disburse_maturity_with_modulation_disburses_correctly(
@@ -2565,8 +2777,10 @@ async fn assert_disburse_maturity_with_modulation_disburses_correctly(
 
     let DisburseMaturityResponse {
         amount_disbursed_e8s,
+        amount_deducted_e8s,
     } = disburse_maturity_response;
     assert_eq!(amount_disbursed_e8s, expected_amount_disbursed_e8s);
+    assert_eq!(amount_deducted_e8s, Some(initial_maturity_e8s));
 
     // Assert that the neuron's maturity is now zero
     let neuron = canister_fixture.get_neuron(&neuron_id);
@@ -62,6 +62,7 @@ lazy_static! {
         neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
             count: 1,
             dissolve_delay_interval_seconds: 1,
+            vesting_period_seconds: None,
         }),
         sale_delay_seconds: None,
     };
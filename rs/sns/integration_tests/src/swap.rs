@@ -504,6 +504,7 @@ fn begin_swap(
                 neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
                     count: neuron_basket_count,
                     dissolve_delay_interval_seconds: 7890000, // 3 months,
+                    vesting_period_seconds: None,
                 }),
                 sale_delay_seconds: None,
             }),
@@ -3123,6 +3124,7 @@ fn test_last_man_less_than_min() {
             neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
                 count: 1,
                 dissolve_delay_interval_seconds: 1,
+                vesting_period_seconds: None,
             }),
             sale_delay_seconds: None,
         }),
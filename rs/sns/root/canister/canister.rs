@@ -21,6 +21,7 @@ use ic_sns_root::{
     logs::{ERROR, INFO},
     pb::v1::{
         CanisterCallError, ListSnsCanistersRequest, ListSnsCanistersResponse,
+        ManageDappCanisterSettingsRequest, ManageDappCanisterSettingsResponse,
         RegisterDappCanisterRequest, RegisterDappCanisterResponse, RegisterDappCanistersRequest,
         RegisterDappCanistersResponse, SetDappControllersRequest, SetDappControllersResponse,
         SnsRootCanister,
@@ -375,6 +376,29 @@ async fn set_dapp_controllers_(request: SetDappControllersRequest) -> SetDappCon
     .await
 }
 
+/// Changes the settings of registered dapp canisters.
+///
+/// Caller must be the Governance canister. Otherwise, the request will be
+/// rejected. All of the listed canisters must be registered dapp canisters.
+#[export_name = "canister_update manage_dapp_canister_settings"]
+fn manage_dapp_canister_settings() {
+    log!(INFO, "manage_dapp_canister_settings");
+    over_async(candid_one, manage_dapp_canister_settings_);
+}
+
+#[candid_method(update, rename = "manage_dapp_canister_settings")]
+async fn manage_dapp_canister_settings_(
+    request: ManageDappCanisterSettingsRequest,
+) -> ManageDappCanisterSettingsResponse {
+    SnsRootCanister::manage_dapp_canister_settings(
+        &STATE,
+        &RealManagementCanisterClient::new(),
+        dfn_core::api::caller(),
+        &request,
+    )
+    .await
+}
+
 fn assert_state_is_valid(state: &SnsRootCanister) {
     assert!(state.governance_canister_id.is_some());
     assert!(state.ledger_canister_id.is_some());
@@ -153,6 +153,40 @@ pub mod set_dapp_controllers_response {
         pub err: ::core::option::Option<super::CanisterCallError>,
     }
 }
+/// Change the settings of the listed dapp canisters. Only the settings that are
+/// set are changed. All listed canisters must be registered dapp canisters.
+/// Same proto in governance.proto. TODO(NNS1-1589)
+#[derive(
+    candid::CandidType,
+    candid::Deserialize,
+    comparable::Comparable,
+    Clone,
+    PartialEq,
+    ::prost::Message,
+)]
+pub struct ManageDappCanisterSettingsRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub canister_ids: ::prost::alloc::vec::Vec<::ic_base_types::PrincipalId>,
+    #[prost(uint64, optional, tag = "2")]
+    pub compute_allocation: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag = "3")]
+    pub memory_allocation: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag = "4")]
+    pub freezing_threshold: ::core::option::Option<u64>,
+}
+#[derive(
+    candid::CandidType,
+    candid::Deserialize,
+    comparable::Comparable,
+    Clone,
+    PartialEq,
+    ::prost::Message,
+)]
+pub struct ManageDappCanisterSettingsResponse {
+    /// Absent if all settings were applied successfully.
+    #[prost(string, optional, tag = "1")]
+    pub failure_reason: ::core::option::Option<::prost::alloc::string::String>,
+}
 #[derive(
     candid::CandidType,
     candid::Deserialize,
@@ -6,6 +6,7 @@ use crate::{
     logs::{ERROR, INFO},
     pb::v1::{
         set_dapp_controllers_response, CanisterCallError, ListSnsCanistersResponse,
+        ManageDappCanisterSettingsRequest, ManageDappCanisterSettingsResponse,
         RegisterDappCanistersRequest, RegisterDappCanistersResponse, SetDappControllersRequest,
         SetDappControllersResponse, SnsRootCanister,
     },
@@ -672,6 +673,85 @@ impl SnsRootCanister {
         SetDappControllersResponse { failed_updates }
     }
 
+    /// Changes the settings of the registered dapp canisters listed in the request.
+    ///
+    /// Caller must be the governance canister. Otherwise, the request will be
+    /// rejected.
+    ///
+    /// Every listed canister must be a registered dapp canister; if any is not,
+    /// no changes are made. Only the settings that are set in the request are
+    /// changed. Since updating many canisters cannot be done atomically, a
+    /// failure to update one canister does not prevent the others from being
+    /// updated; all failures are reported in `failure_reason`.
+    pub async fn manage_dapp_canister_settings(
+        self_ref: &'static LocalKey<RefCell<Self>>,
+        management_canister_client: &impl ManagementCanisterClient,
+        caller: PrincipalId,
+        request: &ManageDappCanisterSettingsRequest,
+    ) -> ManageDappCanisterSettingsResponse {
+        let is_authorized =
+            self_ref.with(|self_ref| caller == self_ref.borrow().governance_canister_id());
+        if !is_authorized {
+            return ManageDappCanisterSettingsResponse {
+                failure_reason: Some(format!("Caller ({caller}) is not authorized.")),
+            };
+        }
+
+        let unregistered_canister_ids = self_ref.with(|self_ref| {
+            let self_ref = self_ref.borrow();
+            request
+                .canister_ids
+                .iter()
+                .filter(|canister_id| !self_ref.dapp_canister_ids.contains(canister_id))
+                .map(|canister_id| canister_id.to_string())
+                .collect::<Vec<_>>()
+        });
+        if !unregistered_canister_ids.is_empty() {
+            return ManageDappCanisterSettingsResponse {
+                failure_reason: Some(format!(
+                    "Operation aborted; no changes have been made: the following canisters \
+                     are not registered dapp canisters: {}",
+                    unregistered_canister_ids.join(", ")
+                )),
+            };
+        }
+
+        let settings = CanisterSettingsArgs {
+            controllers: None,
+            compute_allocation: request.compute_allocation.map(candid::Nat::from),
+            memory_allocation: request.memory_allocation.map(candid::Nat::from),
+            freezing_threshold: request.freezing_threshold.map(candid::Nat::from),
+        };
+
+        let mut failures = vec![];
+        for dapp_canister_id in &request.canister_ids {
+            let update_settings_args = UpdateSettingsArgs {
+                canister_id: *dapp_canister_id,
+                settings: settings.clone(),
+            };
+            if let Err(err) = management_canister_client
+                .update_settings(&update_settings_args)
+                .await
+            {
+                log!(
+                    ERROR,
+                    "Unable to update the settings of {dapp_canister_id}: {err:#?}"
+                );
+                failures.push(format!("{dapp_canister_id}: {}", err.description));
+            }
+        }
+
+        let failure_reason = if failures.is_empty() {
+            None
+        } else {
+            Some(format!(
+                "Failed to update the settings of the following canisters: {}",
+                failures.join("; ")
+            ))
+        };
+        ManageDappCanisterSettingsResponse { failure_reason }
+    }
+
     /// Runs periodic tasks that are not directly triggered by user input.
     pub async fn run_periodic_tasks(
         self_ref: &'static LocalKey<RefCell<Self>>,
@@ -2277,6 +2357,156 @@ mod tests {
         assert_eq!(state, original_state, "{state:#?}");
     }
 
+    #[tokio::test]
+    async fn test_manage_dapp_canister_settings_updates_all_listed_canisters() {
+        // Step 1: Prepare the world.
+        thread_local! {
+            static STATE: RefCell<SnsRootCanister> = RefCell::new(SnsRootCanister {
+                governance_canister_id: Some(PrincipalId::new_user_test_id(1)),
+                ledger_canister_id: Some(PrincipalId::new_user_test_id(2)),
+                swap_canister_id: Some(PrincipalId::new_user_test_id(99)),
+                dapp_canister_ids: vec![
+                    PrincipalId::new_user_test_id(3),
+                    PrincipalId::new_user_test_id(5),
+                ],
+                index_canister_id: Some(PrincipalId::new_user_test_id(4)),
+                ..Default::default()
+            });
+        }
+        let expected_settings = CanisterSettingsArgs {
+            controllers: None,
+            compute_allocation: None,
+            memory_allocation: Some(candid::Nat::from(1_000_000_u64)),
+            freezing_threshold: Some(candid::Nat::from(2_592_000_u64)),
+        };
+
+        // Step 1.1: Prepare helpers.
+        let management_canister_client = MockManagementCanisterClient::new(vec![
+            ManagementCanisterClientCall::UpdateSettings {
+                update_settings_args: UpdateSettingsArgs {
+                    canister_id: PrincipalId::new_user_test_id(3),
+                    settings: expected_settings.clone(),
+                },
+                result: Ok(EmptyBlob {}),
+            },
+            ManagementCanisterClientCall::UpdateSettings {
+                update_settings_args: UpdateSettingsArgs {
+                    canister_id: PrincipalId::new_user_test_id(5),
+                    settings: expected_settings,
+                },
+                result: Err(CanisterCallError {
+                    code: Some(3),
+                    description: "Canister not found".to_string(),
+                }),
+            },
+        ]);
+
+        // Step 2: Run code under test.
+        let response = SnsRootCanister::manage_dapp_canister_settings(
+            &STATE,
+            &management_canister_client,
+            STATE.with(|state| state.borrow().governance_canister_id()),
+            &ManageDappCanisterSettingsRequest {
+                canister_ids: vec![
+                    PrincipalId::new_user_test_id(3),
+                    PrincipalId::new_user_test_id(5),
+                ],
+                compute_allocation: None,
+                memory_allocation: Some(1_000_000),
+                freezing_threshold: Some(2_592_000),
+            },
+        )
+        .await;
+
+        // Step 3: Inspect results. The second canister's failure is reported, but
+        // does not prevent the first canister from being updated.
+        management_canister_client.assert_all_calls_consumed();
+        let failure_reason = response.failure_reason.unwrap();
+        assert!(
+            failure_reason.contains(&PrincipalId::new_user_test_id(5).to_string()),
+            "{failure_reason}"
+        );
+        assert!(
+            !failure_reason.contains(&PrincipalId::new_user_test_id(3).to_string()),
+            "{failure_reason}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_manage_dapp_canister_settings_rejects_unregistered_canisters() {
+        // Step 1: Prepare the world.
+        thread_local! {
+            static STATE: RefCell<SnsRootCanister> = RefCell::new(SnsRootCanister {
+                governance_canister_id: Some(PrincipalId::new_user_test_id(1)),
+                ledger_canister_id: Some(PrincipalId::new_user_test_id(2)),
+                swap_canister_id: Some(PrincipalId::new_user_test_id(99)),
+                dapp_canister_ids: vec![PrincipalId::new_user_test_id(3)],
+                index_canister_id: Some(PrincipalId::new_user_test_id(4)),
+                ..Default::default()
+            });
+        }
+        let unregistered_canister_id = PrincipalId::new_user_test_id(6);
+
+        // Step 1.1: Prepare helpers. No calls are expected.
+        let management_canister_client = MockManagementCanisterClient::new(vec![]);
+
+        // Step 2: Run code under test.
+        let response = SnsRootCanister::manage_dapp_canister_settings(
+            &STATE,
+            &management_canister_client,
+            STATE.with(|state| state.borrow().governance_canister_id()),
+            &ManageDappCanisterSettingsRequest {
+                canister_ids: vec![PrincipalId::new_user_test_id(3), unregistered_canister_id],
+                compute_allocation: Some(10),
+                memory_allocation: None,
+                freezing_threshold: None,
+            },
+        )
+        .await;
+
+        // Step 3: Inspect results.
+        let failure_reason = response.failure_reason.unwrap();
+        assert!(
+            failure_reason.contains(&unregistered_canister_id.to_string()),
+            "{failure_reason}"
+        );
+        management_canister_client.assert_all_calls_consumed();
+    }
+
+    #[tokio::test]
+    async fn test_manage_dapp_canister_settings_rejects_non_governance_caller() {
+        // Step 1: Prepare the world.
+        thread_local! {
+            static STATE: RefCell<SnsRootCanister> = RefCell::new(SnsRootCanister {
+                governance_canister_id: Some(PrincipalId::new_user_test_id(1)),
+                ledger_canister_id: Some(PrincipalId::new_user_test_id(2)),
+                swap_canister_id: Some(PrincipalId::new_user_test_id(99)),
+                dapp_canister_ids: vec![PrincipalId::new_user_test_id(3)],
+                index_canister_id: Some(PrincipalId::new_user_test_id(4)),
+                ..Default::default()
+            });
+        }
+        let management_canister_client = MockManagementCanisterClient::new(vec![]);
+
+        // Step 2: Run code under test.
+        let response = SnsRootCanister::manage_dapp_canister_settings(
+            &STATE,
+            &management_canister_client,
+            STATE.with(|state| state.borrow().swap_canister_id()),
+            &ManageDappCanisterSettingsRequest {
+                canister_ids: vec![PrincipalId::new_user_test_id(3)],
+                compute_allocation: Some(10),
+                memory_allocation: None,
+                freezing_threshold: None,
+            },
+        )
+        .await;
+
+        // Step 3: Inspect results.
+        assert!(response.failure_reason.is_some(), "{response:#?}");
+        management_canister_client.assert_all_calls_consumed();
+    }
+
     #[test]
     fn test_list_sns_canisters() {
         let state = SnsRootCanister {
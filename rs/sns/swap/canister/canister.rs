@@ -21,7 +21,8 @@ use ic_sns_swap::{
     memory::UPGRADES_MEMORY,
     pb::v1::{
         ErrorRefundIcpRequest, ErrorRefundIcpResponse, FinalizeSwapRequest, FinalizeSwapResponse,
-        GetBuyerStateRequest, GetBuyerStateResponse, GetBuyersTotalRequest, GetBuyersTotalResponse,
+        GetAutoFinalizationStatusRequest, GetAutoFinalizationStatusResponse, GetBuyerStateRequest,
+        GetBuyerStateResponse, GetBuyersTotalRequest, GetBuyersTotalResponse,
         GetCanisterStatusRequest, GetDerivedStateRequest, GetDerivedStateResponse, GetInitRequest,
         GetInitResponse, GetLifecycleRequest, GetLifecycleResponse, GetOpenTicketRequest,
         GetOpenTicketResponse, GetSaleParametersRequest, GetSaleParametersResponse,
@@ -287,6 +288,21 @@ fn get_lifecycle_(request: GetLifecycleRequest) -> GetLifecycleResponse {
     swap().get_lifecycle(&request)
 }
 
+/// Returns the progress of finalizing the swap, and when the canister
+/// heartbeat will next attempt to finalize it.
+#[export_name = "canister_query get_auto_finalization_status"]
+fn get_auto_finalization_status() {
+    over(candid_one, get_auto_finalization_status_)
+}
+
+#[candid_method(query, rename = "get_auto_finalization_status")]
+fn get_auto_finalization_status_(
+    request: GetAutoFinalizationStatusRequest,
+) -> GetAutoFinalizationStatusResponse {
+    log!(INFO, "get_auto_finalization_status");
+    swap().get_auto_finalization_status(&request, now_seconds())
+}
+
 /// Returns the initialization data of the canister
 #[export_name = "canister_query get_init"]
 fn get_init() {
@@ -378,7 +394,8 @@ fn notify_payment_failure_(_request: NotifyPaymentFailureRequest) -> NotifyPayme
 // ===               Canister helper & boilerplate methods                   ===
 // =============================================================================
 
-/// Tries to commit or abort the swap if the parameters have been satisfied.
+/// Tries to commit or abort the swap if the parameters have been satisfied,
+/// and to (resume) finalize the swap once it has been committed or aborted.
 #[export_name = "canister_heartbeat"]
 fn canister_heartbeat() {
     const NUMBER_OF_TICKETS_THRESHOLD: u64 = 100_000_000; // 100M * ~size(ticket) = ~25GB
@@ -398,6 +415,18 @@ fn canister_heartbeat() {
     if swap_mut().try_commit_or_abort(now) {
         log!(INFO, "Swap committed/aborted at timestamp {}", now);
     }
+    if swap().should_auto_finalize(now) {
+        log!(
+            INFO,
+            "Automatically finalizing the swap at timestamp {}",
+            now
+        );
+        // finalize acquires the finalize_swap lock before its first await,
+        // so later heartbeats will not start another attempt concurrently.
+        dfn_core::api::futures::spawn(async {
+            finalize_swap_(FinalizeSwapRequest {}).await;
+        });
+    }
 }
 
 fn now_seconds() -> u64 {
@@ -647,7 +676,7 @@ fn main() {}
 mod tests {
     use super::*;
     use async_trait::async_trait;
-    use ic_ic00_types::CanisterStatusType;
+    use ic_ic00_types::{CanisterStatusType, LogVisibility};
 
     /// A test that fails if the API was updated but the candid definition was not.
     #[test]
@@ -684,6 +713,10 @@ mod tests {
             None,
             0,
             0,
+            0,
+            0,
+            None,
+            LogVisibility::Controllers,
         )
     }
 
@@ -89,9 +89,11 @@
 /// offered divided by the total number of ICP tokens contributed to
 /// the swap. In this state, a call to `finalize` will create SNS
 /// neurons for each participant and transfer ICP to the SNS governance
-/// canister. The call to `finalize` does not happen automatically
-/// (i.e., on the canister heartbeat) so that there is a caller to
-/// respond to with potential errors.
+/// canister. `finalize` can be called by anyone, and is also attempted
+/// automatically on the canister heartbeat. Each step of finalization is
+/// recorded in `finalization_progress` once it completes, so that a
+/// finalization that failed midway is resumed (rather than repeated)
+/// by the next attempt.
 ///
 /// Step 3b. (State ABORTED). If the parameters of the swap have not
 /// been satisfied before the due date/time, the swap is aborted and
@@ -202,6 +204,38 @@ pub struct Swap {
     /// running purge_old_tickets routine.
     #[prost(bytes = "vec", optional, tag = "14")]
     pub purge_old_tickets_next_principal: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// The steps of finalization that have been completed so far, and
+    /// information about the latest attempt. Unset until the first attempt
+    /// to finalize the swap.
+    #[prost(message, optional, tag = "15")]
+    pub finalization_progress: ::core::option::Option<FinalizationProgress>,
+}
+/// Persisted state of the finalization of a swap. A step is only recorded
+/// as completed if it succeeded without any errors, and completed steps are
+/// skipped by subsequent calls to `finalize_swap`.
+#[derive(
+    candid::CandidType,
+    candid::Deserialize,
+    serde::Serialize,
+    comparable::Comparable,
+    Clone,
+    PartialEq,
+    ::prost::Message,
+)]
+pub struct FinalizationProgress {
+    /// The steps that completed successfully, in the order they completed.
+    #[prost(enumeration = "FinalizationStep", repeated, tag = "1")]
+    pub completed_steps: ::prost::alloc::vec::Vec<i32>,
+    /// The number of times finalization has been attempted, both by calls to
+    /// `finalize_swap` and automatically by the canister heartbeat.
+    #[prost(uint64, tag = "2")]
+    pub attempt_count: u64,
+    /// When finalization was last attempted.
+    #[prost(uint64, optional, tag = "3")]
+    pub latest_attempt_timestamp_seconds: ::core::option::Option<u64>,
+    /// The response of the latest attempt.
+    #[prost(message, optional, tag = "4")]
+    pub latest_attempt_response: ::core::option::Option<FinalizeSwapResponse>,
 }
 /// The initialisation data of the canister. Always specified on
 /// canister creation, and cannot be modified afterwards.
@@ -395,6 +429,11 @@ pub mod params {
         /// The amount of additional time it takes for the next neuron to dissolve.
         #[prost(uint64, tag = "2")]
         pub dissolve_delay_interval_seconds: u64,
+        /// The vesting period of each neuron in the basket. While a neuron is
+        /// vesting, it cannot be dissolved and its dissolve delay cannot be
+        /// decreased. If unset, the neurons do not vest.
+        #[prost(uint64, optional, tag = "3")]
+        pub vesting_period_seconds: ::core::option::Option<u64>,
     }
 }
 #[derive(
@@ -562,6 +601,10 @@ pub mod sns_neuron_recipe {
         /// of claiming.
         #[prost(message, repeated, tag = "3")]
         pub followees: ::prost::alloc::vec::Vec<super::NeuronId>,
+        /// The vesting period in seconds that the Neuron will be created with. See
+        /// `Params.NeuronBasketConstructionParameters.vesting_period_seconds`.
+        #[prost(uint64, optional, tag = "4")]
+        pub vesting_period_seconds: ::core::option::Option<u64>,
     }
     /// The various statuses of creation that a SnsNeuronRecipe can have in an SNS.
     #[derive(
@@ -1535,6 +1578,40 @@ pub struct GetLifecycleResponse {
     #[prost(uint64, optional, tag = "2")]
     pub decentralization_sale_open_timestamp_seconds: ::core::option::Option<u64>,
 }
+/// Request struct for the method `get_auto_finalization_status`
+#[derive(
+    candid::CandidType,
+    candid::Deserialize,
+    serde::Serialize,
+    comparable::Comparable,
+    Clone,
+    PartialEq,
+    ::prost::Message,
+)]
+pub struct GetAutoFinalizationStatusRequest {}
+/// Response struct for the method `get_auto_finalization_status`
+#[derive(
+    candid::CandidType,
+    candid::Deserialize,
+    serde::Serialize,
+    comparable::Comparable,
+    Clone,
+    PartialEq,
+    ::prost::Message,
+)]
+pub struct GetAutoFinalizationStatusResponse {
+    /// Whether all the finalization steps that apply to the swap's
+    /// lifecycle have completed.
+    #[prost(bool, optional, tag = "1")]
+    pub is_finalized: ::core::option::Option<bool>,
+    #[prost(message, optional, tag = "2")]
+    pub finalization_progress: ::core::option::Option<FinalizationProgress>,
+    /// When the canister heartbeat will next attempt to finalize the swap.
+    /// Unset if no automatic attempt is pending, e.g., because the swap is
+    /// not yet in a terminal state or is already finalized.
+    #[prost(uint64, optional, tag = "3")]
+    pub next_automatic_attempt_timestamp_seconds: ::core::option::Option<u64>,
+}
 /// Request struct for the method `get_init`
 #[derive(
     candid::CandidType,
@@ -2161,3 +2238,59 @@ impl Lifecycle {
         }
     }
 }
+/// The steps performed by `finalize_swap`, in the order in which they are
+/// performed. Which steps apply depends on whether the swap was committed
+/// or aborted.
+#[derive(
+    candid::CandidType,
+    candid::Deserialize,
+    serde::Serialize,
+    comparable::Comparable,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
+#[repr(i32)]
+pub enum FinalizationStep {
+    Unspecified = 0,
+    /// Transfer ICP to the SNS governance canister (COMMITTED) or refund
+    /// it to the buyers (ABORTED).
+    SweepIcp = 1,
+    /// Report the outcome of the swap to NNS governance so that the
+    /// Community Fund participation can be settled.
+    SettleCommunityFundParticipation = 2,
+    /// Return control of the dapp canisters to the fallback controllers
+    /// (ABORTED only).
+    SetDappControllers = 3,
+    /// Transfer SNS tokens to the neuron subaccounts (COMMITTED only).
+    SweepSns = 4,
+    /// Claim the SNS neurons of the participants (COMMITTED only).
+    ClaimNeurons = 5,
+    /// Set SNS governance to normal mode (COMMITTED only).
+    SetMode = 6,
+}
+impl FinalizationStep {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            FinalizationStep::Unspecified => "FINALIZATION_STEP_UNSPECIFIED",
+            FinalizationStep::SweepIcp => "FINALIZATION_STEP_SWEEP_ICP",
+            FinalizationStep::SettleCommunityFundParticipation => {
+                "FINALIZATION_STEP_SETTLE_COMMUNITY_FUND_PARTICIPATION"
+            }
+            FinalizationStep::SetDappControllers => "FINALIZATION_STEP_SET_DAPP_CONTROLLERS",
+            FinalizationStep::SweepSns => "FINALIZATION_STEP_SWEEP_SNS",
+            FinalizationStep::ClaimNeurons => "FINALIZATION_STEP_CLAIM_NEURONS",
+            FinalizationStep::SetMode => "FINALIZATION_STEP_SET_MODE",
+        }
+    }
+}
@@ -10,10 +10,11 @@ use crate::pb::v1::{
     sns_neuron_recipe::Investor,
     sns_neuron_recipe::{ClaimedStatus, NeuronAttributes},
     BuyerState, CanisterCallError, CfInvestment, DerivedState, DirectInvestment,
-    ErrorRefundIcpRequest, ErrorRefundIcpResponse, FinalizeSwapResponse, GetBuyerStateRequest,
-    GetBuyerStateResponse, GetBuyersTotalResponse, GetDerivedStateResponse, GetLifecycleRequest,
-    GetLifecycleResponse, GetOpenTicketRequest, GetOpenTicketResponse, GetSaleParametersRequest,
-    GetSaleParametersResponse, GetStateResponse, Init, Lifecycle,
+    ErrorRefundIcpRequest, ErrorRefundIcpResponse, FinalizationProgress, FinalizationStep,
+    FinalizeSwapResponse, GetAutoFinalizationStatusRequest, GetAutoFinalizationStatusResponse,
+    GetBuyerStateRequest, GetBuyerStateResponse, GetBuyersTotalResponse, GetDerivedStateResponse,
+    GetLifecycleRequest, GetLifecycleResponse, GetOpenTicketRequest, GetOpenTicketResponse,
+    GetSaleParametersRequest, GetSaleParametersResponse, GetStateResponse, Init, Lifecycle,
     ListCommunityFundParticipantsRequest, ListCommunityFundParticipantsResponse,
     ListDirectParticipantsRequest, ListDirectParticipantsResponse, ListSnsNeuronRecipesRequest,
     ListSnsNeuronRecipesResponse, NeuronId as SaleNeuronId, NewSaleTicketRequest,
@@ -96,6 +97,10 @@ pub const FIRST_PRINCIPAL_BYTES: [u8; PrincipalId::MAX_LENGTH_IN_BYTES] =
 /// 2. Avoid having the SNS Governance canister hit the instruction limit per message.
 pub const CLAIM_SWAP_NEURONS_BATCH_SIZE: usize = 500;
 
+/// The minimum amount of time between two attempts of the canister heartbeat
+/// to finalize the swap, counted from the latest attempt (automatic or not).
+pub const AUTO_FINALIZATION_RETRY_INTERVAL_SECONDS: u64 = 60 * 60; // 1 hour
+
 impl From<(Option<i32>, String)> for CanisterCallError {
     fn from((code, description): (Option<i32>, String)) -> Self {
         Self { code, description }
@@ -244,6 +249,7 @@ impl Swap {
             next_ticket_id: Some(0),
             purge_old_tickets_last_completion_timestamp_nanoseconds: Some(0),
             purge_old_tickets_next_principal: Some(FIRST_PRINCIPAL_BYTES.to_vec()),
+            finalization_progress: None,
         }
     }
 
@@ -991,6 +997,12 @@ impl Swap {
     /// IC messages boundaries, it only allows one invocation at a time.
     /// Additional attempts to invoke finalize will return without
     /// performing any subactions.
+    ///
+    /// Each subaction that succeeds is recorded in `finalization_progress`
+    /// and is skipped by later invocations, so that a finalization that
+    /// halted midway resumes at the subaction that failed. The canister
+    /// heartbeat invokes finalize until all subactions have completed
+    /// (see `should_auto_finalize`).
     pub async fn finalize(
         &mut self,
         now_fn: fn(bool) -> u64,
@@ -1018,6 +1030,13 @@ impl Swap {
             )
             .await;
 
+        if self.lifecycle_is_terminal() {
+            let finalization_progress = self.finalization_progress_mut();
+            finalization_progress.attempt_count += 1;
+            finalization_progress.latest_attempt_timestamp_seconds = Some(now_fn(false));
+            finalization_progress.latest_attempt_response = Some(finalize_swap_response.clone());
+        }
+
         if finalize_swap_response.has_error_message() {
             log!(
                 ERROR,
@@ -1068,27 +1087,41 @@ impl Swap {
         }
 
         // Transfer the ICP tokens from the Sale canister.
-        finalize_swap_response.set_sweep_icp_result(self.sweep_icp(now_fn, icp_ledger).await);
-        if finalize_swap_response.has_error_message() {
-            return finalize_swap_response;
+        if !self.is_finalization_step_completed(FinalizationStep::SweepIcp) {
+            finalize_swap_response.set_sweep_icp_result(self.sweep_icp(now_fn, icp_ledger).await);
+            if finalize_swap_response.has_error_message() {
+                return finalize_swap_response;
+            }
+            self.record_completed_finalization_step(FinalizationStep::SweepIcp);
         }
 
         // Settle the CommunityFund's participation in the Sale (if any).
-        finalize_swap_response.set_settle_community_fund_participation_result(
-            self.settle_community_fund_participation(nns_governance_client)
-                .await,
-        );
-        if finalize_swap_response.has_error_message() {
-            return finalize_swap_response;
+        if !self.is_finalization_step_completed(FinalizationStep::SettleCommunityFundParticipation)
+        {
+            finalize_swap_response.set_settle_community_fund_participation_result(
+                self.settle_community_fund_participation(nns_governance_client)
+                    .await,
+            );
+            if finalize_swap_response.has_error_message() {
+                return finalize_swap_response;
+            }
+            self.record_completed_finalization_step(
+                FinalizationStep::SettleCommunityFundParticipation,
+            );
         }
 
         if self.should_restore_dapp_control() {
             // Restore controllers of dapp canisters to their original
             // owners (i.e. self.init.fallback_controller_principal_ids).
-            finalize_swap_response.set_set_dapp_controllers_result(
-                self.set_dapp_controllers_for_finalize(sns_root_client)
-                    .await,
-            );
+            if !self.is_finalization_step_completed(FinalizationStep::SetDappControllers) {
+                finalize_swap_response.set_set_dapp_controllers_result(
+                    self.set_dapp_controllers_for_finalize(sns_root_client)
+                        .await,
+                );
+                if !finalize_swap_response.has_error_message() {
+                    self.record_completed_finalization_step(FinalizationStep::SetDappControllers);
+                }
+            }
 
             // In the case of returning control of the dapp(s) to the fallback
             // controllers, finalize() need not do any more work, so always return
@@ -1097,26 +1130,97 @@ impl Swap {
         }
 
         // Transfer the SNS tokens from the Sale canister.
-        finalize_swap_response.set_sweep_sns_result(self.sweep_sns(now_fn, sns_ledger).await);
-        if finalize_swap_response.has_error_message() {
-            return finalize_swap_response;
+        if !self.is_finalization_step_completed(FinalizationStep::SweepSns) {
+            finalize_swap_response.set_sweep_sns_result(self.sweep_sns(now_fn, sns_ledger).await);
+            if finalize_swap_response.has_error_message() {
+                return finalize_swap_response;
+            }
+            self.record_completed_finalization_step(FinalizationStep::SweepSns);
         }
 
         // Once SNS tokens have been distributed to the correct accounts, claim
         // them as neurons on behalf of the Sale participants.
-        finalize_swap_response
-            .set_claim_neuron_result(self.claim_swap_neurons(sns_governance_client).await);
-        if finalize_swap_response.has_error_message() {
-            return finalize_swap_response;
+        if !self.is_finalization_step_completed(FinalizationStep::ClaimNeurons) {
+            finalize_swap_response
+                .set_claim_neuron_result(self.claim_swap_neurons(sns_governance_client).await);
+            if finalize_swap_response.has_error_message() {
+                return finalize_swap_response;
+            }
+            self.record_completed_finalization_step(FinalizationStep::ClaimNeurons);
         }
 
-        finalize_swap_response.set_set_mode_call_result(
-            Self::set_sns_governance_to_normal_mode(sns_governance_client).await,
-        );
+        if !self.is_finalization_step_completed(FinalizationStep::SetMode) {
+            finalize_swap_response.set_set_mode_call_result(
+                Self::set_sns_governance_to_normal_mode(sns_governance_client).await,
+            );
+            if !finalize_swap_response.has_error_message() {
+                self.record_completed_finalization_step(FinalizationStep::SetMode);
+            }
+        }
 
         finalize_swap_response
     }
 
+    fn finalization_progress_mut(&mut self) -> &mut FinalizationProgress {
+        self.finalization_progress
+            .get_or_insert_with(FinalizationProgress::default)
+    }
+
+    fn is_finalization_step_completed(&self, step: FinalizationStep) -> bool {
+        self.finalization_progress
+            .as_ref()
+            .map(|progress| progress.is_step_completed(step))
+            .unwrap_or(false)
+    }
+
+    fn record_completed_finalization_step(&mut self, step: FinalizationStep) {
+        log!(INFO, "Finalization step {:?} completed", step);
+        self.finalization_progress_mut().record_completed_step(step);
+    }
+
+    /// Returns true if all the finalization steps that apply to the
+    /// (terminal) lifecycle of the swap have completed.
+    pub fn is_finalized(&self) -> bool {
+        self.finalization_progress
+            .as_ref()
+            .map(|progress| progress.are_all_steps_completed(self.lifecycle()))
+            .unwrap_or(false)
+    }
+
+    /// When the canister heartbeat should next attempt to finalize the
+    /// swap, or None if no automatic attempt is needed. The first attempt
+    /// is due as soon as the swap reaches a terminal state; subsequent
+    /// attempts are spaced by AUTO_FINALIZATION_RETRY_INTERVAL_SECONDS.
+    fn next_automatic_finalization_attempt_timestamp_seconds(&self) -> Option<u64> {
+        if !self.lifecycle_is_terminal() || self.is_finalized() {
+            return None;
+        }
+        let latest_attempt_timestamp_seconds = self
+            .finalization_progress
+            .as_ref()
+            .and_then(|progress| progress.latest_attempt_timestamp_seconds);
+        Some(match latest_attempt_timestamp_seconds {
+            Some(timestamp_seconds) => {
+                timestamp_seconds.saturating_add(AUTO_FINALIZATION_RETRY_INTERVAL_SECONDS)
+            }
+            None => 0,
+        })
+    }
+
+    /// Returns true if the canister heartbeat should call finalize, i.e., the
+    /// swap is in a terminal state but not yet fully finalized, no call to
+    /// finalize is in progress, and the retry interval has elapsed since the
+    /// latest attempt.
+    pub fn should_auto_finalize(&self, now_seconds: u64) -> bool {
+        if self.is_finalize_swap_locked() {
+            return false;
+        }
+        match self.next_automatic_finalization_attempt_timestamp_seconds() {
+            Some(next_attempt_timestamp_seconds) => now_seconds >= next_attempt_timestamp_seconds,
+            None => false,
+        }
+    }
+
     /// In state COMMITTED. Claims SNS Neurons on behalf of participants.
     ///
     /// Returns the following values:
@@ -1215,26 +1319,27 @@ impl Swap {
                 }
             };
 
-            let (dissolve_delay_seconds, memo, followees) = match recipe.neuron_attributes.as_ref()
-            {
-                Some(neuron_attribute) => (
-                    neuron_attribute.dissolve_delay_seconds,
-                    neuron_attribute.memo,
-                    neuron_attribute.followees.clone(),
-                ),
-                // SnsNeuronRecipe.neuron_attributes should always be present as it is set in `commit`.
-                // In the case of a bug due to programmer error, increment the invalid field.
-                // This will require a manual intervention via an upgrade to correct
-                None => {
-                    log!(
-                        ERROR,
-                        "Missing neuron_attributes information for neuron recipe {:?}",
-                        recipe,
-                    );
-                    sweep_result.invalid += 1;
-                    continue;
-                }
-            };
+            let (dissolve_delay_seconds, memo, followees, vesting_period_seconds) =
+                match recipe.neuron_attributes.as_ref() {
+                    Some(neuron_attribute) => (
+                        neuron_attribute.dissolve_delay_seconds,
+                        neuron_attribute.memo,
+                        neuron_attribute.followees.clone(),
+                        neuron_attribute.vesting_period_seconds,
+                    ),
+                    // SnsNeuronRecipe.neuron_attributes should always be present as it is set in `commit`.
+                    // In the case of a bug due to programmer error, increment the invalid field.
+                    // This will require a manual intervention via an upgrade to correct
+                    None => {
+                        log!(
+                            ERROR,
+                            "Missing neuron_attributes information for neuron recipe {:?}",
+                            recipe,
+                        );
+                        sweep_result.invalid += 1;
+                        continue;
+                    }
+                };
 
             let amount_e8s = match recipe.sns.as_ref() {
                 Some(transferable_amount) => transferable_amount.amount_e8s,
@@ -1300,6 +1405,7 @@ impl Swap {
                 dissolve_delay_seconds: Some(dissolve_delay_seconds),
                 source_nns_neuron_id,
                 followees,
+                vesting_period_seconds,
             });
 
             claimable_neurons_index.insert(neuron_id, recipe);
@@ -2331,6 +2437,22 @@ impl Swap {
         }
     }
 
+    /// Returns the progress of finalizing the swap, and when the canister
+    /// heartbeat will next attempt to finalize it (if at all).
+    pub fn get_auto_finalization_status(
+        &self,
+        _request: &GetAutoFinalizationStatusRequest,
+        now_seconds: u64,
+    ) -> GetAutoFinalizationStatusResponse {
+        GetAutoFinalizationStatusResponse {
+            is_finalized: Some(self.is_finalized()),
+            finalization_progress: self.finalization_progress.clone(),
+            next_automatic_attempt_timestamp_seconds: self
+                .next_automatic_finalization_attempt_timestamp_seconds()
+                .map(|timestamp_seconds| timestamp_seconds.max(now_seconds)),
+        }
+    }
+
     /// If there is an open sale ticket for the caller then it returns it;
     /// otherwise returns none.
     ///
@@ -2611,6 +2733,8 @@ fn create_sns_neuron_basket_for_direct_participant(
                 memo,
                 dissolve_delay_seconds: scheduled_vesting_event.dissolve_delay_seconds,
                 followees,
+                vesting_period_seconds: neuron_basket_construction_parameters
+                    .vesting_period_seconds,
             }),
             claimed_status: Some(ClaimedStatus::Pending as i32),
         });
@@ -2676,6 +2800,8 @@ fn create_sns_neuron_basket_for_cf_participant(
                 memo,
                 dissolve_delay_seconds: scheduled_vesting_event.dissolve_delay_seconds,
                 followees,
+                vesting_period_seconds: neuron_basket_construction_parameters
+                    .vesting_period_seconds,
             }),
             claimed_status: Some(ClaimedStatus::Pending as i32),
         });
@@ -2886,6 +3012,7 @@ mod tests {
         neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
             count: 12,
             dissolve_delay_interval_seconds: 30 * SECONDS_PER_DAY,
+            vesting_period_seconds: None,
         }),
         sale_delay_seconds: None,
     };
@@ -3164,6 +3291,7 @@ mod tests {
         let neuron_basket_construction_parameters = NeuronBasketConstructionParameters {
             count: 5,
             dissolve_delay_interval_seconds: 100,
+            vesting_period_seconds: None,
         };
 
         assert_eq!(
@@ -3221,6 +3349,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_neuron_basket_recipes_carry_vesting_period() {
+        let neuron_basket_construction_parameters = NeuronBasketConstructionParameters {
+            count: 3,
+            dissolve_delay_interval_seconds: 100,
+            vesting_period_seconds: Some(1000),
+        };
+        let principal = PrincipalId::new_user_test_id(1);
+
+        let direct_recipes = create_sns_neuron_basket_for_direct_participant(
+            &principal,
+            /* amount_sns_token_e8s = */ 30,
+            &neuron_basket_construction_parameters,
+            /* memo_offset = */ 0,
+        );
+        let cf_recipes = create_sns_neuron_basket_for_cf_participant(
+            &principal,
+            /* nns_neuron_id = */ 42,
+            /* amount_sns_token_e8s = */ 30,
+            &neuron_basket_construction_parameters,
+            /* memo_offset = */ 0,
+            PrincipalId::new_user_test_id(2),
+        );
+
+        assert_eq!(direct_recipes.len(), 3);
+        assert_eq!(cf_recipes.len(), 3);
+        for recipe in direct_recipes.iter().chain(cf_recipes.iter()) {
+            assert_eq!(
+                recipe
+                    .neuron_attributes
+                    .as_ref()
+                    .unwrap()
+                    .vesting_period_seconds,
+                Some(1000)
+            );
+        }
+    }
+
     proptest! {
         #[test]
         fn test_generate_vesting_schedule_proptest(
@@ -3231,6 +3397,7 @@ mod tests {
             let vesting_schedule = NeuronBasketConstructionParameters {
                 count,
                 dissolve_delay_interval_seconds,
+                vesting_period_seconds: None,
             }
             .generate_vesting_schedule(total_e8s);
 
@@ -3470,6 +3637,7 @@ mod tests {
                     neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
                         count: 1,
                         dissolve_delay_interval_seconds: 10,
+                        vesting_period_seconds: None,
                     }),
                     sale_delay_seconds: Some(10),
                 }),
@@ -3481,7 +3649,8 @@ mod tests {
                 decentralization_sale_open_timestamp_seconds: Some(1),
                 next_ticket_id: Some(0),
                 purge_old_tickets_last_completion_timestamp_nanoseconds: Some(0),
-                purge_old_tickets_next_principal: Some(FIRST_PRINCIPAL_BYTES.to_vec())
+                purge_old_tickets_next_principal: Some(FIRST_PRINCIPAL_BYTES.to_vec()),
+                finalization_progress: None,
             };
             let mut ticket_ids = HashSet::new();
             for pid in pids {
@@ -3709,6 +3878,7 @@ mod tests {
                 neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
                     count: 1,
                     dissolve_delay_interval_seconds: 1,
+                    vesting_period_seconds: None,
                 }),
                 sale_delay_seconds: Some(0),
             }),
@@ -3721,6 +3891,7 @@ mod tests {
             next_ticket_id: Some(0),
             purge_old_tickets_last_completion_timestamp_nanoseconds: Some(0),
             purge_old_tickets_next_principal: Some(FIRST_PRINCIPAL_BYTES.to_vec()),
+            finalization_progress: None,
         };
 
         let try_purge_old_tickets = |sale: &mut Swap, time: u64| loop {
@@ -3,10 +3,10 @@ use crate::pb::v1::{
     error_refund_icp_response, set_dapp_controllers_call_result, set_mode_call_result,
     set_mode_call_result::SetModeResult, settle_community_fund_participation_result,
     sns_neuron_recipe::ClaimedStatus, sns_neuron_recipe::Investor, BuyerState, CfInvestment,
-    CfNeuron, CfParticipant, DirectInvestment, ErrorRefundIcpResponse, FinalizeSwapResponse, Init,
-    Lifecycle, NeuronId as SaleNeuronId, OpenRequest, Params, SetDappControllersCallResult,
-    SetModeCallResult, SettleCommunityFundParticipationResult, SnsNeuronRecipe, SweepResult,
-    TransferableAmount,
+    CfNeuron, CfParticipant, DirectInvestment, ErrorRefundIcpResponse, FinalizationProgress,
+    FinalizationStep, FinalizeSwapResponse, Init, Lifecycle, NeuronId as SaleNeuronId, OpenRequest,
+    Params, SetDappControllersCallResult, SetModeCallResult,
+    SettleCommunityFundParticipationResult, SnsNeuronRecipe, SweepResult, TransferableAmount,
 };
 use crate::swap::is_valid_principal;
 use ic_base_types::{CanisterId, PrincipalId};
@@ -15,6 +15,7 @@ use ic_ledger_core::Tokens;
 use ic_nervous_system_common::ledger::ICRC1Ledger;
 use ic_nervous_system_common::SECONDS_PER_DAY;
 use ic_sns_governance::pb::v1::{ClaimedSwapNeuronStatus, NeuronId};
+use ic_sns_governance::types::ONE_YEAR_SECONDS;
 use icrc_ledger_types::icrc1::account::{Account, Subaccount};
 use std::str::FromStr;
 
@@ -162,6 +163,9 @@ impl Init {
 impl Params {
     const MIN_SALE_DURATION_SECONDS: u64 = SECONDS_PER_DAY;
     const MAX_SALE_DURATION_SECONDS: u64 = 90 * SECONDS_PER_DAY;
+    /// The vesting period of basket neurons can be at most as long as the
+    /// default maximum dissolve delay of SNS neurons.
+    const MAX_VESTING_PERIOD_SECONDS: u64 = 8 * ONE_YEAR_SECONDS;
 
     pub fn validate(&self, init: &Init) -> Result<(), String> {
         if self.min_icp_e8s == 0 {
@@ -280,6 +284,23 @@ impl Params {
             ));
         }
 
+        if neuron_basket.vesting_period_seconds == Some(0) {
+            return Err(
+                "neuron_basket_construction_parameters.vesting_period_seconds must be > 0 if set"
+                    .to_string(),
+            );
+        }
+
+        if let Some(vesting_period_seconds) = neuron_basket.vesting_period_seconds {
+            if vesting_period_seconds > Self::MAX_VESTING_PERIOD_SECONDS {
+                return Err(format!(
+                    "neuron_basket_construction_parameters.vesting_period_seconds ({}) must be <= {}",
+                    vesting_period_seconds,
+                    Self::MAX_VESTING_PERIOD_SECONDS,
+                ));
+            }
+        }
+
         let maximum_dissolve_delay = neuron_basket
             .count
             .saturating_mul(neuron_basket.dissolve_delay_interval_seconds)
@@ -565,6 +586,48 @@ impl Lifecycle {
     }
 }
 
+impl Lifecycle {
+    /// The steps that `finalize` needs to complete in this lifecycle, in
+    /// the order in which they are performed. Empty if the lifecycle is not
+    /// terminal.
+    pub fn finalization_steps(&self) -> &'static [FinalizationStep] {
+        match self {
+            Self::Committed => &[
+                FinalizationStep::SweepIcp,
+                FinalizationStep::SettleCommunityFundParticipation,
+                FinalizationStep::SweepSns,
+                FinalizationStep::ClaimNeurons,
+                FinalizationStep::SetMode,
+            ],
+            Self::Aborted => &[
+                FinalizationStep::SweepIcp,
+                FinalizationStep::SettleCommunityFundParticipation,
+                FinalizationStep::SetDappControllers,
+            ],
+            Self::Unspecified | Self::Pending | Self::Adopted | Self::Open => &[],
+        }
+    }
+}
+
+impl FinalizationProgress {
+    pub fn is_step_completed(&self, step: FinalizationStep) -> bool {
+        self.completed_steps.contains(&(step as i32))
+    }
+
+    pub fn record_completed_step(&mut self, step: FinalizationStep) {
+        if !self.is_step_completed(step) {
+            self.completed_steps.push(step as i32);
+        }
+    }
+
+    /// Whether all the finalization steps that apply to `lifecycle` have
+    /// completed. Always false for non-terminal lifecycles.
+    pub fn are_all_steps_completed(&self, lifecycle: Lifecycle) -> bool {
+        let steps = lifecycle.finalization_steps();
+        !steps.is_empty() && steps.iter().all(|step| self.is_step_completed(*step))
+    }
+}
+
 /// Result of a token transfer (commit or abort) on a ledger (ICP or
 /// SNS) for a single buyer.
 pub enum TransferResult {
@@ -791,6 +854,7 @@ mod tests {
     use ic_nervous_system_common::{
         assert_is_err, assert_is_ok, E8, SECONDS_PER_DAY, START_OF_2022_TIMESTAMP_SECONDS,
     };
+    use ic_sns_governance::types::ONE_YEAR_SECONDS;
     use lazy_static::lazy_static;
     use prost::Message;
     use std::mem;
@@ -808,6 +872,7 @@ mod tests {
         neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
             count: 3,
             dissolve_delay_interval_seconds: 7890000, // 3 months
+            vesting_period_seconds: None,
         }),
         sale_delay_seconds: None,
     };
@@ -903,6 +968,32 @@ mod tests {
         assert_is_err!(request.validate(START_OF_2022_TIMESTAMP_SECONDS, &INIT));
     }
 
+    #[test]
+    fn params_neuron_basket_vesting_period_validate() {
+        let params_with_vesting_period = |vesting_period_seconds| Params {
+            neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
+                vesting_period_seconds,
+                ..PARAMS
+                    .neuron_basket_construction_parameters
+                    .clone()
+                    .unwrap()
+            }),
+            ..PARAMS
+        };
+
+        assert_is_ok!(params_with_vesting_period(Some(365 * SECONDS_PER_DAY)).validate(&INIT));
+        assert_is_ok!(
+            params_with_vesting_period(Some(Params::MAX_VESTING_PERIOD_SECONDS)).validate(&INIT)
+        );
+        assert_is_err!(params_with_vesting_period(Some(0)).validate(&INIT));
+        assert_is_err!(
+            params_with_vesting_period(Some(Params::MAX_VESTING_PERIOD_SECONDS + 1))
+                .validate(&INIT)
+        );
+        assert_is_err!(params_with_vesting_period(Some(100 * ONE_YEAR_SECONDS)).validate(&INIT));
+        assert_is_err!(params_with_vesting_period(Some(u64::MAX)).validate(&INIT));
+    }
+
     #[test]
     fn participant_total_icp_e8s_no_overflow() {
         let participant = CfParticipant {
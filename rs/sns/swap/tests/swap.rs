@@ -40,7 +40,8 @@ use ic_sns_governance::{
     types::ONE_MONTH_SECONDS,
 };
 use ic_sns_swap::swap::{
-    CLAIM_SWAP_NEURONS_BATCH_SIZE, FIRST_PRINCIPAL_BYTES, SALE_NEURON_MEMO_RANGE_START,
+    AUTO_FINALIZATION_RETRY_INTERVAL_SECONDS, CLAIM_SWAP_NEURONS_BATCH_SIZE, FIRST_PRINCIPAL_BYTES,
+    SALE_NEURON_MEMO_RANGE_START,
 };
 use ic_sns_swap::{
     memory,
@@ -120,6 +121,7 @@ pub fn params() -> Params {
         neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
             count: 3,
             dissolve_delay_interval_seconds: 7890000, // 3 months
+            vesting_period_seconds: None,
         }),
         sale_delay_seconds: None,
     };
@@ -136,6 +138,7 @@ fn create_generic_committed_swap() -> Swap {
         neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
             count: 1,
             dissolve_delay_interval_seconds: ONE_MONTH_SECONDS,
+            vesting_period_seconds: None,
         }),
         ..params()
     };
@@ -157,6 +160,7 @@ fn create_generic_committed_swap() -> Swap {
         next_ticket_id: Some(0),
         purge_old_tickets_last_completion_timestamp_nanoseconds: Some(0),
         purge_old_tickets_next_principal: Some(FIRST_PRINCIPAL_BYTES.to_vec()),
+        finalization_progress: None,
     }
 }
 
@@ -1101,6 +1105,7 @@ async fn test_finalize_swap_ok() {
         neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
             count: 3,
             dissolve_delay_interval_seconds: 7890000, // 3 months
+            vesting_period_seconds: None,
         }),
         sale_delay_seconds: None,
     };
@@ -1122,6 +1127,7 @@ async fn test_finalize_swap_ok() {
         next_ticket_id: Some(0),
         purge_old_tickets_last_completion_timestamp_nanoseconds: Some(0),
         purge_old_tickets_next_principal: Some(vec![0; 32]),
+        finalization_progress: None,
     };
     assert!(swap.try_commit_or_abort(END_TIMESTAMP_SECONDS));
     assert_eq!(swap.lifecycle(), Committed);
@@ -1392,6 +1398,7 @@ async fn test_finalize_swap_abort() {
         neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
             count: 12,
             dissolve_delay_interval_seconds: 7890000, // 3 months
+            vesting_period_seconds: None,
         }),
         sale_delay_seconds: None,
     };
@@ -1411,6 +1418,7 @@ async fn test_finalize_swap_abort() {
         next_ticket_id: Some(0),
         purge_old_tickets_last_completion_timestamp_nanoseconds: Some(0),
         purge_old_tickets_next_principal: Some(vec![0; 32]),
+        finalization_progress: None,
     };
 
     assert!(swap.try_commit_or_abort(/* now_seconds: */ END_TIMESTAMP_SECONDS + 1));
@@ -2759,6 +2767,188 @@ async fn test_finalization_halts_when_sweep_sns_fails() {
     assert!(result.claim_neuron_result.is_none());
 }
 
+/// Tests that a finalization that halted midway resumes at the failed step when
+/// finalize is called again, without repeating the steps that already completed.
+#[tokio::test]
+async fn test_finalization_resumes_after_failed_step() {
+    // Step 1: Prepare the world
+
+    let buyer_principal = i2principal_id_string(1001);
+    let mut swap = Swap {
+        lifecycle: Committed as i32,
+        init: Some(init()),
+        params: Some(params()),
+        buyers: btreemap! {
+            buyer_principal.clone() => BuyerState::new(50 * E8),
+        },
+        neuron_recipes: vec![create_single_neuron_recipe(10 * E8, buyer_principal)],
+        ..Default::default()
+    };
+
+    // Step 2: Call finalize, and have the transfer of SNS tokens fail
+
+    let icp_ledger = SpyLedger::new(vec![LedgerReply::TransferFunds(Ok(1000))]);
+    let sns_ledger = SpyLedger::new(vec![LedgerReply::TransferFunds(Err(
+        NervousSystemError::new_with_message("Error when transferring funds"),
+    ))]);
+
+    let result = swap
+        .finalize(
+            now_fn,
+            &mut SpySnsRootClient::default(),
+            &mut SpySnsGovernanceClient::default(),
+            &icp_ledger,
+            &sns_ledger,
+            &mut SpyNnsGovernanceClient::with_successful_replies(),
+        )
+        .await;
+
+    // Step 3: Inspect results
+
+    assert!(result.has_error_message());
+    assert_eq!(icp_ledger.get_calls_snapshot().len(), 1);
+    assert!(!swap.is_finalized());
+
+    let finalization_progress = swap.finalization_progress.clone().unwrap();
+    assert_eq!(
+        finalization_progress,
+        FinalizationProgress {
+            completed_steps: vec![
+                FinalizationStep::SweepIcp as i32,
+                FinalizationStep::SettleCommunityFundParticipation as i32,
+            ],
+            attempt_count: 1,
+            latest_attempt_timestamp_seconds: Some(now_fn(false)),
+            latest_attempt_response: Some(result),
+        }
+    );
+
+    // Step 4: Call finalize again. The ICP Ledger and NNS Governance have no
+    // replies queued, so the completed steps must not be performed again.
+
+    let icp_ledger = SpyLedger::default();
+    let sns_ledger = SpyLedger::new(vec![LedgerReply::TransferFunds(Ok(1000))]);
+    let mut sns_governance_client = SpySnsGovernanceClient::new(vec![
+        SnsGovernanceClientReply::ClaimSwapNeurons(
+            compute_single_successful_claim_swap_neurons_response(&swap.neuron_recipes),
+        ),
+        SnsGovernanceClientReply::SetMode(SetModeResponse {}),
+    ]);
+
+    let result = swap
+        .finalize(
+            now_fn,
+            &mut SpySnsRootClient::default(),
+            &mut sns_governance_client,
+            &icp_ledger,
+            &sns_ledger,
+            &mut SpyNnsGovernanceClient::default(),
+        )
+        .await;
+
+    // Step 5: Inspect results
+
+    assert!(!result.has_error_message(), "{:?}", result.error_message);
+    assert!(result.sweep_icp_result.is_none());
+    assert!(result.settle_community_fund_participation_result.is_none());
+    assert_eq!(
+        result.sweep_sns_result,
+        Some(SweepResult {
+            success: 1,
+            ..Default::default()
+        })
+    );
+    assert!(result.claim_neuron_result.is_some());
+    assert_eq!(
+        result.set_mode_call_result,
+        Some(successful_set_mode_call_result())
+    );
+    assert!(icp_ledger.get_calls_snapshot().is_empty());
+
+    assert!(swap.is_finalized());
+    let finalization_progress = swap.finalization_progress.clone().unwrap();
+    assert_eq!(finalization_progress.attempt_count, 2);
+    assert_eq!(
+        finalization_progress.completed_steps,
+        Committed
+            .finalization_steps()
+            .iter()
+            .map(|step| *step as i32)
+            .collect::<Vec<_>>()
+    );
+}
+
+/// Tests when the canister heartbeat should attempt to finalize the swap.
+#[test]
+fn test_should_auto_finalize() {
+    let now_seconds = END_TIMESTAMP_SECONDS;
+
+    // The swap is not finalized automatically before it is committed or aborted.
+    let mut swap = Swap {
+        lifecycle: Open as i32,
+        init: Some(init()),
+        params: Some(params()),
+        ..Default::default()
+    };
+    assert!(!swap.should_auto_finalize(now_seconds));
+    assert_eq!(
+        swap.get_auto_finalization_status(&GetAutoFinalizationStatusRequest {}, now_seconds),
+        GetAutoFinalizationStatusResponse {
+            is_finalized: Some(false),
+            finalization_progress: None,
+            next_automatic_attempt_timestamp_seconds: None,
+        }
+    );
+
+    // Once aborted, the first attempt is due immediately.
+    swap.lifecycle = Aborted as i32;
+    assert!(swap.should_auto_finalize(now_seconds));
+    assert_eq!(
+        swap.get_auto_finalization_status(&GetAutoFinalizationStatusRequest {}, now_seconds)
+            .next_automatic_attempt_timestamp_seconds,
+        Some(now_seconds)
+    );
+
+    // No attempt is made while a call to finalize is in progress.
+    swap.lock_finalize_swap().unwrap();
+    assert!(!swap.should_auto_finalize(now_seconds));
+    swap.finalize_swap_in_progress = Some(false);
+
+    // After a failed attempt, the next one is due after the retry interval.
+    swap.finalization_progress = Some(FinalizationProgress {
+        completed_steps: vec![FinalizationStep::SweepIcp as i32],
+        attempt_count: 1,
+        latest_attempt_timestamp_seconds: Some(now_seconds),
+        latest_attempt_response: None,
+    });
+    let next_attempt_timestamp_seconds = now_seconds + AUTO_FINALIZATION_RETRY_INTERVAL_SECONDS;
+    assert!(!swap.should_auto_finalize(next_attempt_timestamp_seconds - 1));
+    assert!(swap.should_auto_finalize(next_attempt_timestamp_seconds));
+    assert_eq!(
+        swap.get_auto_finalization_status(&GetAutoFinalizationStatusRequest {}, now_seconds)
+            .next_automatic_attempt_timestamp_seconds,
+        Some(next_attempt_timestamp_seconds)
+    );
+
+    // Once all the steps of the aborted path completed, no more attempts are made.
+    swap.finalization_progress = Some(FinalizationProgress {
+        completed_steps: Aborted
+            .finalization_steps()
+            .iter()
+            .map(|step| *step as i32)
+            .collect(),
+        attempt_count: 2,
+        latest_attempt_timestamp_seconds: Some(now_seconds),
+        latest_attempt_response: None,
+    });
+    assert!(swap.is_finalized());
+    assert!(!swap.should_auto_finalize(next_attempt_timestamp_seconds));
+    let status =
+        swap.get_auto_finalization_status(&GetAutoFinalizationStatusRequest {}, now_seconds);
+    assert_eq!(status.is_finalized, Some(true));
+    assert_eq!(status.next_automatic_attempt_timestamp_seconds, None);
+}
+
 /// Test that settle_community_fund_participation will handle missing required state
 /// gracefully with an error.
 #[tokio::test]
@@ -3539,6 +3729,7 @@ async fn test_claim_swap_neuron_correctly_creates_neuron_parameters() {
                     memo: 10,
                     dissolve_delay_seconds: ONE_MONTH_SECONDS,
                     followees: vec![NeuronId::new_test_neuron_id(10).try_into().unwrap()],
+                    vesting_period_seconds: Some(ONE_MONTH_SECONDS),
                 }),
                 investor: Some(Investor::Direct(DirectInvestment {
                     buyer_principal: (*TEST_USER1_PRINCIPAL).to_string(),
@@ -3554,6 +3745,7 @@ async fn test_claim_swap_neuron_correctly_creates_neuron_parameters() {
                     memo: 0,
                     dissolve_delay_seconds: 0,
                     followees: vec![NeuronId::new_test_neuron_id(20).try_into().unwrap()],
+                    vesting_period_seconds: None,
                 }),
                 investor: Some(Investor::CommunityFund(CfInvestment {
                     hotkey_principal: (*TEST_USER2_PRINCIPAL).to_string(),
@@ -3604,6 +3796,7 @@ async fn test_claim_swap_neuron_correctly_creates_neuron_parameters() {
                             10
                         ))),
                         followees: vec![NeuronId::new_test_neuron_id(10)],
+                        vesting_period_seconds: Some(ONE_MONTH_SECONDS),
                     },
                     NeuronParameters {
                         controller: Some(NNS_GOVERNANCE_CANISTER_ID.get()),
@@ -3616,6 +3809,7 @@ async fn test_claim_swap_neuron_correctly_creates_neuron_parameters() {
                             0
                         ))),
                         followees: vec![NeuronId::new_test_neuron_id(20)],
+                        vesting_period_seconds: None,
                     }
                 ],
             }
@@ -752,6 +752,7 @@ impl SnsCanisters<'_> {
                 command: Some(Command::Follow(Follow {
                     function_id,
                     followees,
+                    topic: None,
                 })),
             },
         )
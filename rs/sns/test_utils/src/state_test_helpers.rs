@@ -636,6 +636,7 @@ pub fn open_sale(env: &StateMachine, swap_id: &CanisterId, params: Option<Params
                 neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
                     count: 1,
                     dissolve_delay_interval_seconds: 1,
+                    vesting_period_seconds: None,
                 }),
                 sale_delay_seconds: None,
             }),
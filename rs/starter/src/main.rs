@@ -304,6 +304,7 @@ struct CliArgs {
             "canister_sandboxing",
             "http_requests",
             "onchain_observability",
+            "quic_transport",
             "bitcoin_testnet",
             "bitcoin_testnet_syncing",
             "bitcoin_testnet_paused",
@@ -572,11 +573,16 @@ fn to_subnet_features(features: &[String]) -> SubnetFeatures {
         .iter()
         .any(|s| s.as_str() == "onchain_observability")
         .then_some(true);
+    let quic_transport = features
+        .iter()
+        .any(|s| s.as_str() == "quic_transport")
+        .then_some(true);
     SubnetFeatures {
         canister_sandboxing,
         http_requests,
         sev_status,
         onchain_observability,
+        quic_transport,
     }
 }
 
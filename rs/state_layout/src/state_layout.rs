@@ -2,6 +2,7 @@ use crate::error::LayoutError;
 use crate::utils::do_copy;
 
 use ic_base_types::{NumBytes, NumSeconds};
+use ic_ic00_types::LogVisibility;
 use ic_logger::{error, info, ReplicaLogger};
 use ic_metrics::{buckets::decimal_buckets, MetricsRegistry};
 use ic_protobuf::{
@@ -27,6 +28,7 @@ use ic_sys::mmap::ScopedMmap;
 use ic_types::{
     nominal_cycles::NominalCycles, AccumulatedPriority, CanisterId, ComputeAllocation, Cycles,
     ExecutionRound, Height, MemoryAllocation, NumInstructions, PrincipalId,
+    MAX_WASM64_MEMORY_ALLOCATION,
 };
 use ic_utils::fs::sync_path;
 use ic_utils::thread::parallel_map;
@@ -146,6 +148,10 @@ pub struct CanisterStateBits {
     pub canister_version: u64,
     pub consumed_cycles_since_replica_started_by_use_cases: BTreeMap<CyclesUseCase, NominalCycles>,
     pub canister_history: CanisterHistory,
+    pub reserved_balance: Cycles,
+    pub reserved_balance_limit: Option<Cycles>,
+    pub wasm_memory_limit: Option<NumBytes>,
+    pub log_visibility: LogVisibility,
 }
 
 #[derive(Clone)]
@@ -1560,6 +1566,10 @@ impl From<CanisterStateBits> for pb_canister_state_bits::CanisterStateBits {
                 })
                 .collect(),
             canister_history: Some((&item.canister_history).into()),
+            reserved_balance: Some(item.reserved_balance.into()),
+            reserved_balance_limit: item.reserved_balance_limit.map(|v| v.into()),
+            wasm_memory_limit: item.wasm_memory_limit.map(|v| v.get()),
+            log_visibility: pb_canister_state_bits::LogVisibility::from(item.log_visibility).into(),
         }
     }
 }
@@ -1599,6 +1609,32 @@ impl TryFrom<pb_canister_state_bits::CanisterStateBits> for CanisterStateBits {
             .transpose()?
             .unwrap_or_else(Cycles::zero);
 
+        let reserved_balance = value
+            .reserved_balance
+            .map(|c| c.try_into())
+            .transpose()?
+            .unwrap_or_else(Cycles::zero);
+
+        let reserved_balance_limit = value
+            .reserved_balance_limit
+            .map(|c| c.try_into())
+            .transpose()?;
+
+        let log_visibility =
+            match pb_canister_state_bits::LogVisibility::from_i32(value.log_visibility) {
+                // Checkpoints written before log visibility was introduced.
+                Some(pb_canister_state_bits::LogVisibility::Unspecified) => {
+                    LogVisibility::default()
+                }
+                Some(log_visibility) => LogVisibility::try_from(log_visibility)?,
+                None => {
+                    return Err(ProxyDecodeError::ValueOutOfRange {
+                        typ: "LogVisibility",
+                        err: format!("Unknown log visibility: {}", value.log_visibility),
+                    })
+                }
+            };
+
         let task_queue = value
             .task_queue
             .into_iter()
@@ -1617,11 +1653,16 @@ impl TryFrom<pb_canister_state_bits::CanisterStateBits> for CanisterStateBits {
             )?,
             accumulated_priority: value.accumulated_priority.into(),
             execution_state_bits,
-            memory_allocation: MemoryAllocation::try_from(NumBytes::from(value.memory_allocation))
-                .map_err(|e| ProxyDecodeError::ValueOutOfRange {
-                    typ: "MemoryAllocation",
-                    err: format!("{:?}", e),
-                })?,
+            // Canisters on subnets with 64-bit Wasm memories may have larger
+            // allocations, so the checkpoint accepts the largest possible one.
+            memory_allocation: MemoryAllocation::try_from_with_max(
+                NumBytes::from(value.memory_allocation),
+                MAX_WASM64_MEMORY_ALLOCATION,
+            )
+            .map_err(|e| ProxyDecodeError::ValueOutOfRange {
+                typ: "MemoryAllocation",
+                err: format!("{:?}", e),
+            })?,
             freeze_threshold: NumSeconds::from(value.freeze_threshold),
             cycles_balance,
             cycles_debit,
@@ -1661,6 +1702,10 @@ impl TryFrom<pb_canister_state_bits::CanisterStateBits> for CanisterStateBits {
                 "CanisterStateBits::canister_history",
             )
             .unwrap_or_default(),
+            reserved_balance,
+            reserved_balance_limit,
+            wasm_memory_limit: value.wasm_memory_limit.map(NumBytes::from),
+            log_visibility,
         })
     }
 }
@@ -2021,6 +2066,10 @@ mod test {
             canister_version: 0,
             consumed_cycles_since_replica_started_by_use_cases: BTreeMap::new(),
             canister_history: CanisterHistory::default(),
+            reserved_balance: Cycles::zero(),
+            reserved_balance_limit: None,
+            wasm_memory_limit: None,
+            log_visibility: LogVisibility::default(),
         }
     }
 
@@ -2098,6 +2147,35 @@ mod test {
         assert_eq!(canister_state_bits.canister_history, canister_history);
     }
 
+    #[test]
+    fn test_encode_decode_log_visibility() {
+        for log_visibility in [LogVisibility::Controllers, LogVisibility::Public] {
+            let canister_state_bits = CanisterStateBits {
+                log_visibility,
+                ..default_canister_state_bits()
+            };
+
+            let pb_bits = pb_canister_state_bits::CanisterStateBits::from(canister_state_bits);
+            let canister_state_bits = CanisterStateBits::try_from(pb_bits).unwrap();
+
+            assert_eq!(canister_state_bits.log_visibility, log_visibility);
+        }
+    }
+
+    #[test]
+    fn test_decode_unspecified_log_visibility_as_default() {
+        let mut pb_bits =
+            pb_canister_state_bits::CanisterStateBits::from(default_canister_state_bits());
+        pb_bits.log_visibility = pb_canister_state_bits::LogVisibility::Unspecified as i32;
+
+        let canister_state_bits = CanisterStateBits::try_from(pb_bits).unwrap();
+
+        assert_eq!(
+            canister_state_bits.log_visibility,
+            LogVisibility::Controllers
+        );
+    }
+
     #[test]
     fn test_encode_decode_non_empty_history() {
         let mut canister_history = CanisterHistory::default();
@@ -339,6 +339,10 @@ pub fn load_canister_state<P: ReadPolicy>(
         CanisterTimer::from_nanos_since_unix_epoch(canister_state_bits.global_timer_nanos),
         canister_state_bits.canister_version,
         canister_state_bits.canister_history,
+        canister_state_bits.reserved_balance,
+        canister_state_bits.reserved_balance_limit,
+        canister_state_bits.wasm_memory_limit,
+        canister_state_bits.log_visibility,
     );
 
     let canister_state = CanisterState {
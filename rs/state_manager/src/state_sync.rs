@@ -89,6 +89,7 @@ impl ArtifactKind for StateSyncArtifact {
             attribute: (),
             size: size as usize,
             integrity_hash: crypto_hash(msg).get(),
+            chunk_tree_root: None,
         }
     }
 }
@@ -500,6 +500,10 @@ fn serialize_canister_to_tip(
                 .get_consumed_cycles_since_replica_started_by_use_cases()
                 .clone(),
             canister_history: canister_state.system_state.get_canister_history().clone(),
+            reserved_balance: canister_state.system_state.reserved_balance(),
+            reserved_balance_limit: canister_state.system_state.reserved_balance_limit(),
+            wasm_memory_limit: canister_state.system_state.wasm_memory_limit,
+            log_visibility: canister_state.system_state.log_visibility,
         }
         .into(),
     )?;
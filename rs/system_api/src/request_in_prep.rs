@@ -4,10 +4,14 @@ use ic_logger::ReplicaLogger;
 use ic_types::{
     messages::{CallContextId, Request},
     methods::{Callback, WasmClosure},
-    CanisterId, Cycles, NumBytes, PrincipalId,
+    CanisterId, Cycles, NumBytes, PrincipalId, Time,
 };
 use serde::{Deserialize, Serialize};
-use std::convert::TryFrom;
+use std::{convert::TryFrom, time::Duration};
+
+/// The maximum timeout (in seconds) that a canister may specify for a
+/// best-effort response call. Larger values are silently clamped.
+pub const MAX_CALL_TIMEOUT_SECONDS: u32 = 300;
 
 /// Represents an under construction `Request`.
 ///
@@ -37,6 +41,8 @@ pub struct RequestInPrep {
     cycles: Cycles,
     method_name: String,
     method_payload: Vec<u8>,
+    /// The timeout set via `ic0.call_with_best_effort_response`, if any.
+    timeout_seconds: Option<u32>,
     /// The maximum size of a message that will go to a canister on another
     /// subnet.
     max_size_remote_subnet: NumBytes,
@@ -94,6 +100,7 @@ impl RequestInPrep {
             cycles: Cycles::zero(),
             method_name,
             method_payload: Vec::new(),
+            timeout_seconds: None,
             max_size_remote_subnet,
             multiplier_max_size_local_subnet,
         })
@@ -111,6 +118,18 @@ impl RequestInPrep {
         }
     }
 
+    pub(crate) fn set_timeout(&mut self, timeout_seconds: u32) -> HypervisorResult<()> {
+        if self.timeout_seconds.is_some() {
+            Err(HypervisorError::ContractViolation(
+                "ic0.call_with_best_effort_response can be called at most once between `ic0.call_new` and `ic0.call_perform`"
+                    .to_string(),
+            ))
+        } else {
+            self.timeout_seconds = Some(timeout_seconds.min(MAX_CALL_TIMEOUT_SECONDS));
+            Ok(())
+        }
+    }
+
     pub(crate) fn take_cycles(self) -> Cycles {
         self.cycles
     }
@@ -159,10 +178,12 @@ pub(crate) fn into_request(
         cycles,
         method_name,
         method_payload,
+        timeout_seconds,
         max_size_remote_subnet,
         multiplier_max_size_local_subnet,
     }: RequestInPrep,
     call_context_id: CallContextId,
+    time: Time,
     sandbox_safe_system_state: &mut SandboxSafeSystemState,
     _logger: &ReplicaLogger,
 ) -> HypervisorResult<RequestWithPrepayment> {
@@ -204,6 +225,8 @@ pub(crate) fn into_request(
         method_payload,
         sender_reply_callback: callback_id,
         payment: cycles,
+        deadline: timeout_seconds
+            .map(|timeout_seconds| time + Duration::from_secs(timeout_seconds as u64)),
     };
     // We cannot call `Request::payload_size_bytes()` before constructing the
     // request, so ensure our separate calculation matches the actual size.
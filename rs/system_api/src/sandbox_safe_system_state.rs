@@ -513,6 +513,7 @@ pub struct SandboxSafeSystemState {
     global_timer: CanisterTimer,
     canister_version: u64,
     controllers: BTreeSet<PrincipalId>,
+    wasm_memory_limit: Option<NumBytes>,
 }
 
 impl SandboxSafeSystemState {
@@ -536,6 +537,7 @@ impl SandboxSafeSystemState {
         global_timer: CanisterTimer,
         canister_version: u64,
         controllers: BTreeSet<PrincipalId>,
+        wasm_memory_limit: Option<NumBytes>,
     ) -> Self {
         Self {
             canister_id,
@@ -556,6 +558,7 @@ impl SandboxSafeSystemState {
             global_timer,
             canister_version,
             controllers,
+            wasm_memory_limit,
         }
     }
 
@@ -616,6 +619,7 @@ impl SandboxSafeSystemState {
             system_state.global_timer,
             system_state.canister_version,
             system_state.controllers.clone(),
+            system_state.wasm_memory_limit,
         )
     }
 
@@ -631,6 +635,11 @@ impl SandboxSafeSystemState {
         self.canister_version
     }
 
+    /// Returns the upper limit on the Wasm memory of the canister, if any.
+    pub fn wasm_memory_limit(&self) -> Option<NumBytes> {
+        self.wasm_memory_limit
+    }
+
     pub fn set_global_timer(&mut self, timer: CanisterTimer) {
         // Update both sandbox global timer and the changes.
         self.system_state_changes.new_global_timer = Some(timer);
@@ -1416,6 +1416,7 @@ pub struct ExecutionTestBuilder {
     instruction_limit_without_dts: NumInstructions,
     initial_canister_cycles: Cycles,
     subnet_total_memory: i64,
+    subnet_memory_threshold: i64,
     subnet_message_memory: i64,
     subnet_wasm_custom_sections_memory: i64,
     registry_settings: RegistryExecutionSettings,
@@ -1426,6 +1427,7 @@ pub struct ExecutionTestBuilder {
     composite_queries: bool,
     query_caching: bool,
     query_cache_capacity: u64,
+    wasm64: bool,
     allocatable_compute_capacity_in_percent: usize,
     subnet_features: String,
     bitcoin_privileged_access: Vec<CanisterId>,
@@ -1444,6 +1446,9 @@ impl Default for ExecutionTestBuilder {
         let subnet_total_memory = ic_config::execution_environment::Config::default()
             .subnet_memory_capacity
             .get() as i64;
+        let subnet_memory_threshold = ic_config::execution_environment::Config::default()
+            .subnet_memory_threshold
+            .get() as i64;
         let subnet_message_memory = ic_config::execution_environment::Config::default()
             .subnet_message_memory_capacity
             .get() as i64;
@@ -1470,6 +1475,7 @@ impl Default for ExecutionTestBuilder {
                 .max_instructions_per_message_without_dts,
             initial_canister_cycles: INITIAL_CANISTER_CYCLES,
             subnet_total_memory,
+            subnet_memory_threshold,
             subnet_message_memory,
             subnet_wasm_custom_sections_memory,
             registry_settings: test_registry_settings(),
@@ -1480,6 +1486,7 @@ impl Default for ExecutionTestBuilder {
             composite_queries: false,
             query_caching: false,
             query_cache_capacity: 100_000_000, // 100MB
+            wasm64: false,
             allocatable_compute_capacity_in_percent: 100,
             subnet_features: String::default(),
             bitcoin_privileged_access: Vec::default(),
@@ -1607,6 +1614,13 @@ impl ExecutionTestBuilder {
         }
     }
 
+    pub fn with_subnet_memory_threshold(self, subnet_memory_threshold: i64) -> Self {
+        Self {
+            subnet_memory_threshold,
+            ..self
+        }
+    }
+
     pub fn with_subnet_message_memory(self, subnet_message_memory: i64) -> Self {
         Self {
             subnet_message_memory,
@@ -1683,6 +1697,13 @@ impl ExecutionTestBuilder {
         }
     }
 
+    pub fn with_wasm64(self) -> Self {
+        Self {
+            wasm64: true,
+            ..self
+        }
+    }
+
     pub fn with_query_cache_capacity(self, capacity_bytes: u64) -> Self {
         Self {
             query_cache_capacity: capacity_bytes,
@@ -1849,15 +1870,22 @@ impl ExecutionTestBuilder {
         } else {
             FlagStatus::Disabled
         };
+        let wasm64 = if self.wasm64 {
+            FlagStatus::Enabled
+        } else {
+            FlagStatus::Disabled
+        };
         let config = Config {
             rate_limiting_of_instructions,
             deterministic_time_slicing,
             canister_sandboxing_flag,
             composite_queries,
             query_caching,
+            wasm64,
             query_cache_capacity: self.query_cache_capacity.into(),
             allocatable_compute_capacity_in_percent: self.allocatable_compute_capacity_in_percent,
             subnet_memory_capacity: NumBytes::from(self.subnet_total_memory as u64),
+            subnet_memory_threshold: NumBytes::from(self.subnet_memory_threshold as u64),
             subnet_message_memory_capacity: NumBytes::from(self.subnet_message_memory as u64),
             bitcoin: BitcoinConfig {
                 privileged_access: self.bitcoin_privileged_access,
@@ -10,8 +10,8 @@ use ic_interfaces::crypto::{
     BasicSigVerifier, BasicSigVerifierByPublicKey, BasicSigner, CanisterSigVerifier,
     CheckKeysWithRegistryError, CurrentNodePublicKeysError, IDkgDealingEncryptionKeyRotationError,
     IDkgKeyRotationResult, IDkgProtocol, KeyManager, LoadTranscriptResult, NiDkgAlgorithm,
-    ThresholdEcdsaSigVerifier, ThresholdEcdsaSigner, ThresholdSigVerifier,
-    ThresholdSigVerifierByPublicKey, ThresholdSigner,
+    NodeSigningKeysRotationError, NodeSigningKeysRotationResult, ThresholdEcdsaSigVerifier,
+    ThresholdEcdsaSigner, ThresholdSigVerifier, ThresholdSigVerifierByPublicKey, ThresholdSigner,
 };
 use ic_interfaces::crypto::{MultiSigVerifier, MultiSigner};
 use ic_interfaces_registry::RegistryClient;
@@ -40,6 +40,7 @@ use ic_types::{NodeId, RegistryVersion};
 use rand::{rngs::StdRng, RngCore, SeedableRng};
 use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
 pub fn empty_fake_registry() -> Arc<dyn RegistryClient> {
     Arc::new(FakeRegistryClient::new(Arc::new(
@@ -303,6 +304,14 @@ impl KeyManager for CryptoReturningOk {
     ) -> Result<IDkgKeyRotationResult, IDkgDealingEncryptionKeyRotationError> {
         unimplemented!()
     }
+
+    fn rotate_node_signing_keys(
+        &self,
+        _registry_version: RegistryVersion,
+        _key_rotation_period: Duration,
+    ) -> Result<NodeSigningKeysRotationResult, NodeSigningKeysRotationError> {
+        unimplemented!()
+    }
 }
 
 impl IDkgProtocol for CryptoReturningOk {
@@ -1,12 +1,13 @@
 use async_trait::async_trait;
+use ic_crypto_tls_interfaces::rustls::{ClientConfig, ServerConfig};
 use ic_crypto_tls_interfaces::{
-    AllowedClients, AuthenticatedPeer, TlsClientHandshakeError, TlsHandshake,
-    TlsServerHandshakeError, TlsStream,
+    AllowedClients, AuthenticatedPeer, SomeOrAllNodes, TlsClientHandshakeError, TlsConfig,
+    TlsConfigError, TlsHandshake, TlsServerHandshakeError, TlsStream,
 };
 use ic_types::{NodeId, RegistryVersion};
 use tokio::net::TcpStream;
 
-/// This implementation of TlsHandshake is so fake that it panics if
+/// This implementation of TlsHandshake and TlsConfig is so fake that it panics if
 /// you try to call any of the methods.
 pub struct FakeTlsHandshake;
 
@@ -50,3 +51,21 @@ impl TlsHandshake for FakeTlsHandshake {
         unimplemented!()
     }
 }
+
+impl TlsConfig for FakeTlsHandshake {
+    fn server_config(
+        &self,
+        _allowed_clients: SomeOrAllNodes,
+        _registry_version: RegistryVersion,
+    ) -> Result<ServerConfig, TlsConfigError> {
+        unimplemented!()
+    }
+
+    fn client_config(
+        &self,
+        _server: NodeId,
+        _registry_version: RegistryVersion,
+    ) -> Result<ClientConfig, TlsConfigError> {
+        unimplemented!()
+    }
+}
@@ -34,6 +34,7 @@ fn sale_params() -> Params {
         neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
             count: 5,
             dissolve_delay_interval_seconds: 7_889_400,
+            vesting_period_seconds: None,
         }),
         sale_delay_seconds: None,
     }
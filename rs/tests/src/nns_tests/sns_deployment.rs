@@ -600,6 +600,7 @@ pub fn initiate_token_swap_with_oc_parameters(env: TestEnv) {
         neuron_basket_construction_parameters: Some(NeuronBasketConstructionParameters {
             count: 5,
             dissolve_delay_interval_seconds: 7_889_400,
+            vesting_period_seconds: None,
         }),
         sale_delay_seconds: None,
     };
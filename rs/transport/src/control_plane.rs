@@ -535,7 +535,7 @@ async fn connect_to_server(
 }
 
 /// Returns our role wrt the peer connection
-fn connection_role(my_id: &NodeId, peer: &NodeId) -> ConnectionRole {
+pub(crate) fn connection_role(my_id: &NodeId, peer: &NodeId) -> ConnectionRole {
     assert!(*my_id != *peer);
     if *my_id > *peer {
         ConnectionRole::Server
@@ -31,10 +31,15 @@
 //! messages (artifact chunks), for ingress manager, consensus (incl DKG and
 //! certification) and state sync. Thus, Transport has to handle 3 x 3 flows per
 //! peer for Gossip.
+//!
+//! Depending on the `quic_transport` feature of the subnet record, peers are
+//! connected over TLS-on-TCP (the default) or over QUIC, where every channel is
+//! sent on an independent stream, see `quic.rs`.
 
 mod control_plane;
 mod data_plane;
 mod metrics;
+mod quic;
 pub mod transport;
 mod types;
 mod utils;
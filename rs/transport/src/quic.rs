@@ -0,0 +1,850 @@
+//! QUIC implementation of the [`Transport`] interface.
+//!
+//! The TCP transport multiplexes all channels of a peer over a single TLS
+//! stream, so one large message delays every message queued behind it. This
+//! implementation instead keeps one QUIC connection per peer and sends every
+//! channel on its own unidirectional stream, which QUIC flow-controls and
+//! retransmits independently of the others.
+//!
+//! Connections are authenticated with the node TLS certificates from the
+//! registry, using the rustls configurations returned by [`TlsConfig`]. As for
+//! TCP, the node with the larger node ID is the server of a connection. After
+//! the handshake, the client opens a bidirectional stream that is only used for
+//! the SEV attestation; the peer is reported as up once the attestation
+//! succeeded.
+//!
+//! A channel stream starts with the channel ID (u32, little endian), followed
+//! by the messages, each prefixed with its length (u32, little endian). Dead
+//! connections are detected by QUIC keep-alives and the idle timeout, which
+//! replace the heartbeats of the TCP transport.
+
+use crate::{
+    control_plane::connection_role,
+    metrics::{
+        ControlPlaneMetrics, DataPlaneMetrics, IntGaugeResource, SendQueueMetrics, STATUS_SUCCESS,
+    },
+    types::{ConnectionRole, SendQueue, SendQueueReader},
+    utils::{get_peer_label, SendQueueImpl},
+};
+use ic_base_types::{NodeId, RegistryVersion};
+use ic_config::transport::TransportConfig;
+use ic_crypto_tls_interfaces::{
+    rustls::Certificate, SomeOrAllNodes, TlsConfig, TlsPublicKeyCert, TlsStream,
+};
+use ic_icos_sev_interfaces::ValidateAttestedStream;
+use ic_interfaces_transport::{
+    Transport, TransportChannelId, TransportError, TransportEvent, TransportEventHandler,
+    TransportMessage, TransportPayload,
+};
+use ic_logger::{error, info, warn, ReplicaLogger};
+use ic_metrics::MetricsRegistry;
+use quinn::{
+    ClientConfig, Connecting, Connection, Endpoint, EndpointConfig, ReadExactError, RecvStream,
+    SendStream, ServerConfig, TokioRuntime, VarInt,
+};
+use std::{
+    collections::{BTreeSet, HashMap},
+    net::{IpAddr, SocketAddr, UdpSocket},
+    pin::Pin,
+    str::FromStr,
+    sync::{Arc, Weak},
+    task::{Context, Poll},
+};
+use strum::AsRefStr;
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    runtime::Handle,
+    sync::{Mutex, RwLock},
+    task::JoinHandle,
+    time::{sleep, timeout, Duration, Instant},
+};
+use tower::Service;
+
+/// Time to wait before retrying an unsuccessful connection attempt
+const CONNECT_RETRY_SECONDS: u64 = 3;
+/// Time to wait for the QUIC/TLS handshake (for both client/server sides)
+const HANDSHAKE_TIMEOUT_SECONDS: u64 = 30;
+/// Time to wait for the SEV handshake (for both client/server sides)
+const SEV_HANDSHAKE_TIMEOUT_SECONDS: u64 = 10;
+/// Interval at which keep-alives are sent on an otherwise idle connection
+const KEEP_ALIVE_INTERVAL_MS: u64 = 200;
+/// Time without any traffic after which a connection is considered dead
+const IDLE_TIMEOUT_MS: u32 = 5000;
+/// The number of bytes to dequeue and write to a stream at once
+const DEQUEUE_BYTES: usize = 100 * 4 * 1490;
+/// Time to wait for messages in an empty send queue
+const DEQUEUE_TIMEOUT_MS: u64 = 200;
+/// The server name is not checked by the node certificate verifiers, but
+/// rustls requires one.
+const SERVER_NAME: &str = "domain.is-irrelevant-as-hostname-verification-is.disabled";
+/// Written by the client to the attestation stream: QUIC only announces a
+/// stream to the peer once data was sent on it, and the attestation itself
+/// does not exchange any data on subnets without SEV.
+const ATTESTATION_STREAM_PREAMBLE: [u8; 1] = [0];
+
+const CONNECT_TASK_NAME: &str = "quic_connect";
+const ACCEPT_TASK_NAME: &str = "quic_accept";
+
+/// Application error code used when closing connections
+const CLOSE_CODE: u32 = 0;
+
+#[derive(Debug, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+enum QuicHandshakeError {
+    DeadlineExceeded,
+    TlsConfig(String),
+    Connection(String),
+    UnauthenticatedPeer(String),
+    Attestation(String),
+}
+
+/// The connection to a peer.
+enum QuicConnectionState {
+    /// We are the server and wait for the peer to connect.
+    Listening,
+    /// We are the client and the task tries to connect to the peer.
+    Connecting(JoinHandle<()>),
+    /// The connection is established. The tasks write the channel streams,
+    /// read the streams of the peer and wait for the connection to close.
+    Connected {
+        connection: Connection,
+        tasks: Vec<JoinHandle<()>>,
+    },
+}
+
+impl Drop for QuicConnectionState {
+    fn drop(&mut self) {
+        match self {
+            QuicConnectionState::Listening => (),
+            QuicConnectionState::Connecting(connecting_task) => connecting_task.abort(),
+            QuicConnectionState::Connected { connection, tasks } => {
+                for task in tasks.iter() {
+                    task.abort();
+                }
+                connection.close(VarInt::from_u32(CLOSE_CODE), b"disconnected");
+            }
+        }
+    }
+}
+
+struct QuicPeerState {
+    peer_addr: SocketAddr,
+    peer_label: String,
+    /// One send queue per channel, indexed by channel ID
+    send_queues: Vec<SendQueueImpl>,
+    connection: QuicConnectionState,
+}
+
+pub(crate) struct QuicTransportImpl {
+    node_id: NodeId,
+    node_ip: IpAddr,
+    config: TransportConfig,
+
+    /// The UDP endpoint used for all connections, created with the event
+    /// handler
+    endpoint: std::sync::Mutex<Option<Endpoint>>,
+    accept_task: std::sync::Mutex<Option<JoinHandle<()>>>,
+    peer_map: RwLock<HashMap<NodeId, QuicPeerState>>,
+    event_handler: Mutex<Option<TransportEventHandler>>,
+
+    /// Clients that are allowed to connect to this node
+    allowed_clients: std::sync::RwLock<BTreeSet<NodeId>>,
+    latest_registry_version: std::sync::RwLock<RegistryVersion>,
+    earliest_registry_version: std::sync::RwLock<RegistryVersion>,
+    tls_config: Arc<dyn TlsConfig + Send + Sync>,
+    sev_handshake: Arc<dyn ValidateAttestedStream<Box<dyn TlsStream>> + Send + Sync>,
+
+    control_plane_metrics: ControlPlaneMetrics,
+    data_plane_metrics: DataPlaneMetrics,
+    send_queue_metrics: SendQueueMetrics,
+
+    rt_handle: Handle,
+    log: ReplicaLogger,
+    weak_self: Weak<QuicTransportImpl>,
+}
+
+impl QuicTransportImpl {
+    pub(crate) fn new(
+        node_id: NodeId,
+        config: TransportConfig,
+        latest_registry_version: RegistryVersion,
+        earliest_registry_version: RegistryVersion,
+        metrics_registry: MetricsRegistry,
+        tls_config: Arc<dyn TlsConfig + Send + Sync>,
+        sev_handshake: Arc<dyn ValidateAttestedStream<Box<dyn TlsStream>> + Send + Sync>,
+        rt_handle: Handle,
+        log: ReplicaLogger,
+    ) -> Arc<Self> {
+        let node_ip = IpAddr::from_str(&config.node_ip)
+            .unwrap_or_else(|_| panic!("Invalid node IP: {}", &config.node_ip));
+        Arc::new_cyclic(|weak_self| Self {
+            node_id,
+            node_ip,
+            config,
+            endpoint: std::sync::Mutex::new(None),
+            accept_task: std::sync::Mutex::new(None),
+            peer_map: RwLock::new(HashMap::new()),
+            event_handler: Mutex::new(None),
+            allowed_clients: std::sync::RwLock::new(BTreeSet::new()),
+            latest_registry_version: std::sync::RwLock::new(latest_registry_version),
+            earliest_registry_version: std::sync::RwLock::new(earliest_registry_version),
+            tls_config,
+            sev_handshake,
+            control_plane_metrics: ControlPlaneMetrics::new(metrics_registry.clone()),
+            data_plane_metrics: DataPlaneMetrics::new(metrics_registry.clone()),
+            send_queue_metrics: SendQueueMetrics::new(metrics_registry),
+            rt_handle,
+            log,
+            weak_self: weak_self.clone(),
+        })
+    }
+
+    /// QUIC parameters shared by the client and server configurations.
+    fn quic_transport_config(&self) -> Arc<quinn::TransportConfig> {
+        let mut transport_config = quinn::TransportConfig::default();
+        transport_config
+            .max_idle_timeout(Some(VarInt::from_u32(IDLE_TIMEOUT_MS).into()))
+            .keep_alive_interval(Some(Duration::from_millis(KEEP_ALIVE_INTERVAL_MS)))
+            .max_concurrent_uni_streams(VarInt::from_u32(self.config.max_streams as u32))
+            .max_concurrent_bidi_streams(VarInt::from_u32(1));
+        Arc::new(transport_config)
+    }
+
+    /// Returns a server configuration that accepts the current allowed
+    /// clients, or `None` if it cannot be created.
+    fn server_config(&self) -> Option<ServerConfig> {
+        let allowed_clients = self.allowed_clients.read().unwrap().clone();
+        let registry_version = *self.latest_registry_version.read().unwrap();
+        match self
+            .tls_config
+            .server_config(SomeOrAllNodes::Some(allowed_clients), registry_version)
+        {
+            Ok(tls_config) => {
+                let mut server_config = ServerConfig::with_crypto(Arc::new(tls_config));
+                server_config.transport_config(self.quic_transport_config());
+                Some(server_config)
+            }
+            Err(err) => {
+                error!(
+                    self.log,
+                    "QuicTransport: failed to create the server config: error = {:?}", err
+                );
+                None
+            }
+        }
+    }
+
+    /// Applies changes of the allowed clients or the registry version to
+    /// connections accepted in the future.
+    fn refresh_server_config(&self) {
+        if let Some(endpoint) = self.endpoint.lock().unwrap().as_ref() {
+            if let Some(server_config) = self.server_config() {
+                endpoint.set_server_config(Some(server_config));
+            }
+        }
+    }
+
+    fn init_client(&self, event_handler: TransportEventHandler) {
+        // Creating the endpoint requires that we are within a tokio runtime context.
+        let _rt_enter_guard = self.rt_handle.enter();
+        let server_addr = SocketAddr::new(self.node_ip, self.config.listening_port);
+        let socket = UdpSocket::bind(server_addr)
+            .unwrap_or_else(|err| panic!("Failed to bind UDP socket {}: {:?}", server_addr, err));
+        let endpoint = Endpoint::new(
+            EndpointConfig::default(),
+            self.server_config(),
+            socket,
+            Arc::new(TokioRuntime),
+        )
+        .unwrap_or_else(|err| panic!("Failed to create QUIC endpoint: {:?}", err));
+        *self.accept_task.lock().unwrap() = Some(self.spawn_accept_task(endpoint.clone()));
+        *self.endpoint.lock().unwrap() = Some(endpoint);
+        *self.event_handler.blocking_lock() = Some(event_handler);
+    }
+
+    /// Starts the async task that accepts incoming connections.
+    fn spawn_accept_task(&self, endpoint: Endpoint) -> JoinHandle<()> {
+        let weak_self = self.weak_self.clone();
+        let rt_handle = self.rt_handle.clone();
+        let task_gauge = self
+            .control_plane_metrics
+            .async_tasks
+            .with_label_values(&[ACCEPT_TASK_NAME]);
+        self.rt_handle.spawn(async move {
+            let _gauge_guard = IntGaugeResource::new(task_gauge);
+            while let Some(connecting) = endpoint.accept().await {
+                let arc_self = match weak_self.upgrade() {
+                    Some(arc_self) => arc_self,
+                    None => return,
+                };
+                rt_handle.spawn(async move {
+                    let remote_addr = connecting.remote_address();
+                    match arc_self.server_handshake(connecting).await {
+                        Ok((peer_id, connection)) => {
+                            arc_self.observe_handshake(ConnectionRole::Server, STATUS_SUCCESS);
+                            arc_self.on_connect(peer_id, connection).await;
+                        }
+                        Err(err) => {
+                            arc_self.observe_handshake(ConnectionRole::Server, err.as_ref());
+                            warn!(
+                                arc_self.log,
+                                "QuicTransport::spawn_accept_task(): server handshake failed: \
+                                error = {:?}, peer_addr = {:?}",
+                                err,
+                                remote_addr,
+                            );
+                        }
+                    }
+                });
+            }
+        })
+    }
+
+    /// Spawns a task that tries to connect to a peer (forever, or until the
+    /// connection is established or the peer is removed).
+    fn spawn_connect_task(&self, peer_id: NodeId, peer_addr: SocketAddr) -> JoinHandle<()> {
+        let weak_self = self.weak_self.clone();
+        let task_gauge = self
+            .control_plane_metrics
+            .async_tasks
+            .with_label_values(&[CONNECT_TASK_NAME]);
+        self.rt_handle.spawn(async move {
+            let _gauge_guard = IntGaugeResource::new(task_gauge);
+            let mut retries: u32 = 0;
+            while let Some(arc_self) = weak_self.upgrade() {
+                retries += 1;
+                let endpoint = arc_self.endpoint.lock().unwrap().clone();
+                if let Some(endpoint) = endpoint {
+                    match arc_self
+                        .client_handshake(&endpoint, peer_id, peer_addr)
+                        .await
+                    {
+                        Ok(connection) => {
+                            arc_self.observe_handshake(ConnectionRole::Client, STATUS_SUCCESS);
+                            arc_self.on_connect(peer_id, connection).await;
+                            return;
+                        }
+                        Err(err) => {
+                            arc_self.observe_handshake(ConnectionRole::Client, err.as_ref());
+                            warn!(
+                                arc_self.log,
+                                "QuicTransport::spawn_connect_task(): client handshake failed: \
+                                error = {:?}, peer = {:?}/{:?}, retries = {}",
+                                err,
+                                peer_id,
+                                peer_addr,
+                                retries,
+                            );
+                        }
+                    }
+                }
+                drop(arc_self);
+                sleep(Duration::from_secs(CONNECT_RETRY_SECONDS)).await;
+            }
+        })
+    }
+
+    fn observe_handshake(&self, role: ConnectionRole, status: &str) {
+        self.control_plane_metrics
+            .tls_handshakes
+            .with_label_values(&[role.as_ref(), status])
+            .inc();
+    }
+
+    /// Completes the handshake of an incoming connection and returns the
+    /// authenticated peer.
+    async fn server_handshake(
+        &self,
+        connecting: Connecting,
+    ) -> Result<(NodeId, Connection), QuicHandshakeError> {
+        let connection = timeout(Duration::from_secs(HANDSHAKE_TIMEOUT_SECONDS), connecting)
+            .await
+            .map_err(|_| QuicHandshakeError::DeadlineExceeded)?
+            .map_err(|err| QuicHandshakeError::Connection(err.to_string()))?;
+        let peer_id =
+            authenticated_peer_id(&connection).map_err(QuicHandshakeError::UnauthenticatedPeer)?;
+        let (send, recv) = timeout(Duration::from_secs(HANDSHAKE_TIMEOUT_SECONDS), async {
+            let (send, mut recv) = connection.accept_bi().await.map_err(|e| e.to_string())?;
+            let mut preamble = [0; ATTESTATION_STREAM_PREAMBLE.len()];
+            recv.read_exact(&mut preamble)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok::<_, String>((send, recv))
+        })
+        .await
+        .map_err(|_| QuicHandshakeError::DeadlineExceeded)?
+        .map_err(QuicHandshakeError::Connection)?;
+        self.attest(peer_id, send, recv).await?;
+        Ok((peer_id, connection))
+    }
+
+    /// Connects to the given peer and performs the handshake.
+    async fn client_handshake(
+        &self,
+        endpoint: &Endpoint,
+        peer_id: NodeId,
+        peer_addr: SocketAddr,
+    ) -> Result<Connection, QuicHandshakeError> {
+        let registry_version = *self.latest_registry_version.read().unwrap();
+        let tls_config = self
+            .tls_config
+            .client_config(peer_id, registry_version)
+            .map_err(|err| QuicHandshakeError::TlsConfig(err.to_string()))?;
+        let mut client_config = ClientConfig::new(Arc::new(tls_config));
+        client_config.transport_config(self.quic_transport_config());
+        let connecting = endpoint
+            .connect_with(client_config, peer_addr, SERVER_NAME)
+            .map_err(|err| QuicHandshakeError::Connection(err.to_string()))?;
+        let connection = timeout(Duration::from_secs(HANDSHAKE_TIMEOUT_SECONDS), connecting)
+            .await
+            .map_err(|_| QuicHandshakeError::DeadlineExceeded)?
+            .map_err(|err| QuicHandshakeError::Connection(err.to_string()))?;
+        let (send, recv) = timeout(Duration::from_secs(HANDSHAKE_TIMEOUT_SECONDS), async {
+            let (mut send, recv) = connection.open_bi().await.map_err(|e| e.to_string())?;
+            send.write_all(&ATTESTATION_STREAM_PREAMBLE)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok::<_, String>((send, recv))
+        })
+        .await
+        .map_err(|_| QuicHandshakeError::DeadlineExceeded)?
+        .map_err(QuicHandshakeError::Connection)?;
+        self.attest(peer_id, send, recv).await?;
+        Ok(connection)
+    }
+
+    /// Performs the SEV attestation over the given stream.
+    async fn attest(
+        &self,
+        peer_id: NodeId,
+        send: SendStream,
+        recv: RecvStream,
+    ) -> Result<(), QuicHandshakeError> {
+        let latest_registry_version = *self.latest_registry_version.read().unwrap();
+        let earliest_registry_version = *self.earliest_registry_version.read().unwrap();
+        let stream: Box<dyn TlsStream> = Box::new(QuicAttestationStream { send, recv });
+        match timeout(
+            Duration::from_secs(SEV_HANDSHAKE_TIMEOUT_SECONDS),
+            self.sev_handshake.perform_attestation_validation(
+                stream,
+                peer_id,
+                latest_registry_version,
+                earliest_registry_version,
+            ),
+        )
+        .await
+        {
+            Err(_) => Err(QuicHandshakeError::DeadlineExceeded),
+            Ok(Err(err)) => Err(QuicHandshakeError::Attestation(format!("{:?}", err))),
+            Ok(Ok(_)) => Ok(()),
+        }
+    }
+
+    /// Starts the data plane tasks for an established connection and reports
+    /// the peer as up.
+    async fn on_connect(&self, peer_id: NodeId, connection: Connection) {
+        let mut peer_map = self.peer_map.write().await;
+        let peer_state = match peer_map.get_mut(&peer_id) {
+            Some(peer_state) => peer_state,
+            None => {
+                connection.close(VarInt::from_u32(CLOSE_CODE), b"unknown peer");
+                return;
+            }
+        };
+        if let QuicConnectionState::Connected { .. } = peer_state.connection {
+            connection.close(VarInt::from_u32(CLOSE_CODE), b"already connected");
+            return;
+        }
+        let mut event_handler = match self.event_handler.lock().await.as_ref() {
+            Some(event_handler) => event_handler.clone(),
+            None => return,
+        };
+
+        let mut tasks = Vec::with_capacity(peer_state.send_queues.len() + 2);
+        for (channel_id, send_queue) in peer_state.send_queues.iter_mut().enumerate() {
+            let connection = connection.clone();
+            let reader = send_queue.get_reader();
+            let peer_label = peer_state.peer_label.clone();
+            let metrics = self.data_plane_metrics.clone();
+            let log = self.log.clone();
+            tasks.push(self.rt_handle.spawn(async move {
+                let _gauge_guard = IntGaugeResource::new(metrics.write_tasks.clone());
+                if let Err(err) = write_channel(
+                    connection,
+                    TransportChannelId::from(channel_id),
+                    reader,
+                    &peer_label,
+                    &metrics,
+                )
+                .await
+                {
+                    info!(
+                        log,
+                        "QuicTransport: write task stopped: peer = {}, channel_id = {}, error = {}",
+                        peer_label,
+                        channel_id,
+                        err
+                    );
+                }
+            }));
+        }
+        tasks.push(self.spawn_accept_streams_task(
+            peer_id,
+            connection.clone(),
+            peer_state.peer_label.clone(),
+            event_handler.clone(),
+        ));
+        let weak_self = self.weak_self.clone();
+        let closed_connection = connection.clone();
+        tasks.push(self.rt_handle.spawn(async move {
+            let reason = closed_connection.closed().await;
+            if let Some(arc_self) = weak_self.upgrade() {
+                arc_self
+                    .on_disconnect(peer_id, closed_connection.stable_id(), reason)
+                    .await;
+            }
+        }));
+
+        event_handler
+            .call(TransportEvent::PeerUp(peer_id))
+            .await
+            .expect("Can't panic on infallible");
+        peer_state.connection = QuicConnectionState::Connected { connection, tasks };
+    }
+
+    /// Starts the task that accepts the channel streams opened by the peer.
+    fn spawn_accept_streams_task(
+        &self,
+        peer_id: NodeId,
+        connection: Connection,
+        peer_label: String,
+        event_handler: TransportEventHandler,
+    ) -> JoinHandle<()> {
+        let rt_handle = self.rt_handle.clone();
+        let max_streams = self.config.max_streams;
+        let metrics = self.data_plane_metrics.clone();
+        let log = self.log.clone();
+        self.rt_handle.spawn(async move {
+            while let Ok(recv) = connection.accept_uni().await {
+                let event_handler = event_handler.clone();
+                let peer_label = peer_label.clone();
+                let metrics = metrics.clone();
+                let log = log.clone();
+                rt_handle.spawn(async move {
+                    let _gauge_guard = IntGaugeResource::new(metrics.read_tasks.clone());
+                    if let Err(err) = read_channel(
+                        recv,
+                        peer_id,
+                        max_streams,
+                        event_handler,
+                        &peer_label,
+                        &metrics,
+                    )
+                    .await
+                    {
+                        info!(
+                            log,
+                            "QuicTransport: read task stopped: peer = {}, error = {}",
+                            peer_label,
+                            err
+                        );
+                    }
+                });
+            }
+        })
+    }
+
+    /// Reports the peer as down and, if we are the client, starts to
+    /// reconnect.
+    async fn on_disconnect(
+        &self,
+        peer_id: NodeId,
+        stable_id: usize,
+        reason: quinn::ConnectionError,
+    ) {
+        let mut peer_map = self.peer_map.write().await;
+        let peer_state = match peer_map.get_mut(&peer_id) {
+            Some(peer_state) => peer_state,
+            None => return,
+        };
+        match &peer_state.connection {
+            QuicConnectionState::Connected { connection, .. }
+                if connection.stable_id() == stable_id => {}
+            // A stale notification for a connection that was already replaced.
+            _ => return,
+        }
+        let mut event_handler = match self.event_handler.lock().await.as_ref() {
+            Some(event_handler) => event_handler.clone(),
+            None => return,
+        };
+        warn!(
+            self.log,
+            "QuicTransport::on_disconnect(): connection closed: peer = {:?}, reason = {:?}",
+            peer_id,
+            reason
+        );
+        self.control_plane_metrics
+            .retry_connection
+            .with_label_values(&[&peer_id.to_string()])
+            .inc();
+        let connection_state = match connection_role(&self.node_id, &peer_id) {
+            ConnectionRole::Server => QuicConnectionState::Listening,
+            ConnectionRole::Client => QuicConnectionState::Connecting(
+                self.spawn_connect_task(peer_id, peer_state.peer_addr),
+            ),
+        };
+        event_handler
+            .call(TransportEvent::PeerDown(peer_id))
+            .await
+            .expect("Can't panic on infallible");
+        // This aborts the calling task, so there must be no await after it.
+        peer_state.connection = connection_state;
+    }
+}
+
+impl Transport for QuicTransportImpl {
+    fn set_event_handler(&self, event_handler: TransportEventHandler) {
+        self.init_client(event_handler)
+    }
+
+    fn start_connection(
+        &self,
+        peer_id: &NodeId,
+        peer_addr: SocketAddr,
+        latest_registry_version: RegistryVersion,
+        earliest_registry_version: RegistryVersion,
+    ) {
+        info!(
+            self.log,
+            "QuicTransport::start_connection(): peer_id = {:?}", peer_id
+        );
+        let role = connection_role(&self.node_id, peer_id);
+        if role == ConnectionRole::Server {
+            self.allowed_clients.write().unwrap().insert(*peer_id);
+        }
+        *self.latest_registry_version.write().unwrap() = latest_registry_version;
+        *self.earliest_registry_version.write().unwrap() = earliest_registry_version;
+        self.refresh_server_config();
+
+        let mut peer_map = self.peer_map.blocking_write();
+        if peer_map.contains_key(peer_id) {
+            return;
+        }
+        let peer_label = get_peer_label(&peer_addr.ip().to_string(), peer_id);
+        let send_queues = (0..self.config.max_streams)
+            .map(|channel_id| {
+                SendQueueImpl::new(
+                    peer_label.clone(),
+                    TransportChannelId::from(channel_id),
+                    self.config.send_queue_size,
+                    self.send_queue_metrics.clone(),
+                )
+            })
+            .collect();
+        let connection = match role {
+            ConnectionRole::Server => QuicConnectionState::Listening,
+            ConnectionRole::Client => {
+                QuicConnectionState::Connecting(self.spawn_connect_task(*peer_id, peer_addr))
+            }
+        };
+        peer_map.insert(
+            *peer_id,
+            QuicPeerState {
+                peer_addr,
+                peer_label,
+                send_queues,
+                connection,
+            },
+        );
+    }
+
+    fn stop_connection(&self, peer_id: &NodeId) {
+        info!(
+            self.log,
+            "QuicTransport::stop_connection(): peer_id = {:?}", peer_id
+        );
+        self.allowed_clients.write().unwrap().remove(peer_id);
+        self.refresh_server_config();
+        self.peer_map.blocking_write().remove(peer_id);
+    }
+
+    fn send(
+        &self,
+        peer_id: &NodeId,
+        channel_id: TransportChannelId,
+        message: TransportPayload,
+    ) -> Result<(), TransportError> {
+        let peer_map = self.peer_map.blocking_read();
+        let send_queue = peer_map
+            .get(peer_id)
+            .and_then(|peer_state| peer_state.send_queues.get(channel_id.get()))
+            .ok_or(TransportError::NotFound)?;
+        match send_queue.enqueue(message) {
+            Some(unsent) => Err(TransportError::SendQueueFull(unsent)),
+            None => Ok(()),
+        }
+    }
+
+    fn clear_send_queues(&self, peer_id: &NodeId) {
+        let mut peer_map = self.peer_map.blocking_write();
+        let peer_state = peer_map
+            .get_mut(peer_id)
+            .expect("Transport client not found");
+        for send_queue in peer_state.send_queues.iter_mut() {
+            send_queue.clear();
+        }
+    }
+}
+
+impl Drop for QuicTransportImpl {
+    fn drop(&mut self) {
+        if let Some(accept_task) = self.accept_task.lock().unwrap().take() {
+            accept_task.abort();
+        }
+    }
+}
+
+/// Returns the node ID of the peer from the certificate it presented during
+/// the handshake. The certificate was verified against the registry by the
+/// rustls configuration.
+fn authenticated_peer_id(connection: &Connection) -> Result<NodeId, String> {
+    let certs = connection
+        .peer_identity()
+        .ok_or_else(|| "Peer did not present a certificate".to_string())?
+        .downcast::<Vec<Certificate>>()
+        .map_err(|_| "Unexpected peer identity type".to_string())?;
+    let cert = match certs.as_slice() {
+        [cert] => cert,
+        _ => return Err(format!("Expected one certificate, got {}", certs.len())),
+    };
+    TlsPublicKeyCert::new_from_der(cert.0.clone())
+        .map_err(|err| format!("{:?}", err))?
+        .node_id_from_subject_common_name()
+        .map_err(|err| format!("{:?}", err))
+}
+
+/// Opens the stream for the given channel and writes the messages from the
+/// send queue to it, until the connection fails.
+async fn write_channel(
+    connection: Connection,
+    channel_id: TransportChannelId,
+    mut reader: Box<dyn SendQueueReader + Send + Sync>,
+    peer_label: &str,
+    metrics: &DataPlaneMetrics,
+) -> Result<(), String> {
+    let mut send = connection.open_uni().await.map_err(|e| e.to_string())?;
+    send.write_all(&(channel_id.get() as u32).to_le_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+    let channel_label = channel_id.to_string();
+    loop {
+        let messages = reader
+            .dequeue(DEQUEUE_BYTES, Duration::from_millis(DEQUEUE_TIMEOUT_MS))
+            .await;
+        if messages.is_empty() {
+            continue;
+        }
+        let mut buffer = Vec::new();
+        for message in messages {
+            buffer.extend_from_slice(&(message.0.len() as u32).to_le_bytes());
+            buffer.extend_from_slice(&message.0);
+        }
+        let start_time = Instant::now();
+        send.write_all(&buffer).await.map_err(|e| e.to_string())?;
+        metrics
+            .send_message_duration
+            .with_label_values(&[&channel_label])
+            .observe(start_time.elapsed().as_secs_f64());
+        metrics
+            .write_bytes_total
+            .with_label_values(&[&channel_label, peer_label])
+            .inc_by(buffer.len() as u64);
+    }
+}
+
+/// Reads the messages from a stream opened by the peer and passes them to the
+/// event handler, until the stream is finished or fails.
+async fn read_channel(
+    mut recv: RecvStream,
+    peer_id: NodeId,
+    max_streams: usize,
+    mut event_handler: TransportEventHandler,
+    peer_label: &str,
+    metrics: &DataPlaneMetrics,
+) -> Result<(), String> {
+    let mut header = [0; 4];
+    recv.read_exact(&mut header)
+        .await
+        .map_err(|e| e.to_string())?;
+    let channel_id = u32::from_le_bytes(header) as usize;
+    if channel_id >= max_streams {
+        return Err(format!("Invalid channel id {}", channel_id));
+    }
+    let channel_label = channel_id.to_string();
+    loop {
+        let mut length = [0; 4];
+        match recv.read_exact(&mut length).await {
+            Ok(()) => (),
+            Err(ReadExactError::FinishedEarly) => return Ok(()),
+            Err(err) => return Err(err.to_string()),
+        }
+        let mut payload = vec![0; u32::from_le_bytes(length) as usize];
+        recv.read_exact(&mut payload)
+            .await
+            .map_err(|e| e.to_string())?;
+        metrics
+            .read_bytes_total
+            .with_label_values(&[&channel_label, peer_label])
+            .inc_by((length.len() + payload.len()) as u64);
+        let start_time = Instant::now();
+        event_handler
+            .call(TransportEvent::Message(TransportMessage {
+                peer_id,
+                payload: TransportPayload(payload),
+            }))
+            .await
+            .expect("Can't panic on infallible");
+        metrics
+            .event_handler_message_duration
+            .with_label_values(&[&channel_label])
+            .observe(start_time.elapsed().as_secs_f64());
+    }
+}
+
+/// The bidirectional stream used for the SEV attestation, which expects a
+/// [`TlsStream`]. The stream is protected by the QUIC connection's TLS
+/// session.
+struct QuicAttestationStream {
+    send: SendStream,
+    recv: RecvStream,
+}
+
+impl AsyncRead for QuicAttestationStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        AsyncRead::poll_read(Pin::new(&mut self.recv), cx, buf)
+    }
+}
+
+impl AsyncWrite for QuicAttestationStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        AsyncWrite::poll_write(Pin::new(&mut self.send), cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        AsyncWrite::poll_flush(Pin::new(&mut self.send), cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        AsyncWrite::poll_shutdown(Pin::new(&mut self.send), cx)
+    }
+}
+
+impl TlsStream for QuicAttestationStream {}
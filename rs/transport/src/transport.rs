@@ -67,10 +67,11 @@
 //! ```
 
 use crate::metrics::{ControlPlaneMetrics, DataPlaneMetrics, SendQueueMetrics};
+use crate::quic::QuicTransportImpl;
 use crate::types::TransportImpl;
 use ic_base_types::{NodeId, RegistryVersion};
 use ic_config::transport::TransportConfig;
-use ic_crypto_tls_interfaces::{TlsHandshake, TlsStream};
+use ic_crypto_tls_interfaces::{TlsConfig, TlsHandshake, TlsStream};
 use ic_icos_sev_interfaces::ValidateAttestedStream;
 use ic_interfaces_transport::{
     Transport, TransportChannelId, TransportError, TransportEventHandler, TransportPayload,
@@ -155,6 +156,33 @@ pub fn create_transport(
     )
 }
 
+/// Returns the QUIC implementation of the `Transport` interfaces, which sends
+/// every channel on an independent stream. It can only connect to peers that
+/// use QUIC as well.
+pub fn create_quic_transport(
+    node_id: NodeId,
+    transport_config: TransportConfig,
+    latest_registry_version: RegistryVersion,
+    earliest_registry_version: RegistryVersion,
+    metrics_registry: MetricsRegistry,
+    tls_config: Arc<dyn TlsConfig + Send + Sync>,
+    sev_handshake: Arc<dyn ValidateAttestedStream<Box<dyn TlsStream>> + Send + Sync>,
+    rt_handle: Handle,
+    log: ReplicaLogger,
+) -> Arc<dyn Transport> {
+    QuicTransportImpl::new(
+        node_id,
+        transport_config,
+        latest_registry_version,
+        earliest_registry_version,
+        metrics_registry,
+        tls_config,
+        sev_handshake,
+        rt_handle,
+        log,
+    )
+}
+
 /// Trait implementation for
 /// [`Transport`](../../ic_interfaces/transport/trait.Transport.html).
 impl Transport for TransportImpl {
@@ -17,7 +17,7 @@ use ic_registry_keys::{
 };
 use ic_registry_proto_data_provider::ProtoRegistryDataProvider;
 use ic_test_utilities_registry::test_subnet_record;
-use ic_transport::transport::create_transport;
+use ic_transport::transport::{create_quic_transport, create_transport};
 use ic_types_test_utils::ids::{NODE_1, NODE_2, NODE_3, NODE_4, SUBNET_1};
 use std::{collections::HashMap, convert::Infallible, net::SocketAddr, str::FromStr, sync::Arc};
 use tokio::{net::TcpSocket, task::JoinHandle};
@@ -460,3 +460,56 @@ pub fn start_connection_between_two_peers(
     peer_b.start_connection(&node_1, peer_1_addr, REG_V1, REG_V1);
     (peer_a, peer_b)
 }
+
+/// Like `start_connection_between_two_peers`, but for two QUIC transports with
+/// `max_streams` channels.
+pub fn start_quic_connection_between_two_peers(
+    rt_handle: tokio::runtime::Handle,
+    logger: ReplicaLogger,
+    registry_version: RegistryVersion,
+    send_queue_size: usize,
+    max_streams: usize,
+    event_handler_1: TransportEventHandler,
+    event_handler_2: TransportEventHandler,
+    node_1: NodeId,
+    node_2: NodeId,
+) -> (Arc<dyn Transport>, Arc<dyn Transport>) {
+    let registry_and_data = RegistryAndDataProvider::new();
+    let crypto_1 = temp_crypto_component_with_tls_keys_in_registry(&registry_and_data, node_1);
+    let crypto_2 = temp_crypto_component_with_tls_keys_in_registry(&registry_and_data, node_2);
+    registry_and_data.registry.update_to_latest_version();
+
+    let mut peers = Vec::new();
+    for (node_id, crypto, event_handler) in [
+        (node_1, crypto_1, event_handler_1),
+        (node_2, crypto_2, event_handler_2),
+    ] {
+        let port = get_free_localhost_port().expect("Failed to get free localhost port");
+        let config = TransportConfig {
+            node_ip: "127.0.0.1".to_string(),
+            listening_port: port,
+            send_queue_size,
+            max_streams,
+        };
+        let sev_handshake = Arc::new(Sev::new(node_id, registry_and_data.registry.clone()));
+        let peer = create_quic_transport(
+            node_id,
+            config,
+            registry_version,
+            registry_version,
+            MetricsRegistry::new(),
+            Arc::new(crypto),
+            sev_handshake,
+            rt_handle.clone(),
+            logger.clone(),
+        );
+        peer.set_event_handler(event_handler);
+        let addr = SocketAddr::from_str(&format!("127.0.0.1:{}", port)).unwrap();
+        peers.push((peer, addr));
+    }
+    let (peer_b, peer_2_addr) = peers.pop().unwrap();
+    let (peer_a, peer_1_addr) = peers.pop().unwrap();
+    peer_a.start_connection(&node_2, peer_2_addr, REG_V1, REG_V1);
+    peer_b.start_connection(&node_1, peer_1_addr, REG_V1, REG_V1);
+    (peer_a, peer_b)
+}
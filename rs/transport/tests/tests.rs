@@ -14,7 +14,7 @@ use ic_transport::transport::create_transport;
 use ic_transport_test_utils::{
     basic_transport_message, basic_transport_message_v2, blocking_transport_message,
     create_mock_event_handler, get_free_localhost_port, large_transport_message, peer_down_message,
-    setup_test_peer, start_connection_between_two_peers,
+    setup_test_peer, start_connection_between_two_peers, start_quic_connection_between_two_peers,
     temp_crypto_component_with_tls_keys_in_registry, RegistryAndDataProvider, TestPeerBuilder,
     TestTopologyBuilder, NODE_ID_1, NODE_ID_2, NODE_ID_3, NODE_ID_4, REG_V1, TRANSPORT_CHANNEL_ID,
 };
@@ -227,6 +227,123 @@ fn test_send_big_message_succeeds(use_h2: bool) {
     });
 }
 
+// Test scenario: two QUIC peers exchange messages on two channels, then one of
+// them disconnects.
+// Test expectation: messages are delivered on every channel in both directions,
+// and the remaining peer receives a PeerDown event.
+#[test]
+fn test_quic_message_send_on_all_channels() {
+    with_test_replica_logger(|logger| {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        let (peer_a_sender, mut peer_a_receiver) = channel(1);
+        let peer_a_event_handler =
+            setup_message_ack_event_handler(rt.handle().clone(), peer_a_sender);
+
+        let (peer_b_sender, mut peer_b_receiver) = channel(1);
+        let peer_b_event_handler =
+            setup_message_ack_event_handler(rt.handle().clone(), peer_b_sender);
+
+        let (peer_a, peer_b) = start_quic_connection_between_two_peers(
+            rt.handle().clone(),
+            logger,
+            REG_V1,
+            10,
+            2,
+            peer_a_event_handler,
+            peer_b_event_handler,
+            NODE_ID_1,
+            NODE_ID_2,
+        );
+
+        for channel_id in [0, 1].map(TransportChannelId::from) {
+            assert_eq!(
+                peer_a.send(&NODE_ID_2, channel_id, basic_transport_message()),
+                Ok(())
+            );
+            assert_eq!(
+                peer_b_receiver.blocking_recv(),
+                Some(basic_transport_message())
+            );
+            assert_eq!(
+                peer_b.send(&NODE_ID_1, channel_id, basic_transport_message_v2()),
+                Ok(())
+            );
+            assert_eq!(
+                peer_a_receiver.blocking_recv(),
+                Some(basic_transport_message_v2())
+            );
+        }
+        // Channels beyond `max_streams` do not exist.
+        assert_eq!(
+            peer_a.send(
+                &NODE_ID_2,
+                TransportChannelId::from(2),
+                basic_transport_message()
+            ),
+            Err(TransportError::NotFound)
+        );
+
+        peer_a.stop_connection(&NODE_ID_2);
+        assert_eq!(peer_b_receiver.blocking_recv(), Some(peer_down_message()));
+    });
+}
+
+// Test scenario: a QUIC peer sends a large message on one channel and then a
+// small message on another channel, as P2P does for a chunk and an advert.
+// Test expectation: the small message is not delayed by the large one and is
+// delivered first.
+#[test]
+fn test_quic_large_message_does_not_delay_other_channels() {
+    with_test_replica_logger(|logger| {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        let (peer_a_sender, _peer_a_receiver) = channel(1);
+        let peer_a_event_handler =
+            setup_message_ack_event_handler(rt.handle().clone(), peer_a_sender);
+
+        let (peer_b_sender, mut peer_b_receiver) = channel(2);
+        let peer_b_event_handler =
+            setup_message_ack_event_handler(rt.handle().clone(), peer_b_sender);
+
+        let (peer_a, _peer_b) = start_quic_connection_between_two_peers(
+            rt.handle().clone(),
+            logger,
+            REG_V1,
+            10,
+            4,
+            peer_a_event_handler,
+            peer_b_event_handler,
+            NODE_ID_1,
+            NODE_ID_2,
+        );
+
+        let small_message = TransportPayload(vec![0xa; 100]);
+        assert_eq!(
+            peer_a.send(
+                &NODE_ID_2,
+                TransportChannelId::from(2),
+                large_transport_message()
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            peer_a.send(
+                &NODE_ID_2,
+                TransportChannelId::from(0),
+                small_message.clone()
+            ),
+            Ok(())
+        );
+
+        assert_eq!(peer_b_receiver.blocking_recv(), Some(small_message));
+        assert_eq!(
+            peer_b_receiver.blocking_recv(),
+            Some(large_transport_message())
+        );
+    });
+}
+
 /*
 Establish connection with 2 peers, A and B.  Confirm that connection stays alive even when
 no messages are being sent. (In current implementation, this is ensured by heartbeats)
@@ -543,6 +543,10 @@ impl Payload<'_> for UninstallCodeArgs {}
 ///     controller : principal;
 ///     compute_allocation: nat;
 ///     memory_allocation: opt nat;
+///     freezing_threshold: nat;
+///     reserved_cycles_limit: nat;
+///     wasm_memory_limit: nat;
+///     log_visibility: log_visibility;
 /// })`
 #[derive(CandidType, Deserialize, Debug, Eq, PartialEq)]
 pub struct DefiniteCanisterSettingsArgs {
@@ -551,32 +555,56 @@ pub struct DefiniteCanisterSettingsArgs {
     compute_allocation: candid::Nat,
     memory_allocation: candid::Nat,
     freezing_threshold: candid::Nat,
+    reserved_cycles_limit: candid::Nat,
+    wasm_memory_limit: candid::Nat,
+    log_visibility: LogVisibility,
 }
 
 impl DefiniteCanisterSettingsArgs {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         controller: PrincipalId,
         controllers: Vec<PrincipalId>,
         compute_allocation: u64,
         memory_allocation: Option<u64>,
         freezing_threshold: u64,
+        reserved_cycles_limit: u128,
+        wasm_memory_limit: Option<u64>,
+        log_visibility: LogVisibility,
     ) -> Self {
         let memory_allocation = match memory_allocation {
             None => candid::Nat::from(0),
             Some(memory) => candid::Nat::from(memory),
         };
+        // A Wasm memory limit of zero is reported when there is no limit.
+        let wasm_memory_limit = candid::Nat::from(wasm_memory_limit.unwrap_or(0));
         Self {
             controller,
             controllers,
             compute_allocation: candid::Nat::from(compute_allocation),
             memory_allocation,
             freezing_threshold: candid::Nat::from(freezing_threshold),
+            reserved_cycles_limit: candid::Nat::from(reserved_cycles_limit),
+            wasm_memory_limit,
+            log_visibility,
         }
     }
 
     pub fn controllers(&self) -> Vec<PrincipalId> {
         self.controllers.clone()
     }
+
+    pub fn reserved_cycles_limit(&self) -> u128 {
+        self.reserved_cycles_limit.0.to_u128().unwrap()
+    }
+
+    pub fn wasm_memory_limit(&self) -> u64 {
+        self.wasm_memory_limit.0.to_u64().unwrap()
+    }
+
+    pub fn log_visibility(&self) -> LogVisibility {
+        self.log_visibility
+    }
 }
 
 impl Payload<'_> for DefiniteCanisterSettingsArgs {}
@@ -646,6 +674,7 @@ impl Payload<'_> for CanisterStatusResult {}
 ///     memory_size: nat;
 ///     cycles: nat;
 ///     idle_cycles_burned_per_day: nat;
+///     reserved_cycles: nat;
 /// })`
 #[derive(CandidType, Debug, Deserialize, Eq, PartialEq)]
 pub struct CanisterStatusResultV2 {
@@ -659,6 +688,7 @@ pub struct CanisterStatusResultV2 {
     balance: Vec<(Vec<u8>, candid::Nat)>,
     freezing_threshold: candid::Nat,
     idle_cycles_burned_per_day: candid::Nat,
+    reserved_cycles: candid::Nat,
 }
 
 impl CanisterStatusResultV2 {
@@ -674,6 +704,10 @@ impl CanisterStatusResultV2 {
         memory_allocation: Option<u64>,
         freezing_threshold: u64,
         idle_cycles_burned_per_day: u128,
+        reserved_cycles: u128,
+        reserved_cycles_limit: u128,
+        wasm_memory_limit: Option<u64>,
+        log_visibility: LogVisibility,
     ) -> Self {
         Self {
             status,
@@ -690,9 +724,13 @@ impl CanisterStatusResultV2 {
                 compute_allocation,
                 memory_allocation,
                 freezing_threshold,
+                reserved_cycles_limit,
+                wasm_memory_limit,
+                log_visibility,
             ),
             freezing_threshold: candid::Nat::from(freezing_threshold),
             idle_cycles_burned_per_day: candid::Nat::from(idle_cycles_burned_per_day),
+            reserved_cycles: candid::Nat::from(reserved_cycles),
         }
     }
 
@@ -727,6 +765,14 @@ impl CanisterStatusResultV2 {
     pub fn idle_cycles_burned_per_day(&self) -> u128 {
         self.idle_cycles_burned_per_day.0.to_u128().unwrap()
     }
+
+    pub fn reserved_cycles(&self) -> u128 {
+        self.reserved_cycles.0.to_u128().unwrap()
+    }
+
+    pub fn settings(&self) -> &DefiniteCanisterSettingsArgs {
+        &self.settings
+    }
 }
 
 /// Indicates whether the canister is running, stopping, or stopped.
@@ -754,6 +800,58 @@ impl fmt::Display for CanisterStatusType {
     }
 }
 
+/// Who can read the logs of a canister.
+///
+/// `(variant {
+///     controllers;
+///     public;
+/// })`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, CandidType)]
+pub enum LogVisibility {
+    /// Only the controllers of the canister can read its logs.
+    #[default]
+    #[serde(rename = "controllers")]
+    Controllers,
+    /// Anyone can read the logs of the canister.
+    #[serde(rename = "public")]
+    Public,
+}
+
+impl From<LogVisibility> for pb_canister_state_bits::LogVisibility {
+    fn from(item: LogVisibility) -> Self {
+        match item {
+            LogVisibility::Controllers => pb_canister_state_bits::LogVisibility::Controllers,
+            LogVisibility::Public => pb_canister_state_bits::LogVisibility::Public,
+        }
+    }
+}
+
+impl TryFrom<pb_canister_state_bits::LogVisibility> for LogVisibility {
+    type Error = ProxyDecodeError;
+
+    fn try_from(item: pb_canister_state_bits::LogVisibility) -> Result<Self, Self::Error> {
+        match item {
+            pb_canister_state_bits::LogVisibility::Controllers => Ok(LogVisibility::Controllers),
+            pb_canister_state_bits::LogVisibility::Public => Ok(LogVisibility::Public),
+            pb_canister_state_bits::LogVisibility::Unspecified => {
+                Err(ProxyDecodeError::ValueOutOfRange {
+                    typ: "LogVisibility",
+                    err: "Unspecified log visibility".to_string(),
+                })
+            }
+        }
+    }
+}
+
+#[test]
+fn log_visibility_proto_round_trip() {
+    for log_visibility in [LogVisibility::Controllers, LogVisibility::Public] {
+        let proto = pb_canister_state_bits::LogVisibility::from(log_visibility);
+        assert_eq!(LogVisibility::try_from(proto).unwrap(), log_visibility);
+    }
+    assert!(LogVisibility::try_from(pb_canister_state_bits::LogVisibility::Unspecified).is_err());
+}
+
 /// The mode with which a canister is installed.
 #[derive(
     Clone, Debug, Deserialize, PartialEq, Serialize, Eq, EnumString, Hash, CandidType, Copy,
@@ -1036,6 +1134,10 @@ impl Payload<'_> for UpdateSettingsArgs {}
 ///     controllers: opt vec principal;
 ///     compute_allocation: opt nat;
 ///     memory_allocation: opt nat;
+///     freezing_threshold: opt nat;
+///     reserved_cycles_limit: opt nat;
+///     wasm_memory_limit: opt nat;
+///     log_visibility: opt log_visibility;
 /// })`
 #[derive(Default, Clone, CandidType, Deserialize, Debug)]
 pub struct CanisterSettingsArgs {
@@ -1045,6 +1147,9 @@ pub struct CanisterSettingsArgs {
     pub compute_allocation: Option<candid::Nat>,
     pub memory_allocation: Option<candid::Nat>,
     pub freezing_threshold: Option<candid::Nat>,
+    pub reserved_cycles_limit: Option<candid::Nat>,
+    pub wasm_memory_limit: Option<candid::Nat>,
+    pub log_visibility: Option<LogVisibility>,
 }
 
 impl Payload<'_> for CanisterSettingsArgs {}
@@ -1062,6 +1167,9 @@ impl CanisterSettingsArgs {
             compute_allocation: compute_allocation.map(candid::Nat::from),
             memory_allocation: memory_allocation.map(candid::Nat::from),
             freezing_threshold: freezing_threshold.map(candid::Nat::from),
+            reserved_cycles_limit: None,
+            wasm_memory_limit: None,
+            log_visibility: None,
         }
     }
 
@@ -1077,6 +1185,9 @@ pub struct CanisterSettingsArgsBuilder {
     compute_allocation: Option<candid::Nat>,
     memory_allocation: Option<candid::Nat>,
     freezing_threshold: Option<candid::Nat>,
+    reserved_cycles_limit: Option<candid::Nat>,
+    wasm_memory_limit: Option<candid::Nat>,
+    log_visibility: Option<LogVisibility>,
 }
 
 #[allow(dead_code)]
@@ -1092,6 +1203,9 @@ impl CanisterSettingsArgsBuilder {
             compute_allocation: self.compute_allocation,
             memory_allocation: self.memory_allocation,
             freezing_threshold: self.freezing_threshold,
+            reserved_cycles_limit: self.reserved_cycles_limit,
+            wasm_memory_limit: self.wasm_memory_limit,
+            log_visibility: self.log_visibility,
         }
     }
 
@@ -1135,6 +1249,35 @@ impl CanisterSettingsArgsBuilder {
             ..self
         }
     }
+
+    /// Sets the upper limit in cycles on the canister's reserved balance.
+    /// For more details see the description of this field in the IC
+    /// specification.
+    pub fn with_reserved_cycles_limit(self, reserved_cycles_limit: u128) -> Self {
+        Self {
+            reserved_cycles_limit: Some(candid::Nat::from(reserved_cycles_limit)),
+            ..self
+        }
+    }
+
+    /// Sets the Wasm memory limit in bytes. A value of zero removes the
+    /// limit. For more details see the description of this field in the IC
+    /// specification.
+    pub fn with_wasm_memory_limit(self, wasm_memory_limit: u64) -> Self {
+        Self {
+            wasm_memory_limit: Some(candid::Nat::from(wasm_memory_limit)),
+            ..self
+        }
+    }
+
+    /// Sets who can read the logs of the canister. For more details see
+    /// the description of this field in the IC specification.
+    pub fn with_log_visibility(self, log_visibility: LogVisibility) -> Self {
+        Self {
+            log_visibility: Some(log_visibility),
+            ..self
+        }
+    }
 }
 
 /// Struct used for encoding/decoding
@@ -229,6 +229,9 @@ pub struct Advert<Artifact: ArtifactKind> {
     // they are never compared, except in one instance where we compare something
     // in GossipAdvert and Advert<T>, so we can't make a mistake.
     pub integrity_hash: CryptoHash,
+    // Root of the chunk tree for artifacts downloaded in pieces, see
+    // `crate::multi_chunked`.
+    pub chunk_tree_root: Option<CryptoHash>,
 }
 
 impl<Artifact: ArtifactKind> From<Advert<Artifact>> for GossipAdvert
@@ -242,6 +245,7 @@ where
             attribute: advert.attribute.into(),
             size: advert.size,
             integrity_hash: advert.integrity_hash,
+            chunk_tree_root: advert.chunk_tree_root,
         }
     }
 }
@@ -264,24 +268,28 @@ where
                 attribute,
                 size,
                 integrity_hash: advert.integrity_hash,
+                chunk_tree_root: advert.chunk_tree_root,
             }),
             (Err(artifact_id), Ok(attribute)) => Err(GossipAdvert {
                 artifact_id,
                 attribute: attribute.into(),
                 size,
                 integrity_hash: advert.integrity_hash,
+                chunk_tree_root: advert.chunk_tree_root,
             }),
             (Ok(artifact_id), Err(attribute)) => Err(GossipAdvert {
                 artifact_id: artifact_id.into(),
                 attribute,
                 size,
                 integrity_hash: advert.integrity_hash,
+                chunk_tree_root: advert.chunk_tree_root,
             }),
             (Err(artifact_id), Err(attribute)) => Err(GossipAdvert {
                 artifact_id,
                 attribute,
                 size,
                 integrity_hash: advert.integrity_hash,
+                chunk_tree_root: advert.chunk_tree_root,
             }),
         }
     }
@@ -8,6 +8,7 @@ use crate::{
         ConsensusMessageHashable,
     },
     crypto::crypto_hash,
+    multi_chunked::chunk_tree_root,
     CountBytes,
 };
 use serde::{Deserialize, Serialize};
@@ -34,6 +35,7 @@ impl ArtifactKind for ConsensusArtifact {
             attribute,
             size,
             integrity_hash: crypto_hash(msg).get(),
+            chunk_tree_root: chunk_tree_root(size, msg),
         }
     }
 }
@@ -58,6 +60,7 @@ impl ArtifactKind for IngressArtifact {
             attribute: IngressMessageAttribute::new(msg),
             size: msg.count_bytes(),
             integrity_hash: crypto_hash(msg.binary()).get(),
+            chunk_tree_root: None,
         }
     }
 }
@@ -99,6 +102,7 @@ impl ArtifactKind for CertificationArtifact {
             attribute,
             size: bincode::serialized_size(&msg).unwrap() as usize,
             integrity_hash: crypto_hash(msg).get(),
+            chunk_tree_root: None,
         }
     }
 }
@@ -128,6 +132,7 @@ impl ArtifactKind for DkgArtifact {
             attribute,
             size,
             integrity_hash: hash.get(),
+            chunk_tree_root: chunk_tree_root(size, msg),
         }
     }
 }
@@ -153,6 +158,7 @@ impl ArtifactKind for EcdsaArtifact {
             attribute: EcdsaMessageAttribute::from(msg),
             size,
             integrity_hash: crypto_hash(msg).get(),
+            chunk_tree_root: None,
         }
     }
 }
@@ -183,6 +189,7 @@ impl ArtifactKind for CanisterHttpArtifact {
             ),
             size,
             integrity_hash: hash.get(),
+            chunk_tree_root: None,
         }
     }
 }
@@ -660,6 +660,7 @@ mod tests {
                 payment: Cycles::new(10),
                 method_name: "tansform".to_string(),
                 method_payload: Vec::new(),
+                deadline: None,
             },
             time: UNIX_EPOCH,
         };
@@ -700,6 +701,7 @@ mod tests {
                 payment: Cycles::new(10),
                 method_name: "tansform".to_string(),
                 method_payload: Vec::new(),
+                deadline: None,
             },
             time: UNIX_EPOCH,
         };
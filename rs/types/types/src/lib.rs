@@ -77,6 +77,7 @@ pub mod malicious_behaviour;
 pub mod malicious_flags;
 pub mod messages;
 pub mod methods;
+pub mod multi_chunked;
 pub mod nominal_cycles;
 pub mod onchain_observability;
 pub mod p2p;
@@ -555,28 +556,44 @@ pub const MAX_STABLE_MEMORY_IN_BYTES: u64 = 64 * GB;
 /// it is public and `u64` (`NumBytes` cannot be used in const expressions).
 pub const MAX_WASM_MEMORY_IN_BYTES: u64 = 4 * GB;
 
+/// The upper limit on the size of a 64-bit Wasm memory.
+/// This constant is used by other crates to define other constants, that's why
+/// it is public and `u64` (`NumBytes` cannot be used in const expressions).
+pub const MAX_WASM64_MEMORY_IN_BYTES: u64 = 16 * GB;
+
 const MIN_MEMORY_ALLOCATION: NumBytes = NumBytes::new(0);
 pub const MAX_MEMORY_ALLOCATION: NumBytes =
     NumBytes::new(MAX_STABLE_MEMORY_IN_BYTES + MAX_WASM_MEMORY_IN_BYTES);
+/// The upper limit on the memory allocation of a canister on a subnet that
+/// supports 64-bit Wasm memories.
+pub const MAX_WASM64_MEMORY_ALLOCATION: NumBytes =
+    NumBytes::new(MAX_STABLE_MEMORY_IN_BYTES + MAX_WASM64_MEMORY_IN_BYTES);
 
 impl InvalidMemoryAllocationError {
     pub fn new(given: candid::Nat) -> Self {
+        Self::with_max(given, MAX_MEMORY_ALLOCATION)
+    }
+
+    pub fn with_max(given: candid::Nat, max: NumBytes) -> Self {
         Self {
             min: candid::Nat::from(MIN_MEMORY_ALLOCATION.get()),
-            max: candid::Nat::from(MAX_MEMORY_ALLOCATION.get()),
+            max: candid::Nat::from(max.get()),
             given,
         }
     }
 }
 
-impl TryFrom<NumBytes> for MemoryAllocation {
-    type Error = InvalidMemoryAllocationError;
-
-    fn try_from(bytes: NumBytes) -> Result<Self, Self::Error> {
-        if bytes > MAX_MEMORY_ALLOCATION {
-            return Err(InvalidMemoryAllocationError::new(candid::Nat::from(
-                bytes.get(),
-            )));
+impl MemoryAllocation {
+    /// Converts `bytes` into a memory allocation that may not exceed `max`.
+    pub fn try_from_with_max(
+        bytes: NumBytes,
+        max: NumBytes,
+    ) -> Result<Self, InvalidMemoryAllocationError> {
+        if bytes > max {
+            return Err(InvalidMemoryAllocationError::with_max(
+                candid::Nat::from(bytes.get()),
+                max,
+            ));
         }
         // A memory allocation of 0 means that the canister's memory growth will be
         // best-effort.
@@ -588,6 +605,14 @@ impl TryFrom<NumBytes> for MemoryAllocation {
     }
 }
 
+impl TryFrom<NumBytes> for MemoryAllocation {
+    type Error = InvalidMemoryAllocationError;
+
+    fn try_from(bytes: NumBytes) -> Result<Self, Self::Error> {
+        Self::try_from_with_max(bytes, MAX_MEMORY_ALLOCATION)
+    }
+}
+
 /// Allow an object to report its own byte size. It is only meant to be an
 /// estimate, and not an exact measure of its heap usage or length of serialized
 /// bytes.
@@ -1,4 +1,4 @@
-use crate::{ingress::WasmResult, CanisterId, CountBytes, Cycles, Funds, NumBytes};
+use crate::{ingress::WasmResult, CanisterId, CountBytes, Cycles, Funds, NumBytes, Time};
 use ic_error_types::{RejectCode, TryFromError, UserError};
 use ic_ic00_types::{
     CanisterIdRecord, InstallCodeArgs, Method, Payload as _, ProvisionalTopUpCanisterArgs,
@@ -44,6 +44,10 @@ pub struct Request {
     pub method_name: String,
     #[serde(with = "serde_bytes")]
     pub method_payload: Vec<u8>,
+    /// If set, the caller only waits for a best-effort response until this
+    /// deadline, after which a `SYS_UNKNOWN` reject is produced locally.
+    #[serde(default)]
+    pub deadline: Option<Time>,
 }
 
 impl Request {
@@ -142,9 +146,13 @@ impl std::fmt::Debug for Request {
         }
         write!(
             f,
-            "method_payload: [{}] }}",
+            "method_payload: [{}]",
             truncate_and_format(&self.method_payload, 1024)
         )?;
+        if let Some(deadline) = self.deadline {
+            write!(f, ", deadline: {:?}", deadline)?;
+        }
+        write!(f, " }}")?;
         Ok(())
     }
 }
@@ -381,6 +389,10 @@ impl From<&Request> for pb_queues::Request {
             method_name: req.method_name.clone(),
             method_payload: req.method_payload.clone(),
             cycles_payment: Some((req.payment).into()),
+            deadline: req
+                .deadline
+                .map(|deadline| deadline.as_nanos_since_unix_epoch())
+                .unwrap_or_default(),
         }
     }
 }
@@ -404,6 +416,10 @@ impl TryFrom<pb_queues::Request> for Request {
             payment,
             method_name: req.method_name,
             method_payload: req.method_payload,
+            deadline: match req.deadline {
+                0 => None,
+                deadline => Some(Time::from_nanos_since_unix_epoch(deadline)),
+            },
         })
     }
 }
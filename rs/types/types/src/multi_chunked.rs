@@ -0,0 +1,581 @@
+//! Chunk tracking for artifacts that are too large to be downloaded as a
+//! single chunk.
+//!
+//! Artifacts of kinds that can grow large (block proposals carrying big
+//! ingress payloads, DKG dealings) are split into chunks of
+//! [`ARTIFACT_CHUNK_SIZE`] bytes of their serialized message. The advert for
+//! such an artifact carries the root of a [`ChunkTree`], a binary Merkle tree
+//! over these chunks, so that every chunk can be verified on its own,
+//! independently of the peer that served it. This allows P2P to download the
+//! chunks of one artifact from all the peers that advertised it.
+//!
+//! Chunk `CHUNKID_UNIT_CHUNK` keeps denoting the whole artifact, so peers
+//! that do not know about the chunk tree can still serve and fetch the
+//! artifact in one piece. Chunk `i + 1` denotes the `i`-th piece.
+//!
+//! The chunk tree root and size of an advert come from the advertising peer
+//! and are not part of the integrity hash. They are bound to it by checking
+//! the assembled artifact against the integrity hash. If the verified pieces
+//! do not make up the advertised artifact, the tracker falls back to a single
+//! chunk, and P2P retries with the chunk tree advertised by the next most
+//! peers before downloading the artifact in one piece.
+use crate::{
+    artifact::{Artifact, ArtifactTag},
+    chunkable::{
+        ArtifactChunk, ArtifactChunkData, ArtifactErrorCode, ChunkId, Chunkable, CHUNKID_UNIT_CHUNK,
+    },
+    crypto::{crypto_hash, CryptoHash},
+    p2p::GossipAdvert,
+};
+use bincode::{deserialize, serialize};
+use ic_crypto_sha::Sha256;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+/// Size in bytes of the pieces a large artifact is split into.
+pub const ARTIFACT_CHUNK_SIZE: usize = 512 * 1024;
+
+/// Artifacts whose serialized message is at least this large are advertised
+/// with a chunk tree root and downloaded in pieces.
+pub const MIN_MULTI_CHUNKED_SIZE: usize = 2 * ARTIFACT_CHUNK_SIZE;
+
+/// Largest advertised size for which an artifact is downloaded in pieces.
+/// Adverts announcing a larger size are downloaded as a single chunk, so that
+/// a bogus advert cannot make P2P track an arbitrary number of pieces.
+pub const MAX_MULTI_CHUNKED_SIZE: usize = 128 * 1024 * 1024;
+
+const LEAF_DOMAIN: &[u8] = b"ic-artifact-chunk-leaf";
+const NODE_DOMAIN: &[u8] = b"ic-artifact-chunk-node";
+
+fn hash_leaf(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.write(LEAF_DOMAIN);
+    hasher.write(data);
+    hasher.finish()
+}
+
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.write(NODE_DOMAIN);
+    hasher.write(left);
+    hasher.write(right);
+    hasher.finish()
+}
+
+fn num_chunks(size: usize) -> usize {
+    size / ARTIFACT_CHUNK_SIZE + usize::from(size % ARTIFACT_CHUNK_SIZE != 0)
+}
+
+/// Returns the id of the `index`-th piece, or `None` if it does not fit.
+fn piece_chunk_id(index: usize) -> Option<ChunkId> {
+    u32::try_from(index).ok()?.checked_add(1).map(ChunkId::from)
+}
+
+/// Returns the integrity hash P2P advertises for an artifact that may be
+/// downloaded in pieces.
+fn integrity_hash(artifact: &Artifact) -> Option<CryptoHash> {
+    match artifact {
+        Artifact::ConsensusMessage(msg) => Some(crypto_hash(msg).get()),
+        Artifact::DkgMessage(msg) => Some(crypto_hash(msg).get()),
+        _ => None,
+    }
+}
+
+/// A binary Merkle tree over the [`ARTIFACT_CHUNK_SIZE`] pieces of a byte
+/// string. A node without a right sibling is promoted to the next level
+/// unchanged.
+pub struct ChunkTree {
+    // levels[0] holds the leaves, the last level holds the root.
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl ChunkTree {
+    /// Builds the chunk tree over `data`, which must not be empty.
+    pub fn new(data: &[u8]) -> Self {
+        let leaves: Vec<_> = data.chunks(ARTIFACT_CHUNK_SIZE).map(hash_leaf).collect();
+        assert!(!leaves.is_empty(), "Cannot build a chunk tree of no data");
+        let mut levels = vec![leaves];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_node(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    /// Returns the root hash of the tree.
+    pub fn root(&self) -> CryptoHash {
+        CryptoHash(self.levels.last().unwrap()[0].to_vec())
+    }
+
+    /// Returns the sibling hashes on the path from the `index`-th leaf to the
+    /// root, bottom-up.
+    pub fn witness(&self, index: usize) -> Vec<CryptoHash> {
+        let mut witness = Vec::new();
+        let mut index = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = index ^ 1;
+            if sibling < level.len() {
+                witness.push(CryptoHash(level[sibling].to_vec()));
+            }
+            index /= 2;
+        }
+        witness
+    }
+
+    /// Checks that `data` is the `index`-th of `num_chunks` pieces of a byte
+    /// string with chunk tree `root`.
+    pub fn verify(
+        root: &CryptoHash,
+        num_chunks: usize,
+        index: usize,
+        data: &[u8],
+        witness: &[CryptoHash],
+    ) -> bool {
+        if index >= num_chunks {
+            return false;
+        }
+        let mut hash = hash_leaf(data);
+        let mut witness = witness.iter();
+        let (mut index, mut width) = (index, num_chunks);
+        while width > 1 {
+            if index ^ 1 < width {
+                let sibling: [u8; 32] = match witness.next().map(|h| h.0.as_slice().try_into()) {
+                    Some(Ok(sibling)) => sibling,
+                    _ => return false,
+                };
+                hash = if index % 2 == 0 {
+                    hash_node(&hash, &sibling)
+                } else {
+                    hash_node(&sibling, &hash)
+                };
+            }
+            index /= 2;
+            width = (width + 1) / 2;
+        }
+        witness.next().is_none() && hash.as_slice() == root.0.as_slice()
+    }
+}
+
+/// Returns the chunk tree root to advertise for a message whose serialization
+/// is `size` bytes long, or `None` if the message is small enough to be
+/// downloaded as a single chunk.
+pub fn chunk_tree_root<T: Serialize>(size: usize, msg: &T) -> Option<CryptoHash> {
+    if !(MIN_MULTI_CHUNKED_SIZE..=MAX_MULTI_CHUNKED_SIZE).contains(&size) {
+        return None;
+    }
+    ChunkedArtifact::new(msg).map(|chunked| chunked.root())
+}
+
+/// The serialized message of a large artifact together with its chunk tree,
+/// from which the pieces of the artifact are served. Building it serializes
+/// and hashes the whole message, so it is meant to be built once and reused
+/// for all pieces of the artifact.
+pub struct ChunkedArtifact {
+    bytes: Vec<u8>,
+    tree: ChunkTree,
+}
+
+impl ChunkedArtifact {
+    /// Returns the pieces of `msg`, or `None` if `msg` is small enough to be
+    /// served as a single chunk only.
+    pub fn new<T: Serialize>(msg: &T) -> Option<Self> {
+        let bytes = serialize(msg).ok()?;
+        if !(MIN_MULTI_CHUNKED_SIZE..=MAX_MULTI_CHUNKED_SIZE).contains(&bytes.len()) {
+            return None;
+        }
+        Some(Self::from_bytes(bytes))
+    }
+
+    /// Returns the pieces of `artifact`, if it is of a kind that is served in
+    /// pieces and large enough.
+    pub fn from_artifact(artifact: &Artifact) -> Option<Self> {
+        match artifact {
+            Artifact::ConsensusMessage(msg) => Self::new(msg),
+            Artifact::DkgMessage(msg) => Self::new(msg),
+            _ => None,
+        }
+    }
+
+    fn from_bytes(bytes: Vec<u8>) -> Self {
+        let tree = ChunkTree::new(&bytes);
+        Self { bytes, tree }
+    }
+
+    /// Returns the root of the chunk tree.
+    pub fn root(&self) -> CryptoHash {
+        self.tree.root()
+    }
+
+    /// Returns the piece identified by `chunk_id` together with its witness.
+    pub fn get_chunk(&self, chunk_id: ChunkId) -> Option<ArtifactChunk> {
+        let index = chunk_id.get().checked_sub(1)? as usize;
+        if index >= num_chunks(self.bytes.len()) {
+            return None;
+        }
+        let start = index * ARTIFACT_CHUNK_SIZE;
+        let end = std::cmp::min(start + ARTIFACT_CHUNK_SIZE, self.bytes.len());
+        Some(ArtifactChunk {
+            chunk_id,
+            witness: self.tree.witness(index),
+            artifact_chunk_data: ArtifactChunkData::SemiStructuredChunkData(
+                self.bytes[start..end].to_vec(),
+            ),
+        })
+    }
+}
+
+/// Tracker of an artifact that is downloaded in [`ARTIFACT_CHUNK_SIZE`]
+/// pieces, possibly from different peers.
+pub struct MultiChunked {
+    tag: ArtifactTag,
+    integrity_hash: CryptoHash,
+    root: CryptoHash,
+    size: usize,
+    chunks: BTreeMap<usize, Vec<u8>>,
+    // Set once the pieces turned out not to assemble into the advertised
+    // artifact, after which only the whole artifact is downloaded.
+    single_chunk: bool,
+}
+
+impl MultiChunked {
+    /// Returns a tracker for the artifact of the given advert if the advert
+    /// carries a chunk tree root and a plausible size, and `None` if the
+    /// artifact should be downloaded as a single chunk.
+    pub fn from_advert(advert: &GossipAdvert) -> Option<Self> {
+        let root = advert.chunk_tree_root.clone()?;
+        let tag = ArtifactTag::from(&advert.artifact_id);
+        match tag {
+            ArtifactTag::ConsensusArtifact | ArtifactTag::DkgArtifact
+                if (MIN_MULTI_CHUNKED_SIZE..=MAX_MULTI_CHUNKED_SIZE).contains(&advert.size) =>
+            {
+                Some(Self {
+                    tag,
+                    integrity_hash: advert.integrity_hash.clone(),
+                    root,
+                    size: advert.size,
+                    chunks: BTreeMap::new(),
+                    single_chunk: false,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn num_chunks(&self) -> usize {
+        num_chunks(self.size)
+    }
+
+    fn expected_len(&self, index: usize) -> usize {
+        std::cmp::min(ARTIFACT_CHUNK_SIZE, self.size - index * ARTIFACT_CHUNK_SIZE)
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        self.chunks.values().flatten().copied().collect()
+    }
+
+    // Decodes the downloaded pieces, and checks that they make up the
+    // artifact with the advertised integrity hash.
+    fn assemble(&self) -> Option<Artifact> {
+        let bytes = self.bytes();
+        let artifact = match self.tag {
+            ArtifactTag::ConsensusArtifact => {
+                deserialize(&bytes).ok().map(Artifact::ConsensusMessage)
+            }
+            ArtifactTag::DkgArtifact => deserialize(&bytes).ok().map(Artifact::DkgMessage),
+            _ => None,
+        }?;
+        (integrity_hash(&artifact)? == self.integrity_hash).then_some(artifact)
+    }
+}
+
+impl Chunkable for MultiChunked {
+    fn chunks_to_download(&self) -> Box<dyn Iterator<Item = ChunkId>> {
+        if self.single_chunk {
+            return Box::new(std::iter::once(ChunkId::from(CHUNKID_UNIT_CHUNK)));
+        }
+        let missing: Vec<ChunkId> = (0..self.num_chunks())
+            .filter(|index| !self.chunks.contains_key(index))
+            .filter_map(piece_chunk_id)
+            .collect();
+        Box::new(missing.into_iter())
+    }
+
+    fn add_chunk(&mut self, artifact_chunk: ArtifactChunk) -> Result<Artifact, ArtifactErrorCode> {
+        let data = match artifact_chunk.artifact_chunk_data {
+            // A peer that is not aware of chunk trees may still serve the
+            // whole artifact, which P2P verifies against the integrity hash.
+            ArtifactChunkData::UnitChunkData(artifact)
+                if artifact_chunk.chunk_id == ChunkId::from(CHUNKID_UNIT_CHUNK) =>
+            {
+                return Ok(artifact)
+            }
+            // Pieces requested before falling back to a single chunk are
+            // of no use anymore.
+            ArtifactChunkData::SemiStructuredChunkData(_) if self.single_chunk => {
+                return Err(ArtifactErrorCode::ChunksMoreNeeded)
+            }
+            ArtifactChunkData::SemiStructuredChunkData(data) => data,
+            _ => return Err(ArtifactErrorCode::ChunkVerificationFailed),
+        };
+        let index = match artifact_chunk.chunk_id.get().checked_sub(1) {
+            Some(index) if (index as usize) < self.num_chunks() => index as usize,
+            _ => return Err(ArtifactErrorCode::ChunkVerificationFailed),
+        };
+        if self.chunks.contains_key(&index) {
+            return Err(ArtifactErrorCode::ChunksMoreNeeded);
+        }
+        if data.len() != self.expected_len(index)
+            || !ChunkTree::verify(
+                &self.root,
+                self.num_chunks(),
+                index,
+                &data,
+                &artifact_chunk.witness,
+            )
+        {
+            return Err(ArtifactErrorCode::ChunkVerificationFailed);
+        }
+        self.chunks.insert(index, data);
+        if self.chunks.len() < self.num_chunks() {
+            return Err(ArtifactErrorCode::ChunksMoreNeeded);
+        }
+        // All chunks were verified against the advertised root, so the
+        // artifact can only fail to assemble if the root does not belong to
+        // the advertised artifact. The whole artifact is downloaded instead,
+        // and verified against the integrity hash by P2P.
+        self.assemble().ok_or_else(|| {
+            self.chunks.clear();
+            self.single_chunk = true;
+            ArtifactErrorCode::ChunkVerificationFailed
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifact::{ArtifactAttribute, ArtifactId, DkgMessageAttribute};
+    use crate::consensus::{ConsensusMessage, ConsensusMessageHashable, RandomBeaconContent};
+    use crate::crypto::threshold_sig::ni_dkg::{NiDkgId, NiDkgTag, NiDkgTargetSubnet};
+    use crate::crypto::{CombinedThresholdSig, CombinedThresholdSigOf, CryptoHashOf, Signed};
+    use crate::signature::ThresholdSignature;
+    use crate::{Height, PrincipalId, SubnetId};
+
+    fn tracker(msg: &[u8]) -> MultiChunked {
+        let size = bincode::serialized_size(msg).unwrap() as usize;
+        MultiChunked {
+            tag: ArtifactTag::DkgArtifact,
+            integrity_hash: CryptoHash(vec![]),
+            root: chunk_tree_root(size, &msg).unwrap(),
+            size,
+            chunks: BTreeMap::new(),
+            single_chunk: false,
+        }
+    }
+
+    // A random beacon whose signature makes it large enough to be downloaded
+    // in pieces.
+    fn large_consensus_message() -> ConsensusMessage {
+        ConsensusMessage::RandomBeacon(Signed {
+            content: RandomBeaconContent::new(
+                Height::from(1),
+                CryptoHashOf::from(CryptoHash(vec![])),
+            ),
+            signature: ThresholdSignature {
+                signature: CombinedThresholdSigOf::new(CombinedThresholdSig(vec![
+                    1;
+                    3 * ARTIFACT_CHUNK_SIZE
+                ])),
+                signer: NiDkgId {
+                    start_block_height: Height::from(0),
+                    dealer_subnet: SubnetId::from(PrincipalId::new_subnet_test_id(0)),
+                    dkg_tag: NiDkgTag::HighThreshold,
+                    target_subnet: NiDkgTargetSubnet::Local,
+                },
+            },
+        })
+    }
+
+    fn consensus_advert(msg: &ConsensusMessage, integrity_hash: CryptoHash) -> GossipAdvert {
+        let size = bincode::serialized_size(msg).unwrap() as usize;
+        GossipAdvert {
+            attribute: ArtifactAttribute::ConsensusMessage(msg.into()),
+            size,
+            artifact_id: ArtifactId::ConsensusMessage(msg.get_id()),
+            integrity_hash,
+            chunk_tree_root: chunk_tree_root(size, msg),
+        }
+    }
+
+    fn download_all(
+        tracker: &mut MultiChunked,
+        chunked: &ChunkedArtifact,
+    ) -> Result<Artifact, ArtifactErrorCode> {
+        let ids: Vec<_> = tracker.chunks_to_download().collect();
+        let mut result = Err(ArtifactErrorCode::ChunksMoreNeeded);
+        for id in ids {
+            result = tracker.add_chunk(chunked.get_chunk(id).unwrap());
+        }
+        result
+    }
+
+    #[test]
+    fn test_chunk_tree_witnesses_verify() {
+        for num_chunks in 1..10 {
+            let data: Vec<u8> = (0..num_chunks * ARTIFACT_CHUNK_SIZE - 7)
+                .map(|i| i as u8)
+                .collect();
+            let tree = ChunkTree::new(&data);
+            let root = tree.root();
+            for (index, chunk) in data.chunks(ARTIFACT_CHUNK_SIZE).enumerate() {
+                let witness = tree.witness(index);
+                assert!(ChunkTree::verify(&root, num_chunks, index, chunk, &witness));
+                assert!(!ChunkTree::verify(
+                    &root,
+                    num_chunks,
+                    index,
+                    &chunk[1..],
+                    &witness
+                ));
+                assert!(
+                    !ChunkTree::verify(
+                        &root,
+                        num_chunks,
+                        (index + 1) % num_chunks,
+                        chunk,
+                        &witness
+                    ) || num_chunks == 1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_chunks_are_served_and_assembled_out_of_order() {
+        let msg = large_consensus_message();
+        let chunked = ChunkedArtifact::new(&msg).unwrap();
+        let mut tracker =
+            MultiChunked::from_advert(&consensus_advert(&msg, crypto_hash(&msg).get())).unwrap();
+        let mut ids: Vec<_> = tracker.chunks_to_download().collect();
+        assert_eq!(ids.len(), 4);
+        ids.reverse();
+
+        let last = ids.pop().unwrap();
+        for id in ids {
+            assert_eq!(
+                tracker.add_chunk(chunked.get_chunk(id).unwrap()),
+                Err(ArtifactErrorCode::ChunksMoreNeeded)
+            );
+            assert!(tracker.chunks_to_download().all(|missing| missing != id));
+        }
+        assert_eq!(
+            tracker.add_chunk(chunked.get_chunk(last).unwrap()),
+            Ok(Artifact::ConsensusMessage(msg))
+        );
+    }
+
+    #[test]
+    fn test_mismatching_root_falls_back_to_single_chunk() {
+        // The pieces verify against the advertised root, but do not make up
+        // the artifact with the advertised integrity hash.
+        let msg = large_consensus_message();
+        let chunked = ChunkedArtifact::new(&msg).unwrap();
+        let mut tracker =
+            MultiChunked::from_advert(&consensus_advert(&msg, CryptoHash(vec![1; 32]))).unwrap();
+        assert_eq!(
+            download_all(&mut tracker, &chunked),
+            Err(ArtifactErrorCode::ChunkVerificationFailed)
+        );
+        assert_eq!(
+            tracker.chunks_to_download().collect::<Vec<_>>(),
+            vec![ChunkId::from(CHUNKID_UNIT_CHUNK)]
+        );
+
+        // Late pieces are ignored, the whole artifact is accepted.
+        assert_eq!(
+            tracker.add_chunk(chunked.get_chunk(ChunkId::from(1)).unwrap()),
+            Err(ArtifactErrorCode::ChunksMoreNeeded)
+        );
+        let artifact = Artifact::ConsensusMessage(msg);
+        assert_eq!(
+            tracker.add_chunk(ArtifactChunk {
+                chunk_id: ChunkId::from(CHUNKID_UNIT_CHUNK),
+                witness: vec![],
+                artifact_chunk_data: ArtifactChunkData::UnitChunkData(artifact.clone()),
+            }),
+            Ok(artifact)
+        );
+
+        // Bytes that do not decode into an artifact also fall back.
+        let msg: Vec<u8> = (0..3 * ARTIFACT_CHUNK_SIZE)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let mut tracker = tracker(&msg);
+        assert_eq!(
+            download_all(&mut tracker, &ChunkedArtifact::new(&msg).unwrap()),
+            Err(ArtifactErrorCode::ChunkVerificationFailed)
+        );
+        assert_eq!(tracker.chunks_to_download().count(), 1);
+    }
+
+    #[test]
+    fn test_tampered_chunks_are_rejected() {
+        let msg: Vec<u8> = vec![7; 2 * ARTIFACT_CHUNK_SIZE];
+        let chunked = ChunkedArtifact::new(&msg).unwrap();
+        let mut tracker = tracker(&msg);
+
+        let mut chunk = chunked.get_chunk(ChunkId::from(1)).unwrap();
+        if let ArtifactChunkData::SemiStructuredChunkData(data) = &mut chunk.artifact_chunk_data {
+            data[0] ^= 1;
+        }
+        assert_eq!(
+            tracker.add_chunk(chunk),
+            Err(ArtifactErrorCode::ChunkVerificationFailed)
+        );
+
+        let mut chunk = chunked.get_chunk(ChunkId::from(2)).unwrap();
+        chunk.chunk_id = ChunkId::from(1);
+        assert_eq!(
+            tracker.add_chunk(chunk),
+            Err(ArtifactErrorCode::ChunkVerificationFailed)
+        );
+        assert_eq!(tracker.chunks_to_download().count(), 3);
+    }
+
+    #[test]
+    fn test_small_and_oversized_artifacts_are_single_chunked() {
+        let msg: Vec<u8> = vec![0; 16];
+        assert_eq!(chunk_tree_root(16, &msg), None);
+        assert!(ChunkedArtifact::new(&msg).is_none());
+        let mut advert = GossipAdvert {
+            attribute: ArtifactAttribute::DkgMessage(DkgMessageAttribute {
+                interval_start_height: Height::from(0),
+            }),
+            size: 16,
+            artifact_id: ArtifactId::DkgMessage(CryptoHashOf::from(CryptoHash(vec![]))),
+            integrity_hash: CryptoHash(vec![]),
+            chunk_tree_root: Some(CryptoHash(vec![0; 32])),
+        };
+        assert!(MultiChunked::from_advert(&advert).is_none());
+
+        // A bogus size does not make the tracker allocate chunk ids.
+        advert.size = usize::MAX;
+        assert!(MultiChunked::from_advert(&advert).is_none());
+        advert.size = MAX_MULTI_CHUNKED_SIZE;
+        let tracker = MultiChunked::from_advert(&advert).unwrap();
+        assert_eq!(
+            tracker.chunks_to_download().count(),
+            MAX_MULTI_CHUNKED_SIZE / ARTIFACT_CHUNK_SIZE
+        );
+    }
+}
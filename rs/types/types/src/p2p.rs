@@ -20,6 +20,10 @@ pub struct GossipAdvert {
     pub artifact_id: ArtifactId,
     /// Crypto hash of the artifact's message.
     pub integrity_hash: CryptoHash,
+    /// Root of the chunk tree of the artifact's message, set only for
+    /// artifacts that are downloaded in pieces (see
+    /// [`crate::multi_chunked`]).
+    pub chunk_tree_root: Option<CryptoHash>,
 }
 
 // TODO(P2P-380): Move all the constants in a more reasonable shared location in
@@ -87,6 +91,10 @@ impl From<GossipAdvert> for pb::GossipAdvert {
             size: advert.size as u64,
             artifact_id: serialize(&advert.artifact_id).unwrap(),
             integrity_hash: advert.integrity_hash.0,
+            chunk_tree_root: advert
+                .chunk_tree_root
+                .map(|root| root.0)
+                .unwrap_or_default(),
         }
     }
 }
@@ -100,6 +108,8 @@ impl TryFrom<pb::GossipAdvert> for GossipAdvert {
             size: advert.size as usize,
             artifact_id: deserialize(&advert.artifact_id)?,
             integrity_hash: CryptoHash(advert.integrity_hash),
+            chunk_tree_root: (!advert.chunk_tree_root.is_empty())
+                .then(|| CryptoHash(advert.chunk_tree_root)),
         })
     }
 }
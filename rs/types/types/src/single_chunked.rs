@@ -48,7 +48,9 @@ macro_rules! chunkable_artifact_impl {
         impl ChunkableArtifact for $id {
             fn get_chunk($self: Box<Self>, chunk_id: ChunkId) -> Option<ArtifactChunk> {
                 if chunk_id != ChunkId::from(CHUNKID_UNIT_CHUNK) {
-                    // Single chunked in identified only chunk CHUNKID_UNIT_CHUNK
+                    // Single chunked in identified only chunk CHUNKID_UNIT_CHUNK.
+                    // The pieces of large artifacts are served by P2P (see
+                    // `crate::multi_chunked::ChunkedArtifact`).
                     None
                 } else {
                     Some(ArtifactChunk {